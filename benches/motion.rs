@@ -0,0 +1,73 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use geo::Point;
+use rsaxi::motion::plan::Plan;
+use rsaxi::motion::planner::Planner;
+
+/// Детермінований генератор псевдовипадкових чисел у діапазоні [0, 1).
+fn xorshift(seed: u64) -> impl FnMut() -> f64 {
+    let mut state = seed.max(1);
+    move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Випадковий шлях з `count` точок у межах аркуша A4.
+fn random_path(count: usize) -> Vec<Point<f64>> {
+    let mut random = xorshift(0x2545_f491_4f6c_dd1d);
+    (0..count)
+        .map(|_| Point::new(random() * 297.0, random() * 210.0))
+        .collect()
+}
+
+fn planning(c: &mut Criterion) {
+    let planner = Planner::new(200.0, 4000.0, 0.001);
+    let mut group = c.benchmark_group("plan/new");
+    for count in [1_000, 10_000] {
+        let path = random_path(count);
+        group.bench_with_input(BenchmarkId::from_parameter(count), &path, |b, path| {
+            b.iter(|| planner.plan(path.clone()).expect("Шлях має плануватися"))
+        });
+    }
+    group.finish();
+}
+
+fn lookup(c: &mut Criterion) {
+    // Пошук стану руху в довгому плані: бінарний пошук за часом і відстанню
+    let plan: Plan = Planner::new(200.0, 4000.0, 0.001)
+        .plan(random_path(10_000))
+        .expect("Шлях має плануватися");
+    let mut random = xorshift(7);
+    c.bench_function("plan/instant/10000", |b| {
+        b.iter(|| plan.instant(random() * plan.total_time))
+    });
+    c.bench_function("plan/instant_at_distance/10000", |b| {
+        b.iter(|| plan.instant_at_distance(random() * plan.total_distance))
+    });
+
+    // Проходження всього плану кроками потокового малювання: пошук на кожен крок проти
+    // послідовного обходу блоків
+    let step = 0.01;
+    let mut group = c.benchmark_group("plan/sweep");
+    group.sample_size(20);
+    group.bench_function("instant/10000", |b| {
+        b.iter(|| {
+            let mut t = 0.0;
+            let mut last = None;
+            while t <= plan.total_time {
+                last = plan.instant(t);
+                t += step;
+            }
+            last.map(|instant| instant.position)
+        })
+    });
+    group.bench_function("sample/10000", |b| {
+        b.iter(|| plan.sample(step).last().map(|instant| instant.position))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, planning, lookup);
+criterion_main!(benches);
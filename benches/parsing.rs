@@ -0,0 +1,102 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use phf::phf_map;
+use rsaxi::drawing::Drawing;
+use rsaxi::text::font::glyph::Glyph;
+use rsaxi::text::font::roman::Roman;
+use rsaxi::text::font::variant::Simplex;
+use rsaxi::text::text::TextBuilder;
+
+/// Карта Hershey-кодів для гліфів бенчмарку.
+static CMAP: phf::Map<u32, u32> = phf_map! {
+    8u32 => 72u32,     // 'H'
+    2199u32 => 64u32,  // '@'
+};
+
+/// Короткий гліф `H` римського шрифту.
+const SHORT_GLYPH: &str = "    8  9MWOMOV RUMUV ROQUQ";
+
+/// Детермінований генератор псевдовипадкових чисел у діапазоні [0, 1).
+fn xorshift(seed: u64) -> impl FnMut() -> f64 {
+    let mut state = seed.max(1);
+    move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Гліф з багатьма вершинами, як у складних символах шрифтів Hershey.
+fn long_glyph() -> String {
+    let mut random = xorshift(42);
+    let mut line = String::from(" 2199120EX");
+    for index in 0..119 {
+        if index % 20 == 19 {
+            line.push_str(" R");
+        } else {
+            line.push((b'F' + (random() * 24.0) as u8) as char);
+            line.push((b'F' + (random() * 24.0) as u8) as char);
+        }
+    }
+    line
+}
+
+/// SVG-документ з `count` ламаними по вісім точок.
+fn svg_document(count: usize) -> String {
+    let mut random = xorshift(3);
+    let mut svg = String::from(r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 297 210">"#);
+    for _ in 0..count {
+        svg.push_str(r#"<path d="M"#);
+        for _ in 0..8 {
+            let (x, y) = (random() * 297.0, random() * 210.0);
+            svg.push_str(&format!(" {:.3} {:.3}", x, y));
+        }
+        svg.push_str(r#""/>"#);
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+fn glyphs(c: &mut Criterion) {
+    c.bench_function("glyph/from_line/short", |b| {
+        b.iter(|| Glyph::from_line(SHORT_GLYPH, &CMAP).expect("Гліф має розбиратися"))
+    });
+    let long = long_glyph();
+    c.bench_function("glyph/from_line/long", |b| {
+        b.iter(|| Glyph::from_line(&long, &CMAP).expect("Гліф має розбиратися"))
+    });
+}
+
+fn svg(c: &mut Criterion) {
+    let svg = svg_document(2_000);
+    c.bench_function("svg/from_svg/2000", |b| {
+        b.iter(|| Drawing::from_svg(&svg).expect("SVG має розбиратися"))
+    });
+    c.bench_function("svg/svg_paths/2000", |b| {
+        b.iter(|| {
+            Drawing::svg_paths(&svg)
+                .expect("SVG має відкриватися")
+                .filter(Result::is_ok)
+                .count()
+        })
+    });
+}
+
+fn text(c: &mut Criterion) {
+    // Довгий абзац: кожен символ трапляється багато разів
+    let font = Roman::new().simplex().expect("Шрифт має створюватися");
+    let paragraph = "The quick brown fox jumps over the lazy dog. ".repeat(100);
+    c.bench_function("text/build/paragraph", |b| {
+        b.iter(|| {
+            TextBuilder::default()
+                .content(&paragraph)
+                .font(font.clone())
+                .width(150.0)
+                .build()
+                .expect("Текст має розміщуватися")
+        })
+    });
+}
+
+criterion_group!(benches, glyphs, svg, text);
+criterion_main!(benches);
@@ -79,7 +79,7 @@ fn parse_glyphs_files(
             // Перевіряємо, чи рядок має достатню довжину для парсингу номера гліфа.
             if line.len() >= 5 {
                 let potential_id = &line[0..5];
-                if potential_id.trim().chars().all(|c| c.is_digit(10)) {
+                if potential_id.trim().chars().all(|c| c.is_ascii_digit()) {
                     match potential_id.trim().parse::<u32>() {
                         Ok(glyph_id) => {
                             current_glyph_id = Some(glyph_id);
@@ -170,7 +170,7 @@ fn load_glyphs(glyph_files: &[String]) -> Result<HashMap<u32, String>, Box<dyn s
         let path = Path::new(file_path);
 
         // Відкриваємо файл і читаємо його вміст
-        let file = File::open(&path)
+        let file = File::open(path)
             .map_err(|e| format!("Не вдалося відкрити файл '{}': {}", file_path, e))?;
         let reader = BufReader::new(file);
 
@@ -207,7 +207,7 @@ fn generate_unicode_map(csv_path: &str) -> Result<(), Box<dyn std::error::Error>
     let dest_path = Path::new(&out_dir).join("occidental_unicode_map.rs");
     let mut out_file = File::create(&dest_path)?;
 
-    writeln!(out_file, "")?;
+    writeln!(out_file)?;
     writeln!(
         out_file,
         "pub static HERSHEY_OCCIDENTAL_UNICODE_MAP: phf::Map<u32, u32> = phf_map! {{"
@@ -237,6 +237,62 @@ fn generate_unicode_map(csv_path: &str) -> Result<(), Box<dyn std::error::Error>
     Ok(())
 }
 
+/// Початок області приватного використання Unicode, куди відображаються символьні гліфи
+/// без власного Unicode коду: гліф з Hershey кодом `N` отримує код `U+E000 + N`.
+const SYMBOL_PRIVATE_USE_BASE: u32 = 0xE000;
+
+/// Генерує Rust файл з HERSHEY_SYMBOL_UNICODE_MAP для символьних шрифтів (Symbol, Music, Math).
+///
+/// Гліф зберігає свій Unicode код із `unicodemap.csv`, якщо він визначений (не U+FFFD)
+/// і ще не зайнятий іншим гліфом того ж файлу офсетів. Інакше гліфу призначається код
+/// з області приватного використання, щоб кожен символ залишався доступним.
+fn generate_symbol_unicode_map(
+    csv_path: &str,
+    offsets_files: &[PathBuf],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut csv_reader = csv::Reader::from_path(csv_path)?;
+    let mut unicode_by_hid: HashMap<u32, u32> = HashMap::new();
+    for result in csv_reader.records() {
+        let record = result?;
+        let hid = record.get(0).unwrap_or("").trim().parse::<u32>();
+        let unicode = record.get(1).unwrap_or("").trim().parse::<u32>();
+        if let (Ok(hid), Ok(unicode)) = (hid, unicode) {
+            unicode_by_hid.insert(hid, unicode);
+        }
+    }
+
+    let mut symbol_map: Vec<(u32, u32)> = Vec::new();
+    for file_path in offsets_files {
+        let offsets = parse_offsets_file(file_path.to_str().unwrap())?;
+        let mut used: Vec<u32> = Vec::new();
+        for hid in offsets {
+            let unicode = match unicode_by_hid.get(&hid) {
+                Some(&code) if code != 0xFFFD && !used.contains(&code) => code,
+                _ => SYMBOL_PRIVATE_USE_BASE + hid,
+            };
+            used.push(unicode);
+            symbol_map.push((hid, unicode));
+        }
+    }
+    symbol_map.sort_unstable();
+    symbol_map.dedup_by_key(|(hid, _)| *hid);
+
+    let out_dir = env::var("OUT_DIR")?;
+    let dest_path = Path::new(&out_dir).join("symbol_unicode_map.rs");
+    let mut out_file = File::create(&dest_path)?;
+
+    writeln!(out_file)?;
+    writeln!(
+        out_file,
+        "pub static HERSHEY_SYMBOL_UNICODE_MAP: phf::Map<u32, u32> = phf_map! {{"
+    )?;
+    for (hid, unicode) in symbol_map {
+        writeln!(out_file, "    {}u32 => {}u32,", hid, unicode)?;
+    }
+    writeln!(out_file, "}};")?;
+    Ok(())
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("cargo:rerun-if-changed=fonts/hershey/hersh.oc1");
     println!("cargo:rerun-if-changed=fonts/hershey/hersh.oc2");
@@ -276,8 +332,18 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         "oriental_fonts.rs",
     )?;
 
+    // Список файлів з офсетами для символьних шрифтів
+    let symbol_offset_files = vec![
+        PathBuf::from("fonts/hershey/mathc.hmp"),
+        PathBuf::from("fonts/hershey/maths.hmp"),
+        PathBuf::from("fonts/hershey/musicc.hmp"),
+        PathBuf::from("fonts/hershey/musics.hmp"),
+        PathBuf::from("fonts/hershey/symbolc.hmp"),
+        PathBuf::from("fonts/hershey/symbols.hmp"),
+    ];
+
     // Список файлів з офсетами для Occidental шрифтів
-    let occidental_offset_files = vec![
+    let mut occidental_offset_files = vec![
         PathBuf::from("fonts/hershey/cyrilc.hmp"),
         PathBuf::from("fonts/hershey/gothgbt.hmp"),
         PathBuf::from("fonts/hershey/gothgrt.hmp"),
@@ -298,11 +364,14 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         PathBuf::from("fonts/hershey/scripts.hmp"),
     ];
 
+    occidental_offset_files.extend(symbol_offset_files.iter().cloned());
+
     // Генеруємо мапи для Occidental офсетів
     generate_offsets_map(&occidental_offset_files, "offsets.rs")?;
 
     let csv_path = "fonts/hershey/unicodemap.csv";
     generate_unicode_map(csv_path)?;
+    generate_symbol_unicode_map(csv_path, &symbol_offset_files)?;
 
     Ok(())
 }
@@ -0,0 +1,62 @@
+#![no_main]
+
+use std::time::Duration;
+
+use libfuzzer_sys::fuzz_target;
+use rsaxi::device::fake::{FakeSerialPort, Script};
+use rsaxi::device::firmware::FirmwareVersion;
+use rsaxi::device::{Device, DeviceOptions, SerialOptions, StepMode};
+
+fn options() -> DeviceOptions {
+    DeviceOptions {
+        steps_per_unit: 80,
+        pen_up_position: 60,
+        pen_up_speed: 150,
+        pen_up_delay: 0,
+        pen_down_position: 30,
+        pen_down_speed: 150,
+        pen_down_delay: 0,
+        pen_settle: 0,
+        step_mode: StepMode::OneSixteenth,
+        port_name: None,
+        port_config: None,
+        transcript: None,
+        // Без повторів сценарій точно знає, скільки запитів надішле пристрій
+        serial: SerialOptions {
+            retries: 0,
+            retry_backoff: Duration::ZERO,
+            ..SerialOptions::default()
+        },
+    }
+}
+
+// Перший байт вибирає запит і прошивку, решта стає відповіддю плати порціями по рядку
+fuzz_target!(|data: &[u8]| {
+    let Some((&selector, reply)) = data.split_first() else {
+        return;
+    };
+    let firmware = match selector & 0x80 {
+        0 => FirmwareVersion::new(3, 0, 2),
+        _ => FirmwareVersion::new(2, 8, 0),
+    };
+    let reply = String::from_utf8_lossy(reply);
+    let chunks: Vec<&str> = reply.split_inclusive('\n').collect();
+    let request = ["QM", "QS", "QP", "QG", "QT", "PI,*", "V"][(selector & 0x7f) as usize % 7];
+
+    let script = Script::new().handshake(firmware).expect(request, &chunks);
+    let mut device = Device::with_port(Box::new(FakeSerialPort::new(script)), options())
+        .expect("Обмін підключення записаний у сценарії");
+    let _ = match request {
+        "QM" => device.motor_status().map(drop),
+        "QS" => device.read_position().map(drop),
+        "QP" => device.query_pen_state().map(drop),
+        "QG" => device.general_query().map(drop),
+        "QT" => device.query_nickname().map(drop),
+        "PI,*" => device.read_pin('B', 0).map(drop),
+        _ => device.version().map(|version| {
+            let _ = FirmwareVersion::parse(&version);
+        }),
+    };
+    // Відключаємося до знищення, щоб не надсилати команд поза сценарієм
+    device.disconnect();
+});
@@ -0,0 +1,15 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use phf::phf_map;
+use rsaxi::text::font::glyph::Glyph;
+
+/// Карта Hershey-кодів, з якою гліф знаходить свій символ.
+static CMAP: phf::Map<u32, u32> = phf_map! {
+    8u32 => 72u32, // 'H'
+};
+
+// Будь-який рядок має давати гліф або помилку розбору, а не паніку
+fuzz_target!(|line: &str| {
+    let _ = Glyph::from_line(line, &CMAP);
+});
@@ -0,0 +1,14 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rsaxi::drawing::Drawing;
+
+// Дані шляху `d` вставляються в документ, тож до розбору кривих доходить будь-який рядок
+fuzz_target!(|data: &str| {
+    let data = data.replace(['"', '<', '&'], " ");
+    let svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="100mm" height="100mm"><path d="{}"/></svg>"#,
+        data
+    );
+    let _ = Drawing::from_svg(&svg);
+});
@@ -0,0 +1,84 @@
+/* C-інтерфейс rsaxi; відповідає модулю src/capi.rs. */
+
+#ifndef RSAXI_H
+#define RSAXI_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+#define RSAXI_OK 0
+#define RSAXI_ERR_ARGUMENT -1
+#define RSAXI_ERR_DEVICE -2
+#define RSAXI_ERR_IMPORT -3
+#define RSAXI_ERR_PLAN -4
+#define RSAXI_ERR_CANCELLED -5
+#define RSAXI_ERR_OTHER -6
+#define RSAXI_ERR_PANIC -7
+
+typedef enum RsaxiState {
+  Idle = 0,
+  Plotting = 1,
+  Paused = 2,
+  Cancelled = 3,
+  Finished = 4,
+  Failed = 5,
+} RsaxiState;
+
+typedef struct RsaxiControl RsaxiControl;
+
+typedef struct RsaxiPlotter RsaxiPlotter;
+
+typedef struct RsaxiStatus {
+  RsaxiState state;
+  uint64_t current_path;
+  uint64_t total_paths;
+  double x;
+  double y;
+  double velocity;
+  bool pen_down;
+  double drawn_length;
+  double total_length;
+  double eta_seconds;
+} RsaxiStatus;
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+const char *rsaxi_last_error(void);
+
+RsaxiPlotter *rsaxi_connect(const char *port);
+
+RsaxiPlotter *rsaxi_connect_simulated(void);
+
+void rsaxi_disconnect(RsaxiPlotter *plotter);
+
+int rsaxi_plot_svg(RsaxiPlotter *plotter, const char *svg, bool fit);
+
+int rsaxi_pen_up(RsaxiPlotter *plotter);
+
+int rsaxi_pen_down(RsaxiPlotter *plotter);
+
+int rsaxi_jog(RsaxiPlotter *plotter, double dx, double dy);
+
+int rsaxi_home(RsaxiPlotter *plotter);
+
+int rsaxi_status(RsaxiPlotter *plotter, RsaxiStatus *out);
+
+RsaxiControl *rsaxi_control(RsaxiPlotter *plotter);
+
+void rsaxi_control_free(RsaxiControl *control);
+
+int rsaxi_pause(const RsaxiControl *control);
+
+int rsaxi_resume(const RsaxiControl *control);
+
+int rsaxi_cancel(const RsaxiControl *control);
+
+int rsaxi_control_status(const RsaxiControl *control, RsaxiStatus *out);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif
+
+#endif  /* RSAXI_H */
@@ -1,66 +1,471 @@
-use std::f64::EPSILON;
+use std::panic::{self, AssertUnwindSafe};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
 
-use geo::Point;
-use log::{debug, info};
+use geo::{
+    AffineOps, AffineTransform, BoundingRect, Coord, EuclideanLength, LineString, Point, Rect,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::{debug, error, info, warn};
 
-use crate::device::{Device, DeviceError, DeviceOptions, StepMode};
+use crate::control::{Cancelled, PlotControl, PlotState};
+use crate::device::firmware::FirmwareVersion;
+use crate::device::mock::{MockPort, Trace};
+use crate::device::tool::Tool;
+use crate::device::{Device, DeviceError, DeviceOptions, SerialOptions, StepMode};
+use crate::drawing::coordinates::CoordinateSystem;
+use crate::drawing::correction::Correction;
+use crate::drawing::direction::PathDirection;
+use crate::drawing::lead::{Leads, Stroke};
+use crate::drawing::order::PathOrder;
+use crate::drawing::refill::Refill;
+use crate::drawing::tile::Tile;
 use crate::drawing::Drawing;
+use crate::error::Error;
+use crate::motion::emitter::{StepEmitter, TICK_RATE};
+use crate::motion::error::PlanError;
+use crate::motion::job::{Job, JobPlanner, PlanAction, CHAIN_TOLERANCE};
+use crate::motion::kinematics;
+use crate::motion::limits::MAX_STEP_RATE;
 use crate::motion::plan::Plan;
-use crate::motion::point::PointExtension;
+use crate::motion::planner::Planner;
+use crate::paper::Paper;
+use crate::tr;
+use crate::trace;
 
 /// Константи для налаштування AxiDraw.
 const TIMESLICE_MS: i32 = 100;
-const MICROSTEPPING_MODE: u32 = 1;
 const PEN_UP_POSITION: i32 = 60; // Позиція піднятої ручки за замовчуванням
 const PEN_UP_SPEED: i32 = 150; // Швидкість підйому ручки за замовчуванням
 const PEN_UP_DELAY: i32 = 0; // Затримка після підняття ручки
 const PEN_DOWN_POSITION: i32 = 30; // Позиція опущеної ручки за замовчуванням
 const PEN_DOWN_SPEED: i32 = 150; // Швидкість опускання ручки за замовчуванням
 const PEN_DOWN_DELAY: i32 = 0; // Затримка після опускання ручки
-const ACCELERATION: f64 = 16.0; // Прискорення за замовчуванням
-const MAX_VELOCITY: f64 = 20.0; // Швидкість малювання за замовчуванням
+const PEN_SETTLE: i32 = 0; // Час заспокоєння ручки після руху серво
 const CORNER_FACTOR: f64 = 0.001; // Коефіцієнт для обробки кутів у плануванні руху
+const DETAIL_SPEED_FACTOR: f64 = 1.0; // Частка швидкості на дрібних деталях; 1 вимикає сповільнення
+const POSITION_CHECK_INTERVAL: usize = 200; // Кількість команд руху між звірками позиції з QS
+const BUTTON_CHECK_INTERVAL: usize = 5; // Кількість контрольних точок між опитуваннями кнопки через QG
+const BUTTON_POLL: Duration = Duration::from_millis(50); // Інтервал опитування кнопки під час паузи
+const STREAM_BATCH: usize = 512; // Кількість шляхів, що плануються разом у `draw_iter`
+const STREAM_CHAIN: usize = 512; // Скільки шляхів безперервної лінії порція `draw_iter` додає понад `STREAM_BATCH` чи планує наперед
+const IDLE_TIMEOUT: Duration = Duration::from_secs(60); // Найдовше очікування виконання черги руху в кінці шляху
+const DWELL_SLICE: Duration = Duration::from_secs(1); // Найдовша команда паузи, між якими малювання можна призупинити
 
 /// Структура, що представляє опції налаштування для AxiDraw.
+#[derive(Clone)]
 pub struct Options {
-    pub steps_per_unit: i32,
-    pub pen_up_position: i32,        // Положення ручки при піднятій ручці.
-    pub pen_up_speed: i32,           // Швидкість підняття механізму підйому ручки.
-    pub pen_up_delay: i32,           // Затримка після підняття ручки (в мілісекундах).
-    pub pen_down_position: i32,      // Положення ручки при опущеній ручці (малювання).
-    pub pen_down_speed: i32,         // Швидкість опускання механізму підйому ручки.
-    pub pen_down_delay: i32,         // Затримка після опускання ручки (в мілісекундах).
-    pub acceleration: f64,           // Швидкість прискорення/гальмування..
-    pub max_velocity: f64,           // Швидкість малювання за замовчуванням.
-    pub corner_factor: f64,          // Коефіцієнт для обробки кутів у плануванні руху.
-    pub model: AxiDrawModel,         // Вибір моделі апаратного забезпечення AxiDraw.
-    pub port: Option<String>,        // Вказати USB-порт або AxiDraw для використання.
+    pub steps_per_unit: i32,           // Кроки мотора на міліметр руху каретки.
+    pub step_mode: StepMode,           // Режим мікрокроку моторів.
+    pub drive: Drive,                  // Передача, з якої виводяться кроки на міліметр.
+    pub pen_up_position: i32,          // Положення ручки при піднятій ручці.
+    pub pen_up_speed: i32,             // Швидкість підняття механізму підйому ручки.
+    pub pen_up_delay: i32,             // Затримка після підняття ручки (в мілісекундах).
+    pub pen_down_position: i32,        // Положення ручки при опущеній ручці (малювання).
+    pub pen_down_speed: i32,           // Швидкість опускання механізму підйому ручки.
+    pub pen_down_delay: i32,           // Затримка після опускання ручки (в мілісекундах).
+    pub pen_settle: i32, // Час заспокоєння ручки після підйому чи опускання (в мілісекундах).
+    pub stall_limit: Option<Duration>, // Найдовша зупинка руху з опущеним пером; без неї перо не піднімається.
+    pub acceleration: f64,             // Прискорення та гальмування каретки (мм/с²).
+    pub travel_acceleration: Option<f64>, // Прискорення переїздів з піднятим пером (мм/с²); без нього як `acceleration`.
+    pub max_velocity: f64,                // Найбільша швидкість каретки (мм/с).
+    pub corner_factor: f64,               // Коефіцієнт для обробки кутів у плануванні руху.
+    pub detail_speed_factor: f64, // Частка швидкості на коротких відрізках і крутих вигинах (0.05..1).
+    pub low_level_moves: bool,    // Рух командами LM у кроках моторів замість XM у кроках осей.
+    pub model: AxiDrawModel,      // Вибір моделі апаратного забезпечення AxiDraw.
+    pub port: Option<String>,     // Вказати USB-порт або AxiDraw для використання.
     pub port_config: Option<String>, // Перевизначити спосіб знаходження USB-портів.
+    pub paper: Option<Paper>,     // Аркуш паперу; без нього межами є хід кареток моделі.
+    pub leads: Option<Leads>,     // Захід і вихід для шляхів; без них шляхи малюються як є.
+    pub refill: Option<Refill>, // Поповнення чорнила для перових ручок; без нього перо не занурюється.
+    pub tool: Tool, // Інструмент: перо на серво або вихід плати для аерографа чи лазера.
+    pub order: Option<PathOrder>, // Впорядкування шляхів; без нього шляхи малюються в порядку малюнка.
+    pub direction: Option<PathDirection>, // Правило напрямку шляхів; без нього напрямок обирає впорядкування.
+    pub coordinates: CoordinateSystem, // Система координат шляхів малюнка; перетворюється в систему аркуша першою.
+    pub correction: Option<Correction>, // Поправка геометрії плотера; застосовується до кожного малюнка перед плануванням.
+    pub park_position: Option<(f64, f64)>, // Місце паркування каретки після малювання (в мм); без нього каретка повертається додому.
+    pub transcript: Option<String>, // Файл для журналу обміну з платою; без нього журнал не ведеться.
+    pub serial: SerialOptions,      // Тайм-аути, повтори та розбиття запису для обміну з платою.
 }
 
 impl Default for Options {
     fn default() -> Self {
-        // Обчислюємо дільник кроків на основі режиму мікрокрокування
-        let step_divider = 2i32.pow(MICROSTEPPING_MODE - 1);
+        Self::for_model(AxiDrawModel::Mini)
+    }
+}
+
+/// Помилки в налаштуваннях AxiDraw.
+#[derive(Debug, Error)]
+pub enum OptionsError {
+    /// Швидкість не додатна або перевищує можливості моделі чи контролера.
+    #[error(
+        "{}",
+        tr!(
+            "Velocity {} mm/s is outside 0..{:.1} mm/s for {}",
+            "Швидкість {} мм/с поза межами 0..{:.1} мм/с для {}",
+            .velocity,
+            .limit,
+            .model
+        )
+    )]
+    Velocity {
+        velocity: f64,
+        limit: f64,
+        model: &'static str,
+    },
+
+    /// Прискорення не додатне або перевищує безпечне для моделі.
+    #[error(
+        "{}",
+        tr!(
+            "Acceleration {} mm/s² is outside 0..{} mm/s² for {}",
+            "Прискорення {} мм/с² поза межами 0..{} мм/с² для {}",
+            .acceleration,
+            .limit,
+            .model
+        )
+    )]
+    Acceleration {
+        acceleration: f64,
+        limit: f64,
+        model: &'static str,
+    },
+
+    /// Мотори не можуть малювати вимкненими.
+    #[error("{}", tr!("Step mode must enable the motors", "Режим кроку має вмикати мотори"))]
+    StepMode,
+
+    /// Кількість кроків на міліметр не додатна.
+    #[error(
+        "{}",
+        tr!(
+            "Steps per millimetre must be positive, not {}",
+            "Кількість кроків на міліметр має бути додатною, а не {}",
+            .0
+        )
+    )]
+    StepsPerUnit(i32),
+
+    /// Частка швидкості на дрібних деталях поза межами 0.05..1.
+    #[error(
+        "{}",
+        tr!(
+            "Detail speed factor must be between 0.05 and 1, not {}",
+            "Частка швидкості на деталях має бути від 0.05 до 1, а не {}",
+            .0
+        )
+    )]
+    DetailSpeedFactor(f64),
+
+    /// Відстань між зануреннями в чорнильницю не додатна.
+    #[error(
+        "{}",
+        tr!(
+            "Refill distance must be positive, not {} mm",
+            "Відстань між зануреннями має бути додатною, а не {} мм",
+            .0
+        )
+    )]
+    RefillDistance(f64),
 
-        // Обчислюємо кількість кроків на одиницю (мм) з урахуванням дільника
-        let steps_per_unit = 80 / step_divider;
+    /// Чорнильниця з колом пера не вміщується в робочу область моделі.
+    #[error(
+        "{}",
+        tr!(
+            "Dip location {:?} with a {} mm swirl is outside the travel of {}",
+            "Чорнильниця {:?} з колом {} мм поза робочою областю {}",
+            .location,
+            .swirl,
+            .model
+        )
+    )]
+    DipLocation {
+        location: (f64, f64),
+        swirl: f64,
+        model: &'static str,
+    },
+
+    /// Місце паркування поза робочою областю моделі.
+    #[error(
+        "{}",
+        tr!(
+            "Park position {:?} is outside the travel of {}",
+            "Місце паркування {:?} поза робочою областю {}",
+            .position,
+            .model
+        )
+    )]
+    ParkPosition {
+        position: (f64, f64),
+        model: &'static str,
+    },
+
+    /// Потужність інструмента поза межами 0..1.
+    #[error(
+        "{}",
+        tr!(
+            "Tool intensity must be between 0 and 1, not {}",
+            "Потужність інструмента має бути від 0 до 1, а не {}",
+            .0
+        )
+    )]
+    Intensity(f64),
+
+    /// Вихід інструмента не існує на платі.
+    #[error("{}", tr!("Tool output {} does not exist", "Виходу інструмента {} не існує", .0))]
+    ToolOutput(Tool),
+
+    /// Невідомий код моделі AxiDraw.
+    #[error("{}", tr!("Unknown AxiDraw model: '{}'", "Невідома модель AxiDraw: '{}'", .0))]
+    Model(String),
+}
+
+impl Options {
+    /// Створює опції з безпечними для моделі швидкістю та прискоренням.
+    ///
+    /// # Параметри
+    /// - `model`: Модель AxiDraw.
+    ///
+    /// # Повертає
+    /// - `Options`: Типові опції для цієї моделі.
+    pub fn for_model(model: AxiDrawModel) -> Self {
+        // Кількість кроків на одиницю (мм) визначають передача моделі й режим мікрокроку
+        let step_mode = StepMode::OneSixteenth;
+        let drive = model.drive();
 
         Self {
-            steps_per_unit,
+            steps_per_unit: drive.steps_per_unit(step_mode),
+            step_mode,
+            drive,
             pen_up_position: PEN_UP_POSITION,
             pen_up_speed: PEN_UP_SPEED,
             pen_up_delay: PEN_UP_DELAY,
             pen_down_position: PEN_DOWN_POSITION,
             pen_down_speed: PEN_DOWN_SPEED,
             pen_down_delay: PEN_DOWN_DELAY,
-            acceleration: ACCELERATION,
-            max_velocity: MAX_VELOCITY,
+            pen_settle: PEN_SETTLE,
+            stall_limit: None, // Перо лишається опущеним, хоч би скільки стояв рух
+            acceleration: model.default_acceleration(),
+            travel_acceleration: None, // Переїзди розганяються так само, як малювання
+            max_velocity: model.default_velocity(),
             corner_factor: CORNER_FACTOR,
-            model: AxiDrawModel::Mini, // Модель AxiDraw за замовчуванням
-            port: None,                // Автоматичний вибір порту
-            port_config: None,         // Стандартна конфігурація порту
+            detail_speed_factor: DETAIL_SPEED_FACTOR,
+            low_level_moves: false,
+            model,
+            port: None,          // Автоматичний вибір порту
+            port_config: None,   // Стандартна конфігурація порту
+            paper: None,         // Межі визначаються моделлю
+            leads: None,         // Без заходу та виходу
+            refill: None,        // Без занурень у чорнильницю
+            tool: Tool::Pen,     // Перо на серво підйому
+            order: None,         // Порядок шляхів з малюнка
+            direction: None,     // Напрямок шляхів з малюнка чи впорядкування
+            correction: None,    // Без поправки геометрії
+            park_position: None, // Після малювання каретка повертається додому
+            transcript: None,    // Без журналу обміну
+            coordinates: CoordinateSystem::default(),
+            serial: SerialOptions::default(),
+        }
+    }
+
+    /// Змінює режим мікрокроку й перераховує для нього кроки на міліметр.
+    ///
+    /// # Параметри
+    /// - `step_mode`: Новий режим мікрокроку.
+    pub fn set_step_mode(&mut self, step_mode: StepMode) {
+        self.step_mode = step_mode;
+        self.steps_per_unit = self.drive.steps_per_unit(step_mode);
+    }
+
+    /// Замінює передачу моделі, наприклад для набору з іншим шківом, і перераховує для
+    /// неї кроки на міліметр.
+    ///
+    /// # Параметри
+    /// - `drive`: Передача плотера.
+    pub fn set_drive(&mut self, drive: Drive) {
+        self.drive = drive;
+        self.steps_per_unit = drive.steps_per_unit(self.step_mode);
+    }
+
+    /// Найбільша швидкість каретки (мм/с), яку дозволяють модель і частота кроків EBB.
+    pub fn velocity_limit(&self) -> f64 {
+        let step_limit = MAX_STEP_RATE / self.steps_per_unit.max(1) as f64;
+        self.model.max_velocity(self.step_mode).min(step_limit)
+    }
+
+    /// Створює планувальник руху з налаштувань, що тримає мотори в межах частоти кроків EBB.
+    pub fn planner(&self) -> Planner {
+        Planner::new(self.max_velocity, self.acceleration, self.corner_factor)
+            .with_steps_per_unit(self.steps_per_unit as f64)
+    }
+
+    /// Створює планувальник переїздів з піднятим пером з власним прискоренням, якщо його задано.
+    pub fn travel_planner(&self) -> Planner {
+        let acceleration = self.travel_acceleration.unwrap_or(self.acceleration);
+        Planner::new(self.max_velocity, acceleration, self.corner_factor)
+            .with_steps_per_unit(self.steps_per_unit as f64)
+    }
+
+    /// Створює планувальник малюнка з налаштувань руху.
+    pub fn job_planner(&self) -> JobPlanner {
+        JobPlanner::new(self.planner()).with_travel(self.travel_planner())
+    }
+
+    /// Переводить малюнок у систему аркуша, вирівнює напрямок шляхів і впорядковує їх,
+    /// якщо це увімкнено в налаштуваннях.
+    ///
+    /// Після вирівнювання напрямку впорядкування вже не перевертає шляхи, щоб не
+    /// зіпсувати його.
+    ///
+    /// # Параметри
+    /// - `drawing`: Малюнок, шляхи якого потрібно впорядкувати.
+    ///
+    /// # Повертає
+    /// - `Drawing`: Малюнок зі шляхами в порядку й напрямку малювання.
+    pub fn arrange(&self, drawing: &Drawing) -> Drawing {
+        let drawing = drawing.in_coordinates(&self.coordinates);
+        let drawing = match self.direction {
+            Some(direction) => drawing.normalize_direction(direction),
+            None => drawing.clone(),
+        };
+        match self.order {
+            Some(order) => drawing.optimize(&PathOrder {
+                reverse: order.reverse && self.direction.is_none(),
+                ..order
+            }),
+            None => drawing,
+        }
+    }
+
+    /// Готує шляхи малюнка до планування так само, як `Axidraw::draw`.
+    ///
+    /// Шляхи вирівнюються й впорядковуються, виправляються поправкою геометрії, отримують
+    /// захід і вихід та сповільнюються на дрібних деталях, якщо це увімкнено в налаштуваннях.
+    ///
+    /// # Параметри
+    /// - `drawing`: Малюнок, шляхи якого потрібно підготувати.
+    ///
+    /// # Повертає
+    /// - `Vec<Stroke>`: Шляхи в порядку малювання зі швидкостями в кожній точці.
+    pub fn strokes(&self, drawing: &Drawing) -> Vec<Stroke> {
+        let drawing = self.arrange(drawing);
+        let corrected = self
+            .correction
+            .map(|correction| drawing.corrected(&correction));
+        let drawing = corrected.as_ref().unwrap_or(&drawing);
+        let mut strokes = drawing.strokes(self.leads.as_ref());
+        for stroke in &mut strokes {
+            stroke.slow_details(self.detail_speed_factor);
+        }
+        strokes
+    }
+
+    /// Планує рух для малюнка без плотера, наприклад для аналізу чи попереднього перегляду.
+    ///
+    /// Поповнення чорнила не враховується: план описує малюнок одним проходом.
+    ///
+    /// # Параметри
+    /// - `drawing`: Малюнок для планування.
+    ///
+    /// # Повертає
+    /// - `Result<Job, PlanError>`: План усього малюнка або помилка планування.
+    pub fn plan_drawing(&self, drawing: &Drawing) -> Result<Job, PlanError> {
+        self.job_planner().plan(&self.strokes(drawing))
+    }
+
+    /// Перевіряє, що швидкість, прискорення та кроки на міліметр допустимі для моделі.
+    ///
+    /// # Повертає
+    /// - `Result<(), OptionsError>`: Помилка з описом першого недопустимого значення.
+    pub fn validate(&self) -> Result<(), OptionsError> {
+        if self.steps_per_unit <= 0 {
+            return Err(OptionsError::StepsPerUnit(self.steps_per_unit));
+        }
+        if self.step_mode == StepMode::Disable {
+            return Err(OptionsError::StepMode);
+        }
+        let limit = self.velocity_limit();
+        if !(self.max_velocity > 0.0 && self.max_velocity <= limit) {
+            return Err(OptionsError::Velocity {
+                velocity: self.max_velocity,
+                limit,
+                model: self.model.name(),
+            });
+        }
+        let limit = self.model.max_acceleration();
+        for acceleration in [Some(self.acceleration), self.travel_acceleration]
+            .into_iter()
+            .flatten()
+        {
+            if !(acceleration > 0.0 && acceleration <= limit) {
+                return Err(OptionsError::Acceleration {
+                    acceleration,
+                    limit,
+                    model: self.model.name(),
+                });
+            }
+        }
+        if !(0.05..=1.0).contains(&self.detail_speed_factor) {
+            return Err(OptionsError::DetailSpeedFactor(self.detail_speed_factor));
+        }
+        if let Some(refill) = &self.refill {
+            if !(refill.every > 0.0 && refill.every.is_finite()) {
+                return Err(OptionsError::RefillDistance(refill.every));
+            }
+            let (x, y) = refill.location;
+            let (width, height) = self.model.travel();
+            let inside =
+                |value: f64, size: f64| value - refill.swirl >= 0.0 && value + refill.swirl <= size;
+            if !(refill.swirl >= 0.0 && inside(x, width) && inside(y, height)) {
+                return Err(OptionsError::DipLocation {
+                    location: refill.location,
+                    swirl: refill.swirl,
+                    model: self.model.name(),
+                });
+            }
+        }
+        if let Some((x, y)) = self.park_position {
+            if !self.model.reaches(x, y) {
+                return Err(OptionsError::ParkPosition {
+                    position: (x, y),
+                    model: self.model.name(),
+                });
+            }
+        }
+        match &self.tool {
+            Tool::Pen => {}
+            Tool::Pwm { pin, intensities } => {
+                if *pin > 24 {
+                    return Err(OptionsError::ToolOutput(self.tool.clone()));
+                }
+                if let Some(&intensity) = intensities
+                    .iter()
+                    .find(|intensity| !(0.0..=1.0).contains(*intensity))
+                {
+                    return Err(OptionsError::Intensity(intensity));
+                }
+            }
+            Tool::Pin { port, pin } => {
+                if !('A'..='E').contains(port) || *pin > 7 {
+                    return Err(OptionsError::ToolOutput(self.tool.clone()));
+                }
+            }
         }
+        Ok(())
+    }
+
+    /// Переводить швидкість каретки в частоту кроків для команд EBB.
+    ///
+    /// # Параметри
+    /// - `velocity`: Швидкість (мм/с).
+    ///
+    /// # Повертає
+    /// - `u32`: Частота кроків (кроків/с) у допустимому для EBB діапазоні.
+    pub fn step_rate(&self, velocity: f64) -> u32 {
+        (velocity * self.steps_per_unit as f64)
+            .round()
+            .clamp(2.0, MAX_STEP_RATE) as u32
     }
 }
 
@@ -84,21 +489,151 @@ impl AxiDrawModel {
         }
     }
 
+    /// Повертає короткий код моделі для командного рядка та файлів завдань.
+    pub fn code(&self) -> &'static str {
+        match self {
+            AxiDrawModel::V3 => "v3",
+            AxiDrawModel::V3A3 => "v3a3",
+            AxiDrawModel::SEA3 => "sea3",
+            AxiDrawModel::Mini => "mini",
+        }
+    }
+
+    /// Хід кареток моделі по осях X і Y (в мм): межі, в яких можна малювати й
+    /// переїжджати.
+    pub fn travel(&self) -> (f64, f64) {
+        match self {
+            AxiDrawModel::V3 => (215.9, 279.4),
+            AxiDrawModel::V3A3 | AxiDrawModel::SEA3 => (279.4, 431.8),
+            AxiDrawModel::Mini => (160.0, 101.0),
+        }
+    }
+
+    /// Чи досяжна точка (в мм) в межах ходу кареток моделі.
+    pub fn reaches(&self, x: f64, y: f64) -> bool {
+        let (width, height) = self.travel();
+        (0.0..=width).contains(&x) && (0.0..=height).contains(&y)
+    }
+
     /// Повертає ширину робочої області моделі в міліметрах.
     pub fn width(&self) -> f64 {
+        self.travel().0
+    }
+
+    /// Передача від моторів до каретки моделі.
+    ///
+    /// Зараз усі моделі AxiDraw рухають каретку ременем GT2 з кроком 2 мм на шківах з 20
+    /// зубцями від моторів з 200 кроками на оберт, тож повний крок зсуває каретку на 0.2 мм.
+    /// Набори з іншим ременем чи шківом задають свою передачу через `Options::set_drive` або
+    /// поле `drive` профілю.
+    pub fn drive(&self) -> Drive {
+        match self {
+            AxiDrawModel::V3 | AxiDrawModel::V3A3 | AxiDrawModel::SEA3 => Drive {
+                belt_pitch: 2.0,
+                pulley_teeth: 20,
+                steps_per_revolution: 200,
+            },
+            AxiDrawModel::Mini => Drive {
+                belt_pitch: 2.0,
+                pulley_teeth: 20,
+                steps_per_revolution: 200,
+            },
+        }
+    }
+
+    /// Типова швидкість малювання моделі (мм/с).
+    pub fn default_velocity(&self) -> f64 {
+        match self {
+            AxiDrawModel::V3 | AxiDrawModel::V3A3 | AxiDrawModel::Mini => 20.0,
+            AxiDrawModel::SEA3 => 15.0,
+        }
+    }
+
+    /// Типове прискорення моделі (мм/с²).
+    ///
+    /// Довші ремені й важча каретка A3-моделей пружнять сильніше, тож їм потрібне
+    /// м'якше прискорення.
+    pub fn default_acceleration(&self) -> f64 {
+        match self {
+            AxiDrawModel::V3 | AxiDrawModel::Mini => 16.0,
+            AxiDrawModel::V3A3 => 12.0,
+            AxiDrawModel::SEA3 => 10.0,
+        }
+    }
+
+    /// Найбільша безпечна швидкість каретки моделі (мм/с).
+    ///
+    /// У режимі 1/16 мотори втрачають момент на високих частотах кроків, тож грубіші
+    /// режими дозволяють швидші переїзди.
+    ///
+    /// # Параметри
+    /// - `step_mode`: Режим мікрокроку моторів.
+    pub fn max_velocity(&self, step_mode: StepMode) -> f64 {
+        let fine = step_mode == StepMode::OneSixteenth;
+        match (self, fine) {
+            (AxiDrawModel::V3 | AxiDrawModel::V3A3 | AxiDrawModel::Mini, true) => 220.0,
+            (AxiDrawModel::V3 | AxiDrawModel::V3A3 | AxiDrawModel::Mini, false) => 380.0,
+            (AxiDrawModel::SEA3, true) => 200.0,
+            (AxiDrawModel::SEA3, false) => 350.0,
+        }
+    }
+
+    /// Найбільше безпечне прискорення моделі (мм/с²).
+    pub fn max_acceleration(&self) -> f64 {
         match self {
-            AxiDrawModel::V3 => 215.9,
-            AxiDrawModel::V3A3 | AxiDrawModel::SEA3 => 279.4,
-            AxiDrawModel::Mini => 160.0,
+            AxiDrawModel::V3 | AxiDrawModel::Mini => 1000.0,
+            AxiDrawModel::V3A3 => 750.0,
+            AxiDrawModel::SEA3 => 600.0,
         }
     }
 
     /// Повертає висоту робочої області моделі в міліметрах.
     pub fn height(&self) -> f64 {
-        match self {
-            AxiDrawModel::V3 => 279.4,
-            AxiDrawModel::V3A3 | AxiDrawModel::SEA3 => 431.8,
-            AxiDrawModel::Mini => 101.0,
+        self.travel().1
+    }
+}
+
+/// Передача від мотора до каретки: ремінь на шківі мотора.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Drive {
+    pub belt_pitch: f64,           // Крок зубців ременя (в мм).
+    pub pulley_teeth: u32,         // Кількість зубців шківа мотора.
+    pub steps_per_revolution: u32, // Повні кроки мотора на оберт.
+}
+
+impl Drive {
+    /// Кроки мотора на міліметр руху каретки в режимі мікрокроку.
+    ///
+    /// Оберт шківа зсуває ремінь на `belt_pitch · pulley_teeth` мм, а дробове значення
+    /// округлюється до цілого кроку.
+    ///
+    /// # Параметри
+    /// - `step_mode`: Режим мікрокроку моторів.
+    ///
+    /// # Повертає
+    /// - `i32`: Кроки на міліметр; 0 для вимкнених моторів чи передачі без руху.
+    pub fn steps_per_unit(&self, step_mode: StepMode) -> i32 {
+        let travel = self.belt_pitch * self.pulley_teeth as f64;
+        let steps = self.steps_per_revolution as f64 * step_mode.microsteps() as f64;
+        match travel > 0.0 {
+            true => (steps / travel).round() as i32,
+            false => 0,
+        }
+    }
+}
+
+impl FromStr for AxiDrawModel {
+    type Err = OptionsError;
+
+    /// Розбирає код моделі: `v3`, `v3a3`, `sea3` або `mini`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "v3" => Ok(AxiDrawModel::V3),
+            "v3a3" => Ok(AxiDrawModel::V3A3),
+            "sea3" => Ok(AxiDrawModel::SEA3),
+            "mini" => Ok(AxiDrawModel::Mini),
+            _ => Err(OptionsError::Model(s.to_string())),
         }
     }
 }
@@ -107,6 +642,16 @@ impl AxiDrawModel {
 pub struct Axidraw {
     pub device: Device,
     pub options: Options,
+    pub control: PlotControl, // Пульт для паузи, скасування та стану малювання.
+    step_remainder: (f64, f64), // Дробові кроки осей A і B, що переносяться в наступну команду.
+    emitter: StepEmitter,     // Команди LM у кроках моторів з моделлю акумуляторів плати.
+    expected_steps: (i64, i64), // Очікувані глобальні позиції моторів 1 і 2 (в кроках).
+    moves_since_check: usize, // Команди руху від останньої звірки позиції з пристроєм.
+    checkpoints_since_button: usize, // Контрольні точки від останнього опитування кнопки.
+    retries_before_plot: u64, // Повтори запитів до плати до початку поточного малюнка.
+    layer: usize, // Номер шару, що малюється; від нього залежить потужність інструмента.
+    parked: bool, // Каретку запарковано поза початком координат.
+    queue_end: Option<Instant>, // Орієнтовна мить, коли плата виконає чергу руху з опущеним пером.
 }
 
 impl Axidraw {
@@ -118,7 +663,55 @@ impl Axidraw {
     /// # Повертає
     /// - `Result<Self, DeviceError>`: Повертає `Ok(Axidraw)` при успішному створенні або `DeviceError` у разі помилки.
     pub fn new(options: Options) -> Result<Self, DeviceError> {
-        let device_options = DeviceOptions {
+        let device = Device::new(Self::device_options(&options))?;
+        Self::with_device(device, options)
+    }
+
+    /// Створює екземпляр `Axidraw`, що працює через імітований пристрій без апаратного забезпечення.
+    ///
+    /// # Параметри
+    /// - `options`: Об'єкт `Options`, що містить налаштування для AxiDraw.
+    ///
+    /// # Повертає
+    /// - `Result<(Self, Trace), DeviceError>`: Екземпляр `Axidraw` і журнал команд, надісланих пристрою.
+    pub fn simulated(options: Options) -> Result<(Self, Trace), DeviceError> {
+        let port = MockPort::new(options.steps_per_unit);
+        let trace = port.trace();
+        let device = Device::with_port(Box::new(port), Self::device_options(&options))?;
+        Ok((Self::with_device(device, options)?, trace))
+    }
+
+    fn with_device(mut device: Device, options: Options) -> Result<Self, DeviceError> {
+        // Облік позиції починаємо з того місця, де мотори стоять зараз
+        let (motor1, motor2) = device.position()?;
+        // Вихід інструмента лишається вимкненим, доки перо не «опуститься»
+        match options.tool {
+            Tool::Pen => {}
+            Tool::Pwm { pin, .. } => device.pwm_output(pin, 0, None)?,
+            Tool::Pin { port, pin } => {
+                device.pin_direction(port, pin, 0)?;
+                device.pin_output(port, pin, false)?;
+            }
+        }
+        Ok(Self {
+            device,
+            emitter: StepEmitter::new(options.steps_per_unit as f64),
+            options,
+            control: PlotControl::new(),
+            step_remainder: (0.0, 0.0),
+            expected_steps: (motor1 as i64, motor2 as i64),
+            moves_since_check: 0,
+            checkpoints_since_button: 0,
+            retries_before_plot: 0,
+            parked: false,
+            layer: 0,
+            queue_end: None,
+        })
+    }
+
+    /// Переносить налаштування пристрою з опцій AxiDraw.
+    fn device_options(options: &Options) -> DeviceOptions {
+        DeviceOptions {
             steps_per_unit: options.steps_per_unit,
             pen_up_position: options.pen_up_position,
             pen_up_speed: options.pen_up_speed,
@@ -126,102 +719,961 @@ impl Axidraw {
             pen_down_position: options.pen_down_position,
             pen_down_speed: options.pen_down_speed,
             pen_down_delay: options.pen_down_delay,
-            step_mode: StepMode::OneSixteenth,
+            pen_settle: options.pen_settle,
+            step_mode: options.step_mode,
             port_name: options.port.clone(),
             port_config: options.port_config.clone(),
-        };
-
-        // Ініціалізуємо пристрій
-        let device = Device::new(device_options)?;
-        Ok(Self { device, options })
+            transcript: options.transcript.clone(),
+            serial: options.serial,
+        }
     }
 
     /// Метод для малювання, який приймає `Drawing`.
     ///
+    /// Хід малювання відображається в `control`: через нього інший потік може
+    /// призупинити, відновити або скасувати малюнок.
+    ///
     /// # Параметри
     /// - `drawing`: Об'єкт `Drawing`, що містить шляхи для малювання.
     ///
     /// # Повертає
-    /// - `Result<(), anyhow::Error>`: Повертає `Ok(())`, якщо малювання успішне, або помилку в разі невдачі.
-    pub fn draw(&mut self, drawing: &Drawing) -> Result<(), anyhow::Error> {
+    /// - `Result<(), Error>`: Повертає `Ok(())`, якщо малювання успішне, або помилку в разі невдачі.
+    pub fn draw(&mut self, drawing: &Drawing) -> Result<(), Error> {
+        let length = drawing.paths.euclidean_length();
+        self.control.start(drawing.paths.0.len(), length);
+        self.supervise(|axidraw| axidraw.plot(drawing))
+    }
+
+    /// Малює шляхи в міру їх надходження, не тримаючи весь малюнок у пам'яті.
+    ///
+    /// Шляхи плануються й малюються порціями по `STREAM_BATCH`, тож пам'ять обмежена
+    /// розміром порції, а не малюнка. Порція росте ще на `STREAM_CHAIN` шляхів, доки
+    /// наступний шлях починається в кінці попереднього. Довша безперервна крива обривається
+    /// на межі порції з опущеним пером, а наступна порція продовжує рух з тією самою
+    /// швидкістю, тож крива малюється одним рухом без зупинки. Межі аркуша перевіряються
+    /// для кожної порції перед її малюванням, тож шлях за межами зупиняє малюнок уже після
+    /// попередніх порцій.
+    /// Впорядкування шляхів `order`, їх напрямок `direction` і поповнення чорнила `refill`
+    /// не застосовуються: для них потрібен увесь малюнок.
+    ///
+    /// # Параметри
+    /// - `paths`: Шляхи малюнка в порядку малювання, наприклад з `Drawing::svg_paths`.
+    ///
+    /// # Повертає
+    /// - `Result<(), Error>`: Повертає `Ok(())`, якщо малювання успішне, або помилку в разі невдачі.
+    pub fn draw_iter<I>(&mut self, paths: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = LineString<f64>>,
+    {
+        // Кількість і довжина шляхів невідомі наперед і уточнюються в міру малювання
+        self.control.start(0, 0.0);
+        self.supervise(|axidraw| axidraw.plot_stream(paths.into_iter()))
+    }
+
+    /// Малює шари один за одним, зупиняючись між ними для заміни пера.
+    ///
+    /// Після кожного шару, крім останнього, каретка повертається додому, а малювання
+    /// стає на паузу до натискання кнопки PRG або `resume` через пульт. Після
+    /// скасування журнал повідомляє номер шляху, з якого малювання можна продовжити.
+    /// Імпульсний інструмент (`Tool::Pwm`) вмикається з потужністю свого шару.
+    ///
+    /// # Параметри
+    /// - `layers`: Шари в порядку малювання, наприклад з `JobFile`.
+    /// - `first_path`: Номер першого шляху шарів у всьому завданні (з одиниці); від нього
+    ///   рахується номер шляху для продовження.
+    ///
+    /// # Повертає
+    /// - `Result<(), Error>`: Повертає `Ok(())`, якщо намальовано всі шари, або помилку в разі невдачі.
+    pub fn draw_layers(&mut self, layers: &[Drawing], first_path: usize) -> Result<(), Error> {
+        let result = self.draw_layer_sequence(layers, first_path);
+        self.layer = 0;
+        result
+    }
+
+    /// Малює непорожні шари, запам'ятовуючи номер кожного для потужності інструмента.
+    fn draw_layer_sequence(&mut self, layers: &[Drawing], first_path: usize) -> Result<(), Error> {
+        let layers: Vec<(usize, &Drawing)> = layers
+            .iter()
+            .enumerate()
+            .filter(|(_, layer)| !layer.paths.0.is_empty())
+            .collect();
+        let stopped = |path: usize| {
+            info!(
+                "{}",
+                tr!(
+                    "Plot stopped at path {}; it can be resumed from there.",
+                    "Малювання зупинено на шляху {}; з нього його можна продовжити.",
+                    path
+                )
+            );
+        };
+        let mut offset = first_path.saturating_sub(1);
+        for (index, &(number, layer)) in layers.iter().enumerate() {
+            self.layer = number;
+            if let Err(e) = self.draw(layer) {
+                if matches!(e, Error::Cancelled(_)) {
+                    stopped(offset + self.control.status().current_path.max(1));
+                }
+                return Err(e);
+            }
+            offset += layer.paths.0.len();
+            if index + 1 == layers.len() {
+                break;
+            }
+
+            info!(
+                "{}",
+                tr!(
+                    "Layer {} of {} finished; change the pen and press PRG to continue.",
+                    "Шар {} з {} намальовано; змініть перо й натисніть PRG, щоб продовжити.",
+                    index + 1,
+                    layers.len()
+                )
+            );
+            if !self.pause_between_parts()? {
+                stopped(offset + 1);
+                return Err(Cancelled.into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Малює сторінки розбитого малюнка одна за одною, зупиняючись між ними, щоб
+    /// покласти наступний аркуш.
+    ///
+    /// Сторінки з `Drawing::tile` мають розмір області малювання аркуша й зсуваються на
+    /// його поля. Після кожної сторінки, крім останньої, каретка повертається додому, а
+    /// малювання стає на паузу до натискання кнопки PRG або `resume` через пульт. З
+    /// `marks` перед кожною сторінкою малюються її хрестики суміщення.
+    ///
+    /// # Параметри
+    /// - `pages`: Сторінки в порядку малювання.
+    /// - `marks`: Чи малювати хрестики суміщення.
+    ///
+    /// # Повертає
+    /// - `Result<(), Error>`: Повертає `Ok(())`, якщо намальовано всі сторінки, або помилку в разі невдачі.
+    pub fn draw_pages(&mut self, pages: &[Tile], marks: bool) -> Result<(), Error> {
+        let area = self.paper().printable_area()?;
+        let shift = AffineTransform::translate(area.min().x, area.min().y);
+        for (index, page) in pages.iter().enumerate() {
+            let mut drawing = match marks {
+                true => page.with_marks(),
+                false => page.drawing.clone(),
+            };
+            drawing.paths = drawing.paths.affine_transform(&shift);
+            info!(
+                "{}",
+                tr!(
+                    "Plotting page {} of {}: row {}, column {}.",
+                    "Малюємо сторінку {} з {}: рядок {}, стовпець {}.",
+                    index + 1,
+                    pages.len(),
+                    page.row + 1,
+                    page.column + 1
+                )
+            );
+            self.draw(&drawing)?;
+            let Some(next) = pages.get(index + 1) else {
+                break;
+            };
+
+            info!(
+                "{}",
+                tr!(
+                    "Page {} of {} finished; place the sheet for row {}, column {}, align it with the marks and press PRG to continue.",
+                    "Сторінку {} з {} намальовано; покладіть аркуш для рядка {}, стовпця {}, суміщіть його з хрестиками й натисніть PRG, щоб продовжити.",
+                    index + 1,
+                    pages.len(),
+                    next.row + 1,
+                    next.column + 1
+                )
+            );
+            if !self.pause_between_parts()? {
+                return Err(Cancelled.into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Ставить малювання на паузу між шарами чи сторінками й чекає на її кінець.
+    ///
+    /// # Повертає
+    /// - `Result<bool, Error>`: `true`, якщо малювання відновили, `false`, якщо його скасували.
+    fn pause_between_parts(&mut self) -> Result<bool, Error> {
+        self.control.pause();
+        self.control.set_state(PlotState::Paused);
+        if self.wait_while_paused()? {
+            return Ok(true);
+        }
+        info!("{}", tr!("Plot cancelled.", "Малювання скасовано."));
+        self.control.set_state(PlotState::Cancelled);
+        Ok(false)
+    }
+
+    /// Виконує малюнок і відображає результат у `control`.
+    ///
+    /// Після помилки, скасування чи паніки перо піднімається, а каретка паркується.
+    fn supervise<F>(&mut self, plot: F) -> Result<(), Error>
+    where
+        F: FnOnce(&mut Self) -> Result<(), Error>,
+    {
+        self.retries_before_plot = self.device.serial_stats().retries;
+        let result = match panic::catch_unwind(AssertUnwindSafe(|| plot(self))) {
+            Ok(result) => result,
+            Err(payload) => {
+                error!(
+                    "{}",
+                    tr!("Panic while plotting.", "Паніка під час малювання.")
+                );
+                self.control
+                    .fail(tr!("Panic while plotting", "Паніка під час малювання"));
+                self.park();
+                panic::resume_unwind(payload);
+            }
+        };
+        if result.is_err() {
+            self.park();
+        }
+        self.update_retries();
+        let retries = self.control.status().retries;
+        if retries > 0 {
+            info!(
+                "{}",
+                tr!(
+                    "Board queries retried: {}",
+                    "Повторено запитів до плати: {}",
+                    retries
+                )
+            );
+        }
+        match &result {
+            Ok(()) => self.control.set_state(PlotState::Finished),
+            Err(Error::Cancelled(_)) => {
+                info!("{}", tr!("Plot cancelled.", "Малювання скасовано."));
+                self.control.set_state(PlotState::Cancelled);
+            }
+            Err(e) => self.control.fail(e.to_string()),
+        }
+        result
+    }
+
+    /// Виконує малюнок: перевіряє межі, проходить усі шляхи та повертається додому.
+    fn plot(&mut self, drawing: &Drawing) -> Result<(), Error> {
         // Логування інформації про малюнок
-        info!("Кількість шляхів: {}", drawing.paths.0.len());
-        info!("Межі малюнка: {:?}", drawing.bounds);
+        info!(
+            "{}",
+            tr!(
+                "Number of paths: {}",
+                "Кількість шляхів: {}",
+                drawing.paths.0.len()
+            )
+        );
+        info!(
+            "{}",
+            tr!("Drawing bounds: {:?}", "Межі малюнка: {:?}", drawing.bounds)
+        );
+
+        // Обнуляємо позицію одразу, щоб після будь-якої помилки паркування вело сюди ж
+        self.leave_park()?;
+        self.device.zero_position()?;
+        self.reset_position_tracking();
+
+        // Перевіряємо, що малюнок не виходить за межі аркуша або робочої області моделі
+        let paper = self.paper();
+        if !paper.fits_model(self.options.model) {
+            warn!(
+                "{}",
+                tr!(
+                    "Sheet {} ({:?} mm) is larger than the travel of {}.",
+                    "Аркуш {} ({:?} мм) більший за робочу область {}.",
+                    paper.name,
+                    paper.size(),
+                    self.options.model.name()
+                )
+            );
+        }
+        let placed = drawing.in_coordinates(&self.options.coordinates);
+        if !placed.fits(&paper) {
+            return Err(Error::OutOfBounds {
+                bbox: placed.bbox(),
+                paper: paper.name.to_string(),
+                size: paper.size(),
+            });
+        }
 
         // Піднімаємо перо перед початком малювання
+        self.pen_up()?;
+
+        // Впорядковуємо шляхи й додаємо захід і вихід, якщо їх увімкнено
+        let strokes = self.options.strokes(drawing);
+
+        if let Some(refill) = self.options.refill {
+            return self.plot_with_refills(&strokes, &refill);
+        }
+
+        // Плануємо весь малюнок наперед, зливаючи шляхи, що продовжують один одного
+        let job = self.options.job_planner().plan(&strokes)?;
+        debug!("{}", job);
+        info!(
+            "{}",
+            tr!(
+                "Estimated motion time: {:.1} s",
+                "Орієнтовний час руху: {:.1} с",
+                job.total_time()
+            )
+        );
+        self.control.set_total_paths(job.pen_downs());
+
+        let mut path = 0;
+        self.run_job(&job, &mut path)?;
+        self.return_home()
+    }
+
+    /// Малює шляхи порціями, занурюючи перо в чорнильницю перед кожною з них.
+    ///
+    /// Кожна порція планується від чорнильниці, тож переїзд назад до місця, де
+    /// закінчилося чорнило, входить у план порції.
+    fn plot_with_refills(&mut self, strokes: &[Stroke], refill: &Refill) -> Result<(), Error> {
+        let planner = self.options.job_planner();
+        let location = Point::new(refill.location.0, refill.location.1);
+        let batches = refill.split(strokes);
+        let jobs = batches
+            .iter()
+            .map(|batch| planner.plan_from(location, batch))
+            .collect::<Result<Vec<_>, _>>()?;
+        info!(
+            "{}",
+            tr!(
+                "Estimated motion time: {:.1} s, ink dips: {}",
+                "Орієнтовний час руху: {:.1} с, занурень у чорнильницю: {}",
+                jobs.iter().map(Job::total_time).sum::<f64>(),
+                jobs.len()
+            )
+        );
+        self.control
+            .set_total_paths(jobs.iter().map(Job::pen_downs).sum());
+
+        let (mut path, mut position) = (0, Point::new(0.0, 0.0));
+        for (batch, job) in batches.iter().zip(&jobs) {
+            self.dip(position, refill)?;
+            debug!("{}", job);
+            self.run_job(job, &mut path)?;
+            if let Some(end) = batch.last().and_then(|stroke| stroke.points.last()) {
+                position = *end;
+            }
+        }
+        self.return_home()
+    }
+
+    /// Занурює перо: переїжджає до чорнильниці, опускає перо, кружляє ним і піднімає його.
+    fn dip(&mut self, from: Point<f64>, refill: &Refill) -> Result<(), Error> {
+        let location = Point::new(refill.location.0, refill.location.1);
+        self.pen_up()?;
+        if from != location {
+            self.run_path(vec![from, location])?;
+        }
+        self.pen_down()?;
+        let swirl = refill.swirl_path();
+        if swirl.len() > 1 {
+            self.run_path(swirl)?;
+        }
+        Ok(self.pen_up()?)
+    }
+
+    /// Малює шляхи з ітератора порціями, плануючи кожну від кінця попередньої.
+    fn plot_stream(&mut self, paths: impl Iterator<Item = LineString<f64>>) -> Result<(), Error> {
+        self.leave_park()?;
         self.device.zero_position()?;
-        self.device.pen_up()?;
+        self.reset_position_tracking();
+        self.pen_up()?;
 
-        // Ініціалізація змінної для відстеження останньої точки
-        let mut last_position = Point::new(0.0, 0.0);
+        let paper = self.paper();
+        let planner = self.options.job_planner();
+        let mut position = Point::new(0.0, 0.0);
+        let (mut path, mut total_paths, mut batches) = (0, 0, 0);
+        // Шляхи, на яких обірвалася попередня порція, і швидкість каретки на їх початку
+        let (mut held, mut entry): (Vec<LineString<f64>>, Option<f64>) = (vec![], None);
+        // Шлях, на якому каретка встигає загальмувати з найбільшої швидкості
+        let braking = self.options.max_velocity.powi(2) / (2.0 * self.options.acceleration);
+        let mut paths = paths.filter(|line| !line.0.is_empty()).peekable();
+        let continues = |last: &LineString<f64>, line: &LineString<f64>| {
+            last.0.last().is_some_and(|&end| {
+                let gap = line.0[0] - end;
+                gap.x.hypot(gap.y) <= CHAIN_TOLERANCE
+            })
+        };
+        loop {
+            let mut batch: Vec<LineString<f64>> = std::mem::take(&mut held)
+                .into_iter()
+                .chain(paths.by_ref())
+                .take(STREAM_BATCH)
+                .collect();
+            // Шлях, що продовжує останній, планується з ним разом, щоб не зупинятися на стику,
+            // але не довше за `STREAM_CHAIN`: далі порція обривається посеред лінії
+            while batch.len() < STREAM_BATCH + STREAM_CHAIN {
+                let Some(line) = batch
+                    .last()
+                    .and_then(|last| paths.next_if(|line| continues(last, line)))
+                else {
+                    break;
+                };
+                batch.push(line);
+            }
+            if batch.is_empty() {
+                break;
+            }
+            // Продовження обірваної лінії, на якому каретка встигає загальмувати, планується
+            // наперед, а малюється вже в наступній порції
+            let mut ahead = 0.0;
+            while ahead < braking && held.len() < STREAM_CHAIN {
+                let Some(line) = held
+                    .last()
+                    .or(batch.last())
+                    .and_then(|last| paths.next_if(|line| continues(last, line)))
+                else {
+                    break;
+                };
+                ahead += line.euclidean_length();
+                held.push(line);
+            }
 
-        // Ітерація по кожному шляху в MultiLineString
-        for (i, line_string) in drawing.paths.0.iter().enumerate() {
-            if line_string.0.is_empty() {
-                continue;
+            if let Some(line) = batch.iter().chain(&held).find(|line| {
+                line.bounding_rect()
+                    .is_some_and(|bbox| !paper.contains(&bbox))
+            }) {
+                return Err(Error::OutOfBounds {
+                    bbox: line.bounding_rect(),
+                    paper: paper.name.to_string(),
+                    size: paper.size(),
+                });
             }
 
-            // Отримуємо першу точку поточного шляху
-            let start_coord = line_string.0[0];
-            let start_point = Point::new(start_coord.x, start_coord.y);
+            // Поправка геометрії, як і в `Options::strokes`, діє після перевірки меж
+            let stroke = |line: &LineString<f64>| {
+                let line = match &self.options.correction {
+                    Some(correction) => line.affine_transform(&correction.transform()),
+                    None => line.clone(),
+                };
+                let mut stroke = match &self.options.leads {
+                    Some(leads) => leads.apply(&line),
+                    None => Stroke::plain(&line),
+                };
+                stroke.slow_details(self.options.detail_speed_factor);
+                stroke
+            };
+            let strokes: Vec<Stroke> = batch.iter().map(stroke).collect();
+            let ahead: Vec<Stroke> = held.iter().map(stroke).collect();
+            drop(batch);
 
-            // Переміщуємося до початкової точки з піднятим пером
-            self.run_path(vec![last_position, start_point])?;
+            let (job, exit) = planner.plan_batch(position, entry, &strokes, &ahead)?;
+            debug!("{}", job);
+            if let Some(end) = strokes.last().and_then(|stroke| stroke.points.last()) {
+                position = *end;
+            }
+            entry = exit;
+            drop((strokes, ahead));
 
-            // Опускаємо перо для початку малювання після досягнення початкової точки
-            self.device.pen_down()?;
+            batches += 1;
+            total_paths += job.pen_downs();
+            self.control.set_total_paths(total_paths);
+            self.run_job(&job, &mut path)?;
+        }
+        info!(
+            "{}",
+            tr!(
+                "Paths plotted: {} ({} batches)",
+                "Намальовано шляхів: {} ({} порцій)",
+                total_paths,
+                batches
+            )
+        );
 
-            // Малюємо шлях
-            let draw_path: Vec<Point<f64>> = line_string
-                .0
-                .iter()
-                .map(|coord| Point::new(coord.x, coord.y))
-                .collect(); // Конвертуємо всі координати на Point
+        self.return_home()
+    }
 
-            // Отримуємо останню точку перед передачею в run_path
-            let last_point = *draw_path.last().unwrap();
+    /// Виконує дії плану малюнка, нумеруючи лінії від `path`.
+    fn run_job(&mut self, job: &Job, path: &mut usize) -> Result<(), Error> {
+        // Проміжок шляху триває від опускання пера до його підняття
+        let mut path_span = None;
+        for action in &job.actions {
+            match action {
+                PlanAction::Move(plan) => {
+                    debug!("{}", plan);
+                    let _span = tracing::trace_span!(
+                        target: trace::TARGET,
+                        "block",
+                        name = "plan",
+                        detail = %format_args!("{} blocks, {:.3} s", plan.blocks.len(), plan.total_time)
+                    )
+                    .entered();
+                    self.run_plan(plan)?;
+                }
+                PlanAction::PenDown => {
+                    *path += 1;
+                    path_span =
+                        Some(tracing::trace_span!(target: trace::TARGET, "path", detail = *path));
+                    self.control.set_current_path(*path);
+                    self.pen_down()?;
+                }
+                PlanAction::PenDwell(duration) => self.dwell(*duration)?,
+                PlanAction::PenUp => {
+                    // Перо піднімається, лише коли плата виконала весь рух шляху з черги
+                    self.device.wait_until_idle(Some(IDLE_TIMEOUT))?;
+                    self.pen_up()?;
+                    drop(path_span.take());
 
-            // Виконуємо малювання по точках
-            self.run_path(draw_path)?;
+                    // З піднятим пером зупинка для звірки позиції не залишить сліду
+                    if self.moves_since_check >= POSITION_CHECK_INTERVAL {
+                        self.verify_position()?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Піднімає перо, звіряє позицію та повертає каретку до початку малюнка.
+    fn return_home(&mut self) -> Result<(), Error> {
+        // Повертаємося додому з максимальною швидкістю, переведеною в частоту кроків
+        let step_frequency = self.options.step_rate(self.options.max_velocity);
+
+        // Повертаємося до початкової позиції (0, 0) з обчисленими кроками і частотою
+        self.pen_up()?;
+        self.verify_position()?;
+
+        // Виконуємо команду home
+        self.device.home(step_frequency, None, None)?;
+        self.reset_position_tracking();
+
+        match self.options.park_position {
+            Some((x, y)) => self.park_at(x, y),
+            None => Ok(()),
+        }
+    }
+
+    /// Паркує каретку в точці (x, y), наприклад за межами аркуша, щоб його можна було
+    /// оглянути чи забрати.
+    ///
+    /// Мотори лишаються ввімкненими й після відключення, тож каретка тримається на місці,
+    /// а наступне малювання спершу повертає її туди, звідки вона приїхала.
+    ///
+    /// # Параметри
+    /// - `x`: Координата місця паркування по осі X (в мм).
+    /// - `y`: Координата місця паркування по осі Y (в мм).
+    ///
+    /// # Повертає
+    /// - `Result<(), Error>`: Помилка, якщо каретку не вдалося переїхати.
+    pub fn park_at(&mut self, x: f64, y: f64) -> Result<(), Error> {
+        self.pen_up()?;
+        self.goto(x, y)?;
+        self.device.wait_until_idle(Some(IDLE_TIMEOUT))?;
+        self.device.keep_motors_enabled(true);
+        self.parked = true;
+        info!(
+            "{}",
+            tr!(
+                "Carriage parked at ({:.1}, {:.1}) mm.",
+                "Каретку запарковано в ({:.1}, {:.1}) мм.",
+                x,
+                y
+            )
+        );
+        Ok(())
+    }
 
-            // Оновлюємо останню позицію до кінцевої точки поточного шляху
-            last_position = last_point;
+    /// Повертає запарковану каретку до початку координат перед малюванням.
+    ///
+    /// Лічильникам кроків плати можна вірити, лише якщо каретку запарковано в цьому
+    /// з'єднанні або мотори тримали її ще до підключення; інакше каретка вважається вже
+    /// поставленою додому вручну.
+    fn leave_park(&mut self) -> Result<(), Error> {
+        self.device.keep_motors_enabled(false);
+        let trusted = std::mem::take(&mut self.parked) || self.device.motors_were_enabled();
+        if !trusted || self.device.position()? == (0, 0) {
+            return Ok(());
+        }
+        info!(
+            "{}",
+            tr!(
+                "Returning the parked carriage home.",
+                "Повертаємо запарковану каретку додому."
+            )
+        );
+        self.pen_up()?;
+        self.home()
+    }
 
-            // Перевіряємо, чи є наступний шлях
-            if let Some(next_path) = drawing.paths.0.get(i + 1) {
-                // Отримуємо першу точку наступного шляху
-                let next_coord = next_path.0[0];
-                let next_point = Point::new(next_coord.x, next_coord.y);
+    /// Аварійно завершує перерваний малюнок: піднімає перо, чекає, доки мотори виконають
+    /// команди з черги, і повертає каретку до початку малюнка.
+    ///
+    /// Команди надсилаються напряму до пристрою, минаючи контрольні точки, тож паркування
+    /// працює й після скасування. Помилки лише записуються в журнал: каретку, яку не
+    /// вдалося запаркувати, вимкнуть мотори під час закриття `Device`.
+    fn park(&mut self) {
+        if let Err(e) = self.pen_up() {
+            error!(
+                "{}",
+                tr!(
+                    "Failed to raise the pen: {}",
+                    "Не вдалося підняти перо: {}",
+                    e
+                )
+            );
+            return;
+        }
+        if let Err(e) = self.device.wait_for_motors() {
+            error!(
+                "{}",
+                tr!(
+                    "Failed to wait for the motors to stop: {}",
+                    "Не вдалося дочекатися зупинки моторів: {}",
+                    e
+                )
+            );
+            return;
+        }
+        if !self
+            .device
+            .firmware
+            .supports(FirmwareVersion::HOME_AND_GENERAL_QUERY)
+        {
+            warn!(
+                "{}",
+                tr!(
+                    "Firmware does not support HM, leaving the carriage in place.",
+                    "Прошивка не підтримує HM, каретка лишається на місці."
+                )
+            );
+            return;
+        }
 
-                // Порівнюємо останню точку поточного шляху з першою точкою наступного шляху
-                if last_position.distance(&next_point) > EPSILON {
-                    // Піднімаємо перо після завершення шляху тільки якщо наступна точка далеко
-                    self.device.pen_up()?;
+        let step_frequency = self.options.step_rate(self.options.max_velocity);
+        match self
+            .device
+            .home(step_frequency, None, None)
+            .and_then(|()| self.device.wait_for_motors())
+        {
+            Ok(()) => {
+                self.reset_position_tracking();
+                info!("{}", tr!("Carriage parked.", "Каретку запарковано."));
+            }
+            Err(e) => error!(
+                "{}",
+                tr!(
+                    "Failed to park the carriage: {}",
+                    "Не вдалося запаркувати каретку: {}",
+                    e
+                )
+            ),
+        }
+    }
+
+    /// Звіряє очікувані позиції моторів з фактичними, які повідомляє пристрій (QS).
+    ///
+    /// Перед запитом метод чекає, доки мотори виконають усі команди руху. Розбіжність
+    /// після перенесення дробових кроків означає втрачені або зайві кроки й записується
+    /// в журнал як попередження.
+    ///
+    /// # Повертає
+    /// - `Result<(i64, i64), DeviceError>`: Розбіжність (фактична - очікувана) для моторів 1 і 2 в кроках.
+    pub fn verify_position(&mut self) -> Result<(i64, i64), DeviceError> {
+        self.device.wait_for_motors()?;
+        let (motor1, motor2) = self.device.read_position()?;
+        self.moves_since_check = 0;
+
+        let error = (
+            motor1 as i64 - self.expected_steps.0,
+            motor2 as i64 - self.expected_steps.1,
+        );
+        if error != (0, 0) {
+            warn!(
+                "{}",
+                tr!(
+                    "Motor position ({}, {}) differs from the expected ({}, {}) by {:?} steps.",
+                    "Позиція моторів ({}, {}) відрізняється від очікуваної ({}, {}) на {:?} кроків.",
+                    motor1,
+                    motor2,
+                    self.expected_steps.0,
+                    self.expected_steps.1,
+                    error
+                )
+            );
+        } else {
+            debug!(
+                "{}",
+                tr!(
+                    "Motor position matches the expected one: ({}, {}).",
+                    "Позиція моторів збігається з очікуваною: ({}, {}).",
+                    motor1,
+                    motor2
+                )
+            );
+        }
+        Ok(error)
+    }
+
+    /// Аварійно зупиняє мотори (ES), піднімає перо й бере позицію каретки з плати.
+    ///
+    /// Разом з рухом плата скидає чергу FIFO, тож каретка стоїть не там, куди вели надіслані
+    /// команди. Облік кроків і позиція в `control` замінюються фактичною позицією моторів,
+    /// і наступні `goto` рахуються від неї.
+    ///
+    /// # Повертає
+    /// - `Result<(f64, f64), Error>`: Позиція каретки (в мм), де її зупинено.
+    pub fn emergency_stop(&mut self) -> Result<(f64, f64), Error> {
+        self.device.abort_motors(false)?;
+        self.pen_up()?;
+        self.discard_step_state();
+        Ok(self.track_device_position()?)
+    }
+
+    /// Перечитує позицію каретки з плати й замінює нею облік позиції.
+    ///
+    /// Потрібна після зупинки руху поза цим екземпляром, наприклад `Device::abort_motors`.
+    ///
+    /// # Повертає
+    /// - `Result<(f64, f64), Error>`: Позиція каретки (в мм) після зупинки моторів.
+    pub fn resync_position(&mut self) -> Result<(f64, f64), Error> {
+        self.device.resync_position()?;
+        self.discard_step_state();
+        Ok(self.track_device_position()?)
+    }
+
+    /// Скидає дробові кроки й модель акумуляторів емітера, накопичені з команд, частини
+    /// яких плата могла не виконати.
+    fn discard_step_state(&mut self) {
+        self.step_remainder = (0.0, 0.0);
+        self.emitter = StepEmitter::new(self.options.steps_per_unit as f64);
+    }
+
+    /// Переносить щойно прочитану позицію моторів в очікувані кроки й у `control`.
+    fn track_device_position(&mut self) -> Result<(f64, f64), DeviceError> {
+        let (motor1, motor2) = self.device.position()?;
+        let steps_per_unit = self.options.steps_per_unit as f64;
+        let (x, y) = kinematics::motors_to_xy(
+            motor1 as f64 / steps_per_unit,
+            motor2 as f64 / steps_per_unit,
+        );
+        self.expected_steps = (motor1 as i64, motor2 as i64);
+        self.moves_since_check = 0;
+        self.queue_end = None;
+        self.control.set_position(x, y);
+        Ok((x, y))
+    }
+
+    /// Скидає облік позиції після обнулення (CS) або повернення додому (HM).
+    fn reset_position_tracking(&mut self) {
+        self.step_remainder = (0.0, 0.0);
+        self.emitter = StepEmitter::new(self.options.steps_per_unit as f64);
+        self.expected_steps = (0, 0);
+        self.control.set_position(0.0, 0.0);
+    }
+
+    /// Піднімає перо або вимикає інструмент та відображає його стан у `control`.
+    pub fn pen_up(&mut self) -> Result<(), DeviceError> {
+        match self.options.tool {
+            Tool::Pen => self.device.pen_up()?,
+            _ => self.switch_tool(false)?,
+        }
+        self.control.set_pen_down(false);
+        self.queue_end = None;
+        Ok(())
+    }
+
+    /// Опускає перо або вмикає інструмент та відображає його стан у `control`.
+    pub fn pen_down(&mut self) -> Result<(), DeviceError> {
+        match self.options.tool {
+            Tool::Pen => self.device.pen_down()?,
+            _ => self.switch_tool(true)?,
+        }
+        self.control.set_pen_down(true);
+        // Опущене перо стоїть на місці, доки не прийде перша команда руху
+        let delay = Duration::from_millis(self.options.pen_down_delay.max(0) as u64);
+        self.queue_end = Some(Instant::now() + delay);
+        Ok(())
+    }
+
+    /// Вмикає або вимикає вихід інструмента замість руху серво пера.
+    ///
+    /// Імпульсний вихід вмикається з потужністю поточного шару. Затримки після опускання
+    /// й підняття пера дають аерографу чи лазеру час розігнатися або згаснути.
+    fn switch_tool(&mut self, on: bool) -> Result<(), DeviceError> {
+        let delay = if on {
+            self.options.pen_down_delay
+        } else {
+            self.options.pen_up_delay
+        };
+        let delay = (delay > 0).then(|| Duration::from_millis(delay as u64));
+        match self.options.tool {
+            Tool::Pen => {}
+            Tool::Pwm { pin, .. } => {
+                let position = if on {
+                    Tool::pwm_position(self.options.tool.intensity(self.layer))
                 } else {
-                    debug!("Наступна точка близько, не підіймаємо перо.");
+                    0
+                };
+                self.device.pwm_output(pin, position, delay)?;
+            }
+            Tool::Pin { port, pin } => {
+                // PO не стає в чергу, тож спершу чекаємо завершення рухів
+                self.device.wait_for_motors()?;
+                self.device.pin_output(port, pin, on)?;
+                if let Some(delay) = delay {
+                    self.device.stepper_move(delay, 0, Some(0))?;
                 }
             }
         }
+        Ok(())
+    }
 
-        // Обчислюємо кількість кроків для повернення на початкову позицію (0, 0)
-        let steps_per_unit = self.options.steps_per_unit as f64;
+    /// Контрольна точка між командами руху: обробляє запити паузи та скасування.
+    ///
+    /// На паузі перо піднімається, а після відновлення знову опускається, якщо воно
+    /// було опущене. Після скасування перо піднімається, а малювання переривається
+    /// помилкою `Cancelled`.
+    ///
+    /// # Повертає
+    /// - `Result<(), Error>`: Повертає Ok, якщо рух можна продовжувати.
+    fn checkpoint(&mut self) -> Result<(), Error> {
+        self.update_retries();
+        self.guard_stall()?;
+        if !self.control.is_paused() && self.button_check_due() && self.device.button_pressed()? {
+            info!("{}", tr!("PRG button pressed.", "Натиснуто кнопку PRG."));
+            self.control.pause();
+        }
 
-        // Обчислюємо частоту кроків на основі максимальної швидкості
-        let max_velocity = self.options.max_velocity;
-        let step_frequency = (max_velocity * steps_per_unit).round() as u32;
+        if self.control.is_paused() && !self.control.is_cancelled() {
+            let lowered = self.control.status().pen_down;
+            if lowered {
+                self.pen_up()?;
+            }
+            // Поки малювання стоїть, позиція в `control` має показувати, де зупинилася
+            // каретка, а не де закінчилася остання надіслана команда
+            self.verify_position()?;
+            self.track_device_position()?;
+            let state = self.control.status().state;
+            self.control.set_state(PlotState::Paused);
+            info!("{}", tr!("Plot paused.", "Малювання призупинено."));
 
-        // Обмежуємо частоту кроків до дозволеного діапазону
-        let step_frequency = step_frequency.clamp(2, 25000);
+            if self.wait_while_paused()? {
+                info!("{}", tr!("Plot resumed.", "Малювання відновлено."));
+                self.control.set_state(state);
+                if lowered {
+                    self.pen_down()?;
+                }
+            }
+        }
 
-        // Повертаємося до початкової позиції (0, 0) з обчисленими кроками і частотою
-        self.device.pen_up()?;
+        if self.control.is_cancelled() {
+            self.pen_up()?;
+            return Err(Cancelled.into());
+        }
 
-        // Виконуємо команду home
-        self.device.home(step_frequency, None, None)?;
+        Ok(())
+    }
+
+    /// Піднімає перо, якщо черга руху стоїть порожньою з опущеним пером довше за `stall_limit`.
+    ///
+    /// Фломастер, що стоїть на папері, розтікається плямою. Перевірка відбувається перед
+    /// кожною командою руху: якщо плата вже давно виконала надіслане, а наступна команда
+    /// затрималася (повтори запитів, завантажений комп'ютер), перо піднімається й знову
+    /// опускається в тому самому місці перед продовженням шляху.
+    fn guard_stall(&mut self) -> Result<(), Error> {
+        let (Some(limit), Some(queue_end)) = (self.options.stall_limit, self.queue_end) else {
+            return Ok(());
+        };
+        let idle = Instant::now().saturating_duration_since(queue_end);
+        if idle <= limit {
+            return Ok(());
+        }
+        self.pen_up()?;
+        self.control.stall_lift();
+        info!(
+            "{}",
+            tr!(
+                "Motion stalled for {:.1} s with the pen down; pen raised and lowered again.",
+                "Рух стояв {:.1} с з опущеним пером; перо піднято й опущено знову.",
+                idle.as_secs_f64()
+            )
+        );
+        self.pen_down()?;
+        Ok(())
+    }
 
+    /// Стоїть на місці `duration`, не змінюючи стану пера, командами `SM` без кроків.
+    ///
+    /// Пауза стає в чергу руху за попередніми командами й ділиться на частини по
+    /// `DWELL_SLICE`, тож довгу паузу можна призупинити чи скасувати, як і рух.
+    fn dwell(&mut self, duration: Duration) -> Result<(), Error> {
+        let mut left = Duration::from_millis((duration.as_secs_f64() * 1000.0).round() as u64);
+        while !left.is_zero() {
+            self.checkpoint()?;
+            let slice = left.min(DWELL_SLICE);
+            self.device.stepper_move(slice, 0, Some(0))?;
+            self.queue_motion(slice);
+            left -= slice;
+        }
         Ok(())
     }
 
+    /// Подовжує очікувану чергу руху з опущеним пером на тривалість щойно надісланої команди.
+    fn queue_motion(&mut self, duration: Duration) {
+        if let Some(queue_end) = self.queue_end.as_mut() {
+            *queue_end = (*queue_end).max(Instant::now()) + duration;
+        }
+    }
+
+    /// Відображає в `control` кількість запитів, повторених під час поточного малюнка.
+    fn update_retries(&mut self) {
+        let retries = self.device.serial_stats().retries;
+        self.control
+            .set_retries(retries.saturating_sub(self.retries_before_plot));
+    }
+
+    /// Чи настав час опитати кнопку PRG.
+    ///
+    /// Кнопка опитується через `QG` лише на кожній `BUTTON_CHECK_INTERVAL`-й контрольній
+    /// точці, щоб не подвоювати обмін з платою під час руху.
+    fn button_check_due(&mut self) -> bool {
+        if !self.device.has_general_query() {
+            return false;
+        }
+        self.checkpoints_since_button += 1;
+        if self.checkpoints_since_button < BUTTON_CHECK_INTERVAL {
+            return false;
+        }
+        self.checkpoints_since_button = 0;
+        true
+    }
+
+    /// Чекає, доки пауза не закінчиться через пульт або повторне натискання кнопки PRG.
+    ///
+    /// # Повертає
+    /// - `Result<bool, Error>`: `true`, якщо малювання відновили, `false`, якщо його скасували.
+    fn wait_while_paused(&mut self) -> Result<bool, Error> {
+        while self.control.is_paused() {
+            if self.control.is_cancelled() {
+                return Ok(false);
+            }
+            if self.device.button_pressed()? {
+                self.control.resume();
+                break;
+            }
+            std::thread::sleep(BUTTON_POLL);
+        }
+        Ok(!self.control.is_cancelled())
+    }
+
+    /// Повертає аркуш, у межах якого виконується малювання.
+    ///
+    /// # Повертає
+    /// - `Paper`: Аркуш з опцій або, якщо його не вказано, вся робоча область моделі.
+    pub fn paper(&self) -> Paper {
+        self.options
+            .paper
+            .unwrap_or_else(|| Paper::from_model(self.options.model))
+    }
+
     /// Повертає пристрій до "домашньої" позиції (0, 0).
     ///
     /// Ця функція використовує внутрішній виклик функції `go_to`, щоб перемістити
@@ -229,9 +1681,9 @@ impl Axidraw {
     /// пристрою. Це аналог команди повернення до початку робочої області.
     ///
     /// # Повертає:
-    /// - `Result<(), anyhow::Error>`: Повертає `Ok`, якщо переміщення виконане успішно,
-    /// або помилку у випадку невдачі.
-    fn home(&mut self) -> Result<(), anyhow::Error> {
+    /// - `Result<(), Error>`: Повертає `Ok`, якщо переміщення виконане успішно,
+    ///   або помилку у випадку невдачі.
+    pub fn home(&mut self) -> Result<(), Error> {
         self.goto(0.0, 0.0)
     }
 
@@ -242,8 +1694,8 @@ impl Axidraw {
     /// - `dy`: Відносна відстань по осі Y.
     ///
     /// # Повертає
-    /// - `Result<(), anyhow::Error>`: Повертає Ok або помилку у випадку невдачі.
-    fn move_to(&mut self, dx: f64, dy: f64) -> Result<(), anyhow::Error> {
+    /// - `Result<(), Error>`: Повертає Ok або помилку у випадку невдачі.
+    pub fn move_to(&mut self, dx: f64, dy: f64) -> Result<(), Error> {
         // Формування шляху для відносного переміщення від (0, 0) до (dx, dy)
         let path = vec![
             Point::new(0.0, 0.0), // Початкова точка
@@ -260,39 +1712,101 @@ impl Axidraw {
     /// - `plan`: Об'єкт `Plan`, що містить блоки руху.
     ///
     /// # Повертає
-    /// - `Result<(), anyhow::Error>`: Повертає Ok або помилку у випадку невдачі.
-    fn run_plan(&mut self, plan: &Plan) -> Result<(), anyhow::Error> {
+    /// - `Result<(), Error>`: Повертає Ok або помилку у випадку невдачі.
+    fn run_plan(&mut self, plan: &Plan) -> Result<(), Error> {
+        // План з іншого планувальника не сповільнено, тож частоти кроків не надсилаються
+        let violations = plan.rate_violations(self.options.steps_per_unit as f64);
+        if let Some(&first) = violations.first() {
+            return Err(PlanError::StepRate {
+                count: violations.len(),
+                first,
+            }
+            .into());
+        }
+        if self.options.low_level_moves {
+            return self.run_plan_steps(plan);
+        }
         let step_ms = TIMESLICE_MS;
         let step_s = step_ms as f64 / 1000.0;
-        let mut t = 0.0;
 
-        while t < plan.total_time {
-            // Отримуємо стани на початку та в кінці кроку
-            let i1 = plan
-                .instant(t)
-                .ok_or_else(|| anyhow::anyhow!("Не вдалося отримати стан на t"))?;
-            let i2 = plan
-                .instant(t + step_s)
-                .ok_or_else(|| anyhow::anyhow!("Не вдалося отримати стан на t + step_s"))?;
+        // Стани на початку та в кінці кожного кроку беруться по черзі з одного проходу плану
+        let mut samples = plan.sample(step_s);
+        let Some(mut i1) = samples.next() else {
+            return Ok(());
+        };
+        for i2 in samples {
+            // Даємо змогу призупинити або скасувати малювання між командами
+            self.checkpoint()?;
 
             // Обчислюємо зміну позиції
             let delta = i2.position - i1.position;
 
-            // Конвертуємо зміну в кроки двигуна
-            let sx = (delta.x() * self.options.steps_per_unit as f64).round();
-            let sy = (delta.y() * self.options.steps_per_unit as f64).round();
+            // Конвертуємо зміну в кроки двигуна, переносячи дробовий залишок попередніх
+            // команд, щоб округлення не накопичувалось у зміщення від запланованої позиції
+            let steps_per_unit = self.options.steps_per_unit as f64;
+            let exact_x = delta.x() * steps_per_unit + self.step_remainder.0;
+            let exact_y = delta.y() * steps_per_unit + self.step_remainder.1;
+            let sx = exact_x.round();
+            let sy = exact_y.round();
+            self.step_remainder = (exact_x - sx, exact_y - sy);
 
             // Виконуємо команду руху (XM - змішана геометрія для осей A та B)
             self.device
                 .stepper_move_mixed(step_ms as u32, sx as i32, sy as i32)?;
+            self.queue_motion(Duration::from_millis(step_ms as u64));
 
-            // Збільшуємо час
-            t += step_s;
+            // XM сам розкладає зміщення по X і Y на мотори за кінематикою CoreXY
+            let (motor1, motor2) = kinematics::xy_to_motors(sx, sy);
+            self.expected_steps.0 += motor1 as i64;
+            self.expected_steps.1 += motor2 as i64;
+            self.moves_since_check += 1;
+
+            // Відображаємо фактичне зміщення каретки у стані малювання
+            self.control
+                .advance(sx / steps_per_unit, sy / steps_per_unit, i2.velocity);
+            i1 = i2;
         }
 
         Ok(())
     }
 
+    /// Виконує план руху командами `LM` у кроках моторів.
+    ///
+    /// На відміну від `XM`, кроки рахуються одразу для моторів за моделлю акумуляторів
+    /// плати, тож кожна команда закінчується на цілому кроці запланованої позиції.
+    ///
+    /// # Параметри
+    /// - `plan`: Об'єкт `Plan`, що містить блоки руху.
+    ///
+    /// # Повертає
+    /// - `Result<(), Error>`: Повертає Ok або помилку у випадку невдачі.
+    fn run_plan_steps(&mut self, plan: &Plan) -> Result<(), Error> {
+        let steps_per_unit = self.options.steps_per_unit as f64;
+        // Після зміни режиму мікрокроку точна позиція в старих кроках уже не має сенсу
+        if self.emitter.steps_per_unit() != steps_per_unit {
+            self.emitter = StepEmitter::new(steps_per_unit);
+        }
+        for step in self.emitter.emit(plan, TIMESLICE_MS as f64 / 1000.0) {
+            // Даємо змогу призупинити або скасувати малювання між командами
+            self.checkpoint()?;
+
+            let ([rate1, rate2], [steps1, steps2], [accel1, accel2]) =
+                (step.rates, step.steps, step.accels);
+            self.device
+                .low_level_move(rate1, steps1, accel1, rate2, steps2, accel2, step.clear)?;
+            self.queue_motion(Duration::from_secs_f64(step.ticks as f64 / TICK_RATE));
+            self.expected_steps.0 += steps1 as i64;
+            self.expected_steps.1 += steps2 as i64;
+            self.moves_since_check += 1;
+
+            let (dx, dy) = kinematics::motors_to_xy(steps1 as f64, steps2 as f64);
+            let (dx, dy) = (dx / steps_per_unit, dy / steps_per_unit);
+            let velocity = dx.hypot(dy) * TICK_RATE / step.ticks.max(1) as f64;
+            self.control.advance(dx, dy, velocity);
+        }
+        Ok(())
+    }
+
     /// Виконує переміщення до абсолютних координат (x, y).
     ///
     /// # Параметри
@@ -300,18 +1814,27 @@ impl Axidraw {
     /// - `y`: Абсолютна координата по осі Y.
     ///
     /// # Повертає
-    /// - `Result<(), anyhow::Error>`: Повертає Ok або помилку у випадку невдачі.
-    fn goto(&mut self, x: f64, y: f64) -> Result<(), anyhow::Error> {
+    /// - `Result<(), Error>`: Повертає Ok, `Error::OutOfBounds` для точки поза ходом
+    ///   кареток моделі або іншу помилку у випадку невдачі.
+    pub fn goto(&mut self, x: f64, y: f64) -> Result<(), Error> {
+        let model = self.options.model;
+        if !model.reaches(x, y) {
+            return Err(Error::OutOfBounds {
+                bbox: Some(Rect::new(Coord { x, y }, Coord { x, y })),
+                paper: model.name().to_string(),
+                size: model.travel(),
+            });
+        }
+
         // Зчитуємо поточні позиції кроків моторів
-        let (motor1_steps, motor2_steps) = self.device.read_position()?;
+        let (motor1_steps, motor2_steps) = self.device.position()?;
 
         // Конвертуємо позиції кроків у координати x і y
         let steps_per_unit = self.options.steps_per_unit as f64;
-        let a = motor1_steps as f64 / steps_per_unit;
-        let b = motor2_steps as f64 / steps_per_unit;
-
-        let current_y = (a - b) / 2.0;
-        let current_x = current_y + b;
+        let (current_x, current_y) = kinematics::motors_to_xy(
+            motor1_steps as f64 / steps_per_unit,
+            motor2_steps as f64 / steps_per_unit,
+        );
 
         // Формуємо шлях від поточної позиції до нової позиції (x, y)
         let path = vec![
@@ -329,20 +1852,645 @@ impl Axidraw {
     /// - `path`: Вектор точок `Point<f64>`, які визначають шлях руху.
     ///
     /// # Повертає
-    /// - `Result<(), anyhow::Error>`: Повертає Ok або помилку у випадку невдачі.
-    fn run_path(&mut self, path: Vec<Point<f64>>) -> Result<(), anyhow::Error> {
-        // Генеруємо план руху на основі шляху
-        let plan = Plan::new(
-            path,
-            vec![],
-            vec![],
-            self.options.acceleration,
-            self.options.max_velocity,
-            self.options.corner_factor,
-        )?;
+    /// - `Result<(), Error>`: Повертає Ok або помилку у випадку невдачі.
+    fn run_path(&mut self, path: Vec<Point<f64>>) -> Result<(), Error> {
+        // Генеруємо план руху на основі шляху; з піднятим пером це переїзд
+        let planner = match self.control.status().pen_down {
+            true => self.options.planner(),
+            false => self.options.travel_planner(),
+        };
+        let plan = planner.plan_with(path, None, 0.0, 0.0)?;
 
         debug!("{}", plan);
 
         self.run_plan(&plan)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_defaults_and_validation() {
+        let mini = Options::default();
+        assert!(matches!(mini.model, AxiDrawModel::Mini));
+        assert!(mini.validate().is_ok());
+
+        let sea3 = Options::for_model(AxiDrawModel::SEA3);
+        assert!(
+            sea3.acceleration < mini.acceleration,
+            "A3 потребує м'якшого прискорення"
+        );
+        assert!(sea3.validate().is_ok());
+
+        // 80 кроків/мм обмежують швидкість частотою кроків EBB, а не моделлю
+        assert!((mini.velocity_limit() - 220.0).abs() < 1e-9);
+        let coarse = Options {
+            steps_per_unit: 160,
+            ..Options::default()
+        };
+        assert!((coarse.velocity_limit() - 156.25).abs() < 1e-9);
+        assert_eq!(coarse.step_rate(1000.0), 25000);
+        assert_eq!(coarse.step_rate(10.0), 1600);
+
+        let too_fast = Options {
+            max_velocity: 300.0,
+            ..Options::default()
+        };
+        assert!(matches!(
+            too_fast.validate(),
+            Err(OptionsError::Velocity { .. })
+        ));
+        let no_acceleration = Options {
+            acceleration: 0.0,
+            ..Options::default()
+        };
+        assert!(matches!(
+            no_acceleration.validate(),
+            Err(OptionsError::Acceleration { .. })
+        ));
+    }
+
+    #[test]
+    fn test_microstepping_mode_scales_steps() {
+        let mut options = Options::for_model(AxiDrawModel::V3A3);
+        options.set_step_mode(StepMode::OneEighth);
+        assert_eq!(options.steps_per_unit, 40);
+        assert!((options.velocity_limit() - 380.0).abs() < 1e-9);
+        assert!(options.validate().is_ok());
+
+        // Той самий рух у міліметрах займає вдвічі менше кроків, ніж у режимі 1/16
+        let (mut axidraw, _trace) =
+            Axidraw::simulated(options).expect("Імітований пристрій має підключитися");
+        axidraw.move_to(10.0, 5.0).expect("Рух має виконатися");
+        let position = axidraw.device.read_position().expect("QS має відповідати");
+        assert_eq!(position, (600, 200));
+
+        // Кроки на міліметр виводяться з передачі, тож інший шків змінює їх у тому ж режимі
+        assert_eq!(
+            Options::for_model(AxiDrawModel::SEA3).drive,
+            AxiDrawModel::SEA3.drive()
+        );
+        let mut kit = Options::for_model(AxiDrawModel::Mini);
+        assert_eq!(kit.steps_per_unit, 80);
+        kit.set_drive(Drive {
+            pulley_teeth: 16,
+            ..kit.drive
+        });
+        assert_eq!(kit.steps_per_unit, 100);
+        kit.set_step_mode(StepMode::OneQuarter);
+        assert_eq!(kit.steps_per_unit, 25);
+
+        let mut disabled = Options::default();
+        disabled.set_step_mode(StepMode::Disable);
+        assert!(matches!(
+            disabled.validate(),
+            Err(OptionsError::StepsPerUnit(0))
+        ));
+    }
+
+    #[test]
+    fn test_button_pauses_and_resumes_plot() {
+        let options = Options::default();
+        let port = MockPort::new(options.steps_per_unit);
+        let (button, trace) = (port.button(), port.trace());
+        let device = Device::with_port(Box::new(port), Axidraw::device_options(&options))
+            .expect("Імітований пристрій має підключитися");
+        let mut axidraw = Axidraw::with_device(device, options).expect("Пристрій має відповідати");
+        let control = axidraw.control.clone();
+
+        // Друге натискання, коли малювання вже стоїть на паузі, відновлює його
+        button.press();
+        let presser = std::thread::spawn(move || {
+            while control.status().state != PlotState::Paused {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            button.press();
+        });
+
+        let svg = r#"<svg viewBox="0 0 100 100"><path d="M10,10 L90,10 L90,90"/></svg>"#;
+        let drawing = Drawing::from_svg(svg).expect("SVG має розбиратися");
+        axidraw.draw(&drawing).expect("Малювання має завершитися");
+        presser.join().expect("Кнопку має бути натиснуто двічі");
+
+        assert!(!axidraw.control.is_paused());
+        assert!(trace.events().iter().any(|event| event.command == "QG"));
+    }
+
+    #[test]
+    fn test_cancel_parks_carriage() {
+        let options = Options::default();
+        let port = MockPort::new(options.steps_per_unit);
+        let (button, trace) = (port.button(), port.trace());
+        let device = Device::with_port(Box::new(port), Axidraw::device_options(&options))
+            .expect("Імітований пристрій має підключитися");
+        let mut axidraw = Axidraw::with_device(device, options).expect("Пристрій має відповідати");
+        let control = axidraw.control.clone();
+
+        // Кнопка зупиняє малюнок посеред шляху, а пульт скасовує його на паузі
+        button.press();
+        let canceller = std::thread::spawn(move || {
+            while control.status().state != PlotState::Paused {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            control.cancel();
+        });
+
+        let svg = r#"<svg viewBox="0 0 100 100"><path d="M10,10 L90,10 L90,90 L10,90 Z"/></svg>"#;
+        let drawing = Drawing::from_svg(svg).expect("SVG має розбиратися");
+        let result = axidraw.draw(&drawing);
+        canceller.join().expect("Малюнок має бути скасовано");
+
+        assert!(result.is_err_and(|e| matches!(e, Error::Cancelled(_))));
+        assert_eq!(axidraw.control.status().state, PlotState::Cancelled);
+        assert!(
+            !axidraw.device.is_lowered(),
+            "Після скасування перо має бути підняте"
+        );
+        let position = axidraw.device.read_position().expect("QS має відповідати");
+        assert_eq!(
+            position,
+            (0, 0),
+            "Каретка має повернутися до початку малюнка"
+        );
+        assert!(trace
+            .events()
+            .iter()
+            .any(|event| event.command.starts_with("HM")));
+    }
+
+    #[test]
+    fn test_draw_layers_pauses_between_layers() {
+        let options = Options::default();
+        let port = MockPort::new(options.steps_per_unit);
+        let trace = port.trace();
+        let device = Device::with_port(Box::new(port), Axidraw::device_options(&options))
+            .expect("Імітований пристрій має підключитися");
+        let mut axidraw = Axidraw::with_device(device, options).expect("Пристрій має відповідати");
+        let control = axidraw.control.clone();
+
+        // Після першого шару малювання чекає на заміну пера, а пульт його відновлює
+        let resumer = std::thread::spawn(move || {
+            while control.status().state != PlotState::Paused {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            control.resume();
+        });
+
+        let first = r#"<svg viewBox="0 0 100 100"><path d="M10,10 L90,10"/></svg>"#;
+        let second = r#"<svg viewBox="0 0 100 100"><path d="M10,50 L90,50"/><path d="M10,80 L90,80"/></svg>"#;
+        let layers =
+            [first, second].map(|svg| Drawing::from_svg(svg).expect("SVG має розбиратися"));
+        axidraw
+            .draw_layers(&layers, 1)
+            .expect("Усі шари мають намалюватися");
+        resumer.join().expect("Малювання має відновитися");
+
+        let status = axidraw.control.status();
+        assert_eq!(status.state, PlotState::Finished);
+        assert_eq!(status.total_paths, 2, "Стан описує останній шар");
+        let homes = trace
+            .events()
+            .iter()
+            .filter(|event| event.command.starts_with("HM"))
+            .count();
+        assert!(homes >= 2, "Після кожного шару каретка повертається додому");
+    }
+
+    #[test]
+    fn test_draw_pages_pauses_for_each_sheet() {
+        let (mut axidraw, trace) =
+            Axidraw::simulated(Options::default()).expect("Імітований пристрій має підключитися");
+        let control = axidraw.control.clone();
+        let resumer = std::thread::spawn(move || {
+            while control.status().state != PlotState::Paused {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            control.resume();
+        });
+
+        // Лінія вдвічі довша за хід Mini розбивається на дві сторінки
+        let drawing = Drawing::new(
+            (300.0, 50.0),
+            geo::MultiLineString(vec![LineString::from(vec![(10.0, 20.0), (290.0, 20.0)])]),
+        );
+        let area = axidraw.paper().printable_area().unwrap();
+        let pages = drawing.tile((area.width(), area.height()), 10.0);
+        assert_eq!(pages.len(), 2);
+        axidraw
+            .draw_pages(&pages, true)
+            .expect("Усі сторінки мають намалюватися");
+        resumer.join().expect("Малювання має відновитися");
+
+        assert_eq!(axidraw.control.status().state, PlotState::Finished);
+        let events = trace.events();
+        let farthest = events
+            .iter()
+            .filter(|event| event.pen_down)
+            .map(|event| event.position.0)
+            .fold(0.0, f64::max);
+        assert!(
+            farthest <= area.max().x + 1e-6,
+            "Кожна сторінка в межах аркуша"
+        );
+    }
+
+    #[test]
+    fn test_pwm_tool_replaces_pen_with_layer_intensity() {
+        let options = Options {
+            tool: Tool::Pwm {
+                pin: 5,
+                intensities: vec![0.5, 1.0],
+            },
+            ..Options::default()
+        };
+        let (mut axidraw, trace) =
+            Axidraw::simulated(options).expect("Імітований пристрій має підключитися");
+        let control = axidraw.control.clone();
+        let resumer = std::thread::spawn(move || {
+            while control.status().state != PlotState::Paused {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            control.resume();
+        });
+
+        let first = r#"<svg viewBox="0 0 100 100"><path d="M10,10 L90,10"/></svg>"#;
+        let second = r#"<svg viewBox="0 0 100 100"><path d="M10,50 L90,50"/></svg>"#;
+        let layers =
+            [first, second].map(|svg| Drawing::from_svg(svg).expect("SVG має розбиратися"));
+        axidraw
+            .draw_layers(&layers, 1)
+            .expect("Усі шари мають намалюватися");
+        resumer.join().expect("Малювання має відновитися");
+
+        let commands: Vec<String> = trace
+            .events()
+            .into_iter()
+            .map(|event| event.command)
+            .collect();
+        let on: Vec<&String> = commands
+            .iter()
+            .filter(|command| command.starts_with("S2,") && !command.starts_with("S2,0,"))
+            .collect();
+        assert_eq!(
+            on,
+            ["S2,32768,5", "S2,65535,5"],
+            "Кожен шар має свою потужність"
+        );
+        assert!(
+            !commands.iter().any(|command| command.starts_with("SP,0")),
+            "Серво пера не опускається"
+        );
+        assert!(
+            commands.iter().any(|command| command.starts_with("S2,0,5")),
+            "Після шляху вихід вимикається"
+        );
+        assert!(!trace.events().last().unwrap().pen_down);
+    }
+
+    #[test]
+    fn test_dwell_holds_pen_down_before_lifting() {
+        let (mut axidraw, trace) =
+            Axidraw::simulated(Options::default()).expect("Імітований пристрій має підключитися");
+        let svg = r#"<svg viewBox="0 0 100 100"><path d="M10,10 L20,10" data-dwell="1500"/></svg>"#;
+        axidraw
+            .draw(&Drawing::from_svg(svg).expect("SVG має розбиратися"))
+            .expect("Малювання має завершитися");
+
+        let events = trace.events();
+        let dwells: Vec<(&str, bool)> = events
+            .iter()
+            .filter(|event| event.command.starts_with("SM,") && event.command.ends_with(",0,0"))
+            .map(|event| (event.command.as_str(), event.pen_down))
+            .collect();
+        assert_eq!(
+            dwells,
+            [("SM,1000,0,0", true), ("SM,500,0,0", true)],
+            "Пауза ділиться на частини й стоїть з опущеним пером"
+        );
+    }
+
+    #[test]
+    fn test_draw_iter_plots_in_batches() {
+        let (mut axidraw, trace) =
+            Axidraw::simulated(Options::default()).expect("Імітований пристрій має підключитися");
+
+        // Шляхів більше за порцію, а генератор не зберігає їх
+        let count = STREAM_BATCH * 2 + 7;
+        let paths = (0..count).map(|index| {
+            let (x, y) = (
+                (index % 40) as f64 * 2.0 + 5.0,
+                (index / 40) as f64 * 2.0 + 5.0,
+            );
+            LineString::from(vec![(x, y), (x + 1.0, y)])
+        });
+        axidraw.draw_iter(paths).expect("Малювання має завершитися");
+
+        let status = axidraw.control.status();
+        assert_eq!(status.state, PlotState::Finished);
+        assert_eq!((status.total_paths, status.current_path), (count, count));
+        assert!((status.drawn_length - count as f64).abs() < 1e-6);
+        let pen_downs = trace
+            .events()
+            .iter()
+            .zip(trace.events().iter().skip(1))
+            .filter(|(before, after)| !before.pen_down && after.pen_down)
+            .count();
+        assert_eq!(pen_downs, count, "Кожен шлях малюється один раз");
+        let position = axidraw.device.read_position().expect("QS має відповідати");
+        assert_eq!(position, (0, 0));
+
+        // Коло з відрізків довше за порцію не розривається на її межі, а довше за найбільшу
+        // порцію продовжується з наступною без підйому пера
+        let segments = 2 * (STREAM_BATCH + STREAM_CHAIN) + 8;
+        let circle: Vec<(f64, f64)> = (0..=segments)
+            .map(|index| {
+                let angle = index as f64 * std::f64::consts::TAU / segments as f64;
+                (80.0 + 40.0 * angle.cos(), 50.0 + 40.0 * angle.sin())
+            })
+            .collect();
+        let chain: Vec<LineString<f64>> = circle
+            .windows(2)
+            .map(|pair| LineString::from(pair.to_vec()))
+            .collect();
+        let events = trace.events().len();
+        axidraw.draw_iter(chain).expect("Малювання має завершитися");
+        let tail = &trace.events()[events..];
+        let pen_downs = tail
+            .iter()
+            .zip(tail.iter().skip(1))
+            .filter(|(before, after)| !before.pen_down && after.pen_down)
+            .count();
+        assert_eq!(pen_downs, 1, "Безперервна крива малюється одним рухом");
+        let streamed = axidraw.control.status();
+        assert_eq!((streamed.total_paths, streamed.current_path), (1, 1));
+
+        // Без зупинок на межах порцій рух збігається з малюванням усієї кривої одним планом
+        let events = trace.events().len();
+        let whole = Drawing::new(
+            (160.0, 101.0),
+            geo::MultiLineString(vec![LineString::from(circle)]),
+        );
+        axidraw.draw(&whole).expect("Малювання має завершитися");
+        assert_eq!(trace.events().len() - events, tail.len());
+        let status = axidraw.control.status();
+        assert!((status.drawn_length - streamed.drawn_length).abs() < 0.01);
+
+        // Шлях за межами аркуша зупиняє малюнок
+        let outside = vec![LineString::from(vec![(5.0, 5.0), (5000.0, 5.0)])];
+        assert!(axidraw.draw_iter(outside).is_err());
+        assert_eq!(axidraw.control.status().state, PlotState::Failed);
+    }
+
+    #[test]
+    fn test_refill_dips_pen_between_batches() {
+        let refill = Refill {
+            every: 50.0,
+            location: (150.0, 10.0),
+            swirl: 2.0,
+        };
+        let options = Options {
+            refill: Some(refill),
+            ..Options::default()
+        };
+        options
+            .validate()
+            .expect("Чорнильниця в межах робочої області");
+        let (mut axidraw, trace) =
+            Axidraw::simulated(options).expect("Імітований пристрій має підключитися");
+
+        // 120 мм з опущеним пером потребують трьох занурень, а обидва шляхи розрізаються
+        let svg = r#"<svg viewBox="0 0 100 100" width="100mm" height="100mm"><path d="M10,20 L90,20 M10,40 L50,40"/></svg>"#;
+        let drawing = Drawing::from_svg(svg).expect("SVG має розбиратися");
+        axidraw.draw(&drawing).expect("Малювання має завершитися");
+
+        let status = axidraw.control.status();
+        assert_eq!(status.state, PlotState::Finished);
+        assert_eq!(status.total_paths, 4, "Кожен шлях розрізано на два");
+        let pen_downs = trace
+            .events()
+            .iter()
+            .zip(trace.events().iter().skip(1))
+            .filter(|(before, after)| !before.pen_down && after.pen_down)
+            .count();
+        assert_eq!(pen_downs, 4 + 3, "Чотири частини шляхів і три занурення");
+
+        let invalid = Options {
+            refill: Some(Refill {
+                location: (500.0, 10.0),
+                ..refill
+            }),
+            ..Options::default()
+        };
+        assert!(matches!(
+            invalid.validate(),
+            Err(OptionsError::DipLocation { .. })
+        ));
+    }
+
+    #[test]
+    fn test_park_position_returns_before_next_plot() {
+        let options = Options {
+            park_position: Some((60.0, 5.0)),
+            ..Options::default()
+        };
+        let (mut axidraw, trace) =
+            Axidraw::simulated(options).expect("Імітований пристрій має підключитися");
+        let svg = r#"<svg viewBox="0 0 100 100"><path d="M10,10 L40,10 L40,40"/></svg>"#;
+        let drawing = Drawing::from_svg(svg).expect("SVG має розбиратися");
+
+        axidraw.draw(&drawing).expect("Малюнок має виконатися");
+        let last = trace.events().last().unwrap().position;
+        assert!(
+            (last.0 - 60.0).abs() < 1e-6 && (last.1 - 5.0).abs() < 1e-6,
+            "Каретку має бути запарковано, а не повернуто додому: {:?}",
+            last
+        );
+
+        // Наступний малюнок спершу повертає каретку туди, де почався попередній
+        axidraw.draw(&drawing).expect("Малюнок має виконатися");
+        let events = trace.events();
+        let second_zero = events
+            .iter()
+            .rposition(|event| event.command == "CS")
+            .expect("Малюнок обнуляє позицію");
+        let before = events[second_zero - 1].position;
+        assert!(
+            before.0.abs() < 1e-6 && before.1.abs() < 1e-6,
+            "Перед обнуленням каретка має бути вдома: {:?}",
+            before
+        );
+
+        let invalid = Options {
+            park_position: Some((-1.0, 0.0)),
+            ..Options::default()
+        };
+        assert!(matches!(
+            invalid.validate(),
+            Err(OptionsError::ParkPosition { .. })
+        ));
+    }
+
+    #[test]
+    fn test_stalled_motion_lifts_pen() {
+        let options = Options {
+            stall_limit: Some(Duration::from_millis(20)),
+            ..Options::default()
+        };
+        let (mut axidraw, trace) =
+            Axidraw::simulated(options).expect("Імітований пристрій має підключитися");
+        let pen_moves = |trace: &Trace| {
+            trace
+                .events()
+                .iter()
+                .filter(|event| event.command.starts_with("SP"))
+                .count()
+        };
+
+        // Перша команда руху затрималася після опускання пера
+        axidraw.pen_down().expect("Перо має опуститися");
+        std::thread::sleep(Duration::from_millis(50));
+        let before = pen_moves(&trace);
+        axidraw.goto(5.0, 0.0).expect("Рух має виконатися");
+        assert_eq!(
+            pen_moves(&trace) - before,
+            2,
+            "Перо піднімається й опускається"
+        );
+        assert_eq!(axidraw.control.status().stall_lifts, 1);
+        assert!(axidraw.control.status().pen_down);
+
+        // Плата ще виконує попередню чергу, тож наступний рух не зупинявся
+        axidraw.goto(10.0, 0.0).expect("Рух має виконатися");
+        assert_eq!(axidraw.control.status().stall_lifts, 1);
+
+        // З піднятим пером зупинки не рахуються
+        axidraw.pen_up().expect("Перо має піднятися");
+        std::thread::sleep(Duration::from_millis(50));
+        axidraw.goto(0.0, 0.0).expect("Рух має виконатися");
+        assert_eq!(axidraw.control.status().stall_lifts, 1);
+    }
+
+    #[test]
+    fn test_goto_from_negative_position() {
+        let (mut axidraw, _trace) =
+            Axidraw::simulated(Options::default()).expect("Імітований пристрій має підключитися");
+
+        // Каретка лівіше та вище початку координат: обидва мотори мають від'ємні позиції
+        axidraw.move_to(-12.5, -3.0).expect("Рух має виконатися");
+        axidraw.goto(7.0, 4.5).expect("Рух має виконатися");
+
+        let steps_per_unit = axidraw.options.steps_per_unit as f64;
+        let (motor1, motor2) = axidraw.device.read_position().expect("QS має відповідати");
+        let (x, y) = kinematics::motors_to_xy(
+            motor1 as f64 / steps_per_unit,
+            motor2 as f64 / steps_per_unit,
+        );
+        assert!((x - 7.0).abs() < 1e-9 && (y - 4.5).abs() < 1e-9);
+
+        // Ціль поза ходом кареток Mini відхиляється до руху
+        assert!(matches!(
+            axidraw.goto(170.0, 4.5),
+            Err(Error::OutOfBounds {
+                size: (160.0, 101.0),
+                ..
+            })
+        ));
+        assert_eq!(
+            axidraw.device.read_position().unwrap(),
+            (motor1, motor2),
+            "Каретка лишається на місці"
+        );
+    }
+
+    #[test]
+    fn test_emergency_stop_resyncs_position() {
+        let (mut axidraw, _trace) =
+            Axidraw::simulated(Options::default()).expect("Імітований пристрій має підключитися");
+        axidraw.goto(20.0, 10.0).expect("Рух має виконатися");
+
+        // Рух, про який облік не знає, як недовиконана перед ES команда
+        axidraw
+            .device
+            .stepper_move(Duration::from_millis(100), 160, Some(160))
+            .expect("Рух має виконатися");
+        let (x, y) = axidraw.emergency_stop().expect("Зупинка має виконатися");
+        assert!((x - 22.0).abs() < 1e-9 && (y - 10.0).abs() < 1e-9);
+        assert_eq!(axidraw.control.status().position, (x, y));
+        assert!(!axidraw.control.status().pen_down);
+        assert_eq!(axidraw.verify_position().unwrap(), (0, 0));
+
+        axidraw.goto(5.0, 5.0).expect("Рух має виконатися");
+        let (motor1, motor2) = axidraw.device.read_position().expect("QS має відповідати");
+        assert_eq!(
+            (motor1, motor2),
+            (800, 0),
+            "Переїзд рахується від фактичної позиції"
+        );
+        assert_eq!(axidraw.verify_position().unwrap(), (0, 0));
+    }
+
+    #[test]
+    fn test_step_remainder_prevents_drift() {
+        let (mut axidraw, _trace) =
+            Axidraw::simulated(Options::default()).expect("Імітований пристрій має підключитися");
+
+        // Кожен крок менший за крок двигуна: без перенесення залишку округлення накопичується
+        for _ in 0..100 {
+            axidraw.move_to(0.0123, 0.0071).expect("Рух має виконатися");
+        }
+
+        let steps_per_unit = axidraw.options.steps_per_unit as f64;
+        let a = (1.23 * steps_per_unit).round() as i32;
+        let b = (0.71 * steps_per_unit).round() as i32;
+        let position = axidraw.device.read_position().expect("QS має відповідати");
+        assert_eq!(position, (a + b, a - b));
+        assert_eq!(
+            axidraw.verify_position().expect("QS має відповідати"),
+            (0, 0)
+        );
+    }
+
+    #[test]
+    fn test_low_level_moves_land_on_planned_steps() {
+        let options = Options {
+            low_level_moves: true,
+            ..Options::default()
+        };
+        let (mut axidraw, trace) =
+            Axidraw::simulated(options).expect("Імітований пристрій має підключитися");
+        for _ in 0..100 {
+            axidraw.move_to(0.0123, 0.0071).expect("Рух має виконатися");
+        }
+        axidraw.move_to(20.0, -5.0).expect("Рух має виконатися");
+
+        let steps_per_unit = axidraw.options.steps_per_unit as f64;
+        let (x, y) = (1.23 + 20.0, 0.71 - 5.0);
+        let expected = (
+            ((x + y) * steps_per_unit).round() as i32,
+            ((x - y) * steps_per_unit).round() as i32,
+        );
+        let position = axidraw.device.read_position().expect("QS має відповідати");
+        assert_eq!(
+            position, expected,
+            "Кроки моторів без накопиченого округлення"
+        );
+        assert_eq!(
+            axidraw.verify_position().expect("QS має відповідати"),
+            (0, 0)
+        );
+
+        // Рух іде лише командами LM, що тривають за моделлю акумуляторів плати
+        let events = trace.events();
+        let moves: Vec<_> = events
+            .iter()
+            .filter(|event| event.command.starts_with("LM,"))
+            .collect();
+        assert!(!moves.is_empty());
+        assert!(events.iter().all(|event| !event.command.starts_with("XM,")));
+        let last = moves.last().unwrap();
+        assert!(last.duration > Duration::ZERO);
+        assert!((last.position.0 - x).abs() < 0.05 && (last.position.1 - y).abs() < 0.05);
+    }
+}
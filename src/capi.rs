@@ -0,0 +1,477 @@
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use crate::axidraw::{Axidraw, Options};
+use crate::control::{PlotControl, PlotState};
+use crate::drawing::Drawing;
+use crate::error::Error;
+use crate::tr;
+
+/// Виклик завершився успішно.
+pub const RSAXI_OK: c_int = 0;
+/// Недійсний аргумент: нульовий вказівник, рядок не в UTF-8 чи малюнок поза аркушем.
+pub const RSAXI_ERR_ARGUMENT: c_int = -1;
+/// Помилка зв'язку з платою EBB.
+pub const RSAXI_ERR_DEVICE: c_int = -2;
+/// Помилка розбору SVG.
+pub const RSAXI_ERR_IMPORT: c_int = -3;
+/// Помилка планування руху.
+pub const RSAXI_ERR_PLAN: c_int = -4;
+/// Малювання скасовано.
+pub const RSAXI_ERR_CANCELLED: c_int = -5;
+/// Інша помилка бібліотеки.
+pub const RSAXI_ERR_OTHER: c_int = -6;
+/// Паніка всередині бібліотеки; плотер після неї краще від'єднати.
+pub const RSAXI_ERR_PANIC: c_int = -7;
+
+thread_local! {
+    /// Опис останньої помилки в цьому потоці.
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Підключений плотер за непрозорим вказівником.
+pub struct RsaxiPlotter {
+    axidraw: Axidraw,
+}
+
+/// Пульт плотера, яким можна користуватися з іншого потоку, поки малюнок виконується.
+pub struct RsaxiControl {
+    control: PlotControl,
+}
+
+/// Стан виконання малюнка.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RsaxiState {
+    Idle = 0,
+    Plotting = 1,
+    Paused = 2,
+    Cancelled = 3,
+    Finished = 4,
+    Failed = 5,
+}
+
+impl From<PlotState> for RsaxiState {
+    fn from(state: PlotState) -> Self {
+        match state {
+            PlotState::Idle => RsaxiState::Idle,
+            PlotState::Plotting => RsaxiState::Plotting,
+            PlotState::Paused => RsaxiState::Paused,
+            PlotState::Cancelled => RsaxiState::Cancelled,
+            PlotState::Finished => RsaxiState::Finished,
+            PlotState::Failed => RsaxiState::Failed,
+        }
+    }
+}
+
+/// Знімок стану малювання для C.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RsaxiStatus {
+    pub state: RsaxiState, // Поточний стан.
+    pub current_path: u64, // Номер шляху, що малюється (з одиниці).
+    pub total_paths: u64,  // Кількість шляхів у малюнку.
+    pub x: f64,            // Остання відома позиція пера по X (в мм).
+    pub y: f64,            // Остання відома позиція пера по Y (в мм).
+    pub velocity: f64,     // Швидкість каретки на останньому кроці (мм/с).
+    pub pen_down: bool,    // Чи опущене перо.
+    pub drawn_length: f64, // Намальована довжина з опущеним пером (в мм).
+    pub total_length: f64, // Загальна довжина шляхів малюнка (в мм).
+    pub eta_seconds: f64,  // Орієнтовний час до завершення (в секундах) або -1, якщо невідомий.
+}
+
+/// Запам'ятовує опис помилки для `rsaxi_last_error` і повертає її код.
+fn fail(code: c_int, message: String) -> c_int {
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+    code
+}
+
+/// Код помилки бібліотеки для C.
+fn error_code(error: &Error) -> c_int {
+    match error {
+        Error::Device(_) => RSAXI_ERR_DEVICE,
+        Error::Import(_) => RSAXI_ERR_IMPORT,
+        Error::Plan(_) => RSAXI_ERR_PLAN,
+        Error::Cancelled(_) => RSAXI_ERR_CANCELLED,
+        Error::Paper(_) | Error::OutOfBounds { .. } => RSAXI_ERR_ARGUMENT,
+        _ => RSAXI_ERR_OTHER,
+    }
+}
+
+/// Виконує виклик без розкручування паніки через межу C і перетворює результат на код.
+fn call(body: impl FnOnce() -> Result<(), c_int>) -> c_int {
+    match panic::catch_unwind(AssertUnwindSafe(body)) {
+        Ok(Ok(())) => RSAXI_OK,
+        Ok(Err(code)) => code,
+        Err(_) => fail(
+            RSAXI_ERR_PANIC,
+            tr!("Internal library error", "Внутрішня помилка бібліотеки"),
+        ),
+    }
+}
+
+/// Перетворює помилку бібліотеки на код, запам'ятавши її опис.
+fn check<T>(result: Result<T, impl Into<Error>>) -> Result<T, c_int> {
+    result.map_err(|error| {
+        let error = error.into();
+        fail(error_code(&error), error.to_string())
+    })
+}
+
+/// Плотер за вказівником з C або код помилки для нульового вказівника.
+///
+/// # Safety
+/// `plotter` має бути нульовим або отриманим від `rsaxi_connect` і ще не від'єднаним.
+unsafe fn plotter<'a>(plotter: *mut RsaxiPlotter) -> Result<&'a mut Axidraw, c_int> {
+    match plotter.as_mut() {
+        Some(plotter) => Ok(&mut plotter.axidraw),
+        None => Err(null_argument()),
+    }
+}
+
+/// Рядок UTF-8 з C або код помилки.
+///
+/// # Safety
+/// `text` має бути нульовим або вказувати на рядок, що закінчується нулем.
+unsafe fn text<'a>(text: *const c_char) -> Result<&'a str, c_int> {
+    if text.is_null() {
+        return Err(null_argument());
+    }
+    CStr::from_ptr(text).to_str().map_err(|_| {
+        fail(
+            RSAXI_ERR_ARGUMENT,
+            tr!("String is not valid UTF-8", "Рядок не в кодуванні UTF-8"),
+        )
+    })
+}
+
+fn null_argument() -> c_int {
+    fail(
+        RSAXI_ERR_ARGUMENT,
+        tr!("Null pointer argument", "Нульовий вказівник в аргументі"),
+    )
+}
+
+/// Заповнює знімок стану з пульта.
+fn write_status(control: &PlotControl, out: *mut RsaxiStatus) -> Result<(), c_int> {
+    if out.is_null() {
+        return Err(null_argument());
+    }
+    let status = control.status();
+    let status = RsaxiStatus {
+        state: status.state.into(),
+        current_path: status.current_path as u64,
+        total_paths: status.total_paths as u64,
+        x: status.position.0,
+        y: status.position.1,
+        velocity: status.velocity,
+        pen_down: status.pen_down,
+        drawn_length: status.drawn_length,
+        total_length: status.total_length,
+        eta_seconds: status.eta.map_or(-1.0, |eta| eta.as_secs_f64()),
+    };
+    // Безпека: вказівник ненульовий, а за його призначення відповідає викликач
+    unsafe { out.write(status) };
+    Ok(())
+}
+
+/// Опис останньої помилки в потоці, що викликає, або нульовий вказівник.
+///
+/// Рядок належить бібліотеці й лишається дійсним до наступного виклику в цьому потоці.
+#[no_mangle]
+pub extern "C" fn rsaxi_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// Підключається до AxiDraw з типовими налаштуваннями.
+///
+/// # Параметри
+/// - `port`: Назва USB-порту чи ім'я плати або нульовий вказівник, щоб знайти перший AxiDraw.
+///
+/// # Повертає
+/// - `*mut RsaxiPlotter`: Плотер або нульовий вказівник у разі помилки (див. `rsaxi_last_error`).
+///
+/// # Safety
+/// `port` має бути нульовим або вказувати на рядок, що закінчується нулем.
+#[no_mangle]
+pub unsafe extern "C" fn rsaxi_connect(port: *const c_char) -> *mut RsaxiPlotter {
+    let mut connected = None;
+    call(|| {
+        let options = Options {
+            port: match port.is_null() {
+                true => None,
+                false => Some(text(port)?.to_string()),
+            },
+            ..Options::default()
+        };
+        connected = Some(check(Axidraw::new(options))?);
+        Ok(())
+    });
+    match connected {
+        Some(axidraw) => Box::into_raw(Box::new(RsaxiPlotter { axidraw })),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Створює плотер з імітованою платою, що приймає всі команди без апаратного забезпечення.
+///
+/// Корисний для перевірки інтеграції та попереднього перегляду в застосунку.
+#[no_mangle]
+pub extern "C" fn rsaxi_connect_simulated() -> *mut RsaxiPlotter {
+    let mut connected = None;
+    call(|| {
+        connected = Some(check(Axidraw::simulated(Options::default()))?.0);
+        Ok(())
+    });
+    match connected {
+        Some(axidraw) => Box::into_raw(Box::new(RsaxiPlotter { axidraw })),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Від'єднує плотер і звільняє його; нульовий вказівник ігнорується.
+///
+/// # Safety
+/// `plotter` має бути нульовим або отриманим від `rsaxi_connect` і ще не від'єднаним.
+#[no_mangle]
+pub unsafe extern "C" fn rsaxi_disconnect(plotter: *mut RsaxiPlotter) {
+    if !plotter.is_null() {
+        drop(Box::from_raw(plotter));
+    }
+}
+
+/// Малює SVG-документ і повертається, коли малюнок завершено, скасовано чи перервано.
+///
+/// # Параметри
+/// - `plotter`: Підключений плотер.
+/// - `svg`: Текст SVG-документа в UTF-8.
+/// - `fit`: Підігнати малюнок під аркуш.
+///
+/// # Повертає
+/// - `c_int`: `RSAXI_OK` або код помилки.
+///
+/// # Safety
+/// `plotter` має бути отриманим від `rsaxi_connect`, а `svg` — рядком, що закінчується нулем.
+#[no_mangle]
+pub unsafe extern "C" fn rsaxi_plot_svg(
+    plotter: *mut RsaxiPlotter,
+    svg: *const c_char,
+    fit: bool,
+) -> c_int {
+    call(|| {
+        let axidraw = self::plotter(plotter)?;
+        let mut drawing = check(Drawing::from_svg(text(svg)?))?;
+        if fit {
+            drawing = check(drawing.fit_to_page(&axidraw.paper()))?;
+        }
+        check(axidraw.draw(&drawing))
+    })
+}
+
+/// Піднімає перо.
+///
+/// # Safety
+/// `plotter` має бути отриманим від `rsaxi_connect` і ще не від'єднаним.
+#[no_mangle]
+pub unsafe extern "C" fn rsaxi_pen_up(plotter: *mut RsaxiPlotter) -> c_int {
+    call(|| check(self::plotter(plotter)?.pen_up()))
+}
+
+/// Опускає перо.
+///
+/// # Safety
+/// `plotter` має бути отриманим від `rsaxi_connect` і ще не від'єднаним.
+#[no_mangle]
+pub unsafe extern "C" fn rsaxi_pen_down(plotter: *mut RsaxiPlotter) -> c_int {
+    call(|| check(self::plotter(plotter)?.pen_down()))
+}
+
+/// Відносно переміщує каретку.
+///
+/// # Параметри
+/// - `plotter`: Підключений плотер.
+/// - `dx`, `dy`: Зміщення по осях X та Y (в мм).
+///
+/// # Safety
+/// `plotter` має бути отриманим від `rsaxi_connect` і ще не від'єднаним.
+#[no_mangle]
+pub unsafe extern "C" fn rsaxi_jog(plotter: *mut RsaxiPlotter, dx: f64, dy: f64) -> c_int {
+    call(|| {
+        if !dx.is_finite() || !dy.is_finite() {
+            return Err(fail(
+                RSAXI_ERR_ARGUMENT,
+                tr!("Offset must be finite", "Зміщення має бути скінченним"),
+            ));
+        }
+        check(self::plotter(plotter)?.move_to(dx, dy))
+    })
+}
+
+/// Повертає каретку на початкову позицію.
+///
+/// # Safety
+/// `plotter` має бути отриманим від `rsaxi_connect` і ще не від'єднаним.
+#[no_mangle]
+pub unsafe extern "C" fn rsaxi_home(plotter: *mut RsaxiPlotter) -> c_int {
+    call(|| check(self::plotter(plotter)?.home()))
+}
+
+/// Заповнює `out` станом малювання.
+///
+/// # Safety
+/// `plotter` має бути отриманим від `rsaxi_connect`, а `out` — вказувати на `RsaxiStatus`.
+#[no_mangle]
+pub unsafe extern "C" fn rsaxi_status(plotter: *mut RsaxiPlotter, out: *mut RsaxiStatus) -> c_int {
+    call(|| write_status(&self::plotter(plotter)?.control, out))
+}
+
+/// Пульт плотера для паузи, скасування та стану з іншого потоку.
+///
+/// Пульт треба звільнити `rsaxi_control_free`; він лишається дійсним і після
+/// від'єднання плотера, але тоді нічим не керує.
+///
+/// # Safety
+/// `plotter` має бути отриманим від `rsaxi_connect` і ще не від'єднаним.
+#[no_mangle]
+pub unsafe extern "C" fn rsaxi_control(plotter: *mut RsaxiPlotter) -> *mut RsaxiControl {
+    match plotter.as_ref() {
+        Some(plotter) => Box::into_raw(Box::new(RsaxiControl {
+            control: plotter.axidraw.control.clone(),
+        })),
+        None => {
+            null_argument();
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Звільняє пульт; нульовий вказівник ігнорується.
+///
+/// # Safety
+/// `control` має бути нульовим або отриманим від `rsaxi_control` і ще не звільненим.
+#[no_mangle]
+pub unsafe extern "C" fn rsaxi_control_free(control: *mut RsaxiControl) {
+    if !control.is_null() {
+        drop(Box::from_raw(control));
+    }
+}
+
+/// Виконує дію пульта за вказівником з C.
+///
+/// # Safety
+/// `control` має бути нульовим або отриманим від `rsaxi_control` і ще не звільненим.
+unsafe fn with_control(
+    control: *const RsaxiControl,
+    action: impl FnOnce(&PlotControl) -> Result<(), c_int>,
+) -> c_int {
+    call(|| match control.as_ref() {
+        Some(control) => action(&control.control),
+        None => Err(null_argument()),
+    })
+}
+
+/// Призупиняє малюнок і піднімає перо.
+///
+/// # Safety
+/// `control` має бути отриманим від `rsaxi_control` і ще не звільненим.
+#[no_mangle]
+pub unsafe extern "C" fn rsaxi_pause(control: *const RsaxiControl) -> c_int {
+    with_control(control, |control| {
+        control.pause();
+        Ok(())
+    })
+}
+
+/// Відновлює призупинений малюнок.
+///
+/// # Safety
+/// `control` має бути отриманим від `rsaxi_control` і ще не звільненим.
+#[no_mangle]
+pub unsafe extern "C" fn rsaxi_resume(control: *const RsaxiControl) -> c_int {
+    with_control(control, |control| {
+        control.resume();
+        Ok(())
+    })
+}
+
+/// Скасовує малюнок; `rsaxi_plot_svg` повертає `RSAXI_ERR_CANCELLED`.
+///
+/// # Safety
+/// `control` має бути отриманим від `rsaxi_control` і ще не звільненим.
+#[no_mangle]
+pub unsafe extern "C" fn rsaxi_cancel(control: *const RsaxiControl) -> c_int {
+    with_control(control, |control| {
+        control.cancel();
+        Ok(())
+    })
+}
+
+/// Заповнює `out` станом малювання з пульта.
+///
+/// # Safety
+/// `control` має бути отриманим від `rsaxi_control`, а `out` — вказувати на `RsaxiStatus`.
+#[no_mangle]
+pub unsafe extern "C" fn rsaxi_control_status(
+    control: *const RsaxiControl,
+    out: *mut RsaxiStatus,
+) -> c_int {
+    with_control(control, |control| write_status(control, out))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::MaybeUninit;
+
+    #[test]
+    fn test_c_api_plots_on_simulated_plotter() {
+        let plotter = rsaxi_connect_simulated();
+        assert!(!plotter.is_null());
+        let svg = CString::new(
+            r#"<svg width="100mm" height="100mm" viewBox="0 0 100 100"><line x1="10" y1="10" x2="30" y2="10"/></svg>"#,
+        )
+        .unwrap();
+        unsafe {
+            assert_eq!(rsaxi_plot_svg(plotter, svg.as_ptr(), false), RSAXI_OK);
+            let control = rsaxi_control(plotter);
+            let mut status = MaybeUninit::<RsaxiStatus>::uninit();
+            assert_eq!(rsaxi_control_status(control, status.as_mut_ptr()), RSAXI_OK);
+            let status = status.assume_init();
+            assert_eq!(status.state, RsaxiState::Finished);
+            assert_eq!(status.total_paths, 1);
+            assert!((status.total_length - 20.0).abs() < 1e-6);
+
+            assert_eq!(rsaxi_jog(plotter, 5.0, 5.0), RSAXI_OK);
+            assert_eq!(rsaxi_pen_down(plotter), RSAXI_OK);
+            let mut status = MaybeUninit::<RsaxiStatus>::uninit();
+            assert_eq!(rsaxi_status(plotter, status.as_mut_ptr()), RSAXI_OK);
+            assert!(status.assume_init().pen_down);
+            assert_eq!(rsaxi_pen_up(plotter), RSAXI_OK);
+            assert_eq!(rsaxi_home(plotter), RSAXI_OK);
+
+            // Помилки повертаються кодом, а опис чекає в rsaxi_last_error
+            let broken = CString::new("<svg><path d=\"M x\"/></svg>").unwrap();
+            assert_eq!(
+                rsaxi_plot_svg(plotter, broken.as_ptr(), false),
+                RSAXI_ERR_IMPORT
+            );
+            assert!(!rsaxi_last_error().is_null());
+            assert_eq!(
+                rsaxi_plot_svg(plotter, ptr::null(), false),
+                RSAXI_ERR_ARGUMENT
+            );
+            assert_eq!(rsaxi_jog(ptr::null_mut(), 1.0, 0.0), RSAXI_ERR_ARGUMENT);
+            assert_eq!(rsaxi_jog(plotter, f64::NAN, 0.0), RSAXI_ERR_ARGUMENT);
+
+            rsaxi_control_free(control);
+            rsaxi_disconnect(plotter);
+        }
+    }
+}
@@ -0,0 +1,292 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::axidraw::{Drive, Options};
+use crate::drawing::correction::Correction;
+use crate::tr;
+
+/// Змінна середовища з шляхом до файлу конфігурації.
+pub const CONFIG_ENV: &str = "RSAXI_CONFIG";
+
+/// Помилки читання чи запису файлу конфігурації.
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    /// Не вдалося прочитати чи записати файл.
+    #[error(
+        "{}",
+        tr!(
+            "Cannot access config file {}: {}",
+            "Немає доступу до файлу конфігурації {}: {}",
+            .path.display(),
+            .source
+        )
+    )]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    /// Файл не є коректним TOML конфігурації.
+    #[error(
+        "{}",
+        tr!(
+            "Config file is not valid: {}",
+            "Файл конфігурації неправильний: {}",
+            .0
+        )
+    )]
+    Toml(#[from] toml::de::Error),
+
+    /// Конфігурацію не вдалося подати в TOML.
+    #[error(
+        "{}",
+        tr!(
+            "Cannot write the config: {}",
+            "Не вдалося записати конфігурацію: {}",
+            .0
+        )
+    )]
+    Serialize(#[from] toml::ser::Error),
+
+    /// У конфігурації немає профілю з такою назвою.
+    #[error(
+        "{}",
+        tr!(
+            "Unknown profile '{}'; available: {}",
+            "Невідомий профіль '{}'; наявні: {}",
+            .name,
+            .available
+        )
+    )]
+    UnknownProfile { name: String, available: String },
+}
+
+/// Налаштування конкретного плотера, що зберігаються між запусками.
+///
+/// На відміну від параметрів командного рядка, вони описують саму машину, наприклад
+/// поправку геометрії з калібрування, і застосовуються до кожного малюнка.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub correction: Option<Correction>, // Поправка геометрії з `calibrate geometry`.
+    #[serde(rename = "profile", skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, Profile>, // Іменовані профілі машин і пер для `--profile`.
+}
+
+/// Іменований набір параметрів машини чи пера, що вибирається `--profile`.
+///
+/// Незадані поля лишають типові значення моделі, а параметри командного рядка
+/// перекривають і профіль.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Profile {
+    pub model: Option<String>, // Код моделі AxiDraw: v3, v3a3, sea3 або mini.
+    pub port: Option<String>,  // USB-порт або ім'я плати.
+    pub max_velocity: Option<f64>, // Найбільша швидкість каретки (мм/с).
+    pub acceleration: Option<f64>, // Прискорення каретки (мм/с²).
+    pub travel_acceleration: Option<f64>, // Прискорення переїздів з піднятим пером (мм/с²).
+    pub pen_up_position: Option<i32>, // Положення піднятої ручки.
+    pub pen_down_position: Option<i32>, // Положення опущеної ручки.
+    pub pen_up_speed: Option<i32>, // Швидкість підняття ручки.
+    pub pen_down_speed: Option<i32>, // Швидкість опускання ручки.
+    pub drive: Option<Drive>,  // Передача набору, що відрізняється від типової для моделі.
+}
+
+impl Profile {
+    /// Переносить задані поля профілю в параметри плотера; модель вибирається окремо.
+    ///
+    /// # Аргументи
+    /// * `options` - параметри моделі, які перекриває профіль.
+    pub fn apply(&self, options: &mut Options) {
+        if let Some(drive) = self.drive {
+            options.set_drive(drive);
+        }
+        if let Some(port) = &self.port {
+            options.port = Some(port.clone());
+        }
+        if let Some(max_velocity) = self.max_velocity {
+            options.max_velocity = max_velocity;
+        }
+        if let Some(acceleration) = self.acceleration {
+            options.acceleration = acceleration;
+        }
+        if let Some(acceleration) = self.travel_acceleration {
+            options.travel_acceleration = Some(acceleration);
+        }
+        if let Some(position) = self.pen_up_position {
+            options.pen_up_position = position;
+        }
+        if let Some(position) = self.pen_down_position {
+            options.pen_down_position = position;
+        }
+        if let Some(speed) = self.pen_up_speed {
+            options.pen_up_speed = speed;
+        }
+        if let Some(speed) = self.pen_down_speed {
+            options.pen_down_speed = speed;
+        }
+    }
+}
+
+impl Config {
+    /// Типовий шлях до файлу конфігурації.
+    ///
+    /// Шлях береться зі змінної `RSAXI_CONFIG`, інакше це `rsaxi/config.toml` у
+    /// `XDG_CONFIG_HOME` або в `~/.config`.
+    ///
+    /// # Повертає
+    /// * `Option<PathBuf>` - шлях або `None`, якщо домашній каталог невідомий.
+    pub fn default_path() -> Option<PathBuf> {
+        if let Some(path) = std::env::var_os(CONFIG_ENV) {
+            return Some(PathBuf::from(path));
+        }
+        let base = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+        Some(base.join("rsaxi").join("config.toml"))
+    }
+
+    /// Читає конфігурацію з файлу; відсутній файл дає типову конфігурацію.
+    ///
+    /// # Аргументи
+    /// * `path` - шлях до файлу конфігурації.
+    ///
+    /// # Повертає
+    /// * `Result<Config, ConfigError>` - конфігурація або помилка читання чи розбору.
+    pub fn load(path: impl AsRef<Path>) -> Result<Config, ConfigError> {
+        let path = path.as_ref();
+        match std::fs::read_to_string(path) {
+            Ok(content) => Ok(toml::from_str(&content)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(source) => Err(ConfigError::Io {
+                path: path.to_path_buf(),
+                source,
+            }),
+        }
+    }
+
+    /// Профіль за назвою.
+    ///
+    /// # Аргументи
+    /// * `name` - назва профілю з `--profile`.
+    ///
+    /// # Повертає
+    /// * `Result<&Profile, ConfigError>` - профіль або помилка з переліком наявних профілів.
+    pub fn profile(&self, name: &str) -> Result<&Profile, ConfigError> {
+        self.profiles
+            .get(name)
+            .ok_or_else(|| ConfigError::UnknownProfile {
+                name: name.to_string(),
+                available: match self.profiles.is_empty() {
+                    true => "-".to_string(),
+                    false => self.profiles.keys().cloned().collect::<Vec<_>>().join(", "),
+                },
+            })
+    }
+
+    /// Записує конфігурацію у файл, створюючи потрібні каталоги.
+    ///
+    /// # Аргументи
+    /// * `path` - шлях до файлу конфігурації.
+    ///
+    /// # Повертає
+    /// * `Result<(), ConfigError>` - помилка, якщо файл не вдалося записати.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ConfigError> {
+        let path = path.as_ref();
+        let io = |source| ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            std::fs::create_dir_all(parent).map_err(io)?;
+        }
+        std::fs::write(path, toml::to_string_pretty(self)?).map_err(io)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_round_trips_through_file() {
+        let path = std::env::temp_dir()
+            .join(format!("rsaxi-config-{}", std::process::id()))
+            .join("config.toml");
+        assert_eq!(
+            Config::load(&path).unwrap(),
+            Config::default(),
+            "Відсутній файл дає типову конфігурацію"
+        );
+
+        let config = Config {
+            correction: Some(Correction {
+                scale_x: 1.01,
+                scale_y: 0.99,
+                skew: -0.002,
+            }),
+            ..Config::default()
+        };
+        config.save(&path).expect("Конфігурація має записуватися");
+        assert_eq!(Config::load(&path).unwrap(), config);
+
+        std::fs::write(&path, "correction = 3").unwrap();
+        assert!(matches!(Config::load(&path), Err(ConfigError::Toml(_))));
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_profiles_override_model_defaults() {
+        let config: Config = toml::from_str(
+            r#"
+                [profile.a3]
+                model = "v3a3"
+                port = "/dev/ttyACM1"
+                max_velocity = 150
+                drive = { belt_pitch = 2.0, pulley_teeth = 16, steps_per_revolution = 200 }
+
+                [profile.mini-gel-pen]
+                model = "mini"
+                pen_down_position = 35
+                acceleration = 800
+            "#,
+        )
+        .unwrap();
+
+        let gel = config.profile("mini-gel-pen").unwrap();
+        assert_eq!(gel.model.as_deref(), Some("mini"));
+        let mut options = Options::default();
+        let defaults = options.clone();
+        gel.apply(&mut options);
+        assert_eq!(options.pen_down_position, 35);
+        assert_eq!(options.acceleration, 800.0);
+        assert_eq!(
+            options.max_velocity, defaults.max_velocity,
+            "Незадані поля лишаються типовими"
+        );
+        assert_eq!(options.port, None);
+        config.profile("a3").unwrap().apply(&mut options);
+        assert_eq!(options.port.as_deref(), Some("/dev/ttyACM1"));
+        assert_eq!(
+            options.steps_per_unit, 100,
+            "Шків з 16 зубцями дає 100 кроків на мм"
+        );
+
+        match config.profile("a4") {
+            Err(ConfigError::UnknownProfile { available, .. }) => {
+                assert_eq!(available, "a3, mini-gel-pen")
+            }
+            other => panic!("Очікувалася помилка профілю, отримано {:?}", other),
+        }
+        assert!(
+            toml::from_str::<Config>("[profile.x]\nspeed = 1").is_err(),
+            "Описка в назві поля не мовчить"
+        );
+    }
+}
@@ -0,0 +1,373 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use thiserror::Error;
+
+use crate::tr;
+
+/// Інтервал опитування прапорця паузи.
+const PAUSE_POLL: Duration = Duration::from_millis(50);
+
+/// Чи отримав процес Ctrl-C або SIGTERM; діє як скасування для всіх пультів.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Помилка, якою переривається малювання після скасування.
+#[derive(Debug, Error)]
+#[error("{}", tr!("Plot cancelled", "Малювання скасовано"))]
+pub struct Cancelled;
+
+/// Стан виконання малюнка.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlotState {
+    Idle,      // Плотер вільний.
+    Plotting,  // Малюнок виконується.
+    Paused,    // Малюнок призупинено, перо підняте.
+    Cancelled, // Малюнок скасовано.
+    Finished,  // Малюнок завершено.
+    Failed,    // Малювання перервано помилкою.
+}
+
+impl PlotState {
+    /// Повертає назву стану.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PlotState::Idle => "idle",
+            PlotState::Plotting => "plotting",
+            PlotState::Paused => "paused",
+            PlotState::Cancelled => "cancelled",
+            PlotState::Finished => "finished",
+            PlotState::Failed => "failed",
+        }
+    }
+}
+
+/// Знімок стану малювання.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlotStatus {
+    pub state: PlotState,      // Поточний стан.
+    pub current_path: usize,   // Номер шляху, що малюється (з одиниці).
+    pub total_paths: usize,    // Кількість шляхів у малюнку.
+    pub position: (f64, f64),  // Остання відома позиція пера (в мм).
+    pub velocity: f64,         // Швидкість каретки на останньому кроці (мм/с).
+    pub pen_down: bool,        // Чи опущене перо.
+    pub drawn_length: f64,     // Намальована довжина з опущеним пером (в мм).
+    pub total_length: f64,     // Загальна довжина шляхів малюнка (в мм).
+    pub eta: Option<Duration>, // Орієнтовний час до завершення малюнка.
+    pub error: Option<String>, // Опис помилки для стану `Failed`.
+    pub retries: u64,          // Запити до плати, повторені через відсутність відповіді.
+    pub stall_lifts: u64,      // Скільки разів перо піднімалося через зупинку руху.
+}
+
+impl Default for PlotStatus {
+    fn default() -> Self {
+        PlotStatus {
+            state: PlotState::Idle,
+            current_path: 0,
+            total_paths: 0,
+            position: (0.0, 0.0),
+            velocity: 0.0,
+            pen_down: false,
+            drawn_length: 0.0,
+            total_length: 0.0,
+            eta: None,
+            error: None,
+            retries: 0,
+            stall_lifts: 0,
+        }
+    }
+}
+
+/// Спільний пульт керування малюванням.
+///
+/// Клони `PlotControl` посилаються на той самий стан, тож один потік може малювати,
+/// а інший — призупиняти, відновлювати чи скасовувати малюнок і читати його стан.
+#[derive(Debug, Clone, Default)]
+pub struct PlotControl {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    paused: AtomicBool,      // Запит на паузу.
+    cancelled: AtomicBool,   // Запит на скасування.
+    status: Mutex<Progress>, // Поточний стан малювання.
+}
+
+/// Стан малювання разом із часом початку для оцінки часу до завершення.
+#[derive(Debug, Default)]
+struct Progress {
+    status: PlotStatus,       // Поточний стан.
+    started: Option<Instant>, // Момент початку малюнка.
+}
+
+impl PlotControl {
+    /// Створює новий пульт у стані `Idle`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Просить призупинити малювання на найближчій контрольній точці.
+    pub fn pause(&self) {
+        self.inner.paused.store(true, Ordering::SeqCst);
+    }
+
+    /// Відновлює призупинене малювання.
+    pub fn resume(&self) {
+        self.inner.paused.store(false, Ordering::SeqCst);
+    }
+
+    /// Просить скасувати малювання на найближчій контрольній точці.
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Перевіряє, чи надійшов запит на паузу.
+    pub fn is_paused(&self) -> bool {
+        self.inner.paused.load(Ordering::SeqCst)
+    }
+
+    /// Перевіряє, чи надійшов запит на скасування або процес перервано сигналом.
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst) || is_interrupted()
+    }
+
+    /// Повертає знімок поточного стану малювання.
+    ///
+    /// Час до завершення оцінюється з тривалості малювання та частки вже намальованої
+    /// довжини, тож він уточнюється в міру виконання малюнка.
+    pub fn status(&self) -> PlotStatus {
+        let progress = self.lock();
+        let mut status = progress.status.clone();
+        // Без відомої загальної довжини, як під час потокового малювання, оцінки немає
+        if status.state == PlotState::Plotting
+            && status.drawn_length > 0.0
+            && status.total_length > 0.0
+        {
+            if let Some(started) = progress.started {
+                let remaining = (status.total_length - status.drawn_length).max(0.0);
+                let elapsed = started.elapsed().as_secs_f64();
+                status.eta = Some(Duration::from_secs_f64(
+                    elapsed * remaining / status.drawn_length,
+                ));
+            }
+        }
+        status
+    }
+
+    /// Починає новий малюнок: скидає запити паузи й скасування та лічильники шляхів.
+    ///
+    /// # Аргументи
+    /// * `total_paths` - кількість шляхів у малюнку.
+    /// * `total_length` - загальна довжина шляхів малюнка (в мм).
+    pub fn start(&self, total_paths: usize, total_length: f64) {
+        self.inner.paused.store(false, Ordering::SeqCst);
+        self.inner.cancelled.store(false, Ordering::SeqCst);
+        let mut progress = self.lock();
+        progress.status = PlotStatus {
+            state: PlotState::Plotting,
+            total_paths,
+            total_length,
+            position: progress.status.position,
+            pen_down: progress.status.pen_down,
+            ..PlotStatus::default()
+        };
+        progress.started = Some(Instant::now());
+    }
+
+    /// Змінює стан малювання.
+    pub fn set_state(&self, state: PlotState) {
+        let mut progress = self.lock();
+        progress.status.state = state;
+        if state != PlotState::Plotting && state != PlotState::Paused {
+            progress.status.velocity = 0.0;
+        }
+    }
+
+    /// Позначає малюнок як перерваний помилкою.
+    pub fn fail(&self, error: String) {
+        let mut progress = self.lock();
+        progress.status.state = PlotState::Failed;
+        progress.status.velocity = 0.0;
+        progress.status.error = Some(error);
+    }
+
+    /// Уточнює кількість шляхів, коли її відомо лише після планування малюнка.
+    pub fn set_total_paths(&self, total_paths: usize) {
+        self.lock().status.total_paths = total_paths;
+    }
+
+    /// Оновлює кількість повторених запитів до плати за малюнок.
+    pub fn set_retries(&self, retries: u64) {
+        self.lock().status.retries = retries;
+    }
+
+    /// Відзначає підняття пера через зупинку руху посеред шляху.
+    pub fn stall_lift(&self) {
+        self.lock().status.stall_lifts += 1;
+    }
+
+    /// Оновлює номер шляху, що малюється.
+    pub fn set_current_path(&self, current_path: usize) {
+        self.lock().status.current_path = current_path;
+    }
+
+    /// Оновлює останню відому позицію пера.
+    pub fn set_position(&self, x: f64, y: f64) {
+        self.lock().status.position = (x, y);
+    }
+
+    /// Оновлює стан пера.
+    pub fn set_pen_down(&self, pen_down: bool) {
+        self.lock().status.pen_down = pen_down;
+    }
+
+    /// Зсуває позицію пера після кроку руху.
+    ///
+    /// # Аргументи
+    /// * `dx`, `dy` - зміщення каретки за крок (в мм).
+    /// * `velocity` - швидкість каретки наприкінці кроку (мм/с).
+    pub fn advance(&self, dx: f64, dy: f64, velocity: f64) {
+        let mut progress = self.lock();
+        let status = &mut progress.status;
+        status.position = (status.position.0 + dx, status.position.1 + dy);
+        status.velocity = velocity;
+        if status.pen_down {
+            status.drawn_length += dx.hypot(dy);
+        }
+    }
+
+    /// Блокує потік, доки триває пауза.
+    ///
+    /// # Повертає
+    /// * `Result<(), Cancelled>` - помилка, якщо під час паузи малюнок скасували.
+    pub fn wait_while_paused(&self) -> Result<(), Cancelled> {
+        while self.is_paused() {
+            if self.is_cancelled() {
+                return Err(Cancelled);
+            }
+            thread::sleep(PAUSE_POLL);
+        }
+        if self.is_cancelled() {
+            return Err(Cancelled);
+        }
+        Ok(())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Progress> {
+        // Стан лише читається й перезаписується, тож після паніки іншого потоку він цілісний
+        self.inner
+            .status
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Перехоплює Ctrl-C (SIGINT) і SIGTERM, щоб малюнок завершився з піднятим пером.
+///
+/// Перший сигнал лише позначає процес перерваним: малювання скасовується на найближчій
+/// контрольній точці, а `Axidraw::draw` паркує каретку. Повторний сигнал завершує
+/// процес одразу. На Windows так само обробляються Ctrl-C, Ctrl-Break і закриття
+/// консолі через `SetConsoleCtrlHandler`.
+pub fn install_signal_handlers() {
+    #[cfg(unix)]
+    {
+        let handler = on_signal as extern "C" fn(libc::c_int) as libc::sighandler_t;
+        // SAFETY: обробник лише змінює атомарний прапорець або викликає `_exit`
+        unsafe {
+            libc::signal(libc::SIGINT, handler);
+            libc::signal(libc::SIGTERM, handler);
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let installed = ctrlc::set_handler(|| {
+            if INTERRUPTED.swap(true, Ordering::SeqCst) {
+                std::process::exit(130);
+            }
+        });
+        match installed {
+            // Повторний виклик лишає вже встановлений обробник
+            Ok(()) | Err(ctrlc::Error::MultipleHandlers) => {}
+            Err(e) => tracing::warn!(
+                "{}",
+                tr!(
+                    "Failed to catch Ctrl-C: {}",
+                    "Не вдалося перехопити Ctrl-C: {}",
+                    e
+                )
+            ),
+        }
+    }
+}
+
+/// Чи отримав процес сигнал завершення.
+pub fn is_interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+#[cfg(unix)]
+extern "C" fn on_signal(_signal: libc::c_int) {
+    if INTERRUPTED.swap(true, Ordering::SeqCst) {
+        // SAFETY: `_exit` безпечний в обробнику сигналу
+        unsafe { libc::_exit(130) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pause_resume_and_cancel() {
+        let control = PlotControl::new();
+        control.start(3, 0.0);
+        assert_eq!(control.status().state, PlotState::Plotting);
+        assert_eq!(control.status().total_paths, 3);
+        assert!(control.wait_while_paused().is_ok());
+
+        control.pause();
+        let remote = control.clone();
+        let waiter = thread::spawn(move || remote.wait_while_paused());
+        thread::sleep(PAUSE_POLL * 2);
+        assert!(!waiter.is_finished(), "Пауза має блокувати малювання");
+        control.resume();
+        assert!(waiter.join().unwrap().is_ok());
+
+        control.pause();
+        control.cancel();
+        assert!(control.wait_while_paused().is_err());
+
+        // Новий малюнок скидає попередні запити
+        control.start(1, 0.0);
+        assert!(!control.is_paused() && !control.is_cancelled());
+    }
+
+    #[test]
+    fn test_progress_and_eta() {
+        let control = PlotControl::new();
+        control.start(2, 100.0);
+        assert_eq!(control.status().eta, None, "Без намальованого немає оцінки");
+
+        // Переміщення з піднятим пером не рахується намальованим
+        control.advance(10.0, 0.0, 5.0);
+        control.set_pen_down(true);
+        control.advance(15.0, 20.0, 20.0);
+        thread::sleep(Duration::from_millis(20));
+
+        let status = control.status();
+        assert_eq!(status.position, (25.0, 20.0));
+        assert_eq!(status.velocity, 20.0);
+        assert!((status.drawn_length - 25.0).abs() < 1e-9);
+        let eta = status.eta.expect("Має бути оцінка часу");
+        assert!(
+            eta >= Duration::from_millis(60),
+            "Лишилось утричі більше намальованого"
+        );
+
+        control.set_state(PlotState::Finished);
+        assert_eq!(control.status().eta, None);
+        assert_eq!(control.status().velocity, 0.0);
+    }
+}
@@ -1,22 +1,248 @@
-use log::{debug, error, info};
-use serialport::{available_ports, DataBits, Parity, SerialPort, SerialPortType, StopBits};
-use std::{cmp::max, time::Duration};
+pub mod ebb;
+pub mod fake;
+pub mod firmware;
+pub mod hex;
+pub mod mock;
+pub mod tool;
+pub mod transcript;
+
+use regex::Regex;
+use serialport::{
+    available_ports, DataBits, Parity, SerialPort, SerialPortType, StopBits, UsbPortInfo,
+};
+use std::time::{Duration, Instant};
 use thiserror::Error;
+use tracing::{debug, error, info, warn};
+
+use self::ebb::{Command, Response};
+use self::firmware::FirmwareVersion;
+use self::transcript::Transcript;
+use crate::motion::kinematics;
+use crate::tr;
+use crate::trace;
+
+/// Положення серво (в одиницях 1/12 мкс ширини імпульсу) для 0% та 100% ходу ручки.
+const SERVO_MIN: f64 = 7500.0;
+const SERVO_MAX: f64 = 28000.0;
+
+/// Період оновлення серво EBB: швидкість SC,11/SC,12 задає зміну положення за цей період.
+const SERVO_PERIOD_MS: f64 = 24.0;
+
+/// Множник, що переводить швидкість ручки з опцій у швидкість серво для SC,11/SC,12.
+const SERVO_RATE_SCALE: i32 = 5;
+
+/// Інтервал опитування плати під час очікування зупинки моторів.
+const MOTOR_POLL: Duration = Duration::from_millis(10);
+
+/// Обчислює тривалість переміщення ручки між двома положеннями за моделлю швидкості серво.
+///
+/// Швидкість `SC,11`/`SC,12` задає, на скільки одиниць змінюється положення серво за кожен
+/// період оновлення (24 мс). Тривалість руху — кількість періодів, потрібних, щоб пройти
+/// відстань між положеннями, округлена вгору до мілісекунди.
+///
+/// # Параметри:
+/// - `from_position`: Початкове положення ручки (0-100%).
+/// - `to_position`: Кінцеве положення ручки (0-100%).
+/// - `speed`: Швидкість механізму підйому ручки з опцій (0 — миттєво).
+///
+/// # Повертає:
+/// - `Duration`: Час, потрібний для переміщення ручки.
+pub fn servo_move_duration(from_position: i32, to_position: i32, speed: i32) -> Duration {
+    let rate = (speed * SERVO_RATE_SCALE) as f64;
+    if rate <= 0.0 {
+        return Duration::ZERO;
+    }
+    let distance = (SERVO_MAX - SERVO_MIN) * (from_position - to_position).abs() as f64 / 100.0;
+    let millis = (distance / rate * SERVO_PERIOD_MS).ceil();
+    Duration::from_millis(millis as u64)
+}
+
+/// Обчислює повну затримку після зміни положення ручки.
+///
+/// # Параметри:
+/// - `movement`: Тривалість переміщення серво.
+/// - `delay`: Додаткова затримка для цього напрямку руху (мс, може бути від'ємною).
+/// - `settle`: Час заспокоєння ручки після будь-якого руху (мс).
+///
+/// # Повертає:
+/// - `Option<Duration>`: Затримка, обмежена діапазоном команди SP (1-65535 мс),
+///   або `None`, якщо чекати не потрібно.
+pub fn pen_delay(movement: Duration, delay: i32, settle: i32) -> Option<Duration> {
+    let total = movement.as_millis() as i64 + delay as i64 + settle as i64;
+    if total <= 0 {
+        return None;
+    }
+    Some(Duration::from_millis(total.min(u16::MAX as i64) as u64))
+}
+
+/// Переводить тривалість у мілісекунди для параметрів команд EBB.
+///
+/// Тривалість, що не вміщується в `u32`, обмежується `u32::MAX`, тож перевірка меж
+/// команди відхилить її.
+fn millis(duration: Duration) -> u32 {
+    u32::try_from(duration.as_millis()).unwrap_or(u32::MAX)
+}
+
+/// USB VID:PID плати EiBotBoard (SchmalzHaus).
+const EBB_VID: u16 = 0x04d8;
+const EBB_PID: u16 = 0xfd92;
+
+/// Чи є USB-пристрій платою EiBotBoard.
+///
+/// # Параметри:
+/// - `vid`, `pid`: Ідентифікатори виробника та продукту USB.
+/// - `product`: Назва продукту, якщо її повідомляє система.
+fn is_ebb(vid: u16, pid: u16, product: Option<&str>) -> bool {
+    (vid == EBB_VID && pid == EBB_PID)
+        || product.is_some_and(|product| product.starts_with("EiBotBoard"))
+}
+
+/// Опис USB-порту для зіставлення з шаблоном `port_config`.
+fn port_description(port_name: &str, info: &UsbPortInfo) -> String {
+    let mut description = format!("{} {:04x}:{:04x}", port_name, info.vid, info.pid);
+    for field in [&info.manufacturer, &info.product, &info.serial_number]
+        .into_iter()
+        .flatten()
+    {
+        description.push(' ');
+        description.push_str(field);
+    }
+    description
+}
+
+/// Зчитує відповідь плати, доки порт не повідомить тайм-аут.
+///
+/// З `WireLog::Raw` байти кожного читання порту пишуться до журналу `wire`.
+fn read_full_response(
+    port: &mut dyn SerialPort,
+    wire_log: Option<WireLog>,
+) -> std::io::Result<String> {
+    let mut response = Vec::new();
+    loop {
+        let mut buffer = [0; 256];
+        match port.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(bytes_read) => {
+                if wire_log == Some(WireLog::Raw) {
+                    log_wire(WireLog::Raw, false, &buffer[..bytes_read]);
+                }
+                response.extend_from_slice(&buffer[..bytes_read]);
+            }
+            Err(ref e) if e.kind() == std::io::ErrorKind::TimedOut => break,
+            Err(e) => return Err(e),
+        }
+    }
+    let response = String::from_utf8_lossy(&response).to_string();
+    debug!(
+        "{}",
+        tr!("Received response: {}", "Отримано відповідь: {}", response)
+    );
+    Ok(response)
+}
+
+/// Підключена плата EBB, знайдена під час пошуку пристроїв.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceInfo {
+    pub port_name: String,                 // Назва серійного порту.
+    pub nickname: Option<String>,          // Псевдонім плати, якщо його задано.
+    pub firmware: Option<FirmwareVersion>, // Версія прошивки; `None`, якщо порт не відповів.
+}
+
+impl std::fmt::Display for DeviceInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.port_name)?;
+        if let Some(nickname) = &self.nickname {
+            write!(f, " «{}»", nickname)?;
+        }
+        match self.firmware {
+            Some(firmware) => write!(f, "{}", tr!(", firmware {}", ", прошивка {}", firmware)),
+            None => write!(
+                f,
+                "{}",
+                tr!(
+                    ", not responding (port busy?)",
+                    ", не відповідає (порт зайнятий?)"
+                )
+            ),
+        }
+    }
+}
 
 /// Тип для обробки помилок, які можуть виникнути під час роботи з пристроєм
 #[derive(Error, Debug)]
 pub enum DeviceError {
-    #[error("Помилка підключення: {0}")]
+    #[error("{}", tr!("Connection error: {}", "Помилка підключення: {}", .0))]
     ConnectionError(String),
 
-    #[error("Помилка команди '{command}': {message}")]
+    #[error(
+        "{}",
+        tr!("Command '{}' failed: {}", "Помилка команди '{}': {}", .command, .message)
+    )]
     CommandError { command: String, message: String },
 
-    #[error("Невірне значення для параметру: {parameter}, значення: {value}")]
+    #[error(
+        "{}",
+        tr!(
+            "Invalid value for parameter {}: {}",
+            "Невірне значення для параметру: {}, значення: {}",
+            .parameter,
+            .value
+        )
+    )]
     InvalidValue { parameter: String, value: String },
 
-    #[error("Некоректна відповідь: {0}")]
+    #[error("{}", tr!("Invalid response: {}", "Некоректна відповідь: {}", .0))]
     InvalidResponse(String),
+
+    #[error(
+        "{}",
+        tr!(
+            "The board did not answer command '{}'",
+            "Плата не відповіла на команду '{}'",
+            .command
+        )
+    )]
+    Timeout { command: String },
+
+    #[error(
+        "{}",
+        tr!(
+            "The board rejected command '{}': {}",
+            "Плата відхилила команду '{}': {}",
+            .command,
+            .reply
+        )
+    )]
+    Rejected { command: String, reply: String },
+
+    #[error(
+        "{}",
+        tr!(
+            "The motors are still moving after {:?}",
+            "Мотори ще рухаються через {:?}",
+            .0
+        )
+    )]
+    StillMoving(Duration),
+
+    #[error("{}", tr!("Transcript error: {}", "Помилка журналу обміну: {}", .0))]
+    Transcript(#[from] std::io::Error),
+
+    #[error(
+        "{}",
+        tr!(
+            "Command {} requires EBB firmware {} or newer, but the board has {}",
+            "Команда {} потребує прошивки EBB {} або новішої, а плата має {}",
+            .command,
+            .required,
+            .version
+        )
+    )]
+    Unsupported {
+        command: &'static str,
+        required: FirmwareVersion,
+        version: FirmwareVersion,
+    },
 }
 
 /// Режими кроків для моторів (глобальний режим)
@@ -30,6 +256,51 @@ pub enum StepMode {
     FullStep = 5,     // Повний крок
 }
 
+impl StepMode {
+    /// Режим за кодом команди `EM` (0 вимикає мотори, 1–5 від 1/16 до повного кроку).
+    pub fn from_code(code: u8) -> Option<StepMode> {
+        match code {
+            0 => Some(StepMode::Disable),
+            1 => Some(StepMode::OneSixteenth),
+            2 => Some(StepMode::OneEighth),
+            3 => Some(StepMode::OneQuarter),
+            4 => Some(StepMode::OneHalf),
+            5 => Some(StepMode::FullStep),
+            _ => None,
+        }
+    }
+
+    /// Кількість мікрокроків на повний крок мотора (0 для вимкнених моторів).
+    pub fn microsteps(&self) -> u32 {
+        match self {
+            StepMode::Disable => 0,
+            StepMode::OneSixteenth => 16,
+            StepMode::OneEighth => 8,
+            StepMode::OneQuarter => 4,
+            StepMode::OneHalf => 2,
+            StepMode::FullStep => 1,
+        }
+    }
+
+    /// Режим за кількістю мікрокроків на повний крок.
+    ///
+    /// # Аргументи
+    /// * `microsteps` - 16, 8, 4, 2 або 1.
+    ///
+    /// # Повертає
+    /// * `Option<StepMode>` - режим або `None` для іншої кількості.
+    pub fn from_microsteps(microsteps: u32) -> Option<StepMode> {
+        match microsteps {
+            16 => Some(StepMode::OneSixteenth),
+            8 => Some(StepMode::OneEighth),
+            4 => Some(StepMode::OneQuarter),
+            2 => Some(StepMode::OneHalf),
+            1 => Some(StepMode::FullStep),
+            _ => None,
+        }
+    }
+}
+
 /// Стан мотора (чи виконується команда, чи рухається мотор, чи FIFO порожня)
 #[derive(Debug, Clone, Copy)]
 pub struct MotorStatus {
@@ -38,6 +309,163 @@ pub struct MotorStatus {
     pub fifo_empty: bool,        // Чи FIFO порожня
 }
 
+/// Загальний стан плати з однієї відповіді `QG`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GeneralStatus {
+    pub fifo_pending: bool,      // У черзі FIFO є команди руху.
+    pub motor2_moving: bool,     // Мотор 2 рухається.
+    pub motor1_moving: bool,     // Мотор 1 рухається.
+    pub executing_command: bool, // Плата виконує команду руху.
+    pub pen_up: bool,            // Перо підняте.
+    pub button_pressed: bool,    // Кнопку PRG натиснули після попереднього запиту.
+    pub servo_power: bool,       // Серво живиться (прошивка 3.x).
+}
+
+impl GeneralStatus {
+    /// Розбирає байт стану `QG`.
+    ///
+    /// # Параметри:
+    /// - `bits`: Біти 0–7: FIFO, мотор 2, мотор 1, команда, перо, кнопка, RB2, живлення серво.
+    pub fn from_bits(bits: u8) -> Self {
+        let bit = |index: u8| bits & (1 << index) != 0;
+        GeneralStatus {
+            fifo_pending: bit(0),
+            motor2_moving: bit(1),
+            motor1_moving: bit(2),
+            executing_command: bit(3),
+            pen_up: bit(4),
+            button_pressed: bit(5),
+            servo_power: bit(7),
+        }
+    }
+
+    /// Чи завершила плата всі команди руху.
+    pub fn is_idle(&self) -> bool {
+        !(self.fifo_pending || self.motor1_moving || self.motor2_moving || self.executing_command)
+    }
+}
+
+/// Останній відомий стан плати: перо, мотори й позиція.
+///
+/// `Device` оновлює його після кожної команди, що змінює стан, тож верхні рівні, як-от
+/// пауза з продовженням чи програмні межі, читають знімок без запитів до плати. Команди
+/// руху роблять позицію застарілою (`None`), доки її не прочитає `read_position` чи
+/// `sync_state`, бо рух у черзі FIFO ще може тривати.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeviceState {
+    pub pen_lowered: bool,            // Перо опущене.
+    pub motor1_enabled: bool,         // Мотор 1 увімкнений.
+    pub motor2_enabled: bool,         // Мотор 2 увімкнений.
+    pub step_mode: StepMode,          // Режим кроку моторів.
+    pub position: Option<(i32, i32)>, // Позиції моторів у кроках або `None`, якщо застаріли.
+}
+
+impl DeviceState {
+    /// Оновлює стан за командою, яку плата щойно прийняла.
+    fn track(&mut self, command: &Command) {
+        match *command {
+            Command::Sp { value, .. } => self.pen_lowered = value == 0,
+            Command::Tp { .. } => self.pen_lowered = !self.pen_lowered,
+            Command::Em { enable1, enable2 } => {
+                self.motor1_enabled = enable1 != 0;
+                self.motor2_enabled = enable2 != 0;
+                // Enable2 не змінює режим кроку, а вимкнений мотор 1 зберігає попередній
+                let mode = StepMode::from_code(enable1);
+                if let Some(mode) = mode.filter(|mode| *mode != StepMode::Disable) {
+                    self.step_mode = mode;
+                }
+            }
+            Command::Es { disable_motors } => {
+                self.position = None;
+                if disable_motors {
+                    self.motor1_enabled = false;
+                    self.motor2_enabled = false;
+                    self.step_mode = StepMode::Disable;
+                }
+            }
+            Command::Cs | Command::R | Command::Rb => self.position = None,
+            ref motion if motion.is_motion() => self.position = None,
+            _ => {}
+        }
+    }
+}
+
+/// Ціль журналу, до якої пишеться обмін з платою, щоб відфільтрувати його від решти.
+pub const WIRE_TARGET: &str = "wire";
+
+/// Скільки обміну з платою писати до журналу з ціллю `WIRE_TARGET`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WireLog {
+    Commands,  // Лише надіслані команди, без відповідей плати.
+    Responses, // Команди та відповіді плати.
+    Raw,       // Байти кожного запису в порт і читання з нього в шістнадцятковому вигляді.
+}
+
+impl WireLog {
+    /// Рядок журналу для надісланих (`>`) чи отриманих (`<`) байтів.
+    ///
+    /// # Параметри:
+    /// - `sent`: `true` для команди, `false` для відповіді плати.
+    /// - `bytes`: Байти команди чи відповіді.
+    ///
+    /// # Повертає:
+    /// - `Option<String>`: Рядок журналу або `None`, якщо на цьому рівні такі байти не пишуться.
+    pub fn entry(self, sent: bool, bytes: &[u8]) -> Option<String> {
+        let text = match self {
+            WireLog::Commands if !sent => return None,
+            WireLog::Raw => bytes
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<Vec<_>>()
+                .join(" "),
+            _ => String::from_utf8_lossy(bytes)
+                .replace('\r', "\\r")
+                .replace('\n', "\\n"),
+        };
+        Some(format!("{} {}", if sent { '>' } else { '<' }, text))
+    }
+}
+
+/// Пише надіслані чи отримані байти до журналу `wire`, якщо цей рівень їх показує.
+fn log_wire(level: WireLog, sent: bool, bytes: &[u8]) {
+    if let Some(entry) = level.entry(sent, bytes) {
+        info!(target: WIRE_TARGET, "{}", entry);
+    }
+}
+
+/// Налаштування обміну з платою через серійний порт.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SerialOptions {
+    pub timeout: Duration,         // Тайм-аут читання, що завершує відповідь плати.
+    pub retries: u32,              // Скільки разів повторити запит, на який плата не відповіла.
+    pub retry_backoff: Duration,   // Пауза перед першим повтором; кожен наступний удвічі довший.
+    pub write_chunk: usize,        // Найбільша порція байтів для одного запису в порт.
+    pub wire_log: Option<WireLog>, // Скільки обміну писати до журналу `wire`; `None` вимикає.
+    pub fifo_timeout: Duration,    // Найдовше очікування місця в черзі FIFO плати.
+}
+
+impl Default for SerialOptions {
+    fn default() -> Self {
+        SerialOptions {
+            timeout: Duration::from_millis(100),
+            retries: 3,
+            retry_backoff: Duration::from_millis(50),
+            write_chunk: 64,
+            wire_log: None,
+            fifo_timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Лічильники обміну з платою від підключення.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SerialStats {
+    pub commands: u64,   // Надіслані команди, включно з повторами.
+    pub timeouts: u64,   // Команди, на які плата не відповіла вчасно.
+    pub retries: u64,    // Повторно надіслані запити.
+    pub fifo_waits: u64, // Команди руху, які чекали на місце в повній черзі FIFO плати.
+}
+
 /// Структура для налаштувань пристрою, які приймаються в конструкторі `Device`
 pub struct DeviceOptions {
     pub steps_per_unit: i32,
@@ -47,9 +475,12 @@ pub struct DeviceOptions {
     pub pen_down_position: i32,      // Положення ручки при опущеній ручці.
     pub pen_down_speed: i32,         // Швидкість опускання механізму підйому ручки.
     pub pen_down_delay: i32,         // Затримка після опускання ручки.
+    pub pen_settle: i32,             // Час заспокоєння ручки після підйому чи опускання (мс).
     pub step_mode: StepMode,         // Режим кроку для моторів.
     pub port_name: Option<String>,   // Назва порту для підключення (опціонально).
     pub port_config: Option<String>, // Перевизначити спосіб знаходження USB-портів.
+    pub transcript: Option<String>,  // Файл для журналу обміну з платою (опціонально).
+    pub serial: SerialOptions,       // Тайм-аути, повтори та розбиття запису.
 }
 
 /// Структура Device для керування підключенням до пристрою через серійний порт
@@ -67,14 +498,23 @@ pub struct Device {
     pub pen_down_position: i32, // Положення ручки при опущеній ручці.
     pub pen_down_speed: i32,    // Швидкість опускання механізму підйому ручки.
     pub pen_down_delay: i32,    // Затримка після опускання ручки.
+    pub pen_settle: i32,        // Час заспокоєння ручки після підйому чи опускання (мс).
+
+    // Версія прошивки, від якої залежать доступні команди та формат відповідей
+    pub firmware: FirmwareVersion,
 
-    // Стан ручки
-    pub is_lowered: bool, // Стан ручки: true — опущена, false — піднята
+    // Журнал обміну з платою
+    transcript: Option<Transcript>,
 
-    // Стан моторів
-    step_mode: StepMode, // Глобальний режим кроку для обох моторів
-    motor1_enabled: bool,
-    motor2_enabled: bool,
+    // Обмін через серійний порт
+    serial: SerialOptions,
+    stats: SerialStats,
+    in_flight: usize, // Команди руху, які плата могла ще не виконати: поточна й ті, що в черзі.
+
+    // Останній відомий стан пера, моторів і позиції
+    state: DeviceState,
+    motors_on_connect: bool, // Мотори були ввімкнені ще до підключення
+    keep_motors: bool,       // Не вимикати мотори під час відключення
 }
 
 impl Device {
@@ -88,15 +528,29 @@ impl Device {
     /// # Повертає:
     /// - `Result<Self, DeviceError>`: Повертає екземпляр структури Device або помилку в разі невдачі.
     pub fn new(options: DeviceOptions) -> Result<Self, DeviceError> {
-        // Використовуємо вказаний порт або знаходимо порт автоматично
-        let port_name = if let Some(ref port) = options.port_name {
-            port.clone()
+        // Використовуємо вказаний порт чи AxiDraw із таким псевдонімом або знаходимо порт автоматично
+        let port_name = if let Some(ref device) = options.port_name {
+            Device::resolve_port(device, options.port_config.as_deref())?
         } else {
-            Device::find_port()?
+            Device::find_port(options.port_config.as_deref())?
         };
 
-        let port = Device::connect(&port_name)?; // Підключення до знайденого порту
+        let port = Device::connect(&port_name, options.serial.timeout)?; // Підключення до знайденого порту
+        Device::with_port(port, options)
+    }
 
+    /// Створює `Device` поверх уже відкритого порту, наприклад імітованого `MockPort`.
+    ///
+    /// # Параметри:
+    /// - `port`: Відкритий серійний порт.
+    /// - `options`: Параметри налаштування пристрою `DeviceOptions`.
+    ///
+    /// # Повертає:
+    /// - `Result<Self, DeviceError>`: Повертає екземпляр структури Device або помилку в разі невдачі.
+    pub fn with_port(
+        port: Box<dyn SerialPort>,
+        options: DeviceOptions,
+    ) -> Result<Self, DeviceError> {
         // Створення нового екземпляра `Device` з параметрами з `DeviceOptions`
         let mut device = Self {
             port,
@@ -108,26 +562,57 @@ impl Device {
             pen_down_position: options.pen_down_position,
             pen_down_speed: options.pen_down_speed,
             pen_down_delay: options.pen_down_delay,
-            is_lowered: false,
-            step_mode: options.step_mode,
-            motor1_enabled: false,
-            motor2_enabled: false,
+            pen_settle: options.pen_settle,
+            firmware: FirmwareVersion::MINIMUM,
+            transcript: options.transcript.map(Transcript::create).transpose()?,
+            serial: options.serial,
+            stats: SerialStats::default(),
+            in_flight: 0,
+            state: DeviceState {
+                pen_lowered: false,
+                motor1_enabled: false,
+                motor2_enabled: false,
+                step_mode: options.step_mode,
+                position: None,
+            },
+            motors_on_connect: false,
+            keep_motors: false,
         };
 
+        // Від версії прошивки залежать команди, які можна надсилати далі
+        let version = device.version()?;
+        device.firmware = FirmwareVersion::parse(&version).unwrap_or_else(|| {
+            warn!(
+                "{}",
+                tr!(
+                    "Could not parse firmware version '{}', assuming {}.",
+                    "Не вдалося розібрати версію прошивки '{}', вважаємо її {}.",
+                    version.trim(),
+                    FirmwareVersion::MINIMUM
+                )
+            );
+            FirmwareVersion::MINIMUM
+        });
+        debug!(
+            "{}",
+            tr!(
+                "Firmware {} queues {} motion commands in its FIFO.",
+                "Прошивка {} тримає в черзі FIFO {} команд руху.",
+                device.firmware,
+                device.firmware.fifo_depth()
+            )
+        );
+
         // Виконуємо конфігурацію пристрою з використанням параметрів з `DeviceOptions`
         device.configure()?;
-        device.is_lowered = device.query_pen_state()?;
 
-        // Зчитуємо поточний стан моторів
-        let (motor1_enabled, motor2_enabled, step_mode) = device.query_enable_motors()?;
-        if !motor1_enabled || !motor2_enabled {
-            device.enable_motors(step_mode)?;
+        // Зчитуємо поточний стан пера й моторів і вмикаємо мотори у вибраному режимі кроку
+        let state = device.sync_state()?;
+        device.motors_on_connect = state.motor1_enabled && state.motor2_enabled;
+        if !device.motors_on_connect || state.step_mode != options.step_mode {
+            device.enable_motors(options.step_mode)?;
         }
 
-        device.motor1_enabled = motor1_enabled;
-        device.motor2_enabled = motor2_enabled;
-        device.step_mode = step_mode;
-
         Ok(device)
     }
 
@@ -139,22 +624,24 @@ impl Device {
     /// # Повертає:
     /// - `Result<(), DeviceError>`: Повертає Ok у випадку успіху або помилку при невдачі.
     fn configure(&mut self) -> Result<(), DeviceError> {
-        let servo_min = 7500.0;
-        let servo_max = 28000.0;
-
         // Розрахунок позиції підйому ручки
         let pen_up_position = self.pen_up_position as f64 / 100.0;
-        let pen_up_position = servo_min + (servo_max - servo_min) * pen_up_position;
+        let pen_up_position = SERVO_MIN + (SERVO_MAX - SERVO_MIN) * pen_up_position;
 
         // Розрахунок позиції опускання ручки
         let pen_down_position = self.pen_down_position as f64 / 100.0;
-        let pen_down_position = servo_min + (servo_max - servo_min) * pen_down_position;
+        let pen_down_position = SERVO_MIN + (SERVO_MAX - SERVO_MIN) * pen_down_position;
 
         // Відправка команд для конфігурації позицій і швидкостей
-        self.command(&format!("SC,4,{}", pen_up_position as i32))?;
-        self.command(&format!("SC,5,{}", pen_down_position as i32))?;
-        self.command(&format!("SC,11,{}", (self.pen_up_speed * 5) as i32))?;
-        self.command(&format!("SC,12,{}", (self.pen_down_speed * 5) as i32))?;
+        let settings = [
+            (4, pen_up_position as i32),
+            (5, pen_down_position as i32),
+            (11, self.pen_up_speed * SERVO_RATE_SCALE),
+            (12, self.pen_down_speed * SERVO_RATE_SCALE),
+        ];
+        for (parameter, value) in settings {
+            self.send(&Command::Sc { parameter, value })?;
+        }
 
         Ok(())
     }
@@ -162,35 +649,179 @@ impl Device {
     /// Пошук доступного серійного порту
     ///
     /// Цей метод шукає серійний порт, підключений до пристрою EiBotBoard, використовуючи інформацію про USB.
-    /// Якщо пристрій не знайдено, повертається помилка.
+    /// Якщо пристрій не знайдено або знайдено кілька, повертається помилка з переліком кандидатів.
+    ///
+    /// # Параметри:
+    /// - `port_config`: (опціонально) Регулярний вираз, що замінює стандартне розпізнавання плати.
     ///
     /// # Повертає:
     /// - `Result<String, DeviceError>`: Назву порту або помилку, якщо пристрій не знайдено.
-    fn find_port() -> Result<String, DeviceError> {
-        info!("Пошук серійного порту...");
+    fn find_port(port_config: Option<&str>) -> Result<String, DeviceError> {
+        let mut ports = Device::ebb_ports(port_config)?;
+        match ports.len() {
+            0 => Err(DeviceError::ConnectionError(tr!(
+                "No matching port found",
+                "Не знайдено відповідного порту"
+            ))),
+            1 => Ok(ports.remove(0)),
+            _ => Err(DeviceError::ConnectionError(tr!(
+                "Found several EBB boards: {}; pick one with --device",
+                "Знайдено кілька плат EBB: {}; виберіть одну через --device",
+                ports.join(", ")
+            ))),
+        }
+    }
+
+    /// Назви всіх серійних портів, до яких підключені плати EiBotBoard.
+    ///
+    /// Плата впізнається за парою VID:PID SchmalzHaus (`04d8:fd92`) або за назвою продукту
+    /// `EiBotBoard`, яку показують не всі системи й драйвери. Шаблон `port_config` замість
+    /// цього перевіряється проти опису порту: назви, `VID:PID`, виробника, продукту та
+    /// серійного номера.
+    ///
+    /// # Параметри:
+    /// - `port_config`: (опціонально) Регулярний вираз для опису порту.
+    fn ebb_ports(port_config: Option<&str>) -> Result<Vec<String>, DeviceError> {
+        info!(
+            "{}",
+            tr!("Searching for a serial port...", "Пошук серійного порту...")
+        );
+        let pattern = port_config.map(Regex::new).transpose().map_err(|e| {
+            DeviceError::ConnectionError(tr!(
+                "Invalid port_config pattern: {}",
+                "Некоректний шаблон port_config: {}",
+                e
+            ))
+        })?;
         let ports = available_ports().map_err(|e| {
-            DeviceError::ConnectionError(format!("Помилка при отриманні списку портів: {:?}", e))
+            DeviceError::ConnectionError(tr!(
+                "Failed to list ports: {:?}",
+                "Помилка при отриманні списку портів: {:?}",
+                e
+            ))
         })?;
 
-        // Пошук порту, що відповідає певному пристрою
-        ports
+        // Пошук портів, що відповідають певному пристрою
+        Ok(ports
             .into_iter()
-            .find_map(|port| {
-                if let SerialPortType::UsbPort(info) = &port.port_type {
-                    if let Some(product) = &info.product {
-                        if product.starts_with("EiBotBoard") {
-                            info!("Знайдено пристрій продукту: {}", product);
-                            return Some(port.port_name.clone());
-                        }
+            .filter_map(|port| {
+                let SerialPortType::UsbPort(info) = &port.port_type else {
+                    return None;
+                };
+                let description = port_description(&port.port_name, info);
+                let found = match &pattern {
+                    Some(pattern) => pattern.is_match(&description),
+                    None => is_ebb(info.vid, info.pid, info.product.as_deref()),
+                };
+                if found {
+                    info!(
+                        "{}",
+                        tr!("Found device: {}", "Знайдено пристрій: {}", description)
+                    );
+                }
+                found.then_some(port.port_name)
+            })
+            .collect())
+    }
+
+    /// Знаходить усі підключені AxiDraw разом з їхніми псевдонімами та версіями прошивки.
+    ///
+    /// Кожен порт відкривається лише на час запитів `V` і `QT`, без налаштування серво
+    /// та моторів, тож пошук не заважає плоттерам, що вже чекають на роботу.
+    ///
+    /// # Параметри:
+    /// - `port_config`: (опціонально) Регулярний вираз, що замінює стандартне розпізнавання плати.
+    ///
+    /// # Повертає:
+    /// - `Result<Vec<DeviceInfo>, DeviceError>`: Знайдені плати або помилка переліку портів.
+    pub fn list(port_config: Option<&str>) -> Result<Vec<DeviceInfo>, DeviceError> {
+        Ok(Device::ebb_ports(port_config)?
+            .into_iter()
+            .map(|port_name| match Device::probe(&port_name) {
+                Ok((firmware, nickname)) => DeviceInfo {
+                    port_name,
+                    nickname,
+                    firmware: Some(firmware),
+                },
+                Err(e) => {
+                    warn!(
+                        "{}",
+                        tr!(
+                            "Port {} is not responding: {}",
+                            "Порт {} не відповідає: {}",
+                            port_name,
+                            e
+                        )
+                    );
+                    DeviceInfo {
+                        port_name,
+                        nickname: None,
+                        firmware: None,
                     }
                 }
-                None
             })
+            .collect())
+    }
+
+    /// Визначає порт пристрою за назвою порту або псевдонімом AxiDraw.
+    ///
+    /// # Параметри:
+    /// - `device`: Назва серійного порту (наприклад, `/dev/ttyACM0` чи `COM3`) або псевдонім плати.
+    /// - `port_config`: (опціонально) Регулярний вираз для пошуку плат за псевдонімом.
+    ///
+    /// # Повертає:
+    /// - `Result<String, DeviceError>`: Назву порту або помилку, якщо такого пристрою немає.
+    pub fn resolve_port(device: &str, port_config: Option<&str>) -> Result<String, DeviceError> {
+        let is_port = std::path::Path::new(device).exists()
+            || available_ports()
+                .map(|ports| ports.iter().any(|port| port.port_name == device))
+                .unwrap_or(false);
+        if is_port {
+            return Ok(device.to_string());
+        }
+
+        Device::list(port_config)?
+            .into_iter()
+            .find(|info| info.nickname.as_deref() == Some(device))
+            .map(|info| info.port_name)
             .ok_or_else(|| {
-                DeviceError::ConnectionError("Не знайдено відповідного порту".to_string())
+                DeviceError::ConnectionError(tr!(
+                    "No AxiDraw found with port or nickname «{}»",
+                    "Не знайдено AxiDraw з портом або псевдонімом «{}»",
+                    device
+                ))
             })
     }
 
+    /// Зчитує версію прошивки та псевдонім плати без повного підключення.
+    fn probe(port_name: &str) -> Result<(FirmwareVersion, Option<String>), DeviceError> {
+        let mut port = Device::connect(port_name, SerialOptions::default().timeout)?;
+        let mut query = |command: Command| -> Result<String, DeviceError> {
+            let line = command.to_string();
+            let failed = |e: std::io::Error| DeviceError::CommandError {
+                command: line.clone(),
+                message: e.to_string(),
+            };
+            port.write_all(format!("{}\r", line).as_bytes())
+                .map_err(failed)?;
+            let response = read_full_response(port.as_mut(), None).map_err(failed)?;
+            Ok(response
+                .trim_end()
+                .trim_end_matches("OK")
+                .trim()
+                .to_string())
+        };
+
+        let version = query(Command::V)?;
+        let firmware = FirmwareVersion::parse(&version).unwrap_or(FirmwareVersion::MINIMUM);
+        let nickname = if firmware.supports(FirmwareVersion::NICKNAME) {
+            Some(query(Command::Qt)?).filter(|nickname| !nickname.is_empty())
+        } else {
+            None
+        };
+        Ok((firmware, nickname))
+    }
+
     /// Підключення до пристрою через серійний порт
     ///
     /// Метод встановлює з'єднання з пристроєм, використовуючи назву порту, і налаштовує серійний порт для зв'язку.
@@ -198,36 +829,97 @@ impl Device {
     ///
     /// # Параметри:
     /// - `port_name`: Назва порту, до якого потрібно підключитися.
+    /// - `timeout`: Тайм-аут читання, що завершує відповідь плати.
     ///
     /// # Повертає:
     /// - `Result<Box<dyn SerialPort>, DeviceError>`: Повертає відкритий серійний порт або помилку у випадку невдачі.
-    fn connect(port_name: &str) -> Result<Box<dyn SerialPort>, DeviceError> {
-        info!("Підключення до пристрою: {} ...", port_name);
+    fn connect(port_name: &str, timeout: Duration) -> Result<Box<dyn SerialPort>, DeviceError> {
+        info!(
+            "{}",
+            tr!(
+                "Connecting to device: {} ...",
+                "Підключення до пристрою: {} ...",
+                port_name
+            )
+        );
 
         // Створення і конфігурація серійного порту
         let mut port = serialport::new(port_name, 115200)
-            .timeout(Duration::from_millis(100))
+            .timeout(timeout)
             .parity(Parity::None)
             .data_bits(DataBits::Eight)
             .stop_bits(StopBits::One)
             .open()
             .map_err(|e| {
-                DeviceError::ConnectionError(format!("Не вдалося відкрити порт: {}", e))
+                DeviceError::ConnectionError(tr!(
+                    "Failed to open port: {}",
+                    "Не вдалося відкрити порт: {}",
+                    e
+                ))
             })?;
 
         // Ініціалізація порту
         port.write_data_terminal_ready(true).map_err(|e| {
-            DeviceError::ConnectionError(format!("Помилка ініціалізації порту: {}", e))
+            DeviceError::ConnectionError(tr!(
+                "Failed to initialise port: {}",
+                "Помилка ініціалізації порту: {}",
+                e
+            ))
         })?;
 
-        info!("Підключено до пристрою: {}", port_name);
+        info!(
+            "{}",
+            tr!(
+                "Connected to device: {}",
+                "Підключено до пристрою: {}",
+                port_name
+            )
+        );
         Ok(port)
     }
 
-    /// Відправлення команди до пристрою і зчитування повної відповіді
+    /// Відправлення типізованої команди EBB і зчитування відповіді
+    ///
+    /// Перед відправленням перевіряються межі параметрів і те, що прошивка плати вже знає
+    /// команду. Форму відповіді, зокрема чи чекати "OK", задає сама команда.
+    ///
+    /// # Параметри:
+    /// - `command`: Команда, яку потрібно надіслати.
+    ///
+    /// # Повертає:
+    /// - `Result<String, DeviceError>`: Відповідь від пристрою без "OK" або помилку.
+    #[tracing::instrument(
+        target = "rsaxi::trace",
+        name = "command",
+        level = "trace",
+        skip_all,
+        fields(name = command.name(), detail = %command)
+    )]
+    pub fn send(&mut self, command: &Command) -> Result<String, DeviceError> {
+        command.validate()?;
+        if let Some(since) = command.since() {
+            self.require(command.name(), since)?;
+        }
+        if command.is_motion() {
+            self.reserve_fifo()?;
+        }
+        let response = self
+            .exchange(
+                &command.to_string(),
+                command.response(self.firmware),
+                command.is_query(),
+            )
+            .inspect_err(|_| self.forget_position(command.is_query()))?;
+        self.state.track(command);
+        self.track_fifo(command);
+        Ok(response)
+    }
+
+    /// Відправлення сирого рядка команди до пристрою і зчитування повної відповіді
     ///
     /// Цей метод відправляє команду через серійний порт і чекає на відповідь від пристрою.
     /// Якщо команда завершується успішно, пристрій надсилає підтвердження у вигляді "OK".
+    /// Для відомих команд краще використовувати `send`.
     ///
     /// # Параметри:
     /// - `cmd`: Команда, яку потрібно надіслати.
@@ -235,47 +927,112 @@ impl Device {
     /// # Повертає:
     /// - `Result<String, DeviceError>`: Відповідь від пристрою або помилку, якщо команда не виконується.
     pub fn command(&mut self, cmd: &str) -> Result<String, DeviceError> {
-        // Використовуємо closure замість вкладеної функції для зчитування буфера відповіді
-        let read_full_response = |port: &mut dyn SerialPort| -> Result<String, DeviceError> {
-            let mut response = Vec::new();
-            loop {
-                let mut buffer = [0; 256];
-                match port.read(&mut buffer) {
-                    Ok(bytes_read) => {
-                        if bytes_read == 0 {
-                            break;
-                        }
-                        response.extend_from_slice(&buffer[..bytes_read]);
-                    }
-                    Err(ref e) if e.kind() == std::io::ErrorKind::TimedOut => {
-                        break;
-                    }
-                    Err(e) => {
-                        return Err(DeviceError::CommandError {
-                            command: cmd.to_string(),
-                            message: format!("Помилка читання відповіді: {}", e),
-                        });
-                    }
-                }
+        let name = cmd.split(',').next().unwrap_or_default().trim();
+        let response = Response::for_name(name, self.firmware);
+        let parsed = Command::parse(cmd);
+        let query = parsed.as_ref().is_some_and(Command::is_query);
+        if parsed.as_ref().is_some_and(Command::is_motion) {
+            self.reserve_fifo()?;
+        }
+        let response = self
+            .exchange(cmd, response, query)
+            .inspect_err(|_| self.forget_position(query))?;
+        match parsed {
+            Some(command) => {
+                self.state.track(&command);
+                self.track_fifo(&command);
             }
-            let response_str = String::from_utf8_lossy(&response).to_string();
-            debug!("Отримано відповідь: {}", response_str);
-            Ok(response_str)
-        };
+            // Невідома команда могла зрушити каретку
+            None => self.state.position = None,
+        }
+        Ok(response)
+    }
 
+    /// Чекає, доки в черзі FIFO плати звільниться місце для ще однієї команди руху.
+    ///
+    /// Плата виконує одну команду й тримає в черзі ще `FirmwareVersion::fifo_depth`, а
+    /// команда, надіслана в повну чергу, псує попередню. Черга опитується через `QM`, а не
+    /// `QG`, щоб не скинути прапорець кнопки PRG. До прошивки 2.4.4 `QM` не повідомляє
+    /// стану черги, і вона вважається вільною, лише коли плата не виконує команду.
+    /// Якщо черга не звільняється за `SerialOptions::fifo_timeout`, плата вважається
+    /// завислою, і повертається `DeviceError::StillMoving`.
+    fn reserve_fifo(&mut self) -> Result<(), DeviceError> {
+        if self.in_flight <= self.firmware.fifo_depth() {
+            return Ok(());
+        }
+        self.stats.fifo_waits += 1;
+        let start = Instant::now();
+        loop {
+            let (motor1, motor2) = self.motor_status()?;
+            if motor1.fifo_empty {
+                let busy = motor1.executing_command || motor1.moving || motor2.moving;
+                self.in_flight = usize::from(busy);
+                return Ok(());
+            }
+            let waited = start.elapsed();
+            if waited >= self.serial.fifo_timeout {
+                return Err(DeviceError::StillMoving(waited));
+            }
+            std::thread::sleep(MOTOR_POLL);
+        }
+    }
+
+    /// Рахує команди руху, які плата ще виконує, після команди, яку вона щойно прийняла.
+    fn track_fifo(&mut self, command: &Command) {
+        match command {
+            Command::Es { .. } | Command::R | Command::Rb | Command::Bl => self.in_flight = 0,
+            motion if motion.is_motion() => self.in_flight += 1,
+            _ => {}
+        }
+    }
+
+    /// Скільки команд руху плата тримає в черзі FIFO за версією її прошивки.
+    pub fn fifo_depth(&self) -> usize {
+        self.firmware.fifo_depth()
+    }
+
+    /// Забуває позицію після невдалої команди, яку плата могла виконати, не відповівши.
+    fn forget_position(&mut self, query: bool) {
+        if !query {
+            self.state.position = None;
+        }
+    }
+
+    /// Повертає лічильники обміну з платою від підключення.
+    pub fn serial_stats(&self) -> SerialStats {
+        self.stats
+    }
+
+    /// Надсилає рядок команди та перевіряє відповідь на відповідність очікуваній формі.
+    ///
+    /// Запит, на який плата не відповіла вчасно, повторюється до `SerialOptions::retries`
+    /// разів із подвоєнням паузи. Команди руху й налаштування не повторюються: плата
+    /// могла виконати команду, втративши лише відповідь.
+    fn exchange(
+        &mut self,
+        cmd: &str,
+        expected: Response,
+        query: bool,
+    ) -> Result<String, DeviceError> {
         // Перевірка, чи команда порожня
         if cmd.is_empty() {
             return Err(DeviceError::CommandError {
                 command: cmd.to_string(),
-                message: "Команда не може бути порожньою".to_string(),
+                message: tr!(
+                    "Command must not be empty",
+                    "Команда не може бути порожньою"
+                ),
             });
         }
 
         // Перевірка, чи команда містить недопустимі символи
-        if !cmd.is_ascii() || cmd.contains(|c| c == '\n' || c == '\r') {
+        if !cmd.is_ascii() || cmd.contains(['\n', '\r']) {
             return Err(DeviceError::CommandError {
                 command: cmd.to_string(),
-                message: "Команда містить недозволені символи".to_string(),
+                message: tr!(
+                    "Command contains forbidden characters",
+                    "Команда містить недозволені символи"
+                ),
             });
         }
 
@@ -284,49 +1041,208 @@ impl Device {
         if full_cmd.len() > 256 {
             return Err(DeviceError::CommandError {
                 command: cmd.to_string(),
-                message: "Команда перевищує максимальну довжину 256 байт".to_string(),
+                message: tr!(
+                    "Command exceeds the maximum length of 256 bytes",
+                    "Команда перевищує максимальну довжину 256 байт"
+                ),
             });
         }
 
-        // Деякі команди не очікують "OK" у відповіді
-        let no_ok_commands = ["V", "I", "A", "MR", "PI", "QM"];
-
         // Перевірка стану підключення
-        if self.connected != true {
-            return Err(DeviceError::ConnectionError(
-                "Пристрій не підключений".to_string(),
-            ));
+        if !self.connected {
+            return Err(DeviceError::ConnectionError(tr!(
+                "Device is not connected",
+                "Пристрій не підключений"
+            )));
         }
 
-        debug!("Відправлення команди: {}", full_cmd.trim_end());
-        self.port
-            .write_all(full_cmd.as_bytes())
-            .map_err(|e| DeviceError::CommandError {
+        let mut backoff = self.serial.retry_backoff;
+        let mut retries = 0;
+        loop {
+            match self.transfer(cmd, &full_cmd, expected) {
+                Err(DeviceError::Timeout { .. }) if query && retries < self.serial.retries => {
+                    retries += 1;
+                    self.stats.retries += 1;
+                    warn!(
+                        "{}",
+                        tr!(
+                            "The board did not answer {}, retry {} of {} in {:?}.",
+                            "Плата не відповіла на {}, повтор {} з {} через {:?}.",
+                            cmd,
+                            retries,
+                            self.serial.retries,
+                            backoff
+                        )
+                    );
+                    std::thread::sleep(backoff);
+                    backoff *= 2;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Одна спроба обміну: записує команду порціями й зчитує відповідь.
+    fn transfer(
+        &mut self,
+        cmd: &str,
+        full_cmd: &str,
+        expected: Response,
+    ) -> Result<String, DeviceError> {
+        debug!(
+            "{}",
+            tr!(
+                "Sending command: {}",
+                "Відправлення команди: {}",
+                full_cmd.trim_end()
+            )
+        );
+        self.stats.commands += 1;
+        let wire_log = self.serial.wire_log;
+        if let Some(level) = wire_log.filter(|&level| level != WireLog::Raw) {
+            log_wire(level, true, full_cmd.trim_end().as_bytes());
+        }
+        for chunk in full_cmd.as_bytes().chunks(self.serial.write_chunk.max(1)) {
+            if wire_log == Some(WireLog::Raw) {
+                log_wire(WireLog::Raw, true, chunk);
+            }
+            self.port
+                .write_all(chunk)
+                .map_err(|e| DeviceError::CommandError {
+                    command: cmd.to_string(),
+                    message: tr!(
+                        "Failed to send command: {}",
+                        "Помилка відправлення команди: {}",
+                        e
+                    ),
+                })?;
+        }
+        self.port.flush().map_err(|e| DeviceError::CommandError {
+            command: cmd.to_string(),
+            message: tr!(
+                "Failed to send command: {}",
+                "Помилка відправлення команди: {}",
+                e
+            ),
+        })?;
+
+        let serial =
+            tracing::trace_span!(target: trace::TARGET, "serial", name = "response").entered();
+        let response = read_full_response(self.port.as_mut(), wire_log).map_err(|e| {
+            DeviceError::CommandError {
                 command: cmd.to_string(),
-                message: format!("Помилка відправлення команди: {}", e),
-            })?;
+                message: tr!(
+                    "Failed to read response: {}",
+                    "Помилка читання відповіді: {}",
+                    e
+                ),
+            }
+        })?;
+        drop(serial);
+        if wire_log == Some(WireLog::Responses) {
+            log_wire(WireLog::Responses, false, response.as_bytes());
+        }
+        self.log_exchange(full_cmd.trim_end(), &response);
 
-        let response = read_full_response(self.port.as_mut())?;
+        // Порожня або обірвана відповідь означає, що плата не встигла відповісти
+        if !response.ends_with('\n') {
+            self.stats.timeouts += 1;
+            return Err(DeviceError::Timeout {
+                command: cmd.to_string(),
+            });
+        }
 
-        // Якщо команда не входить до списку `no_ok_commands`, перевіряємо наявність "OK" у відповіді
-        if !no_ok_commands
-            .iter()
-            .any(|&c| cmd.to_uppercase().starts_with(c))
-        {
+        // Прошивка відповідає на хибну команду рядком на зразок "!8 Err: ..." замість OK
+        if response.trim_start().starts_with('!') {
+            return Err(DeviceError::Rejected {
+                command: cmd.to_string(),
+                reply: response.trim().to_string(),
+            });
+        }
+
+        // Якщо команда має завершуватися "OK", перевіряємо його наявність у відповіді
+        if expected.expects_ok() {
             if response.ends_with("OK\r\n") {
                 let trimmed_response = response.trim_end_matches("OK\r\n").to_string();
                 Ok(trimmed_response)
             } else {
-                return Err(DeviceError::CommandError {
+                Err(DeviceError::CommandError {
                     command: cmd.to_string(),
-                    message: "Відповідь не містить очікуваного OK".to_string(),
-                });
+                    message: tr!(
+                        "Response does not contain the expected OK",
+                        "Відповідь не містить очікуваного OK"
+                    ),
+                })
             }
         } else {
             Ok(response)
         }
     }
 
+    /// Записує команду та відповідь до журналу обміну, якщо він увімкнений.
+    ///
+    /// Помилка запису не зупиняє малювання: журнал вимикається з попередженням.
+    fn log_exchange(&mut self, command: &str, response: &str) {
+        let Some(transcript) = self.transcript.as_mut() else {
+            return;
+        };
+        if let Err(e) = transcript
+            .sent(command)
+            .and_then(|_| transcript.received(response))
+        {
+            warn!(
+                "{}",
+                tr!(
+                    "Failed to write the transcript, disabling it: {}",
+                    "Не вдалося записати журнал обміну, його вимкнено: {}",
+                    e
+                )
+            );
+            self.transcript = None;
+        }
+    }
+
+    /// Повторно надсилає команди руху та пера з журналу обміну.
+    ///
+    /// Запити й налаштування з журналу пропускаються: вони залежать від плати, на якій
+    /// журнал записали, і не впливають на малюнок.
+    ///
+    /// # Параметри:
+    /// - `commands`: Рядки команд, прочитані `transcript::read_commands`.
+    ///
+    /// # Повертає:
+    /// - `Result<usize, DeviceError>`: Кількість надісланих команд або помилка.
+    pub fn replay(&mut self, commands: &[String]) -> Result<usize, DeviceError> {
+        let mut sent = 0;
+        for line in commands {
+            match Command::parse(line) {
+                Some(command) if command.is_motion() => {
+                    self.send(&command)?;
+                    sent += 1;
+                }
+                Some(_) => {}
+                None => warn!(
+                    "{}",
+                    tr!(
+                        "Unknown command in transcript: {}",
+                        "Невідома команда в журналі: {}",
+                        line
+                    )
+                ),
+            }
+        }
+        self.wait_for_motors()?;
+        info!(
+            "{}",
+            tr!(
+                "Replayed {} commands from the transcript.",
+                "Відтворено {} команд з журналу.",
+                sent
+            )
+        );
+        Ok(sent)
+    }
+
     /// Відправлення команди ReBoot (RB) для перезавантаження пристрою
     ///
     /// Цей метод надсилає команду перезавантаження пристрою і очікує підтвердження успішного виконання.
@@ -334,11 +1250,39 @@ impl Device {
     /// # Повертає:
     /// - `Result<(), DeviceError>`: Повертає Ok або помилку, якщо перезавантаження не вдалося.
     pub fn reboot(&mut self) -> Result<(), DeviceError> {
-        self.command("RB").map(|_| {
-            info!("Пристрій перезавантажується...");
+        self.send(&Command::Rb).map(|_| {
+            info!(
+                "{}",
+                tr!("Device is rebooting...", "Пристрій перезавантажується...")
+            );
         })
     }
 
+    /// Перехід у завантажувач прошивки (BL) для її оновлення.
+    ///
+    /// Плата перезавантажується в завантажувач USB HID і зникає з серійного порту, тож
+    /// відповідь на команду може не надійти. Після команди пристрій вважається
+    /// від'єднаним, і закриття не надсилає йому жодних команд.
+    ///
+    /// # Повертає:
+    /// - `Result<(), DeviceError>`: Помилка, якщо команду не вдалося надіслати.
+    pub fn enter_bootloader(&mut self) -> Result<(), DeviceError> {
+        match self.send(&Command::Bl) {
+            // Якщо плата встигла зникнути з порту, читання відповіді обривається помилкою
+            Ok(_) | Err(DeviceError::Timeout { .. } | DeviceError::CommandError { .. }) => {}
+            Err(e) => return Err(e),
+        }
+        self.connected = false;
+        info!(
+            "{}",
+            tr!(
+                "The board entered the firmware bootloader.",
+                "Плата перейшла в завантажувач прошивки."
+            )
+        );
+        Ok(())
+    }
+
     ///
     /// Цей метод надсилає команду для скидання пристрою до його початкового стану. Після виконання команди пристрій
     /// повинен повернутися до базової конфігурації та бути готовим до прийняття нових команд.
@@ -346,8 +1290,8 @@ impl Device {
     /// # Повертає:
     /// - `Result<(, DeviceError>`: Повертає Ok або помилку в разі невдачі.
     pub fn reset(&mut self) -> Result<(), DeviceError> {
-        self.command("R").map(|_| {
-            info!("Пристрій скинуто.");
+        self.send(&Command::R).map(|_| {
+            info!("{}", tr!("Device reset.", "Пристрій скинуто."));
         })
     }
 
@@ -362,26 +1306,24 @@ impl Device {
     /// # Повертає:
     /// - `Result<(), DeviceError>`: Повертає Ok або помилку в разі, якщо псевдонім недійсний або команда не виконується.
     pub fn nickname(&mut self, nickname: &str) -> Result<(), DeviceError> {
-        if nickname.len() > 16 {
-            return Err(DeviceError::InvalidValue {
-                parameter: "nickname".to_string(),
-                value: nickname.to_string(),
-            });
-        }
-
-        if !nickname.is_ascii() {
-            return Err(DeviceError::InvalidValue {
-                parameter: "nickname".to_string(),
-                value: nickname.to_string(),
-            });
-        }
-
-        let cmd = format!("ST,{}", nickname);
-        self.command(&cmd).map(|_| {
-            info!("Псевдонім встановлено.");
+        let command = Command::St {
+            nickname: nickname.to_string(),
+        };
+        self.send(&command).map(|_| {
+            info!("{}", tr!("Nickname set.", "Псевдонім встановлено."));
         })
     }
 
+    /// Запит псевдоніму плати (QT)
+    ///
+    /// # Повертає:
+    /// - `Result<Option<String>, DeviceError>`: Псевдонім або `None`, якщо його не задано.
+    pub fn query_nickname(&mut self) -> Result<Option<String>, DeviceError> {
+        let response = self.send(&Command::Qt)?;
+        let nickname = response.trim();
+        Ok((!nickname.is_empty()).then(|| nickname.to_string()))
+    }
+
     /// Запит версії прошивки EBB
     ///
     /// Цей метод надсилає команду для запиту версії прошивки пристрою. Відповідь містить номер версії, який
@@ -390,8 +1332,15 @@ impl Device {
     /// # Повертає:
     /// - `Result<String, DeviceError>`: Повертає рядок із версією прошивки або помилку в разі невдачі.
     pub fn version(&mut self) -> Result<String, DeviceError> {
-        let response = self.command("V")?;
-        info!("Версія прошивки: {}", response.trim());
+        let response = self.send(&Command::V)?;
+        info!(
+            "{}",
+            tr!(
+                "Firmware version: {}",
+                "Версія прошивки: {}",
+                response.trim()
+            )
+        );
         Ok(response)
     }
 
@@ -410,29 +1359,18 @@ impl Device {
     /// # Обмеження:
     /// - Якщо передано недійсний порт або пін (порт не з A-E або пін поза діапазоном 0-7), метод повертає помилку.
     pub fn read_pin(&mut self, port: char, pin: u8) -> Result<bool, DeviceError> {
-        if !['A', 'B', 'C', 'D', 'E'].contains(&port) {
-            return Err(DeviceError::InvalidValue {
-                parameter: "port".to_string(),
-                value: port.to_string(),
-            });
-        }
-
-        if pin > 7 {
-            return Err(DeviceError::InvalidValue {
-                parameter: "pin".to_string(),
-                value: pin.to_string(),
-            });
-        }
-
-        let cmd = format!("PI,{},{}", port, pin);
-        let response = self.command(&cmd)?;
-        debug!("Відповідь на PI: {}", response.trim());
+        let response = self.send(&Command::Pi { port, pin })?;
+        debug!(
+            "{}",
+            tr!("PI response: {}", "Відповідь на PI: {}", response.trim())
+        );
 
-        let value = response.trim().split(',').last().unwrap_or("0").trim();
+        let value = response.trim().split(',').next_back().unwrap_or("0").trim();
         match value {
             "1" => Ok(true),
             "0" => Ok(false),
-            _ => Err(DeviceError::InvalidResponse(format!(
+            _ => Err(DeviceError::InvalidResponse(tr!(
+                "Invalid pin response: {}",
                 "Некоректна відповідь для піну: {}",
                 response
             ))),
@@ -456,46 +1394,82 @@ impl Device {
     /// - Порт має бути A-E, а пін повинен знаходитися в діапазоні від 0 до 7.
     /// - Напрямок має бути або 0 (вихід), або 1 (вхід).
     pub fn pin_direction(&mut self, port: char, pin: u8, direction: u8) -> Result<(), DeviceError> {
-        // Перевіряємо, чи передано дійсний порт
-        if !['A', 'B', 'C', 'D', 'E'].contains(&port) {
-            return Err(DeviceError::InvalidValue {
-                parameter: "port".to_string(),
-                value: port.to_string(),
-            });
-        }
-
-        // Перевіряємо, чи номер піну є в межах діапазону (0-7)
-        if pin > 7 {
-            return Err(DeviceError::InvalidValue {
-                parameter: "pin".to_string(),
-                value: pin.to_string(),
-            });
-        }
-
-        // Перевіряємо, чи напрямок є дійсним (0 - вихід, 1 - вхід)
-        if direction > 1 {
-            return Err(DeviceError::InvalidValue {
-                parameter: "direction".to_string(),
-                value: direction.to_string(),
-            });
-        }
+        self.send(&Command::Pd {
+            port,
+            pin,
+            direction,
+        })?;
+        Ok(())
+    }
 
-        // Формуємо команду "PD,Port,Pin,Direction"
-        let cmd = format!("PD,{}, {},{}", port, pin, direction);
+    /// Метод для керування імпульсним виходом RC-серво (S2)
+    ///
+    /// Команда ставиться в чергу рухів, тож вихід перемикається саме там, де закінчився
+    /// попередній рух. Нею керують аерографом або діодним лазером замість серво ручки.
+    ///
+    /// # Параметри:
+    /// - `pin`: Номер виходу RPn (0-24).
+    /// - `position`: Ширина імпульсу в одиницях 1/12 мкс; 0 вимикає вихід.
+    /// - `delay`: (опціонально) Затримка наступної команди в черзі після перемикання.
+    ///
+    /// # Повертає:
+    /// - `Result<(), DeviceError>`: Повертає Ok або помилку в разі невдачі.
+    pub fn pwm_output(
+        &mut self,
+        pin: u8,
+        position: u16,
+        delay: Option<Duration>,
+    ) -> Result<(), DeviceError> {
+        self.send(&Command::S2 {
+            position,
+            pin,
+            delay_ms: delay.map(millis),
+        })?;
+        Ok(())
+    }
 
-        self.command(&cmd)?;
+    /// Метод для встановлення рівня цифрового виходу (PO)
+    ///
+    /// Команда виконується одразу, не чекаючи рухів у черзі. Пін має бути налаштований
+    /// як вихід через `pin_direction`.
+    ///
+    /// # Параметри:
+    /// - `port`: Символ, що представляє порт (A, B, C, D, E).
+    /// - `pin`: Номер піну на порті (значення від 0 до 7).
+    /// - `high`: `true` для високого рівня, `false` для низького.
+    ///
+    /// # Повертає:
+    /// - `Result<(), DeviceError>`: Повертає Ok або помилку в разі невдачі.
+    pub fn pin_output(&mut self, port: char, pin: u8, high: bool) -> Result<(), DeviceError> {
+        self.send(&Command::Po {
+            port,
+            pin,
+            value: high as u8,
+        })?;
         Ok(())
     }
 
+    /// Змінює положення опущеної ручки й одразу передає його платі.
+    ///
+    /// Нове положення діє з наступного опускання ручки, тож між шляхами можна
+    /// пробувати різний натиск пера без повторного підключення.
+    ///
+    /// # Параметри:
+    /// - `position`: Положення ручки при опущеній ручці (0..100).
+    ///
+    /// # Повертає:
+    /// - `Result<(), DeviceError>`: Повертає Ok або помилку в разі невдачі.
+    pub fn set_pen_down_position(&mut self, position: i32) -> Result<(), DeviceError> {
+        self.pen_down_position = position;
+        self.configure()
+    }
+
     /// Метод для опускання ручки
     ///
     /// # Повертає:
     /// - `Result<(), DeviceError>`: Повертає Ok або помилку в разі невдачі.
     pub fn pen_down(&mut self) -> Result<(), DeviceError> {
-        let delta = (self.pen_up_position - self.pen_down_position).abs();
-        let duration = (1000 * delta) / self.pen_down_speed;
-        let delay = max(0, duration + self.pen_down_delay);
-        self.pen_state(0, Some(Duration::from_millis(delay as u64)), None)
+        self.move_pen(false)
     }
 
     /// Метод для підйому ручки
@@ -503,10 +1477,39 @@ impl Device {
     /// # Повертає:
     /// - `Result<(), DeviceError>`: Повертає Ok або помилку в разі невдачі.
     pub fn pen_up(&mut self) -> Result<(), DeviceError> {
-        let delta = (self.pen_up_position - self.pen_down_position).abs();
-        let duration = (1000 * delta) / self.pen_up_speed;
-        let delay = max(0, duration + self.pen_up_delay);
-        self.pen_state(1, Some(Duration::from_millis(delay as u64)), None)
+        self.move_pen(true)
+    }
+
+    /// Піднімає або опускає ручку та чекає, доки серво завершить рух і ручка заспокоїться.
+    ///
+    /// # Параметри:
+    /// - `up`: `true` для підйому, `false` для опускання ручки.
+    ///
+    /// # Повертає:
+    /// - `Result<(), DeviceError>`: Повертає Ok або помилку в разі невдачі.
+    fn move_pen(&mut self, up: bool) -> Result<(), DeviceError> {
+        let (speed, delay, value) = if up {
+            (self.pen_up_speed, self.pen_up_delay, 1)
+        } else {
+            (self.pen_down_speed, self.pen_down_delay, 0)
+        };
+        let movement = servo_move_duration(self.pen_up_position, self.pen_down_position, speed);
+        let duration = pen_delay(movement, delay, self.pen_settle);
+        debug!(
+            "{}",
+            tr!(
+                "Pen move {}: servo {:?}, delay {:?}",
+                "Рух ручки {}: серво {:?}, затримка {:?}",
+                if up {
+                    tr!("up", "вгору")
+                } else {
+                    tr!("down", "вниз")
+                },
+                movement,
+                duration
+            )
+        );
+        self.pen_state(value, duration, None)
     }
 
     /// Метод для перемикання стану ручки (TP)
@@ -519,25 +1522,9 @@ impl Device {
     /// # Повертає:
     /// - `Result<String, DeviceError>`: Повертає відповідь від пристрою або помилку в разі невдачі.
     pub fn pen_toggle(&mut self, duration: Option<Duration>) -> Result<String, DeviceError> {
-        let mut cmd = "TP".to_string();
-
-        if let Some(dur) = duration {
-            let duration_in_ms = dur.as_millis();
-            if duration_in_ms < 1 || duration_in_ms > 65535 {
-                return Err(DeviceError::InvalidValue {
-                    parameter: "duration".to_string(),
-                    value: duration_in_ms.to_string(),
-                });
-            }
-            cmd.push_str(&format!(",{}", duration_in_ms));
-        }
-
-        let response = self.command(&cmd)?;
-
-        // Перемикаємо поточний стан ручки
-        self.is_lowered = !self.is_lowered;
-
-        Ok(response)
+        self.send(&Command::Tp {
+            duration_ms: duration.map(millis),
+        })
     }
 
     /// Метод для встановлення стану ручки (SP)
@@ -558,60 +1545,11 @@ impl Device {
         duration: Option<Duration>, // (необов'язково) затримка
         portb_pin: Option<u8>,      // (необов'язково) номер піна на порту B (0-7)
     ) -> Result<(), DeviceError> {
-        // Перевіряємо, чи значення value є дійсним (0 або 1)
-        if value != 0 && value != 1 {
-            error!("Недійсне значення для value: {}", value);
-            return Err(DeviceError::InvalidValue {
-                parameter: "value".to_string(),
-                value: value.to_string(),
-            });
-        }
-
-        // Перевіряємо, чи значення portb_pin є дійсним (0-7), якщо воно задано
-        if let Some(pin) = portb_pin {
-            if pin > 7 {
-                error!("Недійсне значення для portb_pin: {}", pin);
-                return Err(DeviceError::InvalidValue {
-                    parameter: "portb_pin".to_string(),
-                    value: pin.to_string(),
-                });
-            }
-        }
-
-        // Формуємо команду для пристрою "SP,Value[,Duration[,PortB_Pin]]"
-        let mut cmd = format!("SP,{}", value);
-
-        // Додаємо Duration, якщо він заданий
-        if let Some(dur) = duration {
-            let duration_in_ms = dur.as_millis();
-            // Перевіряємо, чи Duration не перевищує 65535 мс
-            if duration_in_ms < 1 || duration_in_ms > u16::MAX.into() {
-                error!(
-                    "Тривалість повинна бути в діапазоні 1-65535 мс: {} мс",
-                    duration_in_ms
-                );
-                return Err(DeviceError::InvalidValue {
-                    parameter: "duration".to_string(),
-                    value: duration_in_ms.to_string(),
-                });
-            }
-            cmd.push_str(&format!(",{}", duration_in_ms));
-        } else {
-            // Якщо тривалість не вказана, використовується значення 0 мс за замовчуванням
-            cmd.push_str(",0");
-        }
-
-        // Додаємо PortB_Pin, якщо він заданий
-        if let Some(pin) = portb_pin {
-            cmd.push_str(&format!(",{}", pin));
-        }
-
-        // Надсилаємо команду
-        self.command(&cmd)?;
-
-        // Оновлюємо стан ручки після успішної команди
-        self.is_lowered = value == 0; // Ручка опущена, якщо value = 0, і піднята, якщо value = 1
-
+        self.send(&Command::Sp {
+            value,
+            duration_ms: duration.map(millis),
+            portb_pin,
+        })?;
         Ok(())
     }
 
@@ -622,16 +1560,29 @@ impl Device {
     /// # Повертає:
     /// - `Result<bool, DeviceError>`: Повертає `true`, якщо ручка опущена, і `false`, якщо піднята, або помилку в разі невдачі.
     pub fn query_pen_state(&mut self) -> Result<bool, DeviceError> {
-        let cmd = "QP"; // Команда для запиту стану
-        debug!("Відправлення команди: {}", cmd);
-
-        let response = self.command(cmd)?; // Відправляємо команду на пристрій
-        debug!("Отримано відповідь: {}", response.trim());
+        let response = self.send(&Command::Qp)?; // Відправляємо команду на пристрій
+        debug!(
+            "{}",
+            tr!(
+                "Received response: {}",
+                "Отримано відповідь: {}",
+                response.trim()
+            )
+        );
 
         // Парсимо відповідь, очікуємо 0 (опущена) або 1 (піднята)
         let pen_status = response.trim().chars().next().ok_or_else(|| {
-            error!("Некоректна відповідь від пристрою: порожня відповідь");
-            DeviceError::InvalidResponse("Порожня відповідь від пристрою".to_string())
+            error!(
+                "{}",
+                tr!(
+                    "Invalid response from device: empty response",
+                    "Некоректна відповідь від пристрою: порожня відповідь"
+                )
+            );
+            DeviceError::InvalidResponse(tr!(
+                "Empty response from device",
+                "Порожня відповідь від пристрою"
+            ))
         })?;
 
         // Повертаємо стан ручки на основі відповіді
@@ -639,8 +1590,16 @@ impl Device {
             '0' => Ok(true),  // Ручка опущена
             '1' => Ok(false), // Ручка піднята
             _ => {
-                error!("Невідома відповідь від пристрою: {}", response.trim());
-                Err(DeviceError::InvalidResponse(format!(
+                error!(
+                    "{}",
+                    tr!(
+                        "Unknown response from device: {}",
+                        "Невідома відповідь від пристрою: {}",
+                        response.trim()
+                    )
+                );
+                Err(DeviceError::InvalidResponse(tr!(
+                    "Unknown response from device: {}",
                     "Невідома відповідь від пристрою: {}",
                     response
                 )))
@@ -656,11 +1615,11 @@ impl Device {
     /// # Повертає:
     /// - `Result<(), DeviceError>`: Успішне виконання або помилка у разі невдачі.
     pub fn zero_position(&mut self) -> Result<(), DeviceError> {
-        let cmd = "CS"; // Команда для обнулення глобальних позицій
-        debug!("Відправлення команди: {}", cmd);
-
-        self.command(cmd)?; // Виконуємо команду на пристрої
-        info!("Команда CS виконана успішно");
+        self.send(&Command::Cs)?; // Виконуємо команду на пристрої
+        info!(
+            "{}",
+            tr!("CS command succeeded", "Команда CS виконана успішно")
+        );
 
         Ok(())
     }
@@ -685,6 +1644,7 @@ impl Device {
     ///
     /// # Обмеження:
     /// - Принаймні одна з осей повинна мати ненульову кількість кроків або ненульові швидкість/прискорення, інакше рух не відбудеться.
+    #[allow(clippy::too_many_arguments)]
     pub fn low_level_move(
         &mut self,
         rate1: u32,        // Початкова швидкість для осі 1
@@ -695,30 +1655,96 @@ impl Device {
         accel2: i32,       // Прискорення для осі 2
         clear: Option<u8>, // Очищення акумулятора: 0 - не очищати, 1 - очистити для осі 1, 2 - для осі 2, 3 - для обох
     ) -> Result<String, DeviceError> {
-        // Валідація значення параметра `clear`
-        if let Some(c) = clear {
-            if c > 3 {
-                return Err(DeviceError::InvalidValue {
-                    parameter: "clear".to_string(),
-                    value: c.to_string(),
-                });
-            }
-        }
-
-        // Формуємо команду "LM,Rate1,Steps1,Accel1,Rate2,Steps2,Accel2[,Clear]"
-        let clear_param = clear.map_or("".to_string(), |c| format!(",{}", c));
-        let cmd = format!(
-            "LM,{},{},{},{},{},{}{}",
-            rate1, steps1, accel1, rate2, steps2, accel2, clear_param
+        let response = self.send(&Command::Lm {
+            rate1,
+            steps1,
+            accel1,
+            rate2,
+            steps2,
+            accel2,
+            clear,
+        })?;
+        debug!(
+            "{}",
+            tr!(
+                "LM command succeeded: {}",
+                "Команда LM виконана успішно: {}",
+                response.trim()
+            )
         );
 
-        debug!("Відправлення команди: {}", cmd);
-        let response = self.command(&cmd)?;
-        info!("Команда LM виконана успішно: {}", response.trim());
+        Ok(response)
+    }
+
+    /// Виконує низькорівневу команду руху з ривком (L3) для керування осями.
+    ///
+    /// Ривок щокроку додається до прискорення, тож швидкість змінюється плавно, без
+    /// стрибків прискорення. `L3` з'явилася в прошивці 3.0; на старішій прошивці рух
+    /// без ривка надсилається як `LM`, а з ривком повертає помилку.
+    ///
+    /// # Параметри:
+    /// - `rate1`, `steps1`, `accel1`, `jerk1`: Початкова швидкість, кроки, прискорення та ривок для осі 1.
+    /// - `rate2`, `steps2`, `accel2`, `jerk2`: Те саме для осі 2.
+    /// - `clear`: (опціонально) Очищення акумуляторів, як у `low_level_move`.
+    ///
+    /// # Повертає:
+    /// - `Result<String, DeviceError>`: Повертає відповідь від пристрою або помилку в разі невдачі.
+    #[allow(clippy::too_many_arguments)]
+    pub fn low_level_move_jerk(
+        &mut self,
+        rate1: u32,
+        steps1: i32,
+        accel1: i32,
+        jerk1: i32,
+        rate2: u32,
+        steps2: i32,
+        accel2: i32,
+        jerk2: i32,
+        clear: Option<u8>,
+    ) -> Result<String, DeviceError> {
+        if !self.firmware.supports(FirmwareVersion::V3) && jerk1 == 0 && jerk2 == 0 {
+            return self.low_level_move(rate1, steps1, accel1, rate2, steps2, accel2, clear);
+        }
+        let response = self.send(&Command::L3 {
+            rate1,
+            steps1,
+            accel1,
+            jerk1,
+            rate2,
+            steps2,
+            accel2,
+            jerk2,
+            clear,
+        })?;
+        debug!(
+            "{}",
+            tr!(
+                "L3 command succeeded: {}",
+                "Команда L3 виконана успішно: {}",
+                response.trim()
+            )
+        );
 
         Ok(response)
     }
 
+    /// Перевіряє, що прошивка плати вже знає команду.
+    ///
+    /// # Параметри:
+    /// - `command`: Назва команди для повідомлення про помилку.
+    /// - `since`: Версія прошивки, в якій з'явилася команда.
+    fn require(&self, command: &'static str, since: FirmwareVersion) -> Result<(), DeviceError> {
+        if self.firmware.supports(since) {
+            Ok(())
+        } else {
+            Err(DeviceError::Unsupported {
+                command,
+                required: since,
+                version: self.firmware,
+            })
+        }
+    }
+
     /// Команда для повернення моторів до "домашньої" позиції або до абсолютної позиції (HM).
     ///
     /// Ця команда рухає мотори з поточної позиції до позиції (0, 0) або до нової абсолютної позиції,
@@ -737,40 +1763,15 @@ impl Device {
         position1: Option<i32>, // Абсолютна позиція для мотора 1 (опціонально)
         position2: Option<i32>, // Абсолютна позиція для мотора 2 (опціонально)
     ) -> Result<(), DeviceError> {
-        // Валідація параметра step_frequency
-        if step_frequency < 2 || step_frequency > 25000 {
-            return Err(DeviceError::InvalidValue {
-                parameter: "step_frequency".to_string(),
-                value: step_frequency.to_string(),
-            });
-        }
-
-        let mut cmd = format!("HM,{}", step_frequency);
-
-        // Валідація і додавання абсолютної позиції для мотора 1
-        if let Some(pos1) = position1 {
-            if pos1.abs() > 4_294_967 {
-                return Err(DeviceError::InvalidValue {
-                    parameter: "position1".to_string(),
-                    value: pos1.to_string(),
-                });
-            }
-            cmd.push_str(&format!(",{}", pos1));
-        }
-
-        // Валідація і додавання абсолютної позиції для мотора 2
-        if let Some(pos2) = position2 {
-            if pos2.abs() > 4_294_967 {
-                return Err(DeviceError::InvalidValue {
-                    parameter: "position2".to_string(),
-                    value: pos2.to_string(),
-                });
-            }
-            cmd.push_str(&format!(",{}", pos2));
-        }
-
-        self.command(&cmd)?;
-        info!("Команда HM виконана успішно");
+        self.send(&Command::Hm {
+            step_frequency,
+            position1,
+            position2,
+        })?;
+        info!(
+            "{}",
+            tr!("HM command succeeded", "Команда HM виконана успішно")
+        );
 
         Ok(())
     }
@@ -793,42 +1794,15 @@ impl Device {
         axis_steps1: i32,         // Кількість кроків для осі 1
         axis_steps2: Option<i32>, // Опціонально: кількість кроків для осі 2
     ) -> Result<(), DeviceError> {
-        // Конвертуємо тривалість у мілісекунди
-        let duration_ms = duration.as_millis();
-
-        // Перевіряємо діапазон значень для тривалості і кроків
-        if duration_ms < 1 || duration_ms > 16777215 {
-            return Err(DeviceError::InvalidValue {
-                parameter: "duration".to_string(),
-                value: duration_ms.to_string(),
-            });
-        }
-
-        if axis_steps1.abs() > 16777215 {
-            return Err(DeviceError::InvalidValue {
-                parameter: "axis_steps1".to_string(),
-                value: axis_steps1.to_string(),
-            });
-        }
-
-        if let Some(steps2) = axis_steps2 {
-            if steps2.abs() > 16777215 {
-                return Err(DeviceError::InvalidValue {
-                    parameter: "axis_steps2".to_string(),
-                    value: steps2.to_string(),
-                });
-            }
-        }
-
-        // Формуємо команду "SM,Duration,AxisSteps1[,AxisSteps2]"
-        let cmd = if let Some(steps2) = axis_steps2 {
-            format!("SM,{},{},{}", duration_ms, axis_steps1, steps2)
-        } else {
-            format!("SM,{},{}", duration_ms, axis_steps1)
-        };
-
-        self.command(&cmd)?;
-        debug!("Команда SM виконана успішно");
+        self.send(&Command::Sm {
+            ms: millis(duration),
+            steps1: axis_steps1,
+            steps2: axis_steps2,
+        })?;
+        debug!(
+            "{}",
+            tr!("SM command succeeded", "Команда SM виконана успішно")
+        );
 
         Ok(())
     }
@@ -855,34 +1829,29 @@ impl Device {
         axis_steps_a: i32, // Кількість кроків для осі A
         axis_steps_b: i32, // Кількість кроків для осі B
     ) -> Result<(), DeviceError> {
-        // Перевіряємо діапазон значень для тривалості
-        if step_ms < 1 || step_ms > 16777215 {
-            return Err(DeviceError::InvalidValue {
-                parameter: "step_ms".to_string(),
-                value: step_ms.to_string(),
-            });
-        }
-
-        // Перевіряємо діапазон значень для кроків осей A і B
-        if axis_steps_a.abs() > 16777215 {
-            return Err(DeviceError::InvalidValue {
-                parameter: "axis_steps_a".to_string(),
-                value: axis_steps_a.to_string(),
-            });
-        }
-
-        if axis_steps_b.abs() > 16777215 {
-            return Err(DeviceError::InvalidValue {
-                parameter: "axis_steps_b".to_string(),
-                value: axis_steps_b.to_string(),
-            });
+        let command = Command::Xm {
+            ms: step_ms,
+            steps_a: axis_steps_a,
+            steps_b: axis_steps_b,
+        };
+        command.validate()?;
+
+        // Стара прошивка не знає XM, тож розкладаємо рух на мотори самі й надсилаємо SM
+        if !self.firmware.supports(FirmwareVersion::MIXED_MOVE) {
+            let (steps1, steps2) =
+                kinematics::xy_to_motors(axis_steps_a as f64, axis_steps_b as f64);
+            return self.stepper_move(
+                Duration::from_millis(step_ms as u64),
+                steps1 as i32,
+                Some(steps2 as i32),
+            );
         }
 
-        // Формуємо команду "XM,Duration,AxisStepsA,AxisStepsB"
-        let cmd = format!("XM,{},{},{}", step_ms, axis_steps_a, axis_steps_b);
-
-        self.command(&cmd)?;
-        info!("Команда XM виконана успішно");
+        self.send(&command)?;
+        info!(
+            "{}",
+            tr!("XM command succeeded", "Команда XM виконана успішно")
+        );
 
         Ok(())
     }
@@ -902,41 +1871,66 @@ impl Device {
         motor2_enable: Option<bool>,
         step_mode: Option<StepMode>,
     ) -> Result<String, DeviceError> {
-        let m1_enable = motor1_enable.unwrap_or(self.motor1_enabled);
-        let m2_enable = motor2_enable.unwrap_or(self.motor2_enabled);
-        let mode = step_mode.unwrap_or(self.step_mode);
+        let m1_enable = motor1_enable.unwrap_or(self.state.motor1_enabled);
+        let m2_enable = motor2_enable.unwrap_or(self.state.motor2_enabled);
+        let mode = step_mode.unwrap_or(self.state.step_mode);
 
         let enable1 = if m1_enable { mode as u8 } else { 0 };
         let enable2 = if m2_enable { 1 } else { 0 }; // Enable2 не змінює step_mode
 
-        let cmd = format!("EM,{},{}", enable1, enable2);
-
-        let response = self.command(&cmd)?;
-        info!("Команда EM виконана: {}", response.trim());
-
-        self.motor1_enabled = m1_enable;
-        self.motor2_enabled = m2_enable;
-        self.step_mode = mode;
+        let response = self.send(&Command::Em { enable1, enable2 })?;
+        info!(
+            "{}",
+            tr!(
+                "EM command done: {}",
+                "Команда EM виконана: {}",
+                response.trim()
+            )
+        );
 
         Ok(response)
     }
 
-    /// Очікує завершення руху двигунів.
-    fn wait_for_motors(&mut self) -> Result<(), DeviceError> {
-        loop {
-            // Отримуємо статус моторів
-            let (motor1_status, motor2_status) = self.motor_status()?;
+    /// Очікує завершення руху двигунів без обмеження часу.
+    ///
+    /// # Повертає:
+    /// - `Result<(), DeviceError>`: Повертає Ok, коли обидва мотори зупинились, або помилку.
+    pub fn wait_for_motors(&mut self) -> Result<(), DeviceError> {
+        self.wait_until_idle(None)
+    }
 
-            // Якщо обидва мотори зупинилися, виходимо з циклу
-            if !motor1_status.moving && !motor2_status.moving {
-                break;
+    /// Очікує, доки плата виконає всі команди руху з черги FIFO і мотори зупиняться.
+    ///
+    /// Якщо прошивка знає `QG`, стан черги та моторів читається однією командою,
+    /// інакше плата опитується через `QM`. До прошивки 2.4.4 `QM` не повідомляє стану
+    /// черги, і вона вважається порожньою, коли плата не виконує команду.
+    ///
+    /// # Параметри:
+    /// - `timeout`: Найдовше очікування; `None` чекає без обмеження.
+    ///
+    /// # Повертає:
+    /// - `Result<(), DeviceError>`: Ok, коли плата вільна, `StillMoving`, якщо за `timeout`
+    ///   рух не завершився, або помилку обміну.
+    pub fn wait_until_idle(&mut self, timeout: Option<Duration>) -> Result<(), DeviceError> {
+        let start = Instant::now();
+        loop {
+            let idle = if self.has_general_query() {
+                self.general_query()?.is_idle()
+            } else {
+                let (motor1, motor2) = self.motor_status()?;
+                !(motor1.executing_command || motor1.moving || motor2.moving) && motor1.fifo_empty
+            };
+            if idle {
+                self.in_flight = 0;
+                return Ok(());
             }
 
-            // Додаємо невелику затримку перед наступною перевіркою
-            std::thread::sleep(std::time::Duration::from_millis(10));
+            let waited = start.elapsed();
+            if timeout.is_some_and(|timeout| waited >= timeout) {
+                return Err(DeviceError::StillMoving(waited));
+            }
+            std::thread::sleep(MOTOR_POLL);
         }
-
-        Ok(())
     }
 
     /// Вимкнення моторів.
@@ -960,36 +1954,121 @@ impl Device {
 
     /// Негайна зупинка моторів.
     ///
+    /// `ES` перериває рух посеред команди й скидає чергу FIFO, тож після зупинки позиція
+    /// одразу перечитується через `resync_position`.
+    ///
     /// # Параметри:
     /// - `disable_motors`: Вимкнути мотори після зупинки (true) або залишити увімкненими (false).
     ///
     /// # Повертає:
     /// - `Result<String, DeviceError>`: Повертає відповідь від пристрою або помилку.
     pub fn abort_motors(&mut self, disable_motors: bool) -> Result<String, DeviceError> {
-        let cmd = if disable_motors {
-            "ES,1".to_string()
-        } else {
-            "ES".to_string()
-        };
+        let response = self.send(&Command::Es { disable_motors })?;
+        info!(
+            "{}",
+            tr!(
+                "ES command done: {}",
+                "Команда ES виконана: {}",
+                response.trim()
+            )
+        );
+        self.resync_position()?;
 
-        debug!("Відправлення команди: {}", cmd);
-        let response = self.command(&cmd)?;
-        info!("Команда ES виконана: {}", response.trim());
+        Ok(response)
+    }
 
-        if disable_motors {
-            self.motor1_enabled = false;
-            self.motor2_enabled = false;
-            self.step_mode = StepMode::Disable;
-        }
+    /// Загальний запит стану плати (QG).
+    ///
+    /// Одна команда повертає стан черги FIFO, моторів, пера, кнопки PRG і живлення серво
+    /// замість окремих `QM`, `QP` та `QB`. Прапорець кнопки скидається після запиту.
+    ///
+    /// # Повертає:
+    /// - `Result<GeneralStatus, DeviceError>`: Стан плати або помилка.
+    pub fn general_query(&mut self) -> Result<GeneralStatus, DeviceError> {
+        let response = self.send(&Command::Qg)?;
+        let bits = response.trim().trim_start_matches("QG,");
+        u8::from_str_radix(bits, 16)
+            .map(GeneralStatus::from_bits)
+            .map_err(|_| {
+                DeviceError::InvalidResponse(tr!(
+                    "Invalid QG response: {}",
+                    "Некоректна відповідь від QG: {}",
+                    bits
+                ))
+            })
+    }
 
-        Ok(response)
+    /// Чи були обидва мотори ввімкнені ще до підключення.
+    ///
+    /// Увімкнені мотори тримають каретку, тож лічильники кроків плати (`QS`) досі
+    /// відповідають її положенню, наприклад після паркування попереднім запуском.
+    pub fn motors_were_enabled(&self) -> bool {
+        self.motors_on_connect
+    }
+
+    /// Лишає мотори ввімкненими після відключення, щоб каретка трималася на місці.
+    ///
+    /// # Параметри:
+    /// - `keep`: `true` — не вимикати мотори в `Drop`, `false` — вимикати, як зазвичай.
+    pub fn keep_motors_enabled(&mut self, keep: bool) {
+        self.keep_motors = keep;
+    }
+
+    /// Чи підтримує прошивка загальний запит стану `QG`.
+    pub fn has_general_query(&self) -> bool {
+        self.firmware
+            .supports(FirmwareVersion::HOME_AND_GENERAL_QUERY)
+    }
+
+    /// Перевіряє, чи натиснули кнопку PRG після попереднього запиту.
+    ///
+    /// # Повертає:
+    /// - `Result<bool, DeviceError>`: `true`, якщо кнопку натиснули; без `QG` завжди `false`.
+    pub fn button_pressed(&mut self) -> Result<bool, DeviceError> {
+        if !self.has_general_query() {
+            return Ok(false);
+        }
+        Ok(self.general_query()?.button_pressed)
     }
 
     /// Читання стану моторів і глобального режиму кроку.
     ///
+    /// Прошивка 2.8 і новіша повідомляє це однією командою `QE`, старіша — через
+    /// стани п'яти пінів драйверів.
+    ///
     /// # Повертає:
     /// - `Result<(bool, bool, StepMode), DeviceError>`: Повертає стан моторів 1 і 2, а також глобальний режим кроку.
     fn query_enable_motors(&mut self) -> Result<(bool, bool, StepMode), DeviceError> {
+        if self.firmware.supports(FirmwareVersion::MOTOR_QUERY) {
+            let response = self.send(&Command::Qe)?;
+            let modes: Vec<u32> = response
+                .trim()
+                .trim_start_matches("QE,")
+                .split(',')
+                .filter_map(|field| field.trim().parse().ok())
+                .collect();
+            let invalid = || {
+                DeviceError::InvalidResponse(tr!(
+                    "Invalid QE response: {}",
+                    "Некоректна відповідь від QE: {}",
+                    response.trim()
+                ))
+            };
+            let &[motor1, motor2] = modes.as_slice() else {
+                return Err(invalid());
+            };
+            let step_mode = match motor1.max(motor2) {
+                16 => StepMode::OneSixteenth,
+                8 => StepMode::OneEighth,
+                4 => StepMode::OneQuarter,
+                2 => StepMode::OneHalf,
+                1 => StepMode::FullStep,
+                0 => StepMode::Disable,
+                _ => return Err(invalid()),
+            };
+            return Ok((motor1 != 0, motor2 != 0, step_mode));
+        }
+
         let motor1_enabled = self.read_pin('E', 0)?;
         let motor2_enabled = self.read_pin('C', 1)?;
 
@@ -1004,10 +2083,17 @@ impl Device {
             (true, false, false) => StepMode::OneHalf,
             (false, false, false) => StepMode::FullStep,
             _ => {
-                error!("Некоректна комбінація станів MS пінів");
-                return Err(DeviceError::InvalidResponse(
-                    "Некоректна комбінація станів MS пінів".to_string(),
-                ));
+                error!(
+                    "{}",
+                    tr!(
+                        "Invalid combination of MS pin states",
+                        "Некоректна комбінація станів MS пінів"
+                    )
+                );
+                return Err(DeviceError::InvalidResponse(tr!(
+                    "Invalid combination of MS pin states",
+                    "Некоректна комбінація станів MS пінів"
+                )));
             }
         };
 
@@ -1019,18 +2105,33 @@ impl Device {
     /// # Повертає:
     /// - `Result<(MotorStatus, MotorStatus), DeviceError>`: Повертає статус моторів або помилку.
     pub fn motor_status(&mut self) -> Result<(MotorStatus, MotorStatus), DeviceError> {
-        let cmd = "QM";
-
-        let response = self.command(cmd)?;
-        debug!("Отримано відповідь: {}", response.trim());
+        let response = self.send(&Command::Qm)?;
+        debug!(
+            "{}",
+            tr!(
+                "Received response: {}",
+                "Отримано відповідь: {}",
+                response.trim()
+            )
+        );
 
         let parts: Vec<&str> = response.trim().split(',').collect();
 
-        if parts.len() == 5 && parts[0] == "QM" {
+        // Стан FIFO додано до відповіді QM у прошивці 2.4.4
+        let fields = if self.firmware.supports(FirmwareVersion::FIFO_STATUS) {
+            5
+        } else {
+            4
+        };
+
+        if parts.len() == fields && parts[0] == "QM" {
             let command_status = parts[1].trim().parse::<u8>().unwrap_or(0) != 0;
             let motor1_moving = parts[2].trim().parse::<u8>().unwrap_or(0) != 0;
             let motor2_moving = parts[3].trim().parse::<u8>().unwrap_or(0) != 0;
-            let fifo_empty = parts[4].trim().parse::<u8>().unwrap_or(1) == 0;
+            // Без стану FIFO вважаємо чергу порожньою, коли плата не виконує команду
+            let fifo_empty = parts.get(4).map_or(!command_status, |fifo| {
+                fifo.trim().parse::<u8>().unwrap_or(1) == 0
+            });
 
             let motor1_status = MotorStatus {
                 executing_command: command_status,
@@ -1046,10 +2147,18 @@ impl Device {
 
             Ok((motor1_status, motor2_status))
         } else {
-            error!("Некоректна відповідь від QM: {}", response.trim());
-            Err(DeviceError::InvalidResponse(
-                "Некоректна відповідь від QM".to_string(),
-            ))
+            error!(
+                "{}",
+                tr!(
+                    "Invalid QM response: {}",
+                    "Некоректна відповідь від QM: {}",
+                    response.trim()
+                )
+            );
+            Err(DeviceError::InvalidResponse(tr!(
+                "Invalid QM response",
+                "Некоректна відповідь від QM"
+            )))
         }
     }
 
@@ -1060,42 +2169,121 @@ impl Device {
     ///
     /// # Повертає:
     /// - `Result<(i32, i32), DeviceError>`: Повертає кортеж з двох значень (позиція мотора 1, позиція мотора 2),
-    /// або помилку в разі невдачі.
+    ///   або помилку в разі невдачі.
     pub fn read_position(&mut self) -> Result<(i32, i32), DeviceError> {
-        let cmd = "QS";
-        debug!("Відправлення команди: {}", cmd);
-
-        let response = self.command(cmd)?;
-        debug!("Отримано відповідь від QS: {}", response.trim());
+        let response = self.send(&Command::Qs)?;
+        debug!(
+            "{}",
+            tr!(
+                "Received QS response: {}",
+                "Отримано відповідь від QS: {}",
+                response.trim()
+            )
+        );
 
         let positions: Vec<&str> = response.trim().split(',').collect();
         if positions.len() != 2 {
-            return Err(DeviceError::InvalidResponse(
-                "Некоректна відповідь від QS: очікувалося два значення".to_string(),
-            ));
+            return Err(DeviceError::InvalidResponse(tr!(
+                "Invalid QS response: expected two values",
+                "Некоректна відповідь від QS: очікувалося два значення"
+            )));
         }
 
-        let motor1_position = positions[0].parse::<i32>().map_err(|_| {
-            DeviceError::InvalidResponse(format!(
+        let motor1_position = positions[0].trim().parse::<i32>().map_err(|_| {
+            DeviceError::InvalidResponse(tr!(
+                "Invalid value for motor 1 position: {}",
                 "Некоректне значення для позиції мотора 1: {}",
-                positions[0]
+                positions[0].trim()
             ))
         })?;
 
-        let motor2_position = positions[1].parse::<i32>().map_err(|_| {
-            DeviceError::InvalidResponse(format!(
+        let motor2_position = positions[1].trim().parse::<i32>().map_err(|_| {
+            DeviceError::InvalidResponse(tr!(
+                "Invalid value for motor 2 position: {}",
                 "Некоректне значення для позиції мотора 2: {}",
-                positions[1]
+                positions[1].trim()
             ))
         })?;
 
+        self.state.position = Some((motor1_position, motor2_position));
         Ok((motor1_position, motor2_position))
     }
 
+    /// Знімок останнього відомого стану плати без запитів до неї.
+    ///
+    /// Позиція в знімку `None`, якщо після її читання надсилалися команди руху.
+    pub fn state(&self) -> DeviceState {
+        self.state
+    }
+
+    /// Позиції моторів у кроках: збережені, якщо після них не було руху, інакше прочитані `QS`.
+    ///
+    /// # Повертає:
+    /// - `Result<(i32, i32), DeviceError>`: Позиції моторів 1 і 2 або помилку запиту.
+    pub fn position(&mut self) -> Result<(i32, i32), DeviceError> {
+        match self.state.position {
+            Some(position) => Ok(position),
+            None => self.read_position(),
+        }
+    }
+
+    /// Чекає зупинки моторів і перечитує їхні позиції (QS), переводячи їх у координати каретки.
+    ///
+    /// Після аварійної зупинки чи паузи плата могла виконати лише частину надісланих
+    /// команд, тож позицію треба брати з плати, а не з обліку надісланих кроків.
+    ///
+    /// # Повертає:
+    /// - `Result<(f64, f64), DeviceError>`: Позиція каретки (x, y) в мм або помилку запиту.
+    pub fn resync_position(&mut self) -> Result<(f64, f64), DeviceError> {
+        self.wait_for_motors()?;
+        let (motor1, motor2) = self.read_position()?;
+        let steps_per_unit = self.steps_per_unit.max(1) as f64;
+        let (x, y) = kinematics::motors_to_xy(
+            motor1 as f64 / steps_per_unit,
+            motor2 as f64 / steps_per_unit,
+        );
+        debug!(
+            "{}",
+            tr!(
+                "Carriage position resynchronized: ({:.3}, {:.3}) mm.",
+                "Позицію каретки перечитано: ({:.3}, {:.3}) мм.",
+                x,
+                y
+            )
+        );
+        Ok((x, y))
+    }
+
+    /// Чи опущене перо за останнім відомим станом.
+    pub fn is_lowered(&self) -> bool {
+        self.state.pen_lowered
+    }
+
+    /// Перечитує стан пера, моторів і позиції з плати, замінюючи збережений.
+    ///
+    /// Потрібен після подій поза програмою, наприклад натиснутої кнопки чи ручного
+    /// переміщення каретки, і перед рішеннями, яким потрібна свіжа позиція.
+    ///
+    /// # Повертає:
+    /// - `Result<DeviceState, DeviceError>`: Оновлений стан або помилку запиту.
+    pub fn sync_state(&mut self) -> Result<DeviceState, DeviceError> {
+        let pen_lowered = self.query_pen_state()?;
+        let (motor1_enabled, motor2_enabled, step_mode) = self.query_enable_motors()?;
+        let position = self.read_position()?;
+        self.state = DeviceState {
+            pen_lowered,
+            motor1_enabled,
+            motor2_enabled,
+            step_mode,
+            position: Some(position),
+        };
+        Ok(self.state)
+    }
+
     /// Відключення пристрою
     pub fn disconnect(&mut self) {
         if self.connected {
-            info!("Закриваємо порт...");
+            info!("{}", tr!("Closing port...", "Закриваємо порт..."));
             self.connected = false;
         }
     }
@@ -1104,12 +2292,119 @@ impl Device {
 /// Автоматичне відключення пристрою при його знищенні
 impl Drop for Device {
     fn drop(&mut self) {
-        // Вимикаємо мотори перед відключенням пристрою
-        self.wait_for_motors();
+        if !self.connected {
+            return;
+        }
+
+        // Вимикаємо мотори перед відключенням пристрою, якщо каретку не запарковано
+        if let Err(e) = self.wait_for_motors() {
+            error!(
+                "{}",
+                tr!(
+                    "Failed to wait for the motors to stop: {:?}",
+                    "Не вдалося дочекатися зупинки моторів: {:?}",
+                    e
+                )
+            );
+        }
+        if self.keep_motors {
+            self.disconnect();
+            info!(
+                "{}",
+                tr!(
+                    "Device disconnected, motors hold the carriage.",
+                    "Пристрій відключено, мотори тримають каретку."
+                )
+            );
+            return;
+        }
         if let Err(e) = self.disable_motors() {
-            error!("Не вдалося вимкнути мотори: {:?}", e);
+            error!(
+                "{}",
+                tr!(
+                    "Failed to disable the motors: {:?}",
+                    "Не вдалося вимкнути мотори: {:?}",
+                    e
+                )
+            );
         }
         self.disconnect();
-        info!("Пристрій відключено, мотори вимкнено.");
+        info!(
+            "{}",
+            tr!(
+                "Device disconnected, motors disabled.",
+                "Пристрій відключено, мотори вимкнено."
+            )
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ebb_detected_by_vid_pid_or_product() {
+        assert!(
+            is_ebb(0x04d8, 0xfd92, None),
+            "Windows може не повідомляти назву продукту"
+        );
+        assert!(is_ebb(0x1234, 0x5678, Some("EiBotBoard")));
+        assert!(!is_ebb(0x04d8, 0x000a, Some("USB Serial")));
+        assert!(!is_ebb(0x2341, 0x0043, None));
+    }
+
+    #[test]
+    fn test_servo_move_duration_follows_servo_rate() {
+        // 30% ходу = 6150 одиниць; швидкість 150 -> 750 одиниць за 24 мс -> 8.2 періоду
+        let duration = servo_move_duration(60, 30, 150);
+        assert_eq!(duration, Duration::from_millis(197));
+
+        // Малий хід не округлюється до нуля, як при цілочисельному діленні
+        let small = servo_move_duration(31, 30, 150);
+        assert_eq!(small, Duration::from_millis(7));
+
+        // Напрямок руху не впливає на тривалість, а нульова швидкість означає миттєвий рух
+        assert_eq!(servo_move_duration(30, 60, 150), duration);
+        assert_eq!(servo_move_duration(60, 30, 0), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_wire_log_levels_redact_traffic() {
+        let (command, reply) = (b"SM,100,10,10".as_slice(), b"OK\r\n".as_slice());
+        assert_eq!(
+            WireLog::Commands.entry(true, command).as_deref(),
+            Some("> SM,100,10,10")
+        );
+        assert_eq!(
+            WireLog::Commands.entry(false, reply),
+            None,
+            "Відповіді плати не пишуться"
+        );
+        assert_eq!(
+            WireLog::Responses.entry(false, reply).as_deref(),
+            Some("< OK\\r\\n")
+        );
+        assert_eq!(
+            WireLog::Raw.entry(false, reply).as_deref(),
+            Some("< 4f 4b 0d 0a")
+        );
+        assert_eq!(WireLog::Raw.entry(true, b"V\r").as_deref(), Some("> 56 0d"));
+    }
+
+    #[test]
+    fn test_pen_delay_adds_settle_and_clamps() {
+        let movement = Duration::from_millis(197);
+        assert_eq!(pen_delay(movement, 0, 50), Some(Duration::from_millis(247)));
+        assert_eq!(
+            pen_delay(movement, -100, 0),
+            Some(Duration::from_millis(97))
+        );
+        assert_eq!(pen_delay(movement, -500, 0), None);
+        assert_eq!(pen_delay(Duration::ZERO, 0, 0), None);
+        assert_eq!(
+            pen_delay(Duration::from_secs(120), 0, 0),
+            Some(Duration::from_millis(65535))
+        );
     }
 }
@@ -0,0 +1,695 @@
+use std::fmt;
+
+use super::firmware::FirmwareVersion;
+use super::DeviceError;
+
+/// Найбільша тривалість руху для `SM` і `XM` (в мс).
+const MAX_MOVE_MS: u32 = 16_777_215;
+
+/// Найбільша кількість кроків однієї осі для `SM` і `XM`.
+const MAX_MOVE_STEPS: i32 = 16_777_215;
+
+/// Найбільша абсолютна позиція мотора для `HM` (в кроках).
+const MAX_HOME_POSITION: i32 = 4_294_967;
+
+/// Найбільша тривалість затримки для `SP` і `TP` (в мс).
+const MAX_PEN_MS: u32 = 65_535;
+
+/// Найбільший номер виходу RPn для `S2`.
+const MAX_RP_PIN: u8 = 24;
+
+/// Команда протоколу EiBotBoard.
+///
+/// Кожна команда сама перевіряє межі своїх параметрів, записується в рядок протоколу
+/// через `Display`, знає, з якої версії прошивки вона доступна, і яку відповідь слід
+/// на неї чекати. Рядок, надісланий до плати, розбирається назад через `Command::parse`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Версія прошивки.
+    V,
+    /// Перезавантаження плати.
+    Rb,
+    /// Скидання плати до початкового стану.
+    R,
+    /// Перехід у завантажувач прошивки; плата від'єднується від серійного порту.
+    Bl,
+    /// Встановлення псевдоніму.
+    St { nickname: String },
+    /// Запит псевдоніму.
+    Qt,
+    /// Читання піну.
+    Pi { port: char, pin: u8 },
+    /// Напрямок піну: 0 — вихід, 1 — вхід.
+    Pd { port: char, pin: u8, direction: u8 },
+    /// Налаштування плати, наприклад позицій і швидкостей серво.
+    Sc { parameter: u8, value: i32 },
+    /// Стан пера: 0 — опустити, 1 — підняти, із затримкою після руху.
+    Sp {
+        value: u8,
+        duration_ms: Option<u32>,
+        portb_pin: Option<u8>,
+    },
+    /// Перемикання стану пера.
+    Tp { duration_ms: Option<u32> },
+    /// Імпульсний вихід RC-серво на піні RPn: ширина імпульсу 0 вимикає вихід.
+    S2 {
+        position: u16,
+        pin: u8,
+        delay_ms: Option<u32>,
+    },
+    /// Рівень цифрового виходу: 0 — низький, 1 — високий.
+    Po { port: char, pin: u8, value: u8 },
+    /// Запит стану пера.
+    Qp,
+    /// Обнулення глобальних позицій моторів.
+    Cs,
+    /// Рух з постійною швидкістю або затримка.
+    Sm {
+        ms: u32,
+        steps1: i32,
+        steps2: Option<i32>,
+    },
+    /// Рух для змішаної геометрії по осях A і B.
+    Xm { ms: u32, steps_a: i32, steps_b: i32 },
+    /// Низькорівневий рух з прискоренням.
+    Lm {
+        rate1: u32,
+        steps1: i32,
+        accel1: i32,
+        rate2: u32,
+        steps2: i32,
+        accel2: i32,
+        clear: Option<u8>,
+    },
+    /// Низькорівневий рух з прискоренням і ривком.
+    L3 {
+        rate1: u32,
+        steps1: i32,
+        accel1: i32,
+        jerk1: i32,
+        rate2: u32,
+        steps2: i32,
+        accel2: i32,
+        jerk2: i32,
+        clear: Option<u8>,
+    },
+    /// Рух додому або до абсолютної позиції.
+    Hm {
+        step_frequency: u32,
+        position1: Option<i32>,
+        position2: Option<i32>,
+    },
+    /// Увімкнення моторів: 0 вимикає мотор, 1–5 задає режим кроку.
+    Em { enable1: u8, enable2: u8 },
+    /// Негайна зупинка моторів.
+    Es { disable_motors: bool },
+    /// Загальний запит стану.
+    Qg,
+    /// Запит увімкнення та режиму кроку моторів.
+    Qe,
+    /// Запит стану руху.
+    Qm,
+    /// Запит глобальних позицій моторів.
+    Qs,
+}
+
+/// Форма відповіді плати на команду.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Response {
+    Ok,     // Лише підтвердження `OK`.
+    Data,   // Рядок з даними без `OK`.
+    DataOk, // Рядок з даними, а за ним `OK`.
+}
+
+impl Response {
+    /// Форма відповіді на команду з назвою `name` для прошивки `firmware`.
+    ///
+    /// Для сирих рядків команд, які не мають типу `Command`.
+    pub fn for_name(name: &str, firmware: FirmwareVersion) -> Self {
+        match name.to_uppercase().as_str() {
+            "V" | "I" | "A" | "MR" => Response::Data,
+            // З прошивки 3.0 запити PI, QM і QG теж завершуються OK
+            "PI" | "QM" | "QG" if !firmware.supports(FirmwareVersion::V3) => Response::Data,
+            "PI" | "QM" | "QG" | "QP" | "QS" | "QE" | "QT" | "ES" => Response::DataOk,
+            _ => Response::Ok,
+        }
+    }
+
+    /// Чи завершується відповідь підтвердженням `OK`.
+    pub fn expects_ok(&self) -> bool {
+        !matches!(self, Response::Data)
+    }
+
+    /// Формує відповідь плати з даних, як її надіслала б прошивка.
+    ///
+    /// # Аргументи
+    /// * `data` - дані відповіді; для `Response::Ok` ігноруються.
+    pub fn frame(&self, data: &str) -> String {
+        match self {
+            Response::Ok => "OK\r\n".to_string(),
+            Response::Data => format!("{}\r\n", data),
+            Response::DataOk => format!("{}\r\nOK\r\n", data),
+        }
+    }
+}
+
+impl Command {
+    /// Назва команди в протоколі.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Command::V => "V",
+            Command::Rb => "RB",
+            Command::R => "R",
+            Command::Bl => "BL",
+            Command::St { .. } => "ST",
+            Command::Qt => "QT",
+            Command::Pi { .. } => "PI",
+            Command::Pd { .. } => "PD",
+            Command::Sc { .. } => "SC",
+            Command::Sp { .. } => "SP",
+            Command::Tp { .. } => "TP",
+            Command::S2 { .. } => "S2",
+            Command::Po { .. } => "PO",
+            Command::Qp => "QP",
+            Command::Cs => "CS",
+            Command::Sm { .. } => "SM",
+            Command::Xm { .. } => "XM",
+            Command::Lm { .. } => "LM",
+            Command::L3 { .. } => "L3",
+            Command::Hm { .. } => "HM",
+            Command::Em { .. } => "EM",
+            Command::Es { .. } => "ES",
+            Command::Qg => "QG",
+            Command::Qe => "QE",
+            Command::Qm => "QM",
+            Command::Qs => "QS",
+        }
+    }
+
+    /// Версія прошивки, з якої плата приймає команду, або `None` для базових команд.
+    pub fn since(&self) -> Option<FirmwareVersion> {
+        match self {
+            Command::Xm { .. } => Some(FirmwareVersion::MIXED_MOVE),
+            Command::Hm { .. } | Command::Qg => Some(FirmwareVersion::HOME_AND_GENERAL_QUERY),
+            Command::Lm { .. } => Some(FirmwareVersion::LOW_LEVEL_MOVE),
+            Command::Qe => Some(FirmwareVersion::MOTOR_QUERY),
+            Command::St { .. } | Command::Qt => Some(FirmwareVersion::NICKNAME),
+            Command::L3 { .. } => Some(FirmwareVersion::V3),
+            _ => None,
+        }
+    }
+
+    /// Чи рухає команда каретку або перо.
+    pub fn is_motion(&self) -> bool {
+        matches!(
+            self,
+            Command::Sm { .. }
+                | Command::Xm { .. }
+                | Command::Lm { .. }
+                | Command::L3 { .. }
+                | Command::Hm { .. }
+                | Command::Sp { .. }
+                | Command::Tp { .. }
+                | Command::S2 { .. }
+        )
+    }
+
+    /// Чи лише запитує команда стан плати, тож її можна безпечно повторити.
+    pub fn is_query(&self) -> bool {
+        matches!(
+            self,
+            Command::V
+                | Command::Pi { .. }
+                | Command::Qp
+                | Command::Qg
+                | Command::Qe
+                | Command::Qm
+                | Command::Qs
+                | Command::Qt
+        )
+    }
+
+    /// Форма відповіді плати з прошивкою `firmware` на цю команду.
+    pub fn response(&self, firmware: FirmwareVersion) -> Response {
+        Response::for_name(self.name(), firmware)
+    }
+
+    /// Перевіряє межі параметрів команди.
+    ///
+    /// # Повертає
+    /// * `Result<(), DeviceError>` - `InvalidValue` з назвою першого параметра поза межами.
+    pub fn validate(&self) -> Result<(), DeviceError> {
+        match self {
+            Command::St { nickname } => check(
+                "nickname",
+                nickname,
+                nickname.len() <= 16 && nickname.is_ascii() && !nickname.contains([',', '\r']),
+            ),
+            Command::Pi { port, pin } => {
+                check("port", port, is_port(*port))?;
+                check("pin", pin, *pin <= 7)
+            }
+            Command::Pd {
+                port,
+                pin,
+                direction,
+            } => {
+                check("port", port, is_port(*port))?;
+                check("pin", pin, *pin <= 7)?;
+                check("direction", direction, *direction <= 1)
+            }
+            Command::Sp {
+                value,
+                duration_ms,
+                portb_pin,
+            } => {
+                check("value", value, *value <= 1)?;
+                check_pen_duration(*duration_ms)?;
+                check_option("portb_pin", *portb_pin, |pin| pin <= 7)
+            }
+            Command::Tp { duration_ms } => check_pen_duration(*duration_ms),
+            Command::S2 { pin, delay_ms, .. } => {
+                check("output_pin", pin, *pin <= MAX_RP_PIN)?;
+                check_pen_duration(*delay_ms)
+            }
+            Command::Po { port, pin, value } => {
+                check("port", port, is_port(*port))?;
+                check("pin", pin, *pin <= 7)?;
+                check("value", value, *value <= 1)
+            }
+            Command::Sm { ms, steps1, steps2 } => {
+                check("duration", ms, (1..=MAX_MOVE_MS).contains(ms))?;
+                check("axis_steps1", steps1, steps1.abs() <= MAX_MOVE_STEPS)?;
+                check_option("axis_steps2", *steps2, |steps| {
+                    steps.abs() <= MAX_MOVE_STEPS
+                })
+            }
+            Command::Xm {
+                ms,
+                steps_a,
+                steps_b,
+            } => {
+                check("step_ms", ms, (1..=MAX_MOVE_MS).contains(ms))?;
+                check("axis_steps_a", steps_a, steps_a.abs() <= MAX_MOVE_STEPS)?;
+                check("axis_steps_b", steps_b, steps_b.abs() <= MAX_MOVE_STEPS)
+            }
+            Command::Lm { clear, .. } | Command::L3 { clear, .. } => {
+                check_option("clear", *clear, |clear| clear <= 3)
+            }
+            Command::Hm {
+                step_frequency,
+                position1,
+                position2,
+            } => {
+                check(
+                    "step_frequency",
+                    step_frequency,
+                    (2..=25_000).contains(step_frequency),
+                )?;
+                check_option("position1", *position1, |position| {
+                    position.abs() <= MAX_HOME_POSITION
+                })?;
+                check_option("position2", *position2, |position| {
+                    position.abs() <= MAX_HOME_POSITION
+                })
+            }
+            Command::Em { enable1, enable2 } => {
+                check("enable1", enable1, *enable1 <= 5)?;
+                check("enable2", enable2, *enable2 <= 5)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Розбирає рядок команди, як його надсилає `Display`.
+    ///
+    /// # Аргументи
+    /// * `line` - команда без завершального `\r`, наприклад `SM,100,40,20`.
+    ///
+    /// # Повертає
+    /// * `Option<Command>` - команда або `None`, якщо назва невідома чи бракує обов'язкових полів.
+    pub fn parse(line: &str) -> Option<Self> {
+        let fields: Vec<&str> = line.trim().split(',').map(str::trim).collect();
+        let name = fields[0].to_uppercase();
+        // Поле, що є в рядку, має розбиратися; відсутнє поле дає `None`
+        let optional = |index: usize| -> Option<Option<i64>> {
+            match fields.get(index) {
+                Some(field) => field.parse().ok().map(Some),
+                None => Some(None),
+            }
+        };
+        let number = |index: usize| -> Option<i64> { optional(index)? };
+        let port = || fields.get(1)?.chars().next();
+
+        let command = match name.as_str() {
+            "V" => Command::V,
+            "RB" => Command::Rb,
+            "R" => Command::R,
+            "BL" => Command::Bl,
+            "ST" => Command::St {
+                nickname: fields.get(1).unwrap_or(&"").to_string(),
+            },
+            "QT" => Command::Qt,
+            "PI" => Command::Pi {
+                port: port()?,
+                pin: number(2)?.try_into().ok()?,
+            },
+            "PD" => Command::Pd {
+                port: port()?,
+                pin: number(2)?.try_into().ok()?,
+                direction: number(3)?.try_into().ok()?,
+            },
+            "SC" => Command::Sc {
+                parameter: number(1)?.try_into().ok()?,
+                value: number(2)?.try_into().ok()?,
+            },
+            "SP" => Command::Sp {
+                value: number(1)?.try_into().ok()?,
+                duration_ms: optional(2)?.map(u32::try_from).transpose().ok()?,
+                portb_pin: optional(3)?.map(u8::try_from).transpose().ok()?,
+            },
+            "TP" => Command::Tp {
+                duration_ms: optional(1)?.map(u32::try_from).transpose().ok()?,
+            },
+            // Швидкість зміни імпульсу (поле 3) не використовується і не зберігається
+            "S2" => Command::S2 {
+                position: number(1)?.try_into().ok()?,
+                pin: number(2)?.try_into().ok()?,
+                delay_ms: optional(4)?.map(u32::try_from).transpose().ok()?,
+            },
+            "PO" => Command::Po {
+                port: port()?,
+                pin: number(2)?.try_into().ok()?,
+                value: number(3)?.try_into().ok()?,
+            },
+            "QP" => Command::Qp,
+            "CS" => Command::Cs,
+            "SM" => Command::Sm {
+                ms: number(1)?.try_into().ok()?,
+                steps1: number(2)?.try_into().ok()?,
+                steps2: optional(3)?.map(i32::try_from).transpose().ok()?,
+            },
+            "XM" => Command::Xm {
+                ms: number(1)?.try_into().ok()?,
+                steps_a: number(2)?.try_into().ok()?,
+                steps_b: number(3)?.try_into().ok()?,
+            },
+            "LM" => Command::Lm {
+                rate1: number(1)?.try_into().ok()?,
+                steps1: number(2)?.try_into().ok()?,
+                accel1: number(3)?.try_into().ok()?,
+                rate2: number(4)?.try_into().ok()?,
+                steps2: number(5)?.try_into().ok()?,
+                accel2: number(6)?.try_into().ok()?,
+                clear: optional(7)?.map(u8::try_from).transpose().ok()?,
+            },
+            "L3" => Command::L3 {
+                rate1: number(1)?.try_into().ok()?,
+                steps1: number(2)?.try_into().ok()?,
+                accel1: number(3)?.try_into().ok()?,
+                jerk1: number(4)?.try_into().ok()?,
+                rate2: number(5)?.try_into().ok()?,
+                steps2: number(6)?.try_into().ok()?,
+                accel2: number(7)?.try_into().ok()?,
+                jerk2: number(8)?.try_into().ok()?,
+                clear: optional(9)?.map(u8::try_from).transpose().ok()?,
+            },
+            "HM" => Command::Hm {
+                step_frequency: number(1)?.try_into().ok()?,
+                position1: optional(2)?.map(i32::try_from).transpose().ok()?,
+                position2: optional(3)?.map(i32::try_from).transpose().ok()?,
+            },
+            "EM" => Command::Em {
+                enable1: number(1)?.try_into().ok()?,
+                enable2: optional(2)?.unwrap_or(0).try_into().ok()?,
+            },
+            "ES" => Command::Es {
+                disable_motors: optional(1)? == Some(1),
+            },
+            "QG" => Command::Qg,
+            "QE" => Command::Qe,
+            "QM" => Command::Qm,
+            "QS" => Command::Qs,
+            _ => return None,
+        };
+        Some(command)
+    }
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())?;
+        // Необов'язкові поля записуються лише тоді, коли вони задані
+        let optional = |f: &mut fmt::Formatter<'_>, value: Option<i64>| match value {
+            Some(value) => write!(f, ",{}", value),
+            None => Ok(()),
+        };
+
+        match self {
+            Command::St { nickname } => write!(f, ",{}", nickname),
+            Command::Pi { port, pin } => write!(f, ",{},{}", port, pin),
+            Command::Pd {
+                port,
+                pin,
+                direction,
+            } => write!(f, ",{},{},{}", port, pin, direction),
+            Command::Sc { parameter, value } => write!(f, ",{},{}", parameter, value),
+            Command::Sp {
+                value,
+                duration_ms,
+                portb_pin,
+            } => {
+                // Тривалість 0 означає затримку за замовчуванням прошивки
+                write!(f, ",{},{}", value, duration_ms.unwrap_or(0))?;
+                optional(f, portb_pin.map(i64::from))
+            }
+            Command::Tp { duration_ms } => optional(f, duration_ms.map(i64::from)),
+            Command::S2 {
+                position,
+                pin,
+                delay_ms,
+            } => {
+                write!(f, ",{},{}", position, pin)?;
+                // Затримка йде після швидкості, а швидкість 0 означає миттєву зміну
+                match delay_ms {
+                    Some(delay) => write!(f, ",0,{}", delay),
+                    None => Ok(()),
+                }
+            }
+            Command::Po { port, pin, value } => write!(f, ",{},{},{}", port, pin, value),
+            Command::Sm { ms, steps1, steps2 } => {
+                write!(f, ",{},{}", ms, steps1)?;
+                optional(f, steps2.map(i64::from))
+            }
+            Command::Xm {
+                ms,
+                steps_a,
+                steps_b,
+            } => write!(f, ",{},{},{}", ms, steps_a, steps_b),
+            Command::Lm {
+                rate1,
+                steps1,
+                accel1,
+                rate2,
+                steps2,
+                accel2,
+                clear,
+            } => {
+                write!(
+                    f,
+                    ",{},{},{},{},{},{}",
+                    rate1, steps1, accel1, rate2, steps2, accel2
+                )?;
+                optional(f, clear.map(i64::from))
+            }
+            Command::L3 {
+                rate1,
+                steps1,
+                accel1,
+                jerk1,
+                rate2,
+                steps2,
+                accel2,
+                jerk2,
+                clear,
+            } => {
+                write!(
+                    f,
+                    ",{},{},{},{},{},{},{},{}",
+                    rate1, steps1, accel1, jerk1, rate2, steps2, accel2, jerk2
+                )?;
+                optional(f, clear.map(i64::from))
+            }
+            Command::Hm {
+                step_frequency,
+                position1,
+                position2,
+            } => {
+                write!(f, ",{}", step_frequency)?;
+                optional(f, position1.map(i64::from))?;
+                optional(f, position2.map(i64::from))
+            }
+            Command::Em { enable1, enable2 } => write!(f, ",{},{}", enable1, enable2),
+            Command::Es { disable_motors } => optional(f, disable_motors.then_some(1)),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn is_port(port: char) -> bool {
+    ('A'..='E').contains(&port)
+}
+
+fn check(parameter: &str, value: &impl ToString, valid: bool) -> Result<(), DeviceError> {
+    if valid {
+        Ok(())
+    } else {
+        Err(DeviceError::InvalidValue {
+            parameter: parameter.to_string(),
+            value: value.to_string(),
+        })
+    }
+}
+
+fn check_option<T: ToString + Copy>(
+    parameter: &str,
+    value: Option<T>,
+    valid: impl Fn(T) -> bool,
+) -> Result<(), DeviceError> {
+    match value {
+        Some(value) => check(parameter, &value, valid(value)),
+        None => Ok(()),
+    }
+}
+
+fn check_pen_duration(duration_ms: Option<u32>) -> Result<(), DeviceError> {
+    check_option("duration", duration_ms, |ms| (1..=MAX_PEN_MS).contains(&ms))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commands_round_trip_and_validate() {
+        let commands = [
+            Command::Sm {
+                ms: 100,
+                steps1: 40,
+                steps2: Some(-20),
+            },
+            Command::Sm {
+                ms: 5,
+                steps1: 0,
+                steps2: None,
+            },
+            Command::Sp {
+                value: 1,
+                duration_ms: Some(250),
+                portb_pin: Some(3),
+            },
+            Command::L3 {
+                rate1: 100,
+                steps1: 10,
+                accel1: 0,
+                jerk1: 5,
+                rate2: 100,
+                steps2: -10,
+                accel2: 0,
+                jerk2: 5,
+                clear: Some(3),
+            },
+            Command::Hm {
+                step_frequency: 1000,
+                position1: Some(-5),
+                position2: None,
+            },
+            Command::Es {
+                disable_motors: true,
+            },
+            Command::S2 {
+                position: 32_768,
+                pin: 5,
+                delay_ms: Some(40),
+            },
+            Command::Po {
+                port: 'B',
+                pin: 3,
+                value: 1,
+            },
+            Command::Qg,
+        ];
+        for command in commands {
+            let line = command.to_string();
+            assert_eq!(
+                Command::parse(&line),
+                Some(command.clone()),
+                "Рядок {} має розбиратися назад у ту саму команду",
+                line
+            );
+            assert!(command.validate().is_ok(), "{} у межах", line);
+        }
+
+        assert_eq!(
+            Command::Sp {
+                value: 0,
+                duration_ms: None,
+                portb_pin: None
+            }
+            .to_string(),
+            "SP,0,0"
+        );
+        assert_eq!(Command::parse("XM,10"), None, "Бракує кроків осей");
+        assert_eq!(Command::parse("ZZ,1"), None);
+
+        let too_long = Command::Xm {
+            ms: 0,
+            steps_a: 1,
+            steps_b: 1,
+        };
+        assert!(matches!(
+            too_long.validate(),
+            Err(DeviceError::InvalidValue { parameter, .. }) if parameter == "step_ms"
+        ));
+        let bad_pin = Command::Pi { port: 'F', pin: 1 };
+        assert!(bad_pin.validate().is_err());
+        let bad_output = Command::S2 {
+            position: 0,
+            pin: 25,
+            delay_ms: None,
+        };
+        assert!(bad_output.validate().is_err());
+    }
+
+    #[test]
+    fn test_response_shape_follows_firmware() {
+        let v2 = FirmwareVersion::new(2, 8, 1);
+        let v3 = FirmwareVersion::new(3, 0, 2);
+
+        assert_eq!(Command::Qm.response(v2), Response::Data);
+        assert_eq!(Command::Qm.response(v3), Response::DataOk);
+        assert_eq!(Command::V.response(v3), Response::Data);
+        assert_eq!(Command::Qs.response(v2), Response::DataOk);
+        assert_eq!(Command::Cs.response(v2), Response::Ok);
+        assert_eq!(Response::for_name("qg", v2), Response::Data);
+        assert_eq!(Command::Qt.response(v2), Response::DataOk);
+
+        assert_eq!(Response::DataOk.frame("1,2"), "1,2\r\nOK\r\n");
+        assert_eq!(Response::Ok.frame("ігнорується"), "OK\r\n");
+        assert_eq!(
+            Command::L3 {
+                rate1: 0,
+                steps1: 0,
+                accel1: 0,
+                jerk1: 0,
+                rate2: 0,
+                steps2: 0,
+                accel2: 0,
+                jerk2: 0,
+                clear: None
+            }
+            .since(),
+            Some(FirmwareVersion::V3)
+        );
+    }
+}
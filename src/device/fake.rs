@@ -0,0 +1,411 @@
+use std::collections::VecDeque;
+use std::io::{self, Read, Write};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serialport::{ClearBuffer, DataBits, FlowControl, Parity, SerialPort, StopBits};
+
+use super::firmware::FirmwareVersion;
+
+/// Очікуваний запит і відповідь на нього порціями, як їх віддає порт.
+#[derive(Debug, Clone)]
+struct Exchange {
+    request: String,      // Команда без `\r`; `*` у кінці означає будь-яке продовження.
+    chunks: Vec<Vec<u8>>, // Порції відповіді: кожне читання з порту повертає одну.
+}
+
+impl Exchange {
+    fn matches(&self, command: &str) -> bool {
+        match self.request.strip_suffix('*') {
+            Some(prefix) => command.starts_with(prefix),
+            None => command == self.request,
+        }
+    }
+}
+
+/// Сценарій обміну: запити, які має надіслати `Device`, і відповіді плати на них.
+///
+/// Сценарій спільний для тесту й порту, тож після обміну можна перевірити, що всі
+/// очікувані запити надіслано.
+#[derive(Debug, Clone, Default)]
+pub struct Script {
+    exchanges: Arc<Mutex<VecDeque<Exchange>>>,
+}
+
+impl Script {
+    pub fn new() -> Self {
+        Script::default()
+    }
+
+    /// Додає очікуваний запит і відповідь на нього.
+    ///
+    /// # Аргументи
+    /// * `request` - команда без `\r`; `*` у кінці дозволяє будь-які параметри.
+    /// * `chunks` - порції відповіді; без порцій плата мовчить до тайм-ауту.
+    pub fn expect(self, request: &str, chunks: &[&str]) -> Self {
+        self.lock().push_back(Exchange {
+            request: request.to_string(),
+            chunks: chunks
+                .iter()
+                .map(|chunk| chunk.as_bytes().to_vec())
+                .collect(),
+        });
+        self
+    }
+
+    /// Додає обмін під час підключення `Device::with_port`.
+    ///
+    /// Перо підняте, позиція (0, 0), а мотори вже ввімкнені в режимі 1/16 кроку, тож
+    /// команда `EM` не надсилається.
+    pub fn handshake(self, firmware: FirmwareVersion) -> Self {
+        let version = format!("EBBv13_and_above EB Firmware Version {}\r\n", firmware);
+        let mut script = self.expect("V", &[&version]);
+        for _ in 0..4 {
+            script = script.expect("SC,*", &["OK\r\n"]);
+        }
+        script = script.expect("QP", &["1\r\nOK\r\n"]);
+        if firmware.supports(FirmwareVersion::MOTOR_QUERY) {
+            script = script.expect("QE", &["16,16\r\nOK\r\n"]);
+        } else {
+            for _ in 0..5 {
+                script = script.expect("PI,*", &["PI,1\r\n"]);
+            }
+        }
+        script.expect("QS", &["0,0\r\nOK\r\n"])
+    }
+
+    /// Запити, які ще не надіслано.
+    pub fn remaining(&self) -> Vec<String> {
+        self.lock()
+            .iter()
+            .map(|exchange| exchange.request.clone())
+            .collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<Exchange>> {
+        self.exchanges.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Серійний порт, що відповідає за сценарієм, а не імітує плату.
+///
+/// На відміну від `MockPort`, дозволяє подати будь-яку відповідь: порціями, без OK, з
+/// помилкою прошивки чи зовсім без неї. Неочікуваний запит зупиняє тест.
+pub struct FakeSerialPort {
+    script: Script,
+    input: Vec<u8>,             // Байти команди до `\r`.
+    pending: VecDeque<Vec<u8>>, // Порції відповіді, ще не прочитані.
+    timeout: Duration,
+}
+
+impl FakeSerialPort {
+    pub fn new(script: Script) -> Self {
+        FakeSerialPort {
+            script,
+            input: vec![],
+            pending: VecDeque::new(),
+            timeout: Duration::from_millis(100),
+        }
+    }
+}
+
+impl Read for FakeSerialPort {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let Some(mut chunk) = self.pending.pop_front() else {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "timeout"));
+        };
+        let count = chunk.len().min(buf.len());
+        buf[..count].copy_from_slice(&chunk[..count]);
+        if count < chunk.len() {
+            self.pending.push_front(chunk.split_off(count));
+        }
+        Ok(count)
+    }
+}
+
+impl Write for FakeSerialPort {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for &byte in buf {
+            if byte != b'\r' {
+                self.input.push(byte);
+                continue;
+            }
+            let command = String::from_utf8_lossy(&self.input).to_string();
+            self.input.clear();
+            let exchange = self
+                .script
+                .lock()
+                .pop_front()
+                .unwrap_or_else(|| panic!("Неочікуваний запит після сценарію: {}", command));
+            assert!(
+                exchange.matches(&command),
+                "Очікувався запит {}, надіслано {}",
+                exchange.request,
+                command
+            );
+            // Відповідь на попередній запит, якої ніхто не дочитав, губиться
+            self.pending = exchange.chunks.into();
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl SerialPort for FakeSerialPort {
+    fn name(&self) -> Option<String> {
+        Some("fake".to_string())
+    }
+
+    fn baud_rate(&self) -> serialport::Result<u32> {
+        Ok(115200)
+    }
+
+    fn data_bits(&self) -> serialport::Result<DataBits> {
+        Ok(DataBits::Eight)
+    }
+
+    fn flow_control(&self) -> serialport::Result<FlowControl> {
+        Ok(FlowControl::None)
+    }
+
+    fn parity(&self) -> serialport::Result<Parity> {
+        Ok(Parity::None)
+    }
+
+    fn stop_bits(&self) -> serialport::Result<StopBits> {
+        Ok(StopBits::One)
+    }
+
+    fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    fn set_baud_rate(&mut self, _baud_rate: u32) -> serialport::Result<()> {
+        Ok(())
+    }
+
+    fn set_data_bits(&mut self, _data_bits: DataBits) -> serialport::Result<()> {
+        Ok(())
+    }
+
+    fn set_flow_control(&mut self, _flow_control: FlowControl) -> serialport::Result<()> {
+        Ok(())
+    }
+
+    fn set_parity(&mut self, _parity: Parity) -> serialport::Result<()> {
+        Ok(())
+    }
+
+    fn set_stop_bits(&mut self, _stop_bits: StopBits) -> serialport::Result<()> {
+        Ok(())
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> serialport::Result<()> {
+        self.timeout = timeout;
+        Ok(())
+    }
+
+    fn write_request_to_send(&mut self, _level: bool) -> serialport::Result<()> {
+        Ok(())
+    }
+
+    fn write_data_terminal_ready(&mut self, _level: bool) -> serialport::Result<()> {
+        Ok(())
+    }
+
+    fn read_clear_to_send(&mut self) -> serialport::Result<bool> {
+        Ok(true)
+    }
+
+    fn read_data_set_ready(&mut self) -> serialport::Result<bool> {
+        Ok(true)
+    }
+
+    fn read_ring_indicator(&mut self) -> serialport::Result<bool> {
+        Ok(false)
+    }
+
+    fn read_carrier_detect(&mut self) -> serialport::Result<bool> {
+        Ok(true)
+    }
+
+    fn bytes_to_read(&self) -> serialport::Result<u32> {
+        Ok(self.pending.iter().map(Vec::len).sum::<usize>() as u32)
+    }
+
+    fn bytes_to_write(&self) -> serialport::Result<u32> {
+        Ok(0)
+    }
+
+    fn clear(&self, _buffer_to_clear: ClearBuffer) -> serialport::Result<()> {
+        Ok(())
+    }
+
+    fn try_clone(&self) -> serialport::Result<Box<dyn SerialPort>> {
+        Err(serialport::Error::new(
+            serialport::ErrorKind::Unknown,
+            "The fake port cannot be cloned",
+        ))
+    }
+
+    fn set_break(&self) -> serialport::Result<()> {
+        Ok(())
+    }
+
+    fn clear_break(&self) -> serialport::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::{Device, DeviceError, DeviceOptions, SerialOptions, StepMode};
+
+    fn options() -> DeviceOptions {
+        DeviceOptions {
+            steps_per_unit: 80,
+            pen_up_position: 60,
+            pen_up_speed: 150,
+            pen_up_delay: 0,
+            pen_down_position: 30,
+            pen_down_speed: 150,
+            pen_down_delay: 0,
+            pen_settle: 0,
+            step_mode: StepMode::OneSixteenth,
+            port_name: None,
+            port_config: None,
+            transcript: None,
+            serial: SerialOptions {
+                retry_backoff: Duration::ZERO,
+                ..SerialOptions::default()
+            },
+        }
+    }
+
+    /// Підключає `Device` за сценарієм, до якого спершу додається обмін підключення.
+    fn connect(firmware: FirmwareVersion, script: Script) -> (Device, Script) {
+        let full = Script::new().handshake(firmware);
+        full.lock().extend(script.lock().drain(..));
+        let port = FakeSerialPort::new(full.clone());
+        let device =
+            Device::with_port(Box::new(port), options()).expect("Пристрій має підключитися");
+        (device, full)
+    }
+
+    #[test]
+    fn test_chunked_replies_and_errors() {
+        let script = Script::new()
+            .expect("QS", &["12", ",-3\r\nO", "K\r\n"])
+            .expect("QS", &[" 7 , 8 \r\nOK\r\n"])
+            .expect("SC,4,*", &["\r\n"])
+            .expect("SC,4,*", &["!8 Err: Unknown command 'SC'\r\n"])
+            .expect("SM,*", &["OK"])
+            .expect("QS", &["5,5\r\nOK\r\n"]);
+        let (mut device, script) = connect(FirmwareVersion::new(3, 0, 2), script);
+
+        // Відповідь, розірвана між читаннями, склеюється до тайм-ауту
+        assert_eq!(device.read_position().unwrap(), (12, -3));
+        assert_eq!(device.read_position().unwrap(), (7, 8));
+        assert!(matches!(
+            device.command("SC,4,16000"),
+            Err(DeviceError::CommandError { .. })
+        ));
+        match device.command("SC,4,16000") {
+            Err(DeviceError::Rejected { command, reply }) => {
+                assert_eq!(command, "SC,4,16000");
+                assert_eq!(reply, "!8 Err: Unknown command 'SC'");
+            }
+            other => panic!("Очікувалася відмова плати, отримано {:?}", other),
+        }
+        // Обірвана відповідь на команду руху не повторюється
+        assert!(matches!(
+            device.command("SM,100,10,10"),
+            Err(DeviceError::Timeout { .. })
+        ));
+        assert_eq!(device.serial_stats().retries, 0);
+        assert_eq!(device.state().position, None);
+        assert_eq!(device.position().unwrap(), (5, 5));
+        device.disconnect();
+        assert!(script.remaining().is_empty());
+    }
+
+    #[test]
+    fn test_query_timeouts_are_retried() {
+        let script = Script::new()
+            .expect("QS", &[])
+            .expect("QS", &["1,"])
+            .expect("QS", &["1,2\r\nOK\r\n"])
+            .expect("QP", &[])
+            .expect("QP", &[])
+            .expect("QP", &[])
+            .expect("QP", &[]);
+        let (mut device, script) = connect(FirmwareVersion::new(3, 0, 2), script);
+
+        assert_eq!(device.read_position().unwrap(), (1, 2));
+        assert_eq!(device.serial_stats().retries, 2);
+        assert!(matches!(
+            device.query_pen_state(),
+            Err(DeviceError::Timeout { command }) if command == "QP"
+        ));
+        let stats = device.serial_stats();
+        assert_eq!((stats.retries, stats.timeouts), (5, 6));
+        device.disconnect();
+        assert!(script.remaining().is_empty());
+    }
+
+    #[test]
+    fn test_status_parsers() {
+        // Прошивка 2.2: QM без стану FIFO і без OK, стан моторів із пінів
+        let old = Script::new()
+            .expect("QM", &["QM,1,0,1\r\n"])
+            .expect("QM", &["QM,1,0,1,0\r\n"]);
+        let (mut device, script) = connect(FirmwareVersion::new(2, 2, 0), old);
+        let (motor1, motor2) = device.motor_status().unwrap();
+        assert!(motor1.executing_command && !motor1.fifo_empty);
+        assert!(!motor1.moving && motor2.moving);
+        assert!(matches!(
+            device.motor_status(),
+            Err(DeviceError::InvalidResponse(_))
+        ));
+        device.disconnect();
+        assert!(script.remaining().is_empty());
+
+        let new = Script::new()
+            .expect("QM", &["QM,0,0,0,1\r\nOK\r\n"])
+            .expect("QM", &["QM,0,0,0,0\r\nOK\r\n"])
+            .expect("QM", &["QX,0,0,0,0\r\nOK\r\n"])
+            .expect("QP", &["0\r\nOK\r\n"])
+            .expect("QP", &["1\r\nOK\r\n"])
+            .expect("QP", &["2\r\nOK\r\n"])
+            .expect("QP", &["\r\nOK\r\n"])
+            .expect("QS", &["1,2,3\r\nOK\r\n"])
+            .expect("QS", &["1,x\r\nOK\r\n"])
+            .expect("QS", &["-2147483648,2147483647\r\nOK\r\n"]);
+        let (mut device, script) = connect(FirmwareVersion::new(3, 0, 2), new);
+        let (motor1, _) = device.motor_status().unwrap();
+        assert!(!motor1.executing_command && !motor1.fifo_empty);
+        assert!(device.motor_status().unwrap().0.fifo_empty);
+        assert!(device.motor_status().is_err());
+
+        assert!(device.query_pen_state().unwrap(), "0 означає опущене перо");
+        assert!(!device.query_pen_state().unwrap());
+        assert!(matches!(
+            device.query_pen_state(),
+            Err(DeviceError::InvalidResponse(_))
+        ));
+        assert!(device.query_pen_state().is_err());
+
+        assert!(matches!(
+            device.read_position(),
+            Err(DeviceError::InvalidResponse(_))
+        ));
+        assert!(device.read_position().is_err());
+        assert_eq!(device.read_position().unwrap(), (i32::MIN, i32::MAX));
+        device.disconnect();
+        assert!(script.remaining().is_empty());
+    }
+}
@@ -0,0 +1,120 @@
+use std::fmt;
+
+/// Версія прошивки EBB у вигляді основна.додаткова.виправлення.
+///
+/// Від версії залежить, які команди приймає плата та в якому форматі вона відповідає,
+/// тож `Device` зчитує її під час підключення й перевіряє перед побудовою команд.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FirmwareVersion {
+    pub major: u32, // Основна версія.
+    pub minor: u32, // Додаткова версія.
+    pub patch: u32, // Версія виправлень.
+}
+
+impl FirmwareVersion {
+    /// Найстаріша версія, яку припускаємо, якщо відповідь `V` не вдалося розібрати.
+    pub const MINIMUM: FirmwareVersion = FirmwareVersion::new(2, 0, 0);
+
+    /// Версія, з якої `XM` приймає рух для змішаної геометрії.
+    pub const MIXED_MOVE: FirmwareVersion = FirmwareVersion::new(2, 3, 0);
+
+    /// Версія, з якої `QM` повідомляє стан черги команд (FIFO).
+    pub const FIFO_STATUS: FirmwareVersion = FirmwareVersion::new(2, 4, 4);
+
+    /// Версія, з якої плата зберігає псевдонім (`ST`) і повідомляє його (`QT`).
+    pub const NICKNAME: FirmwareVersion = FirmwareVersion::new(2, 5, 5);
+
+    /// Версія, з якої з'явилися `HM` та загальний запит стану `QG`.
+    pub const HOME_AND_GENERAL_QUERY: FirmwareVersion = FirmwareVersion::new(2, 6, 2);
+
+    /// Версія, з якої `LM` приймає прискорення та очищення акумуляторів.
+    pub const LOW_LEVEL_MOVE: FirmwareVersion = FirmwareVersion::new(2, 7, 0);
+
+    /// Версія, з якої `QE` повідомляє, чи увімкнені мотори та їхній режим кроку.
+    pub const MOTOR_QUERY: FirmwareVersion = FirmwareVersion::new(2, 8, 0);
+
+    /// Версія 3.x: рух `L3` з ривком, а запити `PI` і `QM` завершуються `OK`.
+    pub const V3: FirmwareVersion = FirmwareVersion::new(3, 0, 0);
+
+    /// Створює версію з окремих чисел.
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        FirmwareVersion {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Розбирає відповідь на команду `V`.
+    ///
+    /// # Аргументи
+    /// * `response` - відповідь плати, наприклад `EBBv13_and_above EB Firmware Version 2.8.1`.
+    ///
+    /// # Повертає
+    /// * `Option<FirmwareVersion>` - версія або `None`, якщо у відповіді її немає.
+    pub fn parse(response: &str) -> Option<Self> {
+        let text = response
+            .rsplit_once("Version")
+            .map_or(response, |(_, version)| version);
+        let token = text
+            .split_whitespace()
+            .find(|token| token.starts_with(|c: char| c.is_ascii_digit()))?;
+
+        let mut numbers = token.split('.').map(|part| {
+            let digits: String = part.chars().take_while(char::is_ascii_digit).collect();
+            digits.parse::<u32>().ok()
+        });
+        let major = numbers.next()??;
+        let minor = numbers.next().flatten().unwrap_or(0);
+        let patch = numbers.next().flatten().unwrap_or(0);
+        Some(FirmwareVersion::new(major, minor, patch))
+    }
+
+    /// Скільки команд руху плата тримає в черзі FIFO, поки виконує поточну.
+    ///
+    /// До прошивки 3.0 черга вміщує одну команду, а наступна, надіслана зарано,
+    /// перезаписує її; прошивка 3.x тримає в черзі три команди.
+    pub fn fifo_depth(&self) -> usize {
+        match self.supports(FirmwareVersion::V3) {
+            true => 3,
+            false => 1,
+        }
+    }
+
+    /// Чи підтримує прошивка цю можливість, що з'явилася у версії `since`.
+    pub fn supports(&self, since: FirmwareVersion) -> bool {
+        *self >= since
+    }
+}
+
+impl fmt::Display for FirmwareVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_compare_versions() {
+        let v2 = FirmwareVersion::parse("EBBv13_and_above EB Firmware Version 2.8.1\r\n");
+        assert_eq!(v2, Some(FirmwareVersion::new(2, 8, 1)));
+        let v3 = FirmwareVersion::parse("EBBv13_and_above EB Firmware Version 3.0.2 (mock)");
+        assert_eq!(v3, Some(FirmwareVersion::new(3, 0, 2)));
+        assert_eq!(
+            FirmwareVersion::parse("Version 2.5"),
+            Some(FirmwareVersion::new(2, 5, 0))
+        );
+        assert_eq!(FirmwareVersion::parse("EBB без версії"), None);
+
+        let v2 = v2.unwrap();
+        assert!(v2.supports(FirmwareVersion::LOW_LEVEL_MOVE));
+        assert!(!v2.supports(FirmwareVersion::V3), "L3 з'явився лише в 3.0");
+        assert!(FirmwareVersion::new(2, 10, 0) > FirmwareVersion::new(2, 9, 9));
+        assert_eq!(v2.to_string(), "2.8.1");
+        assert_eq!(v2.fifo_depth(), 1);
+        assert_eq!(FirmwareVersion::new(3, 0, 2).fifo_depth(), 3);
+    }
+}
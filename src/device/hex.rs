@@ -0,0 +1,229 @@
+use thiserror::Error;
+
+use super::firmware::FirmwareVersion;
+use crate::tr;
+
+/// Помилки розбору образу прошивки у форматі Intel HEX.
+#[derive(Debug, Error, PartialEq)]
+pub enum HexError {
+    /// Рядок не є записом Intel HEX.
+    #[error(
+        "{}",
+        tr!(
+            "Line {} is not an Intel HEX record: {}",
+            "Рядок {} не є записом Intel HEX: {}",
+            .line,
+            .message
+        )
+    )]
+    Record { line: usize, message: String },
+
+    /// Контрольна сума запису не збігається.
+    #[error(
+        "{}",
+        tr!(
+            "Line {} has checksum {:02X}, expected {:02X}",
+            "Рядок {} має контрольну суму {:02X}, очікувалася {:02X}",
+            .line,
+            .actual,
+            .expected
+        )
+    )]
+    Checksum {
+        line: usize,
+        expected: u8,
+        actual: u8,
+    },
+
+    /// Файл обірвався до запису кінця файлу.
+    #[error(
+        "{}",
+        tr!(
+            "The image has no end-of-file record; the file may be truncated",
+            "Образ не має запису кінця файлу; можливо, файл обрізаний"
+        )
+    )]
+    MissingEnd,
+
+    /// Образ не містить жодного байта даних.
+    #[error("{}", tr!("The image contains no data", "Образ не містить даних"))]
+    Empty,
+}
+
+/// Образ прошивки EBB, прочитаний з файлу Intel HEX.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FirmwareImage {
+    pub segments: Vec<(u32, Vec<u8>)>, // Неперервні ділянки даних: адреса початку та байти.
+}
+
+impl FirmwareImage {
+    /// Розбирає образ у форматі Intel HEX і перевіряє контрольні суми всіх записів.
+    ///
+    /// Записи розширеної адреси (02 і 04) зсувають наступні дані, записи адреси запуску
+    /// (03 і 05) ігноруються, а все після запису кінця файлу відкидається.
+    ///
+    /// # Аргументи
+    /// * `content` - текст файлу `.hex`.
+    ///
+    /// # Повертає
+    /// * `Result<FirmwareImage, HexError>` - образ або помилка першого неправильного запису.
+    pub fn parse(content: &str) -> Result<FirmwareImage, HexError> {
+        let mut segments: Vec<(u32, Vec<u8>)> = vec![];
+        let mut base = 0u32;
+        for (index, text) in content.lines().enumerate() {
+            let line = index + 1;
+            let text = text.trim();
+            if text.is_empty() {
+                continue;
+            }
+            let record = |message: String| HexError::Record { line, message };
+            let digits = text
+                .strip_prefix(':')
+                .ok_or_else(|| record(tr!("missing ':'", "бракує ':'")))?;
+            if digits.len() % 2 != 0 || digits.len() < 10 {
+                return Err(record(tr!("wrong length", "неправильна довжина")));
+            }
+            let bytes = (0..digits.len())
+                .step_by(2)
+                .map(|at| u8::from_str_radix(&digits[at..at + 2], 16))
+                .collect::<Result<Vec<u8>, _>>()
+                .map_err(|_| record(tr!("not a hex digit", "не шістнадцяткова цифра")))?;
+            let count = bytes[0] as usize;
+            if bytes.len() != count + 5 {
+                return Err(record(tr!("wrong length", "неправильна довжина")));
+            }
+            let (body, checksum) = bytes.split_at(bytes.len() - 1);
+            let expected = body
+                .iter()
+                .fold(0u8, |sum, byte| sum.wrapping_add(*byte))
+                .wrapping_neg();
+            if checksum[0] != expected {
+                return Err(HexError::Checksum {
+                    line,
+                    expected,
+                    actual: checksum[0],
+                });
+            }
+
+            let offset = u16::from_be_bytes([bytes[1], bytes[2]]) as u32;
+            let data = &bytes[4..4 + count];
+            let address = |data: &[u8]| match data {
+                [high, low] => Ok(u16::from_be_bytes([*high, *low]) as u32),
+                _ => Err(record(tr!(
+                    "wrong address size",
+                    "неправильний розмір адреси"
+                ))),
+            };
+            match bytes[3] {
+                0x00 => {
+                    let start = base.wrapping_add(offset);
+                    match segments.last_mut() {
+                        Some((at, bytes)) if *at + bytes.len() as u32 == start => {
+                            bytes.extend_from_slice(data)
+                        }
+                        _ => segments.push((start, data.to_vec())),
+                    }
+                }
+                0x01 => return Self::finish(segments),
+                0x02 => base = address(data)? << 4,
+                0x04 => base = address(data)? << 16,
+                0x03 | 0x05 => {}
+                kind => {
+                    return Err(record(tr!(
+                        "unknown record type {:02X}",
+                        "невідомий тип запису {:02X}",
+                        kind
+                    )))
+                }
+            }
+        }
+        Err(HexError::MissingEnd)
+    }
+
+    /// Впорядковує ділянки за адресою й перевіряє, що образ не порожній.
+    fn finish(mut segments: Vec<(u32, Vec<u8>)>) -> Result<FirmwareImage, HexError> {
+        segments.retain(|(_, bytes)| !bytes.is_empty());
+        if segments.is_empty() {
+            return Err(HexError::Empty);
+        }
+        segments.sort_by_key(|(address, _)| *address);
+        Ok(FirmwareImage { segments })
+    }
+
+    /// Кількість байтів даних в образі.
+    pub fn size(&self) -> usize {
+        self.segments.iter().map(|(_, bytes)| bytes.len()).sum()
+    }
+
+    /// Версія прошивки з рядка `Firmware Version`, який EBB зберігає у флеш-пам'яті.
+    ///
+    /// # Повертає
+    /// * `Option<FirmwareVersion>` - версія, яку повідомлятиме `V` після прошивки, або `None`.
+    pub fn version(&self) -> Option<FirmwareVersion> {
+        const MARKER: &[u8] = b"Firmware Version ";
+        self.segments.iter().find_map(|(_, bytes)| {
+            let at = bytes
+                .windows(MARKER.len())
+                .position(|window| window == MARKER)?;
+            let tail: String = bytes[at..]
+                .iter()
+                .take_while(|byte| byte.is_ascii_graphic() || **byte == b' ')
+                .map(|byte| *byte as char)
+                .collect();
+            FirmwareVersion::parse(&tail)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Запис Intel HEX з правильною контрольною сумою.
+    fn record(kind: u8, offset: u16, data: &[u8]) -> String {
+        let mut bytes = vec![data.len() as u8, (offset >> 8) as u8, offset as u8, kind];
+        bytes.extend_from_slice(data);
+        let sum = bytes.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+        bytes.push(sum.wrapping_neg());
+        let digits: String = bytes.iter().map(|byte| format!("{:02X}", byte)).collect();
+        format!(":{}", digits)
+    }
+
+    #[test]
+    fn test_image_parses_records_and_finds_version() {
+        // Відомий запис з документації формату
+        let image = FirmwareImage::parse(":0300300002337A1E\n:00000001FF\n").unwrap();
+        assert_eq!(image.segments, [(0x30, vec![0x02, 0x33, 0x7A])]);
+
+        let version = b"EBBv13_and_above EB Firmware Version 3.0.2\0";
+        let content = [
+            record(0x04, 0, &[0x00, 0x01]),
+            record(0x00, 0x0000, &version[..16]),
+            record(0x00, 0x0010, &version[16..]),
+            record(0x00, 0x2000, &[0xAA; 4]),
+            record(0x01, 0, &[]),
+            "сміття після кінця файлу".to_string(),
+        ]
+        .join("\r\n");
+        let image = FirmwareImage::parse(&content).unwrap();
+        assert_eq!(image.segments.len(), 2, "Послідовні записи зливаються");
+        assert_eq!(image.segments[0].0, 0x1_0000);
+        assert_eq!(image.size(), version.len() + 4);
+        assert_eq!(image.version(), Some(FirmwareVersion::new(3, 0, 2)));
+
+        let broken = content.replacen(":10", ":11", 1);
+        assert!(matches!(
+            FirmwareImage::parse(&broken),
+            Err(HexError::Record { line: 2, .. })
+        ));
+        assert!(matches!(
+            FirmwareImage::parse(":0300300002337A1F\n:00000001FF\n"),
+            Err(HexError::Checksum { line: 1, .. })
+        ));
+        assert_eq!(
+            FirmwareImage::parse(":0300300002337A1E\n"),
+            Err(HexError::MissingEnd)
+        );
+        assert_eq!(FirmwareImage::parse(":00000001FF\n"), Err(HexError::Empty));
+    }
+}
@@ -0,0 +1,992 @@
+use std::io::{self, Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serialport::{ClearBuffer, DataBits, FlowControl, Parity, SerialPort, StopBits};
+
+use super::ebb::{Command, Response};
+use super::firmware::FirmwareVersion;
+use crate::motion::emitter::{axis_ticks, TICK_RATE};
+use crate::motion::kinematics;
+use crate::tr;
+
+/// Версія прошивки, яку типово повідомляє імітований пристрій.
+const MOCK_VERSION: FirmwareVersion = FirmwareVersion::new(3, 0, 2);
+
+/// Запис журналу імітованого пристрою: одна команда EBB.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceEvent {
+    pub time: Duration,       // Момент початку виконання команди.
+    pub duration: Duration,   // Тривалість виконання команди.
+    pub command: String,      // Команда без завершального `\r`.
+    pub position: (f64, f64), // Позиція каретки після команди (в мм).
+    pub velocity: f64,        // Середня швидкість каретки під час команди (мм/с).
+    pub pen_down: bool,       // Чи опущене перо після команди.
+}
+
+/// Спільний журнал команд імітованого пристрою.
+///
+/// Журнал залишається доступним після того, як порт переданий у `Device`.
+#[derive(Debug, Clone, Default)]
+pub struct Trace {
+    events: Arc<Mutex<Vec<TraceEvent>>>,
+}
+
+impl Trace {
+    /// Повертає копію всіх записів журналу.
+    pub fn events(&self) -> Vec<TraceEvent> {
+        self.lock().clone()
+    }
+
+    /// Записує журнал у форматі CSV.
+    ///
+    /// # Аргументи
+    /// * `writer` - куди записати таблицю.
+    ///
+    /// # Повертає
+    /// * `io::Result<()>` - помилка запису, якщо вона сталася.
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(
+            writer,
+            "time_ms,duration_ms,command,x_mm,y_mm,velocity_mm_s,pen_down"
+        )?;
+        for event in self.lock().iter() {
+            writeln!(
+                writer,
+                "{:.3},{:.3},\"{}\",{:.4},{:.4},{:.4},{}",
+                event.time.as_secs_f64() * 1000.0,
+                event.duration.as_secs_f64() * 1000.0,
+                event.command.replace('"', "\"\""),
+                event.position.0,
+                event.position.1,
+                event.velocity,
+                u8::from(event.pen_down)
+            )?;
+        }
+        Ok(())
+    }
+
+    fn push(&self, event: TraceEvent) {
+        self.lock().push(event);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<TraceEvent>> {
+        self.events
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Кнопка PRG імітованого пристрою.
+///
+/// Як і на платі, натискання запам'ятовується, доки його не прочитає запит `QG`.
+#[derive(Debug, Clone, Default)]
+pub struct Button {
+    pressed: Arc<AtomicBool>,
+}
+
+impl Button {
+    /// Натискає кнопку.
+    pub fn press(&self) {
+        self.pressed.store(true, Ordering::SeqCst);
+    }
+
+    fn take(&self) -> bool {
+        self.pressed.swap(false, Ordering::SeqCst)
+    }
+}
+
+/// Імітація EiBotBoard для роботи без апаратного забезпечення.
+///
+/// Порт розбирає надіслані команди, веде облік позицій моторів, стану пера та
+/// імітованого часу й відповідає так, як відповіла б плата. Кожна команда
+/// потрапляє до журналу `Trace`.
+pub struct MockPort {
+    steps_per_unit: f64,       // Кроки на міліметр для перерахунку позицій.
+    firmware: FirmwareVersion, // Версія прошивки, від якої залежать формати відповідей.
+    motor1: i64,               // Глобальна позиція мотора 1 (в кроках).
+    motor2: i64,               // Глобальна позиція мотора 2 (в кроках).
+    accumulators: [u32; 2],    // Акумулятори кроків моторів для команди `LM`.
+    pen_down: bool,            // Стан пера.
+    microsteps: (u32, u32),    // Мікрокроки моторів 1 і 2 (0 — мотор вимкнений).
+    nickname: String,          // Псевдонім плати.
+    button: Button,            // Кнопка PRG.
+    time: Duration,            // Імітований час від підключення.
+    input: Vec<u8>,            // Отримані байти незавершеної команди.
+    output: Vec<u8>,           // Відповідь, що очікує на читання.
+    timeout: Duration,         // Тайм-аут порту (лише зберігається).
+    dropped_replies: usize,    // Скільки наступних відповідей загубити.
+    busy_queries: usize,       // Скільки наступних запитів стану повідомлять про рух.
+    bootloader: bool,          // Плата перейшла в завантажувач і більше не відповідає.
+    trace: Trace,              // Журнал команд.
+}
+
+impl MockPort {
+    /// Створює імітований порт із пером, піднятим у позиції (0, 0).
+    ///
+    /// # Аргументи
+    /// * `steps_per_unit` - кроки на міліметр, з якими працює `Device`.
+    pub fn new(steps_per_unit: i32) -> Self {
+        MockPort {
+            steps_per_unit: steps_per_unit.max(1) as f64,
+            firmware: MOCK_VERSION,
+            motor1: 0,
+            motor2: 0,
+            accumulators: [0; 2],
+            pen_down: false,
+            microsteps: (16, 16),
+            nickname: String::new(),
+            button: Button::default(),
+            time: Duration::ZERO,
+            input: Vec::new(),
+            output: Vec::new(),
+            timeout: Duration::from_millis(100),
+            dropped_replies: 0,
+            busy_queries: 0,
+            bootloader: false,
+            trace: Trace::default(),
+        }
+    }
+
+    /// Імітує плату зі старішою або новішою прошивкою.
+    ///
+    /// Від версії залежать формати відповідей `QM` і `PI` так само, як на справжній платі.
+    ///
+    /// # Аргументи
+    /// * `firmware` - версія, яку повідомлятиме команда `V`.
+    pub fn with_firmware(mut self, firmware: FirmwareVersion) -> Self {
+        self.firmware = firmware;
+        self
+    }
+
+    /// Імітує ненадійне з'єднання: плата виконує перші `count` команд, але їхні
+    /// відповіді губляться, і `Device` бачить тайм-аут.
+    pub fn with_dropped_replies(mut self, count: usize) -> Self {
+        self.dropped_replies = count;
+        self
+    }
+
+    /// Імітує довгий рух: перші `count` запитів стану `QG` і `QM` повідомляють, що черга
+    /// не порожня й мотори рухаються.
+    pub fn with_busy_queries(mut self, count: usize) -> Self {
+        self.busy_queries = count;
+        self
+    }
+
+    /// Чи має поточний запит стану повідомити про рух.
+    fn take_busy(&mut self) -> bool {
+        let busy = self.busy_queries > 0;
+        self.busy_queries = self.busy_queries.saturating_sub(1);
+        busy
+    }
+
+    /// Повертає журнал команд цього порту.
+    pub fn trace(&self) -> Trace {
+        self.trace.clone()
+    }
+
+    /// Повертає кнопку PRG цього порту.
+    pub fn button(&self) -> Button {
+        self.button.clone()
+    }
+
+    /// Байт загального стану для `QG`: рух миттєвий, тож черга й мотори вільні, якщо
+    /// довгий рух не імітується через `with_busy_queries`.
+    fn general_status(&mut self) -> u8 {
+        let mut bits = 0;
+        if self.take_busy() {
+            bits |= 0b1111;
+        }
+        if !self.pen_down {
+            bits |= 1 << 4;
+        }
+        if self.button.take() {
+            bits |= 1 << 5;
+        }
+        if self.firmware.supports(FirmwareVersion::V3) {
+            bits |= 1 << 7;
+        }
+        bits
+    }
+
+    /// Позиція каретки (x, y) в міліметрах для змішаної геометрії AxiDraw.
+    fn position(&self) -> (f64, f64) {
+        kinematics::motors_to_xy(
+            self.motor1 as f64 / self.steps_per_unit,
+            self.motor2 as f64 / self.steps_per_unit,
+        )
+    }
+
+    /// Виконує одну команду та повертає відповідь плати.
+    ///
+    /// Невідомі команди лише підтверджуються, як налаштування, що не впливають на рух.
+    fn execute(&mut self, line: &str) -> String {
+        let start = self.position();
+        let mut duration = Duration::ZERO;
+        if self.bootloader {
+            return self.record(line, start, duration, String::new());
+        }
+        let Some(command) = Command::parse(line) else {
+            return self.record(line, start, duration, Response::Ok.frame(""));
+        };
+
+        let shape = command.response(self.firmware);
+        let data = match command {
+            Command::Xm {
+                ms,
+                steps_a,
+                steps_b,
+            } => {
+                let (motor1, motor2) = kinematics::xy_to_motors(steps_a as f64, steps_b as f64);
+                self.motor1 += motor1 as i64;
+                self.motor2 += motor2 as i64;
+                duration = Duration::from_millis(ms as u64);
+                String::new()
+            }
+            Command::Sm { ms, steps1, steps2 } => {
+                self.motor1 += steps1 as i64;
+                self.motor2 += steps2.unwrap_or(0) as i64;
+                duration = Duration::from_millis(ms as u64);
+                String::new()
+            }
+            Command::Lm {
+                rate1,
+                steps1,
+                accel1,
+                rate2,
+                steps2,
+                accel2,
+                clear,
+            } => {
+                // Тривалість за тими самими акумуляторами, що й у переривання плати
+                let mut ticks = 0;
+                for (axis, (rate, steps, accel)) in
+                    [(rate1, steps1, accel1), (rate2, steps2, accel2)]
+                        .into_iter()
+                        .enumerate()
+                {
+                    if clear.is_some_and(|clear| clear & (1 << axis) != 0) {
+                        self.accumulators[axis] = 0;
+                    }
+                    if let Some((axis_ticks, accumulator)) =
+                        axis_ticks(rate, steps.unsigned_abs(), accel, self.accumulators[axis])
+                    {
+                        ticks = ticks.max(axis_ticks);
+                        self.accumulators[axis] = accumulator;
+                    }
+                }
+                self.motor1 += steps1 as i64;
+                self.motor2 += steps2 as i64;
+                duration = Duration::from_secs_f64(ticks as f64 / TICK_RATE);
+                String::new()
+            }
+            Command::L3 { steps1, steps2, .. } => {
+                self.motor1 += steps1 as i64;
+                self.motor2 += steps2 as i64;
+                String::new()
+            }
+            Command::Hm {
+                step_frequency,
+                position1,
+                position2,
+            } => {
+                let (target1, target2) =
+                    (position1.unwrap_or(0) as i64, position2.unwrap_or(0) as i64);
+                let steps = (self.motor1 - target1)
+                    .abs()
+                    .max((self.motor2 - target2).abs());
+                self.motor1 = target1;
+                self.motor2 = target2;
+                duration = Duration::from_secs_f64(steps as f64 / step_frequency.max(1) as f64);
+                String::new()
+            }
+            Command::Cs => {
+                self.motor1 = 0;
+                self.motor2 = 0;
+                String::new()
+            }
+            Command::Sp {
+                value, duration_ms, ..
+            } => {
+                self.pen_down = value == 0;
+                duration = Duration::from_millis(duration_ms.unwrap_or(0) as u64);
+                String::new()
+            }
+            Command::Tp { duration_ms } => {
+                self.pen_down = !self.pen_down;
+                duration = Duration::from_millis(duration_ms.unwrap_or(0) as u64);
+                String::new()
+            }
+            // Увімкнений вихід інструмента (аерограф, лазер) малює так само, як опущене перо
+            Command::S2 {
+                position, delay_ms, ..
+            } => {
+                self.pen_down = position > 0;
+                duration = Duration::from_millis(delay_ms.unwrap_or(0) as u64);
+                String::new()
+            }
+            Command::Po { value, .. } => {
+                self.pen_down = value == 1;
+                String::new()
+            }
+            Command::Em { enable1, enable2 } => {
+                // Другий мотор працює в глобальному режимі, який задає перший
+                let motor1 = microsteps(enable1);
+                let motor2 = match enable2 {
+                    0 => 0,
+                    mode if motor1 == 0 => microsteps(mode),
+                    _ => motor1,
+                };
+                self.microsteps = (motor1, motor2);
+                String::new()
+            }
+            Command::St { nickname } => {
+                self.nickname = nickname;
+                String::new()
+            }
+            Command::Qt => self.nickname.clone(),
+            Command::Qp => if self.pen_down { "0" } else { "1" }.to_string(),
+            Command::Qs => format!("{},{}", self.motor1, self.motor2),
+            Command::Qm => {
+                let busy = u8::from(self.take_busy());
+                match self.firmware.supports(FirmwareVersion::FIFO_STATUS) {
+                    true => format!("QM,{0},{0},{0},{0}", busy),
+                    false => format!("QM,{0},{0},{0}", busy),
+                }
+            }
+            Command::Qg => format!("{:02X}", self.general_status()),
+            Command::Qe => format!("{},{}", self.microsteps.0, self.microsteps.1),
+            // Мотори увімкнені в режимі 1/16 кроку: усі піни MS у високому стані
+            Command::Pi { .. } => "PI,1".to_string(),
+            Command::V => format!(
+                "EBBv13_and_above EB Firmware Version {} (mock)",
+                self.firmware
+            ),
+            Command::Es { .. } => "0,0,0,0,0".to_string(),
+            // Плата перезавантажується в завантажувач, не встигнувши відповісти
+            Command::Bl => {
+                self.bootloader = true;
+                return self.record(line, start, duration, String::new());
+            }
+            _ => String::new(),
+        };
+
+        let response = shape.frame(&data);
+        self.record(line, start, duration, response)
+    }
+
+    /// Записує команду до журналу, просуває імітований час і повертає відповідь.
+    fn record(
+        &mut self,
+        line: &str,
+        start: (f64, f64),
+        duration: Duration,
+        response: String,
+    ) -> String {
+        let end = self.position();
+        let distance = (end.0 - start.0).hypot(end.1 - start.1);
+        let velocity = if duration.is_zero() {
+            0.0
+        } else {
+            distance / duration.as_secs_f64()
+        };
+        self.trace.push(TraceEvent {
+            time: self.time,
+            duration,
+            command: line.to_string(),
+            position: end,
+            velocity,
+            pen_down: self.pen_down,
+        });
+        self.time += duration;
+
+        response
+    }
+}
+
+/// Мікрокроки мотора для параметра команди `EM` (0 — мотор вимкнений).
+fn microsteps(mode: u8) -> u32 {
+    match mode {
+        1 => 16,
+        2 => 8,
+        3 => 4,
+        4 => 2,
+        5 => 1,
+        _ => 0,
+    }
+}
+
+impl Read for MockPort {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.output.is_empty() {
+            // Як і справжній порт, сигналізуємо тайм-аутом, що відповідь закінчилась
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                tr!("No data", "Немає даних"),
+            ));
+        }
+        let count = buf.len().min(self.output.len());
+        buf[..count].copy_from_slice(&self.output[..count]);
+        self.output.drain(..count);
+        Ok(count)
+    }
+}
+
+impl Write for MockPort {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for &byte in buf {
+            if byte == b'\r' {
+                let command = String::from_utf8_lossy(&self.input).trim().to_string();
+                self.input.clear();
+                if !command.is_empty() {
+                    let response = self.execute(&command);
+                    if self.dropped_replies > 0 {
+                        self.dropped_replies -= 1;
+                    } else {
+                        self.output.extend_from_slice(response.as_bytes());
+                    }
+                }
+            } else {
+                self.input.push(byte);
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl SerialPort for MockPort {
+    fn name(&self) -> Option<String> {
+        Some("mock".to_string())
+    }
+
+    fn baud_rate(&self) -> serialport::Result<u32> {
+        Ok(115200)
+    }
+
+    fn data_bits(&self) -> serialport::Result<DataBits> {
+        Ok(DataBits::Eight)
+    }
+
+    fn flow_control(&self) -> serialport::Result<FlowControl> {
+        Ok(FlowControl::None)
+    }
+
+    fn parity(&self) -> serialport::Result<Parity> {
+        Ok(Parity::None)
+    }
+
+    fn stop_bits(&self) -> serialport::Result<StopBits> {
+        Ok(StopBits::One)
+    }
+
+    fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    fn set_baud_rate(&mut self, _baud_rate: u32) -> serialport::Result<()> {
+        Ok(())
+    }
+
+    fn set_data_bits(&mut self, _data_bits: DataBits) -> serialport::Result<()> {
+        Ok(())
+    }
+
+    fn set_flow_control(&mut self, _flow_control: FlowControl) -> serialport::Result<()> {
+        Ok(())
+    }
+
+    fn set_parity(&mut self, _parity: Parity) -> serialport::Result<()> {
+        Ok(())
+    }
+
+    fn set_stop_bits(&mut self, _stop_bits: StopBits) -> serialport::Result<()> {
+        Ok(())
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> serialport::Result<()> {
+        self.timeout = timeout;
+        Ok(())
+    }
+
+    fn write_request_to_send(&mut self, _level: bool) -> serialport::Result<()> {
+        Ok(())
+    }
+
+    fn write_data_terminal_ready(&mut self, _level: bool) -> serialport::Result<()> {
+        Ok(())
+    }
+
+    fn read_clear_to_send(&mut self) -> serialport::Result<bool> {
+        Ok(true)
+    }
+
+    fn read_data_set_ready(&mut self) -> serialport::Result<bool> {
+        Ok(true)
+    }
+
+    fn read_ring_indicator(&mut self) -> serialport::Result<bool> {
+        Ok(false)
+    }
+
+    fn read_carrier_detect(&mut self) -> serialport::Result<bool> {
+        Ok(true)
+    }
+
+    fn bytes_to_read(&self) -> serialport::Result<u32> {
+        Ok(self.output.len() as u32)
+    }
+
+    fn bytes_to_write(&self) -> serialport::Result<u32> {
+        Ok(0)
+    }
+
+    fn clear(&self, _buffer_to_clear: ClearBuffer) -> serialport::Result<()> {
+        Ok(())
+    }
+
+    fn try_clone(&self) -> serialport::Result<Box<dyn SerialPort>> {
+        Err(serialport::Error::new(
+            serialport::ErrorKind::Unknown,
+            tr!(
+                "The simulated port cannot be cloned",
+                "Імітований порт не можна клонувати"
+            ),
+        ))
+    }
+
+    fn set_break(&self) -> serialport::Result<()> {
+        Ok(())
+    }
+
+    fn clear_break(&self) -> serialport::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::axidraw::{Axidraw, Options};
+    use crate::device::transcript::read_commands;
+    use crate::device::{
+        Device, DeviceError, DeviceInfo, DeviceOptions, GeneralStatus, SerialOptions, StepMode,
+    };
+    use crate::drawing::Drawing;
+
+    fn options() -> DeviceOptions {
+        DeviceOptions {
+            steps_per_unit: 80,
+            pen_up_position: 60,
+            pen_up_speed: 150,
+            pen_up_delay: 0,
+            pen_down_position: 30,
+            pen_down_speed: 150,
+            pen_down_delay: 0,
+            pen_settle: 0,
+            step_mode: StepMode::OneSixteenth,
+            port_name: None,
+            port_config: None,
+            transcript: None,
+            serial: SerialOptions::default(),
+        }
+    }
+
+    fn device(firmware: FirmwareVersion) -> (Device, Trace) {
+        let port = MockPort::new(80).with_firmware(firmware);
+        let trace = port.trace();
+        let device = Device::with_port(Box::new(port), options())
+            .expect("Імітований пристрій має підключитися");
+        (device, trace)
+    }
+
+    #[test]
+    fn test_commands_follow_firmware_version() {
+        // Прошивка 2.2: QM без стану FIFO, XM ще немає, HM і LM недоступні
+        let (mut old, trace) = device(FirmwareVersion::new(2, 2, 0));
+        assert_eq!(old.firmware, FirmwareVersion::new(2, 2, 0));
+        old.wait_for_motors()
+            .expect("QM старого формату має розбиратися");
+        old.stepper_move_mixed(100, 30, 10)
+            .expect("XM має замінюватися на SM");
+        assert_eq!(old.read_position().unwrap(), (40, 20));
+        assert!(trace.events().iter().any(|e| e.command == "SM,100,40,20"));
+        assert!(matches!(
+            old.home(1000, None, None),
+            Err(DeviceError::Unsupported { command: "HM", .. })
+        ));
+        assert!(old
+            .low_level_move_jerk(100, 10, 0, 5, 0, 0, 0, 0, None)
+            .is_err());
+
+        // Прошивка 3.0: запити завершуються OK, а рух з ривком надсилається як L3
+        let (mut new, trace) = device(FirmwareVersion::new(3, 0, 2));
+        new.wait_for_motors().expect("QM з OK має розбиратися");
+        new.low_level_move_jerk(100, 10, 0, 5, 100, -10, 0, 5, None)
+            .expect("L3 має надсилатися");
+        assert_eq!(new.read_position().unwrap(), (10, -10));
+        assert!(trace.events().iter().any(|e| e.command.starts_with("L3,")));
+    }
+
+    #[test]
+    fn test_device_state_tracks_commands() {
+        let (mut board, trace) = device(MOCK_VERSION);
+        let state = board.state();
+        assert!(!state.pen_lowered && state.motor1_enabled && state.motor2_enabled);
+        assert_eq!(state.step_mode, StepMode::OneSixteenth);
+        assert_eq!(
+            state.position,
+            Some((0, 0)),
+            "Позицію прочитано під час підключення"
+        );
+
+        board.pen_down().unwrap();
+        board.stepper_move_mixed(100, 30, 10).unwrap();
+        let state = board.state();
+        assert!(state.pen_lowered && board.is_lowered());
+        assert_eq!(state.position, None, "Рух робить позицію застарілою");
+        let queries = |trace: &Trace| trace.events().iter().filter(|e| e.command == "QS").count();
+        let before = queries(&trace);
+        assert_eq!(board.position().unwrap(), (40, 20));
+        assert_eq!(board.position().unwrap(), (40, 20));
+        assert_eq!(
+            queries(&trace),
+            before + 1,
+            "Свіжа позиція не перечитується"
+        );
+
+        board.disable_motors().unwrap();
+        assert!(!board.state().motor1_enabled);
+        assert_eq!(board.state().step_mode, StepMode::OneSixteenth);
+        board.command("SP,1").unwrap();
+        assert!(!board.is_lowered(), "Сирі команди теж оновлюють стан");
+        let synced = board.sync_state().unwrap();
+        assert_eq!(synced, board.state());
+        assert!(!synced.motor1_enabled && !synced.pen_lowered);
+    }
+
+    #[test]
+    fn test_bootloader_entry_disconnects_without_reply() {
+        let (mut board, trace) = device(MOCK_VERSION);
+        board
+            .enter_bootloader()
+            .expect("Мовчання плати після BL — не помилка");
+        assert!(!board.connected);
+        let sent = trace.events().len();
+        drop(board);
+        assert_eq!(
+            trace.events().len(),
+            sent,
+            "Закриття не надсилає команд плати, що вже в завантажувачі"
+        );
+        assert_eq!(trace.events().last().unwrap().command, "BL");
+    }
+
+    #[test]
+    fn test_general_query_reports_pen_and_button() {
+        let port = MockPort::new(80);
+        let button = port.button();
+        let trace = port.trace();
+        let mut board = Device::with_port(Box::new(port), options())
+            .expect("Імітований пристрій має підключитися");
+        assert!(
+            !trace.events().iter().any(|e| e.command.starts_with("PI")),
+            "Стан моторів має читатися однією командою QE"
+        );
+
+        let status = board.general_query().expect("QG має розбиратися");
+        assert!(status.pen_up && status.servo_power && status.is_idle());
+        assert!(!status.button_pressed);
+
+        button.press();
+        board.pen_down().expect("Перо має опускатися");
+        let status = board.general_query().expect("QG має розбиратися");
+        assert!(!status.pen_up);
+        assert!(status.button_pressed, "Натискання має запам'ятовуватися");
+        assert!(
+            !board.button_pressed().unwrap(),
+            "Натискання скидається після запиту"
+        );
+
+        assert_eq!(
+            GeneralStatus::from_bits(0x2F),
+            GeneralStatus {
+                fifo_pending: true,
+                motor2_moving: true,
+                motor1_moving: true,
+                executing_command: true,
+                pen_up: false,
+                button_pressed: true,
+                servo_power: false,
+            }
+        );
+
+        // До 2.6.2 QG немає, а кнопка просто вважається ненатиснутою
+        let (mut old, _) = device(FirmwareVersion::new(2, 5, 0));
+        assert!(matches!(
+            old.general_query(),
+            Err(DeviceError::Unsupported { command: "QG", .. })
+        ));
+        assert!(!old.button_pressed().unwrap());
+    }
+
+    #[test]
+    fn test_wait_until_idle_polls_until_queue_drains() {
+        // Перші три запити стану бачать рух, а четвертий — вільну плату
+        for firmware in [FirmwareVersion::new(3, 0, 2), FirmwareVersion::new(2, 5, 0)] {
+            let port = MockPort::new(80)
+                .with_firmware(firmware)
+                .with_busy_queries(3);
+            let trace = port.trace();
+            let mut board = Device::with_port(Box::new(port), options())
+                .expect("Імітований пристрій має підключитися");
+            board
+                .wait_until_idle(Some(Duration::from_secs(5)))
+                .expect("Черга має звільнитися");
+            let queries = trace
+                .events()
+                .iter()
+                .filter(|e| e.command == "QG" || e.command == "QM")
+                .count();
+            assert_eq!(queries, 4, "Плата опитується, доки рух не завершиться");
+        }
+
+        // Закриття пристрою дочекається решти руху, тож він не безкінечний
+        let port = MockPort::new(80).with_busy_queries(20);
+        let mut board = Device::with_port(Box::new(port), options())
+            .expect("Імітований пристрій має підключитися");
+        assert!(matches!(
+            board.wait_until_idle(Some(Duration::from_millis(30))),
+            Err(DeviceError::StillMoving(waited)) if waited >= Duration::from_millis(30)
+        ));
+
+        // Перо піднімається в кінці шляху лише після запиту, що підтвердив вільну чергу
+        let (mut axidraw, trace) =
+            Axidraw::simulated(Options::default()).expect("Імітований пристрій має підключитися");
+        let svg = r#"<svg viewBox="0 0 100 100"><path d="M10,10 L20,10"/><path d="M10,30 L20,30"/></svg>"#;
+        axidraw
+            .draw(&Drawing::from_svg(svg).unwrap())
+            .expect("Малювання має завершитися");
+        let events = trace.events();
+        let lifts: Vec<usize> = (1..events.len())
+            .filter(|&i| events[i].command.starts_with("SP,1") && events[i - 1].pen_down)
+            .collect();
+        assert_eq!(lifts.len(), 2);
+        assert!(lifts.iter().all(|&i| events[i - 1].command == "QG"));
+    }
+
+    #[test]
+    fn test_motion_waits_for_fifo_room() {
+        let names = |trace: &Trace| -> Vec<String> {
+            let events = trace.events();
+            let first = events.iter().position(|e| e.command.starts_with("XM"));
+            events[first.unwrap_or(events.len())..]
+                .iter()
+                .map(|e| e.command.split(',').next().unwrap().to_string())
+                .collect()
+        };
+
+        // Прошивка 2.x тримає в черзі одну команду: третя чекає, доки черга спорожніє
+        let port = MockPort::new(80)
+            .with_firmware(FirmwareVersion::new(2, 8, 1))
+            .with_busy_queries(2);
+        let trace = port.trace();
+        let mut board = Device::with_port(Box::new(port), options()).unwrap();
+        assert_eq!(board.fifo_depth(), 1);
+        for _ in 0..3 {
+            board.stepper_move_mixed(100, 10, 10).unwrap();
+        }
+        assert_eq!(names(&trace), ["XM", "XM", "QM", "QM", "QM", "XM"]);
+        assert_eq!(board.serial_stats().fifo_waits, 1);
+
+        // Прошивка 3.x приймає ще три команди, поки виконує поточну
+        let port = MockPort::new(80).with_firmware(FirmwareVersion::new(3, 0, 2));
+        let trace = port.trace();
+        let mut board = Device::with_port(Box::new(port), options()).unwrap();
+        for _ in 0..5 {
+            board.stepper_move_mixed(100, 10, 10).unwrap();
+        }
+        assert_eq!(names(&trace), ["XM", "XM", "XM", "XM", "QM", "XM"]);
+
+        // Після очікування вільної плати черга знову порожня
+        board.wait_for_motors().unwrap();
+        board.stepper_move_mixed(100, 10, 10).unwrap();
+        assert_eq!(names(&trace)[6..], ["QG", "XM"]);
+        assert_eq!(board.serial_stats().fifo_waits, 1);
+
+        // Черга, що не звільняється, не вішає малювання назавжди
+        let port = MockPort::new(80)
+            .with_firmware(FirmwareVersion::new(2, 8, 1))
+            .with_busy_queries(20);
+        let mut stalled = options();
+        stalled.serial.fifo_timeout = Duration::from_millis(30);
+        let mut board = Device::with_port(Box::new(port), stalled).unwrap();
+        board.stepper_move_mixed(100, 10, 10).unwrap();
+        board.stepper_move_mixed(100, 10, 10).unwrap();
+        assert!(matches!(
+            board.stepper_move_mixed(100, 10, 10),
+            Err(DeviceError::StillMoving(waited)) if waited >= Duration::from_millis(30)
+        ));
+    }
+
+    #[test]
+    fn test_lost_query_replies_are_retried() {
+        let serial = SerialOptions {
+            retry_backoff: Duration::from_millis(1),
+            write_chunk: 3,
+            ..SerialOptions::default()
+        };
+
+        // Перші дві відповіді на V губляться, а третя спроба вже проходить
+        let port = MockPort::new(80).with_dropped_replies(2);
+        let trace = port.trace();
+        let device = Device::with_port(
+            Box::new(port),
+            DeviceOptions {
+                serial,
+                ..options()
+            },
+        )
+        .expect("Повтори мають приховати втрачені відповіді");
+        let stats = device.serial_stats();
+        assert_eq!((stats.retries, stats.timeouts), (2, 2));
+        assert_eq!(
+            trace
+                .events()
+                .iter()
+                .take(3)
+                .filter(|e| e.command == "V")
+                .count(),
+            3,
+            "Команду, записану порціями, плата має отримати цілою"
+        );
+
+        let port = MockPort::new(80).with_dropped_replies(1);
+        let no_retries = SerialOptions {
+            retries: 0,
+            ..serial
+        };
+        let result = Device::with_port(
+            Box::new(port),
+            DeviceOptions {
+                serial: no_retries,
+                ..options()
+            },
+        );
+        assert!(matches!(result, Err(DeviceError::Timeout { .. })));
+    }
+
+    #[test]
+    fn test_nickname_identifies_device() {
+        let (mut board, _) = device(MOCK_VERSION);
+        assert_eq!(board.query_nickname().unwrap(), None);
+        board
+            .nickname("left")
+            .expect("Псевдонім має встановлюватися");
+        assert_eq!(board.query_nickname().unwrap().as_deref(), Some("left"));
+        assert!(board.nickname("занадто-довгий-псевдонім").is_err());
+
+        // До 2.5.5 плата не зберігає псевдонім
+        let (mut old, _) = device(FirmwareVersion::new(2, 4, 0));
+        assert!(matches!(
+            old.query_nickname(),
+            Err(DeviceError::Unsupported { command: "QT", .. })
+        ));
+
+        let info = DeviceInfo {
+            port_name: "/dev/ttyACM0".to_string(),
+            nickname: Some("left".to_string()),
+            firmware: Some(MOCK_VERSION),
+        };
+        let expected = crate::i18n::lang().pick(
+            "/dev/ttyACM0 «left», firmware 3.0.2",
+            "/dev/ttyACM0 «left», прошивка 3.0.2",
+        );
+        assert_eq!(info.to_string(), expected);
+    }
+
+    #[test]
+    fn test_transcript_replays_plot() {
+        let path =
+            std::env::temp_dir().join(format!("rsaxi-transcript-{}.log", std::process::id()));
+        let plot_options = Options {
+            transcript: Some(path.to_string_lossy().into_owned()),
+            ..Options::default()
+        };
+        let (mut axidraw, trace) =
+            Axidraw::simulated(plot_options).expect("Імітований пристрій має підключитися");
+        let svg = r#"<svg viewBox="0 0 100 100"><path d="M10,10 L60,10 L60,40"/></svg>"#;
+        let drawing = Drawing::from_svg(svg).expect("SVG має розбиратися");
+        axidraw.draw(&drawing).expect("Малювання має завершитися");
+        drop(axidraw);
+
+        let log = std::fs::read_to_string(&path).expect("Журнал має бути записаний");
+        std::fs::remove_file(&path).ok();
+        assert!(log.lines().any(|line| line.ends_with("< OK\\r\\n")));
+        let commands = read_commands(log.as_bytes()).expect("Журнал має читатися");
+        let recorded: Vec<String> = trace.events().into_iter().map(|e| e.command).collect();
+        assert_eq!(commands, recorded, "Журнал містить усі надіслані команди");
+
+        // Відтворення надсилає лише рух і перо та проходить той самий шлях
+        let port = MockPort::new(80);
+        let replayed = port.trace();
+        let mut board = Device::with_port(Box::new(port), options()).unwrap();
+        let sent = board.replay(&commands).expect("Журнал має відтворюватися");
+        let moves = |events: Vec<TraceEvent>| -> Vec<(f64, f64)> {
+            events
+                .into_iter()
+                .filter(|e| e.command.starts_with("XM"))
+                .map(|e| e.position)
+                .collect()
+        };
+        assert_eq!(moves(replayed.events()), moves(trace.events()));
+        assert!(sent < commands.len(), "Запити не відтворюються");
+        assert!(read_commands("не журнал".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_simulated_plot_records_timeline() {
+        let (mut axidraw, trace) =
+            Axidraw::simulated(Options::default()).expect("Імітований пристрій має підключитися");
+        let svg = r#"<svg viewBox="0 0 100 100"><rect x="10" y="10" width="20" height="20"/><circle cx="60" cy="40" r="15"/></svg>"#;
+        let drawing = Drawing::from_svg(svg).expect("SVG має розбиратися");
+        axidraw.draw(&drawing).expect("Малювання має завершитися");
+
+        let events = trace.events();
+        assert!(events.windows(2).all(|pair| pair[0].time <= pair[1].time));
+
+        // Перо опускається лише після переїзду до початку квадрата
+        let down = events
+            .iter()
+            .position(|event| event.pen_down)
+            .expect("Перо має опускатися");
+        let (x, y) = events[down].position;
+        assert!((x - 10.0).abs() < 0.1 && (y - 10.0).abs() < 0.1);
+
+        // Під час малювання каретка обходить усі кути квадрата з опущеним пером
+        let drawn: Vec<_> = events
+            .iter()
+            .filter(|event| event.pen_down && event.command.starts_with("XM"))
+            .collect();
+        assert!(drawn
+            .iter()
+            .any(|e| (e.position.0 - 30.0).abs() < 0.1 && (e.position.1 - 30.0).abs() < 0.1));
+        assert!(drawn
+            .iter()
+            .all(|e| e.velocity <= Options::default().max_velocity * 1.05));
+
+        let last = events.last().expect("Журнал не порожній");
+        assert_eq!(last.position, (0.0, 0.0), "Каретка повертається додому");
+        assert!(!last.pen_down);
+
+        let mut csv = Vec::new();
+        trace.write_csv(&mut csv).expect("CSV має записуватися");
+        let csv = String::from_utf8(csv).expect("CSV має бути UTF-8");
+        assert!(csv.starts_with("time_ms,duration_ms,command,"));
+        assert_eq!(csv.lines().count(), events.len() + 1);
+    }
+}
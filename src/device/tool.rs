@@ -0,0 +1,102 @@
+use std::fmt;
+
+/// Найбільша ширина імпульсу `S2` (в одиницях 1/12 мкс), що відповідає повній потужності.
+const PWM_FULL: f64 = 65_535.0;
+
+/// Інструмент, яким плотер малює.
+///
+/// Звичайне перо піднімається й опускається серво. Для аерографа чи діодного лазера
+/// «опускання пера» замінюється вмиканням виходу плати, а «підняття» — вимиканням,
+/// тож такі насадки малюють тим самим конвеєром, що й перо.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Tool {
+    /// Перо на серво підйому.
+    #[default]
+    Pen,
+    /// Імпульсний вихід `S2` на піні RPn з потужністю для кожного шару (0..1).
+    ///
+    /// Шар без власного значення бере останнє з `intensities`, а порожній список
+    /// означає повну потужність.
+    Pwm { pin: u8, intensities: Vec<f64> },
+    /// Цифровий вихід `PO`, що вмикає інструмент високим рівнем.
+    Pin { port: char, pin: u8 },
+}
+
+impl Tool {
+    /// Чи малює інструмент серво пера, а не виходом плати.
+    pub fn is_pen(&self) -> bool {
+        matches!(self, Tool::Pen)
+    }
+
+    /// Потужність інструмента для шару з номером `layer` (з нуля) в межах 0..1.
+    pub fn intensity(&self, layer: usize) -> f64 {
+        match self {
+            Tool::Pwm { intensities, .. } => intensities
+                .get(layer)
+                .or(intensities.last())
+                .copied()
+                .unwrap_or(1.0),
+            _ => 1.0,
+        }
+    }
+
+    /// Ширина імпульсу `S2` для потужності `intensity`; потужність поза 0..1 обмежується.
+    pub fn pwm_position(intensity: f64) -> u16 {
+        (intensity.clamp(0.0, 1.0) * PWM_FULL).round() as u16
+    }
+
+    /// Інструмент для завдання, в якому лишилися тільки шари з номерами `layers`.
+    ///
+    /// Потужності переносяться разом із шарами, тож решта шарів малюється з тими самими
+    /// значеннями, що й у повному завданні.
+    pub fn select_layers(&self, layers: &[usize]) -> Tool {
+        match self {
+            Tool::Pwm { pin, intensities } if !intensities.is_empty() => Tool::Pwm {
+                pin: *pin,
+                intensities: layers.iter().map(|&layer| self.intensity(layer)).collect(),
+            },
+            tool => tool.clone(),
+        }
+    }
+}
+
+impl fmt::Display for Tool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Tool::Pen => write!(f, "pen"),
+            Tool::Pwm { pin, .. } => write!(f, "pwm RP{}", pin),
+            Tool::Pin { port, pin } => write!(f, "pin R{}{}", port, pin),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pwm_intensity_per_layer() {
+        let tool = Tool::Pwm {
+            pin: 5,
+            intensities: vec![0.25, 1.0, 0.5],
+        };
+        assert_eq!(tool.intensity(0), 0.25);
+        assert_eq!(
+            tool.intensity(5),
+            0.5,
+            "Зайві шари беруть останню потужність"
+        );
+        assert_eq!(Tool::pwm_position(1.0), 65_535);
+        assert_eq!(Tool::pwm_position(-1.0), 0);
+
+        // Після пропуску першого шару другий шар завдання стає першим
+        assert_eq!(tool.select_layers(&[1, 2]).intensity(0), 1.0);
+        assert_eq!(tool.select_layers(&[2]).intensity(0), 0.5);
+        let full = Tool::Pwm {
+            pin: 5,
+            intensities: vec![],
+        };
+        assert_eq!(full.intensity(2), 1.0, "Без значень потужність повна");
+        assert!(Tool::default().is_pen());
+    }
+}
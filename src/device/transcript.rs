@@ -0,0 +1,95 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufWriter, Write};
+use std::path::Path;
+use std::time::Instant;
+
+use crate::tr;
+
+/// Перший рядок журналу, за яким `read_commands` впізнає формат.
+const HEADER: &str = "# rsaxi transcript 1";
+
+/// Журнал обміну з платою: кожна надіслана команда та сира відповідь з часом.
+///
+/// Рядок журналу має вигляд `<секунди> <напрямок> <текст>`, де `>` позначає команду,
+/// а `<` — відповідь із екранованими `\r` і `\n`. Надіслані команди читає назад
+/// `read_commands`, тож журнал можна відтворити на іншій платі.
+pub struct Transcript {
+    writer: Box<dyn Write + Send>, // Куди записується журнал.
+    start: Instant,                // Момент початку журналу.
+}
+
+impl Transcript {
+    /// Створює файл журналу.
+    ///
+    /// # Аргументи
+    /// * `path` - шлях до файлу; наявний файл перезаписується.
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::new(Box::new(BufWriter::new(File::create(path)?)))
+    }
+
+    /// Починає журнал у довільному записувачі.
+    pub fn new(mut writer: Box<dyn Write + Send>) -> io::Result<Self> {
+        writeln!(writer, "{}", HEADER)?;
+        writer.flush()?;
+        Ok(Transcript {
+            writer,
+            start: Instant::now(),
+        })
+    }
+
+    /// Записує команду, надіслану до плати.
+    pub fn sent(&mut self, command: &str) -> io::Result<()> {
+        self.write('>', command)
+    }
+
+    /// Записує сиру відповідь плати.
+    pub fn received(&mut self, response: &str) -> io::Result<()> {
+        self.write('<', response)
+    }
+
+    fn write(&mut self, direction: char, text: &str) -> io::Result<()> {
+        let text = text.replace('\r', "\\r").replace('\n', "\\n");
+        writeln!(
+            self.writer,
+            "{:.3} {} {}",
+            self.start.elapsed().as_secs_f64(),
+            direction,
+            text
+        )?;
+        // Журнал потрібен саме тоді, коли щось пішло не так, тож не тримаємо його в буфері
+        self.writer.flush()
+    }
+}
+
+/// Читає команди, надіслані до плати, з журналу обміну.
+///
+/// # Аргументи
+/// * `reader` - журнал, записаний `Transcript`.
+///
+/// # Повертає
+/// * `io::Result<Vec<String>>` - команди в порядку надсилання або помилка, якщо це не журнал.
+pub fn read_commands<R: BufRead>(reader: R) -> io::Result<Vec<String>> {
+    let mut lines = reader.lines();
+    match lines.next().transpose()? {
+        Some(header) if header.trim() == HEADER => {}
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                tr!(
+                    "The file is not an rsaxi transcript",
+                    "Файл не є журналом обміну rsaxi"
+                ),
+            ))
+        }
+    }
+
+    let mut commands = vec![];
+    for line in lines {
+        let line = line?;
+        let mut fields = line.splitn(3, ' ');
+        if let (Some(_), Some(">"), Some(command)) = (fields.next(), fields.next(), fields.next()) {
+            commands.push(command.to_string());
+        }
+    }
+    Ok(commands)
+}
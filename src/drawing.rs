@@ -1,11 +1,54 @@
+pub mod boolean;
+pub mod contour;
+pub mod coordinates;
+pub mod correction;
+pub mod crop;
+pub mod direction;
+pub mod generator;
+pub mod hatch;
+pub mod import;
+pub mod layout;
+pub mod lead;
+pub mod offset;
+pub mod order;
+pub mod packing;
+pub mod refill;
+pub mod resample;
+pub mod roughen;
+pub mod sample;
+pub mod shape;
+pub mod stats;
+pub mod style;
+pub mod tile;
+pub mod turtle;
+
 use std::ops::AddAssign;
 
-use anyhow::Result;
-use geo::MultiLineString;
+use crate::error::Result;
+use geo::{AffineOps, AffineTransform, BoundingRect, Coord, MultiLineString, Rect};
 use svg::node::element::path::Data;
-use svg::node::element::Path;
+use svg::node::element::{Description, Group, Path, Polyline};
+use svg::node::Text;
 use svg::Document;
 
+use crate::axidraw::Options;
+use crate::motion::error::PlanError;
+use crate::paper::{Paper, PaperError};
+use crate::tr;
+
+pub use self::contour::contours;
+
+use self::lead::{Leads, Stroke};
+use self::style::PathStyle;
+
+/// Товщина лінії в SVG (в мм) для шляхів без власної товщини.
+const STROKE_WIDTH: f64 = 0.5;
+
+/// Відстань між двома точками шляху.
+pub(crate) fn distance(a: Coord<f64>, b: Coord<f64>) -> f64 {
+    (a.x - b.x).hypot(a.y - b.y)
+}
+
 /// Трейт, що представляє об'єкт, який можна малювати.
 pub trait Drawable {
     /// Генерує геометричні шляхи, що представляють об'єкт для малювання.
@@ -21,6 +64,7 @@ pub trait Drawable {
 pub struct Drawing {
     pub paths: MultiLineString<f64>, // Набір шляхів, що складають малюнок.
     pub bounds: (f64, f64),          // Межі малюнка (ширина, висота).
+    pub styles: Vec<PathStyle>,      // Атрибути шляхів за їх номером; може бути коротшим за шляхи.
 }
 
 impl Drawing {
@@ -33,55 +77,289 @@ impl Drawing {
     /// # Повертає
     /// * Новий екземпляр `Drawing`.
     pub fn new(bounds: (f64, f64), paths: MultiLineString<f64>) -> Self {
-        Drawing { paths, bounds }
+        Drawing {
+            paths,
+            bounds,
+            styles: vec![],
+        }
+    }
+
+    /// Створює порожній малюнок розміром з аркуш паперу.
+    ///
+    /// # Аргументи
+    /// * `paper` - аркуш, розміри якого стають межами малюнка.
+    ///
+    /// # Повертає
+    /// * Новий екземпляр `Drawing` без шляхів.
+    pub fn for_paper(paper: &Paper) -> Self {
+        Drawing::new(paper.size(), MultiLineString(vec![]))
+    }
+
+    /// Повертає обмежувальну рамку всіх шляхів малюнка.
+    ///
+    /// # Повертає
+    /// * `Option<Rect<f64>>` - рамка шляхів або `None`, якщо малюнок порожній.
+    pub fn bbox(&self) -> Option<Rect<f64>> {
+        self.paths.bounding_rect()
+    }
+
+    /// Перевіряє, чи всі шляхи малюнка лежать в області для малювання аркуша.
+    ///
+    /// # Аргументи
+    /// * `paper` - аркуш з полями.
+    ///
+    /// # Повертає
+    /// * `bool` - `true`, якщо малюнок порожній або повністю вміщується в межах полів.
+    pub fn fits(&self, paper: &Paper) -> bool {
+        self.bbox().is_none_or(|bbox| paper.contains(&bbox))
+    }
+
+    /// Масштабує та центрує малюнок в області для малювання аркуша, зберігаючи пропорції.
+    ///
+    /// З вильотом аркуша (`Paper::bleed`) малюнок вписується в область, розширену на виліт
+    /// з кожного боку, і обрізається по полях, тож поза областю для малювання нічого немає.
+    ///
+    /// # Аргументи
+    /// * `paper` - аркуш, на який потрібно вмістити малюнок.
+    ///
+    /// # Повертає
+    /// * `Result<Drawing, PaperError>` - новий малюнок з межами аркуша або помилка,
+    ///   якщо поля не залишають області для малювання.
+    pub fn fit_to_page(&self, paper: &Paper) -> Result<Drawing, PaperError> {
+        let printable = paper.printable_area()?;
+        let bleed = paper.bleed.max(0.0);
+        let area = Rect::new(
+            printable.min() - Coord { x: bleed, y: bleed },
+            printable.max() + Coord { x: bleed, y: bleed },
+        );
+        let bbox = match self.bbox() {
+            Some(bbox) => bbox,
+            None => {
+                return Ok(
+                    Drawing::new(paper.size(), self.paths.clone()).with_styles(self.styles.clone())
+                )
+            }
+        };
+
+        // Вироджений малюнок (точка або лінія) не масштабуємо по нульовому виміру
+        let scale_x = if bbox.width() > 0.0 {
+            area.width() / bbox.width()
+        } else {
+            f64::INFINITY
+        };
+        let scale_y = if bbox.height() > 0.0 {
+            area.height() / bbox.height()
+        } else {
+            f64::INFINITY
+        };
+        let scale = match scale_x.min(scale_y) {
+            scale if scale.is_finite() => scale,
+            _ => 1.0,
+        };
+
+        let center = bbox.center();
+        let target = area.center();
+        let transform = AffineTransform::new(
+            scale,
+            0.0,
+            target.x - center.x * scale,
+            0.0,
+            scale,
+            target.y - center.y * scale,
+        );
+
+        let fitted = Drawing::new(paper.size(), self.paths.affine_transform(&transform))
+            .with_styles(self.styles.clone());
+        if bleed > 0.0 {
+            return Ok(fitted.clip_to_rect(printable));
+        }
+        Ok(fitted)
+    }
+
+    /// Готує шляхи малюнка до планування руху, за потреби додаючи до них захід і вихід.
+    ///
+    /// # Аргументи
+    /// * `leads` - налаштування заходу та виходу або `None`, щоб малювати шляхи як є.
+    ///
+    /// # Повертає
+    /// * `Vec<Stroke>` - непорожні шляхи з частками швидкості для кожної точки й паузами
+    ///   зі стилів шляхів.
+    pub fn strokes(&self, leads: Option<&Leads>) -> Vec<Stroke> {
+        self.paths
+            .0
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| !line.0.is_empty())
+            .map(|(index, line)| {
+                let mut stroke = match leads {
+                    Some(leads) => leads.apply(line),
+                    None => Stroke::plain(line),
+                };
+                stroke.dwell = self.style(index).dwell;
+                stroke
+            })
+            .collect()
     }
 
     /// Генерує SVG-документ із поточного малюнка та повертає його у вигляді рядка.
     ///
+    /// Документ має межі малюнка в міліметрах.
+    ///
     /// # Повертає
     /// Серіалізований в стрічку документ SVG.
     pub fn to_svg(&self) -> String {
-        // Логування початку процесу конвертації
-        log::info!(
-            "Генерація SVG-документа з межами: ширина = {}, висота = {}",
-            self.bounds.0,
-            self.bounds.1
-        );
+        self.svg_document(None, None).to_string()
+    }
 
-        // Ініціалізуємо SVG Path дані
-        let mut data = Data::new();
+    /// Генерує SVG-документ розміром з аркуш паперу.
+    ///
+    /// Документ отримує фізичні розміри в міліметрах і `viewBox` аркуша, тож відкривається
+    /// в масштабі 1:1 незалежно від меж самого малюнка.
+    ///
+    /// # Аргументи
+    /// * `paper` - аркуш, розміри якого задають документ.
+    ///
+    /// # Повертає
+    /// Серіалізований в стрічку документ SVG.
+    pub fn to_svg_on(&self, paper: &Paper) -> String {
+        self.svg_document(Some(paper), None).to_string()
+    }
 
-        // Проходимо по кожному LineString у MultiLineString
-        for line in &self.paths.0 {
-            if let Some(first_point) = line.0.first() {
-                // Переміщуємося до першої точки шляху
-                data = data.move_to((first_point.x, first_point.y));
-                // Малюємо лінії до всіх наступних точок
-                for point in line.0.iter().skip(1) {
-                    data = data.line_to((point.x, point.y));
-                }
-            }
+    /// Генерує SVG-документ розміром з аркуш з описом у `<desc>`, наприклад назвою
+    /// генератора й налаштуваннями, якими малюнок створено.
+    ///
+    /// # Аргументи
+    /// * `paper` - аркуш, розміри якого задають документ.
+    /// * `description` - текст опису.
+    ///
+    /// # Повертає
+    /// Серіалізований в стрічку документ SVG.
+    pub fn to_svg_described(&self, paper: &Paper, description: &str) -> String {
+        self.svg_document(Some(paper), Some(description))
+            .to_string()
+    }
+
+    /// Генерує SVG-документ, у якому шляхи з'являються в порядку й темпі малювання.
+    ///
+    /// Малюнок планується так само, як для `plot`, з упорядкуванням, швидкостями й
+    /// прискореннями налаштувань, а анімація ліній береться з `Job::to_animated_svg`.
+    ///
+    /// # Аргументи
+    /// * `options` - налаштування плотера, за якими планується рух.
+    ///
+    /// # Повертає
+    /// * `Result<String, PlanError>` - документ розміром з аркуш налаштувань (або з робочу
+    ///   область моделі) чи помилка планування.
+    pub fn to_animated_svg(&self, options: &Options) -> std::result::Result<String, PlanError> {
+        let paper = options
+            .paper
+            .unwrap_or_else(|| Paper::from_model(options.model));
+        Ok(options.plan_drawing(self)?.to_animated_svg(&paper))
+    }
+
+    /// Генерує SVG-документ розміром з аркуш з окремим шаром переїздів з піднятим пером.
+    ///
+    /// Переїзди з `travel_moves` малюються червоним пунктиром у групі `travel`, яку
+    /// редактори на кшталт Inkscape показують окремим шаром, тож порядок малювання можна
+    /// оглянути й приховати переїзди перед друком.
+    ///
+    /// # Аргументи
+    /// * `paper` - аркуш, розміри якого задають документ.
+    ///
+    /// # Повертає
+    /// Серіалізований в стрічку документ SVG.
+    pub fn to_svg_with_travel(&self, paper: &Paper) -> String {
+        let mut data = Data::new();
+        for line in &self.travel_moves() {
+            data = data
+                .move_to((line.0[0].x, line.0[0].y))
+                .line_to((line.0[1].x, line.0[1].y));
         }
+        let travel = Group::new()
+            .set("id", "travel")
+            .set("inkscape:groupmode", "layer")
+            .set("inkscape:label", "travel")
+            .add(
+                Path::new()
+                    .set("fill", "none")
+                    .set("stroke", "#e33")
+                    .set("stroke-width", 0.3)
+                    .set("stroke-dasharray", "1,1")
+                    .set("d", data),
+            );
+        self.svg_document(Some(paper), None)
+            .set(
+                "xmlns:inkscape",
+                "http://www.inkscape.org/namespaces/inkscape",
+            )
+            .add(travel)
+            .to_string()
+    }
+
+    /// Будує SVG-документ з межами малюнка або, якщо вказано, аркуша.
+    ///
+    /// Кожен шлях стає окремим `<polyline>` зі своїм кольором, товщиною, інструментом
+    /// (`data-tool`) і паузою (`data-dwell`), тож структуру малюнка видно в редакторі, а записаний файл
+    /// імпортується назад з тими самими стилями. Межі задаються в мм.
+    fn svg_document(&self, paper: Option<&Paper>, description: Option<&str>) -> Document {
+        let (width, height) = paper.map_or(self.bounds, Paper::size);
 
-        // Створюємо SVG Path елемент з згенерованими даними
-        let path = Path::new()
-            .set("fill", "none") // Без заливки
-            .set("stroke", "black") // Чорний колір лінії
-            .set("stroke-width", 1) // Товщина лінії
-            .set("d", data); // Геометричні дані шляху
+        // Логування початку процесу конвертації
+        tracing::info!(
+            "{}",
+            tr!(
+                "Generating SVG document with bounds: width = {}, height = {}",
+                "Генерація SVG-документа з межами: ширина = {}, висота = {}",
+                width,
+                height
+            )
+        );
 
-        // Створюємо документ SVG з визначеним viewBox
-        let document = Document::new()
-            .set("viewBox", (0, 0, self.bounds.0, self.bounds.1)) // Встановлюємо viewBox відповідно до меж
-            .add(path); // Додаємо шлях до документа
+        // Створюємо документ SVG з визначеним viewBox і фізичним розміром у мм
+        let mut document = Document::new()
+            .set("viewBox", (0, 0, width, height))
+            .set("width", format!("{}mm", width))
+            .set("height", format!("{}mm", height));
+        if let Some(description) = description {
+            document = document.add(Description::new().add(Text::new(description)));
+        }
 
-        // Серіалізуємо SVG документ у рядок
-        let svg_string = document.to_string();
+        // Кожен непорожній шлях стає окремим елементом зі своїм стилем
+        for (index, line) in self.paths.0.iter().enumerate() {
+            if line.0.is_empty() {
+                continue;
+            }
+            let points = line
+                .0
+                .iter()
+                .map(|point| format!("{},{}", point.x, point.y))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let style = self.style(index);
+            let mut polyline = Polyline::new()
+                .set("fill", "none")
+                .set("stroke", style.color.as_deref().unwrap_or("black"))
+                .set("stroke-width", style.stroke_width.unwrap_or(STROKE_WIDTH))
+                .set("points", points);
+            if let Some(tool) = style.tool {
+                polyline = polyline.set("data-tool", tool);
+            }
+            if let Some(dwell) = style.dwell {
+                polyline = polyline.set("data-dwell", dwell.as_secs_f64() * 1000.0);
+            }
+            document = document.add(polyline);
+        }
 
         // Логування завершення процесу конвертації
-        log::info!("SVG-документ успішно згенеровано.");
+        tracing::info!(
+            "{}",
+            tr!(
+                "SVG document generated.",
+                "SVG-документ успішно згенеровано."
+            )
+        );
 
-        svg_string
+        document
     }
 }
 
@@ -94,8 +372,64 @@ impl<T: Drawable> AddAssign<T> for Drawing {
             }
             Err(e) => {
                 // Логування помилки
-                log::error!("Помилка при додаванні Drawable: {:?}", e);
+                tracing::error!(
+                    "{}",
+                    tr!(
+                        "Failed to add Drawable: {:?}",
+                        "Помилка при додаванні Drawable: {:?}",
+                        e
+                    )
+                );
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::LineString;
+
+    #[test]
+    fn test_svg_has_one_element_per_path_with_styles() {
+        let drawing = Drawing::new(
+            (40.0, 30.0),
+            MultiLineString(vec![
+                LineString::from(vec![(0.0, 0.0), (10.0, 5.0)]),
+                LineString::from(Vec::<(f64, f64)>::new()),
+                LineString::from(vec![(1.0, 1.0), (2.0, 2.0), (3.0, 1.5)]),
+            ]),
+        )
+        .with_styles(vec![
+            PathStyle {
+                color: Some("#ff0000".to_string()),
+                stroke_width: Some(0.3),
+                tool: Some(2),
+                dwell: Some(std::time::Duration::from_millis(400)),
+                ..PathStyle::default()
+            },
+            PathStyle::default(),
+            PathStyle::default(),
+        ]);
+
+        let svg = drawing.to_svg_described(&Paper::custom(40.0, 30.0), "spiral <turns=3>");
+        assert_eq!(
+            svg.matches("<polyline").count(),
+            2,
+            "Порожній шлях пропускається"
+        );
+        assert!(svg.contains("width=\"40mm\""));
+        assert!(svg.contains("points=\"1,1 2,2 3,1.5\""));
+        assert!(svg.contains("stroke=\"#ff0000\"") && svg.contains("data-tool=\"2\""));
+        assert!(svg.contains("data-dwell=\"400\""));
+        assert!(svg.contains("<desc>spiral &lt;turns=3&gt;</desc>"));
+
+        // Записаний документ імпортується назад з тими самими шляхами й стилями
+        let again = Drawing::from_svg_with(&svg, &import::ImportOptions::default())
+            .expect("Документ має розбиратися");
+        assert_eq!(again.paths.0.len(), 2);
+        assert_eq!(again.paths.0[1], drawing.paths.0[2]);
+        assert_eq!(again.styles[0], drawing.styles[0]);
+        assert_eq!(again.bounds, (40.0, 30.0));
+    }
+}
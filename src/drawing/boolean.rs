@@ -0,0 +1,173 @@
+use geo::{BooleanOps, MultiLineString, MultiPolygon};
+
+use super::Drawing;
+
+impl Drawing {
+    /// Область, яку охоплюють замкнені шляхи малюнка.
+    ///
+    /// Контури поєднуються за правилом парності: контур усередині іншого вирізає в ньому
+    /// отвір, як у літерах «О» чи «В». Незамкнені шляхи області не додають.
+    ///
+    /// # Повертає
+    /// * `MultiPolygon<f64>` - область малюнка; порожня, якщо замкнених шляхів немає.
+    pub fn region(&self) -> MultiPolygon<f64> {
+        self.polygons()
+            .into_iter()
+            .fold(MultiPolygon(vec![]), |region, polygon| {
+                region.xor(&MultiPolygon(vec![polygon]))
+            })
+    }
+
+    /// Об'єднання областей двох малюнків.
+    ///
+    /// # Аргументи
+    /// * `other` - малюнок, область якого додається.
+    ///
+    /// # Повертає
+    /// * `Drawing` - контури об'єднання з межами цього малюнка.
+    pub fn union(&self, other: &Drawing) -> Drawing {
+        self.outline(self.region().union(&other.region()))
+    }
+
+    /// Перетин областей двох малюнків.
+    ///
+    /// # Аргументи
+    /// * `other` - малюнок, з областю якого перетинається ця.
+    ///
+    /// # Повертає
+    /// * `Drawing` - контури спільної частини з межами цього малюнка.
+    pub fn intersection(&self, other: &Drawing) -> Drawing {
+        self.outline(self.region().intersection(&other.region()))
+    }
+
+    /// Різниця областей: частина цього малюнка поза областю `other`.
+    ///
+    /// # Аргументи
+    /// * `other` - малюнок, область якого вирізається.
+    ///
+    /// # Повертає
+    /// * `Drawing` - контури різниці з межами цього малюнка.
+    pub fn difference(&self, other: &Drawing) -> Drawing {
+        self.outline(self.region().difference(&other.region()))
+    }
+
+    /// Обрізає шляхи малюнка областю маски, наприклад штрихування контуром тексту.
+    ///
+    /// На відміну від `intersection`, обрізаються всі шляхи, зокрема незамкнені лінії, а
+    /// кожен шматок зберігає стиль шляху, з якого його вирізано.
+    ///
+    /// # Аргументи
+    /// * `mask` - малюнок, замкнені шляхи якого задають область.
+    /// * `inside` - `true` залишає частини всередині області, `false` — поза нею.
+    ///
+    /// # Повертає
+    /// * `Drawing` - обрізані шляхи з межами цього малюнка.
+    pub fn clip(&self, mask: &Drawing, inside: bool) -> Drawing {
+        let region = mask.region();
+        let (mut paths, mut indices) = (vec![], vec![]);
+        for (index, line) in self.paths.0.iter().enumerate() {
+            let pieces = region.clip(&MultiLineString(vec![line.clone()]), !inside);
+            for piece in pieces.0.into_iter().filter(|piece| piece.0.len() >= 2) {
+                paths.push(piece);
+                indices.push(index);
+            }
+        }
+        let styles = self.styles_for(indices);
+        Drawing::new(self.bounds, MultiLineString(paths)).with_styles(styles)
+    }
+
+    /// Малюнок з контурів області: зовнішні межі й отвори кожного многокутника.
+    fn outline(&self, region: MultiPolygon<f64>) -> Drawing {
+        let paths = region
+            .0
+            .into_iter()
+            .flat_map(|polygon| {
+                let (exterior, interiors) = polygon.into_inner();
+                std::iter::once(exterior).chain(interiors)
+            })
+            .collect();
+        Drawing::new(self.bounds, MultiLineString(paths))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drawing::style::PathStyle;
+    use geo::{Area, EuclideanLength, LineString};
+
+    fn square(x: f64, y: f64, size: f64) -> LineString<f64> {
+        LineString::from(vec![
+            (x, y),
+            (x + size, y),
+            (x + size, y + size),
+            (x, y + size),
+            (x, y),
+        ])
+    }
+
+    #[test]
+    fn test_boolean_operations_combine_closed_shapes() {
+        let a = Drawing::new(
+            (100.0, 100.0),
+            MultiLineString(vec![square(0.0, 0.0, 20.0)]),
+        );
+        let b = Drawing::new(
+            (100.0, 100.0),
+            MultiLineString(vec![square(10.0, 10.0, 20.0)]),
+        );
+        let area = |drawing: &Drawing| drawing.region().unsigned_area();
+
+        assert!((area(&a.union(&b)) - 700.0).abs() < 1e-9);
+        assert!((area(&a.intersection(&b)) - 100.0).abs() < 1e-9);
+        assert!((area(&a.difference(&b)) - 300.0).abs() < 1e-9);
+        assert_eq!(a.union(&b).bounds, a.bounds);
+
+        // Вкладений контур — отвір, як у літері «О»
+        let ring = Drawing::new(
+            (100.0, 100.0),
+            MultiLineString(vec![square(0.0, 0.0, 30.0), square(10.0, 10.0, 10.0)]),
+        );
+        assert!((area(&ring) - 800.0).abs() < 1e-9);
+        assert_eq!(
+            ring.union(&ring).paths.0.len(),
+            2,
+            "Отвір стає окремим контуром"
+        );
+
+        // Штрихування, з якого вирізано маску
+        let hatch = Drawing::new(
+            (100.0, 100.0),
+            MultiLineString(vec![
+                LineString::from(vec![(-10.0, 15.0), (40.0, 15.0)]),
+                LineString::from(vec![(-10.0, 50.0), (40.0, 50.0)]),
+            ]),
+        )
+        .with_styles(vec![
+            PathStyle {
+                color: Some("#ff0000".into()),
+                ..PathStyle::default()
+            },
+            PathStyle::default(),
+        ]);
+        let outside = hatch.clip(&ring, false);
+        let length: f64 = outside
+            .paths
+            .iter()
+            .map(|line| line.euclidean_length())
+            .sum();
+        assert!(
+            (length - 80.0).abs() < 1e-9,
+            "Перша лінія втрачає 20 мм в області, а в отворі лишається"
+        );
+        assert_eq!(outside.paths.0.len(), 4);
+        assert_eq!(outside.style(0).color.as_deref(), Some("#ff0000"));
+        assert!(
+            outside.style(3).is_plain(),
+            "Шматки зберігають стиль свого шляху"
+        );
+
+        let inside = hatch.clip(&ring, true);
+        assert_eq!(inside.paths.0.len(), 2, "Дві частини по обидва боки отвору");
+    }
+}
@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+
+use geo::{Coord, LineString, MultiLineString};
+
+use super::Drawing;
+use crate::raster::Raster;
+
+/// Ребро клітинки сітки, на якому лежить точка ізолінії.
+///
+/// Горизонтальне ребро з'єднує вузли (x, y) і (x + 1, y), вертикальне — (x, y) і (x, y + 1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Edge {
+    Horizontal(usize, usize),
+    Vertical(usize, usize),
+}
+
+/// Відрізки ізолінії в кожному з 16 випадків марширувальних квадратів.
+///
+/// Номер випадку складається з бітів вузлів вище рівня: 8 — лівий верхній, 4 — правий
+/// верхній, 2 — правий нижній, 1 — лівий нижній. Ребра клітинки: 0 — верхнє, 1 — праве,
+/// 2 — нижнє, 3 — ліве. Сідлові випадки 5 і 10 тут подано для центру нижче рівня.
+const CASES: [&[(u8, u8)]; 16] = [
+    &[],
+    &[(3, 2)],
+    &[(2, 1)],
+    &[(3, 1)],
+    &[(1, 0)],
+    &[(3, 2), (1, 0)],
+    &[(2, 0)],
+    &[(3, 0)],
+    &[(0, 3)],
+    &[(0, 2)],
+    &[(0, 3), (2, 1)],
+    &[(0, 1)],
+    &[(1, 3)],
+    &[(1, 2)],
+    &[(2, 3)],
+    &[],
+];
+
+/// Будує ізолінії скалярного поля методом марширувальних квадратів.
+///
+/// Вузлами сітки є центри пікселів, тож точка (x, y) малюнка відповідає точці (x, y)
+/// в `Raster::sample`, а межі малюнка дорівнюють розміру зображення в пікселях. Поле
+/// може бути яскравістю фото чи карти висот або значеннями шуму з `Raster::from_fn`.
+/// Ізолінії, що дійшли до краю сітки, лишаються відкритими, решта замикаються.
+///
+/// # Аргументи
+/// * `grid` - значення поля у вузлах сітки.
+/// * `levels` - рівні, для яких будуються ізолінії.
+///
+/// # Повертає
+/// * `Drawing` - ізолінії в пікселях сітки, рівень за рівнем.
+///
+/// # Приклад
+/// ```
+/// use rsaxi::drawing::contours;
+/// use rsaxi::raster::Raster;
+///
+/// // Пагорб з вершиною в центрі сітки
+/// let hill = Raster::from_fn(41, 41, |x, y| {
+///     let (dx, dy) = (x as f64 - 20.0, y as f64 - 20.0);
+///     (-(dx * dx + dy * dy) / 200.0).exp()
+/// });
+/// let drawing = contours(&hill, &[0.25, 0.5, 0.75]);
+/// assert_eq!(drawing.paths.0.len(), 3, "Одне кільце на кожен рівень");
+/// ```
+pub fn contours(grid: &Raster, levels: &[f64]) -> Drawing {
+    let paths = levels
+        .iter()
+        .flat_map(|&level| isolines(grid, level))
+        .collect();
+    Drawing::new(
+        (grid.width() as f64, grid.height() as f64),
+        MultiLineString(paths),
+    )
+}
+
+/// Ізолінії одного рівня.
+fn isolines(grid: &Raster, level: f64) -> Vec<LineString<f64>> {
+    let (width, height) = (grid.width(), grid.height());
+    if width < 2 || height < 2 {
+        return vec![];
+    }
+    let above = |x: usize, y: usize| grid.luminance(x, y) > level;
+
+    // Відрізки клітинок та відрізки, що торкаються кожного ребра
+    let mut segments: Vec<(Edge, Edge)> = vec![];
+    for y in 0..height - 1 {
+        for x in 0..width - 1 {
+            let case = (above(x, y) as usize) << 3
+                | (above(x + 1, y) as usize) << 2
+                | (above(x + 1, y + 1) as usize) << 1
+                | above(x, y + 1) as usize;
+            let edge = |side: u8| match side {
+                0 => Edge::Horizontal(x, y),
+                1 => Edge::Vertical(x + 1, y),
+                2 => Edge::Horizontal(x, y + 1),
+                _ => Edge::Vertical(x, y),
+            };
+            let center = (grid.luminance(x, y)
+                + grid.luminance(x + 1, y)
+                + grid.luminance(x + 1, y + 1)
+                + grid.luminance(x, y + 1))
+                / 4.0;
+            // Сідло з центром вище рівня з'єднує вузли вище рівня через центр
+            let pairs: &[(u8, u8)] = match case {
+                5 if center > level => &[(3, 0), (1, 2)],
+                10 if center > level => &[(0, 1), (2, 3)],
+                _ => CASES[case],
+            };
+            segments.extend(pairs.iter().map(|&(from, to)| (edge(from), edge(to))));
+        }
+    }
+    let mut touching: HashMap<Edge, Vec<usize>> = HashMap::new();
+    for (index, (from, to)) in segments.iter().enumerate() {
+        touching.entry(*from).or_default().push(index);
+        touching.entry(*to).or_default().push(index);
+    }
+
+    let point = |edge: Edge| {
+        let ((x0, y0), (x1, y1)) = match edge {
+            Edge::Horizontal(x, y) => ((x, y), (x + 1, y)),
+            Edge::Vertical(x, y) => ((x, y), (x, y + 1)),
+        };
+        let (a, b) = (grid.luminance(x0, y0), grid.luminance(x1, y1));
+        let t = ((level - a) / (b - a)).clamp(0.0, 1.0);
+        Coord {
+            x: x0 as f64 + 0.5 + (x1 as f64 - x0 as f64) * t,
+            y: y0 as f64 + 0.5 + (y1 as f64 - y0 as f64) * t,
+        }
+    };
+
+    // Спершу ланцюжки від країв сітки, де ребро має лише один відрізок, потім кільця
+    let mut used = vec![false; segments.len()];
+    let mut lines = vec![];
+    let open = (0..segments.len()).filter(|&index| touching[&segments[index].0].len() == 1);
+    let starts: Vec<usize> = open.chain(0..segments.len()).collect();
+    for start in starts {
+        if used[start] {
+            continue;
+        }
+        let mut edges = vec![segments[start].0];
+        let mut current = start;
+        loop {
+            used[current] = true;
+            let (from, to) = segments[current];
+            let next_edge = match edges.last() == Some(&from) {
+                true => to,
+                false => from,
+            };
+            edges.push(next_edge);
+            match touching[&next_edge].iter().find(|&&segment| !used[segment]) {
+                Some(&segment) => current = segment,
+                None => break,
+            }
+        }
+        lines.push(LineString(edges.into_iter().map(point).collect()));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drawing::shape::is_closed;
+
+    #[test]
+    fn test_contours_trace_level_sets() {
+        // Конус висотою 1 у центрі: ізолінія рівня h — коло радіусом 10·(1 - h)
+        let cone = Raster::from_fn(31, 31, |x, y| {
+            1.0 - (x as f64 - 15.0).hypot(y as f64 - 15.0) / 10.0
+        });
+        let drawing = contours(&cone, &[0.2, 0.5]);
+        assert_eq!(drawing.bounds, (31.0, 31.0));
+        assert_eq!(drawing.paths.0.len(), 2);
+        for (line, radius) in drawing.paths.0.iter().zip([8.0, 5.0]) {
+            assert!(is_closed(line), "Кільце всередині сітки замкнене");
+            for point in &line.0 {
+                let distance = (point.x - 15.5).hypot(point.y - 15.5);
+                assert!((distance - radius).abs() < 0.2, "Радіус {}", distance);
+            }
+        }
+
+        // Нахилена площина: одна відкрита лінія від краю до краю
+        let ramp = Raster::from_fn(5, 4, |x, _| x as f64);
+        let drawing = contours(&ramp, &[1.5]);
+        assert_eq!(drawing.paths.0.len(), 1);
+        let line = &drawing.paths.0[0];
+        assert!(!is_closed(line));
+        assert_eq!(line.0.len(), 4);
+        assert!(line.0.iter().all(|point| point.x == 2.0));
+
+        // Сідло: дві лінії, що не перетинаються
+        let saddle = Raster::new(2, 2, vec![1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(contours(&saddle, &[0.5]).paths.0.len(), 2);
+        assert!(contours(&ramp, &[10.0]).paths.0.is_empty());
+    }
+}
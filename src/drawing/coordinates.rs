@@ -0,0 +1,154 @@
+use geo::{AffineOps, AffineTransform};
+
+use super::Drawing;
+
+/// Кут малюнка, від якого відраховуються координати.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Origin {
+    #[default]
+    TopLeft, // Лівий верхній кут, y донизу, як у SVG і на аркуші плотера.
+    BottomLeft, // Лівий нижній кут, y догори, як у математичних координатах.
+}
+
+/// Система координат, у якій задано шляхи малюнка.
+///
+/// Аркуш плотера, як і SVG, має початок у лівому верхньому куті й вісь y донизу. Малюнки
+/// з генеративних сценаріїв часто простіше будувати з віссю y догори, а дзеркальне
+/// відбиття потрібне, наприклад, для перекладних зображень, що малюються навиворіт.
+/// Відбиття відбуваються в межах малюнка, тож він лишається на тому самому місці аркуша.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CoordinateSystem {
+    pub origin: Origin, // Кут, від якого відраховуються координати.
+    pub flip_x: bool,   // Дзеркально відбити малюнок зліва направо.
+    pub flip_y: bool,   // Дзеркально відбити малюнок згори вниз.
+}
+
+impl CoordinateSystem {
+    /// Чи збігається система з системою аркуша, тож малюнок не потрібно перетворювати.
+    pub fn is_paper(&self) -> bool {
+        self.mirror() == (false, false)
+    }
+
+    /// Перетворення з цієї системи в систему аркуша.
+    ///
+    /// # Аргументи
+    /// * `bounds` - межі малюнка (ширина, висота), в яких відбиваються осі.
+    ///
+    /// # Повертає
+    /// * `AffineTransform<f64>` - перетворення координат шляхів.
+    pub fn transform(&self, (width, height): (f64, f64)) -> AffineTransform<f64> {
+        let (mirror_x, mirror_y) = self.mirror();
+        let axis = |mirror: bool, size: f64| match mirror {
+            true => (-1.0, size),
+            false => (1.0, 0.0),
+        };
+        let ((a, x), (e, y)) = (axis(mirror_x, width), axis(mirror_y, height));
+        AffineTransform::new(a, 0.0, x, 0.0, e, y)
+    }
+
+    /// Які осі дзеркально відбиваються: початок унизу й `flip_y` скасовують одне одного.
+    fn mirror(&self) -> (bool, bool) {
+        (
+            self.flip_x,
+            (self.origin == Origin::BottomLeft) != self.flip_y,
+        )
+    }
+}
+
+impl Drawing {
+    /// Переводить малюнок із заданої системи координат у систему аркуша.
+    ///
+    /// # Аргументи
+    /// * `system` - система координат, у якій задано шляхи.
+    ///
+    /// # Повертає
+    /// * `Drawing` - малюнок з тими самими межами та стилями в координатах аркуша.
+    pub fn in_coordinates(&self, system: &CoordinateSystem) -> Drawing {
+        if system.is_paper() {
+            return self.clone();
+        }
+        Drawing {
+            paths: self.paths.affine_transform(&system.transform(self.bounds)),
+            bounds: self.bounds,
+            styles: self.styles.clone(),
+        }
+    }
+
+    /// Дзеркально відбиває малюнок зліва направо в його межах.
+    ///
+    /// Так малюють на звороті напівпрозорого паперу чи штампи й перекладні зображення,
+    /// які після перенесення мають читатися правильно.
+    ///
+    /// # Повертає
+    /// * `Drawing` - відбитий малюнок з тими самими межами та стилями.
+    pub fn mirror_x(&self) -> Drawing {
+        self.in_coordinates(&CoordinateSystem {
+            flip_x: true,
+            ..CoordinateSystem::default()
+        })
+    }
+
+    /// Дзеркально відбиває малюнок згори вниз у його межах.
+    ///
+    /// # Повертає
+    /// * `Drawing` - відбитий малюнок з тими самими межами та стилями.
+    pub fn mirror_y(&self) -> Drawing {
+        self.in_coordinates(&CoordinateSystem {
+            flip_y: true,
+            ..CoordinateSystem::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::{Coord, LineString, MultiLineString};
+
+    #[test]
+    fn test_coordinate_systems_map_to_paper() {
+        let drawing = Drawing::new(
+            (100.0, 50.0),
+            MultiLineString(vec![LineString::from(vec![(10.0, 5.0), (30.0, 20.0)])]),
+        );
+        let first = |system: CoordinateSystem| drawing.in_coordinates(&system).paths.0[0].0[0];
+
+        assert_eq!(
+            first(CoordinateSystem::default()),
+            Coord { x: 10.0, y: 5.0 }
+        );
+        let math = CoordinateSystem {
+            origin: Origin::BottomLeft,
+            ..CoordinateSystem::default()
+        };
+        assert_eq!(
+            first(math),
+            Coord { x: 10.0, y: 45.0 },
+            "y відраховується знизу"
+        );
+        let mirrored = CoordinateSystem {
+            flip_x: true,
+            ..CoordinateSystem::default()
+        };
+        assert_eq!(first(mirrored), Coord { x: 90.0, y: 5.0 });
+        let cancelled = CoordinateSystem {
+            flip_y: true,
+            ..math
+        };
+        assert!(
+            cancelled.is_paper(),
+            "Два відбиття по y скасовують одне одного"
+        );
+        assert_eq!(drawing.in_coordinates(&math).bounds, (100.0, 50.0));
+
+        assert_eq!(
+            drawing.mirror_x().paths.0[0].0[1],
+            Coord { x: 70.0, y: 20.0 }
+        );
+        assert_eq!(
+            drawing.mirror_y().paths.0[0].0[1],
+            Coord { x: 30.0, y: 30.0 }
+        );
+        assert_eq!(drawing.mirror_x().mirror_x().paths, drawing.paths);
+    }
+}
@@ -0,0 +1,205 @@
+use geo::{AffineOps, AffineTransform, LineString, MultiLineString};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::Drawing;
+use crate::tr;
+
+/// Відступ (в мм) каліброваного квадрата від дому каретки.
+const PATTERN_MARGIN: f64 = 10.0;
+
+/// Помилки обчислення поправки геометрії з вимірів.
+#[derive(Debug, Error)]
+pub enum CorrectionError {
+    /// Вимір має бути додатним скінченним числом.
+    #[error(
+        "{}",
+        tr!(
+            "Measured {} must be positive, got {}",
+            "Вимір {} має бути додатним, отримано {}",
+            .name,
+            .value
+        )
+    )]
+    InvalidMeasurement { name: &'static str, value: f64 },
+
+    /// Виміри не описують паралелограм, наприклад діагоналі не відповідають сторонам.
+    #[error(
+        "{}",
+        tr!(
+            "Measurements are inconsistent: the diagonals do not match the sides",
+            "Виміри суперечливі: діагоналі не відповідають сторонам"
+        )
+    )]
+    Inconsistent,
+}
+
+/// Афінна поправка геометрії плотера: масштаб кожної осі та перекіс між ними.
+///
+/// Ремені, що розтягнулися, чи каретка, що стоїть не під прямим кутом, малюють квадрат
+/// прямокутником або паралелограмом. Поправка застосовується до малюнка перед
+/// плануванням: `x' = scale_x·x + skew·y`, `y' = scale_y·y`, тож плотер зі своїм
+/// спотворенням малює саме те, що задано.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Correction {
+    pub scale_x: f64, // Множник координати x.
+    pub scale_y: f64, // Множник координати y.
+    pub skew: f64,    // Зсув x на міліметр y, що вирівнює перекіс осей.
+}
+
+impl Correction {
+    /// Обчислює поправку з вимірів намальованого `calibration_pattern`.
+    ///
+    /// Спотворення плотера моделюється як `x = a·x₀ + b·y₀`, `y = c·y₀`: `a` дає ширина,
+    /// різниця квадратів діагоналей — перекіс `b`, а висота разом із перекосом — `c`.
+    /// Поправка — обернене до цього перетворення.
+    ///
+    /// # Аргументи
+    /// * `size` - сторона квадрата, заданого в `calibration_pattern` (в мм).
+    /// * `width` - виміряна горизонтальна сторона (в мм).
+    /// * `height` - виміряна вертикальна сторона (в мм).
+    /// * `diagonal` - виміряна діагональ з верхнього лівого кута в нижній правий (в мм).
+    /// * `antidiagonal` - виміряна діагональ з верхнього правого кута в нижній лівий (в мм).
+    ///
+    /// # Повертає
+    /// * `Result<Correction, CorrectionError>` - поправка або помилка недійсних вимірів.
+    pub fn from_measurements(
+        size: f64,
+        width: f64,
+        height: f64,
+        diagonal: f64,
+        antidiagonal: f64,
+    ) -> Result<Correction, CorrectionError> {
+        let measurements = [
+            ("size", size),
+            ("width", width),
+            ("height", height),
+            ("diagonal", diagonal),
+            ("antidiagonal", antidiagonal),
+        ];
+        for (name, value) in measurements {
+            if !value.is_finite() || value <= 0.0 {
+                return Err(CorrectionError::InvalidMeasurement { name, value });
+            }
+        }
+
+        let a = width / size;
+        let b = (diagonal.powi(2) - antidiagonal.powi(2)) / (4.0 * a * size.powi(2));
+        let c_squared = (height / size).powi(2) - b.powi(2);
+        if c_squared <= 0.0 {
+            return Err(CorrectionError::Inconsistent);
+        }
+        let c = c_squared.sqrt();
+        Ok(Correction {
+            scale_x: 1.0 / a,
+            scale_y: 1.0 / c,
+            skew: -b / (a * c),
+        })
+    }
+
+    /// Афінне перетворення поправки для `geo`.
+    pub fn transform(&self) -> AffineTransform<f64> {
+        AffineTransform::new(self.scale_x, self.skew, 0.0, 0.0, self.scale_y, 0.0)
+    }
+}
+
+/// Калібрувальний малюнок: квадрат зі стороною `size` і обома діагоналями.
+///
+/// Квадрат починається за `PATTERN_MARGIN` мм від дому каретки. Його сторони та
+/// діагоналі вимірюються лінійкою й передаються в `Correction::from_measurements`.
+///
+/// # Аргументи
+/// * `size` - сторона квадрата (в мм).
+///
+/// # Повертає
+/// * `Drawing` - малюнок з трьох шляхів: контур і дві діагоналі.
+pub fn calibration_pattern(size: f64) -> Drawing {
+    let (near, far) = (PATTERN_MARGIN, PATTERN_MARGIN + size);
+    let paths = vec![
+        LineString::from(vec![
+            (near, near),
+            (far, near),
+            (far, far),
+            (near, far),
+            (near, near),
+        ]),
+        LineString::from(vec![(near, near), (far, far)]),
+        LineString::from(vec![(far, near), (near, far)]),
+    ];
+    Drawing::new(
+        (far + PATTERN_MARGIN, far + PATTERN_MARGIN),
+        MultiLineString(paths),
+    )
+}
+
+impl Drawing {
+    /// Застосовує поправку геометрії до шляхів малюнка.
+    ///
+    /// # Аргументи
+    /// * `correction` - поправка плотера, наприклад з конфігурації.
+    ///
+    /// # Повертає
+    /// * `Drawing` - малюнок із виправленими шляхами, тими самими межами та стилями.
+    pub fn corrected(&self, correction: &Correction) -> Drawing {
+        Drawing {
+            paths: self.paths.affine_transform(&correction.transform()),
+            bounds: self.bounds,
+            styles: self.styles.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::{Coord, EuclideanLength};
+
+    #[test]
+    fn test_correction_undoes_measured_distortion() {
+        // Плотер розтягує x на 2 %, стискає y на 3 % і перекошує осі
+        let machine = AffineTransform::new(1.02, 0.015, 0.0, 0.0, 0.97, 0.0);
+        let pattern = calibration_pattern(100.0);
+        let drawn = pattern.paths.affine_transform(&machine);
+
+        let outline = &drawn.0[0].0;
+        let side = |from: usize, to: usize| LineString::new(vec![outline[from], outline[to]]);
+        let correction = Correction::from_measurements(
+            100.0,
+            side(0, 1).euclidean_length(),
+            side(1, 2).euclidean_length(),
+            drawn.0[1].euclidean_length(),
+            drawn.0[2].euclidean_length(),
+        )
+        .expect("Виміри мають давати поправку");
+
+        // Виправлений малюнок після спотворення плотера збігається із заданим
+        let plotted = pattern
+            .corrected(&correction)
+            .paths
+            .affine_transform(&machine);
+        for (expected, actual) in pattern.paths.0.iter().zip(&plotted.0) {
+            for (&Coord { x, y }, point) in expected.0.iter().zip(&actual.0) {
+                assert!(
+                    (x - point.x).abs() < 1e-9 && (y - point.y).abs() < 1e-9,
+                    "Точка ({}, {}) намальована в ({}, {})",
+                    x,
+                    y,
+                    point.x,
+                    point.y
+                );
+            }
+        }
+
+        assert!(matches!(
+            Correction::from_measurements(100.0, 100.0, 100.0, 0.0, 141.0),
+            Err(CorrectionError::InvalidMeasurement {
+                name: "diagonal",
+                ..
+            })
+        ));
+        assert!(matches!(
+            Correction::from_measurements(100.0, 100.0, 10.0, 200.0, 10.0),
+            Err(CorrectionError::Inconsistent)
+        ));
+    }
+}
@@ -0,0 +1,176 @@
+use geo::{Coord, LineString, MultiLineString, Rect};
+
+use super::Drawing;
+
+impl Drawing {
+    /// Обрізає шляхи малюнка прямокутником, наприклад аркушем, на який малюнок не вміщується.
+    ///
+    /// Шлях, що виходить за межі й повертається, ділиться в точках перетину межі на
+    /// окремі шляхи, а не просто втрачає точки поза прямокутником, тож перо не проводить
+    /// лінію вздовж межі там, де її не було. Кожен шматок зберігає стиль свого шляху.
+    ///
+    /// # Аргументи
+    /// * `rect` - прямокутник, усередині якого лишаються шляхи (в мм).
+    ///
+    /// # Повертає
+    /// * `Drawing` - обрізані шляхи з межами цього малюнка.
+    pub fn clip_to_rect(&self, rect: Rect<f64>) -> Drawing {
+        let (mut paths, mut indices) = (vec![], vec![]);
+        for (index, line) in self.paths.0.iter().enumerate() {
+            for piece in clip_line(line, rect) {
+                paths.push(piece);
+                indices.push(index);
+            }
+        }
+        let styles = self.styles_for(indices);
+        Drawing::new(self.bounds, MultiLineString(paths)).with_styles(styles)
+    }
+}
+
+/// Частини ламаної всередині прямокутника.
+///
+/// Замкнений шлях, що починається всередині, не розривається в початковій точці:
+/// його останній шматок продовжується першим.
+///
+/// # Аргументи
+/// * `line` - ламана для обрізання.
+/// * `rect` - прямокутник, усередині якого лишаються частини.
+///
+/// # Повертає
+/// * `Vec<LineString<f64>>` - частини в порядку шляху; порожній, якщо шлях повністю зовні.
+pub(crate) fn clip_line(line: &LineString<f64>, rect: Rect<f64>) -> Vec<LineString<f64>> {
+    let mut pieces: Vec<Vec<Coord<f64>>> = vec![];
+    let mut current: Vec<Coord<f64>> = vec![];
+    for pair in line.0.windows(2) {
+        let Some((entry, exit, leaves)) = clip_segment(pair[0], pair[1], rect) else {
+            pieces.extend((!current.is_empty()).then(|| std::mem::take(&mut current)));
+            continue;
+        };
+        if current.last() != Some(&entry) {
+            pieces.extend((!current.is_empty()).then(|| std::mem::take(&mut current)));
+            current.push(entry);
+        }
+        if exit != entry {
+            current.push(exit);
+        }
+        if leaves {
+            pieces.push(std::mem::take(&mut current));
+        }
+    }
+    pieces.push(current);
+    pieces.retain(|piece| piece.len() >= 2);
+
+    let closed = line.0.len() > 2 && line.0.first() == line.0.last();
+    if closed && pieces.len() > 1 && pieces[0].first() == pieces.last().unwrap().last() {
+        let first = pieces.remove(0);
+        pieces.last_mut().unwrap().extend(&first[1..]);
+    }
+    pieces.into_iter().map(LineString).collect()
+}
+
+/// Частина відрізка всередині прямокутника за алгоритмом Ляна — Барскі.
+///
+/// # Повертає
+/// * `Option<(Coord<f64>, Coord<f64>, bool)>` - початок і кінець видимої частини та чи виходить
+///   відрізок за межу до свого кінця; `None`, якщо відрізок повністю зовні.
+fn clip_segment(
+    a: Coord<f64>,
+    b: Coord<f64>,
+    rect: Rect<f64>,
+) -> Option<(Coord<f64>, Coord<f64>, bool)> {
+    let delta = b - a;
+    let (min, max) = (rect.min(), rect.max());
+    let (mut enter, mut leave) = (0.0f64, 1.0f64);
+    for (p, q) in [
+        (-delta.x, a.x - min.x),
+        (delta.x, max.x - a.x),
+        (-delta.y, a.y - min.y),
+        (delta.y, max.y - a.y),
+    ] {
+        if p == 0.0 {
+            // Відрізок паралельний цій межі: або весь з внутрішнього боку, або зовні
+            if q < 0.0 {
+                return None;
+            }
+        } else if p < 0.0 {
+            enter = enter.max(q / p);
+        } else {
+            leave = leave.min(q / p);
+        }
+    }
+    if enter > leave {
+        return None;
+    }
+    // Кінці всередині прямокутника беруться точно, без похибки множення
+    let at = |t: f64| {
+        if t <= 0.0 {
+            a
+        } else if t >= 1.0 {
+            b
+        } else {
+            a + delta * t
+        }
+    };
+    Some((at(enter), at(leave), leave < 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drawing::style::PathStyle;
+    use geo::coord;
+
+    #[test]
+    fn test_clip_to_rect_splits_paths_at_the_border() {
+        let rect = Rect::new(coord! { x: 0.0, y: 0.0 }, coord! { x: 10.0, y: 10.0 });
+        let zigzag = LineString::from(vec![(2.0, 5.0), (18.0, 5.0), (18.0, 8.0), (2.0, 8.0)]);
+        let outside = LineString::from(vec![(20.0, 0.0), (30.0, 10.0)]);
+        let ring = LineString::from(vec![
+            (5.0, 5.0),
+            (15.0, 5.0),
+            (15.0, 7.0),
+            (5.0, 7.0),
+            (5.0, 5.0),
+        ]);
+        let drawing = Drawing::new(
+            (40.0, 40.0),
+            MultiLineString(vec![zigzag, outside.clone(), ring]),
+        )
+        .with_styles(vec![
+            PathStyle::default(),
+            PathStyle::default(),
+            PathStyle {
+                tool: Some(2),
+                ..PathStyle::default()
+            },
+        ]);
+
+        let clipped = drawing.clip_to_rect(rect);
+        let points = |index: usize| -> Vec<(f64, f64)> {
+            clipped.paths.0[index]
+                .0
+                .iter()
+                .map(|c| (c.x, c.y))
+                .collect()
+        };
+        assert_eq!(
+            clipped.paths.0.len(),
+            3,
+            "Шлях ділиться на межі, зовнішній зникає"
+        );
+        assert_eq!(points(0), [(2.0, 5.0), (10.0, 5.0)]);
+        assert_eq!(points(1), [(10.0, 8.0), (2.0, 8.0)]);
+        // Замкнений шлях, що виходить за межу, лишається одним шматком без розриву на початку
+        assert_eq!(
+            points(2),
+            [(10.0, 7.0), (5.0, 7.0), (5.0, 5.0), (10.0, 5.0)]
+        );
+        assert_eq!(clipped.style(2).tool, Some(2));
+        assert_eq!(clipped.bounds, drawing.bounds);
+
+        // Шлях повністю всередині не змінюється
+        let inside = Drawing::new((40.0, 40.0), MultiLineString(vec![outside]));
+        let moved = Rect::new(coord! { x: 0.0, y: 0.0 }, coord! { x: 40.0, y: 40.0 });
+        assert_eq!(inside.clip_to_rect(moved).paths, inside.paths);
+    }
+}
@@ -0,0 +1,115 @@
+use geo::{BoundingRect, Coord, LineString};
+
+use super::shape::{orientation, Orientation};
+use super::{distance, Drawing};
+
+/// Правило, за яким вирівнюється напрямок малювання шляхів.
+///
+/// Деякі пера кладуть лінію по-різному, коли їх ведуть до себе чи від себе, тож
+/// однаковий напрямок робить штрихи однаковими по всьому малюнку. Шляхи, для яких
+/// правило не визначає напрямку, залишаються як є.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathDirection {
+    LeftToRight, // Відкриті шляхи малюються зліва направо, а вертикальні — згори вниз.
+    Outward,     // Відкриті шляхи малюються від центру малюнка назовні.
+    Clockwise,   // Замкнені контури обходяться за годинниковою стрілкою на аркуші.
+}
+
+impl PathDirection {
+    /// Чи потрібно перевернути шлях, щоб він відповідав правилу.
+    ///
+    /// # Аргументи
+    /// * `line` - непорожній шлях малюнка.
+    /// * `center` - центр рамки всіх шляхів малюнка.
+    fn needs_reversal(&self, line: &LineString<f64>, center: Coord<f64>) -> bool {
+        let (first, last) = (line.0[0], line.0[line.0.len() - 1]);
+        match self {
+            PathDirection::LeftToRight => (last.x, last.y) < (first.x, first.y),
+            PathDirection::Outward => distance(last, center) < distance(first, center),
+            PathDirection::Clockwise => orientation(line) == Some(Orientation::CounterClockwise),
+        }
+    }
+}
+
+impl Drawing {
+    /// Вирівнює напрямок малювання шляхів за правилом `direction`.
+    ///
+    /// Шляхи лише перевертаються, тож їх порядок і стилі не змінюються. Напрямок варто
+    /// вирівнювати перед впорядкуванням і не дозволяти тому перевертати шляхи знову.
+    ///
+    /// # Аргументи
+    /// * `direction` - правило напрямку шляхів.
+    ///
+    /// # Повертає
+    /// * `Drawing` - новий малюнок з тими самими межами й шляхами в потрібному напрямку.
+    pub fn normalize_direction(&self, direction: PathDirection) -> Drawing {
+        let center = self
+            .paths
+            .bounding_rect()
+            .map_or(Coord::zero(), |bbox| bbox.center());
+        let mut drawing = self.clone();
+        for line in drawing.paths.0.iter_mut() {
+            if !line.0.is_empty() && direction.needs_reversal(line, center) {
+                line.0.reverse();
+            }
+        }
+        drawing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::MultiLineString;
+
+    #[test]
+    fn test_normalize_direction_reverses_only_mismatched_paths() {
+        let square = vec![
+            (0.0, 0.0),
+            (0.0, 10.0),
+            (10.0, 10.0),
+            (10.0, 0.0),
+            (0.0, 0.0),
+        ];
+        let drawing = Drawing::new(
+            (100.0, 100.0),
+            MultiLineString(vec![
+                LineString::from(vec![(30.0, 5.0), (20.0, 5.0)]),
+                LineString::from(vec![(40.0, 0.0), (40.0, 20.0)]),
+                LineString::from(square.clone()),
+                LineString::from(Vec::<(f64, f64)>::new()),
+            ]),
+        );
+
+        let left_to_right = drawing.normalize_direction(PathDirection::LeftToRight);
+        assert_eq!(left_to_right.paths.0[0].0[0], Coord { x: 20.0, y: 5.0 });
+        assert_eq!(
+            left_to_right.paths.0[1], drawing.paths.0[1],
+            "Вертикальний шлях згори вниз не перевертається"
+        );
+        assert_eq!(
+            left_to_right.paths.0[2], drawing.paths.0[2],
+            "Замкнений контур не має напрямку зліва направо"
+        );
+        assert!(left_to_right.paths.0[3].0.is_empty());
+
+        // Центр рамки шляхів — (20, 10), а кінці вертикального шляху від нього рівновіддалені
+        let outward = drawing.normalize_direction(PathDirection::Outward);
+        assert_eq!(outward.paths.0[0].0[0], Coord { x: 20.0, y: 5.0 });
+        assert_eq!(outward.paths.0[1], drawing.paths.0[1]);
+
+        // Вниз, праворуч, угору: на аркуші з віссю y донизу це проти годинникової стрілки
+        let clockwise = drawing.normalize_direction(PathDirection::Clockwise);
+        let mut reversed = square;
+        reversed.reverse();
+        assert_eq!(clockwise.paths.0[2], LineString::from(reversed));
+        assert_eq!(
+            clockwise.paths.0[0], drawing.paths.0[0],
+            "Відкриті шляхи не мають обходу"
+        );
+        assert_eq!(
+            orientation(&clockwise.paths.0[2]),
+            Some(Orientation::Clockwise)
+        );
+    }
+}
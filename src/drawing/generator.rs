@@ -0,0 +1,434 @@
+mod circles;
+mod lissajous;
+mod spiral;
+
+use std::collections::HashMap;
+
+use thiserror::Error;
+
+use super::Drawing;
+use crate::tr;
+
+pub use self::circles::Circles;
+pub use self::lissajous::Lissajous;
+pub use self::spiral::Spiral;
+
+/// Помилки вибору генератора та розбору його параметрів.
+#[derive(Debug, Error, PartialEq)]
+pub enum GeneratorError {
+    /// Генератора з такою назвою немає в реєстрі.
+    #[error("{}", tr!("Unknown generator '{}'", "Невідомий генератор '{}'", .0))]
+    UnknownGenerator(String),
+
+    /// Параметр не має вигляду `назва=значення`.
+    #[error(
+        "{}",
+        tr!(
+            "Parameter '{}' must look like name=value",
+            "Параметр '{}' має мати вигляд назва=значення",
+            .0
+        )
+    )]
+    Malformed(String),
+
+    /// Генератор не має такого параметра.
+    #[error(
+        "{}",
+        tr!(
+            "Generator '{}' has no parameter '{}'",
+            "Генератор '{}' не має параметра '{}'",
+            .generator,
+            .name
+        )
+    )]
+    UnknownParam { generator: String, name: String },
+
+    /// Значення параметра не відповідає його типу.
+    #[error(
+        "{}",
+        tr!(
+            "Parameter '{}' expects {}, got '{}'",
+            "Параметр '{}' очікує {}, а отримав '{}'",
+            .name,
+            .expected,
+            .value
+        )
+    )]
+    InvalidValue {
+        name: String,
+        expected: String,
+        value: String,
+    },
+
+    /// Числовий параметр поза дозволеними межами.
+    #[error(
+        "{}",
+        tr!(
+            "Parameter '{}' = {} is outside {}..{}",
+            "Параметр '{}' = {} поза межами {}..{}",
+            .name,
+            .value,
+            .min,
+            .max
+        )
+    )]
+    OutOfRange {
+        name: String,
+        value: f64,
+        min: f64,
+        max: f64,
+    },
+}
+
+/// Тип значення параметра генератора.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParamKind {
+    Number,  // Дійсне число.
+    Integer, // Ціле число.
+    Text,    // Довільний рядок.
+}
+
+impl ParamKind {
+    /// Назва типу для повідомлень і довідки.
+    pub fn name(&self) -> String {
+        match self {
+            ParamKind::Number => tr!("a number", "число"),
+            ParamKind::Integer => tr!("an integer", "ціле число"),
+            ParamKind::Text => tr!("text", "текст"),
+        }
+    }
+}
+
+/// Значення параметра після розбору.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParamValue {
+    Number(f64),
+    Integer(i64),
+    Text(String),
+}
+
+/// Опис одного параметра генератора: назва, тип, типове значення та межі.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Param {
+    pub name: &'static str,        // Назва параметра в `--param назва=значення`.
+    pub kind: ParamKind,           // Тип значення.
+    pub default: &'static str,     // Типове значення в тому самому записі, що й у командному рядку.
+    pub range: Option<(f64, f64)>, // Дозволені межі числового значення включно.
+    pub help: String,              // Опис параметра для довідки.
+}
+
+impl Param {
+    /// Дійсний параметр з типовим значенням.
+    pub fn number(name: &'static str, default: &'static str, help: String) -> Self {
+        Param {
+            name,
+            kind: ParamKind::Number,
+            default,
+            range: None,
+            help,
+        }
+    }
+
+    /// Цілий параметр з типовим значенням.
+    pub fn integer(name: &'static str, default: &'static str, help: String) -> Self {
+        Param {
+            kind: ParamKind::Integer,
+            ..Param::number(name, default, help)
+        }
+    }
+
+    /// Текстовий параметр з типовим значенням.
+    pub fn text(name: &'static str, default: &'static str, help: String) -> Self {
+        Param {
+            kind: ParamKind::Text,
+            ..Param::number(name, default, help)
+        }
+    }
+
+    /// Обмежує числове значення межами `min..=max`.
+    pub fn range(mut self, min: f64, max: f64) -> Self {
+        self.range = Some((min, max));
+        self
+    }
+
+    /// Розбирає значення параметра за його типом і межами.
+    fn parse(&self, value: &str) -> Result<ParamValue, GeneratorError> {
+        let invalid = || GeneratorError::InvalidValue {
+            name: self.name.to_string(),
+            expected: self.kind.name(),
+            value: value.to_string(),
+        };
+        let parsed = match self.kind {
+            ParamKind::Number => ParamValue::Number(
+                value
+                    .trim()
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|number| number.is_finite())
+                    .ok_or_else(invalid)?,
+            ),
+            ParamKind::Integer => ParamValue::Integer(value.trim().parse().map_err(|_| invalid())?),
+            ParamKind::Text => ParamValue::Text(value.to_string()),
+        };
+        let number = match parsed {
+            ParamValue::Number(number) => number,
+            ParamValue::Integer(integer) => integer as f64,
+            ParamValue::Text(_) => return Ok(parsed),
+        };
+        match self.range {
+            Some((min, max)) if !(min..=max).contains(&number) => Err(GeneratorError::OutOfRange {
+                name: self.name.to_string(),
+                value: number,
+                min,
+                max,
+            }),
+            _ => Ok(parsed),
+        }
+    }
+}
+
+/// Розібрані параметри генератора: задані користувачем і типові для решти.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Params {
+    values: HashMap<String, ParamValue>, // Значення за назвою параметра.
+}
+
+impl Params {
+    /// Розбирає пари `назва=значення` за схемою параметрів генератора.
+    ///
+    /// # Аргументи
+    /// * `generator` - назва генератора для повідомлень про помилки.
+    /// * `schema` - параметри генератора.
+    /// * `assignments` - пари `назва=значення`; пізніша пара перекриває попередню.
+    ///
+    /// # Повертає
+    /// * `Result<Params, GeneratorError>` - значення всіх параметрів схеми або помилка.
+    pub fn parse<'a>(
+        generator: &str,
+        schema: &[Param],
+        assignments: impl IntoIterator<Item = &'a str>,
+    ) -> Result<Params, GeneratorError> {
+        let mut values = HashMap::new();
+        for param in schema {
+            values.insert(param.name.to_string(), param.parse(param.default)?);
+        }
+        for assignment in assignments {
+            let (name, value) = assignment
+                .split_once('=')
+                .ok_or_else(|| GeneratorError::Malformed(assignment.to_string()))?;
+            let name = name.trim();
+            let param = schema
+                .iter()
+                .find(|param| param.name == name)
+                .ok_or_else(|| GeneratorError::UnknownParam {
+                    generator: generator.to_string(),
+                    name: name.to_string(),
+                })?;
+            values.insert(name.to_string(), param.parse(value)?);
+        }
+        Ok(Params { values })
+    }
+
+    /// Дійсне значення параметра; ціле значення перетворюється на дійсне.
+    ///
+    /// Панікує, якщо параметра немає в схемі генератора: це помилка самого генератора.
+    pub fn number(&self, name: &str) -> f64 {
+        match self.values.get(name) {
+            Some(ParamValue::Number(number)) => *number,
+            Some(ParamValue::Integer(integer)) => *integer as f64,
+            _ => panic!("Параметр '{}' не описаний як число", name),
+        }
+    }
+
+    /// Ціле значення параметра; панікує, якщо параметра немає в схемі як цілого.
+    pub fn integer(&self, name: &str) -> i64 {
+        match self.values.get(name) {
+            Some(ParamValue::Integer(integer)) => *integer,
+            _ => panic!("Параметр '{}' не описаний як ціле число", name),
+        }
+    }
+
+    /// Текстове значення параметра; панікує, якщо параметра немає в схемі як тексту.
+    pub fn text(&self, name: &str) -> &str {
+        match self.values.get(name) {
+            Some(ParamValue::Text(text)) => text,
+            _ => panic!("Параметр '{}' не описаний як текст", name),
+        }
+    }
+}
+
+/// Генератор малюнків з іменованими параметрами.
+///
+/// Новий генератор — це невеликий модуль з типом, що реалізує цей трейт, і рядок у
+/// `Registry::default`; командний рядок `generate` бере назву, довідку й параметри з
+/// самого генератора.
+pub trait Generator: Send + Sync {
+    /// Назва генератора в `generate <назва>`.
+    fn name(&self) -> &'static str;
+
+    /// Короткий опис генератора для списку генераторів.
+    fn description(&self) -> String;
+
+    /// Схема параметрів генератора.
+    fn params(&self) -> Vec<Param>;
+
+    /// Будує малюнок з параметрів, уже перевірених за схемою.
+    fn generate(&self, params: &Params) -> Drawing;
+}
+
+/// Реєстр генераторів за назвою.
+pub struct Registry {
+    generators: Vec<Box<dyn Generator>>, // Генератори в порядку реєстрації.
+}
+
+impl Default for Registry {
+    /// Реєстр з вбудованими генераторами.
+    fn default() -> Self {
+        let mut registry = Registry::empty();
+        registry.register(Spiral);
+        registry.register(Lissajous);
+        registry.register(Circles);
+        registry
+    }
+}
+
+impl Registry {
+    /// Порожній реєстр без жодного генератора.
+    pub fn empty() -> Self {
+        Registry { generators: vec![] }
+    }
+
+    /// Додає генератор; генератор з тією самою назвою замінюється.
+    pub fn register(&mut self, generator: impl Generator + 'static) {
+        self.generators
+            .retain(|existing| existing.name() != generator.name());
+        self.generators.push(Box::new(generator));
+    }
+
+    /// Генератор за назвою.
+    pub fn get(&self, name: &str) -> Option<&dyn Generator> {
+        self.generators
+            .iter()
+            .find(|generator| generator.name() == name)
+            .map(|generator| generator.as_ref())
+    }
+
+    /// Усі генератори в порядку реєстрації.
+    pub fn generators(&self) -> impl Iterator<Item = &dyn Generator> {
+        self.generators.iter().map(|generator| generator.as_ref())
+    }
+
+    /// Будує малюнок генератором `name` з параметрів `назва=значення`.
+    ///
+    /// # Аргументи
+    /// * `name` - назва генератора.
+    /// * `assignments` - пари `назва=значення`; решта параметрів отримує типові значення.
+    ///
+    /// # Повертає
+    /// * `Result<Drawing, GeneratorError>` - малюнок або помилка назви чи параметрів.
+    pub fn generate<'a>(
+        &self,
+        name: &str,
+        assignments: impl IntoIterator<Item = &'a str>,
+    ) -> Result<Drawing, GeneratorError> {
+        let generator = self
+            .get(name)
+            .ok_or_else(|| GeneratorError::UnknownGenerator(name.to_string()))?;
+        let params = Params::parse(name, &generator.params(), assignments)?;
+        Ok(generator.generate(&params))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::{LineString, MultiLineString};
+
+    /// Генератор квадрата, що показує, як додається власний генератор.
+    struct Square;
+
+    impl Generator for Square {
+        fn name(&self) -> &'static str {
+            "spiral"
+        }
+
+        fn description(&self) -> String {
+            "Квадрат".to_string()
+        }
+
+        fn params(&self) -> Vec<Param> {
+            vec![Param::number("size", "10", "Сторона".to_string()).range(1.0, 100.0)]
+        }
+
+        fn generate(&self, params: &Params) -> Drawing {
+            let size = params.number("size");
+            let square = LineString::from(vec![
+                (0.0, 0.0),
+                (size, 0.0),
+                (size, size),
+                (0.0, size),
+                (0.0, 0.0),
+            ]);
+            Drawing::new((size, size), MultiLineString(vec![square]))
+        }
+    }
+
+    #[test]
+    fn test_registry_parses_params_and_generates() {
+        let registry = Registry::default();
+        let names: Vec<&str> = registry
+            .generators()
+            .map(|generator| generator.name())
+            .collect();
+        assert_eq!(names, ["spiral", "lissajous", "circles"]);
+        for generator in registry.generators() {
+            let drawing = registry.generate(generator.name(), []).unwrap();
+            assert!(
+                !drawing.paths.0.is_empty(),
+                "{} з типовими параметрами",
+                generator.name()
+            );
+            assert!(drawing.bbox().is_some_and(|bbox| bbox.min().x >= -1e-9
+                && bbox.min().y >= -1e-9
+                && bbox.max().x <= drawing.bounds.0 + 1e-9
+                && bbox.max().y <= drawing.bounds.1 + 1e-9));
+        }
+
+        // Власний генератор замінює вбудований з тією самою назвою
+        let mut registry = Registry::default();
+        registry.register(Square);
+        assert_eq!(registry.generators().count(), 3);
+        let drawing = registry.generate("spiral", ["size=25"]).unwrap();
+        assert_eq!(drawing.bounds, (25.0, 25.0));
+        assert_eq!(
+            registry.generate("spiral", []).unwrap().bounds,
+            (10.0, 10.0)
+        );
+
+        assert_eq!(
+            registry.generate("spiral", ["size=200"]).unwrap_err(),
+            GeneratorError::OutOfRange {
+                name: "size".to_string(),
+                value: 200.0,
+                min: 1.0,
+                max: 100.0
+            }
+        );
+        assert!(matches!(
+            registry.generate("spiral", ["size"]),
+            Err(GeneratorError::Malformed(_))
+        ));
+        assert!(matches!(
+            registry.generate("spiral", ["colour=red"]),
+            Err(GeneratorError::UnknownParam { .. })
+        ));
+        assert!(matches!(
+            registry.generate("spiral", ["size=big"]),
+            Err(GeneratorError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            registry.generate("flowers", []),
+            Err(GeneratorError::UnknownGenerator(_))
+        ));
+    }
+}
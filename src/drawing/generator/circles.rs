@@ -0,0 +1,89 @@
+use geo::{Coord, MultiLineString, Rect};
+
+use super::{Generator, Param, Params};
+use crate::drawing::packing::CirclePacking;
+use crate::drawing::sample::Mark;
+use crate::drawing::Drawing;
+use crate::tr;
+
+/// Пакування кіл різних розмірів, що не перекриваються, у прямокутнику.
+pub struct Circles;
+
+impl Generator for Circles {
+    fn name(&self) -> &'static str {
+        "circles"
+    }
+
+    fn description(&self) -> String {
+        tr!(
+            "Rectangle packed with non-overlapping circles of different sizes",
+            "Прямокутник, заповнений колами різних розмірів, що не перекриваються"
+        )
+    }
+
+    fn params(&self) -> Vec<Param> {
+        vec![
+            Param::number("width", "150", tr!("Width (mm)", "Ширина (в мм)")).range(1.0, 2000.0),
+            Param::number("height", "150", tr!("Height (mm)", "Висота (в мм)")).range(1.0, 2000.0),
+            Param::number(
+                "min_radius",
+                "1",
+                tr!(
+                    "Smallest circle radius (mm)",
+                    "Найменший радіус кола (в мм)"
+                ),
+            )
+            .range(0.1, 100.0),
+            Param::number(
+                "max_radius",
+                "10",
+                tr!(
+                    "Largest circle radius (mm)",
+                    "Найбільший радіус кола (в мм)"
+                ),
+            )
+            .range(0.1, 500.0),
+            Param::number(
+                "skew",
+                "2",
+                tr!(
+                    "Radius distribution power: above 1 gives more small circles",
+                    "Степінь розподілу радіусів: більше 1 дає більше малих кіл"
+                ),
+            )
+            .range(0.1, 10.0),
+            Param::number(
+                "gap",
+                "0.5",
+                tr!("Gap between circles (mm)", "Проміжок між колами (в мм)"),
+            )
+            .range(0.0, 100.0),
+            Param::integer("seed", "0", tr!("Random seed", "Зерно випадковості"))
+                .range(0.0, u32::MAX as f64),
+        ]
+    }
+
+    fn generate(&self, params: &Params) -> Drawing {
+        let (width, height) = (params.number("width"), params.number("height"));
+        let packing = CirclePacking {
+            min_radius: params.number("min_radius"),
+            max_radius: params.number("max_radius"),
+            skew: params.number("skew"),
+            gap: params.number("gap"),
+            seed: params.integer("seed") as u64,
+        };
+        let region = Rect::new(
+            Coord { x: 0.0, y: 0.0 },
+            Coord {
+                x: width,
+                y: height,
+            },
+        );
+        let paths = packing
+            .pack(&region.to_polygon(), None)
+            .into_iter()
+            .flat_map(|(center, radius)| Mark::Circle(radius).paths(center))
+            .collect();
+        Drawing::new((width, height), MultiLineString(paths))
+    }
+}
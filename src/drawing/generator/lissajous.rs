@@ -0,0 +1,68 @@
+use std::f64::consts::{PI, TAU};
+
+use geo::{LineString, MultiLineString};
+
+use super::{Generator, Param, Params};
+use crate::drawing::Drawing;
+use crate::tr;
+
+/// Фігура Ліссажу: сума двох перпендикулярних коливань з різними частотами.
+pub struct Lissajous;
+
+impl Generator for Lissajous {
+    fn name(&self) -> &'static str {
+        "lissajous"
+    }
+
+    fn description(&self) -> String {
+        tr!(
+            "Lissajous figure of two perpendicular oscillations",
+            "Фігура Ліссажу з двох перпендикулярних коливань"
+        )
+    }
+
+    fn params(&self) -> Vec<Param> {
+        vec![
+            Param::number("width", "150", tr!("Width (mm)", "Ширина (в мм)")).range(1.0, 2000.0),
+            Param::number("height", "150", tr!("Height (mm)", "Висота (в мм)")).range(1.0, 2000.0),
+            Param::integer(
+                "a",
+                "3",
+                tr!("Horizontal frequency", "Частота по горизонталі"),
+            )
+            .range(1.0, 100.0),
+            Param::integer("b", "4", tr!("Vertical frequency", "Частота по вертикалі"))
+                .range(1.0, 100.0),
+            Param::number(
+                "phase",
+                "90",
+                tr!("Phase shift (degrees)", "Зсув фази (в градусах)"),
+            )
+            .range(-360.0, 360.0),
+            Param::integer("points", "2000", tr!("Number of points", "Кількість точок"))
+                .range(10.0, 1_000_000.0),
+        ]
+    }
+
+    fn generate(&self, params: &Params) -> Drawing {
+        let (width, height) = (params.number("width"), params.number("height"));
+        let (a, b) = (params.number("a"), params.number("b"));
+        let phase = params.number("phase") * PI / 180.0;
+        let count = params.integer("points") as usize;
+
+        // За цілих частот крива замикається за один період
+        let points: Vec<(f64, f64)> = (0..=count)
+            .map(|index| {
+                let t = TAU * index as f64 / count as f64;
+                (
+                    width / 2.0 * (1.0 + (a * t + phase).sin()),
+                    height / 2.0 * (1.0 + (b * t).sin()),
+                )
+            })
+            .collect();
+        Drawing::new(
+            (width, height),
+            MultiLineString(vec![LineString::from(points)]),
+        )
+    }
+}
@@ -0,0 +1,65 @@
+use std::f64::consts::TAU;
+
+use geo::{LineString, MultiLineString};
+
+use super::{Generator, Param, Params};
+use crate::drawing::Drawing;
+use crate::tr;
+
+/// Архімедова спіраль з рівним кроком між витками.
+pub struct Spiral;
+
+impl Generator for Spiral {
+    fn name(&self) -> &'static str {
+        "spiral"
+    }
+
+    fn description(&self) -> String {
+        tr!(
+            "Archimedean spiral with evenly spaced turns",
+            "Архімедова спіраль з рівним кроком між витками"
+        )
+    }
+
+    fn params(&self) -> Vec<Param> {
+        vec![
+            Param::number(
+                "radius",
+                "80",
+                tr!("Outer radius (mm)", "Зовнішній радіус (в мм)"),
+            )
+            .range(1.0, 1000.0),
+            Param::integer("turns", "20", tr!("Number of turns", "Кількість витків"))
+                .range(1.0, 1000.0),
+            Param::number(
+                "tolerance",
+                "0.2",
+                tr!(
+                    "Largest chord length (mm)",
+                    "Найбільша довжина хорди (в мм)"
+                ),
+            )
+            .range(0.01, 10.0),
+        ]
+    }
+
+    fn generate(&self, params: &Params) -> Drawing {
+        let radius = params.number("radius");
+        let turns = params.number("turns");
+        let tolerance = params.number("tolerance");
+
+        // Хорда зовнішнього витка найдовша, тож її довжина задає кількість точок
+        let count = ((TAU * radius * turns / tolerance).ceil() as usize).max(2);
+        let points: Vec<(f64, f64)> = (0..=count)
+            .map(|index| {
+                let t = index as f64 / count as f64;
+                let (angle, r) = (TAU * turns * t, radius * t);
+                (radius + r * angle.cos(), radius + r * angle.sin())
+            })
+            .collect();
+        Drawing::new(
+            (2.0 * radius, 2.0 * radius),
+            MultiLineString(vec![LineString::from(points)]),
+        )
+    }
+}
@@ -0,0 +1,120 @@
+use geo::{coord, BooleanOps, LineString, MultiLineString, MultiPolygon};
+
+use super::Drawing;
+
+impl Drawing {
+    /// Заповнює замкнені шляхи малюнка паралельними лініями штрихування.
+    ///
+    /// Області беруться з `fill_polygons`, тож лінії зупиняються на межах отворів
+    /// складених шляхів, як у літерах «О» чи «В». Лінії всіх областей лежать на одній
+    /// сітці, тож штрихування сусідніх фігур продовжує одне одного, а лінії кожної області
+    /// зберігають стиль її контуру.
+    ///
+    /// # Аргументи
+    /// * `spacing` - відстань між лініями (в мм); недодатна не дає жодної лінії.
+    /// * `angle` - кут ліній до осі x (в градусах), за годинниковою стрілкою на аркуші.
+    ///
+    /// # Повертає
+    /// * `Drawing` - лінії штрихування з межами цього малюнка.
+    pub fn hatch(&self, spacing: f64, angle: f64) -> Drawing {
+        let (mut paths, mut indices) = (vec![], vec![]);
+        if spacing > 0.0 && spacing.is_finite() {
+            let (sin, cos) = angle.to_radians().sin_cos();
+            for (index, polygon) in self.fill_polygons() {
+                // Проєкції контуру на напрямок ліній і на перпендикуляр до них
+                let (mut along, mut across) = ((f64::MAX, f64::MIN), (f64::MAX, f64::MIN));
+                for point in polygon.exterior().0.iter() {
+                    let (a, b) = (point.x * cos + point.y * sin, point.y * cos - point.x * sin);
+                    along = (along.0.min(a), along.1.max(a));
+                    across = (across.0.min(b), across.1.max(b));
+                }
+                let lines: Vec<LineString<f64>> = ((across.0 / spacing).ceil() as i64
+                    ..=(across.1 / spacing).floor() as i64)
+                    .map(|row| {
+                        let offset = row as f64 * spacing;
+                        let point = |a: f64| coord! { x: a * cos - offset * sin, y: a * sin + offset * cos };
+                        LineString::new(vec![point(along.0 - 1.0), point(along.1 + 1.0)])
+                    })
+                    .collect();
+                let pieces = MultiPolygon(vec![polygon]).clip(&MultiLineString(lines), false);
+                for piece in pieces.0.into_iter().filter(|piece| piece.0.len() >= 2) {
+                    paths.push(piece);
+                    indices.push(index);
+                }
+            }
+        }
+        let styles = self.styles_for(indices);
+        Drawing::new(self.bounds, MultiLineString(paths)).with_styles(styles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drawing::style::FillRule;
+    use geo::{Contains, EuclideanLength, Point, Polygon};
+
+    #[test]
+    fn test_hatch_leaves_holes_of_compound_paths() {
+        // Літера «О» з квадратів: внутрішній контур обходить проти годинникової стрілки
+        let letter = |rule: &str, inner: &str| {
+            let svg = format!(
+                r#"<svg viewBox="0 0 100 100"><path fill-rule="{}" d="M 1 1 H 31 V 31 H 1 Z {}"/>
+                <rect x="50" y="1" width="10" height="10"/></svg>"#,
+                rule, inner
+            );
+            Drawing::from_svg(&svg).expect("SVG має розбиратися")
+        };
+        let reversed = "M 11 11 V 21 H 21 V 11 Z";
+        let same = "M 11 11 H 21 V 21 H 11 Z";
+        let hole = Polygon::new(
+            LineString::from(vec![(11.0, 11.0), (21.0, 11.0), (21.0, 21.0), (11.0, 21.0)]),
+            vec![],
+        );
+        let length = |drawing: &Drawing| -> f64 {
+            drawing
+                .paths
+                .iter()
+                .map(|line| line.euclidean_length())
+                .sum()
+        };
+        let crosses_hole = |drawing: &Drawing| {
+            drawing.paths.iter().any(|line| {
+                line.0
+                    .windows(2)
+                    .any(|pair| hole.contains(&Point::from((pair[0] + pair[1]) / 2.0)))
+            })
+        };
+
+        let drawing = letter("nonzero", reversed);
+        assert_eq!(drawing.style(0).compound, drawing.style(1).compound);
+        assert_eq!(drawing.style(2).compound, None, "Квадрат поза літерою");
+        let regions = drawing.fill_polygons();
+        assert_eq!(regions.len(), 2);
+        assert_eq!((regions[0].0, regions[0].1.interiors().len()), (0, 1));
+
+        let hatched = drawing.hatch(2.0, 0.0);
+        assert_eq!(hatched.bounds, drawing.bounds);
+        assert!(!crosses_hole(&hatched), "Лінії зупиняються на межі отвору");
+        assert!(
+            (length(&hatched) - (15.0 * 30.0 - 5.0 * 10.0 + 5.0 * 10.0)).abs() < 1e-6,
+            "15 рядків літери, з яких 5 перетинають отвір, і 5 рядків квадрата"
+        );
+        assert_eq!(hatched.style(0).fill_rule, Some(FillRule::NonZero));
+
+        assert!(crosses_hole(&letter("nonzero", same).hatch(2.0, 0.0)));
+        let even_odd = letter("evenodd", same).hatch(2.0, 45.0);
+        assert!(
+            !crosses_hole(&even_odd),
+            "Для evenodd напрямок обходу не важить"
+        );
+        assert!(
+            even_odd.paths.iter().all(|line| {
+                let (a, b) = (line.0[0], line.0[line.0.len() - 1]);
+                ((b.y - a.y) - (b.x - a.x)).abs() < 1e-6
+            }),
+            "Лінії під кутом 45°"
+        );
+        assert!(drawing.hatch(0.0, 0.0).paths.0.is_empty());
+    }
+}
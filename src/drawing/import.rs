@@ -0,0 +1,1021 @@
+mod defs;
+mod hpgl;
+mod jsonl;
+mod transform;
+pub mod units;
+
+use std::cell::Cell;
+use std::collections::{HashMap, VecDeque};
+use std::f64::consts::PI;
+
+use geo::{coord, AffineOps, AffineTransform, Coord, LineString, MultiLineString, Rect};
+use svg::node::element::path::{Command, Data, Position};
+use svg::node::element::tag::Type;
+use svg::node::Attributes;
+use svg::parser::{Event, Parser};
+use thiserror::Error;
+
+use self::defs::{definitions, href, Element};
+use self::transform::{length_scale, parse_transform};
+use self::units::{document_frame, Unit};
+use super::crop::clip_line;
+use super::offset::width_passes;
+use super::shape::is_closed;
+use super::style::{property, PathStyle};
+use super::Drawing;
+use crate::tr;
+
+/// Довжина відрізка (в мм), якою апроксимуються криві та дуги.
+const CURVE_TOLERANCE: f64 = 0.5;
+
+/// Найбільша кількість відрізків на одну криву.
+const MAX_CURVE_SEGMENTS: usize = 256;
+
+/// Елементи, вміст яких не малюється на місці: його показують лише через `<use>` або він допоміжний.
+const NON_RENDERED: [&str; 9] = [
+    "defs", "symbol", "clipPath", "mask", "marker", "pattern", "title", "desc", "metadata",
+];
+
+/// Кількість пікселів на дюйм за замовчуванням, як у CSS.
+pub const DEFAULT_DPI: f64 = 96.0;
+
+/// Найбільша глибина вкладених `<use>`; глибші посилання, як і цикли, пропускаються.
+const MAX_USE_DEPTH: usize = 16;
+
+/// Помилки імпорту SVG, HPGL і рядків JSON.
+#[derive(Debug, Error)]
+pub enum ImportError {
+    /// Документ не вдалося розібрати як XML/SVG.
+    #[error("{}", tr!("Failed to parse SVG: {}", "Помилка розбору SVG: {}", .0))]
+    Parse(String),
+
+    /// Атрибут `d` елемента `<path>` містить недійсні дані.
+    #[error(
+        "{}",
+        tr!("Invalid path data '{}': {}", "Недійсні дані шляху '{}': {}", .data, .message)
+    )]
+    InvalidPathData { data: String, message: String },
+
+    /// Числовий атрибут фігури має недійсне значення.
+    #[error(
+        "{}",
+        tr!(
+            "Invalid value of attribute '{}': '{}'",
+            "Недійсне значення атрибута '{}': '{}'",
+            .attribute,
+            .value
+        )
+    )]
+    InvalidAttribute { attribute: String, value: String },
+
+    /// Довжина задана в невідомих одиницях.
+    #[error("{}", tr!("Unknown length unit '{}'", "Невідома одиниця довжини '{}'", .0))]
+    UnknownUnit(String),
+
+    /// Команда HPGL має недійсні параметри.
+    #[error("{}", tr!("Invalid HPGL command '{}'", "Недійсна команда HPGL '{}'", .0))]
+    InvalidHpgl(String),
+
+    /// Рядок JSON не є масивом точок `[x, y]`.
+    #[error(
+        "{}",
+        tr!(
+            "Line {} is not an array of [x, y] points: {}",
+            "Рядок {} не є масивом точок [x, y]: {}",
+            .line,
+            .message
+        )
+    )]
+    InvalidJsonLine { line: usize, message: String },
+}
+
+/// Налаштування переведення одиниць SVG у міліметри.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImportOptions {
+    pub dpi: f64,                // Кількість пікселів на дюйм для одиниць `px`.
+    pub units: Unit, // Одиниця чисел без позначки в `width`/`height` і у файлах без розмірів.
+    pub crop: Option<Rect<f64>>, // Прямокутник, яким обрізаються шляхи (в мм); без нього шляхи не обрізаються.
+    pub pen_width: Option<f64>, // Товщина пера (в мм), з якою товщі лінії імітуються паралельними проходами.
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        ImportOptions {
+            dpi: DEFAULT_DPI,
+            units: Unit::Mm,
+            crop: None,
+            pen_width: None,
+        }
+    }
+}
+
+impl Drawing {
+    /// Створює малюнок з вмісту SVG-документа.
+    ///
+    /// Підтримуються елементи `<path>`, `<line>`, `<polyline>`, `<polygon>`, `<rect>`,
+    /// `<circle>` та `<ellipse>`; криві Безьє та дуги апроксимуються відрізками.
+    /// Атрибути `transform` вкладених груп і фігур застосовуються до координат, `<use>`
+    /// розгортається в елемент, на який посилається, а елементи з `display:none` і вміст
+    /// `<defs>` не малюються.
+    /// Координати переводяться в міліметри за одиницями `width`/`height` і `viewBox`
+    /// (див. `from_svg_with`), числа без одиниць вважаються міліметрами, а піксель —
+    /// 1/96 дюйма. Колір і товщина лінії та номер інструмента елементів зберігаються як
+    /// стилі шляхів.
+    ///
+    /// # Аргументи
+    /// * `content` - текст SVG-документа.
+    ///
+    /// # Повертає
+    /// * `Result<Drawing, ImportError>` - малюнок зі шляхами документа або помилка розбору.
+    pub fn from_svg(content: &str) -> Result<Drawing, ImportError> {
+        Drawing::from_svg_with(content, &ImportOptions::default())
+    }
+
+    /// Створює малюнок з вмісту SVG-документа із заданим переведенням одиниць.
+    ///
+    /// Межами малюнка стають `width`/`height` кореневого елемента в міліметрах, а
+    /// `viewBox` вписується в них з урахуванням `preserveAspectRatio`, тож квадрат
+    /// `100mm` малюється завбільшки рівно 100 мм. Пікселі переводяться за `options.dpi`,
+    /// а числа без одиниць — за `options.units`. З `options.crop` шляхи обрізаються
+    /// прямокутником і діляться там, де перетинають його межу, а з `options.pen_width`
+    /// лінії, товщі за перо, імітуються паралельними проходами (див. `Drawing::emulate_widths`).
+    ///
+    /// # Аргументи
+    /// * `content` - текст SVG-документа.
+    /// * `options` - кількість пікселів на дюйм і одиниця чисел без позначки.
+    ///
+    /// # Повертає
+    /// * `Result<Drawing, ImportError>` - малюнок зі шляхами документа в мм або помилка розбору.
+    pub fn from_svg_with(content: &str, options: &ImportOptions) -> Result<Drawing, ImportError> {
+        let mut svg_paths = Drawing::svg_paths_with(content, options)?;
+        let (mut lines, mut styles) = (vec![], vec![]);
+        while let Some(styled) = svg_paths.next_styled() {
+            let (line, style) = styled?;
+            lines.push(line);
+            styles.push(style);
+        }
+        // Малюнок без жодного атрибута не тримає порожніх стилів
+        if styles.iter().all(PathStyle::is_plain) {
+            styles.clear();
+        }
+        let paths = MultiLineString(lines);
+        let bounds = svg_paths.bounds().unwrap_or_else(|| {
+            let bbox = Drawing::new((0.0, 0.0), paths.clone()).bbox();
+            bbox.map_or((0.0, 0.0), |rect| (rect.max().x, rect.max().y))
+        });
+
+        Ok(Drawing::new(bounds, paths).with_styles(styles))
+    }
+
+    /// Розбирає шляхи SVG-документа по одному, не збираючи їх у `MultiLineString`.
+    ///
+    /// Підтримує ті самі елементи, що й `from_svg`, але для великих генеративних
+    /// малюнків у пам'яті лишаються лише шлях поточного елемента, стан відкритих груп і
+    /// елементи, на які посилаються `<use>`.
+    ///
+    /// # Аргументи
+    /// * `content` - текст SVG-документа.
+    ///
+    /// # Повертає
+    /// * `Result<SvgPaths, ImportError>` - ітератор шляхів або помилка відкриття документа.
+    pub fn svg_paths(content: &str) -> Result<SvgPaths<'_>, ImportError> {
+        Drawing::svg_paths_with(content, &ImportOptions::default())
+    }
+
+    /// Розбирає шляхи SVG-документа по одному із заданим переведенням одиниць.
+    ///
+    /// # Аргументи
+    /// * `content` - текст SVG-документа.
+    /// * `options` - кількість пікселів на дюйм і одиниця чисел без позначки.
+    ///
+    /// # Повертає
+    /// * `Result<SvgPaths, ImportError>` - ітератор шляхів або помилка відкриття документа.
+    pub fn svg_paths_with<'a>(
+        content: &'a str,
+        options: &ImportOptions,
+    ) -> Result<SvgPaths<'a>, ImportError> {
+        let parser = svg::read(content).map_err(|e| ImportError::Parse(e.to_string()))?;
+        Ok(SvgPaths {
+            parser,
+            pending: VecDeque::new(),
+            bounds: None,
+            failed: false,
+            stack: vec![],
+            definitions: definitions(content),
+            options: *options,
+            compounds: Cell::new(0),
+        })
+    }
+}
+
+/// Ітератор шляхів SVG-документа, що розбирає документ у міру читання.
+///
+/// Після першої помилки ітератор повертає її й закінчується.
+pub struct SvgPaths<'a> {
+    parser: Parser<'a>,                              // Події документа.
+    pending: VecDeque<(LineString<f64>, PathStyle)>, // Решта шляхів поточного елемента.
+    bounds: Option<(f64, f64)>,                      // Розміри кореневого елемента `<svg>` (в мм).
+    failed: bool,                                    // Чи вже повернуто помилку.
+    stack: Vec<Context>, // Стан відкритих елементів від кореня документа.
+    definitions: HashMap<String, Element>, // Елементи, на які посилаються `<use>`, за `id`.
+    options: ImportOptions, // Переведення одиниць документа в міліметри.
+    compounds: Cell<usize>, // Скільки складених шляхів уже пронумеровано.
+}
+
+/// Стан відкритого елемента SVG, який успадковують його нащадки.
+#[derive(Debug, Clone)]
+struct Context {
+    transform: AffineTransform<f64>, // Перетворення з координат елемента в координати документа.
+    style: PathStyle,                // Стиль з урахуванням батьківських елементів.
+    hidden: bool, // Чи не малюється вміст: `display:none` або всередині `<defs>`.
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Context {
+            transform: AffineTransform::identity(),
+            style: PathStyle::default(),
+            hidden: false,
+        }
+    }
+}
+
+impl Context {
+    /// Стан дочірнього елемента з назвою `name` і атрибутами `attributes`.
+    fn child(&self, name: &str, attributes: &Attributes) -> Result<Context, ImportError> {
+        let mut transform = self.transform;
+        if let Some(value) = attributes.get("transform") {
+            let own = parse_transform(value).ok_or_else(|| ImportError::InvalidAttribute {
+                attribute: "transform".to_string(),
+                value: value.to_string(),
+            })?;
+            transform = transform.compose(&own);
+        }
+        // Зсув `<use>` діє після його власного перетворення
+        if name == "use" {
+            let offset =
+                AffineTransform::translate(number(attributes, "x")?, number(attributes, "y")?);
+            transform = transform.compose(&offset);
+        }
+        let hidden = self.hidden
+            || NON_RENDERED.contains(&name)
+            || property(attributes, "display").is_some_and(|display| display == "none");
+        Ok(Context {
+            transform,
+            style: PathStyle::from_attributes(attributes).inherit(&self.style),
+            hidden,
+        })
+    }
+
+    /// Шляхи фігури в координатах документа разом з її стилем.
+    ///
+    /// Товщина лінії масштабується разом із фігурою, а криві апроксимуються з однаковою
+    /// точністю в міліметрах за будь-якого масштабу.
+    fn paths(
+        &self,
+        name: &str,
+        attributes: &Attributes,
+    ) -> Result<Vec<(LineString<f64>, PathStyle)>, ImportError> {
+        let mut style = self.style.clone();
+        style.stroke_width = style
+            .stroke_width
+            .map(|width| width * length_scale(&self.transform));
+        let scale = length_scale(&self.transform);
+        let tolerance = match scale > 0.0 {
+            true => CURVE_TOLERANCE / scale,
+            false => CURVE_TOLERANCE,
+        };
+        let paths = element_paths(name, attributes, tolerance)?;
+        Ok(paths
+            .into_iter()
+            .map(|path| (path.affine_transform(&self.transform), style.clone()))
+            .collect())
+    }
+}
+
+impl SvgPaths<'_> {
+    /// Розміри документа в міліметрах, якщо кореневий елемент уже прочитано і їх задано.
+    pub fn bounds(&self) -> Option<(f64, f64)> {
+        self.bounds
+    }
+
+    /// Наступний шлях разом зі стилем елемента, з якого його прочитано.
+    pub fn next_styled(&mut self) -> Option<Result<(LineString<f64>, PathStyle), ImportError>> {
+        loop {
+            if let Some(styled) = self.pending.pop_front() {
+                return Some(Ok(styled));
+            }
+            if self.failed {
+                return None;
+            }
+
+            let result = match self.parser.next()? {
+                Event::Error(e) => Err(ImportError::Parse(e.to_string())),
+                Event::Tag(name, kind @ (Type::Start | Type::Empty), attributes) => {
+                    self.open(name, kind, &attributes)
+                }
+                Event::Tag(_, Type::End, _) => {
+                    self.stack.pop();
+                    Ok(())
+                }
+                _ => Ok(()),
+            };
+            if let Err(e) = result {
+                self.failed = true;
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+impl SvgPaths<'_> {
+    /// Обробляє початок елемента: додає його шляхи й відкриває його стан для нащадків.
+    fn open(&mut self, name: &str, kind: Type, attributes: &Attributes) -> Result<(), ImportError> {
+        let parent = match self.stack.last() {
+            Some(parent) => parent.clone(),
+            // Кореневий `<svg>` переводить одиниці користувача в міліметри
+            None if name == "svg" => {
+                let (bounds, transform) = document_frame(attributes, &self.options)?;
+                self.bounds = bounds;
+                Context {
+                    transform,
+                    ..Context::default()
+                }
+            }
+            None => Context::default(),
+        };
+        let context = parent.child(name, attributes)?;
+        if !context.hidden {
+            let mut paths = match name {
+                "use" => self.expand(attributes, &context, 0)?,
+                _ => self.shape(&context, name, attributes)?,
+            };
+            // Проходи товстої лінії обрізаються разом з нею
+            if let Some(pen_width) = self.options.pen_width {
+                paths = paths
+                    .iter()
+                    .flat_map(|(path, style)| width_passes(path, style, pen_width))
+                    .collect();
+            }
+            match self.options.crop {
+                Some(rect) => self
+                    .pending
+                    .extend(paths.into_iter().flat_map(|(path, style)| {
+                        clip_line(&path, rect)
+                            .into_iter()
+                            .map(move |piece| (piece, style.clone()))
+                    })),
+                None => self.pending.extend(paths),
+            }
+        }
+        if kind == Type::Start {
+            self.stack.push(context);
+        }
+        Ok(())
+    }
+
+    /// Шляхи фігури; підшляхи елемента з кількома замкненими контурами отримують спільний
+    /// номер складеного шляху, щоб заливка могла знайти в ньому отвори.
+    fn shape(
+        &self,
+        context: &Context,
+        name: &str,
+        attributes: &Attributes,
+    ) -> Result<Vec<(LineString<f64>, PathStyle)>, ImportError> {
+        let mut paths = context.paths(name, attributes)?;
+        if paths.iter().filter(|(path, _)| is_closed(path)).count() > 1 {
+            let compound = self.compounds.replace(self.compounds.get() + 1);
+            for (_, style) in paths.iter_mut() {
+                style.compound = Some(compound);
+            }
+        }
+        Ok(paths)
+    }
+
+    /// Шляхи елемента, на який посилається `<use>`, у стані самого `<use>`.
+    fn expand(
+        &self,
+        attributes: &Attributes,
+        context: &Context,
+        depth: usize,
+    ) -> Result<Vec<(LineString<f64>, PathStyle)>, ImportError> {
+        match href(attributes).and_then(|id| self.definitions.get(id)) {
+            Some(element) if depth < MAX_USE_DEPTH => self.render(element, context, depth),
+            _ => Ok(vec![]),
+        }
+    }
+
+    /// Шляхи збереженого елемента та його нащадків.
+    fn render(
+        &self,
+        element: &Element,
+        parent: &Context,
+        depth: usize,
+    ) -> Result<Vec<(LineString<f64>, PathStyle)>, ImportError> {
+        // Символ малюється як група, коли на нього посилаються
+        let name = match element.name.as_str() {
+            "symbol" => "g",
+            name => name,
+        };
+        let context = parent.child(name, &element.attributes)?;
+        if context.hidden {
+            return Ok(vec![]);
+        }
+        let mut paths = match name {
+            "use" => self.expand(&element.attributes, &context, depth + 1)?,
+            _ => self.shape(&context, name, &element.attributes)?,
+        };
+        for child in &element.children {
+            paths.extend(self.render(child, &context, depth)?);
+        }
+        Ok(paths)
+    }
+}
+
+impl Iterator for SvgPaths<'_> {
+    type Item = Result<LineString<f64>, ImportError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_styled()
+            .map(|styled| styled.map(|(path, _)| path))
+    }
+}
+
+/// Перетворює один елемент SVG на шляхи; непідтримувані елементи шляхів не мають.
+///
+/// Криві апроксимуються відрізками завдовжки `tolerance` в одиницях елемента.
+fn element_paths(
+    name: &str,
+    attributes: &Attributes,
+    tolerance: f64,
+) -> Result<Vec<LineString<f64>>, ImportError> {
+    let mut paths = Vec::new();
+    match name {
+        "path" => {
+            if let Some(data) = attributes.get("d") {
+                paths.extend(path_data(data, tolerance)?);
+            }
+        }
+        "line" => {
+            let line = LineString::new(vec![
+                coord! { x: number(attributes, "x1")?, y: number(attributes, "y1")? },
+                coord! { x: number(attributes, "x2")?, y: number(attributes, "y2")? },
+            ]);
+            paths.push(line);
+        }
+        "polyline" | "polygon" => {
+            let mut points = point_list(attributes.get("points").map_or("", |v| v))?;
+            if name == "polygon" && !points.is_empty() {
+                points.push(points[0]);
+            }
+            if points.len() > 1 {
+                paths.push(LineString::new(points));
+            }
+        }
+        "rect" => {
+            let x = number(attributes, "x")?;
+            let y = number(attributes, "y")?;
+            let width = number(attributes, "width")?;
+            let height = number(attributes, "height")?;
+            if width > 0.0 && height > 0.0 {
+                paths.push(LineString::new(vec![
+                    coord! { x: x, y: y },
+                    coord! { x: x + width, y: y },
+                    coord! { x: x + width, y: y + height },
+                    coord! { x: x, y: y + height },
+                    coord! { x: x, y: y },
+                ]));
+            }
+        }
+        "circle" | "ellipse" => {
+            let cx = number(attributes, "cx")?;
+            let cy = number(attributes, "cy")?;
+            let (rx, ry) = if name == "circle" {
+                let r = number(attributes, "r")?;
+                (r, r)
+            } else {
+                (number(attributes, "rx")?, number(attributes, "ry")?)
+            };
+            if rx > 0.0 && ry > 0.0 {
+                paths.push(ellipse(cx, cy, rx, ry, tolerance));
+            }
+        }
+        _ => {}
+    }
+    Ok(paths)
+}
+
+/// Зчитує числовий атрибут фігури; відсутній атрибут дорівнює нулю, як у SVG.
+fn number(attributes: &Attributes, name: &str) -> Result<f64, ImportError> {
+    match attributes.get(name) {
+        None => Ok(0.0),
+        Some(value) => {
+            let digits = value.trim().trim_end_matches("px");
+            digits
+                .parse::<f64>()
+                .map_err(|_| ImportError::InvalidAttribute {
+                    attribute: name.to_string(),
+                    value: value.to_string(),
+                })
+        }
+    }
+}
+
+/// Розбирає список чисел, розділених комами або пробілами.
+fn numbers(list: &str) -> Result<Vec<f64>, std::num::ParseFloatError> {
+    list.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .map(str::parse::<f64>)
+        .collect()
+}
+
+/// Розбирає атрибут `points` елементів `<polyline>` та `<polygon>`.
+fn point_list(points: &str) -> Result<Vec<Coord<f64>>, ImportError> {
+    let values = numbers(points).map_err(|_| ImportError::InvalidAttribute {
+        attribute: "points".to_string(),
+        value: points.to_string(),
+    })?;
+    Ok(values
+        .chunks_exact(2)
+        .map(|pair| coord! { x: pair[0], y: pair[1] })
+        .collect())
+}
+
+/// Апроксимує еліпс замкненою ламаною.
+fn ellipse(cx: f64, cy: f64, rx: f64, ry: f64, tolerance: f64) -> LineString<f64> {
+    let circumference = PI * (3.0 * (rx + ry) - ((3.0 * rx + ry) * (rx + 3.0 * ry)).sqrt());
+    let segments = curve_segments(circumference, tolerance).max(8);
+    LineString::new(
+        (0..=segments)
+            .map(|i| {
+                let angle = 2.0 * PI * i as f64 / segments as f64;
+                coord! { x: cx + rx * angle.cos(), y: cy + ry * angle.sin() }
+            })
+            .collect(),
+    )
+}
+
+/// Кількість відрізків завдовжки `tolerance` для кривої заданої (наближеної) довжини.
+fn curve_segments(length: f64, tolerance: f64) -> usize {
+    ((length / tolerance).ceil() as usize).clamp(1, MAX_CURVE_SEGMENTS)
+}
+
+/// Стан розбору даних шляху: поточна ламана та останні контрольні точки.
+struct PathBuilder {
+    paths: Vec<LineString<f64>>,   // Завершені ламані.
+    current: Vec<Coord<f64>>,      // Точки поточної ламаної.
+    position: Coord<f64>,          // Поточна точка пера.
+    start: Coord<f64>,             // Початок поточного підшляху для `Z`.
+    cubic: Option<Coord<f64>>,     // Друга контрольна точка попередньої кубічної кривої.
+    quadratic: Option<Coord<f64>>, // Контрольна точка попередньої квадратичної кривої.
+    tolerance: f64,                // Довжина відрізка апроксимації кривих.
+}
+
+impl PathBuilder {
+    fn new(tolerance: f64) -> Self {
+        PathBuilder {
+            paths: Vec::new(),
+            current: Vec::new(),
+            position: coord! { x: 0.0, y: 0.0 },
+            start: coord! { x: 0.0, y: 0.0 },
+            cubic: None,
+            quadratic: None,
+            tolerance,
+        }
+    }
+
+    /// Завершує поточну ламану, якщо в ній є хоча б один відрізок.
+    fn flush(&mut self) {
+        let points = std::mem::take(&mut self.current);
+        if points.len() > 1 {
+            self.paths.push(LineString::new(points));
+        }
+    }
+
+    fn move_to(&mut self, point: Coord<f64>) {
+        self.flush();
+        self.position = point;
+        self.start = point;
+        self.current.push(point);
+    }
+
+    fn line_to(&mut self, point: Coord<f64>) {
+        if self.current.is_empty() {
+            self.current.push(self.position);
+        }
+        self.current.push(point);
+        self.position = point;
+    }
+
+    fn cubic_to(&mut self, c1: Coord<f64>, c2: Coord<f64>, end: Coord<f64>) {
+        let p0 = self.position;
+        let length = distance(p0, c1) + distance(c1, c2) + distance(c2, end);
+        let segments = curve_segments(length, self.tolerance);
+        for i in 1..=segments {
+            let t = i as f64 / segments as f64;
+            let mt = 1.0 - t;
+            let point = p0 * (mt * mt * mt)
+                + c1 * (3.0 * mt * mt * t)
+                + c2 * (3.0 * mt * t * t)
+                + end * (t * t * t);
+            self.line_to(point);
+        }
+        self.position = end;
+    }
+
+    fn quadratic_to(&mut self, control: Coord<f64>, end: Coord<f64>) {
+        let p0 = self.position;
+        let length = distance(p0, control) + distance(control, end);
+        let segments = curve_segments(length, self.tolerance);
+        for i in 1..=segments {
+            let t = i as f64 / segments as f64;
+            let mt = 1.0 - t;
+            let point = p0 * (mt * mt) + control * (2.0 * mt * t) + end * (t * t);
+            self.line_to(point);
+        }
+        self.position = end;
+    }
+
+    /// Апроксимує еліптичну дугу за алгоритмом перетворення з кінцевих точок
+    /// до центральної параметризації (SVG 1.1, додаток F.6.5).
+    fn arc_to(
+        &mut self,
+        radii: (f64, f64),
+        rotation: f64,
+        large: bool,
+        sweep: bool,
+        end: Coord<f64>,
+    ) {
+        let start = self.position;
+        let (mut rx, mut ry) = (radii.0.abs(), radii.1.abs());
+        if rx == 0.0 || ry == 0.0 || distance(start, end) == 0.0 {
+            self.line_to(end);
+            return;
+        }
+
+        let (sin_phi, cos_phi) = rotation.to_radians().sin_cos();
+        let dx = (start.x - end.x) / 2.0;
+        let dy = (start.y - end.y) / 2.0;
+        let x1 = cos_phi * dx + sin_phi * dy;
+        let y1 = -sin_phi * dx + cos_phi * dy;
+
+        // Збільшуємо радіуси, якщо вони замалі для з'єднання точок
+        let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+        if lambda > 1.0 {
+            rx *= lambda.sqrt();
+            ry *= lambda.sqrt();
+        }
+
+        let numerator = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+        let denominator = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+        let mut factor = (numerator / denominator).max(0.0).sqrt();
+        if large == sweep {
+            factor = -factor;
+        }
+        let cx1 = factor * rx * y1 / ry;
+        let cy1 = -factor * ry * x1 / rx;
+        let cx = cos_phi * cx1 - sin_phi * cy1 + (start.x + end.x) / 2.0;
+        let cy = sin_phi * cx1 + cos_phi * cy1 + (start.y + end.y) / 2.0;
+
+        let angle =
+            |ux: f64, uy: f64, vx: f64, vy: f64| (ux * vy - uy * vx).atan2(ux * vx + uy * vy);
+        let theta = angle(1.0, 0.0, (x1 - cx1) / rx, (y1 - cy1) / ry);
+        let mut delta = angle(
+            (x1 - cx1) / rx,
+            (y1 - cy1) / ry,
+            (-x1 - cx1) / rx,
+            (-y1 - cy1) / ry,
+        );
+        if !sweep && delta > 0.0 {
+            delta -= 2.0 * PI;
+        } else if sweep && delta < 0.0 {
+            delta += 2.0 * PI;
+        }
+
+        let segments = curve_segments(delta.abs() * rx.max(ry), self.tolerance);
+        for i in 1..=segments {
+            let t = theta + delta * i as f64 / segments as f64;
+            let (sin_t, cos_t) = t.sin_cos();
+            let point = if i == segments {
+                end
+            } else {
+                coord! {
+                    x: cx + rx * cos_phi * cos_t - ry * sin_phi * sin_t,
+                    y: cy + rx * sin_phi * cos_t + ry * cos_phi * sin_t,
+                }
+            };
+            self.line_to(point);
+        }
+    }
+
+    /// Замикає підшлях відрізком до його початку, якщо перо ще не повернулося туди.
+    ///
+    /// Підшлях з однієї точки стає нульовим відрізком, тож перо ставить у ній крапку.
+    fn close(&mut self) {
+        if self.current.len() == 1 || self.current.last().is_some_and(|&last| last != self.start) {
+            self.line_to(self.start);
+        }
+        self.flush();
+        self.position = self.start;
+    }
+}
+
+fn distance(a: Coord<f64>, b: Coord<f64>) -> f64 {
+    (a.x - b.x).hypot(a.y - b.y)
+}
+
+/// Розбирає атрибут `d` елемента `<path>` у набір ламаних.
+fn path_data(data: &str, tolerance: f64) -> Result<Vec<LineString<f64>>, ImportError> {
+    let commands = Data::parse(data).map_err(|e| ImportError::InvalidPathData {
+        data: data.to_string(),
+        message: e.to_string(),
+    })?;
+    let mut builder = PathBuilder::new(tolerance);
+
+    for command in commands.iter() {
+        // Обчислює абсолютну точку з урахуванням типу позиції команди
+        let absolute = |position: &Position, origin: Coord<f64>, x: f64, y: f64| match position {
+            Position::Absolute => coord! { x: x, y: y },
+            Position::Relative => coord! { x: origin.x + x, y: origin.y + y },
+        };
+        let mut cubic = None;
+        let mut quadratic = None;
+
+        match command {
+            Command::Move(position, params) => {
+                for (i, pair) in params.chunks_exact(2).enumerate() {
+                    let point =
+                        absolute(position, builder.position, pair[0].into(), pair[1].into());
+                    // Пари координат після першої є неявними командами `L`
+                    if i == 0 {
+                        builder.move_to(point);
+                    } else {
+                        builder.line_to(point);
+                    }
+                }
+            }
+            Command::Line(position, params) => {
+                for pair in params.chunks_exact(2) {
+                    let point =
+                        absolute(position, builder.position, pair[0].into(), pair[1].into());
+                    builder.line_to(point);
+                }
+            }
+            Command::HorizontalLine(position, params) => {
+                for &x in params.iter() {
+                    let point = match position {
+                        Position::Absolute => coord! { x: x.into(), y: builder.position.y },
+                        Position::Relative => {
+                            coord! { x: builder.position.x + f64::from(x), y: builder.position.y }
+                        }
+                    };
+                    builder.line_to(point);
+                }
+            }
+            Command::VerticalLine(position, params) => {
+                for &y in params.iter() {
+                    let point = match position {
+                        Position::Absolute => coord! { x: builder.position.x, y: y.into() },
+                        Position::Relative => {
+                            coord! { x: builder.position.x, y: builder.position.y + f64::from(y) }
+                        }
+                    };
+                    builder.line_to(point);
+                }
+            }
+            Command::CubicCurve(position, params) => {
+                for p in params.chunks_exact(6) {
+                    let origin = builder.position;
+                    let c1 = absolute(position, origin, p[0].into(), p[1].into());
+                    let c2 = absolute(position, origin, p[2].into(), p[3].into());
+                    let end = absolute(position, origin, p[4].into(), p[5].into());
+                    builder.cubic_to(c1, c2, end);
+                    cubic = Some(c2);
+                }
+            }
+            Command::SmoothCubicCurve(position, params) => {
+                for p in params.chunks_exact(4) {
+                    let origin = builder.position;
+                    // Перша контрольна точка — відображення попередньої відносно поточної
+                    let c1 = match cubic.or(builder.cubic) {
+                        Some(previous) => origin * 2.0 - previous,
+                        None => origin,
+                    };
+                    let c2 = absolute(position, origin, p[0].into(), p[1].into());
+                    let end = absolute(position, origin, p[2].into(), p[3].into());
+                    builder.cubic_to(c1, c2, end);
+                    cubic = Some(c2);
+                }
+            }
+            Command::QuadraticCurve(position, params) => {
+                for p in params.chunks_exact(4) {
+                    let origin = builder.position;
+                    let control = absolute(position, origin, p[0].into(), p[1].into());
+                    let end = absolute(position, origin, p[2].into(), p[3].into());
+                    builder.quadratic_to(control, end);
+                    quadratic = Some(control);
+                }
+            }
+            Command::SmoothQuadraticCurve(position, params) => {
+                for p in params.chunks_exact(2) {
+                    let origin = builder.position;
+                    let control = match quadratic.or(builder.quadratic) {
+                        Some(previous) => origin * 2.0 - previous,
+                        None => origin,
+                    };
+                    let end = absolute(position, origin, p[0].into(), p[1].into());
+                    builder.quadratic_to(control, end);
+                    quadratic = Some(control);
+                }
+            }
+            Command::EllipticalArc(position, params) => {
+                for p in params.chunks_exact(7) {
+                    let end = absolute(position, builder.position, p[5].into(), p[6].into());
+                    builder.arc_to(
+                        (p[0].into(), p[1].into()),
+                        p[2].into(),
+                        p[3] != 0.0,
+                        p[4] != 0.0,
+                        end,
+                    );
+                }
+            }
+            Command::Close => builder.close(),
+        }
+
+        builder.cubic = cubic;
+        builder.quadratic = quadratic;
+    }
+
+    builder.flush();
+    Ok(builder.paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_basic_svg_shapes() {
+        let content = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 100 50">
+            <path d="M 10 10 L 20 10 l 0 10 Z"/>
+            <rect x="30" y="5" width="10" height="20"/>
+            <circle cx="70" cy="25" r="10"/>
+            <path d="M 0 40 C 10 30 20 50 30 40 A 5 5 0 0 1 40 40"/>
+        </svg>"#;
+        let drawing = Drawing::from_svg(content).expect("SVG має розбиратися");
+        assert_eq!(drawing.bounds, (100.0, 50.0));
+        assert_eq!(drawing.paths.0.len(), 4);
+
+        let triangle = &drawing.paths.0[0];
+        assert_eq!(triangle.0.len(), 4);
+        assert_eq!(triangle.0.first(), triangle.0.last());
+
+        let circle = drawing.paths.0[2].0.clone();
+        assert!(circle
+            .iter()
+            .all(|c| ((c.x - 70.0).hypot(c.y - 25.0) - 10.0).abs() < 1e-9));
+
+        let curve = drawing.paths.0[3].0.clone();
+        let end = curve.last().expect("Крива не порожня");
+        assert!((end.x - 40.0).abs() < 1e-9 && (end.y - 40.0).abs() < 1e-9);
+        let bbox = drawing.bbox().expect("Малюнок не порожній");
+        assert!(bbox.max().y > 40.0, "Дуга має опускатися нижче хорди");
+    }
+
+    #[test]
+    fn test_svg_paths_stream_matches_import() {
+        let content = r#"<svg viewBox="0 0 80 40">
+            <line x1="0" y1="0" x2="10" y2="10"/>
+            <path d="M 20 20 L 30 20 M 40 20 L 50 30"/>
+            <polygon points="60,0 70,0 70,10"/>
+        </svg>"#;
+        let drawing = Drawing::from_svg(content).expect("SVG має розбиратися");
+        let mut stream = Drawing::svg_paths(content).expect("SVG має відкриватися");
+        assert_eq!(stream.bounds(), None, "Документ ще не прочитано");
+
+        let first = stream
+            .next()
+            .expect("Має бути шлях")
+            .expect("Шлях має розбиратися");
+        assert_eq!(first, drawing.paths.0[0]);
+        assert_eq!(stream.bounds(), Some((80.0, 40.0)));
+        let rest: Vec<_> = stream.map(|path| path.unwrap()).collect();
+        assert_eq!(
+            rest,
+            drawing.paths.0[1..],
+            "Підшляхи одного елемента йдуть поспіль"
+        );
+
+        // Після помилки потік закінчується
+        let broken = r#"<svg><line x1="0" y1="0" x2="1" y2="1"/><path d="M 1 x"/><line x1="0" y1="0" x2="2" y2="2"/></svg>"#;
+        let results: Vec<_> = Drawing::svg_paths(broken).unwrap().collect();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_ok() && results[1].is_err());
+    }
+
+    #[test]
+    fn test_import_resolves_groups_and_use() {
+        let content = r##"<svg xmlns:xlink="http://www.w3.org/1999/xlink" viewBox="0 0 200 200">
+            <defs>
+                <path id="tick" d="M 0 0 L 0 5" stroke="red"/>
+                <symbol id="mark"><use href="#tick" x="1"/></symbol>
+            </defs>
+            <g transform="translate(100, 0)" stroke="blue" stroke-width="1">
+                <g transform="scale(2)">
+                    <line x1="0" y1="0" x2="10" y2="0"/>
+                </g>
+                <use xlink:href="#tick" x="10" y="20"/>
+            </g>
+            <g style="display:none"><line x1="0" y1="0" x2="1" y2="1"/></g>
+            <use href="#mark" transform="translate(50 50)"/>
+            <use href="#missing"/>
+        </svg>"##;
+        let drawing = Drawing::from_svg(content).expect("SVG має розбиратися");
+        let points: Vec<Vec<(f64, f64)>> = drawing
+            .paths
+            .0
+            .iter()
+            .map(|line| line.0.iter().map(|c| (c.x, c.y)).collect())
+            .collect();
+        assert_eq!(
+            points,
+            [
+                vec![(100.0, 0.0), (120.0, 0.0)],
+                vec![(110.0, 20.0), (110.0, 25.0)],
+                vec![(51.0, 50.0), (51.0, 55.0)],
+            ],
+            "Перетворення груп і зсуви <use> накладаються, а прихована група та <defs> не малюються"
+        );
+        assert_eq!(drawing.style(0).color.as_deref(), Some("blue"));
+        assert_eq!(
+            drawing.style(0).stroke_width,
+            Some(2.0),
+            "Товщина масштабується з групою"
+        );
+        assert_eq!(
+            drawing.style(1).color.as_deref(),
+            Some("red"),
+            "Власний колір важливіший за груповий"
+        );
+
+        let streamed: Vec<_> = Drawing::svg_paths(content)
+            .unwrap()
+            .map(|path| path.unwrap())
+            .collect();
+        assert_eq!(streamed, drawing.paths.0);
+        assert!(Drawing::from_svg(r#"<svg><g transform="spin(3)"/></svg>"#).is_err());
+    }
+
+    #[test]
+    fn test_compound_paths_split_at_moveto_and_close_rings() {
+        // Літера «O» з отвором, а потім піктограма з відносними командами після `z`
+        let content = r#"<svg viewBox="0 0 100 100">
+            <path d="M 0 0 H 30 V 40 H 0 Z M 10 10 V 30 H 20 V 10 L 10 10 Z"/>
+            <path d="m 50 50 h 10 v 10 z m 20 0 h 10 l -5 -5 z l 0 5 M 90 90 Z"/>
+        </svg>"#;
+        let drawing = Drawing::from_svg(content).expect("SVG має розбиратися");
+        let points: Vec<Vec<(f64, f64)>> = drawing
+            .paths
+            .0
+            .iter()
+            .map(|line| line.0.iter().map(|c| (c.x, c.y)).collect())
+            .collect();
+        assert_eq!(
+            points,
+            [
+                vec![
+                    (0.0, 0.0),
+                    (30.0, 0.0),
+                    (30.0, 40.0),
+                    (0.0, 40.0),
+                    (0.0, 0.0)
+                ],
+                vec![
+                    (10.0, 10.0),
+                    (10.0, 30.0),
+                    (20.0, 30.0),
+                    (20.0, 10.0),
+                    (10.0, 10.0)
+                ],
+                vec![(50.0, 50.0), (60.0, 50.0), (60.0, 60.0), (50.0, 50.0)],
+                vec![(70.0, 50.0), (80.0, 50.0), (75.0, 45.0), (70.0, 50.0)],
+                vec![(70.0, 50.0), (70.0, 55.0)],
+                vec![(90.0, 90.0), (90.0, 90.0)],
+            ],
+            "Кожне `M` піднімає перо, `Z` замикає кільце без зайвої точки, а після `z` \
+             відлік іде від початку підшляху"
+        );
+        assert_eq!(
+            drawing.closed_paths(),
+            [0, 1, 2, 3],
+            "Контур і отвір літери — окремі кільця, а крапка кільцем не є"
+        );
+    }
+
+    #[test]
+    fn test_import_rejects_malformed_svg() {
+        assert!(Drawing::from_svg(r#"<svg><path d="M 10 10 L x"/></svg>"#).is_err());
+        assert!(Drawing::from_svg(r#"<svg><circle cx="a" r="1"/></svg>"#).is_err());
+    }
+}
@@ -0,0 +1,99 @@
+use std::collections::{HashMap, HashSet};
+
+use svg::node::element::tag::Type;
+use svg::node::Attributes;
+use svg::parser::Event;
+
+/// Елемент SVG разом із нащадками, на який посилається `<use>`.
+#[derive(Debug, Clone)]
+pub(super) struct Element {
+    pub name: String,           // Назва елемента, наприклад `g` або `path`.
+    pub attributes: Attributes, // Атрибути елемента.
+    pub children: Vec<Element>, // Вкладені елементи в порядку документа.
+}
+
+/// Номер елемента з посилання `href` або `xlink:href` елемента `<use>`.
+pub(super) fn href(attributes: &Attributes) -> Option<&str> {
+    attributes
+        .get("href")
+        .or_else(|| attributes.get("xlink:href"))
+        .and_then(|href| href.trim().strip_prefix('#'))
+}
+
+/// Збирає елементи, на які посилаються `<use>`, щоб розгортати посилання під час розбору.
+///
+/// Документ читається двічі: спершу збираються посилання, потім — лише потрібні
+/// елементи з нащадками, тож решта документа в пам'яті не лишається. Документ без
+/// `<use>` не читається зовсім. Помилки розбору тут пропускаються, їх повідомить
+/// основний розбір.
+///
+/// # Аргументи
+/// * `content` - текст SVG-документа.
+///
+/// # Повертає
+/// * `HashMap<String, Element>` - елементи за їх `id`.
+pub(super) fn definitions(content: &str) -> HashMap<String, Element> {
+    let mut definitions = HashMap::new();
+    if !content.contains("<use") {
+        return definitions;
+    }
+    let Ok(parser) = svg::read(content) else {
+        return definitions;
+    };
+    let referenced: HashSet<String> = parser
+        .filter_map(|event| match event {
+            Event::Tag("use", Type::Start | Type::Empty, attributes) => {
+                href(&attributes).map(str::to_string)
+            }
+            _ => None,
+        })
+        .collect();
+    if referenced.is_empty() {
+        return definitions;
+    }
+    let Ok(parser) = svg::read(content) else {
+        return definitions;
+    };
+
+    // Відкриті елементи, що записуються: потрібний елемент і всі його нащадки
+    let mut open: Vec<Element> = vec![];
+    let mut close = |element: Element, open: &mut Vec<Element>| {
+        if let Some(id) = element.attributes.get("id") {
+            if referenced.contains(&**id) {
+                definitions.insert(id.to_string(), element.clone());
+            }
+        }
+        if let Some(parent) = open.last_mut() {
+            parent.children.push(element);
+        }
+    };
+    for event in parser {
+        match event {
+            Event::Tag(name, kind @ (Type::Start | Type::Empty), attributes) => {
+                let wanted = attributes
+                    .get("id")
+                    .is_some_and(|id| referenced.contains(&**id));
+                if open.is_empty() && !wanted {
+                    continue;
+                }
+                let element = Element {
+                    name: name.to_string(),
+                    attributes,
+                    children: vec![],
+                };
+                if kind == Type::Start {
+                    open.push(element);
+                } else {
+                    close(element, &mut open);
+                }
+            }
+            Event::Tag(_, Type::End, _) => {
+                if let Some(element) = open.pop() {
+                    close(element, &mut open);
+                }
+            }
+            _ => {}
+        }
+    }
+    definitions
+}
@@ -0,0 +1,216 @@
+use geo::{Coord, LineString, MultiLineString};
+
+use super::ImportError;
+use crate::drawing::style::PathStyle;
+use crate::drawing::Drawing;
+
+/// Довжина одиниці плотера HPGL (в мм).
+const PLOTTER_UNIT: f64 = 0.025;
+
+/// Команди, з яких зазвичай починається файл HPGL.
+const OPENING_COMMANDS: [&str; 6] = ["IN", "DF", "PU", "PD", "PA", "SP"];
+
+/// Символ, яким закінчується текст команди `LB`.
+const LABEL_TERMINATOR: char = '\u{3}';
+
+/// Стан пера під час розбору HPGL.
+#[derive(Default)]
+struct Plotter {
+    position: Coord<f64>,     // Поточне положення (в одиницях плотера).
+    relative: bool,           // Чи задано координати відносно положення (`PR`).
+    down: bool,               // Чи опущене перо.
+    tool: Option<u32>,        // Вибране перо (`SP`); 0 означає без пера.
+    current: Vec<Coord<f64>>, // Точки шляху, що малюється.
+    paths: Vec<(Vec<Coord<f64>>, Option<u32>)>, // Завершені шляхи з пером, яким їх намальовано.
+}
+
+impl Plotter {
+    /// Завершує шлях, що малюється, якщо в ньому є хоч один відрізок.
+    fn finish(&mut self) {
+        let points = std::mem::take(&mut self.current);
+        if points.len() >= 2 {
+            self.paths.push((points, self.tool));
+        }
+    }
+
+    /// Переміщує перо в точки команди, малюючи, якщо перо опущене.
+    fn travel(&mut self, points: &[Coord<f64>]) {
+        if self.down && self.current.is_empty() {
+            self.current.push(self.position);
+        }
+        for &point in points {
+            self.position = match self.relative {
+                true => self.position + point,
+                false => point,
+            };
+            if self.down {
+                self.current.push(self.position);
+            }
+        }
+    }
+}
+
+impl Drawing {
+    /// Чи схожий вміст файлу на HPGL, а не на SVG чи завдання.
+    pub fn is_hpgl(content: &str) -> bool {
+        let head = content.trim_start().as_bytes();
+        head.len() >= 2
+            && OPENING_COMMANDS
+                .iter()
+                .any(|command| head[..2].eq_ignore_ascii_case(command.as_bytes()))
+    }
+
+    /// Створює малюнок з команд HPGL, якими інші програми пишуть файли для перових плотерів.
+    ///
+    /// Розуміються `IN`, `PU`, `PD`, `PA`, `PR` і `SP`: номер пера стає інструментом шляху, як
+    /// атрибут `data-tool` у SVG. Решта команд, зокрема текст `LB`, пропускається. Вісь y у
+    /// HPGL направлена вгору, тож малюнок перевертається так, що верх рисунка стає верхом
+    /// аркуша, а межами малюнка стає рамка від початку координат до найдальшої точки.
+    ///
+    /// # Аргументи
+    /// * `content` - текст файлу HPGL.
+    ///
+    /// # Повертає
+    /// * `Result<Drawing, ImportError>` - малюнок у мм або помилка першої недійсної команди.
+    pub fn from_hpgl(content: &str) -> Result<Drawing, ImportError> {
+        let mut plotter = Plotter::default();
+        let mut rest = content;
+        loop {
+            rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == ';' || c == ',');
+            let mut chars = rest.chars();
+            let (Some(first), Some(second)) = (chars.next(), chars.next()) else {
+                break;
+            };
+            let name = format!("{}{}", first, second).to_ascii_uppercase();
+            rest = &rest[first.len_utf8() + second.len_utf8()..];
+            if name == "LB" {
+                rest = rest
+                    .split_once(LABEL_TERMINATOR)
+                    .map_or("", |(_, after)| after);
+                continue;
+            }
+            let end = rest
+                .find(|c: char| c == ';' || c.is_ascii_alphabetic())
+                .unwrap_or(rest.len());
+            let (arguments, after) = rest.split_at(end);
+            rest = after;
+            let numbers = arguments
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|number| !number.is_empty())
+                .map(|number| number.parse::<f64>())
+                .collect::<Result<Vec<f64>, _>>()
+                .map_err(|_| ImportError::InvalidHpgl(format!("{}{}", name, arguments.trim())))?;
+            let points: Vec<Coord<f64>> = numbers
+                .chunks_exact(2)
+                .map(|pair| Coord {
+                    x: pair[0],
+                    y: pair[1],
+                })
+                .collect();
+
+            match name.as_str() {
+                "IN" => {
+                    plotter.finish();
+                    (plotter.down, plotter.relative) = (false, false);
+                }
+                "PU" => {
+                    plotter.finish();
+                    plotter.down = false;
+                    plotter.travel(&points);
+                }
+                "PD" => {
+                    plotter.down = true;
+                    plotter.travel(&points);
+                }
+                "PA" | "PR" => {
+                    plotter.relative = name == "PR";
+                    plotter.travel(&points);
+                }
+                "SP" => {
+                    plotter.finish();
+                    plotter.tool = numbers.first().map(|&pen| pen as u32);
+                }
+                _ => {}
+            }
+        }
+        plotter.finish();
+
+        let top = plotter
+            .paths
+            .iter()
+            .flat_map(|(points, _)| points.iter().map(|point| point.y))
+            .fold(0.0, f64::max);
+        let right = plotter
+            .paths
+            .iter()
+            .flat_map(|(points, _)| points.iter().map(|point| point.x))
+            .fold(0.0, f64::max);
+        let used_tools = plotter.paths.iter().any(|(_, tool)| tool.is_some());
+        let (mut lines, mut styles) = (vec![], vec![]);
+        for (points, tool) in plotter.paths {
+            lines.push(LineString(
+                points
+                    .into_iter()
+                    .map(|point| Coord {
+                        x: point.x * PLOTTER_UNIT,
+                        y: (top - point.y) * PLOTTER_UNIT,
+                    })
+                    .collect(),
+            ));
+            styles.push(PathStyle {
+                tool,
+                ..PathStyle::default()
+            });
+        }
+        if !used_tools {
+            styles.clear();
+        }
+        Ok(Drawing::new(
+            (right * PLOTTER_UNIT, top * PLOTTER_UNIT),
+            MultiLineString(lines),
+        )
+        .with_styles(styles))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hpgl_commands_become_paths() {
+        let hpgl = "IN;SP1;PU0,0;PD400,0,400,400;PU;\nPR;PU-400,0;PD0,-400;LBText; PD1;\u{3}SP2;PA;PD800,400;PU;";
+        assert!(Drawing::is_hpgl(hpgl));
+        assert!(!Drawing::is_hpgl("<svg/>"));
+
+        let drawing = Drawing::from_hpgl(hpgl).expect("HPGL має розбиратися");
+        assert_eq!(drawing.bounds, (20.0, 10.0));
+        let points = |index: usize| -> Vec<(f64, f64)> {
+            drawing.paths.0[index]
+                .0
+                .iter()
+                .map(|c| (c.x, c.y))
+                .collect()
+        };
+        assert_eq!(drawing.paths.0.len(), 3);
+        // Вісь y перевертається: низ рисунка HPGL стає низом аркуша
+        assert_eq!(points(0), [(0.0, 10.0), (10.0, 10.0), (10.0, 0.0)]);
+        assert_eq!(
+            points(1),
+            [(0.0, 0.0), (0.0, 10.0)],
+            "Відносні координати після PR"
+        );
+        assert_eq!(
+            points(2),
+            [(0.0, 10.0), (20.0, 0.0)],
+            "Текст LB пропускається"
+        );
+        assert_eq!(drawing.style(0).tool, Some(1));
+        assert_eq!(drawing.style(2).tool, Some(2));
+
+        assert!(matches!(
+            Drawing::from_hpgl("IN;PD1,2.5.5;"),
+            Err(ImportError::InvalidHpgl(_))
+        ));
+    }
+}
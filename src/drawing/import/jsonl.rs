@@ -0,0 +1,96 @@
+use geo::{Coord, LineString, MultiLineString};
+
+use super::ImportError;
+use crate::drawing::Drawing;
+
+impl Drawing {
+    /// Чи схожий вміст файлу на рядки JSON з точками, а не на SVG чи завдання.
+    pub fn is_json_lines(content: &str) -> bool {
+        content.trim_start().starts_with('[')
+    }
+
+    /// Створює малюнок з рядків JSON: кожен рядок — масив точок `[x, y]` однієї ламаної.
+    ///
+    /// Такий формат легко записати з будь-якої мови без побудови SVG, наприклад
+    /// `[[0, 0], [10, 0], [10, 10]]`. Координати задаються в мм з віссю y донизу, як на
+    /// аркуші, а межами малюнка стає рамка від початку координат до найдальшої точки.
+    /// Порожні рядки й порожні масиви пропускаються.
+    ///
+    /// # Аргументи
+    /// * `content` - текст з одним масивом точок у рядку.
+    ///
+    /// # Повертає
+    /// * `Result<Drawing, ImportError>` - малюнок або помилка першого недійсного рядка.
+    pub fn from_json_lines(content: &str) -> Result<Drawing, ImportError> {
+        let mut lines = vec![];
+        for (index, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let points: Vec<[f64; 2]> =
+                serde_json::from_str(line).map_err(|e| ImportError::InvalidJsonLine {
+                    line: index + 1,
+                    message: e.to_string(),
+                })?;
+            if !points.is_empty() {
+                lines.push(LineString(
+                    points.into_iter().map(|[x, y]| Coord { x, y }).collect(),
+                ));
+            }
+        }
+        let bounds = lines
+            .iter()
+            .flat_map(|line| line.0.iter())
+            .fold((0.0, 0.0), |(width, height): (f64, f64), point| {
+                (width.max(point.x), height.max(point.y))
+            });
+        Ok(Drawing::new(bounds, MultiLineString(lines)))
+    }
+
+    /// Записує шляхи малюнка рядками JSON, які читає `from_json_lines`.
+    ///
+    /// Стилі шляхів у цьому форматі не зберігаються.
+    ///
+    /// # Повертає
+    /// Текст з одним масивом точок `[x, y]` (в мм) у рядку.
+    pub fn to_json_lines(&self) -> String {
+        self.paths
+            .0
+            .iter()
+            .map(|line| {
+                let points: Vec<[f64; 2]> = line.0.iter().map(|point| [point.x, point.y]).collect();
+                serde_json::to_string(&points).expect("Масив чисел завжди серіалізується") + "\n"
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_lines_round_trip() {
+        let content = "[[0, 0], [10, 0], [10.5, 20]]\n\n[]\n[[3,4],[5,6]]\n";
+        assert!(Drawing::is_json_lines(content));
+        assert!(!Drawing::is_json_lines("{\"version\": 1}"));
+
+        let drawing = Drawing::from_json_lines(content).expect("Рядки мають розбиратися");
+        assert_eq!(drawing.bounds, (10.5, 20.0));
+        assert_eq!(drawing.paths.0.len(), 2);
+        assert_eq!(
+            drawing.paths.0[1],
+            LineString::from(vec![(3.0, 4.0), (5.0, 6.0)])
+        );
+
+        let written = drawing.to_json_lines();
+        assert_eq!(written.lines().count(), 2);
+        let again = Drawing::from_json_lines(&written).unwrap();
+        assert_eq!(again.paths, drawing.paths);
+
+        assert!(matches!(
+            Drawing::from_json_lines("[[0, 0]]\n[[1, \"a\"]]"),
+            Err(ImportError::InvalidJsonLine { line: 2, .. })
+        ));
+    }
+}
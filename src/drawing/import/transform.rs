@@ -0,0 +1,85 @@
+use geo::{AffineTransform, Coord};
+
+use super::numbers;
+
+/// Розбирає атрибут `transform` SVG.
+///
+/// Підтримуються `matrix`, `translate`, `scale`, `rotate`, `skewX` і `skewY`. Перетворення
+/// списку застосовуються справа наліво, як у SVG: останнє діє на точку першим.
+///
+/// # Аргументи
+/// * `value` - значення атрибута, наприклад `translate(10, 20) rotate(45)`.
+///
+/// # Повертає
+/// * `Option<AffineTransform<f64>>` - сукупне перетворення або `None`, якщо запис недійсний.
+pub(super) fn parse_transform(value: &str) -> Option<AffineTransform<f64>> {
+    let mut transform = AffineTransform::identity();
+    let mut rest = value.trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+    while !rest.is_empty() {
+        let (name, tail) = rest.split_once('(')?;
+        let (arguments, tail) = tail.split_once(')')?;
+        let values = numbers(arguments).ok()?;
+        let step = match (name.trim(), values.as_slice()) {
+            ("matrix", &[a, b, c, d, e, f]) => AffineTransform::new(a, c, e, b, d, f),
+            ("translate", &[x]) => AffineTransform::translate(x, 0.0),
+            ("translate", &[x, y]) => AffineTransform::translate(x, y),
+            ("scale", &[factor]) => AffineTransform::scale(factor, factor, Coord::zero()),
+            ("scale", &[x, y]) => AffineTransform::scale(x, y, Coord::zero()),
+            ("rotate", &[angle]) => AffineTransform::rotate(angle, Coord::zero()),
+            ("rotate", &[angle, x, y]) => AffineTransform::rotate(angle, Coord { x, y }),
+            ("skewX", &[angle]) => {
+                AffineTransform::new(1.0, angle.to_radians().tan(), 0.0, 0.0, 1.0, 0.0)
+            }
+            ("skewY", &[angle]) => {
+                AffineTransform::new(1.0, 0.0, 0.0, angle.to_radians().tan(), 1.0, 0.0)
+            }
+            _ => return None,
+        };
+        transform = transform.compose(&step);
+        rest = tail.trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+    }
+    Some(transform)
+}
+
+/// У скільки разів перетворення в середньому змінює довжини, наприклад товщину лінії.
+pub(super) fn length_scale(transform: &AffineTransform<f64>) -> f64 {
+    let origin = transform.apply(Coord::zero());
+    let x = transform.apply(Coord { x: 1.0, y: 0.0 }) - origin;
+    let y = transform.apply(Coord { x: 0.0, y: 1.0 }) - origin;
+    (x.x * y.y - x.y * y.x).abs().sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_transform_list() {
+        let point = |transform: &str, x: f64, y: f64| {
+            let result = parse_transform(transform)
+                .expect("Перетворення має розбиратися")
+                .apply(Coord { x, y });
+            (
+                (result.x * 1e9).round() / 1e9,
+                (result.y * 1e9).round() / 1e9,
+            )
+        };
+        assert_eq!(point("translate(10)", 1.0, 1.0), (11.0, 1.0));
+        assert_eq!(
+            point("translate(10, 5) scale(2)", 1.0, 1.0),
+            (12.0, 7.0),
+            "Масштаб діє першим"
+        );
+        assert_eq!(point("rotate(90)", 1.0, 0.0), (0.0, 1.0));
+        assert_eq!(point("rotate(180 5 5)", 0.0, 0.0), (10.0, 10.0));
+        assert_eq!(point("matrix(1 0 0 1 3 4)", 0.0, 0.0), (3.0, 4.0));
+        assert_eq!(point("skewX(45)", 0.0, 2.0), (2.0, 2.0));
+        assert_eq!(point("", 1.0, 2.0), (1.0, 2.0));
+        assert!(parse_transform("scale(1 2 3)").is_none());
+        assert!(parse_transform("translate(1").is_none());
+        assert_eq!(
+            length_scale(&parse_transform("scale(2) rotate(30)").unwrap()),
+            2.0
+        );
+    }
+}
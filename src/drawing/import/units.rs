@@ -0,0 +1,286 @@
+use std::fmt;
+use std::str::FromStr;
+
+use geo::AffineTransform;
+use svg::node::Attributes;
+
+use super::{numbers, ImportError, ImportOptions};
+
+/// Одиниця довжини SVG.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Unit {
+    Mm, // Міліметр.
+    Cm, // Сантиметр.
+    Q,  // Чверть міліметра.
+    In, // Дюйм.
+    Pt, // Типографський пункт, 1/72 дюйма.
+    Pc, // Піка, 12 пунктів.
+    Px, // Піксель, дюйм ділиться на задану кількість пікселів.
+}
+
+impl Unit {
+    /// Довжина однієї одиниці в міліметрах.
+    ///
+    /// # Аргументи
+    /// * `dpi` - кількість пікселів на дюйм; впливає лише на `px`.
+    ///
+    /// # Повертає
+    /// * `f64` - кількість міліметрів в одиниці.
+    pub fn millimetres(self, dpi: f64) -> f64 {
+        match self {
+            Unit::Mm => 1.0,
+            Unit::Cm => 10.0,
+            Unit::Q => 0.25,
+            Unit::In => 25.4,
+            Unit::Pt => 25.4 / 72.0,
+            Unit::Pc => 25.4 / 6.0,
+            Unit::Px => 25.4 / dpi,
+        }
+    }
+}
+
+impl FromStr for Unit {
+    type Err = ImportError;
+
+    /// Конвертує позначку одиниці SVG (`mm`, `cm`, `q`, `in`, `pt`, `pc`, `px`) в одиницю.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "mm" => Ok(Unit::Mm),
+            "cm" => Ok(Unit::Cm),
+            "q" => Ok(Unit::Q),
+            "in" => Ok(Unit::In),
+            "pt" => Ok(Unit::Pt),
+            "pc" => Ok(Unit::Pc),
+            "px" => Ok(Unit::Px),
+            _ => Err(ImportError::UnknownUnit(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Unit::Mm => "mm",
+            Unit::Cm => "cm",
+            Unit::Q => "q",
+            Unit::In => "in",
+            Unit::Pt => "pt",
+            Unit::Pc => "pc",
+            Unit::Px => "px",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Довжина атрибута `width` чи `height` кореневого елемента.
+enum Length {
+    Absolute(f64), // Довжина з явною одиницею (в мм).
+    Unitless(f64), // Число без одиниці.
+}
+
+impl Length {
+    /// Довжина в міліметрах; число без одиниці береться в `options.units`.
+    fn millimetres(&self, options: &ImportOptions) -> f64 {
+        match *self {
+            Length::Absolute(length) => length,
+            Length::Unitless(length) => length * options.units.millimetres(options.dpi),
+        }
+    }
+}
+
+/// Зчитує довжину атрибута кореневого елемента.
+///
+/// Відсоткові та шрифтові (`em`, `ex`) довжини залежать від оточення документа й
+/// вважаються незаданими, як і значення, що не розбираються.
+fn length(
+    attributes: &Attributes,
+    name: &str,
+    options: &ImportOptions,
+) -> Result<Option<Length>, ImportError> {
+    let Some(value) = attributes.get(name) else {
+        return Ok(None);
+    };
+    let value = value.trim();
+    let split = value
+        .find(|c: char| c.is_ascii_alphabetic() || c == '%')
+        .unwrap_or(value.len());
+    let (digits, suffix) = value.split_at(split);
+    let Ok(number) = digits.trim().parse::<f64>() else {
+        return Ok(None);
+    };
+    Ok(match suffix.to_lowercase().as_str() {
+        "" => Some(Length::Unitless(number)),
+        "%" | "em" | "ex" => None,
+        unit => Some(Length::Absolute(
+            number * unit.parse::<Unit>()?.millimetres(options.dpi),
+        )),
+    })
+}
+
+/// Розміри документа (в мм), якщо їх задано, і перетворення його координат у міліметри.
+pub(super) type Frame = (Option<(f64, f64)>, AffineTransform<f64>);
+
+/// Розміри документа в міліметрах і перетворення з одиниць користувача SVG у міліметри.
+///
+/// З `viewBox` його прямокутник вписується в `width`/`height` з урахуванням
+/// `preserveAspectRatio`; без одного з розмірів він виводиться з пропорцій `viewBox`, а
+/// без обох одиниця користувача дорівнює `options.units`. Без `viewBox` одиниця
+/// користувача — піксель, якщо розміри задано з одиницями, і `options.units` інакше.
+///
+/// # Аргументи
+/// * `attributes` - атрибути кореневого елемента `<svg>`.
+/// * `options` - кількість пікселів на дюйм і одиниця чисел без позначки.
+///
+/// # Повертає
+/// * `Result<Frame, ImportError>` - розміри, якщо їх задано, і перетворення координат
+///   або помилка розбору атрибутів.
+pub(super) fn document_frame(
+    attributes: &Attributes,
+    options: &ImportOptions,
+) -> Result<Frame, ImportError> {
+    let width = length(attributes, "width", options)?;
+    let height = length(attributes, "height", options)?;
+
+    if let Some(value) = attributes.get("viewBox") {
+        let values = numbers(value).map_err(|_| ImportError::InvalidAttribute {
+            attribute: "viewBox".to_string(),
+            value: value.to_string(),
+        })?;
+        if let &[min_x, min_y, box_width, box_height] = values.as_slice() {
+            if box_width > 0.0 && box_height > 0.0 {
+                let width = width.map(|width| width.millimetres(options));
+                let height = height.map(|height| height.millimetres(options));
+                let unit = options.units.millimetres(options.dpi);
+                let size = match (width, height) {
+                    (Some(width), Some(height)) => (width, height),
+                    (Some(width), None) => (width, width * box_height / box_width),
+                    (None, Some(height)) => (height * box_width / box_height, height),
+                    (None, None) => (box_width * unit, box_height * unit),
+                };
+                let transform = view_box_transform(
+                    (min_x, min_y, box_width, box_height),
+                    size,
+                    attributes.get("preserveAspectRatio").map(|value| &**value),
+                );
+                return Ok((Some(size), transform));
+            }
+        }
+    }
+
+    let explicit = [&width, &height]
+        .iter()
+        .any(|length| matches!(length, Some(Length::Absolute(_))));
+    let unit = match explicit {
+        true => Unit::Px,
+        false => options.units,
+    };
+    let scale = unit.millimetres(options.dpi);
+    let size = width
+        .zip(height)
+        .map(|(width, height)| (width.millimetres(options), height.millimetres(options)));
+    Ok((size, AffineTransform::new(scale, 0.0, 0.0, 0.0, scale, 0.0)))
+}
+
+/// Перетворення, що вписує прямокутник `viewBox` у розміри документа.
+///
+/// `preserveAspectRatio="none"` розтягує кожну вісь окремо; інакше масштаб однаковий, а
+/// вирівнювання (`xMidYMid` за замовчуванням) і `meet`/`slice` задають розміщення.
+fn view_box_transform(
+    (min_x, min_y, box_width, box_height): (f64, f64, f64, f64),
+    (width, height): (f64, f64),
+    aspect: Option<&str>,
+) -> AffineTransform<f64> {
+    let mut parts = aspect.unwrap_or("").split_whitespace();
+    let align = parts.next().unwrap_or("xMidYMid");
+    let slice = parts.next() == Some("slice");
+    let (scale_x, scale_y) = (width / box_width, height / box_height);
+    if align == "none" {
+        return AffineTransform::new(
+            scale_x,
+            0.0,
+            -min_x * scale_x,
+            0.0,
+            scale_y,
+            -min_y * scale_y,
+        );
+    }
+
+    let scale = match slice {
+        true => scale_x.max(scale_y),
+        false => scale_x.min(scale_y),
+    };
+    // Частка вільного місця перед вмістом уздовж кожної осі
+    let fraction = |min: &str, max: &str| match (align.contains(min), align.contains(max)) {
+        (true, _) => 0.0,
+        (_, true) => 1.0,
+        _ => 0.5,
+    };
+    let offset_x = (width - box_width * scale) * fraction("xMin", "xMax");
+    let offset_y = (height - box_height * scale) * fraction("YMin", "YMax");
+    AffineTransform::new(
+        scale,
+        0.0,
+        offset_x - min_x * scale,
+        0.0,
+        scale,
+        offset_y - min_y * scale,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::drawing::import::ImportOptions;
+    use crate::drawing::Drawing;
+
+    use super::*;
+
+    #[test]
+    fn test_document_units_map_to_millimetres() {
+        // Квадрат 100 мм, намальований у пікселях viewBox
+        let svg = r#"<svg width="100mm" height="100mm" viewBox="0 0 378 378">
+            <rect x="0" y="0" width="378" height="378"/>
+        </svg>"#;
+        let drawing = Drawing::from_svg(svg).expect("SVG має розбиратися");
+        assert_eq!(drawing.bounds, (100.0, 100.0));
+        let bbox = drawing.bbox().expect("Малюнок не порожній");
+        assert!((bbox.width() - 100.0).abs() < 1e-9, "Квадрат має 100 мм");
+
+        // Без viewBox одиниця користувача — піксель з 96 на дюйм
+        let svg = r#"<svg width="4in" height="2in"><line x1="0" y1="0" x2="96" y2="0"/></svg>"#;
+        let drawing = Drawing::from_svg(svg).expect("SVG має розбиратися");
+        assert!((drawing.bounds.0 - 101.6).abs() < 1e-9);
+        assert!(
+            (drawing.paths.0[0].0[1].x - 25.4).abs() < 1e-9,
+            "96 px — дюйм"
+        );
+
+        // Зсув viewBox і вирівнювання за центром при різних пропорціях
+        let svg = r#"<svg width="200mm" height="100mm" viewBox="10 10 50 50">
+            <line x1="10" y1="10" x2="60" y2="60"/>
+        </svg>"#;
+        let line = Drawing::from_svg(svg).unwrap().paths.0[0].clone();
+        assert_eq!((line.0[0].x, line.0[0].y), (50.0, 0.0));
+        assert_eq!((line.0[1].x, line.0[1].y), (150.0, 100.0));
+
+        // Файл без одиниць читається в заданих одиницях
+        let svg = r#"<svg width="300" height="150"><line x1="0" y1="0" x2="72" y2="0"/></svg>"#;
+        let options = ImportOptions {
+            units: Unit::Pt,
+            ..ImportOptions::default()
+        };
+        let drawing = Drawing::from_svg_with(svg, &options).expect("SVG має розбиратися");
+        assert!(
+            (drawing.paths.0[0].0[1].x - 25.4).abs() < 1e-9,
+            "72 pt — дюйм"
+        );
+        let options = ImportOptions {
+            dpi: 72.0,
+            units: Unit::Px,
+            crop: None,
+            pen_width: None,
+        };
+        let drawing = Drawing::from_svg_with(svg, &options).unwrap();
+        assert!((drawing.bounds.1 - 150.0 * 25.4 / 72.0).abs() < 1e-9);
+        assert!("furlong".parse::<Unit>().is_err());
+    }
+}
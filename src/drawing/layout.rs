@@ -0,0 +1,250 @@
+use std::str::FromStr;
+
+use geo::{coord, AffineOps, AffineTransform, BoundingRect, MultiLineString, Rect};
+use thiserror::Error;
+
+use super::roughen::splitmix;
+use super::Drawing;
+use crate::paper::{Paper, PaperError};
+use crate::tr;
+
+/// Помилки розкладки копій малюнка на аркуші.
+#[derive(Debug, Error)]
+pub enum LayoutError {
+    /// Сітку записано не як `СТОВПЦІxРЯДКИ` з додатними числами.
+    #[error(
+        "{}",
+        tr!(
+            "Grid must look like 3x2 with positive numbers, not '{}'",
+            "Сітка має мати вигляд 3x2 з додатними числами, а не '{}'",
+            .0
+        )
+    )]
+    Grid(String),
+
+    /// Проміжки між копіями не залишають на аркуші місця для самих копій.
+    #[error(
+        "{}",
+        tr!(
+            "A {} mm gap leaves no room for a {}x{} grid on the sheet",
+            "Проміжок {} мм не залишає на аркуші місця для сітки {}x{}",
+            .gap,
+            .columns,
+            .rows
+        )
+    )]
+    NoRoom {
+        gap: f64,
+        columns: usize,
+        rows: usize,
+    },
+
+    /// Аркуш не має області для малювання.
+    #[error(transparent)]
+    Paper(#[from] PaperError),
+}
+
+/// Розкладка однакових копій малюнка сіткою на аркуші, наприклад наліпок чи листівок.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Layout {
+    pub columns: usize, // Кількість копій у рядку.
+    pub rows: usize,    // Кількість рядків копій.
+    pub gap: f64,       // Проміжок між сусідніми копіями (в мм).
+    pub jitter: f64, // Найбільший випадковий поворот кожної копії (в градусах); 0 вимикає поворот.
+    pub seed: u64,   // Зерно випадкових поворотів; те саме зерно дає ту саму розкладку.
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Layout {
+            columns: 1,
+            rows: 1,
+            gap: 0.0,
+            jitter: 0.0,
+            seed: 0,
+        }
+    }
+}
+
+impl Layout {
+    /// Клітинки сітки в області для малювання аркуша, рядок за рядком.
+    ///
+    /// # Аргументи
+    /// * `area` - область для малювання аркуша.
+    ///
+    /// # Повертає
+    /// * `Result<Vec<Rect<f64>>, LayoutError>` - клітинки або помилка, якщо проміжки
+    ///   не лишають для них місця.
+    pub fn cells(&self, area: Rect<f64>) -> Result<Vec<Rect<f64>>, LayoutError> {
+        let gap = self.gap.max(0.0);
+        let size = |length: f64, count: usize| (length - gap * (count - 1) as f64) / count as f64;
+        let (width, height) = (
+            size(area.width(), self.columns),
+            size(area.height(), self.rows),
+        );
+        if !(width > 0.0 && height > 0.0) {
+            return Err(LayoutError::NoRoom {
+                gap,
+                columns: self.columns,
+                rows: self.rows,
+            });
+        }
+        let mut cells = vec![];
+        for row in 0..self.rows {
+            for column in 0..self.columns {
+                let min = coord! {
+                    x: area.min().x + column as f64 * (width + gap),
+                    y: area.min().y + row as f64 * (height + gap),
+                };
+                cells.push(Rect::new(min, min + coord! { x: width, y: height }));
+            }
+        }
+        Ok(cells)
+    }
+
+    /// Випадковий кут повороту копії з номером `index` у межах `-jitter..jitter`.
+    fn angle(&self, index: usize) -> f64 {
+        if self.jitter == 0.0 {
+            return 0.0;
+        }
+        let hash = splitmix(self.seed ^ splitmix(index as u64));
+        let unit = (hash >> 11) as f64 / (1u64 << 53) as f64;
+        self.jitter * (2.0 * unit - 1.0)
+    }
+}
+
+impl FromStr for Layout {
+    type Err = LayoutError;
+
+    /// Розбирає сітку `СТОВПЦІxРЯДКИ`, наприклад `3x2`, без проміжків і поворотів.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || LayoutError::Grid(s.to_string());
+        let (columns, rows) = s
+            .trim()
+            .to_lowercase()
+            .split_once('x')
+            .ok_or_else(error)
+            .and_then(|(columns, rows)| {
+                Ok((
+                    columns.trim().parse::<usize>().map_err(|_| error())?,
+                    rows.trim().parse::<usize>().map_err(|_| error())?,
+                ))
+            })?;
+        if columns == 0 || rows == 0 {
+            return Err(error());
+        }
+        Ok(Layout {
+            columns,
+            rows,
+            ..Layout::default()
+        })
+    }
+}
+
+impl Drawing {
+    /// Розкладає копії малюнка сіткою по області для малювання аркуша.
+    ///
+    /// Кожна копія повертається на свій випадковий кут і вписується в клітинку зі
+    /// збереженням пропорцій, тож повернута копія не заходить на сусідні. Копії йдуть
+    /// рядок за рядком і зберігають стилі шляхів малюнка.
+    ///
+    /// # Аргументи
+    /// * `paper` - аркуш, на якому розкладаються копії.
+    /// * `layout` - сітка, проміжки й повороти копій.
+    ///
+    /// # Повертає
+    /// * `Result<Drawing, LayoutError>` - малюнок з межами аркуша або помилка розкладки.
+    pub fn n_up(&self, paper: &Paper, layout: &Layout) -> Result<Drawing, LayoutError> {
+        let cells = layout.cells(paper.printable_area()?)?;
+        let Some(bbox) = self.bbox() else {
+            return Ok(Drawing::new(paper.size(), MultiLineString(vec![])));
+        };
+
+        let (mut paths, mut indices) = (vec![], vec![]);
+        for (index, cell) in cells.iter().enumerate() {
+            let rotation = AffineTransform::rotate(layout.angle(index), bbox.center());
+            let rotated = self.paths.affine_transform(&rotation);
+            let Some(turned) = rotated.bounding_rect() else {
+                continue;
+            };
+            // Вироджений малюнок (точка чи пряма) не масштабується по нульовому виміру
+            let scale = [
+                cell.width() / turned.width(),
+                cell.height() / turned.height(),
+            ]
+            .into_iter()
+            .filter(|scale| scale.is_finite())
+            .fold(f64::INFINITY, f64::min);
+            let scale = if scale.is_finite() { scale } else { 1.0 };
+            let offset = cell.center() - turned.center() * scale;
+            let fit = AffineTransform::new(scale, 0.0, offset.x, 0.0, scale, offset.y);
+            paths.extend(rotated.affine_transform(&fit).0);
+            indices.extend(0..self.paths.0.len());
+        }
+        let styles = self.styles_for(indices);
+        Ok(Drawing::new(paper.size(), MultiLineString(paths)).with_styles(styles))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paper::Margins;
+    use geo::LineString;
+
+    #[test]
+    fn test_n_up_tiles_copies_in_cells() {
+        let paper = Paper::custom(200.0, 100.0).margins(Margins::uniform(10.0));
+        let card = Drawing::new(
+            (50.0, 50.0),
+            MultiLineString(vec![LineString::from(vec![
+                (0.0, 0.0),
+                (20.0, 0.0),
+                (20.0, 10.0),
+            ])]),
+        );
+        let layout = Layout {
+            gap: 5.0,
+            ..Layout::from_str("3x2").expect("Сітка має розбиратися")
+        };
+
+        let sheet = card.n_up(&paper, &layout).expect("Копії мають вміститися");
+        assert_eq!(sheet.paths.0.len(), 6);
+        assert_eq!(sheet.bounds, (200.0, 100.0));
+        assert!(sheet.fits(&paper));
+        // Клітинка (180 - 2·5) / 3 на (80 - 5) / 2 мм, копія вписується за шириною
+        let first = sheet.paths.0[0].bounding_rect().unwrap();
+        assert!((first.width() - 170.0 / 3.0).abs() < 1e-9);
+        assert!((first.min().x - 10.0).abs() < 1e-9);
+        let last = sheet.paths.0[5].bounding_rect().unwrap();
+        assert!((last.max().x - 190.0).abs() < 1e-9);
+
+        // Повернуті копії не виходять за свої клітинки, а те саме зерно дає ту саму розкладку
+        let jittered = Layout {
+            jitter: 15.0,
+            seed: 7,
+            ..layout
+        };
+        let turned = card.n_up(&paper, &jittered).unwrap();
+        assert!(turned.fits(&paper));
+        assert_ne!(turned.paths, sheet.paths);
+        assert_eq!(turned.paths, card.n_up(&paper, &jittered).unwrap().paths);
+        let cells = jittered.cells(paper.printable_area().unwrap()).unwrap();
+        for (path, cell) in turned.paths.0.iter().zip(&cells) {
+            let bbox = path.bounding_rect().unwrap();
+            assert!(bbox.min().x >= cell.min().x - 1e-9 && bbox.max().x <= cell.max().x + 1e-9);
+            assert!(bbox.min().y >= cell.min().y - 1e-9 && bbox.max().y <= cell.max().y + 1e-9);
+        }
+
+        assert!("3x0".parse::<Layout>().is_err());
+        assert!("3".parse::<Layout>().is_err());
+        let crowded = Layout {
+            gap: 100.0,
+            ..layout
+        };
+        assert!(matches!(
+            card.n_up(&paper, &crowded),
+            Err(LayoutError::NoRoom { .. })
+        ));
+    }
+}
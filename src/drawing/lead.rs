@@ -0,0 +1,264 @@
+use std::time::Duration;
+
+use geo::{LineString, Point};
+
+use crate::motion::point::PointExtension;
+
+/// Найменша частка максимальної швидкості на заході та виході.
+const MIN_SPEED: f64 = 0.05;
+
+/// Відрізки, коротші за цю довжину (в мм), вважаються дрібною деталлю.
+const DETAIL_LENGTH: f64 = 2.0;
+
+/// Вигини з меншим за цей радіусом (в мм) вважаються дрібною деталлю.
+const DETAIL_RADIUS: f64 = 2.0;
+
+/// Налаштування дотичних заходів і виходів для шляхів, що малюються з опущеним пером.
+///
+/// Пензлі та гелеві ручки залишають краплю там, де перо торкається паперу на нульовій
+/// швидкості. Захід продовжує шлях назад уздовж дотичної до його початку, а вихід —
+/// уперед від кінця, тож перо опускається та піднімається вже в русі. Швидкість на
+/// заході плавно зростає від `min_speed` до повної, а на виході плавно спадає.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Leads {
+    pub lead_in: f64,   // Довжина заходу перед початком шляху (в мм).
+    pub lead_out: f64,  // Довжина виходу після кінця шляху (в мм).
+    pub min_speed: f64, // Частка максимальної швидкості на краю заходу/виходу (0.05..1).
+    pub steps: usize,   // Кількість відрізків для поступової зміни швидкості.
+}
+
+impl Default for Leads {
+    fn default() -> Self {
+        Leads {
+            lead_in: 2.0,
+            lead_out: 2.0,
+            min_speed: 0.25,
+            steps: 4,
+        }
+    }
+}
+
+/// Шлях, підготовлений до планування руху: точки та частка максимальної швидкості для
+/// відрізка, що починається в кожній точці.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stroke {
+    pub points: Vec<Point<f64>>, // Точки шляху разом із заходом і виходом.
+    pub speeds: Vec<f64>,        // Частка максимальної швидкості (0..1) для кожної точки.
+    pub dwell: Option<Duration>, // Пауза з опущеним пером в останній точці.
+}
+
+impl Stroke {
+    /// Створює шлях без заходу та виходу, що малюється на повній швидкості.
+    ///
+    /// # Аргументи
+    ///
+    /// * `line` - вихідний шлях.
+    ///
+    /// # Повертає
+    ///
+    /// * `Self` - шлях з однаковою швидкістю в усіх точках.
+    pub fn plain(line: &LineString<f64>) -> Self {
+        let points: Vec<Point<f64>> = line.points().collect();
+        let speeds = vec![1.0; points.len()];
+        Stroke {
+            points,
+            speeds,
+            dwell: None,
+        }
+    }
+
+    /// Сповільнює короткі відрізки та круті вигини, на яких повна швидкість розмиває деталі.
+    ///
+    /// Відрізок, коротший за 2 мм або з вигином радіусом менше 2 мм на початку,
+    /// отримує частку швидкості між `factor` і повною пропорційно до свого розміру, тож
+    /// довгі прямі штрихи малюються так само швидко, а дрібні деталі — повільніше.
+    ///
+    /// # Аргументи
+    ///
+    /// * `factor` - частка швидкості на найдрібніших деталях (0.05..1); 1 нічого не змінює.
+    pub fn slow_details(&mut self, factor: f64) {
+        let factor = factor.clamp(MIN_SPEED, 1.0);
+        if factor == 1.0 {
+            return;
+        }
+        for i in 0..self.points.len().saturating_sub(1) {
+            let (point, next) = (self.points[i], self.points[i + 1]);
+            let length = point.distance(&next);
+            let mut detail = (length / DETAIL_LENGTH).min(1.0);
+            if i > 0 && length > 0.0 {
+                let before = (point - self.points[i - 1]).normalize();
+                let after = (next - point).normalize();
+                let cross = before.x() * after.y() - before.y() * after.x();
+                let dot = before.x() * after.x() + before.y() * after.y();
+                let turn = cross.atan2(dot).abs();
+                // Радіус вигину оцінюється як довжина відрізка на кут повороту
+                if turn > 0.0 {
+                    detail = detail.min(length / turn / DETAIL_RADIUS);
+                }
+            }
+            self.speeds[i] *= factor + (1.0 - factor) * detail;
+        }
+    }
+}
+
+impl Leads {
+    /// Додає до шляху дотичні захід і вихід.
+    ///
+    /// Напрямок заходу береться з першого ненульового відрізка шляху, напрямок виходу —
+    /// з останнього. Шляхи без довжини повертаються без змін.
+    ///
+    /// # Аргументи
+    ///
+    /// * `line` - вихідний шлях.
+    ///
+    /// # Повертає
+    ///
+    /// * `Stroke` - шлях із заходом, виходом і поступовою зміною швидкості.
+    pub fn apply(&self, line: &LineString<f64>) -> Stroke {
+        let mut stroke = Stroke::plain(line);
+        let points = &stroke.points;
+        let steps = self.steps.max(1);
+        // Нульова швидкість зупинила б план руху, тож обмежуємо її знизу
+        let min_speed = self.min_speed.clamp(MIN_SPEED, 1.0);
+
+        // Напрямки дотичних на початку та в кінці шляху
+        let first = points.first().copied();
+        let last = points.last().copied();
+        let entry = points
+            .windows(2)
+            .map(|pair| pair[1] - pair[0])
+            .find(|vector| vector.length() > f64::EPSILON);
+        let exit = points
+            .windows(2)
+            .rev()
+            .map(|pair| pair[1] - pair[0])
+            .find(|vector| vector.length() > f64::EPSILON);
+        let (Some(first), Some(last), Some(entry), Some(exit)) = (first, last, entry, exit) else {
+            return stroke;
+        };
+        let entry = entry.normalize();
+        let exit = exit.normalize();
+
+        // Частка швидкості на `k`-му відрізку заходу/виходу, рахуючи від зовнішнього краю
+        let ramp = |k: usize| min_speed + (1.0 - min_speed) * k as f64 / steps as f64;
+
+        let mut points = Vec::with_capacity(stroke.points.len() + 2 * steps);
+        let mut speeds = Vec::with_capacity(points.capacity());
+
+        if self.lead_in > 0.0 {
+            for k in 0..steps {
+                let distance = self.lead_in * (steps - k) as f64 / steps as f64;
+                points.push(first - entry.mul_scalar(distance));
+                speeds.push(ramp(k));
+            }
+        }
+
+        points.append(&mut stroke.points);
+        speeds.append(&mut stroke.speeds);
+
+        if self.lead_out > 0.0 {
+            // Останній відрізок шляху вже веде до виходу, тож сповільнюємося з кінця шляху
+            let end = speeds.len() - 1;
+            speeds[end] = ramp(steps - 1);
+            for k in 1..=steps {
+                let distance = self.lead_out * k as f64 / steps as f64;
+                points.push(last + exit.mul_scalar(distance));
+                speeds.push(ramp(steps.saturating_sub(k + 1)));
+            }
+        }
+
+        Stroke {
+            points,
+            speeds,
+            dwell: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leads_extend_path_along_tangents() {
+        let leads = Leads {
+            lead_in: 2.0,
+            lead_out: 4.0,
+            min_speed: 0.2,
+            steps: 2,
+        };
+        let line = LineString::from(vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0)]);
+        let stroke = leads.apply(&line);
+
+        let xy: Vec<(f64, f64)> = stroke.points.iter().map(|p| (p.x(), p.y())).collect();
+        assert_eq!(
+            xy,
+            vec![
+                (-2.0, 0.0),
+                (-1.0, 0.0),
+                (0.0, 0.0),
+                (10.0, 0.0),
+                (10.0, 10.0),
+                (10.0, 12.0),
+                (10.0, 14.0),
+            ]
+        );
+        assert_eq!(stroke.speeds.len(), stroke.points.len());
+        assert!(
+            (stroke.speeds[0] - 0.2).abs() < 1e-9,
+            "Захід починається повільно"
+        );
+        assert!(
+            stroke.speeds[0] < stroke.speeds[1],
+            "Швидкість на заході зростає"
+        );
+        assert_eq!(stroke.speeds[2], 1.0);
+        assert!(
+            stroke.speeds[4] > stroke.speeds[5],
+            "Швидкість на виході спадає"
+        );
+
+        // Точка без довжини не отримує заходу та виходу
+        let dot = LineString::from(vec![(5.0, 5.0), (5.0, 5.0)]);
+        assert_eq!(leads.apply(&dot), Stroke::plain(&dot));
+    }
+
+    #[test]
+    fn test_slow_details_keeps_long_strokes_fast() {
+        // Довгий штрих, дрібний зигзаг і плавне продовження по прямій
+        let line = LineString::from(vec![
+            (0.0, 0.0),
+            (50.0, 0.0),
+            (50.5, 0.5),
+            (51.0, 0.0),
+            (60.0, 0.0),
+            (70.0, 0.0),
+        ]);
+        let mut stroke = Stroke::plain(&line);
+        stroke.slow_details(0.2);
+
+        assert_eq!(
+            stroke.speeds[0], 1.0,
+            "Довгий відрізок малюється на повній швидкості"
+        );
+        assert!(stroke.speeds[1] < 0.5, "Короткий відрізок сповільнюється");
+        assert!(stroke.speeds[2] < 0.5);
+        assert_eq!(
+            stroke.speeds[3], 1.0,
+            "Довгий відрізок після зигзагу швидкий"
+        );
+        assert_eq!(stroke.speeds[4], 1.0, "Пряме продовження не сповільнюється");
+        assert!(stroke.speeds.iter().all(|speed| *speed >= 0.2));
+
+        // Прямий кут перед відрізком 2.5 мм — вигин радіусом близько 1.6 мм
+        let corner = LineString::from(vec![(0.0, 0.0), (10.0, 0.0), (10.0, 2.5), (10.0, 20.0)]);
+        let mut stroke = Stroke::plain(&corner);
+        stroke.slow_details(0.2);
+        assert!(stroke.speeds[1] < 1.0, "Крутий вигин сповільнюється");
+        assert_eq!(stroke.speeds[2], 1.0);
+
+        let mut unchanged = Stroke::plain(&line);
+        unchanged.slow_details(1.0);
+        assert_eq!(unchanged, Stroke::plain(&line));
+    }
+}
@@ -0,0 +1,420 @@
+use std::f64::consts::TAU;
+
+use geo::{BooleanOps, Coord, LineString, MultiLineString, MultiPolygon, Polygon};
+
+use super::shape::is_closed;
+use super::style::PathStyle;
+use super::Drawing;
+
+/// Найбільше відношення довжини гострого з'єднання до відстані зсуву; гостріші кути зрізаються.
+const MITER_LIMIT: f64 = 4.0;
+
+/// Найбільша довжина хорди заокругленого з'єднання (в мм).
+const ROUND_TOLERANCE: f64 = 0.1;
+
+/// Спосіб з'єднання зсунутих відрізків на зовнішньому боці кута.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Join {
+    Miter, // Гострий кут, що зрізається, коли стає задовгим.
+    Round, // Дуга навколо вершини.
+    Bevel, // Пряме зрізання кута.
+}
+
+impl Drawing {
+    /// Зсуває шляхи малюнка на відстань перпендикулярно до їхнього напрямку.
+    ///
+    /// Замкнений контур розширюється назовні для додатної відстані й звужується всередину для
+    /// від'ємної; частини, що при звуженні зникають чи розпадаються, обробляються точно,
+    /// тож контур може стати кількома або зникнути. Незамкнений шлях дає паралельну лінію:
+    /// праворуч від напрямку руху на аркуші для додатної відстані, ліворуч для від'ємної.
+    /// Кожен зсунутий шлях зберігає стиль свого шляху.
+    ///
+    /// # Аргументи
+    /// * `distance` - відстань зсуву (в мм).
+    /// * `join` - з'єднання відрізків на зовнішньому боці кутів.
+    ///
+    /// # Повертає
+    /// * `Drawing` - зсунуті шляхи з межами цього малюнка.
+    pub fn offset(&self, distance: f64, join: Join) -> Drawing {
+        let (mut paths, mut indices) = (vec![], vec![]);
+        for (index, line) in self.paths.0.iter().enumerate() {
+            for path in offset_path(line, distance, join) {
+                paths.push(path);
+                indices.push(index);
+            }
+        }
+        let styles = self.styles_for(indices);
+        Drawing::new(self.bounds, MultiLineString(paths)).with_styles(styles)
+    }
+
+    /// Потовщує лінії малюнка кількома обведеннями поруч з кожним шляхом.
+    ///
+    /// Крім самого шляху малюються зсуви на `spacing`, `2·spacing`, ... з обох його боків,
+    /// тож лінія стає завширшки приблизно `2·passes·spacing` плюс товщина пера.
+    ///
+    /// # Аргументи
+    /// * `passes` - кількість додаткових обведень з кожного боку.
+    /// * `spacing` - відстань між сусідніми обведеннями (в мм), зазвичай трохи менша за товщину пера.
+    /// * `join` - з'єднання відрізків на кутах.
+    ///
+    /// # Повертає
+    /// * `Drawing` - шляхи з обведеннями: спершу початкові, далі зсуви від найближчих.
+    pub fn thicken(&self, passes: usize, spacing: f64, join: Join) -> Drawing {
+        let mut paths = self.paths.0.clone();
+        let mut indices: Vec<usize> = (0..paths.len()).collect();
+        for pass in 1..=passes {
+            for side in [1.0, -1.0] {
+                for (index, line) in self.paths.0.iter().enumerate() {
+                    for path in offset_path(line, side * pass as f64 * spacing, join) {
+                        paths.push(path);
+                        indices.push(index);
+                    }
+                }
+            }
+        }
+        let styles = self.styles_for(indices);
+        Drawing::new(self.bounds, MultiLineString(paths)).with_styles(styles)
+    }
+
+    /// Імітує товщину ліній з SVG (`stroke-width`) паралельними проходами тонкого пера.
+    ///
+    /// Шлях, товщий за перо, малюється кілька разів зі зсувом не більшим за товщину пера,
+    /// тож проходи зливаються в одну товсту лінію. Проходи отримують товщину пера, а шляхи
+    /// без товщини чи не товщі за перо не змінюються.
+    ///
+    /// # Аргументи
+    /// * `pen_width` - товщина лінії пера (в мм).
+    ///
+    /// # Повертає
+    /// * `Drawing` - малюнок з проходами кожного шляху поспіль, з тими самими межами.
+    pub fn emulate_widths(&self, pen_width: f64) -> Drawing {
+        let (mut paths, mut styles) = (vec![], vec![]);
+        for (index, line) in self.paths.0.iter().enumerate() {
+            for (path, style) in width_passes(line, self.style(index), pen_width) {
+                paths.push(path);
+                styles.push(style);
+            }
+        }
+        if self.styles.is_empty() {
+            styles.clear();
+        }
+        Drawing::new(self.bounds, MultiLineString(paths)).with_styles(styles)
+    }
+}
+
+/// Проходи пера завширшки `pen_width`, що разом покривають лінію товщиною зі стилю шляху.
+///
+/// Крайні проходи торкаються країв лінії, а відстань між сусідніми рівна й не більша за
+/// товщину пера, тож між ними не лишається просвітів.
+///
+/// # Аргументи
+/// * `line` - шлях малюнка.
+/// * `style` - стиль шляху з товщиною лінії.
+/// * `pen_width` - товщина лінії пера (в мм).
+///
+/// # Повертає
+/// * `Vec<(LineString<f64>, PathStyle)>` - сам шлях і зсуви від найближчих до найдальших з
+///   товщиною пера; лише сам шлях, якщо він не товщий за перо.
+pub(crate) fn width_passes(
+    line: &LineString<f64>,
+    style: &PathStyle,
+    pen_width: f64,
+) -> Vec<(LineString<f64>, PathStyle)> {
+    let width = style.stroke_width.unwrap_or(0.0);
+    if !(pen_width > 0.0 && width > pen_width) {
+        return vec![(line.clone(), style.clone())];
+    }
+    let passes = ((width - pen_width) / (2.0 * pen_width)).ceil() as usize;
+    let spacing = (width - pen_width) / (2 * passes) as f64;
+    let style = PathStyle {
+        stroke_width: Some(pen_width),
+        ..style.clone()
+    };
+    // Без `stroke-linejoin` кути в SVG гострі
+    let mut paths = vec![line.clone()];
+    for pass in 1..=passes {
+        for side in [1.0, -1.0] {
+            paths.extend(offset_path(line, side * pass as f64 * spacing, Join::Miter));
+        }
+    }
+    paths
+        .into_iter()
+        .map(|path| (path, style.clone()))
+        .collect()
+}
+
+/// Зсуви одного шляху: контури замкненого або паралельна лінія незамкненого.
+fn offset_path(line: &LineString<f64>, distance: f64, join: Join) -> Vec<LineString<f64>> {
+    let points = distinct(line);
+    if distance == 0.0 {
+        vec![line.clone()]
+    } else if is_closed(line) && points.len() >= 4 {
+        offset_ring(&points, distance, join)
+    } else if points.len() >= 2 {
+        vec![parallel(&points, distance, join)]
+    } else {
+        vec![]
+    }
+}
+
+/// Точки шляху без повторів поспіль, які дають відрізки нульової довжини.
+fn distinct(line: &LineString<f64>) -> Vec<Coord<f64>> {
+    let mut points: Vec<Coord<f64>> = Vec::with_capacity(line.0.len());
+    for &point in &line.0 {
+        if points.last() != Some(&point) {
+            points.push(point);
+        }
+    }
+    points
+}
+
+/// Одинична нормаль відрізка, праворуч від його напрямку на аркуші з віссю y донизу.
+fn normal(a: Coord<f64>, b: Coord<f64>) -> Coord<f64> {
+    let delta = b - a;
+    let length = delta.x.hypot(delta.y);
+    Coord {
+        x: -delta.y / length,
+        y: delta.x / length,
+    }
+}
+
+/// Перетин двох зсунутих відрізків біля вершини, якщо він не задалеко для гострого з'єднання.
+///
+/// # Аргументи
+/// * `vertex` - спільна вершина відрізків.
+/// * `before`, `after` - нормалі відрізків до і після вершини.
+/// * `distance` - відстань зсуву зі знаком боку.
+fn miter(
+    vertex: Coord<f64>,
+    before: Coord<f64>,
+    after: Coord<f64>,
+    distance: f64,
+) -> Option<Coord<f64>> {
+    let cosine = before.x * after.x + before.y * after.y;
+    // Відношення довжини з'єднання до відстані зсуву дорівнює 1/cos половини кута
+    (1.0 + cosine > 2.0 / (MITER_LIMIT * MITER_LIMIT))
+        .then(|| vertex + (before + after) * (distance / (1.0 + cosine)))
+}
+
+/// Точки дуги навколо вершини від одного зсунутого кінця до іншого коротшим шляхом, без кінців.
+fn arc(vertex: Coord<f64>, from: Coord<f64>, to: Coord<f64>) -> Vec<Coord<f64>> {
+    let (start, end) = (from - vertex, to - vertex);
+    let radius = start.x.hypot(start.y);
+    let mut sweep = end.y.atan2(end.x) - start.y.atan2(start.x);
+    if sweep > TAU / 2.0 {
+        sweep -= TAU;
+    } else if sweep < -TAU / 2.0 {
+        sweep += TAU;
+    }
+    let count = (sweep.abs() * radius / ROUND_TOLERANCE).ceil() as usize;
+    let angle = start.y.atan2(start.x);
+    (1..count)
+        .map(|step| {
+            let at = angle + sweep * step as f64 / count as f64;
+            vertex
+                + Coord {
+                    x: radius * at.cos(),
+                    y: radius * at.sin(),
+                }
+        })
+        .collect()
+}
+
+/// Многокутник кола навколо точки.
+fn disk(center: Coord<f64>, radius: f64) -> Polygon<f64> {
+    let count = ((TAU * radius / ROUND_TOLERANCE).ceil() as usize).max(8);
+    let points: Vec<Coord<f64>> = (0..count)
+        .map(|step| {
+            let at = TAU * step as f64 / count as f64;
+            center
+                + Coord {
+                    x: radius * at.cos(),
+                    y: radius * at.sin(),
+                }
+        })
+        .collect();
+    Polygon::new(LineString(points), vec![])
+}
+
+/// Паралельна лінія до незамкненого шляху.
+///
+/// На внутрішньому боці кута зсунуті відрізки сходяться в точці перетину, а на
+/// зовнішньому з'єднуються за `join`. Петлі, що виникають на зсувах, більших за короткі
+/// відрізки шляху, не вирізаються.
+fn parallel(points: &[Coord<f64>], distance: f64, join: Join) -> LineString<f64> {
+    let normals: Vec<Coord<f64>> = points
+        .windows(2)
+        .map(|pair| normal(pair[0], pair[1]))
+        .collect();
+    let mut shifted = vec![points[0] + normals[0] * distance];
+    for (index, pair) in normals.windows(2).enumerate() {
+        let (vertex, before, after) = (points[index + 1], pair[0], pair[1]);
+        let (from, to) = (vertex + before * distance, vertex + after * distance);
+        // Зсув лежить на зовнішньому боці кута, коли наступний відрізок відвертає від нього
+        let turn = before.x * after.y - before.y * after.x;
+        let outer = turn * distance < 0.0;
+        match (outer, join, miter(vertex, before, after, distance)) {
+            _ if turn == 0.0 && before == after => shifted.push(from),
+            (false, _, Some(corner)) | (true, Join::Miter, Some(corner)) => shifted.push(corner),
+            (true, Join::Round, _) => {
+                shifted.push(from);
+                shifted.extend(arc(vertex, from, to));
+                shifted.push(to);
+            }
+            _ => shifted.extend([from, to]),
+        }
+    }
+    shifted.push(points[points.len() - 1] + normals[normals.len() - 1] * distance);
+    LineString(shifted)
+}
+
+/// Зсув замкненого контуру через його смугу завширшки `2·|distance|`.
+///
+/// Смуга складається з прямокутника вздовж кожного відрізка та з'єднання на
+/// зовнішньому боці кожного кута. Розширений контур — це об'єднання многокутника зі
+/// смугою, звужений — їх різниця, тож точні перетини й розпади дають булеві операції.
+fn offset_ring(points: &[Coord<f64>], distance: f64, join: Join) -> Vec<LineString<f64>> {
+    let reach = distance.abs();
+    let count = points.len() - 1;
+    let normals: Vec<Coord<f64>> = points
+        .windows(2)
+        .map(|pair| normal(pair[0], pair[1]))
+        .collect();
+
+    let mut parts: Vec<MultiPolygon<f64>> = vec![];
+    for (index, pair) in points.windows(2).enumerate() {
+        let shift = normals[index] * reach;
+        let band = vec![
+            pair[0] + shift,
+            pair[1] + shift,
+            pair[1] - shift,
+            pair[0] - shift,
+        ];
+        parts.push(Polygon::new(LineString(band), vec![]).into());
+    }
+    for index in 0..count {
+        let (before, after) = (normals[(index + count - 1) % count], normals[index]);
+        let vertex = points[index];
+        let turn = before.x * after.y - before.y * after.x;
+        if turn == 0.0 && before == after {
+            continue;
+        }
+        // Зовнішній бік кута протилежний тому, куди повертає шлях
+        let side = if turn > 0.0 { -reach } else { reach };
+        let (from, to) = (vertex + before * side, vertex + after * side);
+        let corner = match (join, miter(vertex, before, after, side)) {
+            (Join::Round, _) => disk(vertex, reach),
+            (Join::Miter, Some(corner)) => {
+                Polygon::new(LineString(vec![vertex, from, corner, to]), vec![])
+            }
+            _ => Polygon::new(LineString(vec![vertex, from, to]), vec![]),
+        };
+        parts.push(corner.into());
+    }
+    // Попарне об'єднання тримає проміжні многокутники малими
+    while parts.len() > 1 {
+        parts = parts
+            .chunks(2)
+            .map(|pair| match pair {
+                [a, b] => a.union(b),
+                [a] => a.clone(),
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+    let band = parts.pop().unwrap_or(MultiPolygon(vec![]));
+
+    let shape = MultiPolygon::from(Polygon::new(LineString(points.to_vec()), vec![]));
+    let region = match distance > 0.0 {
+        true => shape.union(&band),
+        false => shape.difference(&band),
+    };
+    region
+        .0
+        .into_iter()
+        .flat_map(|polygon| {
+            let (exterior, interiors) = polygon.into_inner();
+            std::iter::once(exterior).chain(interiors)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::Area;
+
+    #[test]
+    fn test_offset_grows_and_shrinks_shapes() {
+        let square = LineString::from(vec![
+            (0.0, 0.0),
+            (10.0, 0.0),
+            (10.0, 10.0),
+            (0.0, 10.0),
+            (0.0, 0.0),
+        ]);
+        let corner = LineString::from(vec![(0.0, 20.0), (10.0, 20.0), (10.0, 30.0)]);
+        let drawing = Drawing::new((40.0, 40.0), MultiLineString(vec![square, corner]));
+        let area =
+            |drawing: &Drawing| Polygon::new(drawing.paths.0[0].clone(), vec![]).unsigned_area();
+
+        let grown = drawing.offset(1.0, Join::Miter);
+        assert!((area(&grown) - 144.0).abs() < 1e-6, "Квадрат 12×12");
+        let round = drawing.offset(1.0, Join::Round);
+        assert!((area(&round) - (140.0 + std::f64::consts::PI)).abs() < 0.05);
+        let bevel = drawing.offset(1.0, Join::Bevel);
+        assert!(
+            (area(&bevel) - 142.0).abs() < 1e-6,
+            "Кути зрізано трикутниками"
+        );
+        let shrunk = drawing.offset(-2.0, Join::Round);
+        assert!(
+            (area(&shrunk) - 36.0).abs() < 1e-6,
+            "Усередині кути лишаються гострими"
+        );
+        assert_eq!(
+            drawing.offset(-6.0, Join::Miter).paths.0.len(),
+            1,
+            "Квадрат зникає, лишається лінія"
+        );
+
+        // Шлях повертає праворуч, тож додатний зсув лежить на внутрішньому боці кута
+        let points = |drawing: &Drawing| -> Vec<(f64, f64)> {
+            drawing.paths.0[1].0.iter().map(|c| (c.x, c.y)).collect()
+        };
+        assert_eq!(points(&round), [(0.0, 21.0), (9.0, 21.0), (9.0, 30.0)]);
+        assert_eq!(
+            points(&drawing.offset(-1.0, Join::Miter)),
+            [(0.0, 19.0), (11.0, 19.0), (11.0, 30.0)]
+        );
+        assert!(
+            drawing.offset(-1.0, Join::Round).paths.0[1].0.len() > 5,
+            "Дуга на зовнішньому куті"
+        );
+
+        let thick = drawing.thicken(2, 0.3, Join::Round);
+        assert_eq!(thick.paths.0.len(), 2 + 2 * 2 * 2);
+        assert_eq!(thick.paths.0[..2], drawing.paths.0[..]);
+
+        // Лінія 1.3 мм пером 0.3 мм: по два проходи з кожного боку через 0.25 мм
+        let styled = drawing.clone().with_styles(vec![
+            PathStyle::default(),
+            PathStyle {
+                stroke_width: Some(1.3),
+                color: Some("red".to_string()),
+                ..PathStyle::default()
+            },
+        ]);
+        let emulated = styled.emulate_widths(0.3);
+        assert_eq!(emulated.paths.0.len(), 1 + 5);
+        assert_eq!(
+            emulated.paths.0[0], drawing.paths.0[0],
+            "Шлях без товщини не змінюється"
+        );
+        let outermost = &emulated.paths.0[5];
+        assert_eq!((outermost.0[0].x, outermost.0[0].y), (0.0, 19.5));
+        assert_eq!(emulated.style(5).stroke_width, Some(0.3));
+        assert_eq!(emulated.style(5).color.as_deref(), Some("red"));
+        assert_eq!(styled.emulate_widths(2.0).paths, drawing.paths);
+    }
+}
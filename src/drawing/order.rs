@@ -0,0 +1,514 @@
+use std::time::{Duration, Instant};
+
+use geo::{Coord, LineString, MultiLineString};
+use rstar::primitives::GeomWithData;
+use rstar::RTree;
+
+use super::Drawing;
+use crate::tr;
+
+/// Кількість найближчих сусідів, біля яких Or-opt пробує вставити ланцюжок шляхів.
+const NEIGHBORS: usize = 8;
+
+/// Найдовший ланцюжок сусідніх шляхів, який переносить Or-opt.
+const MAX_CHAIN: usize = 3;
+
+/// Найменше скорочення переїзду (в мм), заради якого варто переставляти шляхи.
+const MIN_GAIN: f64 = 1e-6;
+
+/// Кінець шляху в просторовому індексі: номер шляху та чи починати з нього шлях у зворотному напрямку.
+type Endpoint = GeomWithData<[f64; 2], (usize, bool)>;
+
+/// Налаштування впорядкування шляхів для скорочення переїздів з піднятим пером.
+///
+/// Спершу шляхи жадібно з'єднуються з найближчим кінцем наступного шляху, який шукається
+/// в R-дереві, тож навіть десятки тисяч шляхів впорядковуються за частки секунди. Потім,
+/// якщо задано `improve`, Or-opt переносить ланцюжки з одного-трьох шляхів ближче до
+/// сусідів, доки це скорочує переїзди або не вичерпано відведений час.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathOrder {
+    pub reverse: bool,             // Чи можна малювати шлях у зворотному напрямку.
+    pub improve: Option<Duration>, // Час на покращення порядку Or-opt; `None` вимикає його.
+}
+
+impl Default for PathOrder {
+    fn default() -> Self {
+        PathOrder {
+            reverse: true,
+            improve: Some(Duration::from_secs(1)),
+        }
+    }
+}
+
+/// Шлях у маршруті: номер у малюнку та напрямок проходження.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Visit {
+    index: usize,   // Номер шляху в малюнку.
+    reversed: bool, // Чи проходиться шлях від кінця до початку.
+}
+
+impl Visit {
+    /// Точка, з якої починається малювання шляху.
+    fn start(&self, lines: &[&LineString<f64>]) -> Coord<f64> {
+        let points = &lines[self.index].0;
+        if self.reversed {
+            points[points.len() - 1]
+        } else {
+            points[0]
+        }
+    }
+
+    /// Точка, в якій закінчується малювання шляху.
+    fn end(&self, lines: &[&LineString<f64>]) -> Coord<f64> {
+        let points = &lines[self.index].0;
+        if self.reversed {
+            points[0]
+        } else {
+            points[points.len() - 1]
+        }
+    }
+}
+
+/// Маршрут пера через усі шляхи малюнка з початком і кінцем у (0, 0).
+struct Route<'a> {
+    lines: &'a [&'a LineString<f64>], // Непорожні шляхи малюнка.
+    visits: Vec<Visit>,               // Порядок проходження шляхів.
+}
+
+impl Route<'_> {
+    /// Точка, з якої починається малювання шляху.
+    fn start(&self, visit: Visit) -> Coord<f64> {
+        visit.start(self.lines)
+    }
+
+    /// Точка, в якій закінчується малювання шляху.
+    fn end(&self, visit: Visit) -> Coord<f64> {
+        visit.end(self.lines)
+    }
+
+    /// Кінець шляху перед проміжком `gap` (або початок координат для першого проміжку).
+    fn gap_from(&self, gap: usize) -> Coord<f64> {
+        match gap {
+            0 => Coord::zero(),
+            _ => self.end(self.visits[gap - 1]),
+        }
+    }
+
+    /// Початок шляху після проміжку `gap` (або початок координат для останнього).
+    fn gap_to(&self, gap: usize) -> Coord<f64> {
+        self.visits
+            .get(gap)
+            .map_or(Coord::zero(), |visit| self.start(*visit))
+    }
+
+    /// Позиція кожного шляху в маршруті за його номером у малюнку.
+    fn positions(&self) -> Vec<usize> {
+        let mut positions = vec![0; self.lines.len()];
+        for (position, visit) in self.visits.iter().enumerate() {
+            positions[visit.index] = position;
+        }
+        positions
+    }
+
+    /// Загальна довжина переїздів маршруту.
+    fn travel(&self) -> f64 {
+        (0..=self.visits.len())
+            .map(|gap| distance(self.gap_from(gap), self.gap_to(gap)))
+            .sum()
+    }
+}
+
+impl PathOrder {
+    /// Впорядковує шляхи малюнка.
+    ///
+    /// Порожні шляхи відкидаються, а решта можуть малюватися у зворотному напрямку,
+    /// якщо це дозволено `reverse`.
+    ///
+    /// # Аргументи
+    /// * `drawing` - малюнок, шляхи якого впорядковуються.
+    ///
+    /// # Повертає
+    /// * `Drawing` - новий малюнок з тими самими межами та впорядкованими шляхами.
+    pub fn apply(&self, drawing: &Drawing) -> Drawing {
+        let (indices, lines): (Vec<usize>, Vec<_>) = drawing
+            .paths
+            .0
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| !line.0.is_empty())
+            .unzip();
+
+        let mut route = Route {
+            lines: &lines,
+            visits: self.nearest_neighbor(&lines),
+        };
+        let greedy = route.travel();
+
+        if let Some(budget) = self.improve {
+            self.or_opt(&mut route, Instant::now() + budget);
+        }
+        tracing::info!(
+            "{}",
+            tr!(
+                "Ordered {} paths: travel {:.1} mm after greedy search, {:.1} mm after improvement",
+                "Впорядковано {} шляхів: переїзди {:.1} мм після жадібного пошуку, {:.1} мм після покращення",
+                lines.len(),
+                greedy,
+                route.travel()
+            )
+        );
+
+        let paths = route
+            .visits
+            .iter()
+            .map(|visit| {
+                let mut line = lines[visit.index].clone();
+                if visit.reversed {
+                    line.0.reverse();
+                }
+                line
+            })
+            .collect();
+        let styles = drawing.styles_for(route.visits.iter().map(|visit| indices[visit.index]));
+        Drawing::new(drawing.bounds, MultiLineString(paths)).with_styles(styles)
+    }
+
+    /// Жадібно будує маршрут, щоразу переходячи до найближчого кінця ще не намальованого шляху.
+    fn nearest_neighbor(&self, lines: &[&LineString<f64>]) -> Vec<Visit> {
+        let mut tree = RTree::bulk_load(self.endpoints(lines));
+        let mut visits = Vec::with_capacity(lines.len());
+        let mut position = [0.0, 0.0];
+
+        while let Some(nearest) = tree.nearest_neighbor(&position).copied() {
+            let (index, reversed) = nearest.data;
+            // Прибираємо з індексу обидва кінці обраного шляху
+            for endpoint in self.line_endpoints(index, lines[index]) {
+                tree.remove(&endpoint);
+            }
+
+            let visit = Visit { index, reversed };
+            let end = visit.end(lines);
+            position = [end.x, end.y];
+            visits.push(visit);
+        }
+
+        visits
+    }
+
+    /// Покращує маршрут переносом ланцюжків шляхів до найближчих сусідів (Or-opt).
+    ///
+    /// Ланцюжок вставляється перед або після одного з `NEIGHBORS` шляхів, найближчих
+    /// до його кінців, в будь-якому дозволеному напрямку. Прохід повторюється, доки
+    /// він щось покращує і не настав `deadline`.
+    fn or_opt(&self, route: &mut Route, deadline: Instant) {
+        let neighbors = self.neighbors(route.lines);
+        let mut positions = route.positions();
+
+        let mut improved = true;
+        while improved {
+            improved = false;
+            for length in 1..=MAX_CHAIN {
+                let mut i = 0;
+                while i + length <= route.visits.len() {
+                    if Instant::now() >= deadline {
+                        tracing::debug!(
+                            "{}",
+                            tr!(
+                                "Path order improvement ran out of time.",
+                                "Час на покращення порядку шляхів вичерпано."
+                            )
+                        );
+                        return;
+                    }
+                    if self.move_chain(route, &neighbors, &positions, i, length) {
+                        positions = route.positions();
+                        improved = true;
+                    } else {
+                        i += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Переносить ланцюжок `route.visits[i..i + length]` у найвигідніший проміжок маршруту.
+    ///
+    /// # Повертає
+    /// * `bool` - `true`, якщо ланцюжок перенесено і маршрут скоротився.
+    fn move_chain(
+        &self,
+        route: &mut Route,
+        neighbors: &[Vec<usize>],
+        positions: &[usize],
+        i: usize,
+        length: usize,
+    ) -> bool {
+        let first = route.visits[i];
+        let last = route.visits[i + length - 1];
+        let (head, tail) = (route.start(first), route.end(last));
+
+        // Виграш від вилучення ланцюжка з поточного місця
+        let (before, after) = (route.gap_from(i), route.gap_to(i + length));
+        let removed = distance(before, head) + distance(tail, after) - distance(before, after);
+        if removed <= MIN_GAIN {
+            return false;
+        }
+
+        // Проміжки біля найближчих сусідів, крім тих, що прилягають до самого ланцюжка
+        let mut best: Option<(f64, usize, bool)> = None;
+        let chain = route.visits[i..i + length]
+            .iter()
+            .flat_map(|visit| &neighbors[visit.index]);
+        for &neighbor in chain {
+            let position = positions[neighbor];
+            for gap in [position, position + 1] {
+                if (i..=i + length).contains(&gap) {
+                    continue;
+                }
+                let (from, to) = (route.gap_from(gap), route.gap_to(gap));
+                let base = distance(from, to);
+                let forward = distance(from, head) + distance(tail, to) - base;
+                let backward = distance(from, tail) + distance(head, to) - base;
+
+                let mut candidates = vec![(forward, false)];
+                if self.reverse {
+                    candidates.push((backward, true));
+                }
+                for (added, reversed) in candidates {
+                    if best.is_none_or(|(cost, _, _)| added < cost) {
+                        best = Some((added, gap, reversed));
+                    }
+                }
+            }
+        }
+
+        let Some((added, gap, reversed)) = best else {
+            return false;
+        };
+        if removed - added <= MIN_GAIN {
+            return false;
+        }
+
+        let mut chain: Vec<Visit> = route.visits.drain(i..i + length).collect();
+        if reversed {
+            chain.reverse();
+            for visit in &mut chain {
+                visit.reversed = !visit.reversed;
+            }
+        }
+        // Після вилучення ланцюжка проміжки за ним зсуваються на його довжину
+        let gap = if gap > i { gap - length } else { gap };
+        route.visits.splice(gap..gap, chain);
+        true
+    }
+
+    /// Для кожного шляху знаходить `NEIGHBORS` інших шляхів з найближчими кінцями.
+    fn neighbors(&self, lines: &[&LineString<f64>]) -> Vec<Vec<usize>> {
+        let tree = RTree::bulk_load(self.endpoints(lines));
+        (0..lines.len())
+            .map(|index| {
+                let mut found = Vec::with_capacity(NEIGHBORS);
+                for endpoint in self.line_endpoints(index, lines[index]) {
+                    let nearest = tree
+                        .nearest_neighbor_iter(endpoint.geom())
+                        .map(|other| other.data.0)
+                        .filter(|&other| other != index);
+                    for other in nearest {
+                        if found.len() >= NEIGHBORS {
+                            break;
+                        }
+                        if !found.contains(&other) {
+                            found.push(other);
+                        }
+                    }
+                }
+                found
+            })
+            .collect()
+    }
+
+    /// Кінці всіх шляхів для просторового індексу.
+    fn endpoints(&self, lines: &[&LineString<f64>]) -> Vec<Endpoint> {
+        lines
+            .iter()
+            .enumerate()
+            .flat_map(|(index, line)| self.line_endpoints(index, line))
+            .collect()
+    }
+
+    /// Кінці шляху, з яких можна почати його малювання.
+    ///
+    /// Без дозволу на зворотний напрямок шлях починається лише з першої точки, тож
+    /// в індекс потрапляє тільки вона.
+    fn line_endpoints(&self, index: usize, line: &LineString<f64>) -> Vec<Endpoint> {
+        let (first, last) = (line.0[0], line.0[line.0.len() - 1]);
+        let mut endpoints = vec![GeomWithData::new([first.x, first.y], (index, false))];
+        if self.reverse {
+            endpoints.push(GeomWithData::new([last.x, last.y], (index, true)));
+        }
+        endpoints
+    }
+}
+
+/// Відстань між двома точками.
+fn distance(a: Coord<f64>, b: Coord<f64>) -> f64 {
+    (a.x - b.x).hypot(a.y - b.y)
+}
+
+impl Drawing {
+    /// Впорядковує шляхи малюнка, скорочуючи переїзди з піднятим пером.
+    ///
+    /// # Аргументи
+    /// * `order` - налаштування впорядкування.
+    ///
+    /// # Повертає
+    /// * `Drawing` - новий малюнок з тими самими межами та впорядкованими шляхами.
+    pub fn optimize(&self, order: &PathOrder) -> Drawing {
+        order.apply(self)
+    }
+
+    /// З'єднує сусідні шляхи, якщо кінець одного ближчий до початку наступного за `tolerance`.
+    ///
+    /// З'єднуються лише шляхи з однаковим стилем, тож кольори й інструменти не змішуються.
+    /// Короткий переїзд між ними стає відрізком з опущеним пером, а шляхи, що точно
+    /// продовжують один одного, зливаються без повторної точки. Найкраще з'єднувати
+    /// вже впорядковані шляхи, коли сусіди в списку є сусідами й на аркуші.
+    ///
+    /// # Аргументи
+    /// * `tolerance` - найбільший переїзд (в мм), що замінюється відрізком.
+    ///
+    /// # Повертає
+    /// * `Drawing` - малюнок з тими самими межами й не більшою кількістю шляхів.
+    pub fn join(&self, tolerance: f64) -> Drawing {
+        let (mut paths, mut indices): (Vec<LineString<f64>>, Vec<usize>) = (vec![], vec![]);
+        for (index, line) in self.paths.0.iter().enumerate() {
+            let Some(&start) = line.0.first() else {
+                continue;
+            };
+            let joins = match (paths.last(), indices.last()) {
+                (Some(previous), Some(&last)) => {
+                    self.style(last) == self.style(index)
+                        && previous
+                            .0
+                            .last()
+                            .is_some_and(|&end| distance(end, start) <= tolerance)
+                }
+                _ => false,
+            };
+            if !joins {
+                paths.push(line.clone());
+                indices.push(index);
+                continue;
+            }
+            let previous = paths.last_mut().expect("Шлях для з'єднання вже є");
+            let skip = usize::from(previous.0.last() == Some(&start));
+            previous.0.extend(line.0.iter().skip(skip));
+        }
+        let styles = self.styles_for(indices);
+        Drawing::new(self.bounds, MultiLineString(paths)).with_styles(styles)
+    }
+
+    /// Переїзди з піднятим пером у порядку малювання, як їх рахує `stats`.
+    ///
+    /// # Повертає
+    /// * `MultiLineString<f64>` - відрізки від (0, 0) до першого шляху, між шляхами, що не
+    ///   продовжують один одного, і від останнього шляху додому.
+    pub fn travel_moves(&self) -> MultiLineString<f64> {
+        let mut moves = vec![];
+        let mut position = Coord::zero();
+        for line in self.paths.0.iter().filter(|line| !line.0.is_empty()) {
+            let start = line.0[0];
+            if start != position {
+                moves.push(LineString(vec![position, start]));
+            }
+            position = line.0[line.0.len() - 1];
+        }
+        if position != Coord::zero() {
+            moves.push(LineString(vec![position, Coord::zero()]));
+        }
+        MultiLineString(moves)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_order_reduces_travel() {
+        // Горизонтальні відрізки на сітці, перемішані та частково перевернуті
+        let mut lines = Vec::new();
+        for k in 0..400usize {
+            let (row, column) = ((k * 37) % 20, (k * 91) % 20);
+            let (x, y) = (column as f64 * 10.0, row as f64 * 10.0);
+            let line = if k % 2 == 0 {
+                vec![(x, y), (x + 5.0, y)]
+            } else {
+                vec![(x + 5.0, y), (x, y)]
+            };
+            lines.push(LineString::from(line));
+        }
+        lines.push(LineString::from(Vec::<(f64, f64)>::new()));
+        let drawing = Drawing::new((200.0, 200.0), MultiLineString(lines));
+
+        let before = drawing.stats();
+        let greedy = drawing
+            .optimize(&PathOrder {
+                improve: None,
+                ..PathOrder::default()
+            })
+            .stats();
+        let improved = drawing.optimize(&PathOrder::default()).stats();
+
+        assert_eq!(greedy.paths, 400, "Порожній шлях відкидається");
+        assert_eq!(improved.paths, 400);
+        assert!((improved.pen_down_length - before.pen_down_length).abs() < 1e-9);
+        assert!(
+            greedy.pen_up_length < before.pen_up_length / 5.0,
+            "Жадібний порядок має суттєво скоротити переїзди"
+        );
+        assert!(
+            improved.pen_up_length <= greedy.pen_up_length + 1e-9,
+            "Or-opt не може погіршити маршрут"
+        );
+
+        // Без зворотного напрямку кожен шлях малюється так, як задано
+        let forward = drawing.optimize(&PathOrder {
+            reverse: false,
+            ..PathOrder::default()
+        });
+        for line in forward.paths.iter() {
+            let original = drawing
+                .paths
+                .0
+                .iter()
+                .find(|other| other.0[..] == line.0[..]);
+            assert!(original.is_some(), "Шлях не має перевертатися");
+        }
+
+        // Шляхи з переїздом до 0.5 мм зливаються, а переїзди малюнка збігаються зі статистикою
+        let chain = Drawing::new(
+            (50.0, 50.0),
+            MultiLineString(vec![
+                LineString::from(vec![(10.0, 0.0), (20.0, 0.0)]),
+                LineString::from(vec![(20.0, 0.0), (20.0, 10.0)]),
+                LineString::from(vec![(20.3, 10.0), (30.0, 10.0)]),
+                LineString::from(vec![(40.0, 40.0), (45.0, 40.0)]),
+            ]),
+        );
+        let joined = chain.join(0.5);
+        assert_eq!(joined.paths.0.len(), 2);
+        assert_eq!(
+            joined.paths.0[0].0.len(),
+            5,
+            "Спільна точка не повторюється"
+        );
+        assert_eq!(joined.travel_moves().0.len(), 3);
+        let travel: f64 = joined
+            .travel_moves()
+            .iter()
+            .map(|line| distance(line.0[0], line.0[1]))
+            .sum();
+        assert!((travel - joined.stats().pen_up_length).abs() < 1e-9);
+    }
+}
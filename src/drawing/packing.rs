@@ -0,0 +1,192 @@
+use geo::{Coord, EuclideanDistance, LineString, MultiLineString, Point, Polygon};
+use rstar::primitives::GeomWithData;
+use rstar::RTree;
+
+use super::roughen::splitmix;
+use super::sample::{sample_polygon, Mark, Random, Sampling};
+use super::{distance, Drawing};
+
+/// Розміщене коло з радіусом у просторовому індексі.
+type Placed = GeomWithData<[f64; 2], f64>;
+
+/// Налаштування пакування кіл.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CirclePacking {
+    pub min_radius: f64, // Найменший радіус кола (в мм); менші проміжки лишаються порожніми.
+    pub max_radius: f64, // Найбільший радіус кола (в мм).
+    pub skew: f64, // Степінь розподілу радіусів: більше 1 дає більше малих кіл, 1 — рівномірний.
+    pub gap: f64,  // Найменший проміжок між колами та до краю області (в мм).
+    pub seed: u64, // Зерно випадкового розставлення.
+}
+
+impl Default for CirclePacking {
+    fn default() -> Self {
+        CirclePacking {
+            min_radius: 1.0,
+            max_radius: 8.0,
+            skew: 2.0,
+            gap: 0.5,
+            seed: 0,
+        }
+    }
+}
+
+impl CirclePacking {
+    /// Заповнює многокутник колами, що не перекриваються й не виходять за його межі.
+    ///
+    /// Центри кандидатів беруться диском Пуассона з кроком найменшого радіуса, а радіус
+    /// кожного витягується з розподілу й зменшується до найближчого кола чи краю. Більші кола
+    /// ставляться першими, тож малі заповнюють проміжки між ними.
+    ///
+    /// З густотою (`density`, 0..1 у кожній точці, наприклад темнота зображення) кандидат
+    /// лишається з імовірністю, що дорівнює густоті, а його радіус зменшується до найменшого
+    /// там, де густота повна: темні місця вкриваються дрібними щільними колами, а білі
+    /// лишаються порожніми.
+    ///
+    /// # Аргументи
+    /// * `region` - многокутник, який заповнюється (в мм).
+    /// * `density` - густота в точці або `None` для рівномірного пакування.
+    ///
+    /// # Повертає
+    /// * `Vec<(Coord<f64>, f64)>` - центри й радіуси кіл у порядку розміщення.
+    pub fn pack(
+        &self,
+        region: &Polygon<f64>,
+        density: Option<&dyn Fn(Coord<f64>) -> f64>,
+    ) -> Vec<(Coord<f64>, f64)> {
+        let min = self.min_radius.max(0.01);
+        let max = self.max_radius.max(min);
+        let gap = self.gap.max(0.0);
+        let mut random = Random(splitmix(self.seed ^ 0x5eed));
+
+        let mut candidates: Vec<(Coord<f64>, f64)> = vec![];
+        for center in sample_polygon(region, &Sampling::Poisson { spacing: min }, self.seed) {
+            let mut radius = min + (max - min) * random.next().powf(self.skew.max(0.01));
+            if let Some(density) = density {
+                let weight = density(center).clamp(0.0, 1.0);
+                if random.next() >= weight {
+                    continue;
+                }
+                radius = min + (radius - min) * (1.0 - weight);
+            }
+            candidates.push((center, radius));
+        }
+        candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        let rings: Vec<&LineString<f64>> = std::iter::once(region.exterior())
+            .chain(region.interiors())
+            .collect();
+        let mut tree: RTree<Placed> = RTree::new();
+        let mut circles = vec![];
+        for (center, radius) in candidates {
+            let point = Point::from(center);
+            let edge = rings
+                .iter()
+                .map(|ring| point.euclidean_distance(*ring))
+                .fold(f64::INFINITY, f64::min);
+            // Коло обмежують лише сусіди, ближчі за два найбільші радіуси з проміжком
+            let reach = 2.0 * max + gap;
+            let neighbour = tree
+                .locate_within_distance([center.x, center.y], reach * reach)
+                .map(|placed| {
+                    let [x, y] = *placed.geom();
+                    distance(center, Coord { x, y }) - placed.data
+                })
+                .fold(f64::INFINITY, f64::min);
+            let radius = radius.min(edge.min(neighbour) - gap);
+            if radius >= min {
+                tree.insert(Placed::new([center.x, center.y], radius));
+                circles.push((center, radius));
+            }
+        }
+        circles
+    }
+}
+
+impl Drawing {
+    /// Заповнює замкнені шляхи малюнка колами, що не перекриваються.
+    ///
+    /// Кола шукаються в областях з `fill_polygons`, тож отвори складених шляхів лишаються
+    /// порожніми, а незамкнені шляхи пропускаються.
+    /// Кола кожного контуру зберігають його стиль.
+    ///
+    /// # Аргументи
+    /// * `packing` - радіуси, проміжок і зерно пакування.
+    ///
+    /// # Повертає
+    /// * `Drawing` - кола з межами цього малюнка.
+    pub fn pack_circles(&self, packing: &CirclePacking) -> Drawing {
+        let (mut paths, mut indices) = (vec![], vec![]);
+        for (index, polygon) in self.fill_polygons() {
+            let packing = CirclePacking {
+                seed: packing.seed ^ splitmix(index as u64),
+                ..*packing
+            };
+            for (center, radius) in packing.pack(&polygon, None) {
+                paths.extend(Mark::Circle(radius).paths(center));
+                indices.push(index);
+            }
+        }
+        let styles = self.styles_for(indices);
+        Drawing::new(self.bounds, MultiLineString(paths)).with_styles(styles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::Rect;
+
+    #[test]
+    fn test_packed_circles_do_not_overlap() {
+        let region = Rect::new(Coord { x: 0.0, y: 0.0 }, Coord { x: 60.0, y: 40.0 }).to_polygon();
+        let packing = CirclePacking {
+            min_radius: 0.5,
+            max_radius: 5.0,
+            gap: 0.3,
+            seed: 11,
+            ..CirclePacking::default()
+        };
+
+        let circles = packing.pack(&region, None);
+        assert!(circles.len() > 50);
+        for (index, &(a, ra)) in circles.iter().enumerate() {
+            assert!((0.5..=5.0).contains(&ra));
+            assert!(a.x - ra >= 0.3 - 1e-9 && a.x + ra <= 60.0 - 0.3 + 1e-9);
+            assert!(a.y - ra >= 0.3 - 1e-9 && a.y + ra <= 40.0 - 0.3 + 1e-9);
+            for &(b, rb) in &circles[index + 1..] {
+                assert!(
+                    distance(a, b) >= ra + rb + 0.3 - 1e-9,
+                    "Кола перекриваються"
+                );
+            }
+        }
+        assert!(
+            circles.iter().any(|&(_, radius)| radius > 3.0),
+            "Є великі кола"
+        );
+        assert_eq!(circles, packing.pack(&region, None));
+        // Кола займають більшу частину області
+        let covered: f64 = circles
+            .iter()
+            .map(|&(_, radius)| std::f64::consts::PI * radius * radius)
+            .sum();
+        assert!(covered > 0.5 * 60.0 * 40.0, "Покрито {} мм²", covered);
+
+        // Права половина без густоти лишається порожньою, а в лівій кола найдрібніші
+        let left = |point: Coord<f64>| if point.x < 30.0 { 1.0 } else { 0.0 };
+        let weighted = packing.pack(&region, Some(&left));
+        assert!(!weighted.is_empty());
+        assert!(weighted
+            .iter()
+            .all(|&(center, radius)| center.x < 30.0 && radius == 0.5));
+
+        let drawing = Drawing::new(
+            (60.0, 40.0),
+            MultiLineString(vec![region.exterior().clone()]),
+        );
+        let packed = drawing.pack_circles(&packing);
+        assert!(!packed.paths.0.is_empty());
+        assert!(packed.paths.0.iter().all(|circle| circle.is_closed()));
+    }
+}
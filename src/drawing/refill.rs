@@ -0,0 +1,164 @@
+use geo::Point;
+
+use super::lead::Stroke;
+use crate::motion::point::PointExtension;
+
+/// Кількість обертів пера в чорнильниці під час одного занурення.
+const SWIRL_TURNS: usize = 2;
+
+/// Кількість точок на один оберт пера в чорнильниці.
+const SWIRL_POINTS: usize = 16;
+
+/// Налаштування поповнення чорнила для пензлів і перових ручок.
+///
+/// Після кожних `every` міліметрів руху з опущеним пером малювання переривається:
+/// каретка переїжджає до чорнильниці в точці `location`, опускає перо, робить кілька
+/// кіл радіусом `swirl`, піднімає перо й повертається туди, де зупинилася, щоб
+/// продовжити той самий шлях. Перед першим шляхом перо теж занурюється.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Refill {
+    pub every: f64,           // Довжина руху з опущеним пером між зануреннями (в мм).
+    pub location: (f64, f64), // Положення чорнильниці (в мм).
+    pub swirl: f64,           // Радіус кіл пера в чорнильниці (в мм); 0 — без кружляння.
+}
+
+impl Refill {
+    /// Ділить шляхи на порції, кожна з яких малюється одним зануренням.
+    ///
+    /// Шлях, що не вміщується в залишок порції, розрізається в точці, де закінчується
+    /// чорнило, а решта шляху переходить у наступну порцію.
+    ///
+    /// # Аргументи
+    ///
+    /// * `strokes` - шляхи в порядку малювання.
+    ///
+    /// # Повертає
+    ///
+    /// * `Vec<Vec<Stroke>>` - порції шляхів завдовжки не більше `every`.
+    pub fn split(&self, strokes: &[Stroke]) -> Vec<Vec<Stroke>> {
+        let mut batches = vec![];
+        let mut batch = vec![];
+        let mut left = self.every;
+
+        for stroke in strokes.iter().filter(|stroke| !stroke.points.is_empty()) {
+            let mut piece = Stroke {
+                points: vec![stroke.points[0]],
+                speeds: vec![stroke.speeds[0]],
+                dwell: None,
+            };
+            for i in 1..stroke.points.len() {
+                let (mut start, end) = (stroke.points[i - 1], stroke.points[i]);
+                let speed = stroke.speeds[i - 1];
+                let mut length = start.distance(&end);
+                // Розрізаємо відрізок там, де чорнило закінчується, поки він не вміститься
+                while length > left {
+                    let cut = start.lerps(&end, left);
+                    if left > 0.0 {
+                        piece.points.push(cut);
+                        piece.speeds.push(speed);
+                    }
+                    if piece.points.len() > 1 {
+                        batch.push(piece);
+                    }
+                    if !batch.is_empty() {
+                        batches.push(std::mem::take(&mut batch));
+                    }
+                    piece = Stroke {
+                        points: vec![cut],
+                        speeds: vec![speed],
+                        dwell: None,
+                    };
+                    length -= left;
+                    start = cut;
+                    left = self.every;
+                }
+                left -= length;
+                piece.points.push(end);
+                piece.speeds.push(stroke.speeds[i]);
+            }
+            // Шлях з однієї точки лишається крапкою, а від розрізаного не лишаємо точки
+            if piece.points.len() > 1 || stroke.points.len() == 1 {
+                // Пауза лишається в кінці шляху, а не на місці розрізу
+                piece.dwell = stroke.dwell;
+                batch.push(piece);
+            }
+        }
+        if !batch.is_empty() {
+            batches.push(batch);
+        }
+        batches
+    }
+
+    /// Точки кіл, які перо описує в чорнильниці, починаючи й закінчуючи в її центрі.
+    pub fn swirl_path(&self) -> Vec<Point<f64>> {
+        let center = Point::new(self.location.0, self.location.1);
+        if self.swirl <= 0.0 {
+            return vec![center];
+        }
+        let mut points = vec![center];
+        for k in 0..=SWIRL_TURNS * SWIRL_POINTS {
+            let angle = std::f64::consts::TAU * k as f64 / SWIRL_POINTS as f64;
+            points.push(center + Point::new(angle.cos(), angle.sin()).mul_scalar(self.swirl));
+        }
+        points.push(center);
+        points
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::LineString;
+
+    #[test]
+    fn test_refill_splits_strokes_by_pen_down_length() {
+        let refill = Refill {
+            every: 25.0,
+            location: (5.0, 5.0),
+            swirl: 2.0,
+        };
+        let strokes = vec![
+            Stroke::plain(&LineString::from(vec![(0.0, 0.0), (10.0, 0.0)])),
+            Stroke::plain(&LineString::from(vec![(0.0, 10.0), (40.0, 10.0)])),
+        ];
+        let batches = refill.split(&strokes);
+
+        // Перша порція — перший шлях і 15 мм другого, друга — решта 25 мм другого
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].len(), 2);
+        let cut = batches[0][1].points.last().unwrap();
+        assert!(
+            (cut.x() - 15.0).abs() < 1e-9,
+            "Другий шлях розрізано на 15 мм"
+        );
+        assert_eq!(batches[1].len(), 1);
+        assert_eq!(
+            batches[1][0].points[0], *cut,
+            "Продовження починається з місця розрізу"
+        );
+        assert_eq!(batches[1][0].points.last().unwrap().x(), 40.0);
+        assert!(batches
+            .iter()
+            .flatten()
+            .all(|stroke| stroke.points.len() == stroke.speeds.len()));
+
+        // Шлях, що закінчується разом із чорнилом, не залишає порожнього шматка
+        let exact = vec![
+            Stroke::plain(&LineString::from(vec![(0.0, 0.0), (25.0, 0.0)])),
+            Stroke::plain(&LineString::from(vec![(0.0, 5.0), (5.0, 5.0)])),
+        ];
+        let batches = refill.split(&exact);
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[1][0].points.len(), 2);
+
+        let swirl = refill.swirl_path();
+        assert_eq!(
+            swirl.first(),
+            swirl.last(),
+            "Перо повертається в центр чорнильниці"
+        );
+        assert!(swirl
+            .iter()
+            .all(|point| point.distance(&Point::new(5.0, 5.0)) <= 2.0 + 1e-9));
+    }
+}
@@ -0,0 +1,129 @@
+use geo::{LineString, MultiLineString, Simplify};
+
+use super::{distance, Drawing};
+
+/// Розставляє точки шляху рівномірно за довжиною, з кроком не більшим за `spacing`.
+fn resample_line(line: &LineString<f64>, spacing: f64) -> LineString<f64> {
+    let length: f64 = line
+        .0
+        .windows(2)
+        .map(|pair| distance(pair[0], pair[1]))
+        .sum();
+    let (Some(&first), Some(&last)) = (line.0.first(), line.0.last()) else {
+        return line.clone();
+    };
+    if length == 0.0 {
+        return LineString(vec![first, last]);
+    }
+
+    let pieces = (length / spacing).ceil().max(1.0) as usize;
+    let step = length / pieces as f64;
+    let mut points = Vec::with_capacity(pieces + 1);
+    points.push(first);
+
+    // `travelled` — довжина шляху до початку поточного відрізка
+    let (mut travelled, mut next) = (0.0, 1);
+    for pair in line.0.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        let segment = distance(a, b);
+        if segment == 0.0 {
+            continue;
+        }
+        while next < pieces && next as f64 * step <= travelled + segment {
+            let t = (next as f64 * step - travelled) / segment;
+            points.push(a + (b - a) * t);
+            next += 1;
+        }
+        travelled += segment;
+    }
+    points.push(last);
+    LineString(points)
+}
+
+impl Drawing {
+    /// Розставляє точки шляхів рівномірно, з відстанню не більшою за `max_segment_length`.
+    ///
+    /// Довгі відрізки діляться, а мікровідрізки зливаються, тож планувальник отримує
+    /// точки з однаковим кроком замість скупчень на відстані мікронів і довгих стрибків.
+    /// Кінці шляхів зберігаються, а проміжні вершини замінюються точками через рівні
+    /// проміжки довжини, тож гострі кути згладжуються не далі ніж на половину кроку.
+    ///
+    /// # Аргументи
+    /// * `max_segment_length` - найбільша відстань між сусідніми точками (в мм).
+    ///
+    /// # Повертає
+    /// * `Drawing` - новий малюнок з тими самими межами; незмінна копія, якщо довжина
+    ///   не додатна або не скінченна.
+    pub fn resample(&self, max_segment_length: f64) -> Drawing {
+        if !(max_segment_length > 0.0 && max_segment_length.is_finite()) {
+            return self.clone();
+        }
+        let paths = self
+            .paths
+            .0
+            .iter()
+            .map(|line| resample_line(line, max_segment_length));
+        Drawing::new(self.bounds, MultiLineString(paths.collect())).with_styles(self.styles.clone())
+    }
+
+    /// Прибирає зайві вершини шляхів алгоритмом Рамера — Дугласа — Пекера.
+    ///
+    /// Вершина відкидається, якщо шлях без неї відхиляється від початкового не більше ніж на
+    /// `tolerance`, тож густо апроксимовані криві малюються меншою кількістю команд. Кінці
+    /// шляхів зберігаються, а замкнені шляхи лишаються замкненими.
+    ///
+    /// # Аргументи
+    /// * `tolerance` - найбільше відхилення спрощеного шляху (в мм).
+    ///
+    /// # Повертає
+    /// * `Drawing` - новий малюнок з тими самими межами; незмінна копія, якщо відхилення
+    ///   не додатне.
+    pub fn simplify(&self, tolerance: f64) -> Drawing {
+        if tolerance.is_nan() || tolerance <= 0.0 {
+            return self.clone();
+        }
+        let paths = self.paths.0.iter().map(|line| line.simplify(&tolerance));
+        Drawing::new(self.bounds, MultiLineString(paths.collect())).with_styles(self.styles.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::Coord;
+
+    #[test]
+    fn test_resample_to_uniform_spacing() {
+        // Скупчення точок на відстані мікронів, а потім стрибок на 100 мм
+        let mut points: Vec<(f64, f64)> = (0..=100).map(|i| (i as f64 * 0.001, 0.0)).collect();
+        points.push((100.1, 0.0));
+        let drawing = Drawing::new(
+            (200.0, 200.0),
+            MultiLineString(vec![
+                LineString::from(points),
+                LineString::from(vec![(5.0, 5.0), (5.0, 5.0)]),
+            ]),
+        );
+
+        let resampled = drawing.resample(2.0);
+        let line = &resampled.paths.0[0];
+        assert_eq!(line.0.len(), 52, "100.1 мм по 2 мм дають 51 відрізок");
+        assert_eq!(line.0[0], Coord { x: 0.0, y: 0.0 });
+        assert_eq!(line.0[51], Coord { x: 100.1, y: 0.0 });
+        let step = 100.1 / 51.0;
+        assert!(line
+            .0
+            .windows(2)
+            .all(|pair| (distance(pair[0], pair[1]) - step).abs() < 1e-9));
+
+        assert_eq!(resampled.paths.0[1].0.len(), 2, "Точковий шлях не зникає");
+        assert_eq!(drawing.resample(0.0).paths, drawing.paths);
+
+        // Спрощення повертає густо розставлену пряму до її кінців
+        let simplified = resampled.simplify(0.01);
+        assert_eq!(
+            simplified.paths.0[0],
+            LineString::from(vec![(0.0, 0.0), (100.1, 0.0)])
+        );
+    }
+}
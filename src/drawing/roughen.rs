@@ -0,0 +1,221 @@
+use geo::{Coord, LineString, MultiLineString};
+
+use super::{distance, Drawing};
+
+/// Кількість точок на довжину хвилі шуму, до якої `roughen` подрібнює шляхи.
+const POINTS_PER_WAVE: f64 = 8.0;
+
+/// Гладкий шум значень: випадкові значення у вузлах цілочисельної сітки, згладжено
+/// інтерпольовані між ними.
+///
+/// Значення у вузлі визначається лише зерном, каналом і номером вузла, тож той самий
+/// виклик завжди дає той самий результат.
+struct Noise {
+    seed: u64,           // Зерно шуму.
+    channel: u64,        // Незалежний канал, наприклад вісь і номер шляху.
+    period: Option<i64>, // Період у вузлах для замкнених шляхів; без нього шум неперіодичний.
+}
+
+impl Noise {
+    /// Значення шуму в точці `t` (у довжинах хвилі) в межах -1..1.
+    fn sample(&self, t: f64) -> f64 {
+        let cell = t.floor();
+        let fraction = t - cell;
+        let smooth = fraction * fraction * (3.0 - 2.0 * fraction);
+        let (a, b) = (self.node(cell as i64), self.node(cell as i64 + 1));
+        a + (b - a) * smooth
+    }
+
+    /// Випадкове значення у вузлі сітки в межах -1..1.
+    fn node(&self, index: i64) -> f64 {
+        let index = self.period.map_or(index, |period| index.rem_euclid(period));
+        let hash =
+            splitmix(self.seed ^ splitmix(self.channel) ^ splitmix(index as u64).rotate_left(17));
+        (hash >> 11) as f64 / (1u64 << 52) as f64 - 1.0
+    }
+}
+
+/// Перемішувальна функція SplitMix64.
+pub(crate) fn splitmix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Ділить відрізки шляху на частини не довші за `step`, зберігаючи його вершини.
+fn subdivide(line: &LineString<f64>, step: f64) -> LineString<f64> {
+    let mut points = Vec::with_capacity(line.0.len());
+    for pair in line.0.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        let pieces = (distance(a, b) / step).ceil().max(1.0) as usize;
+        for piece in 0..pieces {
+            let t = piece as f64 / pieces as f64;
+            points.push(a + (b - a) * t);
+        }
+    }
+    points.extend(line.0.last());
+    LineString(points)
+}
+
+impl Drawing {
+    /// Зміщує вершини шляхів гладким шумом, щоб малюнок виглядав намальованим від руки.
+    ///
+    /// Спершу довгі відрізки діляться на частини по 1/8 довжини хвилі шуму, тож прямі
+    /// лінії теж хвилюються. Зсув кожної осі не перевищує `amplitude`, а шум залежить
+    /// лише від `seed` і відстані вздовж шляху, тож той самий малюнок з тим самим зерном
+    /// завжди дає однаковий результат. Замкнені шляхи залишаються замкненими без зламу
+    /// в точці стику.
+    ///
+    /// # Аргументи
+    /// * `amplitude` - найбільший зсув вершини по кожній осі (в мм).
+    /// * `frequency` - кількість хвиль шуму на міліметр шляху.
+    /// * `seed` - зерно шуму.
+    ///
+    /// # Повертає
+    /// * `Drawing` - новий малюнок з тими самими межами; незмінна копія, якщо амплітуда
+    ///   чи частота не додатні або не скінченні.
+    pub fn roughen(&self, amplitude: f64, frequency: f64, seed: u64) -> Drawing {
+        let valid = |value: f64| value > 0.0 && value.is_finite();
+        if !valid(amplitude) || !valid(frequency) {
+            return self.clone();
+        }
+
+        let dense = self.wobble_resample(1.0 / (frequency * POINTS_PER_WAVE));
+        let paths = dense
+            .paths
+            .0
+            .iter()
+            .enumerate()
+            .map(|(index, line)| {
+                let mut lengths = Vec::with_capacity(line.0.len());
+                let mut length = 0.0;
+                for (i, point) in line.0.iter().enumerate() {
+                    if i > 0 {
+                        length += distance(line.0[i - 1], *point);
+                    }
+                    lengths.push(length);
+                }
+
+                // Для замкненого шляху ціла кількість хвиль дає однаковий зсув на обох кінцях
+                let closed = line.0.len() > 2 && line.is_closed();
+                let (scale, period) = if closed {
+                    let waves = (length * frequency).round().max(1.0);
+                    (waves / length, Some(waves as i64))
+                } else {
+                    (frequency, None)
+                };
+                let channel = index as u64 * 2;
+                let noise_x = Noise {
+                    seed,
+                    channel,
+                    period,
+                };
+                let noise_y = Noise {
+                    seed,
+                    channel: channel + 1,
+                    period,
+                };
+
+                let mut points: Vec<Coord<f64>> = line
+                    .0
+                    .iter()
+                    .zip(&lengths)
+                    .map(|(point, length)| {
+                        let t = length * scale;
+                        Coord {
+                            x: point.x + amplitude * noise_x.sample(t),
+                            y: point.y + amplitude * noise_y.sample(t),
+                        }
+                    })
+                    .collect();
+                if closed {
+                    let last = points.len() - 1;
+                    points[last] = points[0];
+                }
+                LineString(points)
+            })
+            .collect();
+        Drawing::new(self.bounds, MultiLineString(paths)).with_styles(self.styles.clone())
+    }
+
+    /// Ділить відрізки шляхів на частини не довші за `step`, не змінюючи їхньої форми.
+    ///
+    /// Вершини малюнка зберігаються, а між ними додаються рівномірні проміжні точки, які
+    /// потім можна зміщувати, наприклад через `roughen`.
+    ///
+    /// # Аргументи
+    /// * `step` - найбільша довжина відрізка (в мм).
+    ///
+    /// # Повертає
+    /// * `Drawing` - новий малюнок з тими самими межами; незмінна копія, якщо `step` не
+    ///   додатний або не скінченний.
+    pub fn wobble_resample(&self, step: f64) -> Drawing {
+        if !(step > 0.0 && step.is_finite()) {
+            return self.clone();
+        }
+        let paths = self.paths.0.iter().map(|line| subdivide(line, step));
+        Drawing::new(self.bounds, MultiLineString(paths.collect())).with_styles(self.styles.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roughen_is_seeded_and_bounded() {
+        let drawing = Drawing::new(
+            (100.0, 100.0),
+            MultiLineString(vec![
+                LineString::from(vec![(10.0, 10.0), (90.0, 10.0)]),
+                LineString::from(vec![
+                    (20.0, 20.0),
+                    (80.0, 20.0),
+                    (80.0, 80.0),
+                    (20.0, 80.0),
+                    (20.0, 20.0),
+                ]),
+            ]),
+        );
+
+        let dense = drawing.wobble_resample(3.0);
+        let line = &dense.paths.0[0];
+        assert_eq!(line.0.len(), 28, "80 мм по 3 мм дають 27 відрізків");
+        assert!(line
+            .0
+            .windows(2)
+            .all(|pair| distance(pair[0], pair[1]) <= 3.0 + 1e-9));
+        assert_eq!(line.0[27], Coord { x: 90.0, y: 10.0 });
+
+        let rough = drawing.roughen(1.5, 0.1, 7);
+        assert_eq!(rough.paths, drawing.roughen(1.5, 0.1, 7).paths);
+        assert_ne!(rough.paths, drawing.roughen(1.5, 0.1, 8).paths);
+
+        let line = &rough.paths.0[0];
+        assert!(
+            line.0.len() > 2,
+            "Довгий відрізок подрібнюється перед шумом"
+        );
+        assert!(line
+            .0
+            .iter()
+            .all(|point| (point.y - 10.0).abs() <= 1.5 + 1e-9));
+        assert!(line.0.iter().any(|point| (point.y - 10.0).abs() > 0.01));
+
+        let square = &rough.paths.0[1];
+        assert!(square.is_closed(), "Замкнений шлях лишається замкненим");
+        let steps: Vec<f64> = square
+            .0
+            .windows(2)
+            .map(|pair| distance(pair[0], pair[1]))
+            .collect();
+        let longest = steps[..steps.len() - 1].iter().cloned().fold(0.0, f64::max);
+        assert!(
+            steps[steps.len() - 1] <= longest + 1e-6,
+            "У точці стику немає стрибка"
+        );
+
+        assert_eq!(drawing.roughen(0.0, 0.1, 7).paths, drawing.paths);
+    }
+}
@@ -0,0 +1,400 @@
+use std::f64::consts::TAU;
+
+use geo::{BoundingRect, Contains, Coord, LineString, MultiLineString, Point, Polygon, Rect};
+use rstar::RTree;
+
+use super::roughen::splitmix;
+use super::{distance, Drawing};
+
+/// Скільки сусідів навколо кожної точки пробувати кинути методом Пуассона (алгоритм Бридсона).
+const POISSON_ATTEMPTS: usize = 30;
+
+/// Скільки кандидатів порівнювати за найбільшою відстанню для кожної точки блакитного шуму.
+const BEST_CANDIDATES: usize = 10;
+
+/// Скільки випадкових точок рамки пробувати, шукаючи точку всередині многокутника.
+const INSIDE_ATTEMPTS: usize = 1000;
+
+/// Довжина риски, якою перо ставить точку (в мм).
+const DOT_LENGTH: f64 = 0.1;
+
+/// Найбільша довжина хорди кола позначки (в мм).
+const CIRCLE_TOLERANCE: f64 = 0.2;
+
+/// Відтворювана послідовність випадкових чисел з зерна.
+pub(crate) struct Random(pub(crate) u64);
+
+impl Random {
+    /// Наступне випадкове число в межах 0..1.
+    pub(crate) fn next(&mut self) -> f64 {
+        self.0 = self.0.wrapping_add(1);
+        (splitmix(self.0) >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Випадкова точка прямокутника.
+    fn point_in(&mut self, rect: Rect<f64>) -> Coord<f64> {
+        Coord {
+            x: rect.min().x + self.next() * rect.width(),
+            y: rect.min().y + self.next() * rect.height(),
+        }
+    }
+}
+
+/// Спосіб розставлення точок у многокутнику.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sampling {
+    Poisson { spacing: f64 }, // Диск Пуассона: жодні дві точки не ближчі за `spacing` (в мм).
+    BlueNoise { count: usize }, // Блакитний шум: рівно `count` точок, кожна якомога далі від решти.
+    JitteredGrid { spacing: f64, jitter: f64 }, // Сітка з кроком `spacing` (в мм), кожна точка зсунута в межах частки `jitter` кроку.
+}
+
+/// Позначка, яку перо ставить у кожній точці.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mark {
+    Dot,         // Коротка риска пера.
+    Circle(f64), // Коло з радіусом (в мм), наприклад для пакування кіл.
+    Cross(f64),  // Хрестик з довжиною рисок (в мм).
+}
+
+impl Mark {
+    /// Шляхи позначки з центром у точці.
+    ///
+    /// # Аргументи
+    /// * `center` - точка позначки (в мм).
+    ///
+    /// # Повертає
+    /// * `Vec<LineString<f64>>` - одна риска чи коло або дві риски хрестика.
+    pub fn paths(&self, center: Coord<f64>) -> Vec<LineString<f64>> {
+        let offset = |x: f64, y: f64| Coord { x, y };
+        match *self {
+            Mark::Dot => vec![LineString(vec![
+                center - offset(DOT_LENGTH / 2.0, 0.0),
+                center + offset(DOT_LENGTH / 2.0, 0.0),
+            ])],
+            Mark::Circle(radius) => {
+                let count = ((TAU * radius / CIRCLE_TOLERANCE).ceil() as usize).max(8);
+                let points = (0..=count)
+                    .map(|index| {
+                        let angle = TAU * (index % count) as f64 / count as f64;
+                        center + offset(radius * angle.cos(), radius * angle.sin())
+                    })
+                    .collect();
+                vec![LineString(points)]
+            }
+            Mark::Cross(size) => vec![
+                LineString(vec![
+                    center - offset(size / 2.0, 0.0),
+                    center + offset(size / 2.0, 0.0),
+                ]),
+                LineString(vec![
+                    center - offset(0.0, size / 2.0),
+                    center + offset(0.0, size / 2.0),
+                ]),
+            ],
+        }
+    }
+}
+
+/// Розставляє точки всередині многокутника з урахуванням його отворів.
+///
+/// # Аргументи
+/// * `polygon` - многокутник, у якому лежать точки (в мм).
+/// * `sampling` - спосіб розставлення.
+/// * `seed` - зерно; те саме зерно дає ті самі точки.
+///
+/// # Повертає
+/// * `Vec<Coord<f64>>` - точки всередині многокутника; порожній для многокутника без площі.
+pub fn sample_polygon(polygon: &Polygon<f64>, sampling: &Sampling, seed: u64) -> Vec<Coord<f64>> {
+    let Some(rect) = polygon.bounding_rect() else {
+        return vec![];
+    };
+    let inside = |point: Coord<f64>| polygon.contains(&Point::from(point));
+    let mut random = Random(splitmix(seed));
+    match *sampling {
+        Sampling::Poisson { spacing } if spacing > 0.0 => {
+            poisson(rect, spacing, &inside, &mut random)
+        }
+        Sampling::BlueNoise { count } => best_candidate(rect, count, &inside, &mut random),
+        Sampling::JitteredGrid { spacing, jitter } if spacing > 0.0 => {
+            jittered_grid(rect, spacing, jitter, &inside, &mut random)
+        }
+        _ => vec![],
+    }
+}
+
+/// Випадкова точка рамки, що лежить усередині многокутника.
+fn random_inside(
+    rect: Rect<f64>,
+    inside: &impl Fn(Coord<f64>) -> bool,
+    random: &mut Random,
+) -> Option<Coord<f64>> {
+    (0..INSIDE_ATTEMPTS)
+        .map(|_| random.point_in(rect))
+        .find(|&point| inside(point))
+}
+
+/// Сітка клітинок зі стороною `spacing/√2`, у кожній з яких поміщається щонайбільше одна
+/// точка диска Пуассона.
+struct PoissonGrid {
+    rect: Rect<f64>,           // Рамка многокутника.
+    spacing: f64,              // Найменша відстань між точками (в мм).
+    cell: f64,                 // Сторона клітинки (в мм).
+    columns: usize,            // Кількість клітинок у рядку.
+    cells: Vec<Option<usize>>, // Номер точки в кожній клітинці.
+    points: Vec<Coord<f64>>,   // Знайдені точки.
+}
+
+impl PoissonGrid {
+    fn new(rect: Rect<f64>, spacing: f64) -> Self {
+        let cell = spacing / 2f64.sqrt();
+        let columns = (rect.width() / cell).ceil() as usize + 1;
+        let rows = (rect.height() / cell).ceil() as usize + 1;
+        PoissonGrid {
+            rect,
+            spacing,
+            cell,
+            columns,
+            cells: vec![None; columns * rows],
+            points: vec![],
+        }
+    }
+
+    /// Клітинка точки рамки.
+    fn slot(&self, point: Coord<f64>) -> (usize, usize) {
+        let rows = self.cells.len() / self.columns;
+        let column = ((point.x - self.rect.min().x) / self.cell) as usize;
+        let row = ((point.y - self.rect.min().y) / self.cell) as usize;
+        (column.min(self.columns - 1), row.min(rows - 1))
+    }
+
+    /// Чи лежить точка в рамці не ближче `spacing` до вже знайдених.
+    fn free(&self, point: Coord<f64>) -> bool {
+        if !self.rect.contains(&point) {
+            return false;
+        }
+        let rows = self.cells.len() / self.columns;
+        let (column, row) = self.slot(point);
+        (row.saturating_sub(2)..(row + 3).min(rows)).all(|row| {
+            (column.saturating_sub(2)..(column + 3).min(self.columns)).all(|column| {
+                self.cells[row * self.columns + column]
+                    .is_none_or(|index| distance(self.points[index], point) >= self.spacing)
+            })
+        })
+    }
+
+    /// Додає точку й повертає її номер.
+    fn insert(&mut self, point: Coord<f64>) -> usize {
+        let (column, row) = self.slot(point);
+        self.cells[row * self.columns + column] = Some(self.points.len());
+        self.points.push(point);
+        self.points.len() - 1
+    }
+}
+
+/// Диск Пуассона алгоритмом Бридсона: нові точки кидаються в кільце навколо вже знайдених.
+fn poisson(
+    rect: Rect<f64>,
+    spacing: f64,
+    inside: &impl Fn(Coord<f64>) -> bool,
+    random: &mut Random,
+) -> Vec<Coord<f64>> {
+    let mut grid = PoissonGrid::new(rect, spacing);
+    // Вузький перешийок може відрізати частину многокутника, тож ріст починається кілька разів
+    for _ in 0..POISSON_ATTEMPTS {
+        let Some(start) = random_inside(rect, inside, random) else {
+            break;
+        };
+        if !grid.free(start) {
+            continue;
+        }
+        let mut active = vec![grid.insert(start)];
+        while !active.is_empty() {
+            let at = (random.next() * active.len() as f64) as usize;
+            let center = grid.points[active[at]];
+            let around = (0..POISSON_ATTEMPTS)
+                .map(|_| {
+                    let (angle, reach) = (TAU * random.next(), spacing * (1.0 + random.next()));
+                    center
+                        + Coord {
+                            x: reach * angle.cos(),
+                            y: reach * angle.sin(),
+                        }
+                })
+                .find(|&point| inside(point) && grid.free(point));
+            match around {
+                Some(point) => active.push(grid.insert(point)),
+                None => {
+                    active.swap_remove(at);
+                }
+            }
+        }
+    }
+    grid.points
+}
+
+/// Блакитний шум алгоритмом найкращого кандидата Мітчелла.
+fn best_candidate(
+    rect: Rect<f64>,
+    count: usize,
+    inside: &impl Fn(Coord<f64>) -> bool,
+    random: &mut Random,
+) -> Vec<Coord<f64>> {
+    let mut tree: RTree<[f64; 2]> = RTree::new();
+    let mut points = vec![];
+    for _ in 0..count {
+        let best = (0..BEST_CANDIDATES)
+            .filter_map(|_| random_inside(rect, inside, random))
+            .map(|candidate| {
+                let gap = tree
+                    .nearest_neighbor(&[candidate.x, candidate.y])
+                    .map_or(f64::INFINITY, |&[x, y]| distance(candidate, Coord { x, y }));
+                (candidate, gap)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        let Some((point, _)) = best else {
+            break;
+        };
+        tree.insert([point.x, point.y]);
+        points.push(point);
+    }
+    points
+}
+
+/// Вузли сітки від кута рамки, кожен зсунутий у межах `jitter` кроку.
+fn jittered_grid(
+    rect: Rect<f64>,
+    spacing: f64,
+    jitter: f64,
+    inside: &impl Fn(Coord<f64>) -> bool,
+    random: &mut Random,
+) -> Vec<Coord<f64>> {
+    let jitter = jitter.clamp(0.0, 1.0) * spacing;
+    let columns = (rect.width() / spacing).floor() as usize + 1;
+    let rows = (rect.height() / spacing).floor() as usize + 1;
+    let mut points = vec![];
+    for row in 0..rows {
+        for column in 0..columns {
+            let shift = Coord {
+                x: (random.next() - 0.5) * jitter,
+                y: (random.next() - 0.5) * jitter,
+            };
+            let point = rect.min()
+                + Coord {
+                    x: column as f64 * spacing,
+                    y: row as f64 * spacing,
+                }
+                + shift;
+            if inside(point) {
+                points.push(point);
+            }
+        }
+    }
+    points
+}
+
+impl Drawing {
+    /// Розставляє позначки всередині замкнених шляхів малюнка: пунктир, точкову сітку чи
+    /// пакування кіл.
+    ///
+    /// Точки шукаються в областях з `fill_polygons`, тож отвори складених шляхів лишаються
+    /// порожніми, а незамкнені шляхи пропускаються.
+    /// Позначки кожного контуру зберігають його стиль.
+    ///
+    /// # Аргументи
+    /// * `sampling` - спосіб розставлення точок.
+    /// * `mark` - позначка в кожній точці.
+    /// * `seed` - зерно; кожен контур отримує власну послідовність.
+    ///
+    /// # Повертає
+    /// * `Drawing` - позначки з межами цього малюнка.
+    pub fn scatter(&self, sampling: &Sampling, mark: Mark, seed: u64) -> Drawing {
+        let (mut paths, mut indices) = (vec![], vec![]);
+        for (index, polygon) in self.fill_polygons() {
+            for point in sample_polygon(&polygon, sampling, seed ^ splitmix(index as u64)) {
+                for path in mark.paths(point) {
+                    paths.push(path);
+                    indices.push(index);
+                }
+            }
+        }
+        let styles = self.styles_for(indices);
+        Drawing::new(self.bounds, MultiLineString(paths)).with_styles(styles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drawing::style::PathStyle;
+
+    #[test]
+    fn test_samplings_stay_inside_and_keep_spacing() {
+        let square = LineString::from(vec![
+            (0.0, 0.0),
+            (20.0, 0.0),
+            (20.0, 20.0),
+            (0.0, 20.0),
+            (0.0, 0.0),
+        ]);
+        let hole = LineString::from(vec![
+            (5.0, 5.0),
+            (15.0, 5.0),
+            (15.0, 15.0),
+            (5.0, 15.0),
+            (5.0, 5.0),
+        ]);
+        let frame = Polygon::new(square.clone(), vec![hole]);
+        let outside_hole = |p: &Coord<f64>| !(p.x > 5.0 && p.x < 15.0 && p.y > 5.0 && p.y < 15.0);
+
+        let poisson = sample_polygon(&frame, &Sampling::Poisson { spacing: 1.0 }, 3);
+        assert!(poisson.len() > 100, "Рамка 300 мм² щільно заповнюється");
+        assert!(poisson.iter().all(outside_hole), "Отвір лишається порожнім");
+        for (index, a) in poisson.iter().enumerate() {
+            assert!(poisson[index + 1..].iter().all(|b| distance(*a, *b) >= 1.0));
+        }
+        assert_eq!(
+            poisson,
+            sample_polygon(&frame, &Sampling::Poisson { spacing: 1.0 }, 3)
+        );
+
+        let blue = sample_polygon(&frame, &Sampling::BlueNoise { count: 50 }, 3);
+        assert_eq!(blue.len(), 50);
+        assert!(blue.iter().all(outside_hole));
+
+        // Без зсуву лишаються внутрішні вузли сітки з кроком 5 мм, а в рамці всі вони
+        // лежать на межі отвору або в ньому
+        let grid = Sampling::JitteredGrid {
+            spacing: 5.0,
+            jitter: 0.0,
+        };
+        let square = Polygon::new(square, vec![]);
+        assert_eq!(sample_polygon(&square, &grid, 3).len(), 9);
+        assert!(sample_polygon(&frame, &grid, 3).is_empty());
+
+        let drawing = Drawing::new(
+            (40.0, 40.0),
+            MultiLineString(vec![
+                LineString::from(vec![(0.0, 0.0), (30.0, 30.0)]),
+                square.exterior().clone(),
+            ]),
+        )
+        .with_styles(vec![
+            PathStyle::default(),
+            PathStyle {
+                tool: Some(3),
+                ..PathStyle::default()
+            },
+        ]);
+        let packed = drawing.scatter(&Sampling::Poisson { spacing: 2.0 }, Mark::Circle(1.0), 1);
+        assert!(!packed.paths.0.is_empty());
+        assert!(packed.paths.0.iter().all(|circle| circle.is_closed()));
+        assert_eq!(
+            packed.style(0).tool,
+            Some(3),
+            "Позначки беруть стиль контуру"
+        );
+        assert_eq!(packed.bounds, drawing.bounds);
+        let crosses = drawing.scatter(&grid, Mark::Cross(1.0), 1);
+        assert_eq!(crosses.paths.0.len(), 18);
+    }
+}
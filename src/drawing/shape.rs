@@ -0,0 +1,258 @@
+use geo::{BooleanOps, Contains, InteriorPoint, LineString, MultiPolygon, Polygon};
+
+use super::style::FillRule;
+use super::{distance, Drawing};
+
+/// Напрямок обходу замкненого контуру, як його видно на аркуші з віссю y донизу.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    Clockwise,        // За годинниковою стрілкою.
+    CounterClockwise, // Проти годинникової стрілки.
+}
+
+/// Чи замкнений шлях: щонайменше трикутник, що закінчується у своїй першій точці.
+pub fn is_closed(line: &LineString<f64>) -> bool {
+    line.0.len() >= 4 && line.0.first() == line.0.last()
+}
+
+/// Орієнтована площа шляху (в мм²), додатна для обходу за годинниковою стрілкою на аркуші.
+///
+/// Для незамкненого шляху рахується площа контуру, замкненого відрізком від кінця до початку.
+pub fn signed_area(line: &LineString<f64>) -> f64 {
+    let points = &line.0;
+    let closing = match (points.first(), points.last()) {
+        (Some(first), Some(last)) => last.x * first.y - first.x * last.y,
+        _ => 0.0,
+    };
+    let sum: f64 = points
+        .windows(2)
+        .map(|pair| pair[0].x * pair[1].y - pair[1].x * pair[0].y)
+        .sum();
+    (sum + closing) / 2.0
+}
+
+/// Напрямок обходу замкненого шляху.
+///
+/// # Аргументи
+/// * `line` - шлях малюнка.
+///
+/// # Повертає
+/// * `Option<Orientation>` - напрямок обходу або `None`, якщо шлях не замкнений чи
+///   вироджений у лінію без площі.
+pub fn orientation(line: &LineString<f64>) -> Option<Orientation> {
+    if !is_closed(line) {
+        return None;
+    }
+    let area = signed_area(line);
+    match area {
+        _ if area > 0.0 => Some(Orientation::Clockwise),
+        _ if area < 0.0 => Some(Orientation::CounterClockwise),
+        _ => None,
+    }
+}
+
+impl Drawing {
+    /// Номери замкнених шляхів малюнка.
+    pub fn closed_paths(&self) -> Vec<usize> {
+        (0..self.paths.0.len())
+            .filter(|&index| is_closed(&self.paths.0[index]))
+            .collect()
+    }
+
+    /// Замикає шляхи, кінець яких не далі `tolerance` від початку.
+    ///
+    /// SVG-редактори й генератори часто залишають між кінцями контуру крихітний проміжок,
+    /// через який контур не вважається замкненим. Остання точка такого шляху переноситься
+    /// в його початок.
+    ///
+    /// # Аргументи
+    /// * `tolerance` - найбільший проміжок (в мм) між кінцями, який ще замикається.
+    ///
+    /// # Повертає
+    /// * `Drawing` - новий малюнок з тими самими межами та стилями.
+    pub fn close_rings(&self, tolerance: f64) -> Drawing {
+        let mut drawing = self.clone();
+        for line in drawing.paths.0.iter_mut() {
+            let (Some(&first), Some(&last)) = (line.0.first(), line.0.last()) else {
+                continue;
+            };
+            if line.0.len() >= 4 && first != last && distance(first, last) <= tolerance {
+                *line.0.last_mut().unwrap() = first;
+            }
+        }
+        drawing
+    }
+
+    /// Замкнені шляхи малюнка як многокутники для заливки чи приховування ліній.
+    ///
+    /// Кожен контур стає окремим многокутником без отворів; незамкнені шляхи пропускаються,
+    /// тож майже замкнені варто спершу замкнути через `close_rings`.
+    ///
+    /// # Повертає
+    /// * `Vec<Polygon<f64>>` - многокутники в порядку шляхів малюнка.
+    pub fn polygons(&self) -> Vec<Polygon<f64>> {
+        self.closed_paths()
+            .into_iter()
+            .map(|index| Polygon::new(self.paths.0[index].clone(), vec![]))
+            .collect()
+    }
+
+    /// Області заливки замкнених шляхів як многокутники з отворами.
+    ///
+    /// Контури одного складеного шляху (`PathStyle::compound`) поєднуються за його правилом
+    /// заливки, тож у літері «О» внутрішній контур стає отвором. Для `nonzero` контури не
+    /// мають перетинатися, як у шрифтах і піктограмах, а `evenodd` поєднує будь-які. Кожен
+    /// шлях поза складеним шляхом є окремою областю, як у `polygons`.
+    ///
+    /// # Повертає
+    /// * `Vec<(usize, Polygon<f64>)>` - номер шляху, що задає стиль області, і її многокутник.
+    pub fn fill_polygons(&self) -> Vec<(usize, Polygon<f64>)> {
+        let mut groups: Vec<(Option<usize>, Vec<usize>)> = vec![];
+        for index in self.closed_paths() {
+            let compound = self.style(index).compound;
+            match groups
+                .iter_mut()
+                .find(|(known, _)| known.is_some() && *known == compound)
+            {
+                Some((_, members)) => members.push(index),
+                None => groups.push((compound, vec![index])),
+            }
+        }
+        groups
+            .into_iter()
+            .flat_map(|(_, members)| {
+                let rings: Vec<&LineString<f64>> =
+                    members.iter().map(|&index| &self.paths.0[index]).collect();
+                let polygons = match self.style(members[0]).fill_rule.unwrap_or_default() {
+                    FillRule::NonZero => nonzero_polygons(&rings),
+                    FillRule::EvenOdd => {
+                        rings
+                            .iter()
+                            .fold(MultiPolygon(vec![]), |region, &ring| {
+                                region.xor(&MultiPolygon(vec![Polygon::new(ring.clone(), vec![])]))
+                            })
+                            .0
+                    }
+                };
+                polygons
+                    .into_iter()
+                    .map(move |polygon| (members[0], polygon))
+            })
+            .collect()
+    }
+}
+
+/// Многокутники складеного шляху за правилом `nonzero` для контурів, що не перетинаються.
+///
+/// Контури вкладаються в дерево за площею, а число обертів області всередині контуру
+/// дорівнює сумі напрямків обходу його й усіх зовнішніх контурів. Область з ненульовим
+/// числом обертів зафарбовується, тож протилежно обійдений вкладений контур стає отвором.
+fn nonzero_polygons(rings: &[&LineString<f64>]) -> Vec<Polygon<f64>> {
+    let mut order: Vec<usize> = (0..rings.len())
+        .filter(|&index| signed_area(rings[index]) != 0.0)
+        .collect();
+    order.sort_by(|&a, &b| {
+        signed_area(rings[b])
+            .abs()
+            .total_cmp(&signed_area(rings[a]).abs())
+    });
+
+    // Від найбільшого контуру, тож зовнішній контур обробляється раніше за вкладені
+    let mut parents: Vec<Option<usize>> = vec![None; rings.len()];
+    let mut winding = vec![0; rings.len()];
+    for (position, &index) in order.iter().enumerate() {
+        let polygon = Polygon::new(rings[index].clone(), vec![]);
+        let inside = polygon.interior_point();
+        parents[index] = order[..position].iter().rev().copied().find(|&outer| {
+            inside.is_some_and(|point| Polygon::new(rings[outer].clone(), vec![]).contains(&point))
+        });
+        let direction = signed_area(rings[index]).signum() as i32;
+        winding[index] = direction + parents[index].map_or(0, |parent| winding[parent]);
+    }
+
+    // Зафарбований контур у незафарбованому починає многокутник, а незафарбований
+    // контур у зафарбованому вирізає отвір у многокутнику свого зовнішнього контуру
+    let filled = |index: usize| winding[index] != 0;
+    let root = |mut index: usize| {
+        while let Some(parent) = parents[index].filter(|&parent| filled(parent)) {
+            index = parent;
+        }
+        index
+    };
+    let mut polygons: Vec<(usize, Vec<LineString<f64>>)> = vec![];
+    for &index in &order {
+        match (filled(index), parents[index]) {
+            (true, parent) if !parent.is_some_and(filled) => polygons.push((index, vec![])),
+            (false, Some(parent)) if filled(parent) => {
+                let outer = root(parent);
+                if let Some((_, holes)) = polygons.iter_mut().find(|(ring, _)| *ring == outer) {
+                    holes.push(rings[index].clone());
+                }
+            }
+            _ => {}
+        }
+    }
+    polygons
+        .into_iter()
+        .map(|(index, holes)| Polygon::new(rings[index].clone(), holes))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::{Area, Coord, MultiLineString};
+
+    #[test]
+    fn test_closed_shapes_are_detected_closed_and_converted() {
+        let clockwise = vec![
+            (0.0, 0.0),
+            (10.0, 0.0),
+            (10.0, 10.0),
+            (0.0, 10.0),
+            (0.0, 0.0),
+        ];
+        let drawing = Drawing::new(
+            (100.0, 100.0),
+            MultiLineString(vec![
+                LineString::from(clockwise.clone()),
+                LineString::from(vec![(20.0, 0.0), (20.0, 10.0), (30.0, 10.0), (20.05, 0.0)]),
+                LineString::from(vec![(40.0, 0.0), (50.0, 0.0)]),
+                LineString::from(vec![(60.0, 0.0), (70.0, 0.0), (60.0, 0.0)]),
+            ]),
+        );
+
+        assert_eq!(drawing.closed_paths(), [0], "Лише квадрат замкнений");
+        assert_eq!(
+            orientation(&drawing.paths.0[0]),
+            Some(Orientation::Clockwise),
+            "Праворуч, вниз, ліворуч: на аркуші це за годинниковою стрілкою"
+        );
+        let mut reversed = LineString::from(clockwise);
+        reversed.0.reverse();
+        assert_eq!(orientation(&reversed), Some(Orientation::CounterClockwise));
+        assert_eq!(orientation(&drawing.paths.0[2]), None);
+        assert!((signed_area(&drawing.paths.0[0]) - 100.0).abs() < 1e-9);
+
+        let closed = drawing.close_rings(0.1);
+        assert_eq!(
+            closed.closed_paths(),
+            [0, 1],
+            "Проміжок 0.05 мм замикається"
+        );
+        assert_eq!(closed.paths.0[1].0[3], Coord { x: 20.0, y: 0.0 });
+        assert_eq!(
+            orientation(&closed.paths.0[1]),
+            Some(Orientation::CounterClockwise)
+        );
+        assert_eq!(
+            closed.paths.0[2], drawing.paths.0[2],
+            "Відрізок не замикається"
+        );
+        assert_eq!(drawing.close_rings(0.01).closed_paths(), [0]);
+
+        let polygons = closed.polygons();
+        assert_eq!(polygons.len(), 2);
+        assert!((polygons[0].unsigned_area() - 100.0).abs() < 1e-9);
+    }
+}
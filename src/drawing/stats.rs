@@ -0,0 +1,155 @@
+use std::fmt;
+
+use geo::{EuclideanLength, Point, Rect};
+
+use crate::motion::point::PointExtension;
+use crate::tr;
+
+use super::Drawing;
+
+/// Статистика малюнка: обсяг шляхів, довжини рухів і оцінка витрати чорнила.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DrawingStats {
+    pub paths: usize,            // Кількість непорожніх шляхів.
+    pub vertices: usize,         // Загальна кількість точок у шляхах.
+    pub pen_down_length: f64,    // Довжина малювання з опущеним пером (в мм).
+    pub pen_up_length: f64,      // Довжина переїздів з піднятим пером, від (0, 0) і назад (в мм).
+    pub bbox: Option<Rect<f64>>, // Обмежувальна рамка шляхів.
+}
+
+impl DrawingStats {
+    /// Оцінює витрату чорнила як площу, яку покриває перо.
+    ///
+    /// # Аргументи
+    /// * `nib_width` - ширина лінії пера (в мм).
+    ///
+    /// # Повертає
+    /// * `f64` - площа лінії в мм² (довжина малювання × ширина пера).
+    pub fn ink_usage(&self, nib_width: f64) -> f64 {
+        self.pen_down_length * nib_width.max(0.0)
+    }
+
+    /// Частка шляху, яку перо проходить опущеним (0..1).
+    pub fn pen_down_ratio(&self) -> f64 {
+        let total = self.pen_down_length + self.pen_up_length;
+        if total > 0.0 {
+            self.pen_down_length / total
+        } else {
+            0.0
+        }
+    }
+}
+
+impl fmt::Display for DrawingStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", tr!("Paths: {}", "Шляхів: {}", self.paths))?;
+        writeln!(f, "{}", tr!("Points: {}", "Точок: {}", self.vertices))?;
+        writeln!(
+            f,
+            "{}",
+            tr!(
+                "Pen down: {:.1} mm",
+                "Малювання: {:.1} мм",
+                self.pen_down_length
+            )
+        )?;
+        writeln!(
+            f,
+            "{}",
+            tr!("Pen up: {:.1} mm", "Переїзди: {:.1} мм", self.pen_up_length)
+        )?;
+        writeln!(
+            f,
+            "{}",
+            tr!(
+                "Pen down ratio: {:.0}%",
+                "Частка малювання: {:.0}%",
+                self.pen_down_ratio() * 100.0
+            )
+        )?;
+        match self.bbox {
+            Some(bbox) => write!(
+                f,
+                "{}",
+                tr!(
+                    "Bounds: ({:.1}, {:.1}) - ({:.1}, {:.1}) mm",
+                    "Рамка: ({:.1}, {:.1}) - ({:.1}, {:.1}) мм",
+                    bbox.min().x,
+                    bbox.min().y,
+                    bbox.max().x,
+                    bbox.max().y
+                )
+            ),
+            None => write!(f, "{}", tr!("Bounds: none", "Рамка: немає")),
+        }
+    }
+}
+
+impl Drawing {
+    /// Обчислює статистику малюнка.
+    ///
+    /// Переїзди рахуються так само, як їх виконує `Axidraw::draw`: від початкової позиції
+    /// (0, 0) до першого шляху, між шляхами, що не продовжують один одного, і назад додому.
+    ///
+    /// # Повертає
+    /// * `DrawingStats` - кількість шляхів і точок, довжини рухів та рамка малюнка.
+    pub fn stats(&self) -> DrawingStats {
+        let lines: Vec<_> = self
+            .paths
+            .0
+            .iter()
+            .filter(|line| !line.0.is_empty())
+            .collect();
+
+        let mut pen_up_length = 0.0;
+        let mut position = Point::new(0.0, 0.0);
+        for line in &lines {
+            pen_up_length += position.distance(&Point::from(line.0[0]));
+            position = Point::from(line.0[line.0.len() - 1]);
+        }
+        pen_up_length += position.distance(&Point::new(0.0, 0.0));
+
+        DrawingStats {
+            paths: lines.len(),
+            vertices: lines.iter().map(|line| line.0.len()).sum(),
+            pen_down_length: lines.iter().map(|line| line.euclidean_length()).sum(),
+            pen_up_length,
+            bbox: self.bbox(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::{LineString, MultiLineString};
+
+    #[test]
+    fn test_drawing_stats() {
+        let drawing = Drawing::new(
+            (100.0, 100.0),
+            MultiLineString(vec![
+                LineString::from(vec![(0.0, 10.0), (30.0, 10.0), (30.0, 50.0)]),
+                LineString::from(vec![(30.0, 50.0), (30.0, 80.0)]),
+                LineString::from(vec![(60.0, 80.0), (60.0, 0.0)]),
+                LineString::from(Vec::<(f64, f64)>::new()),
+            ]),
+        );
+        let stats = drawing.stats();
+
+        assert_eq!(stats.paths, 3);
+        assert_eq!(stats.vertices, 7);
+        assert!((stats.pen_down_length - 180.0).abs() < 1e-9);
+        // 10 мм до першого шляху, 30 мм між шляхами та 60 мм додому
+        assert!((stats.pen_up_length - 100.0).abs() < 1e-9);
+        assert!((stats.ink_usage(0.5) - 90.0).abs() < 1e-9);
+        assert!((stats.pen_down_ratio() - 180.0 / 280.0).abs() < 1e-9);
+
+        let bbox = stats.bbox.expect("Малюнок не порожній");
+        assert_eq!((bbox.width(), bbox.height()), (60.0, 80.0));
+
+        let empty = Drawing::new((10.0, 10.0), MultiLineString(vec![])).stats();
+        assert_eq!(empty.pen_up_length, 0.0);
+        assert_eq!(empty.bbox, None);
+    }
+}
@@ -0,0 +1,232 @@
+use std::time::Duration;
+
+use geo::MultiLineString;
+use svg::node::Attributes;
+
+use super::Drawing;
+
+/// Стиль шляху без жодного атрибута.
+const PLAIN: PathStyle = PathStyle {
+    color: None,
+    stroke_width: None,
+    tool: None,
+    dwell: None,
+    fill_rule: None,
+    compound: None,
+};
+
+/// Значення властивості елемента SVG з атрибута `style` або однойменного атрибута.
+///
+/// Властивість у `style` переважає атрибут, як у CSS.
+pub(super) fn property(attributes: &Attributes, name: &str) -> Option<String> {
+    let declared = attributes.get("style").and_then(|style| {
+        style.split(';').find_map(|declaration| {
+            let (key, value) = declaration.split_once(':')?;
+            (key.trim() == name).then(|| value.trim().to_string())
+        })
+    });
+    declared.or_else(|| attributes.get(name).map(|value| value.trim().to_string()))
+}
+
+/// Правило заливки SVG (`fill-rule`), за яким вкладені контури складеного шляху стають отворами.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FillRule {
+    #[default]
+    NonZero, // Отвором є контур, що обходить протилежно до зовнішнього, як у шрифтах.
+    EvenOdd, // Отвором є кожен другий рівень вкладення, незалежно від напрямку обходу.
+}
+
+/// Атрибути шляху, прочитані з SVG: колір і товщина лінії, номер інструмента й пауза.
+///
+/// Геометрія `MultiLineString` їх не зберігає, тож малюнок тримає стилі окремо, за
+/// номером шляху. Вони дають змогу розбити малюнок на шари за кольором пера, показати
+/// кольори в попередньому перегляді та зіставити шляхи з інструментами.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PathStyle {
+    pub color: Option<String>, // Колір лінії (`stroke`) у нижньому регістрі, як у SVG.
+    pub stroke_width: Option<f64>, // Товщина лінії (`stroke-width`), після імпорту в мм.
+    pub tool: Option<u32>,     // Номер інструмента з атрибута `data-tool`.
+    pub dwell: Option<Duration>, // Пауза з опущеним пером у кінці шляху з `data-dwell` (в мс).
+    pub fill_rule: Option<FillRule>, // Правило заливки (`fill-rule`) складеного шляху.
+    pub compound: Option<usize>, // Номер складеного шляху: підшляхи одного елемента з кількома `M`.
+}
+
+impl PathStyle {
+    /// Читає стиль елемента SVG з атрибутів представлення та властивостей `style`.
+    ///
+    /// Властивість у `style` переважає однойменний атрибут, як у CSS. Лінія `none` і
+    /// значення, що не розбираються, вважаються незаданими.
+    ///
+    /// # Аргументи
+    /// * `attributes` - атрибути елемента.
+    ///
+    /// # Повертає
+    /// * `PathStyle` - стиль елемента; незадані атрибути дорівнюють `None`.
+    pub fn from_attributes(attributes: &Attributes) -> PathStyle {
+        let property = |name: &str| property(attributes, name);
+        let color = property("stroke")
+            .map(|color| color.to_lowercase())
+            .filter(|color| !color.is_empty() && color != "none");
+        let stroke_width = property("stroke-width")
+            .and_then(|width| width.trim_end_matches("px").trim().parse().ok())
+            .filter(|width: &f64| *width >= 0.0);
+        let tool = attributes
+            .get("data-tool")
+            .and_then(|tool| tool.trim().parse().ok());
+        let dwell = attributes
+            .get("data-dwell")
+            .and_then(|dwell| dwell.trim().parse::<f64>().ok())
+            .filter(|dwell| dwell.is_finite() && *dwell > 0.0)
+            .map(|dwell| Duration::from_secs_f64(dwell / 1000.0));
+        let fill_rule = property("fill-rule").and_then(|rule| match rule.as_str() {
+            "nonzero" => Some(FillRule::NonZero),
+            "evenodd" => Some(FillRule::EvenOdd),
+            _ => None,
+        });
+        PathStyle {
+            color,
+            stroke_width,
+            tool,
+            dwell,
+            fill_rule,
+            compound: None,
+        }
+    }
+
+    /// Стиль, у якому незадані атрибути взято з батьківського елемента, наприклад групи.
+    ///
+    /// Номер складеного шляху належить самому елементу й не успадковується.
+    pub fn inherit(self, parent: &PathStyle) -> PathStyle {
+        PathStyle {
+            color: self.color.or_else(|| parent.color.clone()),
+            stroke_width: self.stroke_width.or(parent.stroke_width),
+            tool: self.tool.or(parent.tool),
+            dwell: self.dwell.or(parent.dwell),
+            fill_rule: self.fill_rule.or(parent.fill_rule),
+            compound: self.compound,
+        }
+    }
+
+    /// Чи не задано жодного атрибута.
+    pub fn is_plain(&self) -> bool {
+        *self == PLAIN
+    }
+}
+
+impl Drawing {
+    /// Задає стилі шляхів малюнка за їх номером.
+    ///
+    /// # Аргументи
+    /// * `styles` - стилі шляхів; шляхи за межами списку лишаються без стилю.
+    ///
+    /// # Повертає
+    /// * `Drawing` - той самий малюнок зі стилями.
+    pub fn with_styles(mut self, styles: Vec<PathStyle>) -> Drawing {
+        self.styles = styles;
+        self
+    }
+
+    /// Стиль шляху з номером `index`; шлях без стилю має усі атрибути незаданими.
+    pub fn style(&self, index: usize) -> &PathStyle {
+        self.styles.get(index).unwrap_or(&PLAIN)
+    }
+
+    /// Стилі шляхів з номерами `indices`, наприклад після зміни порядку шляхів.
+    ///
+    /// # Повертає
+    /// * `Vec<PathStyle>` - стилі в порядку `indices`; порожній, якщо малюнок без стилів.
+    pub(crate) fn styles_for(&self, indices: impl IntoIterator<Item = usize>) -> Vec<PathStyle> {
+        if self.styles.is_empty() {
+            return vec![];
+        }
+        indices
+            .into_iter()
+            .map(|index| self.style(index).clone())
+            .collect()
+    }
+
+    /// Розбиває малюнок на шари за кольором лінії, щоб малювати кожен колір своїм пером.
+    ///
+    /// Шари йдуть у порядку першої появи кольору, а шляхи в шарі зберігають свій порядок.
+    /// Шляхи без кольору утворюють окремий шар.
+    ///
+    /// # Повертає
+    /// * `Vec<Drawing>` - непорожні шари з межами малюнка та стилями шляхів.
+    pub fn split_by_color(&self) -> Vec<Drawing> {
+        let mut colors: Vec<Option<&String>> = vec![];
+        let mut groups: Vec<Vec<usize>> = vec![];
+        for index in 0..self.paths.0.len() {
+            let color = self.style(index).color.as_ref();
+            match colors.iter().position(|known| *known == color) {
+                Some(group) => groups[group].push(index),
+                None => {
+                    colors.push(color);
+                    groups.push(vec![index]);
+                }
+            }
+        }
+
+        groups
+            .into_iter()
+            .map(|indices| {
+                let paths = indices.iter().map(|&index| self.paths.0[index].clone());
+                Drawing::new(self.bounds, MultiLineString(paths.collect()))
+                    .with_styles(self.styles_for(indices))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drawing::order::PathOrder;
+
+    #[test]
+    fn test_split_by_color_keeps_styles() {
+        let svg = r##"<svg viewBox="0 0 100 100">
+            <path d="M0,0 L10,0" stroke="#FF0000" stroke-width="0.5"/>
+            <path d="M0,5 L10,5" style="stroke: blue; stroke-width: 2px; fill-rule: evenodd" stroke="red" data-tool="2" data-dwell="250"/>
+            <line x1="0" y1="9" x2="10" y2="9" stroke="#ff0000"/>
+            <rect x="1" y="1" width="2" height="2" stroke="none"/>
+        </svg>"##;
+        let drawing = Drawing::from_svg(svg).expect("SVG має розбиратися");
+        assert_eq!(drawing.style(0).color.as_deref(), Some("#ff0000"));
+        assert_eq!(drawing.style(0).stroke_width, Some(0.5));
+        assert_eq!(
+            drawing.style(1),
+            &PathStyle {
+                color: Some("blue".to_string()),
+                stroke_width: Some(2.0),
+                tool: Some(2),
+                dwell: Some(Duration::from_millis(250)),
+                fill_rule: Some(FillRule::EvenOdd),
+                compound: None,
+            },
+            "Властивість style переважає атрибут"
+        );
+        assert!(drawing.style(3).is_plain());
+        assert!(drawing.style(10).is_plain(), "Шлях поза списком без стилю");
+
+        let layers = drawing.split_by_color();
+        let sizes: Vec<usize> = layers.iter().map(|layer| layer.paths.0.len()).collect();
+        assert_eq!(sizes, [2, 1, 1], "Червоні шляхи в одному шарі");
+        assert_eq!(layers[0].style(1).color.as_deref(), Some("#ff0000"));
+        assert_eq!(layers[0].paths.0[1], drawing.paths.0[2]);
+
+        // Після впорядкування стиль іде разом зі своїм шляхом
+        let ordered = drawing.optimize(&PathOrder {
+            reverse: false,
+            improve: None,
+        });
+        for (index, path) in ordered.paths.0.iter().enumerate() {
+            let original = drawing
+                .paths
+                .0
+                .iter()
+                .position(|line| line == path)
+                .unwrap();
+            assert_eq!(ordered.style(index), drawing.style(original));
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use geo::{AffineOps, AffineTransform, LineString, MultiLineString};
+
+use super::style::PathStyle;
+use super::Drawing;
+
+/// Найбільша довжина плеча хрестика суміщення (в мм).
+const MARK_ARM: f64 = 3.0;
+
+/// Одна сторінка малюнка, розбитого на аркуші.
+#[derive(Debug, Clone)]
+pub struct Tile {
+    pub row: usize,         // Рядок сторінки, згори вниз.
+    pub column: usize,      // Стовпець сторінки, зліва направо.
+    pub origin: (f64, f64), // Лівий верхній кут сторінки в координатах усього малюнка (в мм).
+    pub drawing: Drawing,   // Частина малюнка в координатах сторінки, з межами розміру сторінки.
+    pub marks: Drawing,     // Хрестики суміщення в координатах сторінки; порожні без перекриття.
+}
+
+impl Tile {
+    /// Частина малюнка разом із хрестиками суміщення, що малюються першими.
+    pub fn with_marks(&self) -> Drawing {
+        let mut paths = self.marks.paths.0.clone();
+        paths.extend(self.drawing.paths.0.iter().cloned());
+        let mut styles = vec![PathStyle::default(); self.marks.paths.0.len()];
+        styles
+            .extend((0..self.drawing.paths.0.len()).map(|index| self.drawing.style(index).clone()));
+        Drawing::new(self.drawing.bounds, MultiLineString(paths)).with_styles(styles)
+    }
+}
+
+/// Початки сторінок уздовж однієї осі з кроком `page - overlap`.
+fn starts(size: f64, page: f64, overlap: f64) -> Vec<f64> {
+    let step = page - overlap;
+    let count = match size > page {
+        true => ((size - overlap) / step).ceil() as usize,
+        false => 1,
+    };
+    (0..count).map(|index| index as f64 * step).collect()
+}
+
+impl Drawing {
+    /// Розбиває малюнок, більший за робоче поле, на сторінки для малювання по черзі.
+    ///
+    /// Сусідні сторінки перекриваються на `overlap`, тож шляхи на стику малюються на обох
+    /// і після суміщення аркушів не лишають щілини. Посередині кожної смуги перекриття
+    /// стоять хрестики суміщення: той самий хрестик є на обох сусідніх сторінках, тож
+    /// новий аркуш кладуть так, щоб його хрестики лягли на вже намальовані. Сторінки без
+    /// жодного шляху пропускаються.
+    ///
+    /// # Аргументи
+    /// * `page_size` - розмір області малювання однієї сторінки (в мм).
+    /// * `overlap` - перекриття сусідніх сторінок (в мм), менше за сторінку.
+    ///
+    /// # Повертає
+    /// * `Vec<Tile>` - сторінки рядок за рядком.
+    pub fn tile(&self, page_size: (f64, f64), overlap: f64) -> Vec<Tile> {
+        let (width, height) = page_size;
+        let overlap = overlap.clamp(0.0, width.min(height) / 2.0);
+        let columns = starts(self.bounds.0, width, overlap);
+        let rows = starts(self.bounds.1, height, overlap);
+        let arm = MARK_ARM.min(overlap / 2.0);
+
+        // Хрестики посередині смуг перекриття, на середині сусідньої сторінки
+        let mut marks = vec![];
+        if arm > 0.0 {
+            for &x in &columns[1..] {
+                marks.extend(rows.iter().map(|&y| (x + overlap / 2.0, y + height / 2.0)));
+            }
+            for &y in &rows[1..] {
+                marks.extend(
+                    columns
+                        .iter()
+                        .map(|&x| (x + width / 2.0, y + overlap / 2.0)),
+                );
+            }
+        }
+
+        let mut tiles = vec![];
+        for (row, &y) in rows.iter().enumerate() {
+            for (column, &x) in columns.iter().enumerate() {
+                let page = Drawing::new(
+                    self.bounds,
+                    MultiLineString(vec![LineString::from(vec![
+                        (x, y),
+                        (x + width, y),
+                        (x + width, y + height),
+                        (x, y + height),
+                        (x, y),
+                    ])]),
+                );
+                let shift = AffineTransform::translate(-x, -y);
+                let mut drawing = self.clip(&page, true);
+                if drawing.paths.0.is_empty() {
+                    continue;
+                }
+                drawing.paths = drawing.paths.affine_transform(&shift);
+                drawing.bounds = page_size;
+
+                let crosses = marks
+                    .iter()
+                    .map(|&(mx, my)| (mx - x, my - y))
+                    .filter(|&(mx, my)| (0.0..=width).contains(&mx) && (0.0..=height).contains(&my))
+                    .flat_map(|(mx, my)| {
+                        [
+                            LineString::from(vec![(mx - arm, my), (mx + arm, my)]),
+                            LineString::from(vec![(mx, my - arm), (mx, my + arm)]),
+                        ]
+                    })
+                    .collect();
+                tiles.push(Tile {
+                    row,
+                    column,
+                    origin: (x, y),
+                    drawing,
+                    marks: Drawing::new(page_size, MultiLineString(crosses)),
+                });
+            }
+        }
+        tiles
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::{BoundingRect, EuclideanLength};
+
+    #[test]
+    fn test_tile_splits_with_overlap_and_marks() {
+        // Горизонтальна лінія через три сторінки шириною 100 з перекриттям 10
+        let drawing = Drawing::new(
+            (250.0, 80.0),
+            MultiLineString(vec![LineString::from(vec![(5.0, 40.0), (245.0, 40.0)])]),
+        );
+        let tiles = drawing.tile((100.0, 100.0), 10.0);
+        assert_eq!(tiles.len(), 3);
+        let origins: Vec<(f64, f64)> = tiles.iter().map(|tile| tile.origin).collect();
+        assert_eq!(origins, [(0.0, 0.0), (90.0, 0.0), (180.0, 0.0)]);
+        assert_eq!(tiles[1].drawing.bounds, (100.0, 100.0));
+        let lengths: Vec<f64> = tiles
+            .iter()
+            .map(|tile| tile.drawing.paths.euclidean_length())
+            .collect();
+        assert!((lengths[0] - 95.0).abs() < 1e-9, "{:?}", lengths);
+        assert!((lengths[1] - 100.0).abs() < 1e-9);
+        assert!((lengths[2] - 65.0).abs() < 1e-9);
+
+        // Хрестик посередині перекриття першої й другої сторінок є на обох
+        let center = |tile: &Tile, index: usize| {
+            let rect = tile.marks.paths.0[index].bounding_rect().unwrap();
+            (
+                rect.center().x + tile.origin.0,
+                rect.center().y + tile.origin.1,
+            )
+        };
+        assert_eq!(tiles[0].marks.paths.0.len(), 2, "Один хрестик — дві лінії");
+        assert_eq!(tiles[1].marks.paths.0.len(), 4);
+        assert_eq!(center(&tiles[0], 0), (95.0, 50.0));
+        assert_eq!(center(&tiles[1], 0), (95.0, 50.0));
+        assert_eq!(tiles[1].with_marks().paths.0.len(), 5);
+
+        // Без перекриття хрестиків немає, а малюнок у межах сторінки не ділиться
+        assert!(drawing.tile((100.0, 100.0), 0.0)[0]
+            .marks
+            .paths
+            .0
+            .is_empty());
+        assert_eq!(drawing.tile((300.0, 100.0), 10.0).len(), 1);
+    }
+}
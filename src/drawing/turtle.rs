@@ -0,0 +1,466 @@
+use std::time::Duration;
+
+use geo::{AffineOps, AffineTransform, BoundingRect, Coord, LineString, MultiLineString};
+use thiserror::Error;
+
+use super::style::PathStyle;
+use super::Drawing;
+use crate::tr;
+
+/// Помилки розбору й виконання сценарію черепашки.
+#[derive(Debug, Error, PartialEq)]
+pub enum TurtleError {
+    /// Невідома команда сценарію.
+    #[error(
+        "{}",
+        tr!(
+            "Line {}: unknown command '{}'",
+            "Рядок {}: невідома команда '{}'",
+            .line,
+            .command
+        )
+    )]
+    UnknownCommand { line: usize, command: String },
+
+    /// Команді бракує числового аргументу або він не є числом.
+    #[error(
+        "{}",
+        tr!(
+            "Line {}: '{}' expects a number",
+            "Рядок {}: '{}' очікує число",
+            .line,
+            .command
+        )
+    )]
+    MissingNumber { line: usize, command: String },
+
+    /// Дужка блоку `repeat` не має пари.
+    #[error(
+        "{}",
+        tr!(
+            "Line {}: unmatched bracket",
+            "Рядок {}: дужка без пари",
+            .line
+        )
+    )]
+    UnmatchedBracket { line: usize },
+
+    /// `pop` без відповідного `push`.
+    #[error(
+        "{}",
+        tr!(
+            "Line {}: pop without a matching push",
+            "Рядок {}: pop без відповідного push",
+            .line
+        )
+    )]
+    EmptyStack { line: usize },
+}
+
+/// Стан черепашки, який зберігає `push` і відновлює `pop`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct State {
+    position: Coord<f64>, // Положення черепашки (в мм).
+    heading: f64,         // Напрямок руху (в градусах); 0 — праворуч, 90 — донизу аркуша.
+    pen_down: bool,       // Чи залишає черепашка слід.
+}
+
+/// Черепашача графіка: черепашка ходить аркушем і залишає слід опущеним пером.
+///
+/// Кути відраховуються за годинниковою стрілкою, як їх видно на аркуші з віссю y донизу.
+/// Черепашка починає в (0, 0) з опущеним пером і дивиться праворуч.
+///
+/// # Приклад
+///
+/// ```
+/// use rsaxi::drawing::turtle::Turtle;
+/// let mut turtle = Turtle::new();
+/// for _ in 0..4 {
+///     turtle.forward(20.0).turn(90.0);
+/// }
+/// assert_eq!(turtle.to_drawing(0.0).paths.0.len(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Turtle {
+    state: State,                  // Поточний стан.
+    stack: Vec<State>,             // Стани, збережені `push`.
+    paths: Vec<LineString<f64>>,   // Завершені шляхи.
+    dwells: Vec<Option<Duration>>, // Пауза в кінці кожного завершеного шляху.
+    current: Vec<Coord<f64>>,      // Точки шляху, що малюється зараз.
+}
+
+impl Default for Turtle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Turtle {
+    /// Створює черепашку в (0, 0) з опущеним пером, що дивиться праворуч.
+    pub fn new() -> Self {
+        Turtle {
+            state: State {
+                position: Coord::zero(),
+                heading: 0.0,
+                pen_down: true,
+            },
+            stack: vec![],
+            paths: vec![],
+            dwells: vec![],
+            current: vec![],
+        }
+    }
+
+    /// Проходить `distance` мм уперед; від'ємна відстань веде назад.
+    pub fn forward(&mut self, distance: f64) -> &mut Self {
+        let (sin, cos) = self.state.heading.to_radians().sin_cos();
+        let target = self.state.position + Coord { x: cos, y: sin } * distance;
+        if self.state.pen_down {
+            if self.current.is_empty() {
+                self.current.push(self.state.position);
+            }
+            self.current.push(target);
+        }
+        self.state.position = target;
+        self
+    }
+
+    /// Повертає на `degrees` градусів за годинниковою стрілкою; від'ємний кут — проти.
+    pub fn turn(&mut self, degrees: f64) -> &mut Self {
+        self.state.heading = (self.state.heading + degrees).rem_euclid(360.0);
+        self
+    }
+
+    /// Піднімає перо: далі черепашка ходить без сліду.
+    pub fn pen_up(&mut self) -> &mut Self {
+        self.finish_path();
+        self.state.pen_down = false;
+        self
+    }
+
+    /// Опускає перо: далі черепашка залишає слід.
+    pub fn pen_down(&mut self) -> &mut Self {
+        self.state.pen_down = true;
+        self
+    }
+
+    /// Стоїть з опущеним пером `duration`, наприклад щоб чорнило розтеклося крапкою.
+    ///
+    /// Пауза завершує поточний шлях там, де стоїть черепашка, а без шляху ставить крапку.
+    /// Кілька пауз поспіль в одній точці додаються. З піднятим пером пауза не залишає
+    /// сліду, тож нічого не змінюється.
+    pub fn dwell(&mut self, duration: Duration) -> &mut Self {
+        if !self.state.pen_down || duration.is_zero() {
+            return self;
+        }
+        if self.current.is_empty() {
+            let position = self.state.position;
+            if let (Some(path), Some(Some(dwell))) = (self.paths.last(), self.dwells.last_mut()) {
+                if path.0.last() == Some(&position) {
+                    *dwell += duration;
+                    return self;
+                }
+            }
+            self.current.push(position);
+        }
+        self.paths
+            .push(LineString(std::mem::take(&mut self.current)));
+        self.dwells.push(Some(duration));
+        self
+    }
+
+    /// Запам'ятовує положення, напрямок і стан пера.
+    pub fn push(&mut self) -> &mut Self {
+        self.stack.push(self.state);
+        self
+    }
+
+    /// Повертається до стану, збереженого останнім `push`.
+    ///
+    /// Черепашка переноситься без сліду, тож поточний шлях завершується.
+    ///
+    /// # Повертає
+    /// * `bool` - `false`, якщо збережених станів немає і нічого не змінилося.
+    pub fn pop(&mut self) -> bool {
+        let Some(state) = self.stack.pop() else {
+            return false;
+        };
+        self.finish_path();
+        self.state = state;
+        true
+    }
+
+    /// Завершує шлях, що малюється, якщо в ньому є хоч один відрізок.
+    fn finish_path(&mut self) {
+        let points = std::mem::take(&mut self.current);
+        if points.len() >= 2 {
+            self.paths.push(LineString(points));
+            self.dwells.push(None);
+        }
+    }
+
+    /// Повертає слід черепашки як малюнок.
+    ///
+    /// Шляхи зсуваються так, щоб їх рамка починалася на відстані `padding` від початку
+    /// координат, а межі малюнка охоплюють слід разом із полями з усіх боків.
+    ///
+    /// # Аргументи
+    /// * `padding` - поля навколо сліду (в мм).
+    ///
+    /// # Повертає
+    /// * `Drawing` - малюнок сліду; без сліду — порожній малюнок розміром з поля.
+    pub fn to_drawing(&self, padding: f64) -> Drawing {
+        let mut turtle = self.clone();
+        turtle.finish_path();
+        let paths = MultiLineString(turtle.paths);
+        let Some(bbox) = paths.bounding_rect() else {
+            return Drawing::new((2.0 * padding, 2.0 * padding), paths);
+        };
+        let offset = AffineTransform::translate(padding - bbox.min().x, padding - bbox.min().y);
+        let bounds = (bbox.width() + 2.0 * padding, bbox.height() + 2.0 * padding);
+        let styles = match turtle.dwells.iter().any(Option::is_some) {
+            true => turtle
+                .dwells
+                .into_iter()
+                .map(|dwell| PathStyle {
+                    dwell,
+                    ..PathStyle::default()
+                })
+                .collect(),
+            false => vec![],
+        };
+        Drawing::new(bounds, paths.affine_transform(&offset)).with_styles(styles)
+    }
+}
+
+/// Слово сценарію разом із номером рядка, де воно стоїть.
+type Token<'a> = (usize, &'a str);
+
+/// Розбиває сценарій на слова, відкидаючи коментарі від `#` до кінця рядка.
+fn tokenize(script: &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    for (number, line) in script.lines().enumerate() {
+        let code = line.split('#').next().unwrap_or_default();
+        for word in code.split_whitespace() {
+            // Дужки можуть стояти впритул до сусідніх слів, наприклад `[fd 10]`
+            let mut rest = word;
+            while !rest.is_empty() {
+                let split = match rest.find(['[', ']']) {
+                    Some(0) => 1,
+                    Some(position) => position,
+                    None => rest.len(),
+                };
+                tokens.push((number + 1, &rest[..split]));
+                rest = &rest[split..];
+            }
+        }
+    }
+    tokens
+}
+
+/// Виконує слова сценарію до кінця або, для тіла блоку `nested`, до закривальної дужки.
+///
+/// # Повертає
+/// * `Result<usize, TurtleError>` - номер слова після виконаних команд.
+fn execute(
+    turtle: &mut Turtle,
+    tokens: &[Token],
+    mut index: usize,
+    nested: bool,
+) -> Result<usize, TurtleError> {
+    while let Some(&(line, word)) = tokens.get(index) {
+        index += 1;
+        let command = word.to_lowercase();
+        let mut number = || {
+            let value = tokens
+                .get(index)
+                .and_then(|(_, value)| value.parse::<f64>().ok());
+            index += 1;
+            value
+                .filter(|value| value.is_finite())
+                .ok_or_else(|| TurtleError::MissingNumber {
+                    line,
+                    command: word.to_string(),
+                })
+        };
+        match command.as_str() {
+            "forward" | "fd" => {
+                turtle.forward(number()?);
+            }
+            "back" | "bk" => {
+                turtle.forward(-number()?);
+            }
+            "right" | "rt" => {
+                turtle.turn(number()?);
+            }
+            "left" | "lt" => {
+                turtle.turn(-number()?);
+            }
+            "penup" | "pu" => {
+                turtle.pen_up();
+            }
+            "pendown" | "pd" => {
+                turtle.pen_down();
+            }
+            "dwell" => {
+                turtle.dwell(Duration::from_secs_f64(number()?.max(0.0) / 1000.0));
+            }
+            "push" => {
+                turtle.push();
+            }
+            "pop" => {
+                if !turtle.pop() {
+                    return Err(TurtleError::EmptyStack { line });
+                }
+            }
+            "repeat" => {
+                let count = number()?;
+                if tokens.get(index).map(|(_, value)| *value) != Some("[") {
+                    return Err(TurtleError::UnmatchedBracket { line });
+                }
+                let body = index + 1;
+                // Порожнє повторення все одно пропускає тіло блоку
+                index = skip_block(tokens, body, line)?;
+                for _ in 0..count.max(0.0) as usize {
+                    execute(turtle, tokens, body, true)?;
+                }
+            }
+            "]" if nested => return Ok(index),
+            "]" => return Err(TurtleError::UnmatchedBracket { line }),
+            _ => {
+                return Err(TurtleError::UnknownCommand {
+                    line,
+                    command: word.to_string(),
+                })
+            }
+        }
+    }
+    Ok(index)
+}
+
+/// Знаходить кінець блоку, що починається зі слова `start`.
+///
+/// # Повертає
+/// * `Result<usize, TurtleError>` - номер слова після закривальної дужки.
+fn skip_block(tokens: &[Token], start: usize, line: usize) -> Result<usize, TurtleError> {
+    let mut depth = 1;
+    for (offset, (_, word)) in tokens[start..].iter().enumerate() {
+        match *word {
+            "[" => depth += 1,
+            "]" => depth -= 1,
+            _ => {}
+        }
+        if depth == 0 {
+            return Ok(start + offset + 1);
+        }
+    }
+    Err(TurtleError::UnmatchedBracket { line })
+}
+
+/// Виконує сценарій черепашки.
+///
+/// Сценарій складається з команд, розділених пробілами чи новими рядками:
+/// `forward N`/`fd N`, `back N`/`bk N`, `right A`/`rt A`, `left A`/`lt A`,
+/// `penup`/`pu`, `pendown`/`pd`, `dwell MS`, `push`, `pop` і `repeat N [ ... ]`. Відстані
+/// задаються в міліметрах, кути — в градусах, паузи — в мілісекундах, а `#` починає
+/// коментар до кінця рядка.
+///
+/// # Аргументи
+/// * `script` - текст сценарію.
+///
+/// # Повертає
+/// * `Result<Turtle, TurtleError>` - черепашка зі слідом сценарію або помилка з номером рядка.
+///
+/// # Приклад
+///
+/// ```
+/// use rsaxi::drawing::turtle::run_script;
+/// let turtle = run_script("repeat 3 [fd 30 rt 120]").unwrap();
+/// assert_eq!(turtle.to_drawing(0.0).paths.0[0].0.len(), 4);
+/// ```
+pub fn run_script(script: &str) -> Result<Turtle, TurtleError> {
+    let mut turtle = Turtle::new();
+    execute(&mut turtle, &tokenize(script), 0, false)?;
+    Ok(turtle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drawing::distance;
+
+    #[test]
+    fn test_turtle_script_draws_branches() {
+        let script = "
+            # Квадрат зі стороною 10 мм
+            repeat 4 [fd 10 rt 90]
+            pu fd 20 pd
+            push lt 90 fd 5 pop
+            push rt 90 fd 5 pop
+        ";
+        let drawing = run_script(script).unwrap().to_drawing(1.0);
+        assert_eq!(drawing.paths.0.len(), 3, "Квадрат і дві гілки");
+        assert_eq!(drawing.paths.0[0].0.len(), 5);
+        let square = &drawing.paths.0[0].0;
+        assert!(distance(square[0], square[4]) < 1e-9, "Квадрат замикається");
+        assert!(
+            (drawing.bounds.0 - 22.0).abs() < 1e-9,
+            "Слід від 0 до 20 мм і поля по 1 мм"
+        );
+        assert!(
+            (drawing.bounds.1 - 17.0).abs() < 1e-9,
+            "Від верхньої гілки до низу квадрата 15 мм"
+        );
+        let up = &drawing.paths.0[1].0;
+        assert!(
+            (up[1].y - 1.0).abs() < 1e-9,
+            "Поворот ліворуч веде вгору аркуша"
+        );
+
+        assert_eq!(
+            run_script("fd 10\njump 5").unwrap_err(),
+            TurtleError::UnknownCommand {
+                line: 2,
+                command: "jump".into()
+            }
+        );
+        assert!(matches!(
+            run_script("fd ten"),
+            Err(TurtleError::MissingNumber { line: 1, .. })
+        ));
+        assert!(matches!(
+            run_script("repeat 2 [fd 1"),
+            Err(TurtleError::UnmatchedBracket { .. })
+        ));
+        assert!(matches!(
+            run_script("fd 1 ]"),
+            Err(TurtleError::UnmatchedBracket { .. })
+        ));
+        assert_eq!(
+            run_script("pop").unwrap_err(),
+            TurtleError::EmptyStack { line: 1 }
+        );
+    }
+
+    #[test]
+    fn test_dwell_ends_path_or_makes_dot() {
+        let drawing = run_script("fd 10 dwell 200 dwell 100 fd 10 pu fd 5 dwell 50 pd dwell 300")
+            .unwrap()
+            .to_drawing(0.0);
+        let points: Vec<usize> = drawing.paths.0.iter().map(|path| path.0.len()).collect();
+        assert_eq!(points, [2, 2, 1], "Відрізки до і після паузи та крапка");
+        assert_eq!(drawing.style(0).dwell, Some(Duration::from_millis(300)));
+        assert_eq!(drawing.style(1).dwell, None);
+        assert_eq!(
+            drawing.style(2).dwell,
+            Some(Duration::from_millis(300)),
+            "Пауза з піднятим пером не діє"
+        );
+        assert!((drawing.paths.0[2].0[0].x - 25.0).abs() < 1e-9);
+        assert!(run_script("fd 10")
+            .unwrap()
+            .to_drawing(0.0)
+            .styles
+            .is_empty());
+    }
+}
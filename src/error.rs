@@ -0,0 +1,142 @@
+use geo::Rect;
+use thiserror::Error;
+
+use crate::axidraw::OptionsError;
+use crate::config::ConfigError;
+use crate::control::Cancelled;
+use crate::device::hex::HexError;
+use crate::device::DeviceError;
+use crate::drawing::correction::CorrectionError;
+use crate::drawing::generator::GeneratorError;
+use crate::drawing::import::ImportError;
+use crate::drawing::layout::LayoutError;
+use crate::drawing::turtle::TurtleError;
+use crate::jobfile::JobFileError;
+use crate::motion::error::PlanError;
+use crate::paper::PaperError;
+use crate::raster::RasterError;
+use crate::text::font::error::FontError;
+use crate::text::text::TextBuilderError;
+use crate::tr;
+
+/// Помилка бібліотеки, що об'єднує помилки окремих модулів.
+///
+/// Кожна помилка модуля перетворюється на `Error` через `?`, тож код, що керує
+/// плотером, може обробляти їх одним типом і розрізняти за варіантом.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Помилка зв'язку з платою EBB.
+    #[error(transparent)]
+    Device(#[from] DeviceError),
+
+    /// Неправильний образ прошивки EBB.
+    #[error(transparent)]
+    Hex(#[from] HexError),
+
+    /// Недійсні параметри плотера.
+    #[error(transparent)]
+    Options(#[from] OptionsError),
+
+    /// Помилка планування руху.
+    #[error(transparent)]
+    Plan(#[from] PlanError),
+
+    /// Помилка опису аркуша паперу.
+    #[error(transparent)]
+    Paper(#[from] PaperError),
+
+    /// Помилка імпорту SVG.
+    #[error(transparent)]
+    Import(#[from] ImportError),
+
+    /// Помилка шрифту.
+    #[error(transparent)]
+    Font(#[from] FontError),
+
+    /// Помилка побудови тексту.
+    #[error(transparent)]
+    Text(#[from] TextBuilderError),
+
+    /// Малювання скасовано пультом, кнопкою плати або сигналом.
+    #[error(transparent)]
+    Cancelled(#[from] Cancelled),
+
+    /// Помилка читання файлу завдання.
+    #[error(transparent)]
+    JobFile(#[from] JobFileError),
+
+    /// Помилка читання чи запису файлу конфігурації.
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+
+    /// Недійсні виміри калібрування геометрії.
+    #[error(transparent)]
+    Correction(#[from] CorrectionError),
+
+    /// Помилка читання растрового зображення.
+    #[error(transparent)]
+    Raster(#[from] RasterError),
+
+    /// Помилка сценарію черепашки.
+    #[error(transparent)]
+    Turtle(#[from] TurtleError),
+
+    /// Невідомий генератор або недійсні його параметри.
+    #[error(transparent)]
+    Generator(#[from] GeneratorError),
+
+    /// Недійсна сітка копій або проміжки, що не лишають для них місця.
+    #[error(transparent)]
+    Layout(#[from] LayoutError),
+
+    /// Помилка вводу-виводу (файли, мережа).
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    /// Шлях виходить за межі області для малювання аркуша.
+    #[error(
+        "{}",
+        tr!(
+            "Path {:?} is outside the printable area of sheet {} ({:?} mm)",
+            "Шлях {:?} виходить за межі області для малювання аркуша {} ({:?} мм)",
+            .bbox,
+            .paper,
+            .size
+        )
+    )]
+    OutOfBounds {
+        bbox: Option<Rect<f64>>,
+        paper: String,
+        size: (f64, f64),
+    },
+}
+
+/// Результат операцій бібліотеки.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::motion::planner::Planner;
+
+    #[test]
+    fn test_module_errors_convert_with_question_mark() {
+        fn plan() -> Result<f64> {
+            Ok(Planner::new(100.0, 20.0, 1.0).plan(vec![])?.total_time)
+        }
+        fn paper() -> Result<()> {
+            "a9-sideways".parse::<crate::paper::Paper>()?;
+            Ok(())
+        }
+
+        let error = plan().unwrap_err();
+        assert!(matches!(error, Error::Plan(PlanError::InsufficientPoints)));
+        assert_eq!(
+            error.to_string(),
+            PlanError::InsufficientPoints.to_string(),
+            "Повідомлення модуля передається без змін"
+        );
+        assert!(matches!(paper(), Err(Error::Paper(_))));
+        assert!(matches!(Error::from(Cancelled), Error::Cancelled(_)));
+    }
+}
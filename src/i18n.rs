@@ -0,0 +1,114 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Змінна середовища, що задає мову повідомлень.
+pub const LANG_VAR: &str = "RSAXI_LANG";
+
+/// Мова повідомлень для користувача: помилок, довідки командного рядка та журналу.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lang {
+    English,   // Англійська, типова мова.
+    Ukrainian, // Українська.
+}
+
+/// Вибрана мова: 0 — ще не визначена, далі `Lang as u8 + 1`.
+static LANG: AtomicU8 = AtomicU8::new(0);
+
+impl Lang {
+    /// Розбирає код мови на зразок `en`, `uk` або `uk_UA.UTF-8`.
+    ///
+    /// # Аргументи
+    /// * `code` - код мови; регістр і суфікс регіону не враховуються.
+    ///
+    /// # Повертає
+    /// * `Option<Lang>` - мова або `None`, якщо код невідомий.
+    pub fn parse(code: &str) -> Option<Lang> {
+        let code = code.trim().to_ascii_lowercase();
+        let language = code.split(['_', '-', '.']).next().unwrap_or_default();
+        match language {
+            "en" | "english" => Some(Lang::English),
+            "uk" | "ua" | "ukrainian" => Some(Lang::Ukrainian),
+            _ => None,
+        }
+    }
+
+    /// Вибирає з двох варіантів повідомлення той, що відповідає мові.
+    pub fn pick<T>(self, english: T, ukrainian: T) -> T {
+        match self {
+            Lang::English => english,
+            Lang::Ukrainian => ukrainian,
+        }
+    }
+
+    fn from_u8(value: u8) -> Option<Lang> {
+        match value {
+            1 => Some(Lang::English),
+            2 => Some(Lang::Ukrainian),
+            _ => None,
+        }
+    }
+
+    fn to_u8(self) -> u8 {
+        match self {
+            Lang::English => 1,
+            Lang::Ukrainian => 2,
+        }
+    }
+}
+
+/// Повертає мову повідомлень.
+///
+/// Під час першого виклику мова зчитується з `RSAXI_LANG`; без неї або з невідомим
+/// кодом повідомлення виводяться англійською.
+pub fn lang() -> Lang {
+    if let Some(lang) = Lang::from_u8(LANG.load(Ordering::Relaxed)) {
+        return lang;
+    }
+    let lang = std::env::var(LANG_VAR)
+        .ok()
+        .and_then(|code| Lang::parse(&code))
+        .unwrap_or(Lang::English);
+    LANG.store(lang.to_u8(), Ordering::Relaxed);
+    lang
+}
+
+/// Задає мову повідомлень замість `RSAXI_LANG`.
+pub fn set_lang(lang: Lang) {
+    LANG.store(lang.to_u8(), Ordering::Relaxed);
+}
+
+/// Форматує повідомлення мовою, вибраною `lang()`.
+///
+/// Перший рядок формату англійський, другий український; аргументи спільні.
+///
+/// ```
+/// let message = rsaxi::tr!("Port {} not found", "Порт {} не знайдено", "COM3");
+/// assert!(message.contains("COM3"));
+/// ```
+#[macro_export]
+macro_rules! tr {
+    ($english:literal, $ukrainian:literal $(, $arg:expr)* $(,)?) => {
+        match $crate::i18n::lang() {
+            $crate::i18n::Lang::English => format!($english $(, $arg)*),
+            $crate::i18n::Lang::Ukrainian => format!($ukrainian $(, $arg)*),
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_language_codes() {
+        assert_eq!(Lang::parse("uk"), Some(Lang::Ukrainian));
+        assert_eq!(Lang::parse("uk_UA.UTF-8"), Some(Lang::Ukrainian));
+        assert_eq!(Lang::parse(" EN-us "), Some(Lang::English));
+        assert_eq!(Lang::parse("de"), None);
+        assert_eq!(Lang::Ukrainian.pick("Cancelled", "Скасовано"), "Скасовано");
+        assert_eq!(Lang::English.pick("Cancelled", "Скасовано"), "Cancelled");
+
+        // Мова залежить від `RSAXI_LANG` середовища тестів, тож порівнюємо з нею
+        let message = crate::tr!("{} paths", "Шляхів: {}", 3);
+        assert_eq!(message, lang().pick("3 paths", "Шляхів: 3"));
+    }
+}
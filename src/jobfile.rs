@@ -0,0 +1,637 @@
+use std::path::Path;
+use std::time::Duration;
+
+use geo::{Coord, LineString, MultiLineString};
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use crate::axidraw::{AxiDrawModel, Options};
+use crate::device::tool::Tool;
+use crate::device::StepMode;
+use crate::drawing::coordinates::CoordinateSystem;
+use crate::drawing::lead::Leads;
+use crate::drawing::refill::Refill;
+use crate::drawing::style::PathStyle;
+use crate::drawing::{distance, Drawing};
+use crate::error::Error;
+use crate::motion::job::CHAIN_TOLERANCE;
+use crate::paper::{Margins, Paper};
+use crate::tr;
+
+/// Позначка формату на початку кожного файлу завдання.
+const FORMAT: &str = "rsaxi-job";
+
+/// Версія формату, яку записує `JobFile::to_json`.
+pub const VERSION: u64 = 1;
+
+/// Помилки читання файлу завдання.
+#[derive(Debug, Error)]
+pub enum JobFileError {
+    /// Файл не є коректним JSON.
+    #[error(
+        "{}",
+        tr!(
+            "Job file is not valid JSON: {}",
+            "Файл завдання не є коректним JSON: {}",
+            .0
+        )
+    )]
+    Json(#[from] serde_json::Error),
+
+    /// JSON не містить позначки формату `rsaxi-job`.
+    #[error("{}", tr!("Not an rsaxi job file", "Це не файл завдання rsaxi"))]
+    Format,
+
+    /// Файл записано новішою версією формату.
+    #[error(
+        "{}",
+        tr!(
+            "Unsupported job file version {} (supported up to {})",
+            "Непідтримувана версія файлу завдання {} (підтримуються до {})",
+            .0,
+            VERSION
+        )
+    )]
+    Version(u64),
+
+    /// Поле відсутнє або має неправильний тип чи значення.
+    #[error(
+        "{}",
+        tr!(
+            "Job file field '{}' is missing or invalid",
+            "Поле '{}' у файлі завдання відсутнє або неправильне",
+            .0
+        )
+    )]
+    Field(&'static str),
+}
+
+/// Підготовлене завдання: впорядковані шляхи шарів разом із параметрами малювання.
+///
+/// Дороге впорядкування виконується один раз під час підготовки, а файл завдання
+/// малюється без змін, тож кожне малювання того самого файлу однакове. Шляхи, що
+/// продовжують один одного, зливаються, тож номер шляху у файлі збігається з номером
+/// шляху в стані малювання і за ним можна продовжити перерване малювання.
+#[derive(Clone)]
+pub struct JobFile {
+    pub options: Options,     // Параметри пера, руху, аркуша, заходів і занурень.
+    pub layers: Vec<Drawing>, // Шари в порядку малювання; між ними перо можна змінити.
+}
+
+impl JobFile {
+    /// Готує завдання з шарів малюнка.
+    ///
+    /// # Аргументи
+    /// * `options` - параметри, з якими малюватиметься завдання; система координат
+    ///   `coordinates`, впорядкування `order` і напрямок `direction` застосовуються до
+    ///   кожного шару й не зберігаються.
+    /// * `layers` - шари в порядку малювання.
+    ///
+    /// # Повертає
+    /// * `Result<JobFile, Error>` - завдання або `Error::OutOfBounds`, якщо шар не
+    ///   вміщується в області для малювання аркуша.
+    pub fn prepare(options: &Options, layers: Vec<Drawing>) -> Result<Self, Error> {
+        let paper = options
+            .paper
+            .unwrap_or_else(|| Paper::from_model(options.model));
+        let mut prepared = vec![];
+        for layer in layers {
+            let placed = layer.in_coordinates(&options.coordinates);
+            if !placed.fits(&paper) {
+                return Err(Error::OutOfBounds {
+                    bbox: placed.bbox(),
+                    paper: paper.name.to_string(),
+                    size: paper.size(),
+                });
+            }
+            prepared.push(chain_paths(&options.arrange(&layer)));
+        }
+
+        let mut options = options.clone();
+        options.order = None;
+        options.direction = None;
+        options.coordinates = CoordinateSystem::default();
+        Ok(JobFile {
+            options,
+            layers: prepared,
+        })
+    }
+
+    /// Загальна кількість шляхів у всіх шарах.
+    pub fn total_paths(&self) -> usize {
+        self.layers.iter().map(|layer| layer.paths.0.len()).sum()
+    }
+
+    /// Повертає завдання без перших `count` шляхів, щоб продовжити перерване малювання.
+    ///
+    /// Шари, від яких не залишилося шляхів, вилучаються разом зі своєю потужністю інструмента.
+    pub fn skip_paths(&self, count: usize) -> JobFile {
+        let mut remaining = count;
+        let (kept, layers): (Vec<usize>, Vec<Drawing>) = self
+            .layers
+            .iter()
+            .enumerate()
+            .filter_map(|(index, layer)| {
+                let skipped = remaining.min(layer.paths.0.len());
+                remaining -= skipped;
+                let paths: Vec<_> = layer.paths.0[skipped..].to_vec();
+                let styles = layer.styles_for(skipped..layer.paths.0.len());
+                (!paths.is_empty()).then(|| {
+                    let drawing = Drawing::new(layer.bounds, MultiLineString(paths));
+                    (index, drawing.with_styles(styles))
+                })
+            })
+            .unzip();
+        JobFile {
+            options: Options {
+                tool: self.options.tool.select_layers(&kept),
+                ..self.options.clone()
+            },
+            layers,
+        }
+    }
+
+    /// Параметри для малювання завдання на конкретному плотері.
+    ///
+    /// Перо, рух, аркуш і заходи беруться із завдання, а порт, журнал обміну, параметри
+    /// зв'язку, поправка геометрії та місце паркування — з `connection`, бо вони залежать
+    /// від комп'ютера й плотера, а не малюнка.
+    pub fn plot_options(&self, connection: &Options) -> Options {
+        Options {
+            port: connection.port.clone(),
+            port_config: connection.port_config.clone(),
+            transcript: connection.transcript.clone(),
+            serial: connection.serial,
+            correction: connection.correction,
+            park_position: connection.park_position,
+            ..self.options.clone()
+        }
+    }
+
+    /// Перевіряє, чи схожий вміст файлу на завдання, а не на SVG.
+    pub fn is_job(content: &str) -> bool {
+        content.trim_start().starts_with('{')
+    }
+
+    /// Записує завдання в JSON.
+    pub fn to_json(&self) -> String {
+        let options = &self.options;
+        let value = json!({
+            "format": FORMAT,
+            "version": VERSION,
+            "options": {
+                "model": options.model.code(),
+                "microsteps": options.step_mode.microsteps(),
+                "steps_per_unit": options.steps_per_unit,
+                "pen_up_position": options.pen_up_position,
+                "pen_up_speed": options.pen_up_speed,
+                "pen_up_delay": options.pen_up_delay,
+                "pen_down_position": options.pen_down_position,
+                "pen_down_speed": options.pen_down_speed,
+                "pen_down_delay": options.pen_down_delay,
+                "pen_settle": options.pen_settle,
+                "acceleration": options.acceleration,
+                "travel_acceleration": options.travel_acceleration,
+                "max_velocity": options.max_velocity,
+                "corner_factor": options.corner_factor,
+                "detail_speed_factor": options.detail_speed_factor,
+                "paper": options.paper.map(|paper| {
+                    let (width, height) = paper.size();
+                    let margins = paper.margins;
+                    json!({
+                        "name": paper.name,
+                        "width": width,
+                        "height": height,
+                        "margins": [margins.top, margins.right, margins.bottom, margins.left],
+                    })
+                }),
+                "tool": match &options.tool {
+                    Tool::Pen => json!("pen"),
+                    Tool::Pwm { pin, intensities } => json!({
+                        "kind": "pwm",
+                        "pin": pin,
+                        "intensities": intensities,
+                    }),
+                    Tool::Pin { port, pin } => json!({
+                        "kind": "pin",
+                        "port": port.to_string(),
+                        "pin": pin,
+                    }),
+                },
+                "refill": options.refill.map(|refill| json!({
+                    "every": refill.every,
+                    "location": [refill.location.0, refill.location.1],
+                    "swirl": refill.swirl,
+                })),
+                "leads": options.leads.map(|leads| json!({
+                    "lead_in": leads.lead_in,
+                    "lead_out": leads.lead_out,
+                    "min_speed": leads.min_speed,
+                    "steps": leads.steps,
+                })),
+            },
+            "layers": self.layers.iter().map(|layer| {
+                let mut json = json!({
+                    "bounds": [layer.bounds.0, layer.bounds.1],
+                    "paths": layer.paths.0.iter().map(|path| {
+                        path.0.iter().map(|point| [point.x, point.y]).collect::<Vec<_>>()
+                    }).collect::<Vec<_>>(),
+                });
+                // Паузи (в мс) записуються, лише якщо вони є, тож решта файлів не змінюється
+                let dwells: Vec<Option<f64>> = (0..layer.paths.0.len())
+                    .map(|index| layer.style(index).dwell.map(|dwell| dwell.as_secs_f64() * 1000.0))
+                    .collect();
+                if dwells.iter().any(Option::is_some) {
+                    json["dwells"] = json!(dwells);
+                }
+                json
+            }).collect::<Vec<_>>(),
+        });
+        value.to_string()
+    }
+
+    /// Читає завдання з JSON.
+    ///
+    /// # Аргументи
+    /// * `content` - вміст файлу завдання.
+    ///
+    /// # Повертає
+    /// * `Result<JobFile, JobFileError>` - завдання або опис першого неправильного поля.
+    pub fn from_json(content: &str) -> Result<Self, JobFileError> {
+        let value: Value = serde_json::from_str(content)?;
+        if value["format"].as_str() != Some(FORMAT) {
+            return Err(JobFileError::Format);
+        }
+        let version = value["version"]
+            .as_u64()
+            .ok_or(JobFileError::Field("version"))?;
+        if version > VERSION {
+            return Err(JobFileError::Version(version));
+        }
+
+        let json = &value["options"];
+        let model: AxiDrawModel = json["model"]
+            .as_str()
+            .and_then(|code| code.parse().ok())
+            .ok_or(JobFileError::Field("model"))?;
+        let mut options = Options::for_model(model);
+        let step_mode = integer(json, "microsteps")
+            .ok()
+            .and_then(StepMode::from_microsteps)
+            .ok_or(JobFileError::Field("microsteps"))?;
+        options.set_step_mode(step_mode);
+        options.steps_per_unit = integer(json, "steps_per_unit")?;
+        options.pen_up_position = integer(json, "pen_up_position")?;
+        options.pen_up_speed = integer(json, "pen_up_speed")?;
+        options.pen_up_delay = integer(json, "pen_up_delay")?;
+        options.pen_down_position = integer(json, "pen_down_position")?;
+        options.pen_down_speed = integer(json, "pen_down_speed")?;
+        options.pen_down_delay = integer(json, "pen_down_delay")?;
+        options.pen_settle = integer(json, "pen_settle")?;
+        options.acceleration = number(json, "acceleration")?;
+        // Завдання, збережені до появи окремого прискорення переїздів, його не мають
+        options.travel_acceleration = match &json["travel_acceleration"] {
+            Value::Null => None,
+            _ => Some(number(json, "travel_acceleration")?),
+        };
+        options.max_velocity = number(json, "max_velocity")?;
+        options.corner_factor = number(json, "corner_factor")?;
+        options.detail_speed_factor = number(json, "detail_speed_factor")?;
+        options.paper = match &json["paper"] {
+            Value::Null => None,
+            paper => Some(read_paper(paper, model)?),
+        };
+        options.leads = match &json["leads"] {
+            Value::Null => None,
+            leads => Some(Leads {
+                lead_in: number(leads, "lead_in")?,
+                lead_out: number(leads, "lead_out")?,
+                min_speed: number(leads, "min_speed")?,
+                steps: integer(leads, "steps")?,
+            }),
+        };
+
+        options.refill = match &json["refill"] {
+            Value::Null => None,
+            refill => Some(Refill {
+                every: number(refill, "every")?,
+                location: match refill["location"].as_array().map(Vec::as_slice) {
+                    Some([x, y]) => (
+                        x.as_f64().ok_or(JobFileError::Field("location"))?,
+                        y.as_f64().ok_or(JobFileError::Field("location"))?,
+                    ),
+                    _ => return Err(JobFileError::Field("location")),
+                },
+                swirl: number(refill, "swirl")?,
+            }),
+        };
+
+        // Завдання без інструмента збережені до появи аерографа й лазера і малюють пером
+        options.tool = match &json["tool"] {
+            Value::Null => Tool::Pen,
+            Value::String(kind) if kind == "pen" => Tool::Pen,
+            tool => match tool["kind"].as_str() {
+                Some("pwm") => Tool::Pwm {
+                    pin: integer(tool, "pin")?,
+                    intensities: tool["intensities"]
+                        .as_array()
+                        .ok_or(JobFileError::Field("intensities"))?
+                        .iter()
+                        .map(|value| value.as_f64().ok_or(JobFileError::Field("intensities")))
+                        .collect::<Result<_, _>>()?,
+                },
+                Some("pin") => Tool::Pin {
+                    port: tool["port"]
+                        .as_str()
+                        .and_then(|port| port.chars().next())
+                        .ok_or(JobFileError::Field("port"))?,
+                    pin: integer(tool, "pin")?,
+                },
+                _ => return Err(JobFileError::Field("tool")),
+            },
+        };
+
+        let layers = value["layers"]
+            .as_array()
+            .ok_or(JobFileError::Field("layers"))?
+            .iter()
+            .map(read_layer)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(JobFile { options, layers })
+    }
+
+    /// Записує завдання у файл.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        std::fs::write(path, self.to_json())?;
+        Ok(())
+    }
+
+    /// Читає завдання з файлу.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(Self::from_json(&content)?)
+    }
+}
+
+/// Зливає шляхи, кожен з яких починається там, де закінчується попередній, як це
+/// робить планувальник малюнка, і відкидає порожні.
+///
+/// Як і в планувальнику, шлях з паузою завершує ланцюжок. Зі стилів зберігаються лише
+/// паузи: решту атрибутів завдання не використовує.
+fn chain_paths(layer: &Drawing) -> Drawing {
+    let mut chains: Vec<LineString<f64>> = vec![];
+    let mut dwells: Vec<Option<Duration>> = vec![];
+    for (index, path) in layer.paths.0.iter().enumerate() {
+        if path.0.is_empty() {
+            continue;
+        }
+        let dwell = layer.style(index).dwell;
+        match (chains.last_mut(), dwells.last_mut()) {
+            (Some(chain), Some(last @ None))
+                if distance(*chain.0.last().unwrap(), path.0[0]) <= CHAIN_TOLERANCE =>
+            {
+                chain.0.pop();
+                chain.0.extend(&path.0);
+                *last = dwell;
+            }
+            _ => {
+                chains.push(path.clone());
+                dwells.push(dwell);
+            }
+        }
+    }
+    let styles = match dwells.iter().any(Option::is_some) {
+        true => dwells
+            .into_iter()
+            .map(|dwell| PathStyle {
+                dwell,
+                ..PathStyle::default()
+            })
+            .collect(),
+        false => vec![],
+    };
+    Drawing::new(layer.bounds, MultiLineString(chains)).with_styles(styles)
+}
+
+/// Зчитує скінченне число з поля `name`.
+fn number(json: &Value, name: &'static str) -> Result<f64, JobFileError> {
+    json[name]
+        .as_f64()
+        .filter(|value| value.is_finite())
+        .ok_or(JobFileError::Field(name))
+}
+
+/// Зчитує ціле число з поля `name`, що вміщується в тип `T`.
+fn integer<T: TryFrom<i64>>(json: &Value, name: &'static str) -> Result<T, JobFileError> {
+    json[name]
+        .as_i64()
+        .and_then(|value| T::try_from(value).ok())
+        .ok_or(JobFileError::Field(name))
+}
+
+/// Зчитує аркуш: відомий формат за назвою, інакше довільний розмір.
+fn read_paper(json: &Value, model: AxiDrawModel) -> Result<Paper, JobFileError> {
+    let (width, height) = (number(json, "width")?, number(json, "height")?);
+    let margins = match json["margins"].as_array().map(Vec::as_slice) {
+        Some([top, right, bottom, left]) => Margins {
+            top: top.as_f64().ok_or(JobFileError::Field("margins"))?,
+            right: right.as_f64().ok_or(JobFileError::Field("margins"))?,
+            bottom: bottom.as_f64().ok_or(JobFileError::Field("margins"))?,
+            left: left.as_f64().ok_or(JobFileError::Field("margins"))?,
+        },
+        _ => return Err(JobFileError::Field("margins")),
+    };
+
+    let name = json["name"].as_str().unwrap_or_default();
+    let paper = match name.parse::<Paper>() {
+        Ok(paper) => paper,
+        _ if name == model.name() => Paper::from_model(model),
+        _ => Paper::custom(width, height),
+    };
+    let paper = if width > height {
+        paper.landscape()
+    } else {
+        paper.portrait()
+    };
+    if paper.size() != (width, height) {
+        return Err(JobFileError::Field("paper"));
+    }
+    Ok(paper.margins(margins))
+}
+
+/// Зчитує шар: межі та шляхи з пар координат.
+fn read_layer(json: &Value) -> Result<Drawing, JobFileError> {
+    let bounds = match json["bounds"].as_array().map(Vec::as_slice) {
+        Some([width, height]) => (
+            width.as_f64().ok_or(JobFileError::Field("bounds"))?,
+            height.as_f64().ok_or(JobFileError::Field("bounds"))?,
+        ),
+        _ => return Err(JobFileError::Field("bounds")),
+    };
+    let paths = json["paths"]
+        .as_array()
+        .ok_or(JobFileError::Field("paths"))?
+        .iter()
+        .map(|path| {
+            path.as_array()
+                .ok_or(JobFileError::Field("paths"))?
+                .iter()
+                .map(|point| match point.as_array().map(Vec::as_slice) {
+                    Some([x, y]) => match (x.as_f64(), y.as_f64()) {
+                        (Some(x), Some(y)) if x.is_finite() && y.is_finite() => Ok(Coord { x, y }),
+                        _ => Err(JobFileError::Field("paths")),
+                    },
+                    _ => Err(JobFileError::Field("paths")),
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map(LineString)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Завдання, збережені до появи пауз, їх не мають
+    let styles = match &json["dwells"] {
+        Value::Null => vec![],
+        dwells => dwells
+            .as_array()
+            .filter(|dwells| dwells.len() == paths.len())
+            .ok_or(JobFileError::Field("dwells"))?
+            .iter()
+            .map(|dwell| match dwell {
+                Value::Null => Ok(None),
+                _ => dwell
+                    .as_f64()
+                    .filter(|ms| ms.is_finite() && *ms >= 0.0)
+                    .map(|ms| Some(Duration::from_secs_f64(ms / 1000.0)))
+                    .ok_or(JobFileError::Field("dwells")),
+            })
+            .map(|dwell| {
+                dwell.map(|dwell| PathStyle {
+                    dwell,
+                    ..PathStyle::default()
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+    };
+    Ok(Drawing::new(bounds, MultiLineString(paths)).with_styles(styles))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drawing::order::PathOrder;
+
+    fn layer(paths: Vec<Vec<(f64, f64)>>) -> Drawing {
+        Drawing::new(
+            (100.0, 100.0),
+            MultiLineString(paths.into_iter().map(LineString::from).collect()),
+        )
+    }
+
+    #[test]
+    fn test_job_file_round_trip() {
+        let mut options = Options::for_model(AxiDrawModel::V3);
+        options.set_step_mode(StepMode::OneEighth);
+        options.pen_down_position = 25;
+        options.max_velocity = 35.5;
+        options.paper = Some(Paper::a4().landscape().margins(Margins::uniform(10.0)));
+        options.leads = Some(Leads::default());
+        options.refill = Some(Refill {
+            every: 120.0,
+            location: (200.0, 15.0),
+            swirl: 2.0,
+        });
+        options.order = Some(PathOrder {
+            reverse: true,
+            improve: None,
+        });
+        options.tool = Tool::Pwm {
+            pin: 5,
+            intensities: vec![0.3, 0.8],
+        };
+
+        let first = layer(vec![
+            vec![(50.0, 50.0), (60.0, 50.0)],
+            vec![(20.0, 20.0), (30.0, 20.0)],
+            vec![(30.0, 20.0), (30.0, 30.0)],
+        ]);
+        let second = layer(vec![vec![(40.0, 40.0), (45.0, 45.0)]]).with_styles(vec![PathStyle {
+            dwell: Some(Duration::from_millis(300)),
+            ..PathStyle::default()
+        }]);
+        let job = JobFile::prepare(&options, vec![first, second]).expect("Шари вміщуються");
+
+        assert!(job.options.order.is_none(), "Впорядкування вже застосоване");
+        assert_eq!(job.layers.len(), 2);
+        assert_eq!(job.total_paths(), 3, "Шляхи зі спільною точкою зливаються");
+
+        let loaded = JobFile::from_json(&job.to_json()).expect("Завдання має читатися");
+        assert_eq!(loaded.layers[0].paths, job.layers[0].paths);
+        assert_eq!(loaded.layers[1].bounds, (100.0, 100.0));
+        assert_eq!(
+            job.to_json().matches("\"dwells\"").count(),
+            1,
+            "Паузи записуються лише для шару, де вони є"
+        );
+        assert!(loaded.layers[0].style(0).dwell.is_none());
+        assert_eq!(
+            loaded.layers[1].style(0).dwell,
+            Some(Duration::from_millis(300))
+        );
+        assert_eq!(loaded.options.step_mode, StepMode::OneEighth);
+        assert_eq!(loaded.options.steps_per_unit, 40);
+        assert_eq!(loaded.options.pen_down_position, 25);
+        assert_eq!(loaded.options.max_velocity, 35.5);
+        assert_eq!(loaded.options.model.name(), "AxiDraw V3");
+        assert_eq!(loaded.options.leads, Some(Leads::default()));
+        assert_eq!(loaded.options.refill, options.refill);
+        let paper = loaded.options.paper.expect("Аркуш має зберегтися");
+        assert_eq!((paper.name, paper.size()), ("A4", (297.0, 210.0)));
+        assert_eq!(paper.margins, Margins::uniform(10.0));
+
+        // Продовження з третього шляху пропускає перший шар повністю
+        let resumed = job.skip_paths(2);
+        assert_eq!(resumed.layers.len(), 1);
+        assert_eq!(resumed.total_paths(), 1);
+        assert_eq!(loaded.options.tool, options.tool);
+        assert_eq!(
+            resumed.options.tool.intensity(0),
+            0.8,
+            "Другий шар зберігає свою потужність"
+        );
+    }
+
+    #[test]
+    fn test_invalid_job_files_are_rejected() {
+        assert!(JobFile::is_job("  {\"format\": \"rsaxi-job\"}"));
+        assert!(!JobFile::is_job("<svg/>"));
+        assert!(matches!(
+            JobFile::from_json("не json"),
+            Err(JobFileError::Json(_))
+        ));
+        assert!(matches!(
+            JobFile::from_json(r#"{"format": "other"}"#),
+            Err(JobFileError::Format)
+        ));
+        assert!(matches!(
+            JobFile::from_json(r#"{"format": "rsaxi-job", "version": 99}"#),
+            Err(JobFileError::Version(99))
+        ));
+
+        let options = Options::default();
+        let job = JobFile::prepare(&options, vec![layer(vec![vec![(1.0, 1.0), (2.0, 2.0)]])])
+            .expect("Шар вміщується");
+        let broken = job
+            .to_json()
+            .replace("\"microsteps\":16", "\"microsteps\":3");
+        assert!(matches!(
+            JobFile::from_json(&broken),
+            Err(JobFileError::Field("microsteps"))
+        ));
+
+        let outside = layer(vec![vec![(1.0, 1.0), (500.0, 2.0)]]);
+        assert!(matches!(
+            JobFile::prepare(&options, vec![outside]),
+            Err(Error::OutOfBounds { .. })
+        ));
+    }
+}
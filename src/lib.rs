@@ -0,0 +1,21 @@
+pub mod axidraw;
+pub mod capi;
+pub mod config;
+pub mod control;
+pub mod device;
+pub mod drawing;
+pub mod error;
+pub mod i18n;
+pub mod jobfile;
+pub mod motion;
+pub mod notify;
+pub mod paper;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod raster;
+pub mod server;
+pub mod testplot;
+pub mod text;
+pub mod trace;
+
+pub use error::{Error, Result};
@@ -1,37 +1,80 @@
-use anyhow::Result;
-use axidraw::{AxiDrawModel, Axidraw, Options};
+use anyhow::{bail, Result};
 use clap::{Arg, Command};
-use env_logger::Env;
-use log::{error, info};
-
-// Імпортуємо модулі
-mod axidraw;
-mod device;
-mod drawing;
-mod motion;
-mod text;
+use geo::{AffineOps, AffineTransform, Coord};
+use rsaxi::axidraw::{AxiDrawModel, Axidraw, Options};
+use rsaxi::config::{Config, Profile};
+use rsaxi::control;
+use rsaxi::device::hex::FirmwareImage;
+use rsaxi::device::tool::Tool;
+use rsaxi::device::{transcript, Device, StepMode, WireLog, WIRE_TARGET};
+use rsaxi::drawing::coordinates::{CoordinateSystem, Origin};
+use rsaxi::drawing::correction::{calibration_pattern, Correction};
+use rsaxi::drawing::direction::PathDirection;
+use rsaxi::drawing::generator::Registry;
+use rsaxi::drawing::import::units::Unit;
+use rsaxi::drawing::import::ImportOptions;
+use rsaxi::drawing::layout::Layout;
+use rsaxi::drawing::lead::Leads;
+use rsaxi::drawing::order::PathOrder;
+use rsaxi::drawing::packing::CirclePacking;
+use rsaxi::drawing::refill::Refill;
+use rsaxi::drawing::turtle::run_script;
+use rsaxi::drawing::{contours, Drawing};
+use rsaxi::jobfile::JobFile;
+use rsaxi::motion::kinematics;
+use rsaxi::notify::Notifier;
+use rsaxi::paper::{Margins, Paper};
+use rsaxi::raster::lines::{RasterOptions, RasterStyle};
+use rsaxi::raster::stipple::{StippleMethod, StippleOptions};
+use rsaxi::raster::Raster;
+use rsaxi::server;
+use rsaxi::testplot::PenTest;
+use rsaxi::text::font::{self, catalog::FONT_NAMES};
+use rsaxi::text::text::{TextAlign, TextBuilder};
+use rsaxi::tr;
+use rsaxi::trace;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, IsTerminal};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+use tracing::{error, info, warn};
+use tracing_subscriber::filter::{EnvFilter, LevelFilter};
+use tracing_subscriber::prelude::*;
 
 fn main() -> Result<()> {
-    // Ініціалізація логування з рівнем за замовчуванням "info"
-    env_logger::Builder::from_env(Env::default().default_filter_or("info")).init();
-
     // Налаштування CLI за допомогою clap
     let matches = Command::new("rsaxi")
         .version("0.1.0")
         .author("Taras Koval <tkoval83@icloud.com>")
-        .about("Командний інтерфейс для налаштування і керування AxiDraw")
+        .about(tr!(
+            "Command-line interface for configuring and controlling an AxiDraw",
+            "Командний інтерфейс для налаштування і керування AxiDraw"
+        ))
         .arg(
             Arg::new("steps_per_unit")
                 .long("steps_per_unit")
-                .help("Кроки на одиницю (наприклад, мм)")
+                .help(tr!("Steps per unit (e.g. mm)", "Кроки на одиницю (наприклад, мм)"))
                 .value_name("STEPS")
                 .required(false)
                 .value_parser(clap::value_parser!(i32)),
         )
+        .arg(
+            Arg::new("microstepping")
+                .long("microstepping")
+                .help(tr!(
+                    "Motor microstepping: 16, 8, 4, 2 or 1 microsteps per step; coarser modes allow faster travel",
+                    "Режим мікрокроку моторів: 16, 8, 4, 2 або 1 мікрокроків на крок; грубіші режими дозволяють швидші переїзди"
+                ))
+                .value_name("MICROSTEPS")
+                .required(false)
+                .value_parser(["16", "8", "4", "2", "1"]),
+        )
         .arg(
             Arg::new("pen_up_position")
                 .long("pen_up_position")
-                .help("Положення піднятої ручки")
+                .help(tr!("Pen up position", "Положення піднятої ручки"))
                 .value_name("HEIGHT")
                 .required(false)
                 .value_parser(clap::value_parser!(i32)),
@@ -39,7 +82,7 @@ fn main() -> Result<()> {
         .arg(
             Arg::new("pen_up_speed")
                 .long("pen_up_speed")
-                .help("Швидкість підйому ручки")
+                .help(tr!("Pen raising speed", "Швидкість підйому ручки"))
                 .value_name("SPEED")
                 .required(false)
                 .value_parser(clap::value_parser!(i32)),
@@ -47,7 +90,10 @@ fn main() -> Result<()> {
         .arg(
             Arg::new("pen_up_delay")
                 .long("pen_up_delay")
-                .help("Затримку після підйому ручки (в мілісекундах)")
+                .help(tr!(
+                    "Delay after raising the pen (in milliseconds)",
+                    "Затримку після підйому ручки (в мілісекундах)"
+                ))
                 .value_name("DELAY")
                 .required(false)
                 .value_parser(clap::value_parser!(i32)),
@@ -55,7 +101,7 @@ fn main() -> Result<()> {
         .arg(
             Arg::new("pen_down_position")
                 .long("pen_down_position")
-                .help("Положення опущеної ручки")
+                .help(tr!("Pen down position", "Положення опущеної ручки"))
                 .value_name("HEIGHT")
                 .required(false)
                 .value_parser(clap::value_parser!(i32)),
@@ -63,7 +109,7 @@ fn main() -> Result<()> {
         .arg(
             Arg::new("pen_down_speed")
                 .long("pen_down_speed")
-                .help("Швидкість опускання ручки")
+                .help(tr!("Pen lowering speed", "Швидкість опускання ручки"))
                 .value_name("SPEED")
                 .required(false)
                 .value_parser(clap::value_parser!(i32)),
@@ -71,15 +117,54 @@ fn main() -> Result<()> {
         .arg(
             Arg::new("pen_down_delay")
                 .long("pen_down_delay")
-                .help("Затримку після опускання ручки (в мілісекундах)")
+                .help(tr!(
+                    "Delay after lowering the pen (in milliseconds)",
+                    "Затримку після опускання ручки (в мілісекундах)"
+                ))
+                .value_name("DELAY")
+                .required(false)
+                .value_parser(clap::value_parser!(i32)),
+        )
+        .arg(
+            Arg::new("pen_settle")
+                .long("pen_settle")
+                .help(tr!(
+                    "Pen settle time after raising or lowering (in milliseconds)",
+                    "Час заспокоєння ручки після підйому чи опускання (в мілісекундах)"
+                ))
                 .value_name("DELAY")
                 .required(false)
                 .value_parser(clap::value_parser!(i32)),
         )
+        .arg(
+            Arg::new("stall_limit")
+                .long("stall_limit")
+                .help(tr!(
+                    "Raise the pen when motion stalls with the pen down longer than this (in milliseconds)",
+                    "Піднімати перо, якщо рух стоїть з опущеним пером довше за цей час (в мілісекундах)"
+                ))
+                .value_name("DELAY")
+                .required(false)
+                .value_parser(clap::value_parser!(u64)),
+        )
         .arg(
             Arg::new("acceleration")
                 .long("acceleration")
-                .help("Прискорення")
+                .help(tr!(
+                    "Carriage acceleration (mm/s²); the default depends on the model",
+                    "Прискорення каретки (мм/с²); типове залежить від моделі"
+                ))
+                .value_name("ACCELERATION")
+                .required(false)
+                .value_parser(clap::value_parser!(f64)),
+        )
+        .arg(
+            Arg::new("travel_acceleration")
+                .long("travel_acceleration")
+                .help(tr!(
+                    "Acceleration of pen-up travel (mm/s²); by default the same as --acceleration",
+                    "Прискорення переїздів з піднятим пером (мм/с²); типово як --acceleration"
+                ))
                 .value_name("ACCELERATION")
                 .required(false)
                 .value_parser(clap::value_parser!(f64)),
@@ -87,7 +172,10 @@ fn main() -> Result<()> {
         .arg(
             Arg::new("max_velocity")
                 .long("max_velocity")
-                .help("Максимальну швидкість")
+                .help(tr!(
+                    "Maximum carriage velocity (mm/s); the default depends on the model",
+                    "Максимальна швидкість каретки (мм/с); типова залежить від моделі"
+                ))
                 .value_name("VELOCITY")
                 .required(false)
                 .value_parser(clap::value_parser!(f64)),
@@ -95,25 +183,1505 @@ fn main() -> Result<()> {
         .arg(
             Arg::new("corner_factor")
                 .long("corner_factor")
-                .help("Коефіцієнт для обробки кутів")
+                .help(tr!("Corner handling factor", "Коефіцієнт для обробки кутів"))
+                .value_name("FACTOR")
+                .required(false)
+                .value_parser(clap::value_parser!(f64)),
+        )
+        .arg(
+            Arg::new("detail_speed_factor")
+                .long("detail_speed_factor")
+                .help(tr!(
+                    "Speed fraction (0.05..1) for segments under 2 mm and bends tighter than 2 mm radius; 1 keeps full speed",
+                    "Частка швидкості (0.05..1) на відрізках до 2 мм і вигинах радіусом до 2 мм; 1 зберігає повну швидкість"
+                ))
                 .value_name("FACTOR")
                 .required(false)
                 .value_parser(clap::value_parser!(f64)),
         )
+        .arg(
+            Arg::new("low_level_moves")
+                .long("low_level_moves")
+                .help(tr!(
+                    "Move with LM commands counted in motor steps instead of XM (firmware 2.7+)",
+                    "Рухатися командами LM у кроках моторів замість XM (прошивка 2.7+)"
+                ))
+                .action(clap::ArgAction::SetTrue),
+        )
         .arg(
             Arg::new("model")
                 .long("model")
-                .help("Модель AxiDraw: v3, v3a3, sea3, або mini")
+                .help(tr!(
+                    "AxiDraw model: v3, v3a3, sea3 or mini",
+                    "Модель AxiDraw: v3, v3a3, sea3, або mini"
+                ))
                 .value_name("MODEL")
                 .required(false)
                 .value_parser(["v3", "v3a3", "sea3", "mini"]),
         )
+        .arg(
+            Arg::new("paper")
+                .long("paper")
+                .help(tr!(
+                    "Paper sheet: a3, a4, a5, letter or WIDTHxHEIGHT in mm, with a -landscape or -portrait suffix",
+                    "Аркуш паперу: a3, a4, a5, letter або ШИРИНАxВИСОТА в мм, з суфіксом -landscape чи -portrait"
+                ))
+                .value_name("PAPER")
+                .required(false)
+                .value_parser(Paper::from_str),
+        )
+        .arg(
+            Arg::new("margin")
+                .long("margin")
+                .help(tr!(
+                    "Sheet margins (in millimetres): one value, vertical,horizontal or top,right,bottom,left",
+                    "Поля аркуша (в міліметрах): одне значення, вертикальні,бічні або верх,право,низ,ліво"
+                ))
+                .value_name("MARGIN")
+                .required(false)
+                .value_parser(Margins::from_str),
+        )
+        .arg(
+            Arg::new("bleed")
+                .long("bleed")
+                .help(tr!(
+                    "How far a fitted drawing extends past the printable area before it is cut at the margins (in mm)",
+                    "На скільки підігнаний малюнок виступає за область для малювання перед обрізанням по полях (в мм)"
+                ))
+                .value_name("MM")
+                .required(false)
+                .value_parser(clap::value_parser!(f64)),
+        )
+        .arg(
+            Arg::new("dpi")
+                .long("dpi")
+                .help(tr!(
+                    "Pixels per inch for px lengths in SVG files",
+                    "Кількість пікселів на дюйм для довжин у px у файлах SVG"
+                ))
+                .value_name("DPI")
+                .default_value("96")
+                .value_parser(clap::value_parser!(f64)),
+        )
+        .arg(
+            Arg::new("units")
+                .long("units")
+                .help(tr!(
+                    "Unit of unitless SVG sizes and of files without sizes: mm, cm, in, pt, pc or px",
+                    "Одиниця розмірів SVG без позначки і файлів без розмірів: mm, cm, in, pt, pc або px"
+                ))
+                .value_name("UNIT")
+                .default_value("mm")
+                .value_parser(Unit::from_str),
+        )
+        .arg(
+            Arg::new("crop")
+                .long("crop")
+                .help(tr!(
+                    "Cut imported SVG paths at the printable area of the sheet instead of refusing to plot them",
+                    "Обрізати шляхи SVG областю для малювання аркуша замість відмови їх малювати"
+                ))
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("pen_width")
+                .long("pen_width")
+                .help(tr!(
+                    "Emulate SVG stroke widths with parallel passes of a pen this wide (in mm)",
+                    "Імітувати товщину ліній SVG паралельними проходами пера такої товщини (в мм)"
+                ))
+                .value_name("MM")
+                .value_parser(clap::value_parser!(f64)),
+        )
+        .arg(
+            Arg::new("lead_in")
+                .long("lead_in")
+                .help(tr!(
+                    "Length of the tangent lead-in before each path (in mm)",
+                    "Довжина дотичного заходу перед кожним шляхом (в мм)"
+                ))
+                .value_name("LENGTH")
+                .required(false)
+                .value_parser(clap::value_parser!(f64)),
+        )
+        .arg(
+            Arg::new("lead_out")
+                .long("lead_out")
+                .help(tr!(
+                    "Length of the tangent lead-out after each path (in mm)",
+                    "Довжина дотичного виходу після кожного шляху (в мм)"
+                ))
+                .value_name("LENGTH")
+                .required(false)
+                .value_parser(clap::value_parser!(f64)),
+        )
+        .arg(
+            Arg::new("refill_every")
+                .long("refill_every")
+                .help(tr!(
+                    "Dip the pen into the inkwell after this much pen-down travel (in mm)",
+                    "Занурювати перо в чорнильницю після такої довжини руху з опущеним пером (в мм)"
+                ))
+                .value_name("LENGTH")
+                .required(false)
+                .requires("dip_location")
+                .value_parser(clap::value_parser!(f64)),
+        )
+        .arg(
+            Arg::new("dip_location")
+                .long("dip_location")
+                .help(tr!(
+                    "Inkwell position X,Y (in mm) for --refill_every",
+                    "Положення чорнильниці X,Y (в мм) для --refill_every"
+                ))
+                .value_name("X,Y")
+                .required(false)
+                .value_parser(parse_point),
+        )
+        .arg(
+            Arg::new("park")
+                .long("park")
+                .help(tr!(
+                    "Park the carriage at X,Y (in mm) after plotting instead of returning home",
+                    "Паркувати каретку в X,Y (в мм) після малювання замість повернення додому"
+                ))
+                .value_name("X,Y")
+                .required(false)
+                .value_parser(parse_point),
+        )
+        .arg(
+            Arg::new("dip_swirl")
+                .long("dip_swirl")
+                .help(tr!(
+                    "Radius of the circles the pen makes in the inkwell (in mm); 0 disables them",
+                    "Радіус кіл пера в чорнильниці (в мм); 0 вимикає їх"
+                ))
+                .value_name("RADIUS")
+                .default_value("2")
+                .value_parser(clap::value_parser!(f64)),
+        )
+        .arg(
+            Arg::new("tool")
+                .long("tool")
+                .help(tr!(
+                    "Drawing tool: servo pen, PWM output (airbrush, laser) or digital output",
+                    "Інструмент: перо на серво, імпульсний вихід (аерограф, лазер) або цифровий вихід"
+                ))
+                .value_name("TOOL")
+                .default_value("pen")
+                .value_parser(["pen", "pwm", "pin"]),
+        )
+        .arg(
+            Arg::new("tool_pin")
+                .long("tool_pin")
+                .help(tr!(
+                    "Tool output: RPn number 0-24 for pwm, port and pin such as B3 for pin",
+                    "Вихід інструмента: номер RPn 0-24 для pwm, порт і пін, наприклад B3, для pin"
+                ))
+                .value_name("PIN")
+                .required_if_eq_any([("tool", "pwm"), ("tool", "pin")]),
+        )
+        .arg(
+            Arg::new("intensity")
+                .long("intensity")
+                .help(tr!(
+                    "PWM tool intensity 0..1, one comma-separated value per layer",
+                    "Потужність імпульсного інструмента 0..1, через кому для кожного шару"
+                ))
+                .value_name("LEVELS")
+                .value_delimiter(',')
+                .value_parser(clap::value_parser!(f64)),
+        )
+        .arg(
+            Arg::new("optimize")
+                .long("optimize")
+                .help(tr!(
+                    "Reorder paths to shorten pen-up travel",
+                    "Впорядкувати шляхи, щоб скоротити переїзди з піднятим пером"
+                ))
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("direction")
+                .long("direction")
+                .help(tr!(
+                    "Draw paths in one direction: left to right, outward from the center, or closed shapes clockwise",
+                    "Малювати шляхи в одному напрямку: зліва направо, від центру назовні чи замкнені контури за годинниковою стрілкою"
+                ))
+                .value_name("DIRECTION")
+                .value_parser(["left_to_right", "outward", "clockwise"]),
+        )
+        .arg(
+            Arg::new("origin")
+                .long("origin")
+                .help(tr!(
+                    "Corner the drawing coordinates start from: top left with y down, as in SVG, or bottom left with y up",
+                    "Кут, від якого відраховуються координати малюнка: лівий верхній з y донизу, як у SVG, чи лівий нижній з y догори"
+                ))
+                .value_name("ORIGIN")
+                .value_parser(["top_left", "bottom_left"])
+                .default_value("top_left"),
+        )
+        .arg(
+            Arg::new("flip_x")
+                .long("flip_x")
+                .visible_alias("flip_h")
+                .help(tr!(
+                    "Mirror the drawing left to right, e.g. for the back of translucent paper or stamps",
+                    "Дзеркально відбити малюнок зліва направо, наприклад для звороту напівпрозорого паперу чи штампів"
+                ))
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("flip_y")
+                .long("flip_y")
+                .visible_alias("flip_v")
+                .help(tr!(
+                    "Mirror the drawing top to bottom",
+                    "Дзеркально відбити малюнок згори вниз"
+                ))
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("optimize_time")
+                .long("optimize_time")
+                .help(tr!(
+                    "Time to spend improving the path order (in seconds); 0 keeps the greedy order",
+                    "Час на покращення порядку шляхів (в секундах); 0 залишає лише жадібний пошук"
+                ))
+                .value_name("SECONDS")
+                .required(false)
+                .value_parser(clap::value_parser!(f64)),
+        )
+        .arg(
+            Arg::new("device")
+                .long("device")
+                .help(tr!(
+                    "Serial port or nickname of the AxiDraw to use",
+                    "Серійний порт або псевдонім AxiDraw, з яким працювати"
+                ))
+                .value_name("NICKNAME|PORT")
+                .required(false),
+        )
+        .arg(
+            Arg::new("port_config")
+                .long("port_config")
+                .help(tr!(
+                    "Regular expression matching the board by port name, VID:PID, manufacturer, product or serial number",
+                    "Регулярний вираз для пошуку плати за назвою порту, VID:PID, виробником, продуктом чи серійним номером"
+                ))
+                .value_name("REGEX")
+                .required(false),
+        )
+        .arg(
+            Arg::new("all_devices")
+                .long("all_devices")
+                .help(tr!(
+                    "Experimental: plot the same drawing on every connected AxiDraw at once",
+                    "Експериментально: малювати той самий малюнок на всіх підключених AxiDraw одночасно"
+                ))
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("transcript")
+                .long("transcript")
+                .help(tr!(
+                    "Record every board command and response with timestamps to a file",
+                    "Записати всі команди до плати та її відповіді з часом у файл"
+                ))
+                .value_name("FILE")
+                .required(false),
+        )
+        .arg(
+            Arg::new("log_serial")
+                .long("log_serial")
+                .alias("log-serial")
+                .help(tr!(
+                    "Log board traffic under the `wire` target: commands, commands and responses, or raw bytes in hex",
+                    "Писати обмін з платою до журналу з ціллю `wire`: команди, команди й відповіді або сирі байти в hex"
+                ))
+                .value_name("LEVEL")
+                .required(false)
+                .value_parser(["commands", "responses", "raw"]),
+        )
+        .arg(
+            Arg::new("trace_file")
+                .long("trace_file")
+                .help(tr!(
+                    "Record how long planning, paths, motion plans, board commands and serial waits take to a Chrome trace file",
+                    "Записати тривалість планування, шляхів, планів руху, команд до плати й очікування порту у файл сліду Chrome"
+                ))
+                .value_name("FILE")
+                .required(false),
+        )
+        .arg(
+            Arg::new("serial_timeout")
+                .long("serial_timeout")
+                .help(tr!(
+                    "Timeout for a board response (in milliseconds)",
+                    "Тайм-аут очікування відповіді плати (в мілісекундах)"
+                ))
+                .value_name("MS")
+                .required(false)
+                .value_parser(clap::value_parser!(u64).range(1..)),
+        )
+        .arg(
+            Arg::new("retries")
+                .long("retries")
+                .help(tr!(
+                    "How many times to retry a query the board did not answer",
+                    "Скільки разів повторити запит, на який плата не відповіла"
+                ))
+                .value_name("COUNT")
+                .required(false)
+                .value_parser(clap::value_parser!(u32)),
+        )
+        .arg(
+            Arg::new("write_chunk")
+                .long("write_chunk")
+                .help(tr!(
+                    "Largest chunk of bytes for one port write",
+                    "Найбільша порція байтів для одного запису в порт"
+                ))
+                .value_name("BYTES")
+                .required(false)
+                .value_parser(clap::value_parser!(usize)),
+        )
+        .arg(
+            Arg::new("config")
+                .long("config")
+                .help(tr!(
+                    "Plotter config file; defaults to $RSAXI_CONFIG or ~/.config/rsaxi/config.toml",
+                    "Файл конфігурації плотера; типово $RSAXI_CONFIG або ~/.config/rsaxi/config.toml"
+                ))
+                .value_name("FILE")
+                .required(false),
+        )
+        .arg(
+            Arg::new("profile")
+                .long("profile")
+                .help(tr!(
+                    "Named machine or pen profile from the config file; other options override it",
+                    "Іменований профіль машини чи пера з файлу конфігурації; інші параметри перекривають його"
+                ))
+                .value_name("NAME")
+                .required(false),
+        )
+        .subcommand(
+            Command::new("serve")
+                .about(tr!(
+                    "Starts an HTTP server for controlling the plotter over the network",
+                    "Запускає HTTP-сервер для керування плотером через мережу"
+                ))
+                .arg(
+                    Arg::new("host")
+                        .long("host")
+                        .help(tr!("Address the server listens on", "Адреса, на якій слухає сервер"))
+                        .value_name("HOST")
+                        .default_value("127.0.0.1"),
+                )
+                .arg(
+                    Arg::new("port")
+                        .long("port")
+                        .help(tr!("Server port", "Порт сервера"))
+                        .value_name("PORT")
+                        .default_value("8080")
+                        .value_parser(clap::value_parser!(u16)),
+                ),
+        )
+        .subcommand(
+            Command::new("simulate")
+                .about(tr!(
+                    "Runs a drawing on a simulated device and writes the EBB command log to CSV",
+                    "Виконує малюнок на імітованому пристрої та записує журнал команд EBB у CSV"
+                ))
+                .arg(
+                    Arg::new("input")
+                        .help(tr!("SVG file to plot", "SVG-файл для малювання"))
+                        .value_name("FILE")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .help(tr!(
+                            "CSV file for the log; without it the log goes to stdout",
+                            "CSV-файл для журналу; без нього журнал виводиться в stdout"
+                        ))
+                        .value_name("FILE"),
+                )
+                .arg(
+                    Arg::new("fit")
+                        .long("fit")
+                        .help(tr!("Fit the drawing to the sheet", "Підігнати малюнок під аркуш"))
+                        .action(clap::ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("turtle")
+                .about(tr!(
+                    "Runs a turtle graphics script and plots its trail or saves it to SVG",
+                    "Виконує сценарій черепашачої графіки й малює її слід або зберігає його в SVG"
+                ))
+                .arg(
+                    Arg::new("input")
+                        .help(tr!(
+                            "Script with forward, back, left, right, penup, pendown, push, pop and repeat",
+                            "Сценарій з командами forward, back, left, right, penup, pendown, push, pop і repeat"
+                        ))
+                        .value_name("FILE")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .help(tr!(
+                            "SVG file to save the trail to instead of plotting it",
+                            "SVG-файл, у який зберегти слід замість малювання"
+                        ))
+                        .value_name("FILE"),
+                )
+                .arg(
+                    Arg::new("padding")
+                        .long("padding")
+                        .help(tr!(
+                            "Blank space around the trail (in mm)",
+                            "Поля навколо сліду (в мм)"
+                        ))
+                        .value_name("PADDING")
+                        .default_value("10")
+                        .value_parser(clap::value_parser!(f64)),
+                )
+                .arg(
+                    Arg::new("fit")
+                        .long("fit")
+                        .help(tr!(
+                            "Fit the trail to the sheet when plotting",
+                            "Підігнати слід під аркуш під час малювання"
+                        ))
+                        .conflicts_with("output")
+                        .action(clap::ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("raster")
+                .about(tr!(
+                    "Converts a PNG, JPEG or Netpbm image to line work and plots it or saves it to SVG",
+                    "Перетворює зображення PNG, JPEG чи Netpbm на лінії й малює їх або зберігає в SVG"
+                ))
+                .arg(
+                    Arg::new("input")
+                        .help(tr!("Image file", "Файл зображення"))
+                        .value_name("FILE")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("style")
+                        .long("style")
+                        .help(tr!(
+                            "Line style: squiggle rows, spiral, crosshatch or edges",
+                            "Спосіб: рядки хвиль squiggle, спіраль spiral, штрихування crosshatch чи контури edges"
+                        ))
+                        .value_name("STYLE")
+                        .default_value("squiggle")
+                        .value_parser(["squiggle", "spiral", "crosshatch", "edges"]),
+                )
+                .arg(
+                    Arg::new("width")
+                        .long("width")
+                        .help(tr!(
+                            "Drawing width (in mm); the height keeps the image proportions",
+                            "Ширина малюнка (в мм); висота зберігає пропорції зображення"
+                        ))
+                        .value_name("WIDTH")
+                        .default_value("150")
+                        .value_parser(clap::value_parser!(f64)),
+                )
+                .arg(
+                    Arg::new("spacing")
+                        .long("spacing")
+                        .help(tr!(
+                            "Distance between rows, turns or hatch lines (in mm)",
+                            "Відстань між рядками, витками чи штрихами (в мм)"
+                        ))
+                        .value_name("SPACING")
+                        .default_value("1")
+                        .value_parser(clap::value_parser!(f64)),
+                )
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .help(tr!(
+                            "SVG file to save the lines to instead of plotting them",
+                            "SVG-файл, у який зберегти лінії замість малювання"
+                        ))
+                        .value_name("FILE"),
+                )
+                .arg(
+                    Arg::new("fit")
+                        .long("fit")
+                        .help(tr!(
+                            "Fit the lines to the sheet when plotting",
+                            "Підігнати лінії під аркуш під час малювання"
+                        ))
+                        .conflicts_with("output")
+                        .action(clap::ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("contour")
+                .about(tr!(
+                    "Traces contour lines of an image used as a heightmap and plots them or saves them to SVG",
+                    "Будує ізолінії зображення як карти висот і малює їх або зберігає в SVG"
+                ))
+                .arg(
+                    Arg::new("input")
+                        .help(tr!(
+                            "Image file; brighter pixels are higher",
+                            "Файл зображення; світліші пікселі вищі"
+                        ))
+                        .value_name("FILE")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("levels")
+                        .long("levels")
+                        .help(tr!(
+                            "Number of evenly spaced brightness levels",
+                            "Кількість рівнів яскравості з рівним кроком"
+                        ))
+                        .value_name("COUNT")
+                        .default_value("10")
+                        .value_parser(clap::value_parser!(usize)),
+                )
+                .arg(
+                    Arg::new("width")
+                        .long("width")
+                        .help(tr!(
+                            "Drawing width (in mm); the height keeps the image proportions",
+                            "Ширина малюнка (в мм); висота зберігає пропорції зображення"
+                        ))
+                        .value_name("WIDTH")
+                        .default_value("150")
+                        .value_parser(clap::value_parser!(f64)),
+                )
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .help(tr!(
+                            "SVG file to save the contours to instead of plotting them",
+                            "SVG-файл, у який зберегти ізолінії замість малювання"
+                        ))
+                        .value_name("FILE"),
+                )
+                .arg(
+                    Arg::new("fit")
+                        .long("fit")
+                        .help(tr!(
+                            "Fit the contours to the sheet when plotting",
+                            "Підігнати ізолінії під аркуш під час малювання"
+                        ))
+                        .conflicts_with("output")
+                        .action(clap::ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("stipple")
+                .about(tr!(
+                    "Converts an image to stipple dots or one line through them and plots it or saves it to SVG",
+                    "Перетворює зображення на пунктир або одну лінію через його точки й малює чи зберігає в SVG"
+                ))
+                .arg(
+                    Arg::new("input")
+                        .help(tr!("Image file", "Файл зображення"))
+                        .value_name("FILE")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("method")
+                        .long("method")
+                        .help(tr!(
+                            "Dot placement: weighted Voronoi relaxation, Poisson disk sampling or circle packing",
+                            "Розставлення точок: зважена релаксація Вороного voronoi, диск Пуассона poisson чи пакування кіл circles"
+                        ))
+                        .value_name("METHOD")
+                        .default_value("voronoi")
+                        .value_parser(["voronoi", "poisson", "circles"]),
+                )
+                .arg(
+                    Arg::new("min_radius")
+                        .long("min_radius")
+                        .help(tr!(
+                            "Smallest circle radius for circles, used in the darkest areas (in mm)",
+                            "Найменший радіус кола для circles, що стоїть у найтемніших місцях (в мм)"
+                        ))
+                        .value_name("MM")
+                        .default_value("0.5")
+                        .value_parser(clap::value_parser!(f64)),
+                )
+                .arg(
+                    Arg::new("max_radius")
+                        .long("max_radius")
+                        .help(tr!(
+                            "Largest circle radius for circles (in mm)",
+                            "Найбільший радіус кола для circles (в мм)"
+                        ))
+                        .value_name("MM")
+                        .default_value("4")
+                        .value_parser(clap::value_parser!(f64)),
+                )
+                .arg(
+                    Arg::new("count")
+                        .long("count")
+                        .help(tr!("Number of dots", "Кількість точок"))
+                        .value_name("COUNT")
+                        .default_value("5000")
+                        .value_parser(clap::value_parser!(usize)),
+                )
+                .arg(
+                    Arg::new("iterations")
+                        .long("iterations")
+                        .help(tr!(
+                            "Voronoi relaxation steps; more steps spread the dots more evenly",
+                            "Кроки релаксації Вороного; більше кроків розставляють точки рівніше"
+                        ))
+                        .value_name("COUNT")
+                        .default_value("30")
+                        .value_parser(clap::value_parser!(usize)),
+                )
+                .arg(
+                    Arg::new("gamma")
+                        .long("gamma")
+                        .help(tr!(
+                            "Density contrast: above 1 gathers dots in the darkest areas",
+                            "Контраст густоти: більше 1 збирає точки в найтемніших місцях"
+                        ))
+                        .value_name("GAMMA")
+                        .default_value("1")
+                        .value_parser(clap::value_parser!(f64)),
+                )
+                .arg(
+                    Arg::new("seed")
+                        .long("seed")
+                        .help(tr!(
+                            "Seed of the random placement",
+                            "Зерно випадкового розставлення"
+                        ))
+                        .value_name("SEED")
+                        .default_value("0")
+                        .value_parser(clap::value_parser!(u64)),
+                )
+                .arg(
+                    Arg::new("line")
+                        .long("line")
+                        .help(tr!(
+                            "Connect the dots with one continuous line instead of separate dabs",
+                            "З'єднати точки однією безперервною лінією замість окремих рисок"
+                        ))
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("width")
+                        .long("width")
+                        .help(tr!(
+                            "Drawing width (in mm); the height keeps the image proportions",
+                            "Ширина малюнка (в мм); висота зберігає пропорції зображення"
+                        ))
+                        .value_name("WIDTH")
+                        .default_value("150")
+                        .value_parser(clap::value_parser!(f64)),
+                )
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .help(tr!(
+                            "SVG file to save the dots to instead of plotting them",
+                            "SVG-файл, у який зберегти точки замість малювання"
+                        ))
+                        .value_name("FILE"),
+                )
+                .arg(
+                    Arg::new("fit")
+                        .long("fit")
+                        .help(tr!(
+                            "Fit the dots to the sheet when plotting",
+                            "Підігнати точки під аркуш під час малювання"
+                        ))
+                        .conflicts_with("output")
+                        .action(clap::ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("generate")
+                .about(tr!(
+                    "Builds a drawing with a named generator and plots it or saves it to SVG",
+                    "Будує малюнок іменованим генератором і малює його або зберігає в SVG"
+                ))
+                .arg(
+                    Arg::new("name")
+                        .help(tr!(
+                            "Generator name; without it the generators and their parameters are listed",
+                            "Назва генератора; без неї виводиться список генераторів і їхніх параметрів"
+                        ))
+                        .value_name("NAME"),
+                )
+                .arg(
+                    Arg::new("param")
+                        .long("param")
+                        .help(tr!(
+                            "Generator parameter as name=value; may be repeated",
+                            "Параметр генератора у вигляді назва=значення; можна повторювати"
+                        ))
+                        .value_name("NAME=VALUE")
+                        .action(clap::ArgAction::Append),
+                )
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .help(tr!(
+                            "SVG file to save the drawing to instead of plotting it",
+                            "SVG-файл, у який зберегти малюнок замість малювання"
+                        ))
+                        .value_name("FILE"),
+                )
+                .arg(
+                    Arg::new("fit")
+                        .long("fit")
+                        .help(tr!(
+                            "Fit the drawing to the sheet when plotting",
+                            "Підігнати малюнок під аркуш під час малювання"
+                        ))
+                        .conflicts_with("output")
+                        .action(clap::ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("layout")
+                .about(tr!(
+                    "Tiles copies of a drawing across the sheet, e.g. for stickers and cards",
+                    "Розкладає копії малюнка по аркушу, наприклад для наліпок і листівок"
+                ))
+                .arg(
+                    Arg::new("input")
+                        .help(tr!("SVG file to copy", "SVG-файл для копіювання"))
+                        .value_name("FILE")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("nup")
+                        .long("nup")
+                        .help(tr!(
+                            "Grid of copies as COLUMNSxROWS",
+                            "Сітка копій у вигляді СТОВПЦІxРЯДКИ"
+                        ))
+                        .value_name("GRID")
+                        .default_value("2x2")
+                        .value_parser(Layout::from_str),
+                )
+                .arg(
+                    Arg::new("gap")
+                        .long("gap")
+                        .help(tr!("Gap between copies (in mm)", "Проміжок між копіями (в мм)"))
+                        .value_name("MM")
+                        .default_value("5")
+                        .value_parser(clap::value_parser!(f64)),
+                )
+                .arg(
+                    Arg::new("jitter")
+                        .long("jitter")
+                        .help(tr!(
+                            "Largest random rotation of each copy (in degrees)",
+                            "Найбільший випадковий поворот кожної копії (в градусах)"
+                        ))
+                        .value_name("DEGREES")
+                        .default_value("0")
+                        .value_parser(clap::value_parser!(f64)),
+                )
+                .arg(
+                    Arg::new("seed")
+                        .long("seed")
+                        .help(tr!(
+                            "Seed of the random rotations",
+                            "Зерно випадкових поворотів"
+                        ))
+                        .value_name("SEED")
+                        .default_value("0")
+                        .value_parser(clap::value_parser!(u64)),
+                )
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .help(tr!(
+                            "SVG file to save the sheet to instead of plotting it",
+                            "SVG-файл, у який зберегти аркуш замість малювання"
+                        ))
+                        .value_name("FILE"),
+                ),
+        )
+        .subcommand(
+            Command::new("optimize")
+                .about(tr!(
+                    "Reorders, joins and simplifies the paths of a drawing and writes it back to SVG",
+                    "Впорядковує, з'єднує й спрощує шляхи малюнка та записує його назад у SVG"
+                ))
+                .arg(
+                    Arg::new("input")
+                        .help(tr!("SVG file to optimize", "SVG-файл для оптимізації"))
+                        .value_name("FILE")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .help(tr!(
+                            "SVG file to write; .jsonl writes JSON lines",
+                            "SVG-файл для запису; .jsonl записує рядки JSON"
+                        ))
+                        .value_name("FILE")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("join")
+                        .long("join")
+                        .help(tr!(
+                            "Join consecutive paths whose ends are closer than this (in mm)",
+                            "З'єднати сусідні шляхи, кінці яких ближчі за цю відстань (в мм)"
+                        ))
+                        .value_name("MM")
+                        .default_value("0")
+                        .value_parser(clap::value_parser!(f64)),
+                )
+                .arg(
+                    Arg::new("simplify")
+                        .long("simplify")
+                        .help(tr!(
+                            "Drop vertices that move a path by less than this (in mm)",
+                            "Відкинути вершини, що зсувають шлях менше ніж на цю відстань (в мм)"
+                        ))
+                        .value_name("MM")
+                        .default_value("0")
+                        .value_parser(clap::value_parser!(f64)),
+                )
+                .arg(
+                    Arg::new("show_travel")
+                        .long("show_travel")
+                        .help(tr!(
+                            "Draw pen-up moves as a dashed red layer",
+                            "Показати переїзди з піднятим пером червоним пунктиром в окремому шарі"
+                        ))
+                        .action(clap::ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("plan")
+                .about(tr!(
+                    "Plans the motion of a drawing and writes it to JSON or to SVG colored by velocity",
+                    "Планує рух для малюнка й записує план у JSON або в SVG, розфарбований за швидкістю"
+                ))
+                .arg(
+                    Arg::new("input")
+                        .help(tr!("SVG file to plan", "SVG-файл для планування"))
+                        .value_name("FILE")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .help(tr!(
+                            "Output file: .svg for a velocity preview, JSON otherwise; without it JSON goes to stdout",
+                            "Файл результату: .svg для перегляду швидкості, інакше JSON; без нього JSON виводиться в stdout"
+                        ))
+                        .value_name("FILE"),
+                )
+                .arg(
+                    Arg::new("animate")
+                        .long("animate")
+                        .help(tr!(
+                            "Write an SVG animation of the plotting order instead of the velocity preview",
+                            "Записати в SVG анімацію порядку малювання замість перегляду швидкості"
+                        ))
+                        .requires("output")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("fit")
+                        .long("fit")
+                        .help(tr!("Fit the drawing to the sheet", "Підігнати малюнок під аркуш"))
+                        .action(clap::ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("prepare")
+                .about(tr!(
+                    "Orders the paths of SVG files once and saves them as a job file for plot",
+                    "Один раз впорядковує шляхи SVG-файлів і зберігає їх як завдання для plot"
+                ))
+                .arg(
+                    Arg::new("input")
+                        .help(tr!(
+                            "SVG files; each one becomes a layer with a pen change pause after it",
+                            "SVG-файли; кожен стає шаром з паузою для заміни пера після нього"
+                        ))
+                        .value_name("FILE")
+                        .num_args(1..)
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .help(tr!("Job file to write", "Файл завдання для запису"))
+                        .value_name("FILE")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("fit")
+                        .long("fit")
+                        .help(tr!(
+                            "Fit each layer to the sheet",
+                            "Підігнати кожен шар під аркуш"
+                        ))
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("by_color")
+                        .long("by_color")
+                        .help(tr!(
+                            "Split each file into layers by stroke color, one pen per color",
+                            "Розбити кожен файл на шари за кольором лінії, по перу на колір"
+                        ))
+                        .action(clap::ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("plot")
+                .about(tr!(
+                    "Plots an SVG file or a job file from prepare",
+                    "Малює SVG-файл або файл завдання з prepare на плотері"
+                ))
+                .arg(
+                    Arg::new("input")
+                        .help(tr!(
+                            "SVG, HPGL, JSON lines or job file to plot, or its http(s) URL; - reads it from stdin",
+                            "SVG-, HPGL-файл, рядки JSON або файл завдання для малювання чи його адреса http(s); - читає його з stdin"
+                        ))
+                        .value_name("FILE")
+                        .required_unless_present("clipboard"),
+                )
+                .arg(
+                    Arg::new("clipboard")
+                        .long("clipboard")
+                        .help(tr!(
+                            "Plot the drawing copied to the clipboard instead of a file",
+                            "Малювати скопійований у буфер обміну малюнок замість файлу"
+                        ))
+                        .conflicts_with("input")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("notify")
+                        .long("notify")
+                        .help(tr!(
+                            "Show a desktop notification when the plot finishes, pauses or fails",
+                            "Показати сповіщення на робочому столі, коли малюнок завершиться, призупиниться чи перерветься помилкою"
+                        ))
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("webhook")
+                        .long("webhook")
+                        .help(tr!(
+                            "POST JSON with the event and plot statistics to this URL when the plot finishes, pauses or fails",
+                            "Надіслати POST-запит з JSON події та статистики малюнка на цю адресу, коли малюнок завершиться, призупиниться чи перерветься помилкою"
+                        ))
+                        .value_name("URL"),
+                )
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .help(tr!(
+                            "Input format; without it the format is detected from the content",
+                            "Формат вхідних даних; без нього формат визначається за вмістом"
+                        ))
+                        .value_name("FORMAT")
+                        .value_parser(["svg", "hpgl", "jsonl", "json"]),
+                )
+                .arg(
+                    Arg::new("from_path")
+                        .long("from_path")
+                        .help(tr!(
+                            "Resume a job file from this path (counting from 1)",
+                            "Продовжити файл завдання з цього шляху (рахуючи з 1)"
+                        ))
+                        .value_name("PATH")
+                        .value_parser(clap::value_parser!(usize)),
+                )
+                .arg(
+                    Arg::new("fit")
+                        .long("fit")
+                        .help(tr!("Fit the drawing to the sheet", "Підігнати малюнок під аркуш"))
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("stream")
+                        .long("stream")
+                        .help(tr!(
+                            "Plot paths as the file is parsed instead of holding the whole drawing in memory",
+                            "Малювати шляхи в міру розбору файлу, не тримаючи весь малюнок у пам'яті"
+                        ))
+                        .action(clap::ArgAction::SetTrue)
+                        .conflicts_with("fit"),
+                )
+                .arg(
+                    Arg::new("tile")
+                        .long("tile")
+                        .help(tr!(
+                            "Split a drawing larger than the sheet into pages and pause between them to change the paper",
+                            "Розбити малюнок, більший за аркуш, на сторінки й зупинятися між ними для заміни паперу"
+                        ))
+                        .action(clap::ArgAction::SetTrue)
+                        .conflicts_with_all(["fit", "stream"]),
+                )
+                .arg(
+                    Arg::new("overlap")
+                        .long("overlap")
+                        .help(tr!(
+                            "Overlap of neighbouring pages (in mm)",
+                            "Перекриття сусідніх сторінок (в мм)"
+                        ))
+                        .value_name("MM")
+                        .default_value("10")
+                        .value_parser(clap::value_parser!(f64))
+                        .requires("tile"),
+                )
+                .arg(
+                    Arg::new("marks")
+                        .long("marks")
+                        .help(tr!(
+                            "Draw alignment crosses in the page overlaps",
+                            "Малювати хрестики суміщення в перекриттях сторінок"
+                        ))
+                        .action(clap::ArgAction::SetTrue)
+                        .requires("tile"),
+                ),
+        )
+        .subcommand(
+            Command::new("testplot")
+                .about(tr!(
+                    "Plots test sheets for choosing plotter settings",
+                    "Малює тестові аркуші для вибору налаштувань плотера"
+                ))
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("pens")
+                        .about(tr!(
+                            "Plots a labelled grid of pen test strokes at different speeds, accelerations and pen-down positions",
+                            "Малює підписану сітку пробних штрихів з різною швидкістю, прискоренням і положенням опущеного пера"
+                        ))
+                        .arg(
+                            Arg::new("speeds")
+                                .long("speeds")
+                                .help(tr!(
+                                    "Comma-separated speeds of the columns (mm/s); by default half to four times the drawing speed",
+                                    "Швидкості стовпців через кому (мм/с); типово від половини до вчетверо більшої за швидкість малювання"
+                                ))
+                                .value_name("SPEEDS")
+                                .value_delimiter(',')
+                                .value_parser(clap::value_parser!(f64)),
+                        )
+                        .arg(
+                            Arg::new("accelerations")
+                                .long("accelerations")
+                                .help(tr!(
+                                    "Comma-separated accelerations (mm/s²); by default the current one",
+                                    "Прискорення через кому (мм/с²); типово поточне"
+                                ))
+                                .value_name("ACCELERATIONS")
+                                .value_delimiter(',')
+                                .value_parser(clap::value_parser!(f64)),
+                        )
+                        .arg(
+                            Arg::new("pen_down")
+                                .long("pen_down")
+                                .help(tr!(
+                                    "Comma-separated pen-down positions (0..100); by default the current one and 5 above and below",
+                                    "Положення опущеного пера через кому (0..100); типово поточне та на 5 вище й нижче"
+                                ))
+                                .value_name("POSITIONS")
+                                .value_delimiter(',')
+                                .value_parser(clap::value_parser!(i32).range(0..=100)),
+                        )
+                        .arg(
+                            Arg::new("output")
+                                .long("output")
+                                .help(tr!(
+                                    "SVG file to save a preview of the sheet to instead of plotting it",
+                                    "SVG-файл, у який зберегти перегляд аркуша замість малювання"
+                                ))
+                                .value_name("FILE"),
+                        ),
+                ),
+        )
+        .subcommand(
+            Command::new("calibrate")
+                .about(tr!(
+                    "Calibrates the plotter and stores the result in the config file",
+                    "Калібрує плотер і зберігає результат у файлі конфігурації"
+                ))
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("geometry")
+                        .about(tr!(
+                            "Plots a square with diagonals, or computes the scale and skew correction from its measurements",
+                            "Малює квадрат із діагоналями або обчислює поправку масштабу й перекосу з його вимірів"
+                        ))
+                        .arg(
+                            Arg::new("size")
+                                .long("size")
+                                .help(tr!("Side of the square (in mm)", "Сторона квадрата (в мм)"))
+                                .value_name("MM")
+                                .default_value("100")
+                                .value_parser(clap::value_parser!(f64)),
+                        )
+                        .arg(
+                            Arg::new("width")
+                                .long("width")
+                                .help(tr!(
+                                    "Measured horizontal side (in mm)",
+                                    "Виміряна горизонтальна сторона (в мм)"
+                                ))
+                                .value_name("MM")
+                                .requires_all(["height", "diagonal", "antidiagonal"])
+                                .value_parser(clap::value_parser!(f64)),
+                        )
+                        .arg(
+                            Arg::new("height")
+                                .long("height")
+                                .help(tr!(
+                                    "Measured vertical side (in mm)",
+                                    "Виміряна вертикальна сторона (в мм)"
+                                ))
+                                .value_name("MM")
+                                .requires_all(["width", "diagonal", "antidiagonal"])
+                                .value_parser(clap::value_parser!(f64)),
+                        )
+                        .arg(
+                            Arg::new("diagonal")
+                                .long("diagonal")
+                                .help(tr!(
+                                    "Measured diagonal from the top left to the bottom right corner (in mm)",
+                                    "Виміряна діагональ з верхнього лівого кута в нижній правий (в мм)"
+                                ))
+                                .value_name("MM")
+                                .requires_all(["width", "height", "antidiagonal"])
+                                .value_parser(clap::value_parser!(f64)),
+                        )
+                        .arg(
+                            Arg::new("antidiagonal")
+                                .long("antidiagonal")
+                                .help(tr!(
+                                    "Measured diagonal from the top right to the bottom left corner (in mm)",
+                                    "Виміряна діагональ з верхнього правого кута в нижній лівий (в мм)"
+                                ))
+                                .value_name("MM")
+                                .requires_all(["width", "height", "diagonal"])
+                                .value_parser(clap::value_parser!(f64)),
+                        )
+                        .arg(
+                            Arg::new("reset")
+                                .long("reset")
+                                .help(tr!(
+                                    "Remove the stored correction",
+                                    "Вилучити збережену поправку"
+                                ))
+                                .conflicts_with("width")
+                                .action(clap::ArgAction::SetTrue),
+                        ),
+                ),
+        )
+        .subcommand(
+            Command::new("devices")
+                .about(tr!(
+                    "Manage several connected AxiDraws",
+                    "Керування кількома підключеними AxiDraw"
+                ))
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("list")
+                        .about(tr!(
+                            "Lists connected EBB boards with nicknames and firmware versions",
+                            "Виводить підключені плати EBB з псевдонімами та версіями прошивки"
+                        )),
+                ),
+        )
+        .subcommand(
+            Command::new("firmware")
+                .about(tr!(
+                    "Updates the EBB firmware through its bootloader",
+                    "Оновлює прошивку EBB через її завантажувач"
+                ))
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("bootloader").about(tr!(
+                        "Restarts the board into the firmware bootloader for an external flasher",
+                        "Перезапускає плату в завантажувач прошивки для зовнішньої програми"
+                    )),
+                )
+                .subcommand(
+                    Command::new("update")
+                        .about(tr!(
+                            "Flashes an Intel HEX firmware image and checks the version afterwards",
+                            "Записує образ прошивки Intel HEX і перевіряє версію після запису"
+                        ))
+                        .arg(
+                            Arg::new("image")
+                                .help(tr!("Firmware image (.hex)", "Образ прошивки (.hex)"))
+                                .value_name("HEX")
+                                .required(true),
+                        )
+                        .arg(
+                            Arg::new("flasher")
+                                .long("flasher")
+                                .help(tr!(
+                                    "Command that writes the image through the HID bootloader; {{}} is replaced by the image path",
+                                    "Команда, що записує образ через завантажувач HID; {{}} замінюється шляхом до образу"
+                                ))
+                                .value_name("COMMAND")
+                                .default_value("mphidflash -w {} -r"),
+                        )
+                        .arg(
+                            Arg::new("wait")
+                                .long("wait")
+                                .help(tr!(
+                                    "How long to wait for the board to come back after flashing (in seconds)",
+                                    "Скільки чекати на повернення плати після запису (в секундах)"
+                                ))
+                                .value_name("SECONDS")
+                                .default_value("30")
+                                .value_parser(clap::value_parser!(u64)),
+                        ),
+                ),
+        )
+        .subcommand(
+            Command::new("replay")
+                .about(tr!(
+                    "Resends the motion and pen commands from a transcript",
+                    "Повторно надсилає команди руху та пера з журналу обміну"
+                ))
+                .arg(
+                    Arg::new("input")
+                        .help(tr!(
+                            "Transcript recorded with --transcript",
+                            "Журнал, записаний з --transcript"
+                        ))
+                        .value_name("FILE")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            Command::new("park")
+                .about(tr!(
+                    "Moves the carriage to --park, or to the far right, and holds it there",
+                    "Переміщує каретку в --park або до правого краю й тримає її там"
+                )),
+        )
+        .subcommand(
+            Command::new("status")
+                .about(tr!(
+                    "Prints plotter status: firmware, pen, motors, button and position",
+                    "Виводить стан плотера: прошивку, перо, мотори, кнопку та позицію"
+                )),
+        )
+        .subcommand(
+            Command::new("stats")
+                .about(tr!(
+                    "Prints drawing statistics and an ink usage estimate",
+                    "Виводить статистику малюнка та оцінку витрати чорнила"
+                ))
+                .arg(
+                    Arg::new("input")
+                        .help(tr!("SVG file to analyse", "SVG-файл для аналізу"))
+                        .value_name("FILE")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("nib")
+                        .long("nib")
+                        .help(tr!("Pen line width (in mm)", "Ширина лінії пера (в мм)"))
+                        .value_name("WIDTH")
+                        .default_value("0.5")
+                        .value_parser(clap::value_parser!(f64)),
+                ),
+        )
+        .subcommand(
+            Command::new("text")
+                .about(tr!(
+                    "Typesets text in a Hershey font to SVG without a plotter",
+                    "Верстає текст шрифтом Hershey в SVG без плотера"
+                ))
+                .arg(
+                    Arg::new("content")
+                        .help(tr!("Text to typeset", "Текст для верстки"))
+                        .value_name("TEXT")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .help(tr!(
+                            "SVG file to write; standard output if omitted",
+                            "SVG-файл для запису; без нього — стандартний вивід"
+                        ))
+                        .value_name("FILE"),
+                )
+                .arg(
+                    Arg::new("font")
+                        .long("font")
+                        .help(tr!("Hershey font", "Шрифт Hershey"))
+                        .value_name("FONT")
+                        .default_value("romans")
+                        .value_parser(FONT_NAMES),
+                )
+                .arg(
+                    Arg::new("size")
+                        .long("size")
+                        .help(tr!(
+                            "Capital letter height (in mm)",
+                            "Висота великих літер (в мм)"
+                        ))
+                        .value_name("HEIGHT")
+                        .default_value("5")
+                        .value_parser(clap::value_parser!(f64)),
+                )
+                .arg(
+                    Arg::new("width")
+                        .long("width")
+                        .help(tr!(
+                            "Line width before wrapping (in mm)",
+                            "Ширина рядка до перенесення (в мм)"
+                        ))
+                        .value_name("WIDTH")
+                        .default_value("180")
+                        .value_parser(clap::value_parser!(f64)),
+                )
+                .arg(
+                    Arg::new("line_height")
+                        .long("line_height")
+                        .help(tr!(
+                            "Distance between lines (in mm); 1.6 letter heights by default",
+                            "Відстань між рядками (в мм); типово 1,6 висоти літер"
+                        ))
+                        .value_name("HEIGHT")
+                        .value_parser(clap::value_parser!(f64)),
+                )
+                .arg(
+                    Arg::new("align")
+                        .long("align")
+                        .help(tr!(
+                            "Line alignment: left, center or right",
+                            "Вирівнювання рядків: left, center або right"
+                        ))
+                        .value_name("ALIGN")
+                        .default_value("left")
+                        .value_parser(TextAlign::from_str),
+                )
+                .arg(
+                    Arg::new("markup")
+                        .long("markup")
+                        .help(tr!(
+                            "Read ^superscript^ and ~subscript~ markup in the text",
+                            "Розбирати в тексті розмітку ^верхніх^ і ~нижніх~ індексів"
+                        ))
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("padding")
+                        .long("padding")
+                        .help(tr!(
+                            "Blank space around the text (in mm)",
+                            "Поля навколо тексту (в мм)"
+                        ))
+                        .value_name("PADDING")
+                        .default_value("1")
+                        .value_parser(clap::value_parser!(f64)),
+                ),
+        )
         .get_matches();
 
-    // Ініціалізація стандартних опцій
-    let mut options = Options::default();
+    // Ініціалізація логування з рівнем за замовчуванням "info"; обмін з платою пишеться
+    // з ціллю `wire` навіть тоді, коли решту журналу приглушено через RUST_LOG
+    let mut filter = EnvFilter::builder()
+        .with_default_directive(LevelFilter::INFO.into())
+        .from_env_lossy();
+    if matches.contains_id("log_serial") {
+        filter = filter.add_directive(format!("{}=info", WIRE_TARGET).parse()?);
+    }
+    // Слід пише окремий шар, тож його проміжки не потрапляють у журнал
+    let trace_file = matches.get_one::<String>("trace_file");
+    let (trace_layer, recorder) = match trace_file {
+        Some(_) => {
+            let (layer, recorder) = trace::layer();
+            (Some(layer), Some(recorder))
+        }
+        None => (None, None),
+    };
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_writer(std::io::stderr)
+                .with_ansi(std::io::stderr().is_terminal())
+                .with_filter(filter),
+        )
+        .with(trace_layer)
+        .init();
+
+    let (Some(trace_file), Some(recorder)) = (trace_file, recorder) else {
+        return run(&matches);
+    };
+    let result = run(&matches);
+    write_trace(trace_file, &recorder)?;
+    result
+}
+
+/// Виконує команду з розібраних параметрів командного рядка.
+fn run(matches: &clap::ArgMatches) -> Result<()> {
+    let config_path = matches
+        .get_one::<String>("config")
+        .map(PathBuf::from)
+        .or_else(Config::default_path);
+    let config = match &config_path {
+        Some(path) => Config::load(path)?,
+        None => Config::default(),
+    };
+    let profile = match matches.get_one::<String>("profile") {
+        Some(name) => config.profile(name)?.clone(),
+        None => Profile::default(),
+    };
+
+    // Ініціалізація стандартних опцій для вибраної моделі; профіль перекриває їх, а
+    // параметри командного рядка — і профіль
+    let model = match matches
+        .get_one::<String>("model")
+        .or(profile.model.as_ref())
+    {
+        Some(code) => code.parse()?,
+        None => AxiDrawModel::Mini,
+    };
+    let mut options = Options::for_model(model);
+    profile.apply(&mut options);
 
     // Перевизначення опцій на основі введення CLI
+    if let Some(microsteps) = matches.get_one::<String>("microstepping") {
+        options.set_step_mode(match microsteps.as_str() {
+            "16" => StepMode::OneSixteenth,
+            "8" => StepMode::OneEighth,
+            "4" => StepMode::OneQuarter,
+            "2" => StepMode::OneHalf,
+            "1" => StepMode::FullStep,
+            _ => unreachable!(),
+        });
+    }
     if let Some(steps_per_unit) = matches.get_one::<i32>("steps_per_unit") {
         options.steps_per_unit = *steps_per_unit;
     }
@@ -135,35 +1703,1485 @@ fn main() -> Result<()> {
     if let Some(pen_down_delay) = matches.get_one::<i32>("pen_down_delay") {
         options.pen_down_delay = *pen_down_delay;
     }
+    if let Some(pen_settle) = matches.get_one::<i32>("pen_settle") {
+        options.pen_settle = *pen_settle;
+    }
+    if let Some(stall_limit) = matches.get_one::<u64>("stall_limit") {
+        options.stall_limit = Some(Duration::from_millis(*stall_limit));
+    }
     if let Some(acceleration) = matches.get_one::<f64>("acceleration") {
         options.acceleration = *acceleration;
     }
+    if let Some(acceleration) = matches.get_one::<f64>("travel_acceleration") {
+        options.travel_acceleration = Some(*acceleration);
+    }
     if let Some(max_velocity) = matches.get_one::<f64>("max_velocity") {
         options.max_velocity = *max_velocity;
     }
     if let Some(corner_factor) = matches.get_one::<f64>("corner_factor") {
         options.corner_factor = *corner_factor;
     }
-    if let Some(model) = matches.get_one::<String>("model") {
-        options.model = match model.as_str() {
-            "v3" => AxiDrawModel::V3,
-            "v3a3" => AxiDrawModel::V3A3,
-            "sea3" => AxiDrawModel::SEA3,
-            "mini" => AxiDrawModel::Mini,
-            _ => unreachable!(),
+    if let Some(detail_speed_factor) = matches.get_one::<f64>("detail_speed_factor") {
+        options.detail_speed_factor = *detail_speed_factor;
+    }
+    options.low_level_moves = matches.get_flag("low_level_moves");
+    if let Some(paper) = matches.get_one::<Paper>("paper") {
+        options.paper = Some(*paper);
+    }
+    if let Some(margins) = matches.get_one::<Margins>("margin") {
+        let paper = options
+            .paper
+            .unwrap_or_else(|| Paper::from_model(options.model));
+        options.paper = Some(paper.margins(*margins));
+    }
+    if let Some(bleed) = matches.get_one::<f64>("bleed") {
+        if !(bleed.is_finite() && *bleed >= 0.0) {
+            bail!(tr!(
+                "--bleed must not be negative",
+                "--bleed не може бути від'ємним"
+            ));
+        }
+        let paper = options
+            .paper
+            .unwrap_or_else(|| Paper::from_model(options.model));
+        options.paper = Some(paper.bleed(*bleed));
+    }
+
+    // Обрізання йде в координатах файлу, до підгонки під аркуш і зміни системи координат
+    let crop = if matches.get_flag("crop") {
+        let paper = options
+            .paper
+            .unwrap_or_else(|| Paper::from_model(options.model));
+        Some(paper.printable_area()?)
+    } else {
+        None
+    };
+    let import = ImportOptions {
+        dpi: *matches.get_one::<f64>("dpi").unwrap(),
+        units: *matches.get_one::<Unit>("units").unwrap(),
+        crop,
+        pen_width: matches.get_one::<f64>("pen_width").copied(),
+    };
+    if import.dpi <= 0.0 {
+        bail!(tr!("--dpi must be positive", "--dpi має бути додатним"));
+    }
+    if import.pen_width.is_some_and(|width| width <= 0.0) {
+        bail!(tr!(
+            "--pen_width must be positive",
+            "--pen_width має бути додатним"
+        ));
+    }
+
+    let lead_in = matches.get_one::<f64>("lead_in").copied();
+    let lead_out = matches.get_one::<f64>("lead_out").copied();
+    if lead_in.is_some() || lead_out.is_some() {
+        options.leads = Some(Leads {
+            lead_in: lead_in.unwrap_or(0.0),
+            lead_out: lead_out.unwrap_or(0.0),
+            ..Leads::default()
+        });
+    }
+
+    if let Some(every) = matches.get_one::<f64>("refill_every") {
+        options.refill = Some(Refill {
+            every: *every,
+            location: *matches.get_one::<(f64, f64)>("dip_location").unwrap(),
+            swirl: *matches.get_one::<f64>("dip_swirl").unwrap(),
+        });
+    }
+
+    let tool_pin = matches.get_one::<String>("tool_pin");
+    options.tool = match matches.get_one::<String>("tool").map(String::as_str) {
+        Some("pwm") => {
+            let Some(pin) = tool_pin.and_then(|pin| pin.parse().ok()) else {
+                bail!(tr!(
+                    "--tool_pin for pwm must be an RPn number",
+                    "--tool_pin для pwm має бути номером RPn"
+                ));
+            };
+            let intensities = matches
+                .get_many::<f64>("intensity")
+                .map(|levels| levels.copied().collect())
+                .unwrap_or_default();
+            Tool::Pwm { pin, intensities }
+        }
+        Some("pin") => {
+            let pin = tool_pin.and_then(|pin| {
+                let port = pin.chars().next()?.to_ascii_uppercase();
+                Some((port, pin.get(1..)?.parse().ok()?))
+            });
+            let Some((port, pin)) = pin else {
+                bail!(tr!(
+                    "--tool_pin for pin must be a port and pin such as B3",
+                    "--tool_pin для pin має бути портом і піном, наприклад B3"
+                ));
+            };
+            Tool::Pin { port, pin }
+        }
+        _ => Tool::Pen,
+    };
+
+    let optimize_time = matches.get_one::<f64>("optimize_time").copied();
+    if matches.get_flag("optimize") || optimize_time.is_some() {
+        let improve = match optimize_time {
+            Some(seconds) => Some(Duration::try_from_secs_f64(seconds)?).filter(|t| !t.is_zero()),
+            None => PathOrder::default().improve,
         };
+        options.order = Some(PathOrder {
+            improve,
+            ..PathOrder::default()
+        });
+    }
+
+    options.direction = match matches.get_one::<String>("direction").map(String::as_str) {
+        Some("left_to_right") => Some(PathDirection::LeftToRight),
+        Some("outward") => Some(PathDirection::Outward),
+        Some("clockwise") => Some(PathDirection::Clockwise),
+        _ => None,
+    };
+    options.coordinates = CoordinateSystem {
+        origin: match matches.get_one::<String>("origin").map(String::as_str) {
+            Some("bottom_left") => Origin::BottomLeft,
+            _ => Origin::TopLeft,
+        },
+        flip_x: matches.get_flag("flip_x"),
+        flip_y: matches.get_flag("flip_y"),
+    };
+
+    options.transcript = matches.get_one::<String>("transcript").cloned();
+    if let Some(timeout) = matches.get_one::<u64>("serial_timeout") {
+        options.serial.timeout = Duration::from_millis(*timeout);
+    }
+    if let Some(retries) = matches.get_one::<u32>("retries") {
+        options.serial.retries = *retries;
+    }
+    if let Some(write_chunk) = matches.get_one::<usize>("write_chunk") {
+        options.serial.write_chunk = *write_chunk;
+    }
+    if let Some(level) = matches.get_one::<String>("log_serial") {
+        options.serial.wire_log = Some(match level.as_str() {
+            "raw" => WireLog::Raw,
+            "responses" => WireLog::Responses,
+            _ => WireLog::Commands,
+        });
+    }
+    options.park_position = matches.get_one::<(f64, f64)>("park").copied();
+    if let Some(port) = matches.get_one::<String>("device") {
+        options.port = Some(port.clone());
+    }
+    options.port_config = matches.get_one::<String>("port_config").cloned();
+
+    // Поправка геометрії з конфігурації застосовується до кожного малюнка
+    options.correction = config.correction;
+
+    // Перелік плат лише опитує порти й не налаштовує плотер
+    if let Some(devices) = matches.subcommand_matches("devices") {
+        if devices.subcommand_matches("list").is_some() {
+            let found = Device::list(options.port_config.as_deref())?;
+            if found.is_empty() {
+                println!("{}", tr!("No AxiDraw found", "Не знайдено жодного AxiDraw"));
+            }
+            for device in found {
+                println!("{}", device);
+            }
+        }
+        return Ok(());
+    }
+
+    // Статистика малюнка не потребує плотера
+    if let Some(stats) = matches.subcommand_matches("stats") {
+        let drawing = options.arrange(&read_drawing(
+            stats.get_one::<String>("input").unwrap(),
+            &import,
+        )?);
+        let nib = *stats.get_one::<f64>("nib").unwrap();
+        let stats = drawing.stats();
+        println!("{}", stats);
+        println!(
+            "{}",
+            tr!(
+                "Ink: {:.1} mm² (pen {} mm)",
+                "Чорнило: {:.1} мм² (перо {} мм)",
+                stats.ink_usage(nib),
+                nib
+            )
+        );
+        return Ok(());
+    }
+
+    // Верстка тексту лише записує SVG і не потребує плотера
+    if let Some(text) = matches.subcommand_matches("text") {
+        return typeset_text(text, &options);
+    }
+
+    // Швидкість і прискорення мають бути в межах можливостей моделі
+    options.validate()?;
+
+    // Імітація: весь конвеєр виконується без підключення до плотера
+    if let Some(simulate) = matches.subcommand_matches("simulate") {
+        return simulate_plot(
+            options,
+            simulate.get_one::<String>("input").unwrap(),
+            simulate.get_one::<String>("output"),
+            simulate.get_flag("fit"),
+            &import,
+        );
+    }
+
+    if let Some(testplot) = matches.subcommand_matches("testplot") {
+        if let Some(pens) = testplot.subcommand_matches("pens") {
+            return plot_pen_test(options, pens);
+        }
+        return Ok(());
+    }
+
+    if let Some(firmware) = matches.subcommand_matches("firmware") {
+        if let Some(update) = firmware.subcommand_matches("update") {
+            return update_firmware(options, update);
+        }
+        let mut axidraw = Axidraw::new(options)?;
+        return Ok(axidraw.device.enter_bootloader()?);
+    }
+
+    if let Some(calibrate) = matches.subcommand_matches("calibrate") {
+        if let Some(geometry) = calibrate.subcommand_matches("geometry") {
+            return calibrate_geometry(options, geometry, config, config_path);
+        }
+        return Ok(());
+    }
+
+    // План руху обчислюється без плотера, з тими самими налаштуваннями, що й малювання
+    if let Some(optimize) = matches.subcommand_matches("optimize") {
+        return optimize_drawing(options, optimize, &import);
+    }
+    if let Some(plan) = matches.subcommand_matches("plan") {
+        return export_plan(&options, plan, &import);
+    }
+
+    // Підготовка завдання лише впорядковує шляхи й не потребує плотера
+    if let Some(prepare) = matches.subcommand_matches("prepare") {
+        let paper = options
+            .paper
+            .unwrap_or_else(|| Paper::from_model(options.model));
+        let mut layers = vec![];
+        for input in prepare.get_many::<String>("input").unwrap() {
+            let mut layer = read_drawing(input, &import)?;
+            if prepare.get_flag("fit") {
+                layer = layer.fit_to_page(&paper)?;
+            }
+            if prepare.get_flag("by_color") {
+                layers.extend(layer.split_by_color());
+            } else {
+                layers.push(layer);
+            }
+        }
+        let job = JobFile::prepare(&options, layers)?;
+        let output = prepare.get_one::<String>("output").unwrap();
+        job.save(output)?;
+        info!(
+            "{}",
+            tr!(
+                "Job saved to {}: {} layers, {} paths",
+                "Завдання збережено в {}: шарів {}, шляхів {}",
+                output,
+                job.layers.len(),
+                job.total_paths()
+            )
+        );
+        return Ok(());
+    }
+
+    if let Some(plot) = matches.subcommand_matches("plot") {
+        // Ctrl-C скасовує малюнок, а не обриває процес із опущеним пером
+        control::install_signal_handlers();
+        if plot.contains_id("webhook") && !cfg!(feature = "url") {
+            bail!(tr!(
+                "--webhook needs rsaxi built with --features url",
+                "--webhook потребує rsaxi, зібраного з --features url"
+            ));
+        }
+        let notifier = Notifier::new(
+            plot.get_flag("notify"),
+            plot.get_one::<String>("webhook").cloned(),
+        );
+        let result = plot_input(options, plot, matches, &import, &notifier);
+        notifier.finish(result.as_ref().err().map(ToString::to_string));
+        return result;
+    }
+
+    // Зображення перетворюється на лінії й малюється так само, як SVG-файл
+    if let Some(raster) = matches.subcommand_matches("raster") {
+        return convert_raster(options, raster);
+    }
+    if let Some(contour) = matches.subcommand_matches("contour") {
+        return trace_contours(options, contour);
+    }
+    if let Some(stipple) = matches.subcommand_matches("stipple") {
+        return convert_stipple(options, stipple);
+    }
+    if let Some(generate) = matches.subcommand_matches("generate") {
+        return run_generator(options, generate);
+    }
+    if let Some(layout) = matches.subcommand_matches("layout") {
+        return lay_out_copies(options, layout, &import);
+    }
+
+    // Черепашка малює свій слід так само, як SVG-файл, або лише зберігає його
+    if let Some(turtle) = matches.subcommand_matches("turtle") {
+        return run_turtle(options, turtle);
     }
 
     // Ініціалізація AxiDraw з модифікованими опціями
     let mut axidraw = Axidraw::new(options)?;
 
+    if let Some(replay) = matches.subcommand_matches("replay") {
+        let input = File::open(replay.get_one::<String>("input").unwrap())?;
+        let commands = transcript::read_commands(BufReader::new(input))?;
+        axidraw.device.replay(&commands)?;
+        return Ok(());
+    }
+
+    if matches.subcommand_matches("status").is_some() {
+        return print_status(&mut axidraw);
+    }
+
+    // Паркування відводить каретку від аркуша, щоб його можна було оглянути чи забрати
+    if matches.subcommand_matches("park").is_some() {
+        let (x, y) = axidraw
+            .options
+            .park_position
+            .unwrap_or((axidraw.options.model.travel().0, 0.0));
+        return Ok(axidraw.park_at(x, y)?);
+    }
+
+    // Режим сервера: плотер керується через HTTP до зупинки процесу
+    if let Some(serve) = matches.subcommand_matches("serve") {
+        let host = serve.get_one::<String>("host").unwrap();
+        let port = *serve.get_one::<u16>("port").unwrap();
+        return Ok(server::serve(axidraw, host, port)?);
+    }
+
     // Приклад використання: підняти ручку для перевірки застосування опцій
     if let Err(e) = axidraw.device.pen_up() {
-        error!("Помилка підняття ручки: {}", e);
+        error!(
+            "{}",
+            tr!(
+                "Failed to raise the pen: {}",
+                "Помилка підняття ручки: {}",
+                e
+            )
+        );
         std::process::exit(1);
     }
 
-    info!("CLI конфігурація успішно застосована!");
+    info!(
+        "{}",
+        tr!(
+            "CLI configuration applied.",
+            "CLI конфігурація успішно застосована!"
+        )
+    );
+
+    Ok(())
+}
+
+/// Розбирає точку у вигляді `X,Y` (в мм).
+fn parse_point(value: &str) -> Result<(f64, f64), String> {
+    value
+        .split_once(',')
+        .and_then(|(x, y)| Some((x.trim().parse().ok()?, y.trim().parse().ok()?)))
+        .ok_or_else(|| {
+            tr!(
+                "Expected X,Y in millimetres, got '{}'",
+                "Очікувалося X,Y у міліметрах, а не '{}'",
+                value
+            )
+        })
+}
+
+/// Малює тестовий аркуш пера або, з `--output`, записує його перегляд у SVG.
+fn plot_pen_test(options: Options, pens: &clap::ArgMatches) -> Result<()> {
+    let mut test = PenTest::around(&options);
+    if let Some(speeds) = pens.get_many::<f64>("speeds") {
+        test.velocities = speeds.copied().collect();
+    }
+    if let Some(accelerations) = pens.get_many::<f64>("accelerations") {
+        test.accelerations = accelerations.copied().collect();
+    }
+    if let Some(positions) = pens.get_many::<i32>("pen_down") {
+        test.pen_down_positions = positions.copied().collect();
+    }
+
+    let Some(output) = pens.get_one::<String>("output") else {
+        control::install_signal_handlers();
+        Axidraw::new(options)?.draw_pen_test(&test)?;
+        return Ok(());
+    };
+    let drawing = test.to_drawing()?;
+    let (width, height) = drawing.bounds;
+    std::fs::write(output, drawing.to_svg_on(&Paper::custom(width, height)))?;
+    info!(
+        "{}",
+        tr!(
+            "Pen test saved to {}: {} samples, {:.1} x {:.1} mm",
+            "Тестовий аркуш збережено в {}: {} зразків, {:.1} x {:.1} мм",
+            output,
+            test.velocities.len() * test.rows().len(),
+            width,
+            height
+        )
+    );
+    Ok(())
+}
+
+/// Малює калібрувальний квадрат або зберігає поправку геометрії, обчислену з його вимірів.
+///
+/// Квадрат малюється без поправки, тож виміри описують спотворення самого плотера, а
+/// нова поправка замінює попередню.
+fn calibrate_geometry(
+    mut options: Options,
+    geometry: &clap::ArgMatches,
+    mut config: Config,
+    config_path: Option<PathBuf>,
+) -> Result<()> {
+    let size = *geometry.get_one::<f64>("size").unwrap();
+    if size <= 0.0 {
+        bail!(tr!("--size must be positive", "--size має бути додатним"));
+    }
+    let measured = |name: &str| geometry.get_one::<f64>(name).copied();
+    let reset = geometry.get_flag("reset");
+
+    if !reset && measured("width").is_none() {
+        options.correction = None;
+        control::install_signal_handlers();
+        Axidraw::new(options)?.draw(&calibration_pattern(size))?;
+        info!(
+            "{}",
+            tr!(
+                "Measure the sides and diagonals of the square and run calibrate geometry with --width, --height, --diagonal and --antidiagonal.",
+                "Виміряйте сторони й діагоналі квадрата та запустіть calibrate geometry з --width, --height, --diagonal і --antidiagonal."
+            )
+        );
+        return Ok(());
+    }
+
+    let Some(path) = config_path else {
+        bail!(tr!(
+            "No config file location; pass --config",
+            "Невідоме розташування файлу конфігурації; вкажіть --config"
+        ));
+    };
+    config.correction = match reset {
+        true => None,
+        false => Some(Correction::from_measurements(
+            size,
+            measured("width").unwrap(),
+            measured("height").unwrap(),
+            measured("diagonal").unwrap(),
+            measured("antidiagonal").unwrap(),
+        )?),
+    };
+    config.save(&path)?;
+    match config.correction {
+        Some(correction) => info!(
+            "{}",
+            tr!(
+                "Geometry correction saved to {}: scale_x {:.5}, scale_y {:.5}, skew {:.5}",
+                "Поправку геометрії збережено в {}: scale_x {:.5}, scale_y {:.5}, перекіс {:.5}",
+                path.display(),
+                correction.scale_x,
+                correction.scale_y,
+                correction.skew
+            )
+        ),
+        None => info!(
+            "{}",
+            tr!(
+                "Geometry correction removed from {}",
+                "Поправку геометрії вилучено з {}",
+                path.display()
+            )
+        ),
+    }
+    Ok(())
+}
+
+/// Планує рух для малюнка та записує план у JSON або, для файлу `.svg`, у попередній перегляд.
+fn export_plan(options: &Options, plan: &clap::ArgMatches, import: &ImportOptions) -> Result<()> {
+    let paper = options
+        .paper
+        .unwrap_or_else(|| Paper::from_model(options.model));
+    let mut drawing = read_drawing(plan.get_one::<String>("input").unwrap(), import)?;
+    if plan.get_flag("fit") {
+        drawing = drawing.fit_to_page(&paper)?;
+    }
+    let job = options.plan_drawing(&drawing)?;
+    info!(
+        "{}",
+        tr!(
+            "Estimated motion time: {:.1} s",
+            "Орієнтовний час руху: {:.1} с",
+            job.total_time()
+        )
+    );
+
+    match plan.get_one::<String>("output") {
+        Some(path) if plan.get_flag("animate") || path.to_lowercase().ends_with(".svg") => {
+            match plan.get_flag("animate") {
+                true => std::fs::write(path, job.to_animated_svg(&paper))?,
+                false => std::fs::write(path, job.to_svg(&paper))?,
+            }
+        }
+        Some(path) => serde_json::to_writer(BufWriter::new(File::create(path)?), &job)?,
+        None => println!("{}", serde_json::to_string(&job)?),
+    }
+    Ok(())
+}
+
+/// Спрощує, впорядковує й з'єднує шляхи малюнка та записує результат у файл.
+///
+/// Шляхи впорядковуються завжди, а `--optimize_time` задає час на покращення порядку.
+fn optimize_drawing(
+    mut options: Options,
+    optimize: &clap::ArgMatches,
+    import: &ImportOptions,
+) -> Result<()> {
+    let output = optimize.get_one::<String>("output").unwrap();
+    let drawing = read_drawing(optimize.get_one::<String>("input").unwrap(), import)?;
+    let before = drawing.stats();
+    options.order.get_or_insert_with(PathOrder::default);
+    let optimized = options
+        .arrange(&drawing.simplify(*optimize.get_one::<f64>("simplify").unwrap()))
+        .join(*optimize.get_one::<f64>("join").unwrap());
+    let after = optimized.stats();
+    info!(
+        "{}",
+        tr!(
+            "Paths: {} -> {}, points: {} -> {}, pen up: {:.1} -> {:.1} mm",
+            "Шляхів: {} -> {}, точок: {} -> {}, переїзди: {:.1} -> {:.1} мм",
+            before.paths,
+            after.paths,
+            before.vertices,
+            after.vertices,
+            before.pen_up_length,
+            after.pen_up_length
+        )
+    );
+
+    let (width, height) = optimized.bounds;
+    let paper = Paper::custom(width, height);
+    match optimize.get_flag("show_travel") && !output.ends_with(".jsonl") {
+        true => std::fs::write(output, optimized.to_svg_with_travel(&paper))?,
+        false => save_drawing(output, &optimized, &paper)?,
+    }
+    info!(
+        "{}",
+        tr!(
+            "Drawing saved to {}: {} paths",
+            "Малюнок збережено в {}: шляхів {}",
+            output,
+            after.paths
+        )
+    );
+    Ok(())
+}
+
+/// Верстає текст шрифтом Hershey і записує його в SVG розміром з текст.
+fn typeset_text(text: &clap::ArgMatches, options: &Options) -> Result<()> {
+    let font = font::catalog::by_name(text.get_one::<String>("font").unwrap())?;
+    let size = *text.get_one::<f64>("size").unwrap();
+    let cap_height = font.metrics().cap_height;
+    if size <= 0.0 || cap_height <= 0.0 {
+        bail!(tr!("--size must be positive", "--size має бути додатним"));
+    }
+    let line_height = text
+        .get_one::<f64>("line_height")
+        .copied()
+        .unwrap_or(1.6 * size);
+
+    let content = text.get_one::<String>("content").unwrap();
+    let mut builder = TextBuilder::default()
+        .font(font)
+        .fallbacks(font::fallback::default_chain()?)
+        .scale(size / cap_height)
+        .width(*text.get_one::<f64>("width").unwrap())
+        .line_height(line_height)
+        .align(*text.get_one::<TextAlign>("align").unwrap());
+    builder = match text.get_flag("markup") {
+        true => builder.markup(content),
+        false => builder.content(content),
+    };
+    let drawing = options.arrange(
+        &builder
+            .build()?
+            .to_drawing(*text.get_one::<f64>("padding").unwrap())?,
+    );
+
+    // Документ отримує розміри в міліметрах, щоб відкриватися в масштабі 1:1
+    let (width, height) = drawing.bounds;
+    let svg = drawing.to_svg_on(&Paper::custom(width, height));
+    match text.get_one::<String>("output") {
+        Some(output) => {
+            std::fs::write(output, svg)?;
+            info!(
+                "{}",
+                tr!(
+                    "Text saved to {}: {:.1} x {:.1} mm",
+                    "Текст збережено в {}: {:.1} x {:.1} мм",
+                    output,
+                    width,
+                    height
+                )
+            );
+        }
+        None => println!("{}", svg),
+    }
+    Ok(())
+}
+
+/// Зчитує вміст файлу, адреси `http://` чи `https://` або, якщо шлях `-`, увесь stdin,
+/// щоб малюнки можна було подавати конвеєром з інших програм.
+fn read_input(path: &str) -> Result<String> {
+    if path.starts_with("http://") || path.starts_with("https://") {
+        return fetch_url(path);
+    }
+    if path != "-" {
+        return Ok(std::fs::read_to_string(path)?);
+    }
+    let mut content = String::new();
+    std::io::Read::read_to_string(&mut std::io::stdin(), &mut content)?;
+    Ok(content)
+}
+
+/// Найдовше завантаження малюнка за адресою.
+#[cfg(feature = "url")]
+const FETCH_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Завантажує малюнок за адресою клієнтом HTTP(S) з перевіркою сертифікатів TLS.
+///
+/// Відповідь читається не більше ніж до 10 МБ, тож адреса не змусить rsaxi виділити
+/// пам'ять без меж.
+#[cfg(feature = "url")]
+fn fetch_url(url: &str) -> Result<String> {
+    info!("{}", tr!("Downloading {}", "Завантаження {}", url));
+    let response = ureq::get(url).timeout(FETCH_TIMEOUT).call().map_err(|e| {
+        // Помилка ureq уже називає адресу, тож у повідомленні лишається тільки причина
+        let reason = match e {
+            ureq::Error::Status(code, response) => {
+                format!("HTTP {} {}", code, response.status_text())
+            }
+            ureq::Error::Transport(transport) => match std::error::Error::source(&transport) {
+                Some(source) => format!("{}: {}", transport.kind(), source),
+                None => match transport.message() {
+                    Some(message) => format!("{}: {}", transport.kind(), message),
+                    None => transport.kind().to_string(),
+                },
+            },
+        };
+        anyhow::anyhow!(tr!(
+            "Cannot download {}: {}",
+            "Не вдалося завантажити {}: {}",
+            url,
+            reason
+        ))
+    })?;
+    Ok(response.into_string()?)
+}
+
+/// Без ознаки `url` адреса не завантажується, а помилка підказує, як її ввімкнути.
+#[cfg(not(feature = "url"))]
+fn fetch_url(url: &str) -> Result<String> {
+    bail!(tr!(
+        "Cannot open {}: rsaxi was built without the url feature; download the file or rebuild with --features url",
+        "Не вдалося відкрити {}: rsaxi зібрано без ознаки url; завантажте файл або зберіть з --features url",
+        url
+    ));
+}
+
+/// Зчитує малюнок із буфера обміну, куди його скопіювали, наприклад, з вебредактора.
+#[cfg(feature = "clipboard")]
+fn read_clipboard() -> Result<String> {
+    let content = arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_text())
+        .map_err(|e| match e {
+            arboard::Error::ContentNotAvailable => anyhow::anyhow!(tr!(
+                "The clipboard holds no text",
+                "У буфері обміну немає тексту"
+            )),
+            e => anyhow::anyhow!(tr!(
+                "Cannot read the clipboard: {}",
+                "Не вдалося прочитати буфер обміну: {}",
+                e
+            )),
+        })?;
+    if content.trim().is_empty() {
+        bail!(tr!("The clipboard is empty", "Буфер обміну порожній"));
+    }
+    Ok(content)
+}
+
+/// Без ознаки `clipboard` буфер обміну не читається, а помилка підказує, як її ввімкнути.
+#[cfg(not(feature = "clipboard"))]
+fn read_clipboard() -> Result<String> {
+    bail!(tr!(
+        "Cannot read the clipboard: rsaxi was built without the clipboard feature; rebuild with --features clipboard or pipe the drawing to `plot -`",
+        "Не вдалося прочитати буфер обміну: rsaxi зібрано без ознаки clipboard; зберіть з --features clipboard або передайте малюнок конвеєром у `plot -`"
+    ));
+}
+
+/// Зчитує малюнок із SVG-, HPGL-файлу, рядків JSON або з stdin.
+fn read_drawing(path: &str, import: &ImportOptions) -> Result<Drawing> {
+    let content = read_input(path)?;
+    parse_drawing(&content, detect_format(&content), import)
+}
+
+/// Визначає формат вхідних даних за вмістом: `json` (завдання), `hpgl`, `jsonl` або `svg`.
+fn detect_format(content: &str) -> &'static str {
+    if JobFile::is_job(content) {
+        "json"
+    } else if Drawing::is_hpgl(content) {
+        "hpgl"
+    } else if Drawing::is_json_lines(content) {
+        "jsonl"
+    } else {
+        "svg"
+    }
+}
+
+/// Розбирає малюнок у форматі `format`; HPGL і рядки JSON обрізаються прямокутником
+/// `--crop`, як і SVG.
+///
+/// Товщини ліній у цих форматах немає, тож `--pen_width` до них не застосовується.
+fn parse_drawing(content: &str, format: &str, import: &ImportOptions) -> Result<Drawing> {
+    let drawing = match format {
+        "hpgl" => Drawing::from_hpgl(content)?,
+        "jsonl" => Drawing::from_json_lines(content)?,
+        _ => return Ok(Drawing::from_svg_with(content, import)?),
+    };
+    Ok(match import.crop {
+        Some(rect) => drawing.clip_to_rect(rect),
+        None => drawing,
+    })
+}
+
+/// Записує малюнок на аркуші у файл: рядками JSON, якщо назва закінчується на `.jsonl`,
+/// інакше в SVG з командою, якою його створено, в `<desc>`.
+fn save_drawing(output: &str, drawing: &Drawing, paper: &Paper) -> Result<()> {
+    let content = match output.ends_with(".jsonl") {
+        true => drawing.to_json_lines(),
+        false => drawing.to_svg_described(paper, &command_description()),
+    };
+    std::fs::write(output, content)?;
+    Ok(())
+}
+
+/// Опис малюнка для `<desc>`: версія rsaxi й аргументи команди, тобто генератор і його
+/// налаштування, щоб малюнок можна було відтворити.
+fn command_description() -> String {
+    let arguments: Vec<String> = std::env::args().skip(1).collect();
+    format!(
+        "rsaxi {}: {}",
+        env!("CARGO_PKG_VERSION"),
+        arguments.join(" ")
+    )
+}
+
+/// Малює SVG-файл на плотері, розбираючи шляхи в міру малювання.
+///
+/// Малює вхід команди `plot`: файл, адресу, stdin чи буфер обміну.
+fn plot_input(
+    options: Options,
+    plot: &clap::ArgMatches,
+    matches: &clap::ArgMatches,
+    import: &ImportOptions,
+    notifier: &Notifier,
+) -> Result<()> {
+    let content = match plot.get_one::<String>("input") {
+        Some(input) => read_input(input)?,
+        None => read_clipboard()?,
+    };
+    let format = plot
+        .get_one::<String>("format")
+        .map_or_else(|| detect_format(&content), String::as_str);
+    if format == "json" {
+        if plot.get_flag("fit")
+            || plot.get_flag("stream")
+            || plot.get_flag("tile")
+            || matches.get_flag("all_devices")
+        {
+            bail!(tr!(
+                "Job files do not support --fit, --stream, --tile or --all_devices",
+                "Файли завдань не підтримують --fit, --stream, --tile і --all_devices"
+            ));
+        }
+        let from_path = plot.get_one::<usize>("from_path").copied().unwrap_or(1);
+        return plot_job(options, JobFile::from_json(&content)?, from_path, notifier);
+    }
+    if plot.contains_id("from_path") {
+        bail!(tr!(
+            "--from_path applies only to job files",
+            "--from_path застосовується лише до файлів завдань"
+        ));
+    }
+    if plot.get_flag("stream") {
+        if matches.get_flag("all_devices") {
+            bail!(tr!(
+                "--stream does not support --all_devices",
+                "--stream не підтримує --all_devices"
+            ));
+        }
+        if format != "svg" {
+            bail!(tr!(
+                "--stream supports only SVG files",
+                "--stream підтримує лише SVG-файли"
+            ));
+        }
+        return stream_drawing(connect(options, notifier)?, &content, import);
+    }
+
+    let drawing = parse_drawing(&content, format, import)?;
+    if plot.get_flag("tile") {
+        if matches.get_flag("all_devices") {
+            bail!(tr!(
+                "--tile does not support --all_devices",
+                "--tile не підтримує --all_devices"
+            ));
+        }
+        return plot_tiled(connect(options, notifier)?, drawing, plot);
+    }
+    let fit = plot.get_flag("fit");
+    if matches.get_flag("all_devices") {
+        return plot_on_all_devices(options, drawing, fit, notifier);
+    }
+    plot_drawing(connect(options, notifier)?, drawing, fit)
+}
+
+/// Підключає плотер і передає його пульт сповіщувачу, щоб той бачив паузи й статистику.
+fn connect(options: Options, notifier: &Notifier) -> Result<Axidraw> {
+    let axidraw = Axidraw::new(options)?;
+    notifier.watch(&axidraw.control);
+    Ok(axidraw)
+}
+
+/// Помилка розбору зупиняє малюнок на шляху, який не вдалося прочитати.
+fn stream_drawing(mut axidraw: Axidraw, content: &str, import: &ImportOptions) -> Result<()> {
+    if axidraw.options.order.is_some() || axidraw.options.direction.is_some() {
+        warn!(
+            "{}",
+            tr!(
+                "Path ordering and direction do not apply to streamed plotting.",
+                "Впорядкування й напрямок шляхів не застосовуються до потокового малювання."
+            )
+        );
+    }
+    if !axidraw.options.coordinates.is_paper() {
+        warn!(
+            "{}",
+            tr!(
+                "--origin, --flip_x and --flip_y do not apply to streamed plotting.",
+                "--origin, --flip_x і --flip_y не застосовуються до потокового малювання."
+            )
+        );
+    }
+    if axidraw.options.refill.is_some() {
+        warn!(
+            "{}",
+            tr!(
+                "Ink refills do not apply to streamed plotting.",
+                "Поповнення чорнила не застосовується до потокового малювання."
+            )
+        );
+    }
+    let mut failure = None;
+    let paths = Drawing::svg_paths_with(content, import)?
+        .map_while(|path| path.map_err(|e| failure = Some(e)).ok());
+    axidraw.draw_iter(paths)?;
+    match failure {
+        Some(e) => Err(e.into()),
+        None => Ok(()),
+    }
+}
+
+/// Малює малюнок на підключеному плотері.
+fn plot_drawing(mut axidraw: Axidraw, mut drawing: Drawing, fit: bool) -> Result<()> {
+    if fit {
+        drawing = drawing.fit_to_page(&axidraw.paper())?;
+    }
+    axidraw.draw(&drawing)?;
+    Ok(())
+}
+
+/// Розбиває малюнок на сторінки розміром з область малювання аркуша й малює їх по черзі.
+fn plot_tiled(mut axidraw: Axidraw, drawing: Drawing, plot: &clap::ArgMatches) -> Result<()> {
+    let overlap = *plot.get_one::<f64>("overlap").unwrap();
+    let area = axidraw.paper().printable_area()?;
+    if !(0.0..area.width().min(area.height()) / 2.0).contains(&overlap) {
+        bail!(tr!(
+            "--overlap must be at least 0 and less than half the sheet",
+            "--overlap має бути не меншим за 0 і меншим за половину аркуша"
+        ));
+    }
+    // Сторінки ріжуться вже в системі аркуша, тож самі сторінки не перетворюються
+    let drawing = drawing.in_coordinates(&axidraw.options.coordinates);
+    axidraw.options.coordinates = CoordinateSystem::default();
+    let pages = drawing.tile((area.width(), area.height()), overlap);
+    info!(
+        "{}",
+        tr!(
+            "Drawing split into {} pages.",
+            "Малюнок розбито на {} сторінок.",
+            pages.len()
+        )
+    );
+    axidraw.draw_pages(&pages, plot.get_flag("marks"))?;
+    Ok(())
+}
+
+/// Перетворює зображення на лінії та малює їх на плотері або записує в SVG.
+fn convert_raster(options: Options, raster: &clap::ArgMatches) -> Result<()> {
+    let width = *raster.get_one::<f64>("width").unwrap();
+    let spacing = *raster.get_one::<f64>("spacing").unwrap();
+    if width <= 0.0 || spacing <= 0.0 {
+        bail!(tr!(
+            "--width and --spacing must be positive",
+            "--width і --spacing мають бути додатними"
+        ));
+    }
+    let style = match raster.get_one::<String>("style").unwrap().as_str() {
+        "spiral" => RasterStyle::Spiral,
+        "crosshatch" => RasterStyle::Crosshatch,
+        "edges" => RasterStyle::Edges,
+        _ => RasterStyle::Squiggle,
+    };
+    let drawing =
+        Raster::open(raster.get_one::<String>("input").unwrap())?.to_lines(&RasterOptions {
+            style,
+            width,
+            spacing,
+        });
+    emit_drawing(options, drawing, raster)
+}
+
+/// Будує ізолінії яскравості зображення й малює їх на плотері або записує в SVG.
+fn trace_contours(options: Options, contour: &clap::ArgMatches) -> Result<()> {
+    let width = *contour.get_one::<f64>("width").unwrap();
+    let count = *contour.get_one::<usize>("levels").unwrap();
+    if width <= 0.0 || count == 0 {
+        bail!(tr!(
+            "--width and --levels must be positive",
+            "--width і --levels мають бути додатними"
+        ));
+    }
+    let raster = Raster::open(contour.get_one::<String>("input").unwrap())?;
+    let levels: Vec<f64> = (1..=count)
+        .map(|level| level as f64 / (count + 1) as f64)
+        .collect();
+    let mut drawing = contours(&raster, &levels);
+    let scale = width / drawing.bounds.0;
+    drawing
+        .paths
+        .affine_transform_mut(&AffineTransform::scale(scale, scale, Coord::zero()));
+    drawing.bounds = (width, drawing.bounds.1 * scale);
+    emit_drawing(options, drawing, contour)
+}
+
+/// Перетворює зображення на пунктир і малює його на плотері або записує в SVG.
+fn convert_stipple(options: Options, stipple: &clap::ArgMatches) -> Result<()> {
+    let width = *stipple.get_one::<f64>("width").unwrap();
+    let gamma = *stipple.get_one::<f64>("gamma").unwrap();
+    if width <= 0.0 || gamma <= 0.0 {
+        bail!(tr!(
+            "--width and --gamma must be positive",
+            "--width і --gamma мають бути додатними"
+        ));
+    }
+    let seed = *stipple.get_one::<u64>("seed").unwrap();
+    let method = match stipple.get_one::<String>("method").unwrap().as_str() {
+        "circles" => {
+            let min_radius = *stipple.get_one::<f64>("min_radius").unwrap();
+            let max_radius = *stipple.get_one::<f64>("max_radius").unwrap();
+            if !(min_radius > 0.0 && max_radius >= min_radius) {
+                bail!(tr!(
+                    "--min_radius must be positive and not above --max_radius",
+                    "--min_radius має бути додатним і не більшим за --max_radius"
+                ));
+            }
+            let packing = CirclePacking {
+                min_radius,
+                max_radius,
+                seed,
+                ..CirclePacking::default()
+            };
+            let raster = Raster::open(stipple.get_one::<String>("input").unwrap())?;
+            return emit_drawing(options, raster.to_circles(&packing, width, gamma), stipple);
+        }
+        "poisson" => StippleMethod::Poisson,
+        _ => StippleMethod::Voronoi,
+    };
+    let drawing =
+        Raster::open(stipple.get_one::<String>("input").unwrap())?.to_stipples(&StippleOptions {
+            method,
+            count: *stipple.get_one::<usize>("count").unwrap(),
+            iterations: *stipple.get_one::<usize>("iterations").unwrap(),
+            gamma,
+            width,
+            line: stipple.get_flag("line"),
+            seed,
+        });
+    emit_drawing(options, drawing, stipple)
+}
+
+/// Малює згенерований малюнок на плотері або, з `--output`, записує його в SVG розміром з малюнок.
+fn emit_drawing(options: Options, drawing: Drawing, matches: &clap::ArgMatches) -> Result<()> {
+    let Some(output) = matches.get_one::<String>("output") else {
+        control::install_signal_handlers();
+        return plot_drawing(Axidraw::new(options)?, drawing, matches.get_flag("fit"));
+    };
+
+    let (width, height) = drawing.bounds;
+    save_drawing(
+        output,
+        &options.arrange(&drawing),
+        &Paper::custom(width, height),
+    )?;
+    info!(
+        "{}",
+        tr!(
+            "Drawing saved to {}: {} paths",
+            "Малюнок збережено в {}: шляхів {}",
+            output,
+            drawing.paths.0.len()
+        )
+    );
+    Ok(())
+}
+
+/// Розкладає копії малюнка по аркушу та малює їх на плотері або записує аркуш у SVG.
+fn lay_out_copies(
+    options: Options,
+    layout: &clap::ArgMatches,
+    import: &ImportOptions,
+) -> Result<()> {
+    let grid = Layout {
+        gap: *layout.get_one::<f64>("gap").unwrap(),
+        jitter: *layout.get_one::<f64>("jitter").unwrap(),
+        seed: *layout.get_one::<u64>("seed").unwrap(),
+        ..*layout.get_one::<Layout>("nup").unwrap()
+    };
+    let paper = options
+        .paper
+        .unwrap_or_else(|| Paper::from_model(options.model));
+    let drawing = read_drawing(layout.get_one::<String>("input").unwrap(), import)?;
+    let sheet = drawing.n_up(&paper, &grid)?;
+    info!(
+        "{}",
+        tr!(
+            "{} copies in a {}x{} grid on {}",
+            "{} копій сіткою {}x{} на {}",
+            grid.columns * grid.rows,
+            grid.columns,
+            grid.rows,
+            paper.name
+        )
+    );
+
+    let Some(output) = layout.get_one::<String>("output") else {
+        control::install_signal_handlers();
+        return plot_drawing(Axidraw::new(options)?, sheet, false);
+    };
+    save_drawing(output, &options.arrange(&sheet), &paper)?;
+    info!(
+        "{}",
+        tr!(
+            "Drawing saved to {}: {} paths",
+            "Малюнок збережено в {}: шляхів {}",
+            output,
+            sheet.paths.0.len()
+        )
+    );
+    Ok(())
+}
+
+/// Будує малюнок генератором з реєстру та малює його на плотері або записує в SVG.
+///
+/// Без назви генератора виводить список генераторів з їхніми параметрами.
+fn run_generator(options: Options, generate: &clap::ArgMatches) -> Result<()> {
+    let registry = Registry::default();
+    let Some(name) = generate.get_one::<String>("name") else {
+        for generator in registry.generators() {
+            println!("{} — {}", generator.name(), generator.description());
+            for param in generator.params() {
+                println!(
+                    "    {}={} ({}) {}",
+                    param.name,
+                    param.default,
+                    param.kind.name(),
+                    param.help
+                );
+            }
+        }
+        return Ok(());
+    };
+    let params = generate
+        .get_many::<String>("param")
+        .unwrap_or_default()
+        .map(String::as_str);
+    let drawing = registry.generate(name, params)?;
+    emit_drawing(options, drawing, generate)
+}
+
+/// Виконує сценарій черепашки та малює слід на плотері або записує його в SVG.
+fn run_turtle(options: Options, turtle: &clap::ArgMatches) -> Result<()> {
+    let script = read_input(turtle.get_one::<String>("input").unwrap())?;
+    let drawing = run_script(&script)?.to_drawing(*turtle.get_one::<f64>("padding").unwrap());
+    emit_drawing(options, drawing, turtle)
+}
+
+/// Малює файл завдання з параметрами, збереженими в ньому, починаючи зі шляху `from_path`.
+///
+/// Із параметрів командного рядка беруться лише порт, журнал обміну та параметри
+/// зв'язку з платою.
+fn plot_job(options: Options, job: JobFile, from_path: usize, notifier: &Notifier) -> Result<()> {
+    let total = job.total_paths();
+    if from_path == 0 || from_path > total {
+        bail!(tr!(
+            "--from_path must be between 1 and {}",
+            "--from_path має бути від 1 до {}",
+            total
+        ));
+    }
+    let options = job.plot_options(&options);
+    options.validate()?;
+    let job = job.skip_paths(from_path - 1);
+    info!(
+        "{}",
+        tr!(
+            "Plotting job from path {} of {} ({} layers)",
+            "Малювання завдання зі шляху {} з {} (шарів: {})",
+            from_path,
+            total,
+            job.layers.len()
+        )
+    );
+
+    // Номери шляхів у журналі рахуються від початку завдання, а не від точки продовження
+    connect(options, notifier)?.draw_layers(&job.layers, from_path)?;
+    Ok(())
+}
+
+/// Малює той самий малюнок на всіх підключених AxiDraw, по потоку на пристрій.
+///
+/// Помилка одного плотера не зупиняє інші; команда завершується помилкою, якщо малюнок
+/// не вдалося завершити хоча б на одному пристрої.
+fn plot_on_all_devices(
+    options: Options,
+    drawing: Drawing,
+    fit: bool,
+    notifier: &Notifier,
+) -> Result<()> {
+    let devices: Vec<_> = Device::list(options.port_config.as_deref())?
+        .into_iter()
+        .filter(|device| device.firmware.is_some())
+        .collect();
+    if devices.is_empty() {
+        bail!(tr!("No AxiDraw found", "Не знайдено жодного AxiDraw"));
+    }
+    info!(
+        "{}",
+        tr!(
+            "Plotting on {} devices at once",
+            "Малювання на {} пристроях одночасно",
+            devices.len()
+        )
+    );
+
+    let mut plotters = vec![];
+    for (index, device) in devices.into_iter().enumerate() {
+        let mut options = options.clone();
+        options.port = Some(device.port_name.clone());
+        // Кожен пристрій пише власний журнал обміну
+        options.transcript = options
+            .transcript
+            .map(|path| format!("{}.{}", path, index + 1));
+        let (drawing, notifier) = (drawing.clone(), notifier.clone());
+        let plotter = thread::Builder::new()
+            .name(device.to_string())
+            .spawn(move || plot_drawing(connect(options, &notifier)?, drawing, fit))?;
+        plotters.push((device, plotter));
+    }
+
+    let total = plotters.len();
+    let mut failed = 0;
+    for (device, plotter) in plotters {
+        match plotter.join() {
+            Ok(Ok(())) => info!(
+                "{}",
+                tr!("{}: plot finished", "{}: малюнок завершено", device)
+            ),
+            Ok(Err(e)) => {
+                error!("{}: {}", device, e);
+                failed += 1;
+            }
+            Err(_) => {
+                error!(
+                    "{}",
+                    tr!(
+                        "{}: plotting thread crashed",
+                        "{}: потік малювання завершився аварійно",
+                        device
+                    )
+                );
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        bail!(tr!(
+            "Plot did not finish on {} of {} devices",
+            "Малюнок не завершено на {} з {} пристроїв",
+            failed,
+            total
+        ));
+    }
+    Ok(())
+}
+
+/// Записує слід у файл і виводить, скільки часу забрав кожен вид роботи.
+fn write_trace(path: &str, recorder: &trace::Recorder) -> Result<()> {
+    let events = recorder.finish();
+    trace::write_chrome(&events, BufWriter::new(File::create(path)?))?;
+    for (category, count, total) in trace::summary(&events) {
+        info!(
+            "{}",
+            tr!(
+                "Trace: {} × {}, {:.3} s",
+                "Слід: {} × {}, {:.3} с",
+                category.name(),
+                count,
+                total.as_secs_f64()
+            )
+        );
+    }
+    info!("{}", tr!("Trace saved to {}", "Слід збережено в {}", path));
+    Ok(())
+}
+
+/// Оновлює прошивку EBB: переводить плату в завантажувач, запускає програму запису образу
+/// та чекає, доки плата повернеться з версією з образу.
+///
+/// Завантажувач EBB працює через USB HID, а не серійний порт, тож сам запис виконує
+/// зовнішня програма, типово `mphidflash`, що є для Linux, macOS і Windows.
+fn update_firmware(options: Options, update: &clap::ArgMatches) -> Result<()> {
+    let path = update.get_one::<String>("image").unwrap();
+    let image = FirmwareImage::parse(&std::fs::read_to_string(path)?)?;
+    let expected = image.version();
+    info!(
+        "{}",
+        tr!(
+            "Firmware image {}: {} bytes, version {}",
+            "Образ прошивки {}: {} байт, версія {}",
+            path,
+            image.size(),
+            expected.map_or("?".to_string(), |version| version.to_string())
+        )
+    );
+    let command = update.get_one::<String>("flasher").unwrap();
+    let mut words = command
+        .split_whitespace()
+        .map(|word| word.replace("{}", path));
+    let Some(program) = words.next() else {
+        bail!(tr!(
+            "--flasher must not be empty",
+            "--flasher не може бути порожнім"
+        ));
+    };
+
+    let mut axidraw = Axidraw::new(options.clone())?;
+    let previous = axidraw.device.firmware;
+    axidraw.device.enter_bootloader()?;
+    drop(axidraw);
+    // Завантажувачу потрібен час, щоб з'явитися як пристрій HID
+    thread::sleep(Duration::from_secs(2));
+
+    let status = std::process::Command::new(&program)
+        .args(words)
+        .status()
+        .map_err(|e| {
+            anyhow::anyhow!(tr!(
+                "Cannot run {}: {}; install it or pass another program with --flasher",
+                "Не вдалося запустити {}: {}; встановіть її або вкажіть іншу програму в --flasher",
+                program,
+                e
+            ))
+        })?;
+    if !status.success() {
+        bail!(tr!(
+            "{} failed ({}); the board stays in the bootloader, run the update again",
+            "{} завершилася з помилкою ({}); плата лишається в завантажувачі, запустіть оновлення ще раз",
+            program,
+            status
+        ));
+    }
+
+    // Після запису плата перезапускається з новою прошивкою й знову з'являється на порту
+    let deadline =
+        std::time::Instant::now() + Duration::from_secs(*update.get_one::<u64>("wait").unwrap());
+    let firmware = loop {
+        match Axidraw::new(options.clone()) {
+            Ok(axidraw) => break axidraw.device.firmware,
+            Err(_) if std::time::Instant::now() < deadline => thread::sleep(Duration::from_secs(1)),
+            Err(e) => return Err(e.into()),
+        }
+    };
+    if expected.is_some_and(|expected| expected != firmware) {
+        bail!(tr!(
+            "The board reports firmware {} instead of {} from the image",
+            "Плата повідомляє прошивку {} замість {} з образу",
+            firmware,
+            expected.unwrap()
+        ));
+    }
+    info!(
+        "{}",
+        tr!(
+            "Firmware updated: {} → {}",
+            "Прошивку оновлено: {} → {}",
+            previous,
+            firmware
+        )
+    );
+    Ok(())
+}
+
+/// Виводить стан плотера.
+///
+/// Стан пера, моторів і кнопки читається однією командою `QG`, якщо її підтримує прошивка.
+fn print_status(axidraw: &mut Axidraw) -> Result<()> {
+    let device = &mut axidraw.device;
+    println!("{}", tr!("Firmware: {}", "Прошивка: {}", device.firmware));
+    println!(
+        "{}",
+        tr!(
+            "Motion queue (FIFO): {} commands",
+            "Черга руху (FIFO): {} команд",
+            device.fifo_depth()
+        )
+    );
+
+    if device.has_general_query() {
+        let status = device.general_query()?;
+        let yes_no = |value: bool| {
+            if value {
+                tr!("yes", "так")
+            } else {
+                tr!("no", "ні")
+            }
+        };
+        println!(
+            "{}",
+            tr!(
+                "Pen: {}",
+                "Перо: {}",
+                if status.pen_up {
+                    tr!("up", "підняте")
+                } else {
+                    tr!("down", "опущене")
+                }
+            )
+        );
+        println!(
+            "{}",
+            tr!(
+                "Motors moving: {}",
+                "Мотори рухаються: {}",
+                yes_no(!status.is_idle())
+            )
+        );
+        println!(
+            "{}",
+            tr!(
+                "Button pressed: {}",
+                "Кнопку натиснуто: {}",
+                yes_no(status.button_pressed)
+            )
+        );
+        println!(
+            "{}",
+            tr!(
+                "Servo power: {}",
+                "Живлення серво: {}",
+                yes_no(status.servo_power)
+            )
+        );
+    } else {
+        let lowered = device.query_pen_state()?;
+        println!(
+            "{}",
+            tr!(
+                "Pen: {}",
+                "Перо: {}",
+                if lowered {
+                    tr!("down", "опущене")
+                } else {
+                    tr!("up", "підняте")
+                }
+            )
+        );
+    }
+
+    let (motor1, motor2) = device.read_position()?;
+    let steps_per_unit = device.steps_per_unit as f64;
+    let (x, y) = kinematics::motors_to_xy(
+        motor1 as f64 / steps_per_unit,
+        motor2 as f64 / steps_per_unit,
+    );
+    println!(
+        "{}",
+        tr!(
+            "Position: ({:.2}, {:.2}) mm",
+            "Позиція: ({:.2}, {:.2}) мм",
+            x,
+            y
+        )
+    );
+
+    Ok(())
+}
+
+/// Малює SVG-файл на імітованому пристрої та записує журнал команд EBB у CSV.
+fn simulate_plot(
+    options: Options,
+    input: &str,
+    output: Option<&String>,
+    fit: bool,
+    import: &ImportOptions,
+) -> Result<()> {
+    let (mut axidraw, trace) = Axidraw::simulated(options)?;
+
+    let mut drawing = read_drawing(input, import)?;
+    if fit {
+        drawing = drawing.fit_to_page(&axidraw.paper())?;
+    }
+    axidraw.draw(&drawing)?;
+
+    let events = trace.events();
+    let total = events
+        .last()
+        .map_or(Duration::ZERO, |event| event.time + event.duration);
+    info!(
+        "{}",
+        tr!(
+            "Simulation finished: {} commands, {:.1} s",
+            "Імітація завершена: {} команд, тривалість {:.1} с",
+            events.len(),
+            total.as_secs_f64()
+        )
+    );
+
+    match output {
+        Some(path) => trace.write_csv(BufWriter::new(File::create(path)?))?,
+        None => trace.write_csv(std::io::stdout().lock())?,
+    }
 
     Ok(())
 }
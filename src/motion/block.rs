@@ -1,12 +1,14 @@
 use std::fmt;
 
 use geo::Point;
+use serde::Serialize;
 
 use super::{instant::Instant, point::PointExtension};
+use crate::tr;
 
 /// Представляє один сегмент руху.
 /// Він містить інформацію про початкову швидкість, прискорення та тривалість руху.
-#[derive(Clone)]
+#[derive(Clone, Serialize)]
 pub struct Block {
     pub acceleration: f64, // Прискорення, яке застосовується під час цього блоку руху.
     pub duration: f64,     // Тривалість блоку руху (в секундах).
@@ -84,8 +86,11 @@ impl Block {
             return self.instant(self.duration, dt, ds);
         }
 
-        // Обчислюємо кінцеву швидкість при пройденій відстані s
-        let vf = (self.initial_velocity.powi(2) + 2.0 * self.acceleration * s).sqrt();
+        // Обчислюємо кінцеву швидкість при пройденій відстані s; наприкінці гальмування
+        // похибка округлення може зробити підкореневий вираз трохи від'ємним
+        let vf = (self.initial_velocity.powi(2) + 2.0 * self.acceleration * s)
+            .max(0.0)
+            .sqrt();
 
         // Обчислюємо час t за формулою рівноприскореного руху
         let t = (2.0 * s) / (vf + self.initial_velocity);
@@ -99,15 +104,19 @@ impl fmt::Display for Block {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "Блок руху:\n  Прискорення: {}\n  Тривалість: {}\n  Початкова швидкість: {}\n  Відстань: {}\n  Початкова точка: ({}, {})\n  Кінцева точка: ({}, {})",
-            self.acceleration,
-            self.duration,
-            self.initial_velocity,
-            self.distance,
-            self.p1.x(),
-            self.p1.y(),
-            self.p2.x(),
-            self.p2.y(),
+            "{}",
+            tr!(
+                "Motion block:\n  Acceleration: {}\n  Duration: {}\n  Initial velocity: {}\n  Distance: {}\n  Start point: ({}, {})\n  End point: ({}, {})",
+                "Блок руху:\n  Прискорення: {}\n  Тривалість: {}\n  Початкова швидкість: {}\n  Відстань: {}\n  Початкова точка: ({}, {})\n  Кінцева точка: ({}, {})",
+                self.acceleration,
+                self.duration,
+                self.initial_velocity,
+                self.distance,
+                self.p1.x(),
+                self.p1.y(),
+                self.p2.x(),
+                self.p2.y()
+            )
         )
     }
 }
@@ -0,0 +1,351 @@
+use geo::Point;
+
+use super::kinematics::xy_to_motors;
+use super::plan::Plan;
+
+/// Частота переривання EBB, в якому команда `LM` додає швидкості до акумуляторів (Гц).
+pub const TICK_RATE: f64 = 25_000.0;
+
+/// Значення акумулятора, на якому мотор робить крок (старший біт 32-бітного лічильника).
+const THRESHOLD: u64 = 1 << 31;
+
+/// Найбільша швидкість `LM`: один крок за такт.
+const MAX_RATE: u32 = (THRESHOLD - 1) as u32;
+
+/// Одна команда `LM` для моторів 1 і 2.
+///
+/// Швидкість і прискорення задаються в одиницях акумулятора за такт: швидкість `2³¹`
+/// означає крок щотакту, тобто 25000 кроків/с.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StepMove {
+    pub rates: [u32; 2],   // Початкові швидкості моторів 1 і 2.
+    pub steps: [i32; 2],   // Кроки моторів 1 і 2; знак задає напрямок.
+    pub accels: [i32; 2],  // Прирости швидкостей моторів за такт.
+    pub clear: Option<u8>, // Які акумулятори обнулити перед рухом: біт 0 — мотор 1, біт 1 — мотор 2.
+    pub ticks: u32,        // Тривалість руху в тактах за моделлю акумуляторів.
+}
+
+/// Проганяє один мотор команди `LM` так, як це робить переривання EBB.
+///
+/// Щотакту до акумулятора додається швидкість, а до швидкості — прискорення. Коли
+/// акумулятор сягає старшого біта, мотор робить крок, а біт скидається; решта
+/// акумулятора переходить у наступний крок і, якщо його не обнулити, у наступну команду.
+///
+/// # Параметри
+/// - `rate`: Початкова швидкість (в одиницях акумулятора за такт).
+/// - `steps`: Кількість кроків без знаку.
+/// - `accel`: Приріст швидкості за такт.
+/// - `accumulator`: Значення акумулятора перед рухом.
+///
+/// # Повертає
+/// - `Option<(u64, u32)>`: Такт останнього кроку й акумулятор після нього або `None`, якщо
+///   швидкість упала до нуля раніше, ніж мотор зробив усі кроки.
+pub(crate) fn axis_ticks(
+    rate: u32,
+    steps: u32,
+    accel: i32,
+    accumulator: u32,
+) -> Option<(u64, u32)> {
+    let (mut rate, mut accumulator) = (rate as i64, accumulator as u64);
+    let (mut taken, mut tick) = (0, 0);
+    while taken < steps {
+        if rate == 0 && accel <= 0 {
+            return None;
+        }
+        tick += 1;
+        accumulator += rate as u64;
+        if accumulator >= THRESHOLD {
+            accumulator -= THRESHOLD;
+            taken += 1;
+        }
+        rate = (rate + accel as i64).clamp(0, MAX_RATE as i64);
+    }
+    Some((tick, accumulator as u32))
+}
+
+/// Швидкість і прискорення, з якими мотор робить `steps` кроків за `ticks` тактів.
+///
+/// Швидкість змінюється лінійно в пропорції швидкостей каретки `v0` на початку й `v1` у
+/// кінці руху, а початкова швидкість округлюється вгору, тож останній крок припадає не
+/// пізніше за останній такт. Якщо лінійний профіль виходить за крок щотакту або зупиняє
+/// мотор раніше за останній крок (коли крок часу охоплює і розгін, і гальмування), мотор
+/// іде зі сталою швидкістю, не більшою за крок щотакту, і рух триває стільки тактів,
+/// скільки потрібно.
+///
+/// # Повертає
+/// - `(u32, i32, u64, u32)`: Швидкість, прискорення, такт останнього кроку й акумулятор
+///   після нього.
+fn profile(steps: u32, ticks: u32, v0: f64, v1: f64, accumulator: u32) -> (u32, i32, u64, u32) {
+    let need = steps as f64 * THRESHOLD as f64 - accumulator as f64;
+    let n = ticks as f64;
+    let (w0, w1) = match v0 + v1 > 0.0 {
+        true => (v0 / (v0 + v1), v1 / (v0 + v1)),
+        false => (0.5, 0.5),
+    };
+    let accel = match ticks > 1 {
+        true => (2.0 * need * (w1 - w0) / (n * (n - 1.0))).round(),
+        false => 0.0,
+    };
+    let rate = ((need - accel * n * (n - 1.0) / 2.0) / n).ceil();
+    let rate = rate.clamp(if accel > 0.0 { 0.0 } else { 1.0 }, MAX_RATE as f64);
+    if rate.max(rate + accel * (n - 1.0)) <= MAX_RATE as f64 {
+        let (rate, accel) = (rate as u32, accel as i32);
+        if let Some((ticks, accumulator)) = axis_ticks(rate, steps, accel, accumulator) {
+            return (rate, accel, ticks, accumulator);
+        }
+    }
+    // Стала швидкість: останній крок — на першому такті, де акумулятор сягає потрібного
+    let rate = (need / n).ceil().clamp(1.0, MAX_RATE as f64);
+    let ticks = (need / rate).ceil().max(1.0);
+    let accumulator = accumulator as f64 + ticks * rate - steps as f64 * THRESHOLD as f64;
+    (rate as u32, 0, ticks as u64, accumulator as u32)
+}
+
+/// Перетворює плани руху на команди `LM` у просторі кроків моторів.
+///
+/// Позиція кожного кроку часу округлюється до цілих кроків від точної позиції моторів, а не
+/// від суми попередніх зміщень, тож округлення не накопичується, і кроки всіх команд у
+/// сумі дорівнюють запланованому зміщенню. Емітер веде модель акумуляторів EBB: залишок
+/// акумулятора переходить у наступну команду, а перед першою командою та після зміни
+/// напрямку мотора акумулятор обнуляється параметром `clear`. Тривалість, яку дає
+/// модель, віднімається від запланованого часу, тож команди не відстають від плану.
+pub struct StepEmitter {
+    steps_per_unit: f64,    // Кроки мотора на міліметр.
+    position: [i64; 2],     // Кроки моторів 1 і 2, вже віддані в команди.
+    exact: [f64; 2],        // Точна позиція моторів (в кроках) у кінці останнього плану.
+    accumulators: [u32; 2], // Акумулятори моторів після останньої команди за моделлю.
+    directions: [i32; 2],   // Напрямок останніх кроків моторів; 0, якщо акумулятор невідомий.
+    lag: f64,               // Запланований час (в тактах), ще не відданий у команди.
+}
+
+impl StepEmitter {
+    /// Створює емітер з нульовою позицією та невідомими акумуляторами.
+    ///
+    /// # Параметри
+    /// - `steps_per_unit`: Кроки мотора на міліметр руху.
+    pub fn new(steps_per_unit: f64) -> Self {
+        StepEmitter {
+            steps_per_unit,
+            position: [0; 2],
+            exact: [0.0; 2],
+            accumulators: [0; 2],
+            directions: [0; 2],
+            lag: 0.0,
+        }
+    }
+
+    /// Кроки мотора на міліметр, з якими емітер рахує позицію.
+    pub fn steps_per_unit(&self) -> f64 {
+        self.steps_per_unit
+    }
+
+    /// Кроки моторів 1 і 2, віддані в команди від створення емітера.
+    pub fn position(&self) -> (i64, i64) {
+        (self.position[0], self.position[1])
+    }
+
+    /// Перетворює план на команди `LM` з кроком часу `dt`.
+    ///
+    /// План задає відносний рух: його початок збігається з кінцем попереднього плану.
+    /// Кроки, на яких жоден мотор не рухається, приєднуються до наступної команди.
+    ///
+    /// # Параметри
+    /// - `plan`: План руху каретки (в мм).
+    /// - `dt`: Крок часу між командами (в секундах).
+    ///
+    /// # Повертає
+    /// - `Vec<StepMove>`: Команди в порядку виконання.
+    pub fn emit(&mut self, plan: &Plan, dt: f64) -> Vec<StepMove> {
+        let mut samples = plan.sample(dt);
+        let Some(first) = samples.next() else {
+            return vec![];
+        };
+        let (origin, start, scale) = (first.position, self.exact, self.steps_per_unit);
+        let target = |position: Point<f64>| {
+            let (motor1, motor2) =
+                xy_to_motors(position.x() - origin.x(), position.y() - origin.y());
+            [start[0] + motor1 * scale, start[1] + motor2 * scale]
+        };
+
+        let mut moves = vec![];
+        let (mut time, mut velocity) = (first.time_elapsed, first.velocity);
+        for instant in samples {
+            self.lag += (instant.time_elapsed - time) * TICK_RATE;
+            time = instant.time_elapsed;
+            self.exact = target(instant.position);
+            let steps =
+                [0, 1].map(|axis| (self.exact[axis].round() as i64 - self.position[axis]) as i32);
+            if steps == [0, 0] {
+                continue;
+            }
+            let ticks = self.lag.round().max(1.0) as u32;
+            let step = self.step_move(steps, ticks, velocity, instant.velocity);
+            self.lag -= step.ticks as f64;
+            moves.push(step);
+            velocity = instant.velocity;
+        }
+        // Пауза без кроків у кінці плану не переноситься в наступний рух
+        self.lag = self.lag.min(0.0);
+        moves
+    }
+
+    /// Команда для кроків `steps` за `ticks` тактів зі швидкістю каретки від `v0` до `v1`.
+    fn step_move(&mut self, steps: [i32; 2], ticks: u32, v0: f64, v1: f64) -> StepMove {
+        let mut step = StepMove {
+            rates: [0; 2],
+            steps,
+            accels: [0; 2],
+            clear: None,
+            ticks: 0,
+        };
+        let mut clear = 0;
+        for (axis, &signed) in steps.iter().enumerate() {
+            let count = signed.unsigned_abs();
+            if count == 0 {
+                continue;
+            }
+            // Залишок акумулятора від руху в інший бік не наближає крок у цей бік
+            if signed.signum() != self.directions[axis] {
+                clear |= 1 << axis;
+                self.accumulators[axis] = 0;
+                self.directions[axis] = signed.signum();
+            }
+            let (rate, accel, axis_ticks, accumulator) =
+                profile(count, ticks, v0, v1, self.accumulators[axis]);
+            step.rates[axis] = rate;
+            step.accels[axis] = accel;
+            step.ticks = step.ticks.max(axis_ticks as u32);
+            self.accumulators[axis] = accumulator;
+            self.position[axis] += signed as i64;
+        }
+        step.clear = (clear != 0).then_some(clear);
+        step
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::motion::planner::Planner;
+
+    #[test]
+    fn test_emitted_steps_sum_to_planned_displacement() {
+        // Той самий генератор, що й у перевірці планувальника, для відтворюваності
+        let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+        let mut random = move || {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            (state >> 11) as f64 / (1u64 << 53) as f64
+        };
+
+        let steps_per_unit = 80.0;
+        let mut emitter = StepEmitter::new(steps_per_unit);
+        let mut displacement = (0.0, 0.0);
+        let mut accumulators = [0u32; 2];
+        for _ in 0..40 {
+            let points: Vec<Point<f64>> = (0..4)
+                .map(|_| Point::new(random() * 60.0 - 30.0, random() * 60.0 - 30.0))
+                .collect();
+            let plan = Planner::new(150.0, 2000.0, 0.01)
+                .plan(points.clone())
+                .unwrap();
+            let moves = emitter.emit(&plan, 0.01);
+
+            let end = points[3] - points[0];
+            displacement = (displacement.0 + end.x(), displacement.1 + end.y());
+            let (motor1, motor2) = xy_to_motors(displacement.0, displacement.1);
+            assert_eq!(
+                emitter.position(),
+                (
+                    (motor1 * steps_per_unit).round() as i64,
+                    (motor2 * steps_per_unit).round() as i64
+                ),
+                "Кроки в сумі дорівнюють запланованому зміщенню"
+            );
+
+            // Незалежний прогін моделі з обнуленням підтверджує тривалість і кроки команд
+            let mut ticks = 0;
+            for step in &moves {
+                let mut longest = 0;
+                for (axis, state) in accumulators.iter_mut().enumerate() {
+                    if step.clear.is_some_and(|clear| clear & (1 << axis) != 0) {
+                        *state = 0;
+                    }
+                    let count = step.steps[axis].unsigned_abs();
+                    let (axis_ticks, accumulator) =
+                        axis_ticks(step.rates[axis], count, step.accels[axis], *state)
+                            .expect("Мотор має зробити всі кроки");
+                    *state = accumulator;
+                    longest = longest.max(axis_ticks);
+                }
+                assert_eq!(longest, step.ticks as u64);
+                ticks += step.ticks as u64;
+            }
+            let planned = plan.total_time * TICK_RATE;
+            assert!(
+                (ticks as f64 - planned).abs() < 0.01 * planned + 10.0,
+                "Команди займають запланований час: {} проти {}",
+                ticks,
+                planned
+            );
+        }
+
+        // Перша команда обнуляє акумулятори моторів, що рухаються
+        let mut emitter = StepEmitter::new(steps_per_unit);
+        let plan = Planner::new(150.0, 2000.0, 0.01)
+            .plan(vec![
+                Point::new(0.0, 0.0),
+                Point::new(10.0, 0.0),
+                Point::new(0.0, 0.0),
+            ])
+            .unwrap();
+        let moves = emitter.emit(&plan, 0.01);
+        assert_eq!(moves[0].clear, Some(3));
+        assert_eq!(
+            moves.iter().filter(|step| step.clear.is_some()).count(),
+            2,
+            "Ще раз — після розвороту"
+        );
+        assert_eq!(emitter.position(), (0, 0));
+
+        // Крок часу охоплює розгін і гальмування, а лінійний профіль перевищує крок щотакту
+        for (end, dt) in [
+            ((21.0, 21.0), 0.1),
+            ((50.0, 15.0), 0.1),
+            ((100.0, 0.0), 0.2),
+        ] {
+            let start = (0.0, 0.0);
+            let mut emitter = StepEmitter::new(steps_per_unit);
+            let plan = Planner::new(212.0, 3092.0, 0.01)
+                .plan(vec![Point::from(start), Point::from(end)])
+                .unwrap();
+            let moves = emitter.emit(&plan, dt);
+            let (motor1, motor2) = xy_to_motors(end.0 - start.0, end.1 - start.1);
+            assert_eq!(
+                emitter.position(),
+                (
+                    (motor1 * steps_per_unit).round() as i64,
+                    (motor2 * steps_per_unit).round() as i64
+                )
+            );
+            let mut accumulators = [0u32; 2];
+            for step in &moves {
+                assert!(step.rates.iter().all(|&rate| rate <= MAX_RATE));
+                let mut longest = 0;
+                for (axis, state) in accumulators.iter_mut().enumerate() {
+                    if step.clear.is_some_and(|clear| clear & (1 << axis) != 0) {
+                        *state = 0;
+                    }
+                    let count = step.steps[axis].unsigned_abs();
+                    let (axis_ticks, accumulator) =
+                        axis_ticks(step.rates[axis], count, step.accels[axis], *state)
+                            .expect("Мотор має зробити всі кроки");
+                    *state = accumulator;
+                    longest = longest.max(axis_ticks);
+                }
+                assert_eq!(longest, step.ticks as u64);
+            }
+        }
+    }
+}
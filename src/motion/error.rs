@@ -1,13 +1,72 @@
 use thiserror::Error;
 
+use super::limits::RateViolation;
+use crate::tr;
+
 /// Перелік можливих помилок при створенні плану руху.
 #[derive(Debug, Error)]
 pub enum PlanError {
     /// Недостатня кількість точок для створення плану.
-    #[error("Необхідно принаймні дві точки для створення плану.")]
+    #[error(
+        "{}",
+        tr!(
+            "At least one point is required to build a plan.",
+            "Необхідно принаймні одна точка для створення плану."
+        )
+    )]
     InsufficientPoints,
 
     /// Розміри масивів `vs` та `vmaxs` не відповідають кількості точок.
-    #[error("Довжини масивів `vs` та `vmaxs` повинні відповідати кількості точок.")]
+    #[error(
+        "{}",
+        tr!(
+            "The lengths of `vs` and `vmaxs` must match the number of points.",
+            "Довжини масивів `vs` та `vmaxs` повинні відповідати кількості точок."
+        )
+    )]
     VelocityMismatch,
+
+    /// Точка шляху має нескінченну або невизначену координату.
+    #[error(
+        "{}",
+        tr!(
+            "Path point {} has invalid coordinates ({}, {}).",
+            "Точка {} шляху має недійсні координати ({}, {}).",
+            .index,
+            .x,
+            .y
+        )
+    )]
+    NonFinitePoint { index: usize, x: f64, y: f64 },
+
+    /// Параметр руху має недопустиме значення (наприклад, нульове прискорення).
+    #[error(
+        "{}",
+        tr!(
+            "Invalid motion parameter `{}`: {}.",
+            "Недопустиме значення параметра руху `{}`: {}.",
+            .name,
+            .value
+        )
+    )]
+    InvalidParameter { name: &'static str, value: f64 },
+
+    /// План не містить стану руху для вказаного моменту часу.
+    #[error(
+        "{}",
+        tr!("The plan has no motion state at {} s.", "План не містить стану руху на {} с.", .t)
+    )]
+    InstantOutOfRange { t: f64 },
+
+    /// Частота кроків або прискорення мотора виходить за межі, що приймає EBB.
+    #[error(
+        "{}",
+        tr!(
+            "Step rate is out of the EBB range in {} blocks, first at {}.",
+            "Частота кроків виходить за межі EBB у {} блоках, уперше — {}.",
+            .count,
+            .first
+        )
+    )]
+    StepRate { count: usize, first: RateViolation },
 }
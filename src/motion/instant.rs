@@ -1,9 +1,13 @@
 use std::fmt;
 
 use geo::Point;
+use serde::Serialize;
+
+use crate::tr;
 
 /// Структура представляє стан руху в певний момент часу.
 /// Вона включає час, пройдену відстань, швидкість, прискорення та положення в просторі.
+#[derive(Serialize)]
 pub struct Instant {
     pub time_elapsed: f64,      // Час, що минув з початку руху
     pub distance_traveled: f64, // Пройдена відстань з початку руху
@@ -42,13 +46,17 @@ impl fmt::Display for Instant {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "Момент {{ час: {:.2}, пройдена відстань: {:.2}, швидкість: {:.2}, прискорення: {:.2}, позиція: ({:.2}, {:.2}) }}",
-            self.time_elapsed,
-            self.distance_traveled,
-            self.velocity,
-            self.acceleration,
-            self.position.x(),
-            self.position.y()
+            "{}",
+            tr!(
+                "Instant {{ time: {:.2}, distance: {:.2}, velocity: {:.2}, acceleration: {:.2}, position: ({:.2}, {:.2}) }}",
+                "Момент {{ час: {:.2}, пройдена відстань: {:.2}, швидкість: {:.2}, прискорення: {:.2}, позиція: ({:.2}, {:.2}) }}",
+                self.time_elapsed,
+                self.distance_traveled,
+                self.velocity,
+                self.acceleration,
+                self.position.x(),
+                self.position.y()
+            )
         )
     }
 }
@@ -0,0 +1,458 @@
+use std::fmt;
+use std::time::Duration;
+
+use geo::Point;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use tracing::warn;
+
+use super::{
+    error::PlanError, limits::MAX_STEP_RATE, plan::Plan, planner::Planner, point::PointExtension,
+};
+use crate::drawing::lead::Stroke;
+use crate::tr;
+
+/// Скільки ліній зі сповільненими відрізками перелічувати в попередженні.
+const LISTED_LINES: usize = 10;
+
+/// Найбільша відстань (в мм) між кінцем шляху та початком наступного, за якої шляхи
+/// малюються одним рухом без підйому пера.
+pub(crate) const CHAIN_TOLERANCE: f64 = 1e-9;
+
+/// Дія в плані малюнка.
+///
+/// У JSON дія має поле `action` (`move`, `pen_up`, `pen_down`, `pen_dwell`), рух містить
+/// ще й поля плану, а пауза — тривалість `seconds`.
+#[derive(Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum PlanAction {
+    Move(Plan), // Рух за планом з поточним станом пера.
+    PenUp,      // Підняти перо.
+    PenDown,    // Опустити перо.
+    #[serde(serialize_with = "dwell_seconds")]
+    PenDwell(Duration), // Постояти на місці, не змінюючи стану пера.
+}
+
+/// Записує паузу в JSON полем `seconds`, бо поле дії з тегом не може бути просто числом.
+fn dwell_seconds<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut dwell = serializer.serialize_struct("PenDwell", 1)?;
+    dwell.serialize_field("seconds", &duration.as_secs_f64())?;
+    dwell.end()
+}
+
+/// План усього малюнка: переїзди, малювання та зміни стану пера в порядку виконання.
+#[derive(Serialize)]
+pub struct Job {
+    pub actions: Vec<PlanAction>, // Дії в порядку виконання.
+}
+
+impl Job {
+    /// Загальний час за планом (в секундах): рух і паузи `PenDwell`, без затримок пера.
+    pub fn total_time(&self) -> f64 {
+        self.actions
+            .iter()
+            .map(|action| match action {
+                PlanAction::Move(plan) => plan.total_time,
+                PlanAction::PenDwell(duration) => duration.as_secs_f64(),
+                PlanAction::PenUp | PlanAction::PenDown => 0.0,
+            })
+            .sum()
+    }
+
+    /// Загальна відстань руху за планом (в мм).
+    pub fn total_distance(&self) -> f64 {
+        self.moves().map(|plan| plan.total_distance).sum()
+    }
+
+    /// Кількість опускань пера, тобто безперервних ліній малюнка.
+    pub fn pen_downs(&self) -> usize {
+        self.actions
+            .iter()
+            .filter(|action| matches!(action, PlanAction::PenDown))
+            .count()
+    }
+
+    fn moves(&self) -> impl Iterator<Item = &Plan> {
+        self.actions.iter().filter_map(|action| match action {
+            PlanAction::Move(plan) => Some(plan),
+            _ => None,
+        })
+    }
+}
+
+impl fmt::Display for Job {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            tr!(
+                "Job(actions: {}, pen downs: {}, distance: {:.2} mm, time: {:.2} s)",
+                "Job(дій: {}, опускань пера: {}, відстань: {:.2} мм, час: {:.2} с)",
+                self.actions.len(),
+                self.pen_downs(),
+                self.total_distance(),
+                self.total_time()
+            )
+        )
+    }
+}
+
+/// Планувальник усього малюнка.
+///
+/// На відміну від `Planner`, який планує один шлях, `JobPlanner` бачить усі шляхи
+/// разом зі станом пера. Шляхи, що продовжують один одного, зливаються в один план,
+/// тож каретка не зупиняється на їхньому стику, а час усього малюнка відомий ще до
+/// початку малювання.
+pub struct JobPlanner {
+    planner: Planner,        // Планувальник ліній з опущеним пером.
+    travel: Option<Planner>, // Планувальник переїздів з піднятим пером; без нього як `planner`.
+}
+
+impl JobPlanner {
+    /// Створює новий `JobPlanner`.
+    ///
+    /// # Параметри:
+    /// - `planner`: Планувальник, що задає швидкість, прискорення та обробку кутів.
+    pub fn new(planner: Planner) -> Self {
+        Self {
+            planner,
+            travel: None,
+        }
+    }
+
+    /// Задає окремий планувальник для переїздів з піднятим пером.
+    ///
+    /// Без чорнила на папері переїзд витримує різкіші розгони, ніж лінія, тож його
+    /// планувальник зазвичай має більше прискорення.
+    ///
+    /// # Параметри:
+    /// - `travel`: Планувальник переїздів між лініями.
+    pub fn with_travel(mut self, travel: Planner) -> Self {
+        self.travel = Some(travel);
+        self
+    }
+
+    /// Планує малюнок від початку координат через усі шляхи.
+    ///
+    /// Перед кожною лінією малюнка є переїзд з піднятим пером і `PenDown`, після неї —
+    /// `PenUp`, а для шляху з паузою між ними ще й `PenDwell`. Переїзд нульової довжини
+    /// пропускається. Повернення додому до плану не входить.
+    ///
+    /// # Параметри:
+    /// - `strokes`: Шляхи з частками максимальної швидкості для кожної точки.
+    ///
+    /// # Повертає:
+    /// - `Result<Job, PlanError>`: План малюнка або помилка планування.
+    pub fn plan(&self, strokes: &[Stroke]) -> Result<Job, PlanError> {
+        self.plan_from(Point::new(0.0, 0.0), strokes)
+    }
+
+    /// Планує частину малюнка від вказаної позиції каретки.
+    ///
+    /// Так малюнок можна планувати порціями: кожна наступна порція починається там,
+    /// де закінчилася попередня. Якщо планувальник знає кроки на міліметр, лінії, на яких
+    /// мотор перевищив би частоту кроків EBB, сповільнюються з одним попередженням на
+    /// весь план.
+    ///
+    /// # Параметри:
+    /// - `start`: Позиція каретки перед першим шляхом (в мм).
+    /// - `strokes`: Шляхи з частками максимальної швидкості для кожної точки.
+    ///
+    /// # Повертає:
+    /// - `Result<Job, PlanError>`: План частини малюнка або помилка планування.
+    pub fn plan_from(&self, start: Point<f64>, strokes: &[Stroke]) -> Result<Job, PlanError> {
+        self.plan_batch(start, None, strokes, &[])
+            .map(|(job, _)| job)
+    }
+
+    /// Планує порцію малюнка, лінії якої можуть продовжуватися з попередньої порції чи
+    /// в наступну, не зупиняючи каретку на межі порцій.
+    ///
+    /// Якщо задано `entry`, а перший шлях починається в `start`, перо вже опущене:
+    /// перша лінія не має переїзду й `PenDown` і починається зі швидкості `entry`.
+    /// Якщо остання лінія продовжується шляхами `ahead`, вона планується разом із тими з них,
+    /// що продовжують один одного, ніби рух зупиняється в їх кінці, але план обривається на
+    /// початку `ahead` без `PenUp`. Швидкість у цій точці повертається: наступна порція, що
+    /// починається з `ahead`, продовжує рух із неї, а шляхів `ahead` завжди вистачає, щоб
+    /// загальмувати. Чим вони довші, тим менше вони сповільнюють каретку на межі порцій.
+    ///
+    /// # Параметри:
+    /// - `start`: Позиція каретки перед першим шляхом (в мм).
+    /// - `entry`: Швидкість, з якою продовжується лінія попередньої порції, або `None`.
+    /// - `strokes`: Шляхи з частками максимальної швидкості для кожної точки.
+    /// - `ahead`: Перші шляхи наступної порції; порожній зріз, якщо порція остання.
+    ///
+    /// # Повертає:
+    /// - `Result<(Job, Option<f64>), PlanError>`: План порції й швидкість, з якою її
+    ///   остання лінія продовжується в `ahead`, або `None`, якщо перо піднімається.
+    #[tracing::instrument(
+        target = "rsaxi::trace",
+        name = "planning",
+        level = "trace",
+        skip_all,
+        fields(name = "job", detail = %format_args!("{} strokes", strokes.len()))
+    )]
+    pub fn plan_batch(
+        &self,
+        start: Point<f64>,
+        entry: Option<f64>,
+        strokes: &[Stroke],
+        ahead: &[Stroke],
+    ) -> Result<(Job, Option<f64>), PlanError> {
+        let mut actions = vec![];
+        let mut position = start;
+        let mut slowed = vec![];
+        let mut exit = None;
+
+        let mut chains = Self::chains(strokes);
+        // Довжина останньої лінії, після якої план обривається в шляхах `ahead`
+        let split = match (chains.last_mut(), ahead.first()) {
+            (Some(chain), Some(next)) if Self::continues(chain, next) => {
+                let length = chain
+                    .points
+                    .windows(2)
+                    .map(|pair| pair[0].distance(&pair[1]))
+                    .sum::<f64>();
+                for stroke in ahead {
+                    if !Self::continues(chain, stroke) {
+                        break;
+                    }
+                    Self::extend(chain, stroke);
+                }
+                Some(length)
+            }
+            _ => None,
+        };
+        let count = chains.len();
+        for (line, chain) in chains.into_iter().enumerate() {
+            let start = chain.points[0];
+            let continued = match entry {
+                Some(velocity) if line == 0 && position.distance(&start) <= CHAIN_TOLERANCE => {
+                    Some(velocity)
+                }
+                _ => None,
+            };
+            if continued.is_none() {
+                if position.distance(&start) > CHAIN_TOLERANCE {
+                    let travel = self.travel.as_ref().unwrap_or(&self.planner);
+                    actions.push(PlanAction::Move(travel.plan(vec![position, start])?));
+                }
+                actions.push(PlanAction::PenDown);
+            }
+
+            let max_velocity = self.planner.max_velocity();
+            let vmaxs = chain
+                .speeds
+                .iter()
+                .map(|speed| speed * max_velocity)
+                .collect();
+            position = *chain.points.last().unwrap();
+            let ends = (continued.unwrap_or(0.0), 0.0);
+            let (mut plan, clamped) = self.planner.plan_clamped_with(chain.points, vmaxs, ends)?;
+            if !clamped.is_empty() {
+                slowed.push(format!("{} ({})", line + 1, clamped.len()));
+            }
+            if let (Some(length), true) = (split, line + 1 == count) {
+                plan.truncate(length);
+                exit = Some(plan.exit_velocity());
+                actions.push(PlanAction::Move(plan));
+                break;
+            }
+            actions.push(PlanAction::Move(plan));
+            if let Some(dwell) = chain.dwell {
+                actions.push(PlanAction::PenDwell(dwell));
+            }
+            actions.push(PlanAction::PenUp);
+        }
+
+        if !slowed.is_empty() {
+            let more = slowed.len().saturating_sub(LISTED_LINES);
+            slowed.truncate(LISTED_LINES);
+            if more > 0 {
+                slowed.push(tr!("… {} more", "… ще {}", more));
+            }
+            warn!(
+                "{}",
+                tr!(
+                    "Segments that would exceed {} steps/s on a motor are slowed down on lines (segments): {}",
+                    "Відрізки, на яких мотор перевищив би {} кроків/с, сповільнено на лініях (відрізків): {}",
+                    MAX_STEP_RATE,
+                    slowed.join(", ")
+                )
+            );
+        }
+
+        Ok((Job { actions }, exit))
+    }
+
+    /// Чи продовжує шлях `stroke` лінію `chain` без підйому пера.
+    fn continues(chain: &Stroke, stroke: &Stroke) -> bool {
+        // Після паузи перо піднімається, тож шлях з паузою завершує ланцюжок
+        chain.dwell.is_none()
+            && !stroke.points.is_empty()
+            && chain.points.last().unwrap().distance(&stroke.points[0]) <= CHAIN_TOLERANCE
+    }
+
+    /// Дописує шлях `stroke` у кінець лінії `chain`.
+    fn extend(chain: &mut Stroke, stroke: &Stroke) {
+        // Швидкість у точці стику задає відрізок, що з неї починається
+        chain.speeds.pop();
+        chain.points.pop();
+        chain.points.extend(&stroke.points);
+        chain.speeds.extend(&stroke.speeds);
+        chain.dwell = stroke.dwell;
+    }
+
+    /// Зливає шляхи, кожен з яких починається там, де закінчується попередній.
+    fn chains(strokes: &[Stroke]) -> Vec<Stroke> {
+        let mut chains: Vec<Stroke> = vec![];
+        for stroke in strokes.iter().filter(|stroke| !stroke.points.is_empty()) {
+            match chains.last_mut() {
+                Some(chain) if Self::continues(chain, stroke) => Self::extend(chain, stroke),
+                _ => chains.push(stroke.clone()),
+            }
+        }
+        chains
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::LineString;
+
+    #[test]
+    fn test_job_chains_touching_strokes() {
+        let strokes = vec![
+            Stroke::plain(&LineString::from(vec![(10.0, 0.0), (20.0, 0.0)])),
+            Stroke::plain(&LineString::from(vec![(20.0, 0.0), (20.0, 10.0)])),
+            Stroke::plain(&LineString::from(vec![(40.0, 10.0), (40.0, 30.0)])),
+        ];
+        let job = JobPlanner::new(Planner::new(20.0, 16.0, 0.001))
+            .plan(&strokes)
+            .expect("Малюнок має плануватися");
+
+        let kinds: Vec<&str> = job
+            .actions
+            .iter()
+            .map(|action| match action {
+                PlanAction::Move(_) => "move",
+                PlanAction::PenUp => "up",
+                PlanAction::PenDown => "down",
+                PlanAction::PenDwell(_) => "dwell",
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            ["move", "down", "move", "up", "move", "down", "move", "up"],
+            "Шляхи, що торкаються, малюються одним рухом"
+        );
+        assert_eq!(job.pen_downs(), 2);
+        assert!((job.total_distance() - 70.0).abs() < 1e-6);
+
+        // Злитий план не зупиняється на стику шляхів
+        let PlanAction::Move(plan) = &job.actions[2] else {
+            panic!("Очікувався рух");
+        };
+        let corner = plan
+            .instant_at_distance(10.0)
+            .expect("Стик шляхів має бути в плані");
+        assert!(corner.velocity > 0.0, "Каретка не має зупинятися на стику");
+        assert!(job.total_time() > 0.0);
+
+        // Переїзди розганяються різкіше, а лінії лишаються такими самими
+        let harsh = JobPlanner::new(Planner::new(20.0, 16.0, 0.001))
+            .with_travel(Planner::new(20.0, 160.0, 0.001))
+            .plan(&strokes)
+            .expect("Малюнок має плануватися");
+        let time = |job: &Job, index: usize| match &job.actions[index] {
+            PlanAction::Move(plan) => plan.total_time,
+            _ => panic!("Очікувався рух"),
+        };
+        assert!(time(&harsh, 0) < time(&job, 0), "Переїзд швидший");
+        assert!(time(&harsh, 4) < time(&job, 4), "Переїзд швидший");
+        assert_eq!(time(&harsh, 2), time(&job, 2), "Лінія не змінилася");
+    }
+
+    #[test]
+    fn test_dwell_ends_chain_before_pen_up() {
+        let line = |points: Vec<(f64, f64)>| Stroke::plain(&LineString::from(points));
+        let mut dot = line(vec![(20.0, 0.0)]);
+        dot.dwell = Some(Duration::from_millis(1500));
+        let strokes = vec![line(vec![(10.0, 0.0), (20.0, 0.0)]), dot, {
+            let mut after = line(vec![(20.0, 0.0), (30.0, 0.0)]);
+            after.dwell = Some(Duration::from_millis(250));
+            after
+        }];
+        let job = JobPlanner::new(Planner::new(20.0, 16.0, 0.001))
+            .plan(&strokes)
+            .expect("Малюнок має плануватися");
+
+        let dwells: Vec<usize> = job
+            .actions
+            .iter()
+            .enumerate()
+            .filter(|(_, action)| matches!(action, PlanAction::PenDwell(_)))
+            .map(|(index, _)| index)
+            .collect();
+        assert_eq!(job.pen_downs(), 2, "Після паузи перо піднімається");
+        assert_eq!(dwells.len(), 2);
+        for index in dwells {
+            assert!(matches!(job.actions[index + 1], PlanAction::PenUp));
+        }
+        let motion: f64 = job.moves().map(|plan| plan.total_time).sum();
+        assert!((job.total_time() - motion - 1.75).abs() < 1e-9);
+
+        let json = serde_json::to_value(&job.actions).unwrap();
+        assert!(json
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|action| action["action"] == "pen_dwell" && action["seconds"] == 1.5));
+    }
+
+    #[test]
+    fn test_batch_continues_line_at_its_velocity() {
+        let line = |points: Vec<(f64, f64)>| Stroke::plain(&LineString::from(points));
+        let planner = JobPlanner::new(Planner::new(20.0, 16.0, 0.001));
+        let (first, second, third) = (
+            line(vec![(10.0, 0.0), (40.0, 0.0)]),
+            line(vec![(40.0, 0.0), (70.0, 0.0)]),
+            line(vec![(70.0, 0.0), (100.0, 0.0)]),
+        );
+        let whole = planner
+            .plan(&[first.clone(), second.clone(), third.clone()])
+            .expect("Малюнок має плануватися");
+
+        let (head, exit) = planner
+            .plan_batch(
+                Point::new(0.0, 0.0),
+                None,
+                std::slice::from_ref(&first),
+                std::slice::from_ref(&second),
+            )
+            .expect("Порція має плануватися");
+        let exit = exit.expect("Лінія продовжується в наступній порції");
+        assert!(matches!(head.actions.last(), Some(PlanAction::Move(_))));
+        assert_eq!(head.pen_downs(), 1);
+        let (tail, end) = planner
+            .plan_batch(Point::new(40.0, 0.0), Some(exit), &[second, third], &[])
+            .expect("Порція має плануватися");
+        assert_eq!(end, None);
+        assert_eq!(tail.pen_downs(), 0, "Перо вже опущене");
+        let PlanAction::Move(plan) = &tail.actions[0] else {
+            panic!("Порція починається з руху лінії");
+        };
+        assert!(exit > 0.0, "Каретка не зупиняється на межі порцій");
+        assert!((plan.instant(0.0).unwrap().velocity - exit).abs() < 1e-9);
+        assert!(matches!(tail.actions.last(), Some(PlanAction::PenUp)));
+
+        // Зшитий рух триває не довше, ніж злитий в одну лінію, бо `next` лише обмежує
+        // швидкість на стику
+        let time = head.total_time() + tail.total_time();
+        assert!(time >= whole.total_time() - 1e-9);
+        assert!(
+            (head.total_distance() + tail.total_distance() - whole.total_distance()).abs() < 1e-9
+        );
+    }
+}
@@ -0,0 +1,72 @@
+/// Переводить зміщення каретки по X і Y у зміщення моторів 1 і 2.
+///
+/// AxiDraw має кінематику CoreXY: рух каретки по X обертає обидва мотори в один бік,
+/// а по Y — у протилежні. Так само зміщення розкладає команда `XM` EBB, а `QS`
+/// повертає позиції саме моторів.
+///
+/// # Параметри
+/// - `x`, `y`: Зміщення каретки (в мм або кроках).
+///
+/// # Повертає
+/// - `(f64, f64)`: Зміщення моторів 1 і 2 у тих самих одиницях.
+pub fn xy_to_motors(x: f64, y: f64) -> (f64, f64) {
+    (x + y, x - y)
+}
+
+/// Переводить позиції моторів 1 і 2 у позицію каретки по X і Y.
+///
+/// # Параметри
+/// - `motor1`, `motor2`: Позиції моторів (в мм або кроках).
+///
+/// # Повертає
+/// - `(f64, f64)`: Позиція каретки (x, y) у тих самих одиницях.
+pub fn motors_to_xy(motor1: f64, motor2: f64) -> (f64, f64) {
+    ((motor1 + motor2) / 2.0, (motor1 - motor2) / 2.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        // Детермінований генератор, щоб перевірити перетворення на тисячах точок з усіх квадрантів
+        let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            (seed % 2_000_001) as f64 / 100.0 - 10_000.0
+        };
+
+        for _ in 0..10_000 {
+            let (x, y) = (next(), next());
+            let (motor1, motor2) = xy_to_motors(x, y);
+            let (x2, y2) = motors_to_xy(motor1, motor2);
+            assert!(
+                (x - x2).abs() < 1e-9 && (y - y2).abs() < 1e-9,
+                "Перетворення ({}, {}) не повертається до себе: ({}, {})",
+                x,
+                y,
+                x2,
+                y2
+            );
+
+            // Цілі кроки моторів лишаються цілими після розкладання
+            let (m1, m2) = (x.round(), y.round());
+            let (sx, sy) = motors_to_xy(m1 + m2, m1 - m2);
+            assert_eq!((sx, sy), (m1, m2));
+        }
+
+        assert_eq!(
+            xy_to_motors(1.0, 0.0),
+            (1.0, 1.0),
+            "Рух по X крутить мотори разом"
+        );
+        assert_eq!(
+            xy_to_motors(0.0, 1.0),
+            (1.0, -1.0),
+            "Рух по Y крутить мотори назустріч"
+        );
+    }
+}
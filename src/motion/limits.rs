@@ -0,0 +1,179 @@
+use std::fmt;
+
+use geo::Point;
+
+use super::emitter::TICK_RATE;
+use super::plan::Plan;
+use crate::tr;
+
+/// Найбільша частота кроків одного мотора, яку приймає EBB (кроків/с).
+pub const MAX_STEP_RATE: f64 = 25_000.0;
+
+/// Найбільше прискорення мотора (кроків/с²), що ще вміщується в параметр прискорення `LM`.
+pub const MAX_STEP_ACCELERATION: f64 =
+    i32::MAX as f64 * TICK_RATE * TICK_RATE / (1u64 << 31) as f64;
+
+/// Відносний запас, у межах якого округлення не вважається перевищенням.
+const TOLERANCE: f64 = 1e-9;
+
+/// У скільки разів на відрізку найшвидший мотор обертається швидше, ніж рухається каретка.
+///
+/// На CoreXY кожен мотор проходить суму або різницю зміщень по X і Y, тож на діагоналі
+/// один з моторів обертається в √2 рази швидше, ніж на відрізку вздовж осі.
+///
+/// # Параметри
+/// - `p1`, `p2`: Кінці відрізка.
+///
+/// # Повертає
+/// - `f64`: Множник від 1 до √2; 1 для відрізка нульової довжини.
+pub fn motor_factor(p1: Point<f64>, p2: Point<f64>) -> f64 {
+    let (dx, dy) = (p2.x() - p1.x(), p2.y() - p1.y());
+    let length = dx.hypot(dy);
+    if length == 0.0 {
+        return 1.0;
+    }
+    (dx + dy).abs().max((dx - dy).abs()) / length
+}
+
+/// Найбільша швидкість каретки на відрізку, за якої жоден мотор не перевищує `MAX_STEP_RATE`.
+///
+/// # Параметри
+/// - `p1`, `p2`: Кінці відрізка.
+/// - `steps_per_unit`: Кроки мотора на міліметр.
+pub fn segment_velocity_limit(p1: Point<f64>, p2: Point<f64>, steps_per_unit: f64) -> f64 {
+    MAX_STEP_RATE / (steps_per_unit * motor_factor(p1, p2))
+}
+
+/// Обмежує швидкість кожного відрізка шляху так, щоб мотори не перевищували `MAX_STEP_RATE`.
+///
+/// # Параметри
+/// - `points`: Точки шляху.
+/// - `vmaxs`: Найбільша швидкість відрізка, що починається в кожній точці, або порожній
+///   вектор для `max_velocity`; вектор іншої довжини повертається без змін.
+/// - `max_velocity`: Найбільша швидкість шляху.
+/// - `steps_per_unit`: Кроки мотора на міліметр.
+///
+/// # Повертає
+/// - `(Vec<f64>, Vec<usize>)`: Швидкості відрізків і номери відрізків, які довелося сповільнити.
+pub fn clamp_step_rates(
+    points: &[Point<f64>],
+    vmaxs: Vec<f64>,
+    max_velocity: f64,
+    steps_per_unit: f64,
+) -> (Vec<f64>, Vec<usize>) {
+    let mut vmaxs = match vmaxs.is_empty() {
+        true => vec![max_velocity; points.len()],
+        false if vmaxs.len() == points.len() => vmaxs,
+        false => return (vmaxs, vec![]),
+    };
+    let mut clamped = vec![];
+    for (index, pair) in points.windows(2).enumerate() {
+        let limit = segment_velocity_limit(pair[0], pair[1], steps_per_unit);
+        if limit < vmaxs[index] * (1.0 - TOLERANCE) {
+            vmaxs[index] = limit;
+            clamped.push(index);
+        }
+    }
+    (vmaxs, clamped)
+}
+
+/// Блок плану, на якому мотор вийшов би за межі, що приймає EBB.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateViolation {
+    pub block: usize,           // Номер блоку в плані.
+    pub start: Point<f64>,      // Початок блоку (в мм).
+    pub end: Point<f64>,        // Кінець блоку (в мм).
+    pub step_rate: f64,         // Найбільша частота кроків мотора в блоці (кроків/с).
+    pub step_acceleration: f64, // Прискорення найшвидшого мотора (кроків/с²).
+}
+
+impl fmt::Display for RateViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            tr!(
+                "block {} ({:.2}, {:.2})–({:.2}, {:.2}): {:.0} steps/s, {:.0} steps/s²",
+                "блок {} ({:.2}, {:.2})–({:.2}, {:.2}): {:.0} кроків/с, {:.0} кроків/с²",
+                self.block,
+                self.start.x(),
+                self.start.y(),
+                self.end.x(),
+                self.end.y(),
+                self.step_rate,
+                self.step_acceleration
+            )
+        )
+    }
+}
+
+impl Plan {
+    /// Знаходить блоки, на яких частота кроків чи прискорення мотора виходять за межі EBB.
+    ///
+    /// # Параметри:
+    /// - `steps_per_unit`: Кроки мотора на міліметр.
+    ///
+    /// # Повертає:
+    /// - `Vec<RateViolation>`: Блоки понад `MAX_STEP_RATE` або `MAX_STEP_ACCELERATION`.
+    pub fn rate_violations(&self, steps_per_unit: f64) -> Vec<RateViolation> {
+        self.blocks
+            .iter()
+            .enumerate()
+            .filter_map(|(index, block)| {
+                let factor = motor_factor(block.p1, block.p2) * steps_per_unit;
+                let final_velocity = block.initial_velocity + block.acceleration * block.duration;
+                let violation = RateViolation {
+                    block: index,
+                    start: block.p1,
+                    end: block.p2,
+                    step_rate: block.initial_velocity.max(final_velocity) * factor,
+                    step_acceleration: block.acceleration.abs() * factor,
+                };
+                let exceeds = violation.step_rate > MAX_STEP_RATE * (1.0 + TOLERANCE)
+                    || violation.step_acceleration > MAX_STEP_ACCELERATION;
+                exceeds.then_some(violation)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::motion::planner::Planner;
+
+    #[test]
+    fn test_step_rates_stay_within_ebb_range() {
+        let steps_per_unit = 100.0;
+        let diagonal = vec![Point::new(0.0, 0.0), Point::new(100.0, 100.0)];
+        let straight = vec![Point::new(0.0, 0.0), Point::new(100.0, 0.0)];
+        assert!((motor_factor(diagonal[0], diagonal[1]) - 2f64.sqrt()).abs() < 1e-12);
+        assert_eq!(motor_factor(straight[0], straight[1]), 1.0);
+
+        // 240 мм/с вздовж осі — це 24000 кроків/с, а по діагоналі вже майже 34000
+        let plan = |points: Vec<Point<f64>>, vmaxs: Vec<f64>| {
+            Planner::new(240.0, 1000.0, 0.001)
+                .plan_with_limits(points, vmaxs)
+                .unwrap()
+        };
+        assert!(plan(straight.clone(), vec![])
+            .rate_violations(steps_per_unit)
+            .is_empty());
+        let violations = plan(diagonal.clone(), vec![]).rate_violations(steps_per_unit);
+        assert!(!violations.is_empty());
+        assert!(violations
+            .iter()
+            .all(|violation| violation.step_rate > MAX_STEP_RATE));
+
+        // Сповільнений діагональний відрізок проходить перевірку, а прямий не змінюється
+        let (vmaxs, clamped) = clamp_step_rates(&diagonal, vec![], 240.0, steps_per_unit);
+        assert_eq!(clamped, [0]);
+        assert!((vmaxs[0] - 250.0 / 2f64.sqrt()).abs() < 1e-9);
+        assert!(plan(diagonal, vmaxs)
+            .rate_violations(steps_per_unit)
+            .is_empty());
+        let (vmaxs, clamped) = clamp_step_rates(&straight, vec![], 240.0, steps_per_unit);
+        assert!(clamped.is_empty());
+        assert_eq!(vmaxs, [240.0, 240.0]);
+    }
+}
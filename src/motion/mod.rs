@@ -1,9 +1,14 @@
 pub mod block;
+pub mod emitter;
 pub mod error;
 pub mod instant;
+pub mod job;
+pub mod kinematics;
+pub mod limits;
 pub mod plan;
 pub mod planner;
 pub mod point;
+pub mod preview;
 pub mod segment;
 pub mod trapezoid;
 pub mod triangle;
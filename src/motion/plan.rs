@@ -3,13 +3,16 @@ use super::{
     triangle::Triangle,
 };
 use crate::motion::util::corner_velocity;
+use crate::tr;
 use geo::Point;
-use std::{f64::EPSILON, fmt}; // Додаємо обидва рівні логування
+use serde::Serialize;
+use std::fmt; // Додаємо обидва рівні логування
 
 /// Структура `Plan` представляє план руху, що складається з кількох сегментів (блоків).
 /// Кожен блок містить інформацію про час та пройдену відстань на цьому етапі.
 /// Вся траєкторія розбивається на сегменти, кожен з яких описує прискорення,
 /// максимальну швидкість та інші параметри руху.
+#[derive(Serialize)]
 pub struct Plan {
     pub blocks: Vec<Block>,  // Масив блоків руху, що складають план
     pub total_time: f64,     // Загальний час руху
@@ -32,6 +35,7 @@ impl Plan {
     ///
     /// # Повертає:
     /// - `Result<Self, PlanError>`: Успішно створений план або помилка `PlanError`.
+    #[deprecated(note = "плани будує `Planner::plan` або `Planner::plan_with`")]
     pub fn new(
         points: Vec<Point>,
         vs: Vec<f64>,
@@ -40,100 +44,138 @@ impl Plan {
         vmax: f64,
         cf: f64,
     ) -> Result<Self, PlanError> {
-        let eps = EPSILON;
+        let exit = vs.last().copied().unwrap_or(0.0);
+        Self::build(points, vs, vmaxs, a, vmax, cf, (0.0, exit))
+    }
+
+    /// Будує план, як `new`, але рух починається й закінчується на заданих швидкостях.
+    ///
+    /// Швидкості на краях — лише верхні межі: їх обмежують `vmaxs` крайніх відрізків, а
+    /// закороткий шлях не дає розігнатися чи загальмувати до них.
+    ///
+    /// # Аргументи:
+    /// - `ends`: Швидкість у першій і в останній точці шляху.
+    pub(super) fn build(
+        points: Vec<Point>,
+        vs: Vec<f64>,
+        vmaxs: Vec<f64>,
+        a: f64,
+        vmax: f64,
+        cf: f64,
+        ends: (f64, f64),
+    ) -> Result<Self, PlanError> {
+        let (entry, exit) = ends;
+        let eps = f64::EPSILON;
 
-        // Create segments for each consecutive pair of points
-        let mut segments: Vec<Segment> = vec![];
-        for i in 1..points.len() {
-            segments.push(Segment::new(points[i - 1], points[i]));
+        if points.is_empty() {
+            return Err(PlanError::InsufficientPoints);
+        }
+        if let Some((index, point)) = points
+            .iter()
+            .enumerate()
+            .find(|(_, point)| !point.x().is_finite() || !point.y().is_finite())
+        {
+            return Err(PlanError::NonFinitePoint {
+                index,
+                x: point.x(),
+                y: point.y(),
+            });
+        }
+        if !vmaxs.is_empty() && vmaxs.len() != points.len()
+            || !vs.is_empty() && vs.len() != points.len()
+        {
+            return Err(PlanError::VelocityMismatch);
         }
 
-        // Add a dummy segment at the end for setting final velocity
-        let last_point = points[points.len() - 1];
-        segments.push(Segment::new(last_point, last_point));
+        // Нульове прискорення чи швидкість дають нескінченний час руху, а від'ємні
+        // значення ламають обмеження швидкості в проходах, тож відхиляємо їх заздалегідь
+        let positive = |value: f64| value.is_finite() && value > 0.0;
+        let limits = [("a", a), ("vmax", vmax)]
+            .into_iter()
+            .chain(vmaxs.iter().map(|&value| ("vmaxs", value)));
+        for (name, value) in limits {
+            if !positive(value) {
+                return Err(PlanError::InvalidParameter { name, value });
+            }
+        }
+        let non_negative = |value: f64| value.is_finite() && value >= 0.0;
+        let factors = [("cf", cf), ("entry_v", entry), ("exit_v", exit)]
+            .into_iter()
+            .chain(vs.iter().map(|&value| ("vs", value)));
+        for (name, value) in factors {
+            if !non_negative(value) {
+                return Err(PlanError::InvalidParameter { name, value });
+            }
+        }
 
         // Optional per-segment vmax
         let vmaxs = if vmaxs.is_empty() {
             vec![vmax; points.len()]
-        } else if vmaxs.len() != points.len() {
-            panic!("vmaxs array must be empty or same length as points array");
         } else {
             vmaxs
         };
 
-        // Set max_entry_velocity based on the input velocities or angles between segments
-        if vs.is_empty() {
-            for i in 1..segments.len() - 1 {
-                let (s1_slice, s2_slice) = segments.split_at_mut(i);
-                let s1 = &s1_slice[i - 1];
-                let s2 = &mut s2_slice[0];
-                s2.max_entry_velocity = corner_velocity(s1, s2, vmaxs[i], a, cf);
-            }
-        } else if vs.len() == points.len() {
-            for (i, &v) in vs.iter().enumerate() {
-                segments[i].max_entry_velocity = vmaxs[i].min(v);
-            }
-        } else {
-            panic!("vs array must be empty or same length as points array");
+        // Повтори точок, яких багато в реальних SVG, дають сегменти нульової довжини без
+        // напрямку, тож однакові сусідні точки зливаються ще до побудови сегментів
+        let (points, vs, vmaxs) = merge_duplicates(points, vs, vmaxs);
+
+        // Сегменти між сусідніми точками; сегмент `i` починається в точці `i`
+        let mut segments: Vec<Segment> = points
+            .windows(2)
+            .map(|pair| Segment::new(pair[0], pair[1]))
+            .collect();
+
+        // Найбільша швидкість у кожній внутрішній точці: її обмежує кут між сегментами
+        // або задані `vs`, а також межі швидкості обох сегментів, що в ній сходяться
+        for i in 1..segments.len() {
+            let limit = if vs.is_empty() {
+                corner_velocity(&segments[i - 1], &segments[i], vmaxs[i], a, cf)
+            } else {
+                vmaxs[i].min(vs[i])
+            };
+            segments[i].max_entry_velocity = limit.min(vmaxs[i - 1]);
         }
 
-        // Loop over segments
-        let mut i: usize = 0;
-        while i < segments.len() - 1 {
-            let (current_segments, next_segments) = segments.split_at_mut(i + 1);
-            let segment = &mut current_segments[i];
-            let next_segment = &mut next_segments[0];
-            let s = segment.length;
-            let vi = segment.entry_velocity;
-            let vmax = vmaxs[i];
-            let vexit = vmax.min(next_segment.max_entry_velocity);
-            let p1 = segment.p1;
-            let p2 = segment.p2;
-
-            let mut blocks: Vec<Block> = vec![];
-
-            // Determine which profile to use for this segment
-            let m = Triangle::triangular_profile(s, vi, vexit, a, p1, p2);
-            if m.s1 < -eps {
-                // Too fast! Update max_entry_velocity and backtrack
-                segment.max_entry_velocity = (vexit * vexit + 2.0 * a * s).sqrt();
-                if i > 0 {
-                    i -= 1;
-                }
-            } else if m.s2 < 0.0 {
-                // Accelerate
-                let vf = (vi * vi + 2.0 * a * s).sqrt();
-                let t = (vf - vi) / a;
-                blocks.push(Block::new(a, t, vi, p1, p2));
-                next_segment.entry_velocity = vf;
-                i += 1;
-            } else if m.vmax > vmax {
-                // Accelerate, cruise, decelerate
-                let z = Trapezoid::trapezoidal_profile(s, vi, vmax, vexit, a, p1, p2);
-                blocks.push(Block::new(a, z.t1, vi, z.p1, z.p2));
-                blocks.push(Block::new(0.0, z.t2, vmax, z.p2, z.p3));
-                blocks.push(Block::new(-a, z.t3, vmax, z.p3, z.p4));
-                next_segment.entry_velocity = vexit;
-                i += 1;
-            } else {
-                // Accelerate, decelerate
-                blocks.push(Block::new(a, m.t1, vi, m.p1, m.p2));
-                blocks.push(Block::new(-a, m.t2, m.vmax, m.p2, m.p3));
-                next_segment.entry_velocity = vexit;
-                i += 1;
-            }
-            segment.blocks = blocks;
+        // Рух починається й закінчується на швидкостях `ends`, яких не можна перевищити
+        // на крайніх відрізках і в крайніх точках, якщо для них задано `vs`
+        let (entry, exit) = match (vs.first(), vs.last()) {
+            (Some(&first), Some(&last)) => (entry.min(first), exit.min(last)),
+            _ => (entry, exit),
+        };
+        let last = points.len() - 1;
+        let final_velocity = match last {
+            0 => 0.0,
+            _ => exit.min(vmaxs[last]).min(vmaxs[last - 1]),
+        };
+        let mut velocities: Vec<f64> = segments
+            .iter()
+            .map(|segment| segment.max_entry_velocity)
+            .chain([final_velocity])
+            .collect();
+        velocities[0] = match last {
+            0 => 0.0,
+            _ => entry.min(vmaxs[0]),
+        };
+
+        // Зворотний прохід: з кожної точки має вистачити шляху, щоб загальмувати до наступної
+        for (i, segment) in segments.iter().enumerate().rev() {
+            let reachable = (velocities[i + 1].powi(2) + 2.0 * a * segment.length).sqrt();
+            velocities[i] = velocities[i].min(reachable);
+        }
+        // Прямий прохід: у точці не можна бути швидшим, ніж дозволяє розгін від попередньої
+        for (i, segment) in segments.iter().enumerate() {
+            let reachable = (velocities[i].powi(2) + 2.0 * a * segment.length).sqrt();
+            velocities[i + 1] = velocities[i + 1].min(reachable);
         }
 
         // Concatenate all blocks
-        let mut all_blocks: Vec<Block> = vec![];
-        for segment in &segments {
-            for block in &segment.blocks {
-                if block.duration > eps {
-                    all_blocks.push(block.clone());
-                }
-            }
-        }
+        let all_blocks: Vec<Block> = segments
+            .iter()
+            .zip(velocities.windows(2))
+            .zip(&vmaxs)
+            .flat_map(|((segment, v), &vmax)| Plan::profile(segment, v[0], v[1], vmax, a))
+            .filter(|block| block.duration > eps)
+            .collect();
 
         // Compute starting time and position for each block
         let mut ts = vec![0.0; all_blocks.len()];
@@ -156,6 +198,68 @@ impl Plan {
         })
     }
 
+    /// Обрізає план на пройденій відстані `s`, що збігається з точкою шляху.
+    ///
+    /// Блоки не діляться, тож обрізаний план закінчується не зупинкою, а швидкістю,
+    /// з якою рух проходить цю точку.
+    pub(super) fn truncate(&mut self, s: f64) {
+        let keep = self
+            .distances
+            .iter()
+            .take_while(|&&start| start < s - 1e-9)
+            .count();
+        self.blocks.truncate(keep);
+        self.times.truncate(keep);
+        self.distances.truncate(keep);
+        let ends = self
+            .blocks
+            .last()
+            .map(|block| (block.duration, block.distance));
+        let starts = (self.times.last(), self.distances.last());
+        (self.total_time, self.total_distance) = match (ends, starts) {
+            (Some((duration, distance)), (Some(t), Some(s))) => (t + duration, s + distance),
+            _ => (0.0, 0.0),
+        };
+    }
+
+    /// Швидкість руху в кінці плану.
+    pub fn exit_velocity(&self) -> f64 {
+        self.blocks.last().map_or(0.0, |block| {
+            (block.initial_velocity + block.acceleration * block.duration).max(0.0)
+        })
+    }
+
+    /// Блоки руху сегмента від швидкості `vi` на вході до `vf` на виході.
+    ///
+    /// Після проходів `new` обидві швидкості досяжні одна з одної на довжині сегмента, тож
+    /// профіль — це лише розгін, лише гальмування, розгін з гальмуванням або, якщо пік
+    /// перевищує `vmax`, трапеція з рухом на сталій швидкості.
+    fn profile(segment: &Segment, vi: f64, vf: f64, vmax: f64, a: f64) -> Vec<Block> {
+        let (s, p1, p2) = (segment.length, segment.p1, segment.p2);
+        let m = Triangle::triangular_profile(s, vi, vf, a, p1, p2);
+        if m.s1 <= 0.0 {
+            // Decelerate
+            vec![Block::new(-a, (vi - vf) / a, vi, p1, p2)]
+        } else if m.s2 <= 0.0 {
+            // Accelerate
+            vec![Block::new(a, (vf - vi) / a, vi, p1, p2)]
+        } else if m.vmax > vmax {
+            // Accelerate, cruise, decelerate
+            let z = Trapezoid::trapezoidal_profile(s, vi, vmax, vf, a, p1, p2);
+            vec![
+                Block::new(a, z.t1, vi, z.p1, z.p2),
+                Block::new(0.0, z.t2, vmax, z.p2, z.p3),
+                Block::new(-a, z.t3, vmax, z.p3, z.p4),
+            ]
+        } else {
+            // Accelerate, decelerate
+            vec![
+                Block::new(a, m.t1, vi, m.p1, m.p2),
+                Block::new(-a, m.t2, m.vmax, m.p2, m.p3),
+            ]
+        }
+    }
+
     /// Повертає стан руху в певний момент часу.
     ///
     /// # Параметри:
@@ -191,6 +295,29 @@ impl Plan {
         })
     }
 
+    /// Повертає стани руху з кроком `dt` від початку до кінця плану.
+    ///
+    /// На відміну від `instant`, що для кожного моменту шукає блок бінарним пошуком,
+    /// ітератор іде блоками по черзі за накопиченими `times`, тож кожен стан обчислюється
+    /// за сталий час. Крок накопичується додаванням, як і в циклі з `instant(t)`,
+    /// `instant(t + dt)`, тож стани збігаються з ним до біта.
+    ///
+    /// # Параметри:
+    /// - `dt`: Крок часу між станами (в секундах); недодатний крок дає лише початковий стан.
+    ///
+    /// # Повертає:
+    /// - `Samples`: Стани в моменти `0`, `dt`, `2·dt`, … і останній у `total_time`;
+    ///   порожній для плану без блоків.
+    pub fn sample(&self, dt: f64) -> Samples<'_> {
+        Samples {
+            plan: self,
+            dt,
+            t: 0.0,
+            block: 0,
+            done: self.blocks.is_empty(),
+        }
+    }
+
     /// Повертає стан руху на певній пройденій відстані.
     ///
     /// # Параметри:
@@ -227,14 +354,70 @@ impl Plan {
     }
 }
 
+/// Ітератор станів руху з рівним кроком часу, створений `Plan::sample`.
+pub struct Samples<'a> {
+    plan: &'a Plan, // План, стани якого перебираються.
+    dt: f64,        // Крок часу між станами.
+    t: f64,         // Час наступного стану.
+    block: usize,   // Блок, у якому лежить наступний стан.
+    done: bool,     // Чи повернуто вже стан у кінці плану.
+}
+
+impl Iterator for Samples<'_> {
+    type Item = Instant;
+
+    fn next(&mut self) -> Option<Instant> {
+        if self.done {
+            return None;
+        }
+        let plan = self.plan;
+        let t = self.t.clamp(0.0, plan.total_time);
+
+        // Той самий блок, що знайшов би бінарний пошук: останній, що почався до `t`
+        while self.block + 1 < plan.blocks.len() && plan.times[self.block + 1] <= t {
+            self.block += 1;
+        }
+        let index = self.block;
+        let instant = plan.blocks[index].instant(
+            t - plan.times[index],
+            plan.times[index],
+            plan.distances[index],
+        );
+
+        self.done = self.t >= plan.total_time || self.dt <= 0.0 || self.dt.is_nan();
+        self.t += self.dt;
+        Some(instant)
+    }
+}
+
 impl fmt::Display for Plan {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "План:")?;
-        writeln!(f, "  Загальний час: {:.2} секунд", self.total_time)?;
-        writeln!(f, "  Загальна відстань: {:.2} мм", self.total_distance)?;
-        writeln!(f, "  Кількість блоків: {}", self.blocks.len())?;
-
-        for (_, block) in self.blocks.iter().enumerate() {
+        writeln!(f, "{}", tr!("Plan:", "План:"))?;
+        writeln!(
+            f,
+            "{}",
+            tr!(
+                "  Total time: {:.2} seconds",
+                "  Загальний час: {:.2} секунд",
+                self.total_time
+            )
+        )?;
+        writeln!(
+            f,
+            "{}",
+            tr!(
+                "  Total distance: {:.2} mm",
+                "  Загальна відстань: {:.2} мм",
+                self.total_distance
+            )
+        )?;
+        writeln!(
+            f,
+            "{}",
+            tr!("  Blocks: {}", "  Кількість блоків: {}", self.blocks.len())
+        )?;
+
+        for block in self.blocks.iter() {
             writeln!(f, "{}", block)?;
         }
 
@@ -242,17 +425,52 @@ impl fmt::Display for Plan {
     }
 }
 
+/// Зливає сусідні точки з однаковими координатами в одну.
+///
+/// Злита точка отримує найменшу з `vs` своїх повторів і межу `vmaxs` останнього з них,
+/// бо саме з нього починається наступний відрізок шляху.
+fn merge_duplicates(
+    points: Vec<Point>,
+    vs: Vec<f64>,
+    vmaxs: Vec<f64>,
+) -> (Vec<Point>, Vec<f64>, Vec<f64>) {
+    let mut merged = (
+        Vec::with_capacity(points.len()),
+        Vec::with_capacity(vs.len()),
+        Vec::with_capacity(vmaxs.len()),
+    );
+    for (i, point) in points.into_iter().enumerate() {
+        if merged.0.last() == Some(&point) {
+            if let Some(v) = merged.1.last_mut() {
+                *v = vs[i].min(*v);
+            }
+            *merged.2.last_mut().expect("Межа є для кожної точки") = vmaxs[i];
+            continue;
+        }
+        merged.0.push(point);
+        if !vs.is_empty() {
+            merged.1.push(vs[i]);
+        }
+        merged.2.push(vmaxs[i]);
+    }
+    merged
+}
+
 #[cfg(test)]
+#[allow(deprecated)] // Перевіряємо сам конструктор `new`, яким користуються зовнішні програми
 mod tests {
     use super::*;
+    use crate::motion::point::PointExtension;
     use geo::Point;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
 
     #[test]
     fn test_plan_with_three_points_investigate_velocity() {
         // Ініціалізуємо логгер
-        let _ = env_logger::builder()
-            .is_test(true)
-            .filter_level(log::LevelFilter::Debug)
+        let _ = tracing_subscriber::fmt()
+            .with_test_writer()
+            .with_max_level(tracing::Level::DEBUG)
             .try_init();
 
         // Визначаємо три точки для плану
@@ -280,4 +498,320 @@ mod tests {
         let plan = plan_result.unwrap();
         println!("{}", plan);
     }
+
+    #[test]
+    fn test_sample_matches_instant() {
+        let points = vec![
+            Point::new(0.0, 0.0),
+            Point::new(30.0, 10.0),
+            Point::new(30.0, 40.0),
+            Point::new(0.0, 0.0),
+        ];
+        let plan = Plan::new(points, vec![], vec![], 200.0, 50.0, 0.01).unwrap();
+        let dt = 0.013;
+
+        let samples: Vec<Instant> = plan.sample(dt).collect();
+        let mut t = 0.0;
+        for (index, sample) in samples.iter().enumerate() {
+            let expected = plan.instant(t).unwrap();
+            assert_eq!(
+                sample.time_elapsed, expected.time_elapsed,
+                "Стан {} у момент {}",
+                index, t
+            );
+            assert_eq!(sample.distance_traveled, expected.distance_traveled);
+            assert_eq!(sample.position, expected.position);
+            assert_eq!(sample.velocity, expected.velocity);
+            t += dt;
+        }
+        assert_eq!(samples.len(), (plan.total_time / dt).ceil() as usize + 1);
+        assert_eq!(
+            samples.last().unwrap().time_elapsed,
+            plan.total_time,
+            "Останній стан у кінці"
+        );
+
+        let still = Plan::new(
+            vec![Point::new(1.0, 1.0)],
+            vec![],
+            vec![],
+            200.0,
+            50.0,
+            0.01,
+        );
+        assert_eq!(still.unwrap().sample(dt).count(), 0, "План без блоків");
+        assert_eq!(plan.sample(0.0).count(), 1);
+    }
+
+    #[test]
+    fn test_invalid_input_returns_errors() {
+        let points = vec![Point::new(0.0, 0.0), Point::new(10.0, 0.0)];
+        let plan = |points: Vec<Point>, vs: Vec<f64>, vmaxs: Vec<f64>, a: f64| {
+            Plan::new(points, vs, vmaxs, a, 100.0, 1.0).map(|plan| plan.total_time)
+        };
+
+        assert!(matches!(
+            plan(vec![], vec![], vec![], 20.0),
+            Err(PlanError::InsufficientPoints)
+        ));
+        assert!(matches!(
+            plan(points.clone(), vec![0.0], vec![], 20.0),
+            Err(PlanError::VelocityMismatch)
+        ));
+        assert!(matches!(
+            plan(points.clone(), vec![], vec![50.0; 3], 20.0),
+            Err(PlanError::VelocityMismatch)
+        ));
+        assert!(matches!(
+            plan(points.clone(), vec![], vec![], 0.0),
+            Err(PlanError::InvalidParameter { name: "a", .. })
+        ));
+        assert!(matches!(
+            plan(points.clone(), vec![], vec![50.0, -1.0], 20.0),
+            Err(PlanError::InvalidParameter { name: "vmaxs", .. })
+        ));
+        assert!(matches!(
+            plan(vec![Point::new(0.0, f64::NAN)], vec![], vec![], 20.0),
+            Err(PlanError::NonFinitePoint { index: 0, .. })
+        ));
+
+        // Одна точка — коректний план без руху
+        let total_time = plan(vec![Point::new(1.0, 1.0)], vec![], vec![], 20.0).unwrap();
+        assert_eq!(total_time, 0.0);
+        assert!(plan(points, vec![0.0, 0.0], vec![], 20.0).unwrap() > 0.0);
+    }
+
+    /// Крок шляху для перевірки неперервності: рідкісні повтори точок і крихітні кроки
+    /// перевіряють вироджені сегменти.
+    fn free_step() -> impl Strategy<Value = Point<f64>> {
+        let length = prop_oneof![1 => Just(0.0), 1 => Just(1e-6), 8 => 0.0..50.0];
+        (length, 0.0..std::f64::consts::TAU)
+            .prop_map(|(length, angle)| Point::new(length * angle.cos(), length * angle.sin()))
+    }
+
+    /// Крок шляху на цілій сітці: точні розвороти назад і повтори точок.
+    fn grid_step() -> impl Strategy<Value = Point<f64>> {
+        prop_oneof![
+            3 => Just(Point::new(0.0, 0.0)),
+            8 => (-20i32..=20).prop_map(|step| Point::new(step as f64, 0.0)),
+            9 => (-20i32..=20).prop_map(|step| Point::new(0.0, step as f64)),
+        ]
+    }
+
+    /// Шлях з кроків `step` від початку координат та, можливо, швидкості й межі швидкості
+    /// в кожній точці.
+    fn path(
+        counts: std::ops::RangeInclusive<usize>,
+        step: BoxedStrategy<Point<f64>>,
+    ) -> impl Strategy<Value = (Vec<Point<f64>>, Vec<f64>, Vec<f64>)> {
+        counts.prop_flat_map(move |count| {
+            (
+                vec(step.clone(), count - 1),
+                prop_oneof![Just(vec![]), vec(0.0..80.0, count)],
+                prop_oneof![Just(vec![]), vec(1.0..61.0, count)],
+            )
+                .prop_map(|(steps, vs, vmaxs)| {
+                    let points = std::iter::once(Point::new(0.0, 0.0))
+                        .chain(steps.into_iter().scan(Point::new(0.0, 0.0), |last, step| {
+                            *last += step;
+                            Some(*last)
+                        }))
+                        .collect();
+                    (points, vs, vmaxs)
+                })
+        })
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(300))]
+
+        #[test]
+        fn test_random_paths_keep_velocity_continuous(
+            (points, vs, vmaxs) in path(1..=12, free_step().boxed()),
+            vmax in 1.0..101.0,
+            a in 0.5..50.5,
+            cf in 0.0..2.0,
+        ) {
+            let length: f64 = points
+                .windows(2)
+                .map(|pair| pair[0].distance(&pair[1]))
+                .sum();
+            let limit = vmaxs.iter().copied().fold(vmax, f64::max);
+            let stops = vs.is_empty();
+
+            let plan = Plan::new(points, vs, vmaxs, a, vmax, cf)
+                .expect("Коректні дані мають давати план");
+            let tolerance = 1e-6 * (1.0 + limit);
+            prop_assert!(
+                (plan.total_distance - length).abs() < 1e-6 * (1.0 + length),
+                "План проходить увесь шлях"
+            );
+            let mut velocity = 0.0;
+            for block in &plan.blocks {
+                prop_assert!(block.duration >= 0.0, "Тривалість блоку від'ємна");
+                prop_assert!(
+                    (block.initial_velocity - velocity).abs() < tolerance,
+                    "Швидкість між блоками має бути неперервною: {} проти {}",
+                    velocity,
+                    block.initial_velocity
+                );
+                velocity = block.initial_velocity + block.acceleration * block.duration;
+                prop_assert!(velocity > -tolerance, "Швидкість від'ємна");
+                prop_assert!(velocity < limit + tolerance, "Швидкість перевищує межу");
+            }
+            if stops {
+                prop_assert!(velocity.abs() < tolerance, "Рух має закінчуватися зупинкою");
+            }
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(500))]
+
+        #[test]
+        fn test_random_plans_respect_physical_limits(
+            (points, vs, vmaxs) in path(2..=11, grid_step().boxed()),
+            a in 0.5..50.5,
+            vmax in 1.0..101.0,
+            cf in 0.0..2.0,
+        ) {
+            let count = points.len();
+            let plan = Plan::new(points.clone(), vs.clone(), vmaxs.clone(), a, vmax, cf)
+                .expect("Коректні дані мають давати план");
+
+            let limits = match vmaxs.is_empty() {
+                true => vec![vmax; count],
+                false => vmaxs,
+            };
+            let tolerance = 1e-6 * (1.0 + vmax.max(80.0));
+            // Відстань від початку до кожної точки шляху
+            let ends: Vec<f64> = std::iter::once(0.0)
+                .chain(points.windows(2).scan(0.0, |s, pair| {
+                    *s += pair[0].distance(&pair[1]);
+                    Some(*s)
+                }))
+                .collect();
+            let segment_at = |s: f64| ends[1..].partition_point(|&end| end <= s).min(count - 2);
+
+            for (index, block) in plan.blocks.iter().enumerate() {
+                prop_assert!(block.acceleration.abs() <= a, "Прискорення перевищує межу");
+                let exit = block.initial_velocity + block.acceleration * block.duration;
+                let segment = segment_at(plan.distances[index] + block.distance / 2.0);
+                prop_assert!(
+                    block.initial_velocity.max(exit) < limits[segment] + tolerance,
+                    "Швидкість {} на сегменті {} з межею {}",
+                    block.initial_velocity.max(exit),
+                    segment,
+                    limits[segment]
+                );
+            }
+
+            // Швидкість у точках: не більша за задану, а на розвороті назад — нуль. Точку
+            // знаходимо за відстанню, а похибка відстані біля зупинки дає швидкість √(2·a·Δs)
+            let length = ends[count - 1];
+            prop_assert!(
+                (plan.total_distance - length).abs() < 1e-9 * (1.0 + length),
+                "План проходить {} замість {}",
+                plan.total_distance,
+                length
+            );
+            let tolerance = tolerance + (2.0 * a * 1e-9 * (1.0 + length)).sqrt();
+            for k in 1..count {
+                let speed = plan
+                    .instant_at_distance(ends[k])
+                    .map_or(0.0, |i| i.velocity);
+                if !vs.is_empty() {
+                    prop_assert!(
+                        speed < vs[k] + tolerance,
+                        "Швидкість у точці {} вища за vs",
+                        k
+                    );
+                    continue;
+                }
+                let before = points[..k].iter().rev().find(|&&p| p != points[k]);
+                let after = points[k + 1..].iter().find(|&&p| p != points[k]);
+                if let (Some(&before), Some(&after)) = (before, after) {
+                    let incoming = (points[k] - before).normalize();
+                    let outgoing = (after - points[k]).normalize();
+                    if incoming.dot(outgoing) < -1.0 + 1e-9 {
+                        prop_assert!(
+                            speed < tolerance,
+                            "Розворот у точці {} на швидкості {}",
+                            k,
+                            speed
+                        );
+                    }
+                }
+            }
+
+            // Пройдена відстань не зменшується з часом
+            let mut previous = 0.0;
+            for step in 0..=200 {
+                let t = plan.total_time * step as f64 / 200.0;
+                let Some(instant) = plan.instant(t) else {
+                    break;
+                };
+                prop_assert!(
+                    instant.distance_traveled >= previous - 1e-9,
+                    "Відстань зменшилася з {} до {} у момент {}",
+                    previous,
+                    instant.distance_traveled,
+                    t
+                );
+                prop_assert!(instant.velocity > -tolerance);
+                prop_assert!(instant.distance_traveled <= plan.total_distance + 1e-9);
+                previous = instant.distance_traveled;
+            }
+        }
+    }
+
+    #[test]
+    fn test_reversal_with_rounded_direction_stops() {
+        // Нормалізація округлює напрямки цих сегментів, тож косинус кута лише близький до -1
+        let points = vec![
+            Point::new(7.984, 0.0),
+            Point::new(7.983986, 0.0),
+            Point::new(7.983993, 0.0),
+        ];
+        let plan = Plan::new(points, vec![], vec![], 15000.0, 0.1, 1.9).unwrap();
+        let speed = plan.instant_at_distance(1.4e-5).unwrap().velocity;
+        assert!(speed.abs() < 1e-3, "Розворот на швидкості {}", speed);
+    }
+
+    #[test]
+    fn test_degenerate_paths_give_finite_plans() {
+        let finite = |plan: &Plan| {
+            plan.total_time.is_finite()
+                && plan.blocks.iter().all(|block| {
+                    block.initial_velocity.is_finite() && block.acceleration.is_finite()
+                })
+        };
+
+        // Усі точки однакові: руху немає, але й NaN теж
+        let same = vec![Point::new(3.0, 4.0); 5];
+        let plan = Plan::new(same, vec![1.0; 5], vec![], 100.0, 10.0, 0.5).unwrap();
+        assert!(finite(&plan));
+        assert!(plan.blocks.is_empty());
+        assert_eq!(plan.total_time, 0.0);
+
+        // Шлях з однієї точки
+        let plan = Plan::new(vec![Point::new(1.0, 1.0)], vec![], vec![], 100.0, 10.0, 0.5).unwrap();
+        assert!(finite(&plan) && plan.blocks.is_empty());
+
+        // Повтори посеред шляху не змінюють його довжини й не зупиняють рух на прямій
+        let points = vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 0.0),
+            Point::new(10.0, 0.0),
+            Point::new(10.0, 0.0),
+            Point::new(10.0, 0.0),
+            Point::new(20.0, 0.0),
+            Point::new(20.0, 0.0),
+        ];
+        let plan = Plan::new(points, vec![], vec![], 100.0, 10.0, 0.5).unwrap();
+        assert!(finite(&plan));
+        assert!((plan.total_distance - 20.0).abs() < 1e-9);
+        let speed = plan.instant_at_distance(10.0).unwrap().velocity;
+        assert!((speed - 10.0).abs() < 1e-9, "Швидкість у повторі {}", speed);
+    }
 }
@@ -1,13 +1,17 @@
 use geo::Point;
 
-use super::{error::PlanError, plan::Plan};
+use super::{error::PlanError, limits::clamp_step_rates, plan::Plan};
 
 /// Структура `Planner` відповідає за планування руху для AxiDraw.
 /// Вона використовує профіль швидкості та контроль інструменту для обчислення шляхів руху.
+///
+/// Це єдиний спосіб побудувати `Plan` поза модулем руху.
+#[derive(Clone, Debug)]
 pub struct Planner {
     max_velocity: f64,  // Максимальна швидкість, яку може досягти під час руху.
     acceleration: f64,  // Максимальне прискорення, яке може бути застосоване під час руху.
     corner_factor: f64, // Фактор для корекції швидкості на поворотах.
+    steps_per_unit: Option<f64>, // Кроки мотора на міліметр; з ними відрізки сповільнюються до частоти кроків EBB.
 }
 
 impl Planner {
@@ -22,9 +26,23 @@ impl Planner {
             max_velocity,
             acceleration,
             corner_factor,
+            steps_per_unit: None,
         }
     }
 
+    /// Обмежує швидкість кожного відрізка частотою кроків EBB для моторів із `steps_per_unit`.
+    ///
+    /// На діагоналях CoreXY один мотор обертається в √2 рази швидше, ніж рухається
+    /// каретка, тож без цього обмеження він може перевищити 25 кГц навіть тоді, коли
+    /// `max_velocity` вміщується в межі вздовж осей.
+    ///
+    /// # Параметри:
+    /// - `steps_per_unit`: Кроки мотора на міліметр.
+    pub fn with_steps_per_unit(mut self, steps_per_unit: f64) -> Self {
+        self.steps_per_unit = Some(steps_per_unit);
+        self
+    }
+
     /// Створює новий план руху на основі наданих точок.
     ///
     /// # Параметри:
@@ -33,13 +51,146 @@ impl Planner {
     /// # Повертає:
     /// - `Result<Plan, PlanError>`: Результат плану руху або помилка.
     pub fn plan(&self, points: Vec<Point<f64>>) -> Result<Plan, PlanError> {
-        Plan::new(
+        self.plan_with_limits(points, vec![])
+    }
+
+    /// Створює план руху з налаштуваннями, зміненими лише для цього виклику.
+    ///
+    /// Швидкості на краях дозволяють зшивати план із сусідніми без зупинки; це верхні
+    /// межі, до яких рух може не встигнути розігнатися чи загальмувати на короткому шляху.
+    ///
+    /// # Параметри:
+    /// - `points`: Вектор точок `Point<f64>`, що визначає шлях руху.
+    /// - `vmax_override`: Максимальна швидкість замість `max_velocity` або `None`.
+    /// - `entry_v`: Швидкість у першій точці шляху.
+    /// - `exit_v`: Швидкість в останній точці шляху.
+    ///
+    /// # Повертає:
+    /// - `Result<Plan, PlanError>`: Результат плану руху або помилка.
+    pub fn plan_with(
+        &self,
+        points: Vec<Point<f64>>,
+        vmax_override: Option<f64>,
+        entry_v: f64,
+        exit_v: f64,
+    ) -> Result<Plan, PlanError> {
+        let vmax = vmax_override.unwrap_or(self.max_velocity);
+        self.build(points, vec![], vmax, (entry_v, exit_v))
+            .map(|(plan, _)| plan)
+    }
+
+    /// Створює план руху з обмеженням швидкості для кожного відрізка.
+    ///
+    /// # Параметри:
+    /// - `points`: Вектор точок `Point<f64>`, що визначає шлях руху.
+    /// - `vmaxs`: Максимальна швидкість для відрізка, що починається в кожній точці,
+    ///   або порожній вектор, щоб використовувати `max_velocity`.
+    ///
+    /// # Повертає:
+    /// - `Result<Plan, PlanError>`: Результат плану руху або помилка.
+    pub fn plan_with_limits(
+        &self,
+        points: Vec<Point<f64>>,
+        vmaxs: Vec<f64>,
+    ) -> Result<Plan, PlanError> {
+        self.plan_clamped(points, vmaxs).map(|(plan, _)| plan)
+    }
+
+    /// Створює план руху, як `plan_with_limits`, і повідомляє, які відрізки сповільнено.
+    ///
+    /// # Параметри:
+    /// - `points`: Вектор точок `Point<f64>`, що визначає шлях руху.
+    /// - `vmaxs`: Максимальна швидкість для відрізка, що починається в кожній точці,
+    ///   або порожній вектор, щоб використовувати `max_velocity`.
+    ///
+    /// # Повертає:
+    /// - `Result<(Plan, Vec<usize>), PlanError>`: План і номери відрізків, сповільнених до
+    ///   частоти кроків EBB; без `with_steps_per_unit` список порожній.
+    pub fn plan_clamped(
+        &self,
+        points: Vec<Point<f64>>,
+        vmaxs: Vec<f64>,
+    ) -> Result<(Plan, Vec<usize>), PlanError> {
+        self.build(points, vmaxs, self.max_velocity, (0.0, 0.0))
+    }
+
+    /// Створює план руху, як `plan_clamped`, але зі швидкостями `ends` на краях шляху.
+    pub(crate) fn plan_clamped_with(
+        &self,
+        points: Vec<Point<f64>>,
+        vmaxs: Vec<f64>,
+        ends: (f64, f64),
+    ) -> Result<(Plan, Vec<usize>), PlanError> {
+        self.build(points, vmaxs, self.max_velocity, ends)
+    }
+
+    /// Будує план зі швидкістю `vmax` і швидкостями `ends` на краях шляху, сповільнюючи
+    /// відрізки до частоти кроків EBB.
+    fn build(
+        &self,
+        points: Vec<Point<f64>>,
+        vmaxs: Vec<f64>,
+        vmax: f64,
+        ends: (f64, f64),
+    ) -> Result<(Plan, Vec<usize>), PlanError> {
+        let (vmaxs, clamped) = match self.steps_per_unit {
+            Some(steps_per_unit) => clamp_step_rates(&points, vmaxs, vmax, steps_per_unit),
+            None => (vmaxs, vec![]),
+        };
+        let plan = Plan::build(
             points,
             vec![],
-            vec![],
+            vmaxs,
             self.acceleration,
-            self.max_velocity,
+            vmax,
             self.corner_factor,
-        )
+            ends,
+        )?;
+        Ok((plan, clamped))
+    }
+
+    /// Повертає максимальну швидкість руху.
+    pub fn max_velocity(&self) -> f64 {
+        self.max_velocity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_with_overrides_speed_and_ends() {
+        let planner = Planner::new(50.0, 100.0, 0.001);
+        let points = vec![Point::new(0.0, 0.0), Point::new(100.0, 0.0)];
+        let speed = |plan: &Plan, s: f64| plan.instant_at_distance(s).unwrap().velocity;
+
+        let plain = planner.plan(points.clone()).unwrap();
+        let same = planner.plan_with(points.clone(), None, 0.0, 0.0).unwrap();
+        assert_eq!(plain.total_time, same.total_time);
+        assert_eq!(speed(&plain, 0.0), 0.0);
+
+        let slow = planner
+            .plan_with(points.clone(), Some(20.0), 0.0, 0.0)
+            .unwrap();
+        assert!((speed(&slow, 50.0) - 20.0).abs() < 1e-9);
+        assert!(slow.total_time > plain.total_time);
+
+        let flying = planner.plan_with(points.clone(), None, 30.0, 40.0).unwrap();
+        assert!((speed(&flying, 0.0) - 30.0).abs() < 1e-9);
+        assert!((speed(&flying, 100.0) - 40.0).abs() < 1e-9);
+        // Швидкість на краю не перевищує межу відрізка
+        let capped = planner
+            .plan_with(points.clone(), Some(20.0), 30.0, 0.0)
+            .unwrap();
+        assert!((speed(&capped, 0.0) - 20.0).abs() < 1e-9);
+
+        assert!(matches!(
+            planner.plan_with(points, None, -1.0, 0.0),
+            Err(PlanError::InvalidParameter {
+                name: "entry_v",
+                ..
+            })
+        ));
     }
 }
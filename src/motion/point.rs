@@ -34,7 +34,7 @@ pub trait PointExtension {
     ///
     /// # Параметри
     /// - `other`: Друга точка для інтерполяції.
-    /// - `s`: Коефіцієнт інтерполяції (значення від 0 до 1).
+    /// - `s`: Відстань від поточної точки в напрямку `other` (а не частка від 0 до 1).
     fn lerps(&self, other: &Point<f64>, s: f64) -> Point<f64>;
 }
 
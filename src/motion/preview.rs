@@ -0,0 +1,286 @@
+use svg::node::element::{Animate, Line, Polyline, Rectangle, Title};
+use svg::Document;
+
+use super::block::Block;
+use super::job::{Job, PlanAction};
+use crate::paper::Paper;
+use crate::tr;
+
+const SAMPLE_TIME: f64 = 0.01; // Крок у часі (в секундах) між точками попереднього перегляду
+const STROKE_WIDTH: f64 = 0.3; // Товщина лінії попереднього перегляду (в мм)
+const MIN_DURATION: f64 = 0.001; // Найкоротша тривалість анімації лінії (в секундах)
+
+/// Колір швидкості: від синього для зупинки до червоного для найбільшої швидкості.
+///
+/// # Аргументи
+/// * `velocity` - швидкість руху.
+/// * `vmax` - швидкість, що відповідає червоному кольору.
+///
+/// # Повертає
+/// * `String` - колір у форматі CSS `hsl()`.
+fn velocity_color(velocity: f64, vmax: f64) -> String {
+    let ratio = match vmax > 0.0 {
+        true => (velocity / vmax).clamp(0.0, 1.0),
+        false => 0.0,
+    };
+    format!("hsl({:.0}, 100%, 45%)", 240.0 * (1.0 - ratio))
+}
+
+/// Порожній документ розміром з аркуш із заголовком і сірою рамкою області для малювання,
+/// якщо аркуш має поля.
+fn sheet(paper: &Paper, title: String) -> Document {
+    let (width, height) = paper.size();
+    let mut document = Document::new()
+        .set("viewBox", (0, 0, width, height))
+        .set("width", format!("{}mm", width))
+        .set("height", format!("{}mm", height))
+        .add(Title::new(title));
+    if let Ok(area) = paper.printable_area() {
+        let margins = paper.margins;
+        if margins.top > 0.0 || margins.right > 0.0 || margins.bottom > 0.0 || margins.left > 0.0 {
+            document = document.add(
+                Rectangle::new()
+                    .set("x", area.min().x)
+                    .set("y", area.min().y)
+                    .set("width", area.width())
+                    .set("height", area.height())
+                    .set("fill", "none")
+                    .set("stroke", "#bbb")
+                    .set("stroke-width", STROKE_WIDTH),
+            );
+        }
+    }
+    document
+}
+
+/// Найбільша швидкість блоку, на його початку чи в кінці.
+fn peak_velocity(block: &Block) -> f64 {
+    let end = block.initial_velocity + block.acceleration * block.duration;
+    block.initial_velocity.max(end)
+}
+
+impl Job {
+    /// Малює траєкторію плану в SVG, розфарбовуючи положення за швидкістю.
+    ///
+    /// Кожен блок розбивається на відрізки з кроком `SAMPLE_TIME`, і колір відрізка
+    /// відповідає швидкості в його середині: синій — повільно, червоний — найбільша
+    /// швидкість плану. Переїзди з піднятим пером малюються пунктиром. Так видно, де
+    /// каретка розганяється й гальмує, і як на це впливають прискорення та коефіцієнт кутів.
+    /// Область для малювання аркуша обводиться сірою рамкою, тож видно, що нічого не
+    /// потрапляє на поля під затискачі.
+    ///
+    /// # Аргументи
+    /// * `paper` - аркуш, розміри якого задають документ.
+    ///
+    /// # Повертає
+    /// * `String` - документ SVG з фізичними розмірами в міліметрах.
+    pub fn to_svg(&self, paper: &Paper) -> String {
+        let blocks = self.actions.iter().filter_map(|action| match action {
+            PlanAction::Move(plan) => Some(&plan.blocks),
+            _ => None,
+        });
+        let vmax = blocks.flatten().map(peak_velocity).fold(0.0, f64::max);
+
+        let mut document = sheet(
+            paper,
+            tr!(
+                "Motion plan, top speed {:.1} mm/s",
+                "План руху, найбільша швидкість {:.1} мм/с",
+                vmax
+            ),
+        );
+
+        let mut pen_down = false;
+        for action in &self.actions {
+            let plan = match action {
+                PlanAction::Move(plan) => plan,
+                PlanAction::PenUp | PlanAction::PenDown => {
+                    pen_down = matches!(action, PlanAction::PenDown);
+                    continue;
+                }
+                PlanAction::PenDwell(_) => continue,
+            };
+            for block in &plan.blocks {
+                let count = (block.duration / SAMPLE_TIME).ceil().max(1.0) as usize;
+                let step = block.duration / count as f64;
+                for index in 0..count {
+                    let t = index as f64 * step;
+                    let start = block.instant(t, 0.0, 0.0).position;
+                    let end = block.instant(t + step, 0.0, 0.0).position;
+                    let velocity = block.instant(t + step / 2.0, 0.0, 0.0).velocity;
+                    let mut line = Line::new()
+                        .set("x1", start.x())
+                        .set("y1", start.y())
+                        .set("x2", end.x())
+                        .set("y2", end.y())
+                        .set("stroke", velocity_color(velocity, vmax))
+                        .set("stroke-width", STROKE_WIDTH)
+                        .set("stroke-linecap", "round");
+                    if !pen_down {
+                        line = line.set("stroke-dasharray", "1 1").set("opacity", 0.5);
+                    }
+                    document = document.add(line);
+                }
+            }
+        }
+
+        document.to_string()
+    }
+
+    /// Малює план в SVG, де лінії з'являються в тому порядку й темпі, як їх малюватиме плотер.
+    ///
+    /// Кожен рух з опущеним пером стає лінією, яку відкриває анімація `stroke-dashoffset`:
+    /// вона починається, коли перо доходить до лінії, і триває стільки, скільки її малювання
+    /// за планом, а проміжні точки часу збігаються з межами блоків, тож розгін і гальмування
+    /// видно як зміну темпу. Переїзди й паузи `PenDwell` лише витримують паузу. Затримки
+    /// пера не враховуються, як і в `total_time`. Браузер показує анімацію, відкривши файл.
+    ///
+    /// # Аргументи
+    /// * `paper` - аркуш, розміри якого задають документ.
+    ///
+    /// # Повертає
+    /// * `String` - документ SVG з фізичними розмірами в міліметрах.
+    pub fn to_animated_svg(&self, paper: &Paper) -> String {
+        let mut document = sheet(
+            paper,
+            tr!(
+                "Plotting order, {:.1} s",
+                "Порядок малювання, {:.1} с",
+                self.total_time()
+            ),
+        );
+
+        let (mut pen_down, mut start) = (false, 0.0);
+        for action in &self.actions {
+            let plan = match action {
+                PlanAction::Move(plan) => plan,
+                PlanAction::PenUp | PlanAction::PenDown => {
+                    pen_down = matches!(action, PlanAction::PenDown);
+                    continue;
+                }
+                PlanAction::PenDwell(duration) => {
+                    start += duration.as_secs_f64();
+                    continue;
+                }
+            };
+            let begin = start;
+            start += plan.total_time;
+            let Some(first) = plan.blocks.first() else {
+                continue;
+            };
+            if !pen_down || plan.total_distance <= 0.0 {
+                continue;
+            }
+
+            // Решта довжини лінії на межі кожного блоку стає кадром анімації
+            let length = plan.total_distance;
+            let duration = plan.total_time.max(MIN_DURATION);
+            let (mut points, mut values, mut key_times) = (
+                vec![format!("{},{}", first.p1.x(), first.p1.y())],
+                vec![length.to_string()],
+                vec!["0".to_string()],
+            );
+            let (mut elapsed, mut drawn) = (0.0, 0.0);
+            for block in &plan.blocks {
+                elapsed += block.duration;
+                drawn += block.distance;
+                points.push(format!("{},{}", block.p2.x(), block.p2.y()));
+                values.push((length - drawn).max(0.0).to_string());
+                key_times.push((elapsed / duration).clamp(0.0, 1.0).to_string());
+            }
+            // Кадри мають закінчуватися повністю намальованою лінією рівно в кінці руху
+            *values.last_mut().expect("Кадр є для кожного блоку") = "0".to_string();
+            *key_times.last_mut().expect("Кадр є для кожного блоку") = "1".to_string();
+
+            let animation = Animate::new()
+                .set("attributeName", "stroke-dashoffset")
+                .set("begin", format!("{}s", begin))
+                .set("dur", format!("{}s", duration))
+                .set("values", values.join(";"))
+                .set("keyTimes", key_times.join(";"))
+                .set("fill", "freeze");
+            document = document.add(
+                Polyline::new()
+                    .set("points", points.join(" "))
+                    .set("fill", "none")
+                    .set("stroke", "black")
+                    .set("stroke-width", STROKE_WIDTH)
+                    .set("stroke-linecap", "round")
+                    .set("stroke-dasharray", length)
+                    .set("stroke-dashoffset", length)
+                    .add(animation),
+            );
+        }
+
+        document.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drawing::lead::Stroke;
+    use crate::motion::job::JobPlanner;
+    use crate::motion::planner::Planner;
+    use crate::paper::Margins;
+    use geo::LineString;
+
+    #[test]
+    fn test_job_exports_to_json_and_svg() {
+        let strokes = vec![Stroke::plain(&LineString::from(vec![
+            (10.0, 10.0),
+            (60.0, 10.0),
+        ]))];
+        let job = JobPlanner::new(Planner::new(40.0, 100.0, 0.001))
+            .plan(&strokes)
+            .expect("Малюнок має плануватися");
+
+        let json = serde_json::to_value(&job).expect("План має серіалізуватися");
+        let actions = json["actions"].as_array().expect("Дії — масив");
+        assert_eq!(actions.len(), job.actions.len());
+        assert_eq!(actions[0]["action"], "move");
+        assert_eq!(actions[1]["action"], "pen_down");
+        let block = &actions[2]["blocks"][0];
+        assert_eq!(block["p1"]["x"], 10.0, "Точки мають координати x і y");
+        assert_eq!(block["initial_velocity"], 0.0);
+        assert!(actions[2]["total_time"].as_f64().unwrap() > 0.0);
+
+        let svg = job.to_svg(&Paper::custom(100.0, 50.0));
+        assert!(svg.contains("width=\"100mm\""));
+        assert!(!svg.contains("<rect"), "Без полів рамки немає");
+        let framed = job.to_svg(&Paper::custom(100.0, 50.0).margins(Margins::uniform(5.0)));
+        assert!(
+            framed.contains("width=\"90\""),
+            "Рамка області для малювання"
+        );
+        assert_eq!(
+            velocity_color(0.0, 40.0),
+            "hsl(240, 100%, 45%)",
+            "Зупинка синя"
+        );
+        let slowest = svg
+            .split("hsl(")
+            .skip(1)
+            .filter_map(|rest| rest.split(',').next()?.parse::<f64>().ok())
+            .fold(0.0, f64::max);
+        assert!(slowest > 200.0, "Розгін починається з повільних кольорів");
+        assert!(
+            svg.contains("hsl(0, 100%, 45%)"),
+            "Найбільша швидкість червона"
+        );
+        assert!(svg.contains("stroke-dasharray"), "Переїзд пунктиром");
+
+        // Єдина лінія з'являється після переїзду до неї й малюється до кінця плану
+        let animated = job.to_animated_svg(&Paper::custom(100.0, 50.0));
+        assert_eq!(animated.matches("<animate").count(), 1);
+        let travel = match &job.actions[0] {
+            PlanAction::Move(plan) => plan.total_time,
+            _ => unreachable!("План починається з переїзду"),
+        };
+        assert!(travel > 0.0);
+        assert!(animated.contains(&format!("begin=\"{}s\"", travel)));
+        assert!(animated.contains("stroke-dashoffset=\"50\""));
+        assert!(animated.contains("values=\"50;"));
+        assert!(animated.contains("keyTimes=\"0;"));
+    }
+}
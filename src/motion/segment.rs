@@ -2,18 +2,17 @@ use std::fmt;
 
 use geo::Point;
 
-use super::{block::Block, point::PointExtension};
+use super::point::PointExtension;
+use crate::tr;
 
 /// Структура `Segment` представляє геометричний сегмент між двома точками.
-/// Вона включає в себе довжину сегмента, напрямний вектор, вхідну швидкість, блоки руху.
+/// Вона включає в себе довжину сегмента, напрямний вектор і найбільшу швидкість на вході.
 pub struct Segment {
     pub p1: Point<f64>,          // Початкова точка сегмента.
     pub p2: Point<f64>,          // Кінцева точка сегмента.
     pub vector: Point<f64>,      // Напрямний вектор між p1 і p2.
     pub length: f64,             // Довжина сегмента.
     pub max_entry_velocity: f64, // Максимальна вхідна швидкість для цього сегмента.
-    pub entry_velocity: f64,     // Початкова швидкість входу в сегмент.
-    pub blocks: Vec<Block>,      // Блоки руху для цього сегмента.
 }
 
 impl Segment {
@@ -38,8 +37,6 @@ impl Segment {
             vector,
             length,
             max_entry_velocity: 0.0,
-            entry_velocity: 0.0,
-            blocks: vec![],
         }
     }
 }
@@ -48,14 +45,19 @@ impl fmt::Display for Segment {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "Сегмент:\n  Початкова точка: ({:.2}, {:.2})\n  Кінцева точка: ({:.2}, {:.2})\n  Довжина: {:.2}\n  Напрямний вектор: ({:.2}, {:.2})\n  Максимальна вхідна швидкість: {:.2}\n  Початкова швидкість входу: {:.2}\n  Кількість блоків: {}",
-            self.p1.x(), self.p1.y(),
-            self.p2.x(), self.p2.y(),
-            self.length,
-            self.vector.x(), self.vector.y(),
-            self.max_entry_velocity,
-            self.entry_velocity,
-            self.blocks.len()
+            "{}",
+            tr!(
+                "Segment:\n  Start point: ({:.2}, {:.2})\n  End point: ({:.2}, {:.2})\n  Length: {:.2}\n  Direction: ({:.2}, {:.2})\n  Max entry velocity: {:.2}",
+                "Сегмент:\n  Початкова точка: ({:.2}, {:.2})\n  Кінцева точка: ({:.2}, {:.2})\n  Довжина: {:.2}\n  Напрямний вектор: ({:.2}, {:.2})\n  Максимальна вхідна швидкість: {:.2}",
+                self.p1.x(),
+                self.p1.y(),
+                self.p2.x(),
+                self.p2.y(),
+                self.length,
+                self.vector.x(),
+                self.vector.y(),
+                self.max_entry_velocity
+            )
         )
     }
 }
@@ -41,7 +41,7 @@ impl Triangle {
         let vmax = (vi * vi + 2.0 * a * s1).sqrt();
         let t1 = (vmax - vi) / a;
         let t2 = (vf - vmax) / -a;
-        let p2 = p1.lerps(&p3, s1 / s);
+        let p2 = p1.lerps(&p3, s1);
 
         Self {
             s1,
@@ -1,7 +1,10 @@
-use std::f64::EPSILON;
-
 use super::segment::Segment;
 
+/// Відхилення косинуса від розвороту, яке ще вважається розворотом: нормалізація
+/// напрямних векторів округлює їх на кілька ULP, і без допуску точний розворот давав би
+/// ненульову швидкість на куті.
+const REVERSAL_TOLERANCE: f64 = 1e-12;
+
 /// Обчислює максимальну швидкість на куті між двома сегментами.
 ///
 /// # Параметри
@@ -12,12 +15,16 @@ use super::segment::Segment;
 /// - `cf`: Кутовий коефіцієнт (corner factor).
 ///
 /// # Повертає
-/// Максимальна швидкість на куті між двома сегментами.
+/// Максимальна швидкість на куті між двома сегментами. Сегмент нульової довжини не має
+/// напрямку, тож кут невідомий і на ньому треба зупинитися: швидкість дорівнює нулю.
 pub fn corner_velocity(s1: &Segment, s2: &Segment, vmax: f64, a: f64, cf: f64) -> f64 {
+    if s1.length <= 0.0 || s2.length <= 0.0 {
+        return 0.0;
+    }
     let cosine = -s1.vector.dot(s2.vector);
 
     // Перевірка на майже паралельні сегменти (кут близький до 180 градусів)
-    if (cosine - 1.0).abs() < EPSILON {
+    if 1.0 - cosine < REVERSAL_TOLERANCE {
         return 0.0;
     }
 
@@ -25,7 +32,7 @@ pub fn corner_velocity(s1: &Segment, s2: &Segment, vmax: f64, a: f64, cf: f64) -
     let sine = ((1.0 - cosine) / 2.0).sqrt();
 
     // Перевірка на майже перпендикулярні сегменти (кут близький до 90 градусів)
-    if (sine - 1.0).abs() < EPSILON {
+    if (sine - 1.0).abs() < f64::EPSILON {
         return vmax;
     }
 
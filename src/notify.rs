@@ -0,0 +1,321 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+use tracing::{debug, warn};
+
+use crate::control::{PlotControl, PlotState, PlotStatus};
+use crate::tr;
+
+/// Інтервал, з яким сповіщувач перевіряє стан пультів.
+const WATCH_POLL: Duration = Duration::from_millis(200);
+
+/// Подія малюнка, про яку надсилається сповіщення.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlotEvent {
+    Finished,  // Малюнок завершено.
+    Paused,    // Малюнок призупинено кнопкою PRG чи між шарами.
+    Cancelled, // Малюнок скасовано.
+    Failed,    // Малювання перервано помилкою.
+}
+
+impl PlotEvent {
+    /// Назва події у вмісті вебхука.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PlotEvent::Finished => "finished",
+            PlotEvent::Paused => "paused",
+            PlotEvent::Cancelled => "cancelled",
+            PlotEvent::Failed => "failed",
+        }
+    }
+
+    /// Заголовок сповіщення на робочому столі.
+    fn title(&self) -> String {
+        match self {
+            PlotEvent::Finished => tr!("Plot finished", "Малюнок завершено"),
+            PlotEvent::Paused => tr!("Plot paused", "Малюнок призупинено"),
+            PlotEvent::Cancelled => tr!("Plot cancelled", "Малювання скасовано"),
+            PlotEvent::Failed => tr!("Plot failed", "Малювання перервано помилкою"),
+        }
+    }
+}
+
+/// Подія, яку дає перехід пульта зі стану `previous` у `current` посеред малювання.
+///
+/// Лише пауза: завершення, скасування й помилку повідомляє `Notifier::finish`, коли
+/// вже відомий результат усієї команди.
+fn transition(previous: PlotState, current: PlotState) -> Option<PlotEvent> {
+    (current == PlotState::Paused && previous != PlotState::Paused).then_some(PlotEvent::Paused)
+}
+
+/// Сповіщення про завершення, паузу чи помилку довгого малюнка, що йде без нагляду.
+///
+/// Сповіщення показується на робочому столі (`notify-send` на Linux, `osascript` на macOS,
+/// PowerShell на Windows) і/або надсилається JSON-запитом POST на вебхук. Вебхук
+/// потребує ознаки збирання `url`. Помилка доставки лише записується в
+/// журнал і не перериває малювання.
+#[derive(Debug, Clone)]
+pub struct Notifier {
+    desktop: bool,                          // Показувати сповіщення на робочому столі.
+    webhook: Option<String>,                // Адреса вебхука.
+    controls: Arc<Mutex<Vec<PlotControl>>>, // Пульти плотерів, за якими стежить сповіщувач.
+    finished: Arc<AtomicBool>,              // Команда завершилася, стеження зупиняється.
+    started: Instant,                       // Момент створення, від якого рахується тривалість.
+}
+
+impl Notifier {
+    /// Створює сповіщувач.
+    ///
+    /// # Аргументи
+    /// * `desktop` - показувати сповіщення на робочому столі.
+    /// * `webhook` - адреса, на яку надсилається JSON з подією й статистикою малюнка.
+    pub fn new(desktop: bool, webhook: Option<String>) -> Self {
+        Notifier {
+            desktop,
+            webhook,
+            controls: Arc::default(),
+            finished: Arc::default(),
+            started: Instant::now(),
+        }
+    }
+
+    /// Чи надсилає сповіщувач хоч щось.
+    pub fn is_enabled(&self) -> bool {
+        self.desktop || self.webhook.is_some()
+    }
+
+    /// Стежить за пультом плотера й повідомляє про кожну паузу малюнка.
+    ///
+    /// Статистика пульта входить і в підсумкове сповіщення `finish`; з кількома плотерами
+    /// вона підсумовується.
+    pub fn watch(&self, control: &PlotControl) {
+        if !self.is_enabled() {
+            return;
+        }
+        self.controls.lock().unwrap().push(control.clone());
+        let (notifier, control) = (self.clone(), control.clone());
+        thread::spawn(move || {
+            let mut previous = control.status().state;
+            while !notifier.finished.load(Ordering::SeqCst) {
+                let current = control.status().state;
+                if let Some(event) = transition(previous, current) {
+                    notifier.send(event, None);
+                }
+                previous = current;
+                thread::sleep(WATCH_POLL);
+            }
+        });
+    }
+
+    /// Надсилає підсумкове сповіщення команди й зупиняє стеження за пультами.
+    ///
+    /// # Аргументи
+    /// * `error` - опис помилки, якою завершилася команда, або `None` для успіху.
+    pub fn finish(&self, error: Option<String>) {
+        self.finished.store(true, Ordering::SeqCst);
+        let cancelled = self
+            .controls
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|control| control.status().state == PlotState::Cancelled);
+        let event = match (&error, cancelled) {
+            (None, _) => PlotEvent::Finished,
+            (Some(_), true) => PlotEvent::Cancelled,
+            (Some(_), false) => PlotEvent::Failed,
+        };
+        self.send(event, error);
+    }
+
+    /// Вміст сповіщення: подія, помилка й статистика всіх пультів.
+    fn payload(&self, event: PlotEvent, error: Option<String>) -> Value {
+        let statuses: Vec<PlotStatus> = self
+            .controls
+            .lock()
+            .unwrap()
+            .iter()
+            .map(PlotControl::status)
+            .collect();
+        payload(event, error, &statuses, self.started.elapsed())
+    }
+
+    /// Доставляє сповіщення на робочий стіл і на вебхук незалежно одне від одного.
+    fn send(&self, event: PlotEvent, error: Option<String>) {
+        if !self.is_enabled() {
+            return;
+        }
+        let payload = self.payload(event, error);
+        debug!("{}", tr!("Notification: {}", "Сповіщення: {}", payload));
+        let mut results = vec![];
+        if self.desktop {
+            results.push(show_desktop(&event.title(), &summary(&payload)));
+        }
+        if let Some(url) = &self.webhook {
+            results.push(post_webhook(url, &payload));
+        }
+        for e in results.into_iter().filter_map(Result::err) {
+            warn!(
+                "{}",
+                tr!(
+                    "Cannot send the notification: {}",
+                    "Не вдалося надіслати сповіщення: {}",
+                    e
+                )
+            );
+        }
+    }
+}
+
+/// Збирає вміст сповіщення зі станів пультів.
+///
+/// # Аргументи
+/// * `event` - подія, про яку повідомляється.
+/// * `error` - опис помилки для подій `failed` і `cancelled`.
+/// * `statuses` - стани пультів усіх плотерів команди.
+/// * `elapsed` - час від початку команди.
+///
+/// # Повертає
+/// * `Value` - JSON з назвою події та підсумованою статистикою малюнка.
+fn payload(
+    event: PlotEvent,
+    error: Option<String>,
+    statuses: &[PlotStatus],
+    elapsed: Duration,
+) -> Value {
+    let sum = |value: fn(&PlotStatus) -> f64| statuses.iter().map(value).fold(0.0, |a, b| a + b);
+    json!({
+        "event": event.name(),
+        "error": error,
+        "devices": statuses.len(),
+        "elapsed": elapsed.as_secs_f64(),
+        "current_path": statuses.iter().map(|status| status.current_path).sum::<usize>(),
+        "total_paths": statuses.iter().map(|status| status.total_paths).sum::<usize>(),
+        "drawn_length": sum(|status| status.drawn_length),
+        "total_length": sum(|status| status.total_length),
+        "retries": statuses.iter().map(|status| status.retries).sum::<u64>(),
+        "stall_lifts": statuses.iter().map(|status| status.stall_lifts).sum::<u64>(),
+    })
+}
+
+/// Короткий текст сповіщення на робочому столі.
+fn summary(payload: &Value) -> String {
+    if let Some(error) = payload["error"].as_str() {
+        return error.to_string();
+    }
+    tr!(
+        "Path {} of {}, {:.0} of {:.0} mm drawn in {:.0} s",
+        "Шлях {} з {}, намальовано {:.0} з {:.0} мм за {:.0} с",
+        payload["current_path"],
+        payload["total_paths"],
+        payload["drawn_length"].as_f64().unwrap_or(0.0),
+        payload["total_length"].as_f64().unwrap_or(0.0),
+        payload["elapsed"].as_f64().unwrap_or(0.0)
+    )
+}
+
+/// Показує сповіщення на робочому столі, не чекаючи, доки його закриють.
+fn show_desktop(title: &str, body: &str) -> std::io::Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        let quote = |text: &str| format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""));
+        let mut command = std::process::Command::new("osascript");
+        command.arg("-e").arg(format!(
+            "display notification {} with title {}",
+            quote(body),
+            quote(title)
+        ));
+        command
+    } else if cfg!(windows) {
+        let quote = |text: &str| format!("'{}'", text.replace('\'', "''"));
+        let mut command = std::process::Command::new("powershell");
+        command.args(["-NoProfile", "-Command"]).arg(format!(
+            "Add-Type -AssemblyName System.Windows.Forms; \
+             $icon = New-Object System.Windows.Forms.NotifyIcon; \
+             $icon.Icon = [System.Drawing.SystemIcons]::Information; $icon.Visible = $true; \
+             $icon.ShowBalloonTip(10000, {}, {}, 'Info'); Start-Sleep -Seconds 10; $icon.Dispose()",
+            quote(title),
+            quote(body)
+        ));
+        command
+    } else {
+        let mut command = std::process::Command::new("notify-send");
+        command.args(["--app-name", "rsaxi", title, body]);
+        command
+    };
+    command.spawn().map(|_| ())
+}
+
+/// Надсилає вміст сповіщення запитом POST на вебхук.
+#[cfg(feature = "url")]
+fn post_webhook(url: &str, payload: &Value) -> std::io::Result<()> {
+    ureq::post(url)
+        .timeout(std::time::Duration::from_secs(30))
+        .set("Content-Type", "application/json")
+        .send_string(&payload.to_string())
+        .map(|_| ())
+        .map_err(std::io::Error::other)
+}
+
+/// Без ознаки `url` вебхук не надсилається, а помилка підказує, як її ввімкнути.
+#[cfg(not(feature = "url"))]
+fn post_webhook(url: &str, _payload: &Value) -> std::io::Result<()> {
+    Err(std::io::Error::other(tr!(
+        "webhook {} needs rsaxi built with --features url",
+        "вебхук {} потребує rsaxi, зібраного з --features url",
+        url
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_sums_devices_and_reports_pauses() {
+        assert_eq!(
+            transition(PlotState::Plotting, PlotState::Paused),
+            Some(PlotEvent::Paused)
+        );
+        assert_eq!(transition(PlotState::Paused, PlotState::Paused), None);
+        assert_eq!(transition(PlotState::Plotting, PlotState::Finished), None);
+
+        let first = PlotStatus {
+            current_path: 3,
+            total_paths: 4,
+            drawn_length: 120.0,
+            total_length: 150.0,
+            retries: 1,
+            ..PlotStatus::default()
+        };
+        let second = PlotStatus {
+            current_path: 4,
+            total_paths: 4,
+            drawn_length: 150.0,
+            total_length: 150.0,
+            stall_lifts: 2,
+            ..PlotStatus::default()
+        };
+        let value = payload(
+            PlotEvent::Finished,
+            None,
+            &[first, second],
+            Duration::from_secs(90),
+        );
+        assert_eq!(value["event"], "finished");
+        assert_eq!(value["error"], Value::Null);
+        assert_eq!(value["devices"], 2);
+        assert_eq!(value["total_paths"], 8);
+        assert_eq!(value["drawn_length"], 270.0);
+        assert_eq!(
+            (value["retries"].clone(), value["stall_lifts"].clone()),
+            (1.into(), 2.into())
+        );
+        assert!(summary(&value).contains("270"));
+
+        let failed = payload(PlotEvent::Failed, Some("QS".into()), &[], Duration::ZERO);
+        assert_eq!(failed["devices"], 0);
+        assert_eq!(summary(&failed), "QS");
+    }
+}
@@ -0,0 +1,484 @@
+use std::str::FromStr;
+
+use geo::{coord, Rect};
+use thiserror::Error;
+
+use crate::axidraw::AxiDrawModel;
+use crate::tr;
+
+/// Помилки при описі аркуша паперу.
+#[derive(Debug, Error)]
+pub enum PaperError {
+    /// Невідомий формат паперу або неправильний запис розміру.
+    #[error("{}", tr!("Unknown paper format: '{}'", "Невідомий формат паперу: '{}'", .0))]
+    UnknownFormat(String),
+
+    /// Поля записано не одним, двома чи чотирма невід'ємними числами.
+    #[error(
+        "{}",
+        tr!(
+            "Margins must be 1, 2 or 4 non-negative numbers like 10 or 10,15,10,15, not '{}'",
+            "Поля мають бути 1, 2 або 4 невід'ємними числами, як 10 чи 10,15,10,15, а не '{}'",
+            .0
+        )
+    )]
+    Margins(String),
+
+    /// Розміри аркуша або поля не залишають області для малювання.
+    #[error(
+        "{}",
+        tr!(
+            "Margins {:?} leave no printable area on a {}x{} mm sheet",
+            "Поля {:?} не залишають області для малювання на аркуші {}x{} мм",
+            .margins,
+            .width,
+            .height
+        )
+    )]
+    NoPrintableArea {
+        width: f64,
+        height: f64,
+        margins: Margins,
+    },
+}
+
+/// Орієнтація аркуша.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Orientation {
+    Portrait,  // Книжкова: висота більша за ширину.
+    Landscape, // Альбомна: ширина більша за висоту.
+}
+
+/// Поля аркуша в міліметрах.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Margins {
+    pub top: f64,    // Верхнє поле.
+    pub right: f64,  // Праве поле.
+    pub bottom: f64, // Нижнє поле.
+    pub left: f64,   // Ліве поле.
+}
+
+impl Margins {
+    /// Створює однакові поля з усіх боків.
+    ///
+    /// # Аргументи
+    ///
+    /// * `margin` - ширина поля в міліметрах.
+    ///
+    /// # Повертає
+    ///
+    /// * `Self` - поля з однаковим відступом.
+    pub fn uniform(margin: f64) -> Self {
+        Margins {
+            top: margin,
+            right: margin,
+            bottom: margin,
+            left: margin,
+        }
+    }
+}
+
+impl FromStr for Margins {
+    type Err = PaperError;
+
+    /// Розбирає поля в порядку CSS: одне число для всіх боків, два — для верху й низу та
+    /// для боків, або чотири — верх, право, низ і ліво.
+    ///
+    /// # Аргументи
+    ///
+    /// * `s` - рядок, наприклад `"10"`, `"10,20"` або `"10,15,10,15"`.
+    ///
+    /// # Повертає
+    ///
+    /// * `Result<Margins, PaperError>` - поля в міліметрах або помилка запису.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || PaperError::Margins(s.to_string());
+        let values = s
+            .split(',')
+            .map(|value| value.trim().parse::<f64>().map_err(|_| error()))
+            .collect::<Result<Vec<f64>, _>>()?;
+        if values
+            .iter()
+            .any(|value| !(value.is_finite() && *value >= 0.0))
+        {
+            return Err(error());
+        }
+        match values[..] {
+            [margin] => Ok(Margins::uniform(margin)),
+            [vertical, horizontal] => Ok(Margins {
+                top: vertical,
+                right: horizontal,
+                bottom: vertical,
+                left: horizontal,
+            }),
+            [top, right, bottom, left] => Ok(Margins {
+                top,
+                right,
+                bottom,
+                left,
+            }),
+            _ => Err(error()),
+        }
+    }
+}
+
+/// Аркуш паперу, на якому виконується малюнок.
+///
+/// Розміри задаються в міліметрах у книжковій орієнтації, а `orientation` визначає,
+/// як аркуш лежить на плотері. Координати малюнка відраховуються від лівого верхнього
+/// кута аркуша, як і в SVG.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Paper {
+    pub name: &'static str,       // Назва формату (наприклад, "A4" або "custom").
+    width: f64,                   // Коротша сторона аркуша в міліметрах.
+    height: f64,                  // Довша сторона аркуша в міліметрах.
+    pub orientation: Orientation, // Орієнтація аркуша.
+    pub margins: Margins,         // Поля аркуша.
+    pub bleed: f64, // Виліт підігнаного малюнка за область для малювання (в мм); обрізається по полях.
+}
+
+impl Paper {
+    /// Створює аркуш довільного розміру.
+    ///
+    /// Орієнтація визначається зі співвідношення сторін.
+    ///
+    /// # Аргументи
+    ///
+    /// * `width` - ширина аркуша в міліметрах.
+    /// * `height` - висота аркуша в міліметрах.
+    ///
+    /// # Повертає
+    ///
+    /// * `Self` - аркуш без полів.
+    pub fn custom(width: f64, height: f64) -> Self {
+        let orientation = if width > height {
+            Orientation::Landscape
+        } else {
+            Orientation::Portrait
+        };
+        Paper {
+            name: "custom",
+            width: width.min(height),
+            height: width.max(height),
+            orientation,
+            margins: Margins::default(),
+            bleed: 0.0,
+        }
+    }
+
+    /// Аркуш A3 (297 x 420 мм).
+    pub fn a3() -> Self {
+        Self::preset("A3", 297.0, 420.0)
+    }
+
+    /// Аркуш A4 (210 x 297 мм).
+    pub fn a4() -> Self {
+        Self::preset("A4", 210.0, 297.0)
+    }
+
+    /// Аркуш A5 (148 x 210 мм).
+    pub fn a5() -> Self {
+        Self::preset("A5", 148.0, 210.0)
+    }
+
+    /// Аркуш Letter (215.9 x 279.4 мм).
+    pub fn letter() -> Self {
+        Self::preset("Letter", 215.9, 279.4)
+    }
+
+    /// Аркуш, що відповідає всій робочій області моделі AxiDraw.
+    ///
+    /// # Аргументи
+    ///
+    /// * `model` - модель AxiDraw.
+    ///
+    /// # Повертає
+    ///
+    /// * `Self` - аркуш розміром з хід кареток моделі.
+    pub fn from_model(model: AxiDrawModel) -> Self {
+        let (width, height) = model.travel();
+        let mut paper = Self::custom(width, height);
+        paper.name = model.name();
+        paper
+    }
+
+    fn preset(name: &'static str, width: f64, height: f64) -> Self {
+        Paper {
+            name,
+            width,
+            height,
+            orientation: Orientation::Portrait,
+            margins: Margins::default(),
+            bleed: 0.0,
+        }
+    }
+
+    /// Повертає аркуш у книжковій орієнтації.
+    pub fn portrait(mut self) -> Self {
+        self.orientation = Orientation::Portrait;
+        self
+    }
+
+    /// Повертає аркуш в альбомній орієнтації.
+    pub fn landscape(mut self) -> Self {
+        self.orientation = Orientation::Landscape;
+        self
+    }
+
+    /// Встановлює поля аркуша.
+    ///
+    /// # Аргументи
+    ///
+    /// * `margins` - поля в міліметрах.
+    ///
+    /// # Повертає
+    ///
+    /// * `Self` - аркуш з новими полями.
+    pub fn margins(mut self, margins: Margins) -> Self {
+        self.margins = margins;
+        self
+    }
+
+    /// Задає виліт малюнка під час підгонки під аркуш.
+    ///
+    /// Підігнаний малюнок більшає так, щоб виступати за область для малювання на `bleed`
+    /// з кожного боку, а виступ обрізається по полях. Так краї малюнка доходять до самих
+    /// полів без білої смуги, а під затискачами паперу нічого не малюється.
+    ///
+    /// # Аргументи
+    ///
+    /// * `bleed` - виліт у міліметрах.
+    ///
+    /// # Повертає
+    ///
+    /// * `Self` - аркуш з новим вильотом.
+    pub fn bleed(mut self, bleed: f64) -> Self {
+        self.bleed = bleed;
+        self
+    }
+
+    /// Повертає ширину аркуша з урахуванням орієнтації.
+    pub fn width(&self) -> f64 {
+        match self.orientation {
+            Orientation::Portrait => self.width,
+            Orientation::Landscape => self.height,
+        }
+    }
+
+    /// Повертає висоту аркуша з урахуванням орієнтації.
+    pub fn height(&self) -> f64 {
+        match self.orientation {
+            Orientation::Portrait => self.height,
+            Orientation::Landscape => self.width,
+        }
+    }
+
+    /// Повертає розміри аркуша (ширина, висота) з урахуванням орієнтації.
+    pub fn size(&self) -> (f64, f64) {
+        (self.width(), self.height())
+    }
+
+    /// Повертає область для малювання: аркуш без полів.
+    ///
+    /// # Повертає
+    ///
+    /// * `Result<Rect<f64>, PaperError>` - прямокутник області або помилка, якщо поля
+    ///   перекривають увесь аркуш.
+    pub fn printable_area(&self) -> Result<Rect<f64>, PaperError> {
+        let (width, height) = self.size();
+        let min = coord! { x: self.margins.left, y: self.margins.top };
+        let max = coord! { x: width - self.margins.right, y: height - self.margins.bottom };
+        if max.x <= min.x || max.y <= min.y {
+            return Err(PaperError::NoPrintableArea {
+                width,
+                height,
+                margins: self.margins,
+            });
+        }
+        Ok(Rect::new(min, max))
+    }
+
+    /// Перевіряє, чи вміщується прямокутник в області для малювання.
+    ///
+    /// # Аргументи
+    ///
+    /// * `rect` - прямокутник у координатах аркуша.
+    ///
+    /// # Повертає
+    ///
+    /// * `bool` - `true`, якщо прямокутник повністю лежить у межах полів.
+    pub fn contains(&self, rect: &Rect<f64>) -> bool {
+        match self.printable_area() {
+            Ok(area) => {
+                rect.min().x >= area.min().x - f64::EPSILON
+                    && rect.min().y >= area.min().y - f64::EPSILON
+                    && rect.max().x <= area.max().x + f64::EPSILON
+                    && rect.max().y <= area.max().y + f64::EPSILON
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Перевіряє, чи вміщується аркуш у робочу область моделі AxiDraw.
+    ///
+    /// # Аргументи
+    ///
+    /// * `model` - модель AxiDraw.
+    ///
+    /// # Повертає
+    ///
+    /// * `bool` - `true`, якщо аркуш не виходить за межі ходу кареток.
+    pub fn fits_model(&self, model: AxiDrawModel) -> bool {
+        let (width, height) = model.travel();
+        self.width() <= width + f64::EPSILON && self.height() <= height + f64::EPSILON
+    }
+}
+
+impl FromStr for Paper {
+    type Err = PaperError;
+
+    /// Конвертує текстовий рядок у аркуш паперу.
+    ///
+    /// Підтримуються формати `a3`, `a4`, `a5`, `letter` і довільний розмір `ШИРИНАxВИСОТА`
+    /// в міліметрах. Суфікс `-landscape` або `-portrait` задає орієнтацію.
+    ///
+    /// # Аргументи
+    ///
+    /// * `s` - рядок, наприклад `"a4"`, `"a3-landscape"` або `"200x150"`.
+    ///
+    /// # Повертає
+    ///
+    /// * `Result<Paper, PaperError>` - аркуш або помилка, якщо формат невідомий.
+    ///
+    /// # Приклад
+    ///
+    /// ```
+    /// use rsaxi::paper::Paper;
+    /// let paper: Paper = "a4-landscape".parse().unwrap();
+    /// assert_eq!(paper.size(), (297.0, 210.0));
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.trim().to_lowercase();
+        let (format, orientation) = match lower.rsplit_once('-') {
+            Some((format, "landscape")) => (format, Some(Orientation::Landscape)),
+            Some((format, "portrait")) => (format, Some(Orientation::Portrait)),
+            _ => (lower.as_str(), None),
+        };
+
+        let paper = match format {
+            "a3" => Paper::a3(),
+            "a4" => Paper::a4(),
+            "a5" => Paper::a5(),
+            "letter" => Paper::letter(),
+            custom => {
+                let (width, height) = custom
+                    .split_once('x')
+                    .and_then(|(w, h)| Some((w.parse::<f64>().ok()?, h.parse::<f64>().ok()?)))
+                    .filter(|(w, h)| *w > 0.0 && *h > 0.0)
+                    .ok_or_else(|| PaperError::UnknownFormat(s.to_string()))?;
+                Paper::custom(width, height)
+            }
+        };
+
+        Ok(match orientation {
+            Some(Orientation::Landscape) => paper.landscape(),
+            Some(Orientation::Portrait) => paper.portrait(),
+            None => paper,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drawing::Drawing;
+    use geo::{LineString, MultiLineString};
+
+    #[test]
+    fn test_paper_orientation_margins_and_bounds() {
+        let paper = Paper::a4().landscape().margins(Margins::uniform(10.0));
+        assert_eq!(paper.size(), (297.0, 210.0));
+
+        let area = paper
+            .printable_area()
+            .expect("Має бути область для малювання");
+        assert_eq!(area.width(), 277.0);
+        assert_eq!(area.height(), 190.0);
+
+        let inside = Rect::new(coord! { x: 10.0, y: 10.0 }, coord! { x: 287.0, y: 200.0 });
+        let outside = Rect::new(coord! { x: 5.0, y: 10.0 }, coord! { x: 100.0, y: 100.0 });
+        assert!(paper.contains(&inside));
+        assert!(!paper.contains(&outside));
+
+        assert!(!Paper::a3().fits_model(AxiDrawModel::V3));
+        assert!(Paper::a4().fits_model(AxiDrawModel::V3A3));
+
+        let crowded = Paper::a5().margins(Margins::uniform(80.0));
+        assert!(crowded.printable_area().is_err());
+    }
+
+    #[test]
+    fn test_parse_paper() {
+        let custom: Paper = "200x150".parse().expect("Розмір має розпізнаватися");
+        assert_eq!(custom.size(), (200.0, 150.0));
+        assert_eq!(custom.orientation, Orientation::Landscape);
+
+        let letter: Paper = "Letter-portrait"
+            .parse()
+            .expect("Формат має розпізнаватися");
+        assert_eq!(letter.size(), (215.9, 279.4));
+
+        assert!("b5".parse::<Paper>().is_err());
+        assert!("0x100".parse::<Paper>().is_err());
+
+        assert_eq!("12".parse::<Margins>().unwrap(), Margins::uniform(12.0));
+        let sides: Margins = "10, 20".parse().expect("Поля мають розпізнаватися");
+        assert_eq!(
+            (sides.top, sides.right, sides.bottom, sides.left),
+            (10.0, 20.0, 10.0, 20.0)
+        );
+        let each: Margins = "1,2,3,4".parse().expect("Поля мають розпізнаватися");
+        assert_eq!(
+            (each.top, each.right, each.bottom, each.left),
+            (1.0, 2.0, 3.0, 4.0)
+        );
+        assert!("1,2,3".parse::<Margins>().is_err());
+        assert!("-5".parse::<Margins>().is_err());
+    }
+
+    #[test]
+    fn test_fit_drawing_to_page() {
+        let paper = Paper::a5().margins(Margins::uniform(10.0));
+        let square = LineString::from(vec![(0.0, 0.0), (500.0, 0.0), (500.0, 500.0), (0.0, 500.0)]);
+        let drawing = Drawing::new((500.0, 500.0), MultiLineString(vec![square]));
+        assert!(!drawing.fits(&paper));
+
+        let fitted = drawing.fit_to_page(&paper).expect("Малюнок має вміститися");
+        assert!(fitted.fits(&paper));
+        assert_eq!(fitted.bounds, (148.0, 210.0));
+
+        let bbox = fitted.bbox().expect("Малюнок не порожній");
+        assert!((bbox.width() - 128.0).abs() < 1e-9);
+        assert!((bbox.center().y - 105.0).abs() < 1e-9);
+
+        let svg = fitted.to_svg_on(&paper);
+        assert!(svg.contains("width=\"148mm\""));
+        assert!(svg.contains("viewBox=\"0 0 148 210\""));
+
+        // З вильотом малюнок більший за область, але обрізаний по полях
+        let bled = drawing
+            .fit_to_page(&paper.bleed(5.0))
+            .expect("Малюнок має вміститися");
+        assert!(bled.fits(&paper));
+        let bbox = bled.bbox().expect("Малюнок не порожній");
+        assert!(
+            (bbox.width() - 128.0).abs() < 1e-9,
+            "Ширину обрізано по полях"
+        );
+        assert!(
+            (bbox.height() - 138.0).abs() < 1e-9,
+            "Квадрат виступає на 5 мм"
+        );
+        assert_eq!(bled.paths.0.len(), 2, "Бічні сторони квадрата відрізано");
+    }
+}
@@ -0,0 +1,466 @@
+// Макроси pyo3 0.22 розгортаються з `cfg(feature = "gil-refs")` і зайвими `.into()` для
+// `PyResult`, тож ці попередження стосуються згенерованого коду, а не модуля
+#![allow(unexpected_cfgs, clippy::useless_conversion)]
+
+use std::str::FromStr;
+
+use geo::{LineString, MultiLineString, Point};
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::axidraw::{Axidraw, Options};
+use crate::control::PlotControl;
+use crate::drawing::Drawing;
+use crate::error::Error;
+use crate::motion::plan::Plan;
+use crate::motion::planner::Planner;
+use crate::paper::Paper;
+use crate::text::font::catalog;
+use crate::text::text::{TextAlign, TextBuilder};
+
+create_exception!(rsaxi, RsaxiError, PyException, "Помилка бібліотеки rsaxi.");
+create_exception!(
+    rsaxi,
+    DeviceError,
+    RsaxiError,
+    "Помилка зв'язку з платою EBB."
+);
+create_exception!(rsaxi, SvgError, RsaxiError, "Помилка розбору SVG.");
+create_exception!(rsaxi, PlanError, RsaxiError, "Помилка планування руху.");
+create_exception!(rsaxi, CancelledError, RsaxiError, "Малювання скасовано.");
+
+/// Виняток Python для помилки бібліотеки, за тими самими видами, що й коди `capi`.
+fn to_py(error: impl Into<Error>) -> PyErr {
+    let error = error.into();
+    let message = error.to_string();
+    match error {
+        Error::Device(_) => DeviceError::new_err(message),
+        Error::Import(_) => SvgError::new_err(message),
+        Error::Plan(_) => PlanError::new_err(message),
+        Error::Cancelled(_) => CancelledError::new_err(message),
+        Error::Paper(_) | Error::OutOfBounds { .. } => PyValueError::new_err(message),
+        _ => RsaxiError::new_err(message),
+    }
+}
+
+/// Малюнок із набору шляхів у міліметрах.
+#[pyclass(name = "Drawing", module = "rsaxi")]
+#[derive(Clone)]
+pub struct PyDrawing {
+    drawing: Drawing,
+}
+
+#[pymethods]
+impl PyDrawing {
+    /// Створює малюнок розміром `width`×`height` зі шляхів — списків точок `(x, y)`.
+    #[new]
+    #[pyo3(signature = (width, height, paths = vec![]))]
+    fn new(width: f64, height: f64, paths: Vec<Vec<(f64, f64)>>) -> Self {
+        let paths = paths.into_iter().map(LineString::from).collect();
+        PyDrawing {
+            drawing: Drawing::new((width, height), MultiLineString(paths)),
+        }
+    }
+
+    /// Розбирає SVG-документ.
+    #[staticmethod]
+    fn from_svg(svg: &str) -> PyResult<Self> {
+        let drawing = Drawing::from_svg(svg).map_err(to_py)?;
+        Ok(PyDrawing { drawing })
+    }
+
+    /// Межі малюнка `(ширина, висота)`.
+    #[getter]
+    fn bounds(&self) -> (f64, f64) {
+        self.drawing.bounds
+    }
+
+    /// Шляхи малюнка як списки точок `(x, y)`.
+    #[getter]
+    fn paths(&self) -> Vec<Vec<(f64, f64)>> {
+        self.drawing
+            .paths
+            .iter()
+            .map(|line| line.0.iter().map(|point| (point.x, point.y)).collect())
+            .collect()
+    }
+
+    /// Підганяє малюнок під аркуш на кшталт `"A4"` чи `"210x297"`.
+    fn fit_to_page(&self, paper: &str) -> PyResult<Self> {
+        let paper = Paper::from_str(paper).map_err(to_py)?;
+        let drawing = self.drawing.fit_to_page(&paper).map_err(to_py)?;
+        Ok(PyDrawing { drawing })
+    }
+
+    /// Заповнює замкнені шляхи лініями через `spacing` мм під кутом `angle` градусів.
+    #[pyo3(signature = (spacing, angle = 0.0))]
+    fn hatch(&self, spacing: f64, angle: f64) -> Self {
+        PyDrawing {
+            drawing: self.drawing.hatch(spacing, angle),
+        }
+    }
+
+    /// SVG-документ малюнка.
+    fn to_svg(&self) -> String {
+        self.drawing.to_svg()
+    }
+
+    fn __len__(&self) -> usize {
+        self.drawing.paths.0.len()
+    }
+
+    fn __repr__(&self) -> String {
+        let (width, height) = self.drawing.bounds;
+        format!(
+            "Drawing({} x {} mm, {} paths)",
+            width,
+            height,
+            self.drawing.paths.0.len()
+        )
+    }
+}
+
+/// Будівельник тексту шрифтами Hershey; методи повертають той самий будівельник.
+#[pyclass(name = "TextBuilder", module = "rsaxi")]
+#[derive(Default)]
+pub struct PyTextBuilder {
+    builder: TextBuilder,
+}
+
+impl PyTextBuilder {
+    /// Замінює будівельник результатом `change` і повертає його для ланцюжка викликів.
+    fn with(
+        mut slf: PyRefMut<'_, Self>,
+        change: impl FnOnce(TextBuilder) -> TextBuilder,
+    ) -> PyRefMut<'_, Self> {
+        slf.builder = change(std::mem::take(&mut slf.builder));
+        slf
+    }
+}
+
+#[pymethods]
+impl PyTextBuilder {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Текст для малювання.
+    fn content<'py>(slf: PyRefMut<'py, Self>, content: &str) -> PyRefMut<'py, Self> {
+        Self::with(slf, |builder| builder.content(content))
+    }
+
+    /// Текст із розміткою індексів (`H~2~O`, `x^2^`).
+    fn markup<'py>(slf: PyRefMut<'py, Self>, markup: &str) -> PyRefMut<'py, Self> {
+        Self::with(slf, |builder| builder.markup(markup))
+    }
+
+    /// Шрифт за назвою з каталогу, наприклад `"romans"`.
+    fn font<'py>(slf: PyRefMut<'py, Self>, name: &str) -> PyResult<PyRefMut<'py, Self>> {
+        let font = catalog::by_name(name).map_err(to_py)?;
+        Ok(Self::with(slf, |builder| builder.font(font)))
+    }
+
+    /// Запасний шрифт для символів, яких немає в основному.
+    fn fallback<'py>(slf: PyRefMut<'py, Self>, name: &str) -> PyResult<PyRefMut<'py, Self>> {
+        let font = catalog::by_name(name).map_err(to_py)?;
+        Ok(Self::with(slf, |builder| builder.fallback(font)))
+    }
+
+    /// Масштаб гліфів.
+    fn scale(slf: PyRefMut<'_, Self>, scale: f64) -> PyRefMut<'_, Self> {
+        Self::with(slf, |builder| builder.scale(scale))
+    }
+
+    /// Ширина рядка (в мм), за якою текст переноситься.
+    fn width(slf: PyRefMut<'_, Self>, width: f64) -> PyRefMut<'_, Self> {
+        Self::with(slf, |builder| builder.width(width))
+    }
+
+    /// Міжрядковий інтервал як частка висоти рядка.
+    fn line_height(slf: PyRefMut<'_, Self>, line_height: f64) -> PyRefMut<'_, Self> {
+        Self::with(slf, |builder| builder.line_height(line_height))
+    }
+
+    /// Вирівнювання: `"left"`, `"center"` чи `"right"`.
+    fn align<'py>(slf: PyRefMut<'py, Self>, align: &str) -> PyResult<PyRefMut<'py, Self>> {
+        let align = TextAlign::from_str(align).map_err(to_py)?;
+        Ok(Self::with(slf, |builder| builder.align(align)))
+    }
+
+    /// Вирівнювання рядків за шириною.
+    fn justify(slf: PyRefMut<'_, Self>, justify: bool) -> PyRefMut<'_, Self> {
+        Self::with(slf, |builder| builder.justify(justify))
+    }
+
+    /// Поворот тексту (в градусах).
+    fn rotation(slf: PyRefMut<'_, Self>, degrees: f64) -> PyRefMut<'_, Self> {
+        Self::with(slf, |builder| builder.rotation(degrees))
+    }
+
+    /// Нахил гліфів (в градусах).
+    fn slant(slf: PyRefMut<'_, Self>, degrees: f64) -> PyRefMut<'_, Self> {
+        Self::with(slf, |builder| builder.slant(degrees))
+    }
+
+    /// Кількість проходів пера для жирнішого тексту.
+    fn weight(slf: PyRefMut<'_, Self>, weight: usize) -> PyRefMut<'_, Self> {
+        Self::with(slf, |builder| builder.weight(weight))
+    }
+
+    /// Будує текст як малюнок з полями `padding` мм; будівельник після цього порожній.
+    #[pyo3(signature = (padding = 1.0))]
+    fn build(&mut self, padding: f64) -> PyResult<PyDrawing> {
+        let text = std::mem::take(&mut self.builder).build().map_err(to_py)?;
+        let drawing = text.to_drawing(padding).map_err(to_py)?;
+        Ok(PyDrawing { drawing })
+    }
+}
+
+/// Планувальник руху зі швидкістю (мм/с), прискоренням (мм/с²) і коефіцієнтом кутів.
+#[pyclass(name = "Planner", module = "rsaxi")]
+pub struct PyPlanner {
+    planner: Planner,
+}
+
+#[pymethods]
+impl PyPlanner {
+    #[new]
+    #[pyo3(signature = (max_velocity, acceleration, corner_factor = 0.001))]
+    fn new(max_velocity: f64, acceleration: f64, corner_factor: f64) -> Self {
+        PyPlanner {
+            planner: Planner::new(max_velocity, acceleration, corner_factor),
+        }
+    }
+
+    /// Планує рух через точки `(x, y)`.
+    fn plan(&self, points: Vec<(f64, f64)>) -> PyResult<PyPlan> {
+        let points = points.into_iter().map(Point::from).collect();
+        let plan = self.planner.plan(points).map_err(to_py)?;
+        Ok(PyPlan { plan })
+    }
+}
+
+/// План руху, побудований `Planner.plan`.
+#[pyclass(name = "Plan", module = "rsaxi")]
+pub struct PyPlan {
+    plan: Plan,
+}
+
+#[pymethods]
+impl PyPlan {
+    /// Тривалість руху (в секундах).
+    #[getter]
+    fn total_time(&self) -> f64 {
+        self.plan.total_time
+    }
+
+    /// Довжина руху (в мм).
+    #[getter]
+    fn total_distance(&self) -> f64 {
+        self.plan.total_distance
+    }
+
+    /// Стани руху `(t, x, y, швидкість)` з кроком `dt` секунд.
+    fn sample(&self, dt: f64) -> Vec<(f64, f64, f64, f64)> {
+        self.plan
+            .sample(dt)
+            .map(|instant| {
+                let (x, y) = instant.position.x_y();
+                (instant.time_elapsed, x, y, instant.velocity)
+            })
+            .collect()
+    }
+}
+
+/// Знімок стану малювання як словник.
+fn status<'py>(py: Python<'py>, control: &PlotControl) -> PyResult<Bound<'py, PyDict>> {
+    let status = control.status();
+    let dict = PyDict::new_bound(py);
+    dict.set_item("state", status.state.name())?;
+    dict.set_item("current_path", status.current_path)?;
+    dict.set_item("total_paths", status.total_paths)?;
+    dict.set_item("position", status.position)?;
+    dict.set_item("velocity", status.velocity)?;
+    dict.set_item("pen_down", status.pen_down)?;
+    dict.set_item("drawn_length", status.drawn_length)?;
+    dict.set_item("total_length", status.total_length)?;
+    dict.set_item("eta", status.eta.map(|eta| eta.as_secs_f64()))?;
+    dict.set_item("error", status.error)?;
+    Ok(dict)
+}
+
+/// Підключений AxiDraw; поки малюнок виконується, GIL відпущено.
+#[pyclass(name = "Axidraw", module = "rsaxi")]
+pub struct PyAxidraw {
+    axidraw: Axidraw,
+}
+
+#[pymethods]
+impl PyAxidraw {
+    /// Підключається до AxiDraw на порту `port` або до першого знайденого.
+    #[new]
+    #[pyo3(signature = (port = None))]
+    fn new(py: Python<'_>, port: Option<String>) -> PyResult<Self> {
+        let options = Options {
+            port,
+            ..Options::default()
+        };
+        let axidraw = py.allow_threads(|| Axidraw::new(options)).map_err(to_py)?;
+        Ok(PyAxidraw { axidraw })
+    }
+
+    /// Плотер з імітованою платою, що приймає всі команди без апаратного забезпечення.
+    #[staticmethod]
+    fn simulated() -> PyResult<Self> {
+        let (axidraw, _) = Axidraw::simulated(Options::default()).map_err(to_py)?;
+        Ok(PyAxidraw { axidraw })
+    }
+
+    /// Назва аркуша, на якому малює плотер.
+    #[getter]
+    fn paper(&self) -> String {
+        self.axidraw.paper().name.to_string()
+    }
+
+    /// Малює малюнок і повертається, коли його завершено, скасовано чи перервано.
+    fn draw(&mut self, py: Python<'_>, drawing: PyRef<'_, PyDrawing>) -> PyResult<()> {
+        let (axidraw, drawing) = (&mut self.axidraw, &drawing.drawing);
+        py.allow_threads(|| axidraw.draw(drawing)).map_err(to_py)
+    }
+
+    /// Піднімає перо.
+    fn pen_up(&mut self) -> PyResult<()> {
+        self.axidraw.pen_up().map_err(to_py)
+    }
+
+    /// Опускає перо.
+    fn pen_down(&mut self) -> PyResult<()> {
+        self.axidraw.pen_down().map_err(to_py)
+    }
+
+    /// Відносно переміщує каретку (в мм).
+    fn move_to(&mut self, py: Python<'_>, dx: f64, dy: f64) -> PyResult<()> {
+        let axidraw = &mut self.axidraw;
+        py.allow_threads(|| axidraw.move_to(dx, dy)).map_err(to_py)
+    }
+
+    /// Повертає каретку на початкову позицію.
+    fn home(&mut self, py: Python<'_>) -> PyResult<()> {
+        let axidraw = &mut self.axidraw;
+        py.allow_threads(|| axidraw.home()).map_err(to_py)
+    }
+
+    /// Стан малювання.
+    fn status<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        status(py, &self.axidraw.control)
+    }
+
+    /// Пульт для паузи й скасування з іншого потоку, поки `draw` виконується.
+    fn control(&self) -> PyPlotControl {
+        PyPlotControl {
+            control: self.axidraw.control.clone(),
+        }
+    }
+}
+
+/// Пульт плотера, створений `Axidraw.control`.
+#[pyclass(name = "PlotControl", module = "rsaxi")]
+pub struct PyPlotControl {
+    control: PlotControl,
+}
+
+#[pymethods]
+impl PyPlotControl {
+    /// Призупиняє малюнок і піднімає перо.
+    fn pause(&self) {
+        self.control.pause();
+    }
+
+    /// Відновлює призупинений малюнок.
+    fn resume(&self) {
+        self.control.resume();
+    }
+
+    /// Скасовує малюнок.
+    fn cancel(&self) {
+        self.control.cancel();
+    }
+
+    /// Стан малювання.
+    fn status<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        status(py, &self.control)
+    }
+}
+
+/// Модуль Python `rsaxi`.
+#[pymodule]
+fn rsaxi(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = module.py();
+    module.add_class::<PyDrawing>()?;
+    module.add_class::<PyTextBuilder>()?;
+    module.add_class::<PyPlanner>()?;
+    module.add_class::<PyPlan>()?;
+    module.add_class::<PyAxidraw>()?;
+    module.add_class::<PyPlotControl>()?;
+    module.add("RsaxiError", py.get_type_bound::<RsaxiError>())?;
+    module.add("DeviceError", py.get_type_bound::<DeviceError>())?;
+    module.add("SvgError", py.get_type_bound::<SvgError>())?;
+    module.add("PlanError", py.get_type_bound::<PlanError>())?;
+    module.add("CancelledError", py.get_type_bound::<CancelledError>())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::types::PyModule;
+
+    #[test]
+    fn test_python_module_plots_text_and_svg() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new_bound(py, "rsaxi").unwrap();
+            rsaxi(&module).unwrap();
+            let globals = PyDict::new_bound(py);
+            globals.set_item("rsaxi", module).unwrap();
+            py.run_bound(
+                r#"
+drawing = rsaxi.Drawing.from_svg('<svg viewBox="0 0 50 50" width="50mm" height="50mm"><path d="M5,5 L40,5 L40,40"/></svg>')
+assert len(drawing) == 1 and drawing.bounds == (50.0, 50.0)
+assert drawing.paths[0][-1] == (40.0, 40.0)
+
+text = rsaxi.TextBuilder().content("Hi").font("romans").width(100).scale(0.5).build(padding=2)
+assert len(text) > 0 and text.bounds[0] > 4
+
+plan = rsaxi.Planner(50, 200).plan([(0, 0), (30, 40)])
+assert abs(plan.total_distance - 50) < 1e-9
+samples = plan.sample(0.05)
+assert samples[0][3] == 0 and abs(samples[-1][1] - 30) < 1e-9
+
+plotter = rsaxi.Axidraw.simulated()
+plotter.draw(drawing)
+plotter.draw(text)
+status = plotter.control().status()
+assert status["state"] == "finished" and status["total_paths"] == len(text)
+
+try:
+    rsaxi.Drawing.from_svg('<svg><path d="M 10 10 L x"/></svg>')
+    raise AssertionError("SVG має не розбиратися")
+except rsaxi.SvgError as error:
+    assert isinstance(error, rsaxi.RsaxiError)
+try:
+    plotter.draw(rsaxi.Drawing(5000, 10, [[(10, 5), (4000, 5)]]))
+    raise AssertionError("Малюнок поза аркушем")
+except ValueError:
+    pass
+"#,
+                Some(&globals),
+                None,
+            )
+            .unwrap_or_else(|error| panic!("{}", error));
+        });
+    }
+}
@@ -0,0 +1,296 @@
+pub mod lines;
+mod pnm;
+pub mod stipple;
+
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use image::{ImageFormat, ImageReader, Limits};
+use thiserror::Error;
+
+use crate::tr;
+
+/// Помилки читання растрового зображення.
+#[derive(Debug, Error)]
+pub enum RasterError {
+    /// Не вдалося прочитати файл.
+    #[error(
+        "{}",
+        tr!(
+            "Cannot read image {}: {}",
+            "Не вдалося прочитати зображення {}: {}",
+            .path.display(),
+            .source
+        )
+    )]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    /// Формат файлу не розпізнано або він не підтримується, як-от GIF.
+    #[error(
+        "{}",
+        tr!(
+            "Unsupported image format; use PNG, JPEG or Netpbm (PGM, PPM, PBM)",
+            "Формат зображення не підтримується; використайте PNG, JPEG або Netpbm (PGM, PPM, PBM)"
+        )
+    )]
+    UnsupportedFormat,
+
+    /// Не вдалося розібрати PNG чи JPEG: файл пошкоджений, завеликий чи має
+    /// непідтримувану можливість.
+    #[error(
+        "{}",
+        tr!(
+            "Cannot decode image: {}",
+            "Не вдалося розібрати зображення: {}",
+            .0
+        )
+    )]
+    Image(#[from] image::ImageError),
+
+    /// Можливість формату, яку не реалізовано, наприклад завелике зображення Netpbm.
+    #[error(
+        "{}",
+        tr!(
+            "Unsupported image feature: {}",
+            "Можливість зображення не підтримується: {}",
+            .0
+        )
+    )]
+    Unsupported(&'static str),
+
+    /// Файл пошкоджений або обрізаний.
+    #[error(
+        "{}",
+        tr!(
+            "Image is corrupt: {}",
+            "Зображення пошкоджене: {}",
+            .0
+        )
+    )]
+    Corrupt(&'static str),
+}
+
+/// Найбільша сторона зображення PNG чи JPEG в пікселях, яку варто розпаковувати.
+const MAX_SIDE: u32 = 16384;
+
+/// Найбільший обсяг пам'яті (в байтах), який може виділити розбір PNG чи JPEG.
+const MAX_ALLOC: u64 = 1 << 30;
+
+/// Растрове зображення у відтінках сірого: яскравість кожного пікселя від 0 (чорний) до 1 (білий).
+///
+/// Кольорові зображення зводяться до яскравості, а прозорі пікселі — до білого аркуша,
+/// бо плотер малює лише темні лінії на світлому папері.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Raster {
+    width: usize,     // Ширина в пікселях.
+    height: usize,    // Висота в пікселях.
+    pixels: Vec<f64>, // Яскравість пікселів рядок за рядком, згори вниз.
+}
+
+impl Raster {
+    /// Створює зображення з яскравості пікселів.
+    ///
+    /// # Аргументи
+    /// * `width` - ширина в пікселях.
+    /// * `height` - висота в пікселях.
+    /// * `pixels` - яскравість 0..1 рядок за рядком; довжина має бути `width * height`.
+    pub fn new(width: usize, height: usize, pixels: Vec<f64>) -> Raster {
+        assert_eq!(pixels.len(), width * height, "Кількість пікселів");
+        Raster {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Створює зображення з функції яскравості, наприклад шуму чи математичного поля.
+    ///
+    /// # Аргументи
+    /// * `field` - яскравість пікселя за його стовпцем і рядком.
+    pub fn from_fn(width: usize, height: usize, field: impl Fn(usize, usize) -> f64) -> Raster {
+        let pixels = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| field(x, y))
+            .collect();
+        Raster::new(width, height, pixels)
+    }
+
+    /// Розпізнає формат за підписом і розбирає зображення.
+    ///
+    /// PNG і JPEG розбирає крейт `image` з обмеженням розміру: сторона не більша за
+    /// `MAX_SIDE` пікселів, а розпакування не виділяє більше `MAX_ALLOC` байтів, тож
+    /// чужий файл не змусить виділити пам'ять без меж.
+    ///
+    /// # Аргументи
+    /// * `bytes` - вміст файлу PNG, JPEG або Netpbm.
+    ///
+    /// # Повертає
+    /// * `Result<Raster, RasterError>` - зображення або помилка формату.
+    pub fn decode(bytes: &[u8]) -> Result<Raster, RasterError> {
+        let mut reader = ImageReader::new(Cursor::new(bytes))
+            .with_guessed_format()
+            .map_err(image::ImageError::IoError)?;
+        if !matches!(reader.format(), Some(ImageFormat::Png | ImageFormat::Jpeg)) {
+            return pnm::decode(bytes);
+        }
+        let mut limits = Limits::default();
+        limits.max_image_width = Some(MAX_SIDE);
+        limits.max_image_height = Some(MAX_SIDE);
+        limits.max_alloc = Some(MAX_ALLOC);
+        reader.limits(limits);
+
+        let image = reader.decode()?.into_rgba32f();
+        let (width, height) = (image.width() as usize, image.height() as usize);
+        let pixels = image
+            .pixels()
+            .map(|pixel| {
+                let [red, green, blue, alpha] = pixel.0.map(f64::from);
+                // Прозорість відкриває білий аркуш під пікселем
+                alpha * Raster::luma([red, green, blue]) + (1.0 - alpha)
+            })
+            .collect();
+        Ok(Raster::new(width, height, pixels))
+    }
+
+    /// Читає зображення з файлу.
+    ///
+    /// # Аргументи
+    /// * `path` - шлях до файлу PNG, JPEG або Netpbm.
+    ///
+    /// # Повертає
+    /// * `Result<Raster, RasterError>` - зображення або помилка читання чи формату.
+    pub fn open(path: impl AsRef<Path>) -> Result<Raster, RasterError> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path).map_err(|source| RasterError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Raster::decode(&bytes)
+    }
+
+    /// Ширина в пікселях.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Висота в пікселях.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Яскравість пікселя в стовпці `x` і рядку `y`.
+    pub fn luminance(&self, x: usize, y: usize) -> f64 {
+        self.pixels[y * self.width + x]
+    }
+
+    /// Яскравість у довільній точці зображення з білінійною інтерполяцією.
+    ///
+    /// # Аргументи
+    /// * `x`, `y` - координати в пікселях, де (0, 0) — лівий верхній кут зображення,
+    ///   а центр першого пікселя — (0.5, 0.5).
+    ///
+    /// # Повертає
+    /// * `f64` - яскравість 0..1; за межами зображення береться найближчий край.
+    pub fn sample(&self, x: f64, y: f64) -> f64 {
+        let clamp = |value: f64, size: usize| (value - 0.5).clamp(0.0, (size - 1) as f64);
+        let (x, y) = (clamp(x, self.width), clamp(y, self.height));
+        let (x0, y0) = (x.floor() as usize, y.floor() as usize);
+        let (x1, y1) = ((x0 + 1).min(self.width - 1), (y0 + 1).min(self.height - 1));
+        let (tx, ty) = (x - x0 as f64, y - y0 as f64);
+        let top = self.luminance(x0, y0) * (1.0 - tx) + self.luminance(x1, y0) * tx;
+        let bottom = self.luminance(x0, y1) * (1.0 - tx) + self.luminance(x1, y1) * tx;
+        top * (1.0 - ty) + bottom * ty
+    }
+
+    /// Яскравість кольору RGB за вагами Rec. 709.
+    pub(crate) fn luma([red, green, blue]: [f64; 3]) -> f64 {
+        0.2126 * red + 0.7152 * green + 0.0722 * blue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{DynamicImage, GrayImage, RgbaImage};
+
+    /// Байти з запису в шістнадцятковому вигляді.
+    fn hex(text: &str) -> Vec<u8> {
+        (0..text.len())
+            .step_by(2)
+            .map(|index| u8::from_str_radix(&text[index..index + 2], 16).unwrap())
+            .collect()
+    }
+
+    /// Зображення, записане у формат `format`.
+    fn encode(image: DynamicImage, format: ImageFormat) -> Vec<u8> {
+        let mut bytes = Cursor::new(vec![]);
+        image.write_to(&mut bytes, format).unwrap();
+        bytes.into_inner()
+    }
+
+    #[test]
+    fn test_images_decode_to_luminance() {
+        let gray = GrayImage::from_raw(2, 2, vec![0, 255, 0, 255]).unwrap();
+        let gray = encode(DynamicImage::ImageLuma8(gray), ImageFormat::Png);
+        let raster = Raster::decode(&gray).unwrap();
+        assert_eq!((raster.width(), raster.height()), (2, 2));
+        assert_eq!(raster.pixels, [0.0, 1.0, 0.0, 1.0]);
+
+        // RGBA: напівпрозорий чорний на білому аркуші стає сірим
+        let rgba = RgbaImage::from_raw(1, 1, vec![0, 0, 0, 128]).unwrap();
+        let rgba = encode(DynamicImage::ImageRgba8(rgba), ImageFormat::Png);
+        let value = Raster::decode(&rgba).unwrap().luminance(0, 0);
+        assert!((value - (1.0 - 128.0 / 255.0)).abs() < 1e-6);
+
+        // Черезрядковий (Adam7) PNG 3×3 з шаховим візерунком
+        let interlaced = hex(
+            "89504e470d0a1a0a0000000d49484452000000030000000308000000010444daf50000000e494441\
+             5478da63608080ff1008000fff03fde9f51ae00000000049454e44ae426082",
+        );
+        let chess = Raster::decode(&interlaced).unwrap();
+        assert_eq!(chess.pixels, [0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0]);
+
+        // Заголовок 100000×100000 не змушує виділяти десять гігабайтів
+        let huge = hex(
+            "89504e470d0a1a0a0000000d49484452000186a0000186a008000000008d395414000000114944\
+             415478da63601805a360140c77000003e80001ce494c580000000049454e44ae426082",
+        );
+        assert!(matches!(
+            Raster::decode(&huge),
+            Err(RasterError::Image(image::ImageError::Limits(_)))
+        ));
+
+        // JPEG стискає з втратами, тож рівномірне поле лише близьке до свого значення
+        let field = GrayImage::from_pixel(16, 16, image::Luma([64]));
+        let jpeg = encode(DynamicImage::ImageLuma8(field), ImageFormat::Jpeg);
+        let photo = Raster::decode(&jpeg).unwrap();
+        assert_eq!((photo.width(), photo.height()), (16, 16));
+        assert!(photo
+            .pixels
+            .iter()
+            .all(|&value| (value - 64.0 / 255.0).abs() < 0.02));
+
+        let pgm = Raster::decode("P2\n# градієнт\n3 1\n4\n0 2 4\n".as_bytes()).unwrap();
+        assert_eq!(pgm.pixels, [0.0, 0.5, 1.0]);
+        let pbm = Raster::decode(b"P1 2 1 10").unwrap();
+        assert_eq!(pbm.pixels, [0.0, 1.0], "У PBM одиниця — чорний");
+        let ppm = Raster::decode(b"P6 1 1 255 \xff\xff\xff").unwrap();
+        assert!((ppm.luminance(0, 0) - 1.0).abs() < 1e-9);
+
+        assert!(matches!(
+            Raster::decode(b"GIF89a"),
+            Err(RasterError::UnsupportedFormat)
+        ));
+        assert!(matches!(
+            Raster::decode(&gray[..gray.len() - 20]),
+            Err(RasterError::Image(_))
+        ));
+
+        let ramp = Raster::from_fn(2, 1, |x, _| x as f64);
+        assert_eq!(ramp.sample(1.0, 0.5), 0.5, "Посередині між пікселями");
+        assert_eq!(ramp.sample(-5.0, 0.5), 0.0, "За межами — край");
+    }
+}
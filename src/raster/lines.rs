@@ -0,0 +1,434 @@
+use std::f64::consts::TAU;
+
+use geo::{Coord, LineString, MultiLineString};
+
+use super::Raster;
+use crate::drawing::Drawing;
+
+/// Темнота, нижче якої аркуш вважається білим і перо піднімається.
+const WHITE: f64 = 0.05;
+
+/// Кути шарів штрихування (в градусах) у порядку, в якому вони вмикаються з темнотою.
+const HATCH_ANGLES: [f64; 4] = [45.0, 135.0, 0.0, 90.0];
+
+/// Поріг градієнта Собеля, вище якого піксель вважається краєм.
+const EDGE_THRESHOLD: f64 = 0.5;
+
+/// Найкоротший ланцюжок пікселів краю, що стає шляхом.
+const MIN_EDGE_PIXELS: usize = 3;
+
+/// Спосіб перетворення зображення на лінії.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RasterStyle {
+    Spiral,     // Спіраль від центру, що хвилюється сильніше на темних місцях.
+    Squiggle,   // Горизонтальні рядки хвиль, амплітуда яких зростає з темнотою.
+    Crosshatch, // Шари штрихування під різними кутами, що додаються з темнотою.
+    Edges,      // Контури за різкими перепадами яскравості.
+}
+
+/// Налаштування перетворення зображення на лінії.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RasterOptions {
+    pub style: RasterStyle, // Спосіб перетворення.
+    pub width: f64,         // Ширина малюнка (в мм); висота зберігає пропорції зображення.
+    pub spacing: f64,       // Відстань між рядками, витками чи штрихами (в мм).
+}
+
+impl Default for RasterOptions {
+    fn default() -> Self {
+        RasterOptions {
+            style: RasterStyle::Squiggle,
+            width: 150.0,
+            spacing: 1.0,
+        }
+    }
+}
+
+/// Зображення, розміщене на аркуші: перетворює міліметри на пікселі.
+struct Canvas<'a> {
+    raster: &'a Raster, // Зображення.
+    scale: f64,         // Пікселів на міліметр.
+    width: f64,         // Ширина малюнка (в мм).
+    height: f64,        // Висота малюнка (в мм).
+}
+
+impl Canvas<'_> {
+    /// Темнота 0 (білий)..1 (чорний) у точці малюнка.
+    fn darkness(&self, point: Coord<f64>) -> f64 {
+        1.0 - self
+            .raster
+            .sample(point.x * self.scale, point.y * self.scale)
+    }
+
+    /// Чи лежить точка на малюнку.
+    fn contains(&self, point: Coord<f64>) -> bool {
+        (0.0..=self.width).contains(&point.x) && (0.0..=self.height).contains(&point.y)
+    }
+}
+
+/// Збирає шляхи з точок, піднімаючи перо там, де `keep` не виконується.
+struct Pen {
+    paths: Vec<LineString<f64>>, // Завершені шляхи.
+    current: Vec<Coord<f64>>,    // Точки шляху, що малюється.
+}
+
+impl Pen {
+    /// Створює перо без шляхів.
+    fn new() -> Self {
+        Pen {
+            paths: vec![],
+            current: vec![],
+        }
+    }
+
+    /// Додає точку до поточного шляху або завершує його, якщо точку не треба малювати.
+    fn visit(&mut self, point: Coord<f64>, keep: bool) {
+        match keep {
+            true => self.current.push(point),
+            false => self.lift(),
+        }
+    }
+
+    /// Завершує поточний шлях, якщо в ньому є хоч один відрізок.
+    fn lift(&mut self) {
+        let points = std::mem::take(&mut self.current);
+        if points.len() >= 2 {
+            self.paths.push(LineString(points));
+        }
+    }
+
+    /// Завершує останній шлях і повертає всі шляхи.
+    fn finish(mut self) -> Vec<LineString<f64>> {
+        self.lift();
+        self.paths
+    }
+}
+
+/// Архімедова спіраль від центру, радіус якої хвилюється на темних місцях.
+fn spiral(canvas: &Canvas, spacing: f64) -> Vec<LineString<f64>> {
+    let center = Coord {
+        x: canvas.width / 2.0,
+        y: canvas.height / 2.0,
+    };
+    let max_radius = canvas.width.hypot(canvas.height) / 2.0;
+    let (step, wavelength) = (spacing / 10.0, spacing / 2.0);
+    let mut pen = Pen::new();
+    let (mut angle, mut phase) = (0.0, 0.0);
+    loop {
+        let radius = spacing * angle / TAU;
+        if radius > max_radius {
+            break;
+        }
+        let direction = Coord {
+            x: angle.cos(),
+            y: angle.sin(),
+        };
+        let base = center + direction * radius;
+        let darkness = canvas.darkness(base);
+        let amplitude = darkness * spacing * 0.45;
+        let point = center + direction * (radius + amplitude * f64::sin(phase));
+        pen.visit(point, canvas.contains(base) && darkness >= WHITE);
+        angle += step / radius.max(spacing);
+        phase += TAU * step / wavelength;
+    }
+    pen.finish()
+}
+
+/// Рядки хвиль зліва направо й назад, амплітуда яких зростає з темнотою.
+fn squiggle(canvas: &Canvas, spacing: f64) -> Vec<LineString<f64>> {
+    let (step, wavelength) = (spacing / 10.0, spacing * 0.6);
+    let mut pen = Pen::new();
+    let rows = (canvas.height / spacing).floor() as usize;
+    let samples = (canvas.width / step).ceil() as usize;
+    for row in 0..rows {
+        let y = (row as f64 + 0.5) * spacing;
+        let mut phase = 0.0;
+        for sample in 0..=samples {
+            // Парні рядки йдуть праворуч, непарні — ліворуч, тож переїзди короткі
+            let index = match row % 2 {
+                0 => sample,
+                _ => samples - sample,
+            };
+            let x = (index as f64 * step).min(canvas.width);
+            let darkness = canvas.darkness(Coord { x, y });
+            let amplitude = darkness * spacing * 0.45;
+            pen.visit(
+                Coord {
+                    x,
+                    y: y + amplitude * f64::sin(phase),
+                },
+                darkness >= WHITE,
+            );
+            phase += TAU * step / wavelength;
+        }
+        pen.lift();
+    }
+    pen.finish()
+}
+
+/// Шари паралельних штрихів, кожен з яких малюється там, де темнота перевищує його поріг.
+fn crosshatch(canvas: &Canvas, spacing: f64) -> Vec<LineString<f64>> {
+    let corners = [
+        Coord { x: 0.0, y: 0.0 },
+        Coord {
+            x: canvas.width,
+            y: 0.0,
+        },
+        Coord {
+            x: 0.0,
+            y: canvas.height,
+        },
+        Coord {
+            x: canvas.width,
+            y: canvas.height,
+        },
+    ];
+    let step = spacing / 4.0;
+    let mut pen = Pen::new();
+    for (layer, angle) in HATCH_ANGLES.iter().enumerate() {
+        let threshold = (layer + 1) as f64 / (HATCH_ANGLES.len() + 1) as f64;
+        let (sin, cos) = angle.to_radians().sin_cos();
+        let (along, across) = (Coord { x: cos, y: sin }, Coord { x: -sin, y: cos });
+        let project = |axis: Coord<f64>| {
+            corners
+                .iter()
+                .map(|corner| corner.x * axis.x + corner.y * axis.y)
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), value| {
+                    (low.min(value), high.max(value))
+                })
+        };
+        let ((start, end), (low, high)) = (project(along), project(across));
+        let lines = ((high - low) / spacing).floor() as usize;
+        let samples = ((end - start) / step).ceil() as usize;
+        for line in 0..lines {
+            let offset = low + (line as f64 + 0.5) * spacing;
+            for sample in 0..=samples {
+                let index = match line % 2 {
+                    0 => sample,
+                    _ => samples - sample,
+                };
+                let t = (start + index as f64 * step).min(end);
+                let point = across * offset + along * t;
+                pen.visit(
+                    point,
+                    canvas.contains(point) && canvas.darkness(point) > threshold,
+                );
+            }
+            pen.lift();
+        }
+    }
+    pen.finish()
+}
+
+/// Контури за перепадами яскравості: градієнт Собеля з потоншенням до одного пікселя.
+fn edges(canvas: &Canvas) -> Vec<LineString<f64>> {
+    let raster = canvas.raster;
+    let (width, height) = (raster.width(), raster.height());
+    let at = |x: usize, y: usize| raster.luminance(x.min(width - 1), y.min(height - 1));
+    let mut gradient = vec![(0.0, 0.0); width * height];
+    for y in 0..height {
+        for x in 0..width {
+            let (left, up) = (x.saturating_sub(1), y.saturating_sub(1));
+            let gx = at(x + 1, up) + 2.0 * at(x + 1, y) + at(x + 1, y + 1)
+                - at(left, up)
+                - 2.0 * at(left, y)
+                - at(left, y + 1);
+            let gy = at(left, y + 1) + 2.0 * at(x, y + 1) + at(x + 1, y + 1)
+                - at(left, up)
+                - 2.0 * at(x, up)
+                - at(x + 1, up);
+            gradient[y * width + x] = (gx, gy);
+        }
+    }
+    let magnitude = |x: isize, y: isize| {
+        if x < 0 || y < 0 || x >= width as isize || y >= height as isize {
+            return 0.0;
+        }
+        let (gx, gy): (f64, f64) = gradient[y as usize * width + x as usize];
+        gx.hypot(gy)
+    };
+
+    // Край залишається лише там, де градієнт найбільший упоперек краю
+    let mut edge = vec![false; width * height];
+    for y in 0..height as isize {
+        for x in 0..width as isize {
+            let (gx, gy) = gradient[y as usize * width + x as usize];
+            let value = gx.hypot(gy);
+            if value < EDGE_THRESHOLD {
+                continue;
+            }
+            let angle = gy.atan2(gx).to_degrees().rem_euclid(180.0);
+            let (dx, dy) = match angle {
+                a if !(22.5..157.5).contains(&a) => (1, 0),
+                a if a < 67.5 => (1, 1),
+                a if a < 112.5 => (0, 1),
+                _ => (-1, 1),
+            };
+            edge[y as usize * width + x as usize] =
+                value >= magnitude(x + dx, y + dy) && value >= magnitude(x - dx, y - dy);
+        }
+    }
+
+    // Сусідні пікселі краю з'єднуються в ланцюжки
+    let mut paths = vec![];
+    for start in 0..edge.len() {
+        if !edge[start] {
+            continue;
+        }
+        let mut chain = vec![start];
+        edge[start] = false;
+        let mut current = start;
+        'walk: loop {
+            let (x, y) = ((current % width) as isize, (current / width) as isize);
+            for (dx, dy) in [
+                (1, 0),
+                (1, 1),
+                (0, 1),
+                (-1, 1),
+                (-1, 0),
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+            ] {
+                let (nx, ny) = (x + dx, y + dy);
+                if nx < 0 || ny < 0 || nx >= width as isize || ny >= height as isize {
+                    continue;
+                }
+                let next = ny as usize * width + nx as usize;
+                if edge[next] {
+                    edge[next] = false;
+                    chain.push(next);
+                    current = next;
+                    continue 'walk;
+                }
+            }
+            break;
+        }
+        if chain.len() >= MIN_EDGE_PIXELS {
+            let points = chain.iter().map(|&pixel| Coord {
+                x: ((pixel % width) as f64 + 0.5) / canvas.scale,
+                y: ((pixel / width) as f64 + 0.5) / canvas.scale,
+            });
+            paths.push(LineString(points.collect()));
+        }
+    }
+    paths
+}
+
+impl Raster {
+    /// Перетворює зображення на лінії для плотера.
+    ///
+    /// Темніші місця отримують більше чорнила: сильніші хвилі спіралі чи рядків або більше
+    /// шарів штрихування. Спосіб `Edges` натомість обводить різкі перепади яскравості й не
+    /// залежить від `spacing`.
+    ///
+    /// # Аргументи
+    /// * `options` - спосіб перетворення, розмір малюнка та відстань між лініями.
+    ///
+    /// # Повертає
+    /// * `Drawing` - малюнок шириною `options.width` з висотою за пропорціями зображення.
+    pub fn to_lines(&self, options: &RasterOptions) -> Drawing {
+        let scale = self.width as f64 / options.width;
+        let canvas = Canvas {
+            raster: self,
+            scale,
+            width: options.width,
+            height: self.height as f64 / scale,
+        };
+        let spacing = options.spacing;
+        let paths = match options.style {
+            RasterStyle::Spiral => spiral(&canvas, spacing),
+            RasterStyle::Squiggle => squiggle(&canvas, spacing),
+            RasterStyle::Crosshatch => crosshatch(&canvas, spacing),
+            RasterStyle::Edges => edges(&canvas),
+        };
+        Drawing::new((canvas.width, canvas.height), MultiLineString(paths))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::EuclideanLength;
+
+    #[test]
+    fn test_darker_images_get_more_ink() {
+        // Ліва половина біла, права — градієнт до чорного
+        let raster = Raster::from_fn(40, 20, |x, _| match x < 20 {
+            true => 1.0,
+            false => 1.0 - (x - 20) as f64 / 19.0,
+        });
+        let ink = |drawing: &Drawing, inside: fn(&Coord<f64>) -> bool| -> f64 {
+            drawing
+                .paths
+                .iter()
+                .filter(|line| line.0.iter().all(inside))
+                .map(|line| line.euclidean_length())
+                .sum()
+        };
+
+        for style in [
+            RasterStyle::Spiral,
+            RasterStyle::Squiggle,
+            RasterStyle::Crosshatch,
+        ] {
+            let drawing = raster.to_lines(&RasterOptions {
+                style,
+                width: 40.0,
+                spacing: 1.0,
+            });
+            assert_eq!(drawing.bounds, (40.0, 20.0));
+            assert!(!drawing.paths.0.is_empty(), "{:?} малює щось", style);
+            let white = ink(&drawing, |point| point.x < 19.0);
+            assert!(white < 1e-9, "{:?} не малює на білому: {}", style, white);
+            for line in drawing.paths.iter() {
+                for point in line.0.iter() {
+                    assert!(
+                        (-1.0..=41.0).contains(&point.x) && (-1.0..=21.0).contains(&point.y),
+                        "{:?}: точка {:?} поза малюнком",
+                        style,
+                        point
+                    );
+                }
+            }
+        }
+
+        // Штрихування темної частини густіше за світлу
+        let hatch = raster.to_lines(&RasterOptions {
+            style: RasterStyle::Crosshatch,
+            width: 40.0,
+            spacing: 1.0,
+        });
+        let light = ink(&hatch, |point| point.x > 20.0 && point.x < 30.0);
+        let dark = ink(&hatch, |point| point.x >= 30.0);
+        assert!(
+            dark > 2.0 * light,
+            "Темне {} проти світлого {}",
+            dark,
+            light
+        );
+
+        // Межа білого квадрата на чорному тлі
+        let square = Raster::from_fn(20, 20, |x, y| {
+            match (5..15).contains(&x) && (5..15).contains(&y) {
+                true => 1.0,
+                false => 0.0,
+            }
+        });
+        let outline = square.to_lines(&RasterOptions {
+            style: RasterStyle::Edges,
+            width: 20.0,
+            spacing: 1.0,
+        });
+        let length: f64 = outline
+            .paths
+            .iter()
+            .map(|line| line.euclidean_length())
+            .sum();
+        assert!(
+            (30.0..90.0).contains(&length),
+            "Контур квадрата 10 мм, отримано {} мм",
+            length
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use super::{Raster, RasterError};
+
+/// Читач заголовка Netpbm: числа, розділені пробілами, з коментарями від `#`.
+struct Header<'a> {
+    bytes: &'a [u8], // Увесь файл.
+    position: usize, // Номер наступного байта.
+}
+
+impl Header<'_> {
+    /// Читає наступне десяткове число заголовка.
+    fn number(&mut self) -> Result<usize, RasterError> {
+        loop {
+            match self.bytes.get(self.position) {
+                Some(b'#') => {
+                    while self
+                        .bytes
+                        .get(self.position)
+                        .is_some_and(|&byte| byte != b'\n')
+                    {
+                        self.position += 1;
+                    }
+                }
+                Some(byte) if byte.is_ascii_whitespace() => self.position += 1,
+                _ => break,
+            }
+        }
+        let start = self.position;
+        while self
+            .bytes
+            .get(self.position)
+            .is_some_and(u8::is_ascii_digit)
+        {
+            self.position += 1;
+        }
+        std::str::from_utf8(&self.bytes[start..self.position])
+            .ok()
+            .and_then(|digits| digits.parse().ok())
+            .ok_or(RasterError::Corrupt("Netpbm header"))
+    }
+}
+
+/// Розбирає файл Netpbm (PBM, PGM чи PPM, текстовий або двійковий) у яскравість пікселів.
+pub(super) fn decode(bytes: &[u8]) -> Result<Raster, RasterError> {
+    let kind = match bytes {
+        [b'P', kind @ b'1'..=b'6', ..] => *kind,
+        _ => return Err(RasterError::UnsupportedFormat),
+    };
+    let mut header = Header { bytes, position: 2 };
+    let (width, height) = (header.number()?, header.number()?);
+    let bitmap = matches!(kind, b'1' | b'4');
+    let max = match bitmap {
+        true => 1,
+        false => header.number()?,
+    };
+    if width == 0 || height == 0 || max == 0 || max > 65535 {
+        return Err(RasterError::Corrupt("Netpbm header"));
+    }
+    let channels = match kind {
+        b'3' | b'6' => 3,
+        _ => 1,
+    };
+    let count = width
+        .checked_mul(height)
+        .and_then(|pixels| pixels.checked_mul(channels))
+        .ok_or(RasterError::Unsupported("Netpbm image size"))?;
+
+    let samples: Vec<usize> = match kind {
+        b'1' | b'2' | b'3' => (0..count)
+            .map(|_| match bitmap {
+                // Цифри бітової карти можуть стояти без пробілів між ними
+                true => {
+                    while header
+                        .bytes
+                        .get(header.position)
+                        .is_some_and(|byte| !byte.is_ascii_digit())
+                    {
+                        header.position += 1;
+                    }
+                    let digit = header.bytes.get(header.position).copied();
+                    header.position += 1;
+                    digit
+                        .map(|digit| (digit - b'0') as usize)
+                        .ok_or(RasterError::Corrupt("Netpbm data"))
+                }
+                false => header.number(),
+            })
+            .collect::<Result<_, _>>()?,
+        _ => {
+            // Після заголовка двійкового файлу стоїть рівно один пробіл
+            let data = bytes
+                .get(header.position + 1..)
+                .ok_or(RasterError::Corrupt("Netpbm data"))?;
+            match kind {
+                b'4' => {
+                    let stride = width.div_ceil(8);
+                    if data.len() < stride * height {
+                        return Err(RasterError::Corrupt("Netpbm data"));
+                    }
+                    (0..count)
+                        .map(|index| {
+                            let (row, column) = (index / width, index % width);
+                            ((data[row * stride + column / 8] >> (7 - column % 8)) & 1) as usize
+                        })
+                        .collect()
+                }
+                _ if max > 255 => data
+                    .chunks_exact(2)
+                    .take(count)
+                    .map(|pair| u16::from_be_bytes([pair[0], pair[1]]) as usize)
+                    .collect(),
+                _ => data.iter().take(count).map(|&byte| byte as usize).collect(),
+            }
+        }
+    };
+    if samples.len() < count {
+        return Err(RasterError::Corrupt("Netpbm data"));
+    }
+
+    let pixels = samples
+        .chunks(channels)
+        .map(|pixel| {
+            let values: Vec<f64> = pixel
+                .iter()
+                .map(|&value| (value.min(max) as f64) / max as f64)
+                .collect();
+            match (bitmap, channels) {
+                // У бітовій карті одиниця — чорний піксель
+                (true, _) => 1.0 - values[0],
+                (false, 3) => Raster::luma([values[0], values[1], values[2]]),
+                _ => values[0],
+            }
+        })
+        .collect();
+    Ok(Raster::new(width, height, pixels))
+}
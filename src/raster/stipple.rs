@@ -0,0 +1,362 @@
+use std::time::Duration;
+
+use geo::{Coord, LineString, MultiLineString, Rect};
+use rstar::primitives::GeomWithData;
+use rstar::RTree;
+
+use super::Raster;
+use crate::drawing::order::PathOrder;
+use crate::drawing::packing::CirclePacking;
+use crate::drawing::roughen::splitmix;
+use crate::drawing::sample::{Mark, Random};
+use crate::drawing::Drawing;
+
+/// Найбільша кількість точок зображення, за якими шукаються центри ваги комірок.
+const MAX_SAMPLES: usize = 250_000;
+
+/// Скільки разів на кожну точку пробувати кинути нову точку методом Пуассона.
+const POISSON_ATTEMPTS: usize = 30;
+
+/// Точка з номером у просторовому індексі.
+type Site = GeomWithData<[f64; 2], usize>;
+
+/// Спосіб розставлення точок.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StippleMethod {
+    Voronoi, // Зважена релаксація Ллойда: точки зсуваються до центрів ваги своїх комірок Вороного.
+    Poisson, // Диск Пуассона: точки кидаються випадково на відстані, що зменшується з темнотою.
+}
+
+/// Налаштування пунктиру з зображення.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StippleOptions {
+    pub method: StippleMethod, // Спосіб розставлення точок.
+    pub count: usize,          // Кількість точок.
+    pub iterations: usize,     // Кроки релаксації Ллойда; для Пуассона не вживаються.
+    pub gamma: f64,            // Степінь темноти: більше 1 збирає точки в найтемніших місцях.
+    pub width: f64,            // Ширина малюнка (в мм); висота зберігає пропорції зображення.
+    pub line: bool,            // З'єднати точки однією лінією замість окремих рисок.
+    pub seed: u64,             // Зерно випадкового розставлення.
+}
+
+impl Default for StippleOptions {
+    fn default() -> Self {
+        StippleOptions {
+            method: StippleMethod::Voronoi,
+            count: 5000,
+            iterations: 30,
+            gamma: 1.0,
+            width: 150.0,
+            line: false,
+            seed: 0,
+        }
+    }
+}
+
+/// Вага зображення в точках сітки: темнота в степені `gamma` та положення в мм.
+struct Density {
+    points: Vec<Coord<f64>>, // Точки сітки (в мм).
+    weights: Vec<f64>,       // Вага кожної точки.
+    cell: f64,               // Крок сітки (в мм).
+    max: f64,                // Найбільша вага.
+}
+
+impl Density {
+    fn new(raster: &Raster, options: &StippleOptions) -> Density {
+        let pixels = raster.width() * raster.height();
+        let stride = ((pixels as f64 / MAX_SAMPLES as f64).sqrt().ceil() as usize).max(1);
+        let scale = options.width / raster.width() as f64;
+        let (mut points, mut weights) = (vec![], vec![]);
+        for y in (0..raster.height()).step_by(stride) {
+            for x in (0..raster.width()).step_by(stride) {
+                let (u, v) = (
+                    x as f64 + stride as f64 / 2.0,
+                    y as f64 + stride as f64 / 2.0,
+                );
+                let darkness = 1.0 - raster.sample(u, v);
+                points.push(Coord {
+                    x: u * scale,
+                    y: v * scale,
+                });
+                weights.push(darkness.clamp(0.0, 1.0).powf(options.gamma));
+            }
+        }
+        let max = weights.iter().copied().fold(0.0, f64::max);
+        Density {
+            points,
+            weights,
+            cell: stride as f64 * scale,
+            max,
+        }
+    }
+
+    /// Випадкова точка, розподілена за вагою, зі зсувом у межах своєї комірки сітки.
+    fn draw(&self, random: &mut Random) -> Option<Coord<f64>> {
+        for _ in 0..self.points.len().max(1000) {
+            let index = (random.next() * self.points.len() as f64) as usize;
+            if random.next() * self.max < self.weights[index] {
+                let jitter = Coord {
+                    x: random.next() - 0.5,
+                    y: random.next() - 0.5,
+                };
+                return Some(self.points[index] + jitter * self.cell);
+            }
+        }
+        None
+    }
+}
+
+/// Зважена релаксація Ллойда на сітці зображення.
+fn voronoi(density: &Density, options: &StippleOptions, random: &mut Random) -> Vec<Coord<f64>> {
+    let mut sites: Vec<Coord<f64>> = (0..options.count)
+        .map_while(|_| density.draw(random))
+        .collect();
+    for _ in 0..options.iterations {
+        let tree = RTree::bulk_load(
+            sites
+                .iter()
+                .enumerate()
+                .map(|(index, site)| Site::new([site.x, site.y], index))
+                .collect(),
+        );
+        let mut sums = vec![(Coord::zero(), 0.0); sites.len()];
+        for (point, &weight) in density.points.iter().zip(&density.weights) {
+            if weight <= 0.0 {
+                continue;
+            }
+            if let Some(nearest) = tree.nearest_neighbor(&[point.x, point.y]) {
+                let (sum, total) = &mut sums[nearest.data];
+                *sum = *sum + *point * weight;
+                *total += weight;
+            }
+        }
+        // Точка без ваги в комірці переноситься туди, де її бракує
+        for (site, (sum, total)) in sites.iter_mut().zip(sums) {
+            *site = match total > 0.0 {
+                true => sum / total,
+                false => density.draw(random).unwrap_or(*site),
+            };
+        }
+    }
+    sites
+}
+
+/// Диск Пуассона зі змінним радіусом: у темних місцях точки стоять щільніше.
+fn poisson(density: &Density, options: &StippleOptions, random: &mut Random) -> Vec<Coord<f64>> {
+    // Середня відстань між точками, якщо розподілити їх за вагою всього зображення
+    let area: f64 = density.weights.iter().sum::<f64>() * density.cell * density.cell;
+    let spacing = (area / options.count.max(1) as f64).sqrt();
+    let radius = |weight: f64| 0.75 * spacing / weight.max(1e-3).sqrt().min(1.0);
+
+    let grid = RTree::bulk_load(
+        density
+            .points
+            .iter()
+            .enumerate()
+            .map(|(index, point)| Site::new([point.x, point.y], index))
+            .collect(),
+    );
+    let weight_near = |point: Coord<f64>| {
+        grid.nearest_neighbor(&[point.x, point.y])
+            .map_or(0.0, |site| {
+                density.weights[site.data] / density.max.max(1e-9)
+            })
+    };
+
+    let mut tree: RTree<Site> = RTree::new();
+    let mut sites = vec![];
+    for _ in 0..options.count * POISSON_ATTEMPTS {
+        if sites.len() >= options.count {
+            break;
+        }
+        let Some(candidate) = density.draw(random) else {
+            break;
+        };
+        let reach = radius(weight_near(candidate));
+        let crowded = tree
+            .locate_within_distance([candidate.x, candidate.y], reach * reach)
+            .next()
+            .is_some();
+        if !crowded {
+            tree.insert(Site::new([candidate.x, candidate.y], sites.len()));
+            sites.push(candidate);
+        }
+    }
+    sites
+}
+
+impl Raster {
+    /// Розставляє точки пунктиру так, щоб їх густота відповідала темноті зображення.
+    ///
+    /// # Аргументи
+    /// * `options` - спосіб, кількість точок, кроки релаксації та ширина малюнка.
+    ///
+    /// # Повертає
+    /// * `Vec<Coord<f64>>` - точки (в мм); менше `count`, якщо зображення майже біле.
+    pub fn stipples(&self, options: &StippleOptions) -> Vec<Coord<f64>> {
+        let density = Density::new(self, options);
+        if density.max <= 0.0 {
+            return vec![];
+        }
+        let mut random = Random(splitmix(options.seed));
+        match options.method {
+            StippleMethod::Voronoi => voronoi(&density, options, &mut random),
+            StippleMethod::Poisson => poisson(&density, options, &mut random),
+        }
+    }
+
+    /// Перетворює зображення на пунктир: окремі риски пера або одну лінію через усі точки.
+    ///
+    /// Лінія проходить точки в порядку, знайденому тим самим впорядкуванням, що скорочує
+    /// переїзди малюнка, тож виходить наближений розв'язок задачі комівояжера.
+    ///
+    /// # Аргументи
+    /// * `options` - налаштування пунктиру.
+    ///
+    /// # Повертає
+    /// * `Drawing` - малюнок шириною `options.width` з висотою за пропорціями зображення.
+    pub fn to_stipples(&self, options: &StippleOptions) -> Drawing {
+        let bounds = (
+            options.width,
+            options.width * self.height as f64 / self.width as f64,
+        );
+        let sites = self.stipples(options);
+        let paths = match options.line {
+            false => sites
+                .iter()
+                .flat_map(|site| Mark::Dot.paths(*site))
+                .collect(),
+            true => {
+                let dots = sites.iter().map(|&site| LineString(vec![site])).collect();
+                let tour = Drawing::new(bounds, MultiLineString(dots)).optimize(&PathOrder {
+                    reverse: false,
+                    improve: Some(Duration::from_secs(1)),
+                });
+                let points: Vec<Coord<f64>> = tour.paths.iter().map(|dot| dot.0[0]).collect();
+                match points.len() >= 2 {
+                    true => vec![LineString(points)],
+                    false => vec![],
+                }
+            }
+        };
+        Drawing::new(bounds, MultiLineString(paths))
+    }
+
+    /// Перетворює зображення на пакування кіл: темні місця вкриваються дрібними щільними
+    /// колами, а білі лишаються порожніми.
+    ///
+    /// # Аргументи
+    /// * `packing` - радіуси, проміжок і зерно пакування.
+    /// * `width` - ширина малюнка (в мм); висота зберігає пропорції зображення.
+    /// * `gamma` - степінь темноти: більше 1 лишає кола лише в найтемніших місцях.
+    ///
+    /// # Повертає
+    /// * `Drawing` - кола в межах малюнка.
+    pub fn to_circles(&self, packing: &CirclePacking, width: f64, gamma: f64) -> Drawing {
+        let scale = width / self.width as f64;
+        let bounds = (width, self.height as f64 * scale);
+        let region = Rect::new(Coord::zero(), Coord::from(bounds)).to_polygon();
+        let darkness = |point: Coord<f64>| {
+            (1.0 - self.sample(point.x / scale, point.y / scale))
+                .clamp(0.0, 1.0)
+                .powf(gamma)
+        };
+        let paths = packing
+            .pack(&region, Some(&darkness))
+            .into_iter()
+            .flat_map(|(center, radius)| Mark::Circle(radius).paths(center))
+            .collect();
+        Drawing::new(bounds, MultiLineString(paths))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drawing::distance;
+    use geo::BoundingRect;
+
+    /// Загальна довжина лінії через точки в заданому порядку.
+    fn tour_length(points: &[Coord<f64>]) -> f64 {
+        points
+            .windows(2)
+            .map(|pair| distance(pair[0], pair[1]))
+            .sum()
+    }
+
+    #[test]
+    fn test_stipples_follow_darkness() {
+        // Ліва половина чорна, права — світло-сіра
+        let raster = Raster::from_fn(60, 30, |x, _| match x < 30 {
+            true => 0.0,
+            false => 0.8,
+        });
+        for method in [StippleMethod::Voronoi, StippleMethod::Poisson] {
+            let options = StippleOptions {
+                method,
+                count: 400,
+                iterations: 10,
+                width: 60.0,
+                ..StippleOptions::default()
+            };
+            let sites = raster.stipples(&options);
+            assert!(sites.len() > 200, "{:?}: {} точок", method, sites.len());
+            let dark = sites.iter().filter(|site| site.x < 30.0).count();
+            assert!(
+                dark > 3 * (sites.len() - dark),
+                "{:?}: у темній половині {} з {}",
+                method,
+                dark,
+                sites.len()
+            );
+            assert!(sites
+                .iter()
+                .all(|site| (-1.0..=61.0).contains(&site.x) && (-1.0..=31.0).contains(&site.y)));
+            assert_eq!(
+                sites,
+                raster.stipples(&options),
+                "Те саме зерно — ті самі точки"
+            );
+        }
+
+        let options = StippleOptions {
+            count: 200,
+            iterations: 5,
+            width: 60.0,
+            ..StippleOptions::default()
+        };
+        let dots = raster.to_stipples(&options);
+        assert_eq!(dots.bounds, (60.0, 30.0));
+        assert_eq!(dots.paths.0.len(), 200, "Кожна точка — окрема риска");
+
+        let line = raster.to_stipples(&StippleOptions {
+            line: true,
+            ..options
+        });
+        assert_eq!(line.paths.0.len(), 1, "Одна лінія через усі точки");
+        let tour = &line.paths.0[0].0;
+        assert_eq!(tour.len(), 200);
+        let sites = raster.stipples(&options);
+        assert!(
+            tour_length(tour) < tour_length(&sites) / 2.0,
+            "Впорядкована лінія коротша за точки в порядку розставлення"
+        );
+        assert!(Raster::from_fn(4, 4, |_, _| 1.0)
+            .stipples(&options)
+            .is_empty());
+
+        let circles = raster.to_circles(&CirclePacking::default(), 60.0, 1.0);
+        assert_eq!(circles.bounds, (60.0, 30.0));
+        let dark = circles
+            .paths
+            .iter()
+            .filter(|circle| circle.bounding_rect().unwrap().center().x < 30.0)
+            .count();
+        assert!(
+            dark > 3 * (circles.paths.0.len() - dark),
+            "Кола збираються в темній половині: {} з {}",
+            dark,
+            circles.paths.0.len()
+        );
+    }
+}
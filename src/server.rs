@@ -0,0 +1,478 @@
+use std::io::{self, Read};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, ReadWrite, Request, Response, Server};
+use tracing::{error, info, warn};
+use tungstenite::handshake::derive_accept_key;
+use tungstenite::protocol::Role;
+use tungstenite::{Message, WebSocket};
+
+use crate::axidraw::{AxiDrawModel, Axidraw};
+use crate::control::PlotControl;
+use crate::drawing::Drawing;
+use crate::error::Error;
+use crate::paper::Paper;
+use crate::tr;
+
+/// Найбільший розмір SVG, який приймає сервер (в байтах).
+const MAX_BODY_SIZE: u64 = 32 * 1024 * 1024;
+
+/// Інтервал між подіями потоку стану.
+const STREAM_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Кількість інтервалів без змін, після яких клієнту надсилається ping.
+const STREAM_PING_INTERVALS: u32 = 50;
+
+/// Завдання, яке виконує потік плотера.
+#[derive(Debug)]
+enum Job {
+    Plot(Drawing), // Намалювати малюнок.
+    Jog(f64, f64), // Відносно перемістити каретку з піднятим пером (в мм).
+    Home,          // Повернути каретку на початкову позицію.
+}
+
+/// Стан, спільний для обробників запитів.
+#[derive(Clone)]
+struct Shared {
+    control: PlotControl,  // Пульт плотера.
+    jobs: Sender<Job>,     // Черга завдань потоку плотера.
+    busy: Arc<AtomicBool>, // Прапорець завдання, що виконується.
+    model: AxiDrawModel,   // Модель плотера.
+    paper: Paper,          // Аркуш, на якому виконується малювання.
+}
+
+/// Відповідь обробника: HTTP-код і JSON-тіло.
+type Reply = (u16, Value);
+
+/// Запускає HTTP-сервер для керування плотером через мережу.
+///
+/// Плотер переходить у власність окремого потоку, який по черзі виконує завдання,
+/// а сервер лише приймає запити й повертає стан. Доступні кінцеві точки:
+///
+/// * `GET /status` - стан плотера та малюнка.
+/// * `GET /events` - WebSocket з подіями стану під час малювання.
+/// * `POST /plot` - намалювати SVG з тіла запиту (`?fit=true` підганяє його під аркуш).
+/// * `POST /pause`, `POST /resume`, `POST /cancel` - керування малюнком.
+/// * `POST /jog?dx=10&dy=-5` - відносне переміщення каретки (в мм).
+/// * `POST /home` - повернення на початкову позицію.
+///
+/// # Аргументи
+/// * `axidraw` - підключений плотер.
+/// * `host` - адреса, на якій слухає сервер.
+/// * `port` - порт сервера.
+///
+/// # Повертає
+/// * `Result<(), Error>` - помилка, якщо сервер не вдалося запустити.
+pub fn serve(axidraw: Axidraw, host: &str, port: u16) -> Result<(), Error> {
+    let server = Server::http((host, port)).map_err(|e| {
+        io::Error::other(tr!(
+            "Failed to start server on {}:{}: {}",
+            "Не вдалося запустити сервер на {}:{}: {}",
+            host,
+            port,
+            e
+        ))
+    })?;
+    info!(
+        "{}",
+        tr!(
+            "Server listening on http://{}:{}",
+            "Сервер слухає на http://{}:{}",
+            host,
+            port
+        )
+    );
+
+    let (jobs, queue) = mpsc::channel();
+    let shared = Shared {
+        control: axidraw.control.clone(),
+        jobs,
+        busy: Arc::new(AtomicBool::new(false)),
+        model: axidraw.options.model,
+        paper: axidraw.paper(),
+    };
+
+    let busy = shared.busy.clone();
+    thread::spawn(move || worker(axidraw, queue, busy));
+
+    for request in server.incoming_requests() {
+        handle(&shared, request);
+    }
+
+    Ok(())
+}
+
+/// Потік плотера: виконує завдання з черги одне за одним.
+fn worker(mut axidraw: Axidraw, queue: Receiver<Job>, busy: Arc<AtomicBool>) {
+    for job in queue {
+        let result = match job {
+            Job::Plot(drawing) => axidraw.draw(&drawing),
+            Job::Jog(dx, dy) => axidraw.move_to(dx, dy),
+            Job::Home => axidraw.home(),
+        };
+        if let Err(e) = result {
+            error!(
+                "{}",
+                tr!("Job failed: {}", "Помилка виконання завдання: {}", e)
+            );
+        }
+        busy.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Обробляє один HTTP-запит і надсилає відповідь.
+fn handle(shared: &Shared, mut request: Request) {
+    if *request.method() == Method::Get && request.url() == "/events" {
+        return events(shared, request);
+    }
+
+    let mut body = String::new();
+    let reply = match request
+        .as_reader()
+        .take(MAX_BODY_SIZE)
+        .read_to_string(&mut body)
+    {
+        Ok(_) => route(shared, request.method(), request.url(), &body),
+        Err(e) => (
+            400,
+            json!({
+                "error": tr!(
+                    "Failed to read request body: {}",
+                    "Не вдалося прочитати тіло запиту: {}",
+                    e
+                )
+            }),
+        ),
+    };
+
+    respond(request, reply);
+}
+
+/// Надсилає JSON-відповідь.
+fn respond(request: Request, (status, value): Reply) {
+    let header = Header::from_bytes("Content-Type", "application/json")
+        .expect("Заголовок Content-Type має бути дійсним");
+    let response = Response::from_string(value.to_string())
+        .with_status_code(status)
+        .with_header(header);
+    if let Err(e) = request.respond(response) {
+        warn!(
+            "{}",
+            tr!(
+                "Failed to send response: {}",
+                "Не вдалося надіслати відповідь: {}",
+                e
+            )
+        );
+    }
+}
+
+/// Переводить з'єднання на протокол WebSocket і запускає для нього потік подій стану.
+fn events(shared: &Shared, request: Request) {
+    let key = request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv("Sec-WebSocket-Key"))
+        .map(|header| derive_accept_key(header.value.as_bytes()));
+    let Some(accept) = key else {
+        return respond(
+            request,
+            (
+                400,
+                json!({ "error": tr!("Expected a WebSocket request", "Очікується запит WebSocket") }),
+            ),
+        );
+    };
+
+    let header = Header::from_bytes("Sec-WebSocket-Accept", accept)
+        .expect("Заголовок Sec-WebSocket-Accept має бути дійсним");
+    let stream = request.upgrade("websocket", Response::empty(101).with_header(header));
+    let socket = WebSocket::from_raw_socket(stream, Role::Server, None);
+
+    let shared = shared.clone();
+    thread::spawn(move || stream_events(&shared, socket));
+}
+
+/// Надсилає клієнту стан плотера щоразу, коли він змінюється, доки з'єднання відкрите.
+fn stream_events(shared: &Shared, mut socket: WebSocket<Box<dyn ReadWrite + Send>>) {
+    info!(
+        "{}",
+        tr!(
+            "Client subscribed to status events.",
+            "Клієнт підписався на події стану."
+        )
+    );
+    let mut last = Value::Null;
+    let mut idle = 0;
+
+    loop {
+        let event = status(shared);
+        let message = if event != last {
+            idle = 0;
+            Some(Message::Text(event.to_string()))
+        } else if idle >= STREAM_PING_INTERVALS {
+            // Без змін перевіряємо, чи клієнт ще на зв'язку
+            idle = 0;
+            Some(Message::Ping(Vec::new()))
+        } else {
+            idle += 1;
+            None
+        };
+
+        if let Some(message) = message {
+            if socket.send(message).is_err() {
+                break;
+            }
+        }
+        last = event;
+        thread::sleep(STREAM_INTERVAL);
+    }
+
+    info!(
+        "{}",
+        tr!(
+            "Client unsubscribed from status events.",
+            "Клієнт відключився від подій стану."
+        )
+    );
+}
+
+/// Визначає обробник за методом і шляхом запиту.
+fn route(shared: &Shared, method: &Method, url: &str, body: &str) -> Reply {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    match (method, path) {
+        (Method::Get, "/status") => (200, status(shared)),
+        (Method::Post, "/plot") => plot(shared, query, body),
+        (Method::Post, "/pause") => control(shared, PlotControl::pause),
+        (Method::Post, "/resume") => control(shared, PlotControl::resume),
+        (Method::Post, "/cancel") => control(shared, PlotControl::cancel),
+        (Method::Post, "/jog") => jog(shared, query),
+        (Method::Post, "/home") => submit(shared, Job::Home),
+        (_, "/status" | "/plot" | "/pause" | "/resume" | "/cancel" | "/jog" | "/home") => (
+            405,
+            json!({ "error": tr!("Method not allowed", "Метод не підтримується") }),
+        ),
+        _ => (
+            404,
+            json!({ "error": tr!("Unknown path: {}", "Невідомий шлях: {}", path) }),
+        ),
+    }
+}
+
+/// Формує JSON зі станом плотера та малюнка.
+fn status(shared: &Shared) -> Value {
+    let status = shared.control.status();
+    json!({
+        "state": status.state.name(),
+        "busy": shared.busy.load(Ordering::SeqCst),
+        "current_path": status.current_path,
+        "total_paths": status.total_paths,
+        "position": { "x": status.position.0, "y": status.position.1 },
+        "velocity": status.velocity,
+        "pen_down": status.pen_down,
+        "drawn_length": status.drawn_length,
+        "total_length": status.total_length,
+        "eta": status.eta.map(|eta| eta.as_secs_f64()),
+        "error": status.error,
+        "retries": status.retries,
+        "stall_lifts": status.stall_lifts,
+        "model": shared.model.name(),
+        "paper": {
+            "name": shared.paper.name,
+            "width": shared.paper.width(),
+            "height": shared.paper.height(),
+        },
+    })
+}
+
+/// Розбирає SVG з тіла запиту та ставить малюнок у чергу.
+fn plot(shared: &Shared, query: &str, body: &str) -> Reply {
+    let drawing = match Drawing::from_svg(body) {
+        Ok(drawing) => drawing,
+        Err(e) => return (400, json!({ "error": e.to_string() })),
+    };
+    let drawing = if query_param(query, "fit").is_some_and(|fit| fit == "true" || fit == "1") {
+        match drawing.fit_to_page(&shared.paper) {
+            Ok(drawing) => drawing,
+            Err(e) => return (400, json!({ "error": e.to_string() })),
+        }
+    } else {
+        drawing
+    };
+    if !drawing.fits(&shared.paper) {
+        return (
+            422,
+            json!({
+                "error": tr!(
+                    "Drawing is outside sheet {}",
+                    "Малюнок виходить за межі аркуша {}",
+                    shared.paper.name
+                )
+            }),
+        );
+    }
+    submit(shared, Job::Plot(drawing))
+}
+
+/// Ставить у чергу відносне переміщення каретки.
+fn jog(shared: &Shared, query: &str) -> Reply {
+    let axis = |name: &str| match query_param(query, name) {
+        None => Ok(0.0),
+        Some(value) => value
+            .parse::<f64>()
+            .ok()
+            .filter(|v| v.is_finite())
+            .ok_or_else(|| {
+                tr!(
+                    "Invalid value of parameter '{}': '{}'",
+                    "Недійсне значення параметра '{}': '{}'",
+                    name,
+                    value
+                )
+            }),
+    };
+    match (axis("dx"), axis("dy")) {
+        (Ok(dx), Ok(dy)) => submit(shared, Job::Jog(dx, dy)),
+        (Err(e), _) | (_, Err(e)) => (400, json!({ "error": e })),
+    }
+}
+
+/// Передає запит паузи, відновлення чи скасування поточному завданню.
+fn control(shared: &Shared, action: fn(&PlotControl)) -> Reply {
+    if !shared.busy.load(Ordering::SeqCst) {
+        return (
+            409,
+            json!({ "error": tr!("No job is running", "Немає завдання, що виконується") }),
+        );
+    }
+    action(&shared.control);
+    (200, status(shared))
+}
+
+/// Ставить завдання в чергу, якщо плотер вільний.
+fn submit(shared: &Shared, job: Job) -> Reply {
+    if shared
+        .busy
+        .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+        .is_err()
+    {
+        return (
+            409,
+            json!({ "error": tr!("The plotter is busy with another job", "Плотер зайнятий іншим завданням") }),
+        );
+    }
+    if shared.jobs.send(job).is_err() {
+        shared.busy.store(false, Ordering::SeqCst);
+        return (
+            500,
+            json!({ "error": tr!("The plotter thread has stopped", "Потік плотера зупинено") }),
+        );
+    }
+    (202, status(shared))
+}
+
+/// Повертає значення параметра з рядка запиту.
+fn query_param<'a>(query: &'a str, name: &str) -> Option<&'a str> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shared() -> (Shared, Receiver<Job>) {
+        let (jobs, queue) = mpsc::channel();
+        let shared = Shared {
+            control: PlotControl::new(),
+            jobs,
+            busy: Arc::new(AtomicBool::new(false)),
+            model: AxiDrawModel::V3,
+            paper: Paper::a4(),
+        };
+        (shared, queue)
+    }
+
+    #[test]
+    fn test_routes_queue_jobs_and_reject_when_busy() {
+        let (shared, queue) = shared();
+        let svg = r#"<svg viewBox="0 0 100 100"><line x1="10" y1="10" x2="90" y2="90"/></svg>"#;
+
+        let (code, body) = route(&shared, &Method::Get, "/status", "");
+        assert_eq!(code, 200);
+        assert_eq!(body["state"], "idle");
+        assert_eq!(body["paper"]["name"], "A4");
+
+        // Без завдання пауза неможлива
+        assert_eq!(route(&shared, &Method::Post, "/pause", "").0, 409);
+
+        assert_eq!(route(&shared, &Method::Post, "/plot", svg).0, 202);
+        assert!(matches!(queue.try_recv(), Ok(Job::Plot(_))));
+
+        // Поки малюнок виконується, нові завдання відхиляються
+        assert_eq!(route(&shared, &Method::Post, "/jog?dx=1", "").0, 409);
+        assert_eq!(route(&shared, &Method::Post, "/pause", "").0, 200);
+        assert!(shared.control.is_paused());
+
+        shared.busy.store(false, Ordering::SeqCst);
+        assert_eq!(
+            route(&shared, &Method::Post, "/jog?dx=5&dy=-2.5", "").0,
+            202
+        );
+        assert!(matches!(queue.try_recv(), Ok(Job::Jog(dx, dy)) if dx == 5.0 && dy == -2.5));
+
+        shared.busy.store(false, Ordering::SeqCst);
+        assert_eq!(route(&shared, &Method::Post, "/jog?dx=abc", "").0, 400);
+        assert_eq!(
+            route(
+                &shared,
+                &Method::Post,
+                "/plot",
+                "<svg><path d=\"M x\"/></svg>"
+            )
+            .0,
+            400
+        );
+        assert_eq!(route(&shared, &Method::Get, "/plot", "").0, 405);
+        assert_eq!(route(&shared, &Method::Get, "/unknown", "").0, 404);
+    }
+
+    #[test]
+    fn test_events_stream_status_over_websocket() {
+        let (shared, _queue) = shared();
+        let server = Server::http("127.0.0.1:0").expect("Сервер має запуститися");
+        let addr = server.server_addr().to_ip().expect("Адреса має бути IP");
+        let remote = shared.clone();
+        thread::spawn(move || {
+            if let Ok(request) = server.recv() {
+                handle(&remote, request);
+            }
+        });
+
+        let (mut socket, _) =
+            tungstenite::connect(format!("ws://{}/events", addr)).expect("Клієнт має підключитися");
+        let Message::Text(text) = socket.read().expect("Має надійти подія") else {
+            panic!("Подія має бути текстовою");
+        };
+        let event: Value = serde_json::from_str(&text).expect("Подія має бути JSON");
+        assert_eq!(event["state"], "idle");
+        assert_eq!(event["pen_down"], false);
+
+        // Зміна стану надходить наступною подією
+        shared.control.start(1, 10.0);
+        let Message::Text(text) = socket.read().expect("Має надійти подія") else {
+            panic!("Подія має бути текстовою");
+        };
+        let event: Value = serde_json::from_str(&text).expect("Подія має бути JSON");
+        assert_eq!(event["state"], "plotting");
+        assert_eq!(event["total_length"], 10.0);
+    }
+}
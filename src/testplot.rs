@@ -0,0 +1,402 @@
+use geo::{AffineOps, AffineTransform, BoundingRect, LineString, MultiLineString};
+
+use crate::axidraw::{Axidraw, Options};
+use crate::drawing::Drawing;
+use crate::error::{Error, Result};
+use crate::text::font::catalog;
+use crate::text::text::TextBuilder;
+
+const MARGIN: f64 = 5.0; // Поле від дому каретки до сітки (в мм)
+const LABEL_WIDTH: f64 = 24.0; // Ширина стовпця з підписами рядків (в мм)
+const HEADER_HEIGHT: f64 = 7.0; // Висота рядка з підписами стовпців (в мм)
+const CELL_WIDTH: f64 = 22.0; // Ширина зразка в клітинці (в мм)
+const CELL_HEIGHT: f64 = 14.0; // Висота зразка в клітинці (в мм)
+const GAP: f64 = 4.0; // Проміжок між клітинками (в мм)
+const LABEL_SIZE: f64 = 2.5; // Висота великих літер підписів (в мм)
+
+/// Тестовий аркуш для нового пера: сітка однакових зразків, кожен зі своєю швидкістю,
+/// прискоренням і положенням опущеного пера.
+///
+/// Стовпці відповідають швидкостям, рядки — парам положення пера та прискорення. Кожен
+/// зразок має паралельні лінії для рівності штриха, зигзаг для кутів, коло для плавних
+/// вигинів і короткі риски для крапель на початку та в кінці штриха. Підписи малюються
+/// шрифтом Hershey з типовими налаштуваннями, тож за аркушем легко обрати найкращі.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PenTest {
+    pub velocities: Vec<f64>,         // Швидкості стовпців (мм/с).
+    pub accelerations: Vec<f64>,      // Прискорення рядків (мм/с²).
+    pub pen_down_positions: Vec<i32>, // Положення опущеного пера рядків (0..100).
+}
+
+/// Один зразок тестового аркуша разом із налаштуваннями, з якими його малювати.
+#[derive(Debug, Clone)]
+pub struct PenTestCell {
+    pub velocity: f64,          // Найбільша швидкість (мм/с).
+    pub acceleration: f64,      // Прискорення (мм/с²).
+    pub pen_down_position: i32, // Положення опущеного пера.
+    pub drawing: Drawing,       // Штрихи зразка на його місці в сітці.
+}
+
+impl PenTest {
+    /// Тестовий аркуш навколо поточних налаштувань: чотири швидкості від половини до
+    /// вчетверо більшої за `max_velocity` і три положення пера з кроком 5.
+    ///
+    /// Швидкості обмежуються можливостями моделі, а положення пера — межами 0..100.
+    ///
+    /// # Аргументи
+    /// * `options` - налаштування плотера, від яких відштовхується аркуш.
+    pub fn around(options: &Options) -> PenTest {
+        let limit = options.velocity_limit();
+        let mut velocities: Vec<f64> = [0.5, 1.0, 2.0, 4.0]
+            .iter()
+            .map(|factor| (options.max_velocity * factor).min(limit))
+            .collect();
+        velocities.dedup();
+        let mut pen_down_positions: Vec<i32> = [-5, 0, 5]
+            .iter()
+            .map(|offset| (options.pen_down_position + offset).clamp(0, 100))
+            .collect();
+        pen_down_positions.dedup();
+        PenTest {
+            velocities,
+            accelerations: vec![options.acceleration],
+            pen_down_positions,
+        }
+    }
+
+    /// Пари положення пера та прискорення в порядку рядків сітки.
+    pub fn rows(&self) -> Vec<(i32, f64)> {
+        self.pen_down_positions
+            .iter()
+            .flat_map(|&position| {
+                self.accelerations
+                    .iter()
+                    .map(move |&acceleration| (position, acceleration))
+            })
+            .collect()
+    }
+
+    /// Межі всього аркуша (в мм) разом із полями.
+    pub fn bounds(&self) -> (f64, f64) {
+        let (columns, rows) = (self.velocities.len() as f64, self.rows().len() as f64);
+        (
+            2.0 * MARGIN + LABEL_WIDTH + columns * (CELL_WIDTH + GAP),
+            2.0 * MARGIN + HEADER_HEIGHT + rows * (CELL_HEIGHT + GAP),
+        )
+    }
+
+    /// Зразки сітки рядок за рядком.
+    pub fn cells(&self) -> Vec<PenTestCell> {
+        let bounds = self.bounds();
+        self.rows()
+            .into_iter()
+            .enumerate()
+            .flat_map(|(row, (pen_down_position, acceleration))| {
+                self.velocities
+                    .iter()
+                    .enumerate()
+                    .map(move |(column, &velocity)| {
+                        let (x, y) = cell_origin(row, column);
+                        PenTestCell {
+                            velocity,
+                            acceleration,
+                            pen_down_position,
+                            drawing: Drawing::new(bounds, MultiLineString(swatch(x, y))),
+                        }
+                    })
+            })
+            .collect()
+    }
+
+    /// Підписи стовпців і рядків.
+    ///
+    /// # Повертає
+    /// * `Result<Drawing>` - підписи або помилка шрифту.
+    pub fn labels(&self) -> Result<Drawing> {
+        let mut paths = vec![];
+        for (column, velocity) in self.velocities.iter().enumerate() {
+            let (x, y) = cell_origin(0, column);
+            paths.extend(label(&format!("{} mm/s", velocity), x, y - HEADER_HEIGHT)?);
+        }
+        for (row, (position, acceleration)) in self.rows().into_iter().enumerate() {
+            let (_, y) = cell_origin(row, 0);
+            paths.extend(label(&format!("pen {}", position), MARGIN, y)?);
+            paths.extend(label(
+                &format!("acc {}", acceleration),
+                MARGIN,
+                y + 2.0 * LABEL_SIZE,
+            )?);
+        }
+        Ok(Drawing::new(self.bounds(), MultiLineString(paths)))
+    }
+
+    /// Увесь аркуш одним малюнком, наприклад для перегляду в SVG.
+    ///
+    /// # Повертає
+    /// * `Result<Drawing>` - підписи та всі зразки або помилка шрифту.
+    pub fn to_drawing(&self) -> Result<Drawing> {
+        let mut drawing = self.labels()?;
+        for cell in self.cells() {
+            drawing.paths.0.extend(cell.drawing.paths.0);
+        }
+        Ok(drawing)
+    }
+}
+
+/// Лівий верхній кут зразка в рядку `row` і стовпці `column`.
+fn cell_origin(row: usize, column: usize) -> (f64, f64) {
+    (
+        MARGIN + LABEL_WIDTH + column as f64 * (CELL_WIDTH + GAP),
+        MARGIN + HEADER_HEIGHT + row as f64 * (CELL_HEIGHT + GAP),
+    )
+}
+
+/// Штрихи одного зразка з лівим верхнім кутом у (x, y).
+fn swatch(x: f64, y: f64) -> Vec<LineString<f64>> {
+    let mut paths: Vec<LineString<f64>> = (0..3)
+        .map(|line| {
+            let y = y + line as f64;
+            LineString::from(vec![(x, y), (x + CELL_WIDTH, y)])
+        })
+        .collect();
+
+    let zigzag = (0..=10).map(|tooth| {
+        let offset = match tooth % 2 {
+            0 => 9.0,
+            _ => 4.0,
+        };
+        (x + tooth as f64 * CELL_WIDTH / 10.0, y + offset)
+    });
+    paths.push(LineString::from(zigzag.collect::<Vec<_>>()));
+
+    // Коло радіусом 2 мм у лівому нижньому куті
+    let (cx, cy, radius) = (x + 2.0, y + CELL_HEIGHT - 2.0, 2.0);
+    let circle = (0..=48).map(|step| {
+        let angle = step as f64 / 48.0 * std::f64::consts::TAU;
+        (cx + radius * angle.cos(), cy + radius * angle.sin())
+    });
+    paths.push(LineString::from(circle.collect::<Vec<_>>()));
+
+    // Короткі риски, на яких видно краплі від опускання та підйому пера
+    for dash in 0..6 {
+        let start = x + 6.0 + dash as f64 * 2.5;
+        paths.push(LineString::from(vec![
+            (start, y + CELL_HEIGHT - 2.0),
+            (start + 1.0, y + CELL_HEIGHT - 2.0),
+        ]));
+    }
+    paths
+}
+
+/// Підпис шрифтом Hershey з лівим верхнім кутом рамки в (x, y).
+fn label(content: &str, x: f64, y: f64) -> Result<Vec<LineString<f64>>> {
+    let font = catalog::by_name("romans")?;
+    let scale = LABEL_SIZE / font.metrics().cap_height;
+    let text = TextBuilder::default()
+        .font(font)
+        .scale(scale)
+        .width(LABEL_WIDTH)
+        .content(content)
+        .build()?;
+    let paths = text.to_drawing(0.0)?.paths;
+    let Some(bbox) = paths.bounding_rect() else {
+        return Ok(vec![]);
+    };
+    let offset = AffineTransform::translate(x - bbox.min().x, y - bbox.min().y);
+    Ok(paths.affine_transform(&offset).0)
+}
+
+impl Axidraw {
+    /// Малює тестовий аркуш пера: спершу підписи з поточними налаштуваннями, потім кожен
+    /// зразок зі своєю швидкістю, прискоренням і положенням опущеного пера.
+    ///
+    /// Налаштування всіх зразків перевіряються до початку малювання. Після кожного зразка
+    /// каретка повертається додому, а після аркуша відновлюються початкові налаштування.
+    ///
+    /// # Параметри
+    /// - `test`: Тестовий аркуш, наприклад з `PenTest::around`.
+    ///
+    /// # Повертає
+    /// - `Result<(), Error>`: Повертає `Ok(())`, якщо аркуш намальовано, або помилку в разі невдачі.
+    pub fn draw_pen_test(&mut self, test: &PenTest) -> Result<(), Error> {
+        let original = self.options.clone();
+        let cells: Vec<(Options, Drawing)> = test
+            .cells()
+            .into_iter()
+            .map(|cell| {
+                let mut options = original.clone();
+                options.max_velocity = cell.velocity;
+                options.acceleration = cell.acceleration;
+                options.pen_down_position = cell.pen_down_position;
+                // Паркування лише після останнього зразка
+                options.park_position = None;
+                options.validate().map(|_| (options, cell.drawing))
+            })
+            .collect::<std::result::Result<_, _>>()?;
+        let labels = test.labels()?;
+
+        let result = self.draw_pen_test_cells(&original, labels, cells);
+        let restored = self
+            .device
+            .set_pen_down_position(original.pen_down_position);
+        self.options = original;
+        result?;
+        restored?;
+        Ok(())
+    }
+
+    /// Малює підписи та зразки тестового аркуша один за одним.
+    fn draw_pen_test_cells(
+        &mut self,
+        original: &Options,
+        labels: Drawing,
+        cells: Vec<(Options, Drawing)>,
+    ) -> Result<(), Error> {
+        self.options = Options {
+            park_position: None,
+            ..original.clone()
+        };
+        self.draw(&labels)?;
+        let count = cells.len();
+        for (index, (mut options, drawing)) in cells.into_iter().enumerate() {
+            if index + 1 == count {
+                options.park_position = original.park_position;
+            }
+            if self.device.pen_down_position != options.pen_down_position {
+                self.device
+                    .set_pen_down_position(options.pen_down_position)?;
+            }
+            self.options = options;
+            self.draw(&drawing)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::mock::TraceEvent;
+    use std::time::Duration;
+
+    /// Чи є команда рухом каретки.
+    fn is_motion(command: &str) -> bool {
+        ["XM,", "LM,", "SM,", "HM,"]
+            .iter()
+            .any(|prefix| command.starts_with(prefix))
+    }
+
+    #[test]
+    fn test_pen_test_varies_settings_per_cell() {
+        let options = Options {
+            max_velocity: 20.0,
+            pen_down_position: 30,
+            ..Options::default()
+        };
+        let test = PenTest::around(&options);
+        assert_eq!(test.velocities, [10.0, 20.0, 40.0, 80.0]);
+        assert_eq!(test.pen_down_positions, [25, 30, 35]);
+        let cells = test.cells();
+        assert_eq!(cells.len(), 12);
+        assert_eq!(
+            (cells[5].velocity, cells[5].pen_down_position),
+            (20.0, 30),
+            "Другий рядок, другий стовпець"
+        );
+
+        // Сітка з підписами вміщується в робочу область Mini
+        let drawing = test.to_drawing().expect("Підписи мають будуватися");
+        let bbox = drawing.bbox().unwrap();
+        let (width, height) = test.bounds();
+        assert!(bbox.max().x <= width && bbox.max().y <= height);
+        assert!(width <= options.model.width() && height <= options.model.height());
+        let first = cells[0].drawing.bbox().unwrap();
+        let labels = test.labels().unwrap().bbox().unwrap();
+        assert!(
+            labels.min().y < first.min().y,
+            "Підписи стовпців над сіткою"
+        );
+
+        let small = PenTest {
+            velocities: vec![10.0, 30.0],
+            accelerations: vec![16.0, 64.0],
+            pen_down_positions: vec![20, 40],
+        };
+        let (mut axidraw, trace) =
+            Axidraw::simulated(options.clone()).expect("Імітований пристрій має підключитися");
+        axidraw
+            .draw_pen_test(&small)
+            .expect("Аркуш має намалюватися");
+        let commands: Vec<String> = trace
+            .events()
+            .into_iter()
+            .map(|event| event.command)
+            .filter(|command| command.starts_with("SC,5,"))
+            .collect();
+        assert_eq!(
+            commands.len(),
+            4,
+            "Налаштування при підключенні, два рядки й відновлення"
+        );
+        assert_ne!(commands[0], commands[1]);
+        assert_ne!(commands[1], commands[2]);
+        assert_eq!(commands[0], commands[3], "Початкове положення відновлено");
+        assert_eq!(axidraw.options.max_velocity, 20.0);
+
+        // Перший штрих кожного зразка: рух від першого опускання пера до підйому. Зразки
+        // розділяє повернення додому, а перший відрізок трасування — це підписи.
+        let mut cells: Vec<Vec<TraceEvent>> = vec![vec![]];
+        for event in trace.events() {
+            let home = event.position == (0.0, 0.0) && is_motion(&event.command);
+            cells.last_mut().unwrap().push(event);
+            if home {
+                cells.push(vec![]);
+            }
+        }
+        let strokes: Vec<Vec<TraceEvent>> = cells
+            .into_iter()
+            .skip(1)
+            .map(|cell| {
+                cell.into_iter()
+                    .skip_while(|event| !event.command.starts_with("SP,0"))
+                    .take_while(|event| !event.command.starts_with("SP,1"))
+                    .filter(|event| is_motion(&event.command))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|stroke| !stroke.is_empty())
+            .collect();
+        assert_eq!(
+            strokes.len(),
+            8,
+            "Два положення, два прискорення, дві швидкості"
+        );
+        let peak = |stroke: &[TraceEvent]| stroke.iter().map(|e| e.velocity).fold(0.0, f64::max);
+        let duration = |stroke: &[TraceEvent]| stroke.iter().map(|e| e.duration).sum::<Duration>();
+        for row in strokes.chunks(2) {
+            let (slow, fast) = (&row[0], &row[1]);
+            assert!(
+                peak(slow) <= 10.0 + 1e-6,
+                "Зразок 10 мм/с не швидший за 10 мм/с"
+            );
+            assert!(
+                peak(fast) > peak(slow) + 1.0,
+                "Зразок 30 мм/с розганяється далі"
+            );
+            assert!(duration(fast) < duration(slow));
+        }
+        assert_ne!(
+            strokes[1][0].command, strokes[3][0].command,
+            "Перший крок розгону залежить від прискорення рядка"
+        );
+        assert!(peak(&strokes[3]) > peak(&strokes[1]) + 1.0);
+
+        let invalid = PenTest {
+            velocities: vec![10_000.0],
+            ..small
+        };
+        assert!(matches!(
+            axidraw.draw_pen_test(&invalid),
+            Err(Error::Options(_))
+        ));
+    }
+}
@@ -1,9 +1,13 @@
-use std::{collections::HashMap, sync::Mutex};
+use std::{
+    collections::HashMap,
+    sync::{Mutex, PoisonError},
+};
 
-use log::{debug, info};
 use once_cell::sync::Lazy;
+use tracing::{debug, info};
 
 use super::font::Font;
+use crate::tr;
 
 /// Статична структура для кешування шрифтів.
 pub struct Fonts {
@@ -26,7 +30,7 @@ impl Fonts {
     ///
     /// * `Option<Font>` - знайдений шрифт або `None`.
     pub fn get(font_name: &str) -> Option<Font> {
-        let cache = FONTS.cache.lock().unwrap();
+        let cache = FONTS.cache.lock().unwrap_or_else(PoisonError::into_inner);
         cache.get(font_name).cloned()
     }
 
@@ -37,15 +41,22 @@ impl Fonts {
     /// * `font_name` - назва шрифту.
     /// * `font` - екземпляр шрифту для додавання.
     pub fn insert(font_name: String, font: Font) {
-        let mut cache = FONTS.cache.lock().unwrap();
+        let mut cache = FONTS.cache.lock().unwrap_or_else(PoisonError::into_inner);
         cache.insert(font_name.clone(), font);
-        debug!("Шрифт '{}' додано до кешу.", font_name);
+        debug!(
+            "{}",
+            tr!(
+                "Font '{}' added to the cache.",
+                "Шрифт '{}' додано до кешу.",
+                font_name
+            )
+        );
     }
 
     /// Очищує весь кеш шрифтів.
     pub fn clear() {
-        let mut cache = FONTS.cache.lock().unwrap();
+        let mut cache = FONTS.cache.lock().unwrap_or_else(PoisonError::into_inner);
         cache.clear();
-        info!("Кеш шрифтів очищено.");
+        info!("{}", tr!("Font cache cleared.", "Кеш шрифтів очищено."));
     }
 }
@@ -0,0 +1,85 @@
+use super::{
+    cyrilic::Cyrilic,
+    error::FontError,
+    font::Font,
+    gothic::Gothic,
+    greek::Greek,
+    italic::Italic,
+    math::Math,
+    music::Music,
+    roman::Roman,
+    script::Script,
+    symbol::Symbol,
+    variant::{
+        Complex, ComplexSmall, Duplex, GermanTriplex, GreatBritainTriplex, ItalianTriplex, Plain,
+        Simplex, Triplex,
+    },
+};
+
+/// Назви шрифтів, доступних через `by_name`, за назвами файлів офсетів Hershey.
+pub const FONT_NAMES: [&str; 24] = [
+    "romans", "romand", "romant", "romanc", "romancs", "italicc", "italiccs", "italict", "scripts",
+    "scriptc", "greekp", "greeks", "greekc", "greekcs", "cyrilc", "gothgbt", "gothgrt", "gothitt",
+    "maths", "mathc", "musics", "musicc", "symbols", "symbolc",
+];
+
+/// Створює шрифт за назвою його файлу офсетів Hershey, наприклад `romans` чи `gothgbt`.
+///
+/// Назва складається з назви шрифту та суфікса варіанта: `s` — simplex, `d` — duplex,
+/// `t` — triplex, `c` — complex, `cs` — complex small, `p` — plain; готичні шрифти мають
+/// суфікси `gbt`, `grt` і `itt`. Так шрифт можна вибрати з командного рядка.
+///
+/// # Аргументи
+///
+/// * `name` - назва шрифту з `FONT_NAMES`, без урахування регістру.
+///
+/// # Повертає
+///
+/// * `Result<Font, FontError>` - шрифт або помилка, якщо назва невідома.
+pub fn by_name(name: &str) -> Result<Font, FontError> {
+    match name.trim().to_lowercase().as_str() {
+        "romans" => Roman::new().simplex(),
+        "romand" => Roman::new().duplex(),
+        "romant" => Roman::new().triplex(),
+        "romanc" => Roman::new().complex(),
+        "romancs" => Roman::new().complex_small(),
+        "italicc" => Italic::new().complex(),
+        "italiccs" => Italic::new().complex_small(),
+        "italict" => Italic::new().triplex(),
+        "scripts" => Script::new().simplex(),
+        "scriptc" => Script::new().complex(),
+        "greekp" => Greek::new().plain(),
+        "greeks" => Greek::new().simplex(),
+        "greekc" => Greek::new().complex(),
+        "greekcs" => Greek::new().complex_small(),
+        "cyrilc" => Cyrilic::new().complex(),
+        "gothgbt" => Gothic::new().great_britain_triplex(),
+        "gothgrt" => Gothic::new().german_triplex(),
+        "gothitt" => Gothic::new().italian_triplex(),
+        "maths" => Math::new().simplex(),
+        "mathc" => Math::new().complex(),
+        "musics" => Music::new().simplex(),
+        "musicc" => Music::new().complex(),
+        "symbols" => Symbol::new().simplex(),
+        "symbolc" => Symbol::new().complex(),
+        _ => Err(FontError::UnknownFont(name.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_font_name_builds() {
+        for name in FONT_NAMES {
+            let font = by_name(name).expect("Шрифт з переліку має створюватися");
+            assert!(!font.is_empty(), "Шрифт {} без гліфів", name);
+        }
+        assert!(by_name("RomanS").is_ok(), "Назва без урахування регістру");
+        assert!(matches!(
+            by_name("comic"),
+            Err(FontError::UnknownFont(name)) if name == "comic"
+        ));
+    }
+}
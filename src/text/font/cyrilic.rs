@@ -22,6 +22,12 @@ impl Cyrilic {
     }
 }
 
+impl Default for Cyrilic {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Реалізація трейту `TypeFace` для шрифту Cyrilic.
 impl TypeFace for Cyrilic {
     /// Повертає назву шрифту Cyrilic.
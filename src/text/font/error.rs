@@ -1,24 +1,46 @@
 use thiserror::Error;
 
+use crate::tr;
+
 /// Перелік можливих помилок, що виникають при роботі зі шрифтами.
 #[derive(Debug, Error)]
 pub enum FontError {
     /// Помилка при парсингу окремого гліфу.
-    #[error("Помилка парсингу гліфу: {glyph}. Причина: {message}")]
+    #[error(
+        "{}",
+        tr!(
+            "Failed to parse glyph: {}. Reason: {}",
+            "Помилка парсингу гліфу: {}. Причина: {}",
+            .glyph,
+            .message
+        )
+    )]
     GlyphParsingError {
         glyph: String,   // Символ гліфа, який викликав помилку.
         message: String, // Повідомлення про причину помилки парсингу.
     },
 
     /// Помилка при створенні шрифту з файлу офсетів.
-    #[error("Помилка під час створення шрифту з файлу: {file}. Причина: {source}")]
+    #[error(
+        "{}",
+        tr!(
+            "Failed to create font from file: {}. Reason: {}",
+            "Помилка під час створення шрифту з файлу: {}. Причина: {}",
+            .file,
+            .source
+        )
+    )]
     FontCreationError {
         file: String, // Назва файлу офсетів, який викликав помилку.
         #[source] // Додаткова інформація про помилку створення шрифту.
         source: Box<dyn std::error::Error + Send + Sync>,
     },
 
+    /// Шрифту з такою назвою немає.
+    #[error("{}", tr!("Unknown font '{}'", "Невідомий шрифт '{}'", .0))]
+    UnknownFont(String),
+
     /// Загальна помилка шрифту.
-    #[error("Загальна помилка шрифту: {0}")]
+    #[error("{}", tr!("Font error: {}", "Загальна помилка шрифту: {}", .0))]
     GenericError(String),
 }
@@ -0,0 +1,60 @@
+use super::{
+    cyrilic::Cyrilic,
+    error::FontError,
+    font::Font,
+    greek::Greek,
+    symbol::Symbol,
+    variant::{Complex, Simplex},
+};
+
+/// Повертає стандартний ланцюжок резервних шрифтів для `TextBuilder`.
+///
+/// Шрифти перебираються по черзі, доки не знайдеться гліф для символа,
+/// відсутнього в основному шрифті: спершу грецький, потім кириличний,
+/// і насамкінець символьний.
+///
+/// # Повертає
+///
+/// * `Result<Vec<Font>, FontError>` - впорядкований список резервних шрифтів або помилка.
+pub fn default_chain() -> Result<Vec<Font>, FontError> {
+    Ok(vec![
+        Greek::new().simplex()?,
+        Cyrilic::new().complex()?,
+        Symbol::new().simplex()?,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::font::roman::Roman;
+    use crate::text::text::TextBuilder;
+
+    #[test]
+    fn test_default_chain_falls_through_to_symbols() {
+        let chain = default_chain().expect("Не вдалося завантажити резервні шрифти");
+        assert_eq!(chain.len(), 3);
+        // Масті є лише в символьному шрифті, тож пошук проходить увесь ланцюжок
+        assert!(chain[..2]
+            .iter()
+            .all(|font| font.glyph_by_unicode('♡' as u32).is_none()));
+
+        let text = TextBuilder::default()
+            .content("Aα♡")
+            .font(
+                Roman::new()
+                    .simplex()
+                    .expect("Не вдалося ініціалізувати шрифт `Roman`"),
+            )
+            .fallbacks(chain)
+            .width(200.0)
+            .build()
+            .expect("Не вдалося створити текст");
+        assert!(text.missing.is_empty());
+        let codes: Vec<_> = text.glyphs.iter().map(|glyph| glyph.charcode).collect();
+        assert_eq!(
+            codes,
+            vec![Some('A' as u32), Some('α' as u32), Some('♡' as u32)]
+        );
+    }
+}
@@ -1,32 +1,58 @@
 use super::{error::FontError, glyph::Glyph};
-use log::{debug, error, info};
-use std::collections::HashMap;
+use crate::tr;
+use once_cell::sync::OnceCell;
+use std::{collections::HashMap, sync::Arc};
+use tracing::{debug, error, info};
 
 // Include generated maps
 include!(concat!(env!("OUT_DIR"), "/offsets.rs"));
 
+/// Метрики шрифту для самостійного розміщення тексту.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FontMetrics {
+    pub cap_height: f64,      // Висота великих літер (за гліфом `H`).
+    pub baseline: f64,        // Базова лінія: нижній край гліфа `H`.
+    pub x_height: f64,        // Висота малих літер (за гліфом `x`).
+    pub average_advance: f64, // Середня ширина кроку гліфа.
+}
+
 /// Представляє шрифт Hershey.
+///
+/// Рядки гліфів розбираються лише під час першого звернення до гліфа, а результат
+/// запам'ятовується. Копії шрифту (зокрема з кешу `Fonts`) ділять ту саму таблицю,
+/// тож кожен гліф розбирається не більше одного разу.
 #[derive(Debug, Clone)]
 pub struct Font {
-    pub name: String,                // Назва шрифту.
-    pub glyphs: HashMap<u32, Glyph>, // Карта гліфів для швидкого доступу за символом.
+    pub name: String,                         // Назва шрифту.
+    glyphs: Arc<GlyphTable>,                  // Гліфи шрифту, розібрані за потреби.
+    unicode_map: &'static phf::Map<u32, u32>, // Відповідності Hershey кодів та Unicode кодів.
 }
 
-impl Font {
-    /// Створює новий шрифт із вказаною назвою і вже парсеним набором гліфів.
-    ///
-    /// # Аргументів
-    ///
-    /// * `name` - назва шрифту.
-    /// * `glyphs` - карта гліфів.
-    ///
-    /// # Повертає
-    ///
-    /// * `Self` - новий екземпляр шрифту.
-    fn new(name: String, mut glyphs: HashMap<u32, Glyph>) -> Self {
-        Font { name, glyphs }
+/// Гліфи шрифту з індексами за Unicode кодом та за номером Hershey.
+#[derive(Debug, Default)]
+struct GlyphTable {
+    entries: Vec<LazyGlyph>,         // Усі рядки гліфів у порядку файлу офсетів.
+    by_unicode: HashMap<u32, usize>, // Індекс запису за Unicode кодом.
+    by_hershey: HashMap<u32, usize>, // Індекс запису за номером гліфа Hershey.
+}
+
+/// Рядок гліфа Hershey, що розбирається під час першого звернення.
+#[derive(Debug)]
+struct LazyGlyph {
+    line: String,                    // Сирий рядок гліфа.
+    parsed: OnceCell<Option<Glyph>>, // Розібраний гліф або `None`, якщо рядок некоректний.
+}
+
+impl LazyGlyph {
+    /// Ширина кроку гліфа за лівою та правою межами, без розбору вершин.
+    fn advance(&self) -> Option<f64> {
+        let bytes = self.line.as_bytes();
+        let margin = |index: usize| bytes.get(index).map(|&byte| byte as f64 - b'R' as f64);
+        Some(margin(9)? - margin(8)?)
     }
+}
 
+impl Font {
     /// Створює шрифт на основі назви, гліфів та файлу офсетів.
     ///
     /// # Аргументів
@@ -43,20 +69,36 @@ impl Font {
         name: &str,
         glyphs_map: &phf::Map<u32, &'static str>,
         offsets_filename: &str,
-        unicode_map: &phf::Map<u32, u32>,
+        unicode_map: &'static phf::Map<u32, u32>,
     ) -> Result<Self, FontError> {
         info!(
-            "Створення шрифту '{}' з файлу офсетів '{}'.",
-            name, offsets_filename
+            "{}",
+            tr!(
+                "Creating font '{}' from offsets file '{}'.",
+                "Створення шрифту '{}' з файлу офсетів '{}'.",
+                name,
+                offsets_filename
+            )
         );
 
         // Отримуємо офсети для даного файлу офсетів безпосередньо з мапи OFFSETS.
         let offsets = OFFSETS.get(offsets_filename).copied().ok_or_else(|| {
-            error!("Файл офсетів '{}' не знайдено.", offsets_filename);
-            FontError::GenericError(format!("Файл {} не знайдено в офсетах", offsets_filename))
+            error!(
+                "{}",
+                tr!(
+                    "Offsets file '{}' not found.",
+                    "Файл офсетів '{}' не знайдено.",
+                    offsets_filename
+                )
+            );
+            FontError::GenericError(tr!(
+                "File {} not found among the offsets",
+                "Файл {} не знайдено в офсетах",
+                offsets_filename
+            ))
         })?;
 
-        debug!("Отримані офсети: {:?}", offsets);
+        debug!("{}", tr!("Offsets: {:?}", "Отримані офсети: {:?}", offsets));
 
         // Вибираємо гліфи відповідно до офсетів і одразу створюємо шрифт.
         let selected_glyphs: Vec<&'static str> = offsets
@@ -64,7 +106,14 @@ impl Font {
             .filter_map(|&glyph_id| glyphs_map.get(&glyph_id).copied())
             .collect();
 
-        debug!("Вибрані гліфи для створення шрифту: {:?}", selected_glyphs);
+        debug!(
+            "{}",
+            tr!(
+                "Glyphs selected for the font: {:?}",
+                "Вибрані гліфи для створення шрифту: {:?}",
+                selected_glyphs
+            )
+        );
 
         // Створюємо шрифт з відфільтрованих гліфів.
         Font::from_glyphs(name, &selected_glyphs, unicode_map)
@@ -72,6 +121,9 @@ impl Font {
 
     /// Створює шрифт на основі назви та гліфів.
     ///
+    /// Тут зчитуються лише номери гліфів, щоб зв'язати рядки з Unicode кодами; самі
+    /// гліфи розбираються під час першого виклику `glyph_by_unicode`.
+    ///
     /// # Аргументів
     ///
     /// * `name` - назва шрифту.
@@ -84,42 +136,79 @@ impl Font {
     pub fn from_glyphs(
         name: &str,
         glyphs: &[&str],
-        cmap: &phf::Map<u32, u32>,
+        cmap: &'static phf::Map<u32, u32>,
     ) -> Result<Self, FontError> {
-        info!("Створення шрифту '{}' з наданих гліфів.", name);
+        info!(
+            "{}",
+            tr!(
+                "Creating font '{}' from the given glyphs.",
+                "Створення шрифту '{}' з наданих гліфів.",
+                name
+            )
+        );
 
-        let mut glyph_map = HashMap::new();
+        let mut table = GlyphTable::default();
         for line in glyphs {
-            // Парсимо кожний рядок гліфа, передаючи cmap.
-            match Glyph::from_line(line, cmap) {
-                Ok(glyph) => {
-                    // Перевіряємо, чи визначено charcode. Якщо немає, пропускаємо гліф.
-                    if let Some(charcode) = glyph.charcode {
-                        glyph_map.insert(charcode, glyph);
-                    } else {
-                        debug!(
-                            "Гліф пропущено, оскільки charcode не було визначено для лінії: '{}'.",
-                            line
-                        );
-                    }
-                }
-                Err(e) => {
-                    error!("Помилка парсингу гліфа для лінії '{}': {:?}", line, e);
+            // Номер гліфа займає перші п'ять символів рядка
+            let hershey_id = line.get(0..5).and_then(|id| id.trim().parse::<u32>().ok());
+            let Some(hershey_id) = hershey_id else {
+                error!(
+                    "{}",
+                    tr!(
+                        "Cannot read the glyph number in line '{}'.",
+                        "Неможливо прочитати номер гліфа в лінії '{}'.",
+                        line
+                    )
+                );
+                continue;
+            };
+
+            let index = table.entries.len();
+            table.entries.push(LazyGlyph {
+                line: line.to_string(),
+                parsed: OnceCell::new(),
+            });
+            table.by_hershey.insert(hershey_id, index);
+
+            // Гліф без charcode доступний лише за номером Hershey.
+            match cmap.get(&hershey_id) {
+                Some(&charcode) => {
+                    table.by_unicode.insert(charcode, index);
                 }
+                None => debug!(
+                    "{}",
+                    tr!(
+                        "Glyph has no Unicode code and is only available by number {}: '{}'.",
+                        "Гліф не має Unicode коду, доступний лише за номером {}: '{}'.",
+                        hershey_id,
+                        line
+                    )
+                ),
             }
         }
 
         debug!(
-            "Шрифт '{}' успішно створено з {} гліфів.",
-            name,
-            glyph_map.len()
+            "{}",
+            tr!(
+                "Font '{}' created with {} glyphs, {} of them with a Unicode code.",
+                "Шрифт '{}' успішно створено з {} гліфів, {} з них мають Unicode код.",
+                name,
+                table.entries.len(),
+                table.by_unicode.len()
+            )
         );
 
-        Ok(Font::new(name.to_string(), glyph_map))
+        Ok(Font {
+            name: name.to_string(),
+            glyphs: Arc::new(table),
+            unicode_map: cmap,
+        })
     }
 
     /// Повертає гліф для вказаного Unicode коду, якщо він існує.
     ///
+    /// Під час першого звернення гліф розбирається з рядка й запам'ятовується.
+    ///
     /// # Аргументів
     ///
     /// * `charcode` - Unicode код, для якого потрібно знайти гліф.
@@ -128,8 +217,168 @@ impl Font {
     ///
     /// * `Option<&Glyph>` - посилання на гліф або `None`, якщо гліф не знайдено.
     pub fn glyph_by_unicode(&self, charcode: u32) -> Option<&Glyph> {
-        self.glyphs
+        let &index = self.glyphs.by_unicode.get(&charcode)?;
+        self.parse(index)
+    }
+
+    /// Повертає гліф за його номером у наборі Hershey.
+    ///
+    /// Так доступні й гліфи без відповідника в Unicode (декоративні знаки, частини
+    /// великих символів).
+    ///
+    /// # Аргументів
+    ///
+    /// * `hershey_id` - номер гліфа Hershey.
+    ///
+    /// # Повертає
+    ///
+    /// * `Option<&Glyph>` - посилання на гліф або `None`, якщо шрифт не містить цього номера.
+    pub fn glyph_by_hershey_id(&self, hershey_id: u32) -> Option<&Glyph> {
+        let &index = self.glyphs.by_hershey.get(&hershey_id)?;
+        self.parse(index)
+    }
+
+    /// Розбирає запис таблиці гліфів під час першого звернення.
+    fn parse(&self, index: usize) -> Option<&Glyph> {
+        let entry = &self.glyphs.entries[index];
+        entry
+            .parsed
+            .get_or_init(|| match Glyph::from_line(&entry.line, self.unicode_map) {
+                Ok(glyph) => Some(glyph),
+                Err(e) => {
+                    error!(
+                        "{}",
+                        tr!(
+                            "Failed to parse glyph line '{}': {:?}",
+                            "Помилка парсингу гліфа для лінії '{}': {:?}",
+                            entry.line,
+                            e
+                        )
+                    );
+                    None
+                }
+            })
+            .as_ref()
+    }
+
+    /// Кількість гліфів шрифту, що мають Unicode код.
+    pub fn len(&self) -> usize {
+        self.glyphs.by_unicode.len()
+    }
+
+    /// Чи немає у шрифті жодного гліфа з Unicode кодом.
+    pub fn is_empty(&self) -> bool {
+        self.glyphs.by_unicode.is_empty()
+    }
+
+    /// Обчислює метрики шрифту.
+    ///
+    /// Висота великих літер вимірюється за гліфом `H`, висота малих — за гліфом `x`.
+    /// Якщо шрифт не містить `H` (наприклад, символьні шрифти), береться висота найвищого
+    /// гліфа; якщо немає `x`, висота малих літер дорівнює висоті великих.
+    ///
+    /// # Повертає
+    ///
+    /// * `FontMetrics` - висота великих і малих літер та середня ширина кроку гліфа.
+    pub fn metrics(&self) -> FontMetrics {
+        let height = |glyph: &Glyph| glyph.bbox().height();
+
+        let capital = self.glyph_by_unicode('H' as u32);
+        let cap_height = capital.map(height).unwrap_or_else(|| {
+            self.glyphs
+                .by_unicode
+                .values()
+                .filter_map(|&index| self.parse(index))
+                .map(height)
+                .fold(0.0, f64::max)
+        });
+        let baseline = capital.map_or(0.0, |glyph| glyph.ymax);
+        let x_height = self
+            .glyph_by_unicode('x' as u32)
+            .map(height)
+            .unwrap_or(cap_height);
+        // Ширина кроку береться з меж у рядку гліфа, тож метрики не розбирають увесь шрифт
+        let advances: Vec<f64> = self
+            .glyphs
+            .by_unicode
             .values()
-            .find(|glyph| glyph.charcode == Some(charcode))
+            .filter_map(|&index| self.glyphs.entries[index].advance())
+            .collect();
+        let average_advance = if advances.is_empty() {
+            0.0
+        } else {
+            advances.iter().sum::<f64>() / advances.len() as f64
+        };
+
+        FontMetrics {
+            cap_height,
+            baseline,
+            x_height,
+            average_advance,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::group::OCCIDENTAL_FONT_GROUP;
+    use super::*;
+
+    #[test]
+    fn test_glyphs_are_parsed_lazily_and_shared() {
+        // Оминаємо кеш `Fonts`, щоб інші тести не розбирали гліфи цього екземпляра
+        let group = &OCCIDENTAL_FONT_GROUP;
+        let font =
+            Font::from_glyphs_offsets("romans", group.fonts, "romans", group.unicode_map).unwrap();
+        let parsed = |font: &Font| {
+            font.glyphs
+                .entries
+                .iter()
+                .filter(|entry| entry.parsed.get().is_some())
+                .count()
+        };
+
+        let copy = font.clone();
+        let metrics = font.metrics();
+        assert!(metrics.average_advance > 0.0);
+        assert!(
+            parsed(&font) <= 2,
+            "Метрики розбирають лише гліфи `H` та `x`"
+        );
+
+        let glyph = copy
+            .glyph_by_unicode('A' as u32)
+            .expect("Гліф `A` є в шрифті");
+        assert_eq!(glyph.charcode, Some('A' as u32));
+        assert!(!glyph.paths.0.is_empty());
+        assert_eq!(
+            parsed(&font),
+            parsed(&copy),
+            "Копії шрифту ділять розібрані гліфи"
+        );
+        assert!(parsed(&font) < font.len());
+        assert!(font.glyph_by_unicode(0x10FFFF).is_none());
+    }
+
+    #[test]
+    fn test_glyph_by_hershey_id() {
+        let group = &OCCIDENTAL_FONT_GROUP;
+        let font =
+            Font::from_glyphs_offsets("romans", group.fonts, "romans", group.unicode_map).unwrap();
+        let by_id = font.glyph_by_hershey_id(501).expect("Гліф 501 є в шрифті");
+        let by_unicode = font.glyph_by_unicode('A' as u32).unwrap();
+        assert_eq!(by_id.charcode, Some('A' as u32));
+        assert_eq!(by_id.paths, by_unicode.paths);
+        assert!(font.glyph_by_hershey_id(1).is_none());
+
+        // Гліф без відповідника в Unicode доступний лише за номером
+        let line = format!("99999{}", &group.fonts.get(&501).unwrap()[5..]);
+        let font = Font::from_glyphs("custom", &[line.as_str()], group.unicode_map).unwrap();
+        assert!(font.is_empty());
+        let glyph = font
+            .glyph_by_hershey_id(99999)
+            .expect("Гліф доступний за номером");
+        assert_eq!(glyph.charcode, None);
+        assert_eq!(glyph.paths, by_unicode.paths);
     }
 }
@@ -1,7 +1,8 @@
-use geo::{coord, AffineOps, AffineTransform, LineString, MultiLineString, Point, Rect};
-use log::{debug, error, info};
+use geo::{coord, AffineOps, AffineTransform, Coord, LineString, MultiLineString, Point, Rect};
+use tracing::{debug, error, info};
 
 use crate::text::font::error::FontError;
+use crate::tr;
 
 /// Представляє окремий гліф (символ) шрифту Hershey як набір шляхів.
 #[derive(Debug, Clone)]
@@ -56,6 +57,30 @@ impl Glyph {
         }
     }
 
+    /// Створює гліф-замінник у вигляді прямокутника для символів, яких немає в жодному шрифті.
+    ///
+    /// # Аргументи
+    ///
+    /// * `xmin` - мінімальне значення x для гліфа.
+    /// * `xmax` - максимальне значення x для гліфа.
+    /// * `ymin` - мінімальне значення y для гліфа.
+    /// * `ymax` - максимальне значення y для гліфа.
+    ///
+    /// # Повертає
+    ///
+    /// * `Self` - гліф з прямокутною рамкою, відступленою на одиницю від бокових меж.
+    pub fn missing_box(xmin: f64, xmax: f64, ymin: f64, ymax: f64) -> Self {
+        let rect = LineString::from(vec![
+            Point::new(xmin + 1.0, ymin),
+            Point::new(xmax - 1.0, ymin),
+            Point::new(xmax - 1.0, ymax),
+            Point::new(xmin + 1.0, ymax),
+            Point::new(xmin + 1.0, ymin),
+        ]);
+
+        Glyph::new(MultiLineString(vec![rect]), None, xmin, xmax, ymin, ymax)
+    }
+
     /// Повертає обмежувальну рамку гліфа.
     ///
     /// # Повертає
@@ -63,11 +88,10 @@ impl Glyph {
     /// * `Rect<f64>` - Прямокутник, що представляє обмежувальну рамку гліфа.
     pub fn bbox(&self) -> Rect<f64> {
         // Створюємо обмежувальну рамку, використовуючи координати xmin, ymin, xmax, ymax
-        let rect = Rect::new(
+        Rect::new(
             coord! { x: self.xmin, y: self.ymin },
             coord! { x: self.xmax, y: self.ymax },
-        );
-        rect
+        )
     }
 
     /// Зміщує гліф на задані відстані по осях X та Y.
@@ -134,6 +158,111 @@ impl Glyph {
         }
     }
 
+    /// Повертає гліф на заданий кут навколо вказаної точки.
+    ///
+    /// # Аргументи
+    ///
+    /// * `degrees` - Кут повороту в градусах (проти годинникової стрілки для осі Y, що йде вгору).
+    /// * `origin` - Точка, навколо якої виконується поворот.
+    ///
+    /// # Повертає
+    ///
+    /// * `Self` - Новий екземпляр гліфа з повернутими шляхами та обмежувальною рамкою,
+    ///   що охоплює повернуту рамку.
+    pub fn rotate(&self, degrees: f64, origin: Point<f64>) -> Self {
+        // Створюємо афінну трансформацію для повороту
+        let rotation = AffineTransform::rotate(degrees, origin);
+
+        // Застосовуємо трансформацію до всіх шляхів
+        let new_paths = self.paths.affine_transform(&rotation);
+
+        // Обчислюємо нові межі за повернутими кутами старої рамки
+        let corners = self.bbox().to_polygon().affine_transform(&rotation);
+        let (mut xmin, mut xmax) = (f64::MAX, f64::MIN);
+        let (mut ymin, mut ymax) = (f64::MAX, f64::MIN);
+        for corner in corners.exterior().coords() {
+            xmin = xmin.min(corner.x);
+            xmax = xmax.max(corner.x);
+            ymin = ymin.min(corner.y);
+            ymax = ymax.max(corner.y);
+        }
+
+        Glyph {
+            charcode: self.charcode,
+            paths: new_paths,
+            xmin,
+            xmax,
+            ymin,
+            ymax,
+        }
+    }
+
+    /// Нахиляє гліф на заданий кут, зсуваючи точки по X пропорційно висоті над базовою лінією.
+    ///
+    /// Межі гліфа не змінюються: вони задають крок гліфа в рядку, тож похилий текст
+    /// розміщується так само, як прямий.
+    ///
+    /// # Аргументи
+    ///
+    /// * `degrees` - Кут нахилу в градусах; додатний нахиляє верх гліфа праворуч.
+    /// * `baseline` - Координата Y базової лінії, яка лишається на місці.
+    ///
+    /// # Повертає
+    ///
+    /// * `Self` - Новий екземпляр гліфа з нахиленими шляхами.
+    pub fn skew(&self, degrees: f64, baseline: f64) -> Self {
+        // Вісь Y гліфів Hershey спрямована вниз, тож верх гліфа має менші Y
+        let shear = AffineTransform::skew(-degrees, 0.0, coord! { x: 0.0, y: baseline });
+
+        Glyph {
+            paths: self.paths.affine_transform(&shear),
+            ..self.clone()
+        }
+    }
+
+    /// Потовщує штрихи гліфа, проходячи кожен шлях кілька разів зі зсувом убік.
+    ///
+    /// Копії шляху з'єднуються в одну ламану, що йде то вперед, то назад, тож перо
+    /// проводить весь товстий штрих без підйому.
+    ///
+    /// # Аргументи
+    ///
+    /// * `strokes` - Кількість проходів кожного шляху; 0 або 1 лишають гліф без змін.
+    /// * `spacing` - Відстань між сусідніми проходами.
+    ///
+    /// # Повертає
+    ///
+    /// * `Self` - Новий екземпляр гліфа з потовщеними шляхами.
+    pub fn embolden(&self, strokes: usize, spacing: f64) -> Self {
+        if strokes <= 1 {
+            return self.clone();
+        }
+
+        let middle = (strokes - 1) as f64 / 2.0;
+        let paths = self
+            .paths
+            .0
+            .iter()
+            .map(|line| {
+                let mut points = Vec::with_capacity(line.0.len() * strokes);
+                for stroke in 0..strokes {
+                    let copy = offset_line(line, (stroke as f64 - middle) * spacing);
+                    if stroke % 2 == 0 {
+                        points.extend(copy.0);
+                    } else {
+                        points.extend(copy.0.into_iter().rev());
+                    }
+                }
+                LineString(points)
+            })
+            .collect();
+
+        Glyph {
+            paths: MultiLineString(paths),
+            ..self.clone()
+        }
+    }
+
     /// Парсить окремий гліф з рядка і застосовує мапу Unicode для відповідної групи шрифтів.
     ///
     /// # Аргументи
@@ -145,68 +274,130 @@ impl Glyph {
     ///
     /// * `Result<Self, FontError>` - новий гліф або помилка парсингу.
     pub fn from_line(glyph: &str, cmap: &phf::Map<u32, u32>) -> Result<Self, FontError> {
-        debug!("Парсимо гліф із рядка: {}", glyph);
+        debug!(
+            "{}",
+            tr!("Parsing glyph line: {}", "Парсимо гліф із рядка: {}", glyph)
+        );
 
         // Перевіряємо мінімальну довжину рядка, необхідну для парсингу.
         if glyph.len() < 10 {
-            error!("Помилка парсингу: рядок занадто короткий для гліфа.");
+            error!(
+                "{}",
+                tr!(
+                    "Parse error: the line is too short for a glyph.",
+                    "Помилка парсингу: рядок занадто короткий для гліфа."
+                )
+            );
+            return Err(FontError::GlyphParsingError {
+                glyph: glyph.to_string(),
+                message: tr!(
+                    "The line is too short to parse a glyph",
+                    "Рядок занадто короткий для парсингу гліфа"
+                ),
+            });
+        }
+        if !glyph.is_ascii() {
             return Err(FontError::GlyphParsingError {
                 glyph: glyph.to_string(),
-                message: "Рядок занадто короткий для парсингу гліфа".to_string(),
+                message: tr!(
+                    "A glyph line must contain only ASCII characters",
+                    "Рядок гліфа має містити лише символи ASCII"
+                ),
             });
         }
 
         // Парсимо номер гліфа (перші п'ять символів).
         let character_str = &glyph[0..5].trim();
-        debug!("Парсимо символ з рядка: '{}'", character_str);
+        debug!(
+            "{}",
+            tr!(
+                "Parsing glyph number from: '{}'",
+                "Парсимо символ з рядка: '{}'",
+                character_str
+            )
+        );
 
         // Парсимо значення символа як число (u32)
         let character = character_str.parse::<u32>().map_err(|_| {
             error!(
-                "Неможливо парсити символ гліфа '{}' як число.",
-                character_str
+                "{}",
+                tr!(
+                    "Cannot parse glyph number '{}' as a number.",
+                    "Неможливо парсити символ гліфа '{}' як число.",
+                    character_str
+                )
             );
             FontError::GlyphParsingError {
                 glyph: glyph.to_string(),
-                message: "Неможливо парсити символ гліфа як число".to_string(),
+                message: tr!(
+                    "Cannot parse the glyph number as a number",
+                    "Неможливо парсити символ гліфа як число"
+                ),
             }
         })?;
 
-        info!("Гліф для символа '{}' успішно оброблено.", character);
+        info!(
+            "{}",
+            tr!(
+                "Glyph number '{}' read.",
+                "Гліф для символа '{}' успішно оброблено.",
+                character
+            )
+        );
 
         // Перевіряємо, чи існує відповідний Unicode код для цього Hershey коду
         let charcode = cmap.get(&character).copied();
         debug!(
-            "Для Hershey коду '{}' знайдено Unicode код: {:?}",
-            character, charcode
+            "{}",
+            tr!(
+                "Unicode code for Hershey code '{}': {:?}",
+                "Для Hershey коду '{}' знайдено Unicode код: {:?}",
+                character,
+                charcode
+            )
         );
 
         // Парсимо кількість вершин (символи з 5 до 8).
         let num_vertices_str = &glyph[5..8].trim();
         let num_vertices = num_vertices_str.parse::<usize>().map_err(|_| {
-            error!("Неможливо парсити кількість вершин '{}'.", num_vertices_str);
+            error!(
+                "{}",
+                tr!(
+                    "Cannot parse vertex count '{}'.",
+                    "Неможливо парсити кількість вершин '{}'.",
+                    num_vertices_str
+                )
+            );
             FontError::GlyphParsingError {
                 glyph: glyph.to_string(),
-                message: "Неможливо парсити кількість вершин".to_string(),
+                message: tr!(
+                    "Cannot parse the vertex count",
+                    "Неможливо парсити кількість вершин"
+                ),
             }
         })?;
 
-        debug!("Кількість вершин: {}", num_vertices);
-
-        // Парсимо ліву межу (символ на позиції 8) і праву межу (символ на позиції 9).
-        let left_margin = (glyph.chars().nth(8).ok_or(FontError::GlyphParsingError {
-            glyph: glyph.to_string(),
-            message: "Неможливо отримати символ лівої межі".to_string(),
-        })? as i32)
-            - ('R' as i32);
+        debug!(
+            "{}",
+            tr!("Vertex count: {}", "Кількість вершин: {}", num_vertices)
+        );
 
-        let right_margin = (glyph.chars().nth(9).ok_or(FontError::GlyphParsingError {
-            glyph: glyph.to_string(),
-            message: "Неможливо отримати символ правої межі".to_string(),
-        })? as i32)
-            - ('R' as i32);
+        // Рядок гліфа Hershey складається з ASCII, тож індексуємо байти, а не символи
+        let bytes = glyph.as_bytes();
 
-        info!("Ліва межа: {}, Права межа: {}", left_margin, right_margin);
+        // Парсимо ліву межу (символ на позиції 8) і праву межу (символ на позиції 9).
+        let left_margin = bytes[8] as i32 - 'R' as i32;
+        let right_margin = bytes[9] as i32 - 'R' as i32;
+
+        info!(
+            "{}",
+            tr!(
+                "Left margin: {}, right margin: {}",
+                "Ліва межа: {}, Права межа: {}",
+                left_margin,
+                right_margin
+            )
+        );
 
         // Обчислюємо xmin та xmax
         let xmin = left_margin as f64;
@@ -220,44 +411,36 @@ impl Glyph {
         let mut paths = Vec::new(); // Містить шляхи для поточного гліфа.
         let mut current_points = Vec::new(); // Містить точки для поточного шляху.
         let mut is_pen_down = true; // Визначає, чи активний режим малювання (перо опущене).
-        let mut current_index = 10; // Початкова позиція для парсингу координат.
-
-        // Проходимо по рядку, витягуючи координати пар точок.
-        while current_index + 1 < glyph.len() {
-            let x_char = glyph
-                .chars()
-                .nth(current_index)
-                .ok_or(FontError::GlyphParsingError {
-                    glyph: glyph.to_string(),
-                    message: "Неможливо отримати координату x".to_string(),
-                })?;
-            let y_char =
-                glyph
-                    .chars()
-                    .nth(current_index + 1)
-                    .ok_or(FontError::GlyphParsingError {
-                        glyph: glyph.to_string(),
-                        message: "Неможливо отримати координату y".to_string(),
-                    })?;
+
+        // Проходимо по рядку один раз, витягуючи координати пар точок після десятого символу.
+        for pair in bytes[10..].chunks_exact(2) {
+            let (x_char, y_char) = (pair[0] as char, pair[1] as char);
 
             // Якщо зустрічаємо " R", це означає підйом пера.
             if x_char == ' ' && y_char == 'R' {
                 if !current_points.is_empty() {
                     info!(
-                        "Закінчення шляху: кількість точок = {}",
-                        current_points.len()
+                        "{}",
+                        tr!(
+                            "Path finished: points = {}",
+                            "Закінчення шляху: кількість точок = {}",
+                            current_points.len()
+                        )
                     );
-                    paths.push(LineString::from(
-                        current_points.iter().cloned().collect::<Vec<Point<f64>>>(),
-                    ));
+                    paths.push(LineString::from(current_points.to_vec()));
                     current_points.clear(); // Очищаємо точки для нового шляху.
                 }
                 is_pen_down = false;
-                info!("Ручка піднята.");
+                info!("{}", tr!("Pen up.", "Ручка піднята."));
             } else {
                 debug!(
-                    "Обчислюємо координати відносно 'R': x = {}, y = {}",
-                    x_char, y_char
+                    "{}",
+                    tr!(
+                        "Coordinates relative to 'R': x = {}, y = {}",
+                        "Обчислюємо координати відносно 'R': x = {}, y = {}",
+                        x_char,
+                        y_char
+                    )
                 );
                 let x_coordinate = (x_char as i32) - ('R' as i32);
                 let y_coordinate = (y_char as i32) - ('R' as i32);
@@ -267,31 +450,51 @@ impl Glyph {
                 ymax = ymax.max(point.y());
 
                 if is_pen_down {
-                    debug!("Додана точка: x = {}, y = {}", x_coordinate, y_coordinate);
+                    debug!(
+                        "{}",
+                        tr!(
+                            "Point added: x = {}, y = {}",
+                            "Додана точка: x = {}, y = {}",
+                            x_coordinate,
+                            y_coordinate
+                        )
+                    );
                     current_points.push(point);
                 } else {
                     is_pen_down = true;
-                    info!("Ручка опущена. Початок нового шляху.");
+                    info!(
+                        "{}",
+                        tr!(
+                            "Pen down. Starting a new path.",
+                            "Ручка опущена. Початок нового шляху."
+                        )
+                    );
                     current_points.push(point);
                 }
             }
-
-            // Переміщуємо індекс для наступної пари координат.
-            current_index += 2;
         }
 
         // Якщо є залишкові точки після завершення рядка, додаємо їх як шлях.
         if !current_points.is_empty() {
-            paths.push(LineString::from(
-                current_points.iter().cloned().collect::<Vec<Point<f64>>>(),
-            ));
+            paths.push(LineString::from(current_points.to_vec()));
             debug!(
-                "Додано завершальний шлях з кількістю точок = {}",
-                current_points.len()
+                "{}",
+                tr!(
+                    "Final path added with points = {}",
+                    "Додано завершальний шлях з кількістю точок = {}",
+                    current_points.len()
+                )
             );
         }
 
-        info!("Гліф успішно парсено для символа '{}'.", character);
+        info!(
+            "{}",
+            tr!(
+                "Glyph '{}' parsed.",
+                "Гліф успішно парсено для символа '{}'.",
+                character
+            )
+        );
 
         // Повертаємо новий гліф зі списком шляхів, межами та опційним кодом символа.
         Ok(Glyph::new(
@@ -305,16 +508,62 @@ impl Glyph {
     }
 }
 
+/// Зсуває ламану перпендикулярно до її напрямку.
+///
+/// Кожна вершина зсувається вздовж середньої нормалі сусідніх відрізків; для зсувів,
+/// менших за товщину пера, цього достатньо без точного обчислення з'єднань.
+///
+/// # Аргументи
+///
+/// * `line` - Ламана для зсуву.
+/// * `distance` - Відстань зсуву; від'ємна зсуває в інший бік.
+///
+/// # Повертає
+///
+/// * `LineString<f64>` - Зсунута ламана з тією самою кількістю точок.
+fn offset_line(line: &LineString<f64>, distance: f64) -> LineString<f64> {
+    let normal = |a: Coord<f64>, b: Coord<f64>| {
+        let (dx, dy) = (b.x - a.x, b.y - a.y);
+        let length = dx.hypot(dy);
+        (length > 0.0).then(|| (-dy / length, dx / length))
+    };
+
+    let points = &line.0;
+    points
+        .iter()
+        .enumerate()
+        .map(|(index, &point)| {
+            let before = index
+                .checked_sub(1)
+                .and_then(|previous| normal(points[previous], point));
+            let after = points.get(index + 1).and_then(|&next| normal(point, next));
+            let (nx, ny) = match (before, after) {
+                (Some(a), Some(b)) => {
+                    let (x, y) = (a.0 + b.0, a.1 + b.1);
+                    let length = x.hypot(y);
+                    if length > 0.0 {
+                        (x / length, y / length)
+                    } else {
+                        a
+                    }
+                }
+                (Some(n), None) | (None, Some(n)) => n,
+                (None, None) => (0.0, 0.0),
+            };
+            coord! { x: point.x + nx * distance, y: point.y + ny * distance }
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use env_logger;
     use phf::phf_map;
 
     fn init_logger() {
-        let _ = env_logger::builder()
-            .is_test(true)
-            .filter_level(log::LevelFilter::Debug)
+        let _ = tracing_subscriber::fmt()
+            .with_test_writer()
+            .with_max_level(tracing::Level::DEBUG)
             .try_init();
     }
 
@@ -339,7 +588,7 @@ mod tests {
         assert!(!glyph.paths.0.is_empty());
 
         // Визначаємо очікувані шляхи
-        let expected_paths = vec![
+        let expected_paths = [
             LineString::from(vec![Point::new(-3.0, -5.0), Point::new(-3.0, 4.0)]),
             LineString::from(vec![Point::new(3.0, -5.0), Point::new(3.0, 4.0)]),
             LineString::from(vec![Point::new(-3.0, -1.0), Point::new(3.0, -1.0)]),
@@ -383,7 +632,7 @@ mod tests {
         assert!(!glyph.paths.0.is_empty());
 
         // Define expected paths before offset
-        let expected_paths_before_offset = vec![
+        let expected_paths_before_offset = [
             LineString::from(vec![Point::new(-3.0, -5.0), Point::new(-3.0, 4.0)]),
             LineString::from(vec![Point::new(3.0, -5.0), Point::new(3.0, 4.0)]),
             LineString::from(vec![Point::new(-3.0, -1.0), Point::new(3.0, -1.0)]),
@@ -423,7 +672,7 @@ mod tests {
         println!("Offset: {:?}", offset_glyph);
 
         // Define expected paths after x offset
-        let expected_paths_after_x_offset = vec![
+        let expected_paths_after_x_offset = [
             LineString::from(vec![Point::new(-1.0, -5.0), Point::new(-1.0, 4.0)]),
             LineString::from(vec![Point::new(5.0, -5.0), Point::new(5.0, 4.0)]),
             LineString::from(vec![Point::new(-1.0, -1.0), Point::new(5.0, -1.0)]),
@@ -478,6 +727,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_rotate_glyph_a() {
+        init_logger();
+
+        // Визначаємо мапу для тесту
+        static TEST_CMAP: phf::Map<u32, u32> = phf_map! {
+            8u32 => 72u32, // Hershey код 8 -> 'H'
+        };
+
+        let glyph_line = "    8  9MWOMOV RUMUV ROQUQ";
+        let glyph = Glyph::from_line(glyph_line, &TEST_CMAP).expect("Не вдалося розпарсити гліф");
+
+        // Поворот на 90 градусів навколо початку координат: (x, y) -> (-y, x)
+        let rotated = glyph.rotate(90.0, Point::new(0.0, 0.0));
+
+        let first = rotated.paths.0[0].0[0];
+        assert!(
+            (first.x - 5.0).abs() < 1e-9,
+            "Координата X не співпадає після повороту"
+        );
+        assert!(
+            (first.y + 3.0).abs() < 1e-9,
+            "Координата Y не співпадає після повороту"
+        );
+
+        // Рамка повернутого гліфа міняє ширину і висоту місцями
+        assert!((rotated.bbox().width() - glyph.bbox().height()).abs() < 1e-9);
+        assert!((rotated.bbox().height() - glyph.bbox().width()).abs() < 1e-9);
+    }
+
     #[test]
     fn test_scale_glyph_a() {
         init_logger();
@@ -506,7 +785,7 @@ mod tests {
         println!("Масштабований гліф: {:?}", scaled_glyph);
 
         // Перевіряємо оновлені шляхи
-        let expected_scaled_paths = vec![
+        let expected_scaled_paths = [
             LineString::from(vec![Point::new(-6.0, -10.0), Point::new(-6.0, 8.0)]),
             LineString::from(vec![Point::new(6.0, -10.0), Point::new(6.0, 8.0)]),
             LineString::from(vec![Point::new(-6.0, -2.0), Point::new(6.0, -2.0)]),
@@ -558,4 +837,53 @@ mod tests {
             "ymax не співпадає після масштабу"
         );
     }
+
+    #[test]
+    fn test_non_ascii_line_is_an_error() {
+        static TEST_CMAP: phf::Map<u32, u32> = phf_map! {
+            8u32 => 72u32, // Hershey код 8 -> 'H'
+        };
+        // Межі полів номера й кількості вершин припадають на середину багатобайтових символів
+        for line in [
+            "ї   8  9MWOMOV",
+            "    8ї 9MWOMOV",
+            "    8  9MWOMOVї",
+            "  😀  9MWOMOV",
+        ] {
+            assert!(matches!(
+                Glyph::from_line(line, &TEST_CMAP),
+                Err(FontError::GlyphParsingError { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_skew_and_embolden() {
+        static TEST_CMAP: phf::Map<u32, u32> = phf_map! {
+            8u32 => 72u32, // Hershey код 8 -> 'H'
+        };
+        let glyph = Glyph::from_line("    8  9MWOMOV RUMUV ROQUQ", &TEST_CMAP)
+            .expect("Парсинг гліфа не вдався");
+
+        // Ліва вертикаль H від (-3, -5) до (-3, 4): верх іде праворуч, база лишається
+        let slanted = glyph.skew(45.0, 4.0);
+        let stem = &slanted.paths.0[0].0;
+        assert!((stem[0].x - 6.0).abs() < 1e-9 && (stem[0].y + 5.0).abs() < 1e-9);
+        assert!((stem[1].x + 3.0).abs() < 1e-9);
+        assert_eq!((slanted.xmin, slanted.xmax), (glyph.xmin, glyph.xmax));
+
+        // Три проходи вертикалі з кроком 0.5 з'єднуються в одну ламану туди й назад
+        let bold = glyph.embolden(3, 0.5);
+        assert_eq!(bold.paths.0.len(), glyph.paths.0.len());
+        let stem = &bold.paths.0[0].0;
+        assert_eq!(stem.len(), 6);
+        let xs: Vec<f64> = stem.iter().map(|point| point.x).collect();
+        assert_eq!(xs, vec![-2.5, -2.5, -3.0, -3.0, -3.5, -3.5]);
+        assert_eq!(
+            (stem[1].y, stem[2].y),
+            (4.0, 4.0),
+            "Другий прохід іде назад"
+        );
+        assert_eq!(glyph.embolden(1, 0.5).paths, glyph.paths);
+    }
 }
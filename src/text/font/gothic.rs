@@ -25,6 +25,12 @@ impl Gothic {
     }
 }
 
+impl Default for Gothic {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Реалізація трейту `TypeFace` для шрифту Gothic.
 impl TypeFace for Gothic {
     /// Повертає назву шрифту Gothic.
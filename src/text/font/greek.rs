@@ -23,6 +23,12 @@ impl Greek {
     }
 }
 
+impl Default for Greek {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Реалізація трейту `TypeFace` для шрифту Greek.
 impl TypeFace for Greek {
     /// Повертає назву шрифту Greek.
@@ -4,12 +4,32 @@ use phf::phf_map;
 include!(concat!(env!("OUT_DIR"), "/occidental_fonts.rs"));
 include!(concat!(env!("OUT_DIR"), "/occidental_unicode_map.rs"));
 include!(concat!(env!("OUT_DIR"), "/oriental_fonts.rs"));
+include!(concat!(env!("OUT_DIR"), "/symbol_unicode_map.rs"));
 
 /// Типи груп шрифтів.
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub enum FontGroupType {
     Occidental, // Західна група шрифтів: містить символи латинського алфавіту та інші західні символи.
     Oriental, // Східна група шрифтів: містить символи східних мов, таких як китайська, японська, корейська.
+    Symbol, // Символьна група: маркери, астрономічні, музичні та математичні знаки із західного набору.
+}
+
+/// Початок області приватного використання Unicode для символьних гліфів без власного коду.
+/// Гліф з Hershey кодом `N` доступний як символ `U+E000 + N`.
+pub const SYMBOL_PRIVATE_USE_BASE: u32 = 0xE000;
+
+/// Повертає символ, під яким гліф з вказаним Hershey кодом доступний у символьних шрифтах,
+/// якщо гліф не має власного Unicode коду.
+///
+/// # Аргументи
+///
+/// * `hershey_id` - номер гліфа у наборі Hershey.
+///
+/// # Повертає
+///
+/// * `Option<char>` - символ з області приватного використання або `None`, якщо код недійсний.
+pub fn private_use_char(hershey_id: u32) -> Option<char> {
+    char::from_u32(SYMBOL_PRIVATE_USE_BASE + hershey_id)
 }
 
 /// Група шрифтів, яка містить інформацію про тип групи, відповідні файли шрифтів та мапу відповідностей Unicode.
@@ -35,3 +55,10 @@ pub static ORIENTAL_FONT_GROUP: FontGroup = FontGroup {
     fonts: &ORIENTAL_HERSHEY_FONTS,
     unicode_map: &EMPTY_UNICODE_MAP,
 };
+
+/// Група символьних шрифтів: гліфи західного набору з мапою, що покриває кожен символ.
+pub static SYMBOL_FONT_GROUP: FontGroup = FontGroup {
+    group_type: FontGroupType::Symbol,
+    fonts: &OCCIDENTAL_HERSHEY_FONTS,
+    unicode_map: &HERSHEY_SYMBOL_UNICODE_MAP,
+};
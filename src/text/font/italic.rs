@@ -23,6 +23,12 @@ impl Italic {
     }
 }
 
+impl Default for Italic {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Реалізація трейту `TypeFace` для шрифту Italic.
 impl TypeFace for Italic {
     /// Повертає назву шрифту Italic.
@@ -0,0 +1,70 @@
+use super::{
+    group::{FontGroup, SYMBOL_FONT_GROUP},
+    variant::{BaseFontFace, Complex, FontVariant, Simplex, TypeFace},
+};
+
+/// Структура для математичного шрифту Math: оператори, стрілки, відношення та великі
+/// знаки сум, добутків, коренів і інтегралів.
+///
+/// Гліфи використовують власні коди Unicode. Якщо код уже зайнятий іншим гліфом того ж
+/// варіанту (наприклад, великий `√` або `∫`), гліф доступний через область приватного
+/// використання: гліф з Hershey кодом `N` має код `U+E000 + N`
+/// (див. [`private_use_char`](super::group::private_use_char)).
+///
+/// # Simplex (`maths`)
+///
+/// * 1227–1246 — `⟨ ⟩ | ∥ − + ± ∓ ⨯ ⋅ ÷ = ≠ ≡ < > ≦ ≧ ∝ ∼`.
+/// * 1247–1254 — комбіновані діакритичні знаки (`U+0302`, `U+0300`, `U+0301`, ...).
+/// * 1256–1279 — `⊂ ∪ ⊃ ∩ ∈ → ↑ ← ↓ Y ∇ √ ∫ ∮ ∞ % & @ $ # § † ‡ ∃`.
+/// * 1401–1410 — великі `Π Σ ( ) [ ] { } ⎰ ⎱`.
+/// * 1411–1412 — великі `√` і `∫` (`U+E583`, `U+E584`).
+///
+/// # Complex (`mathc`)
+///
+/// * 2227–2279 — ті самі оператори; другий `√` (2267) доступний як `U+E8DB`.
+/// * 2401–2410 — великі `Π Σ ( ) [ ] { } ⎰ ⎱`.
+/// * 2411–2412 — великі `√` і `∫` (`U+E96B`, `U+E96C`).
+#[derive(Debug, Clone)]
+pub struct Math {
+    /// Базовий шрифт.
+    base: BaseFontFace,
+}
+
+impl Math {
+    /// Створює новий екземпляр Math зі статичними значеннями.
+    ///
+    /// # Повертає
+    ///
+    /// * `Self` - новий екземпляр Math.
+    pub fn new() -> Self {
+        Math {
+            base: BaseFontFace::new("math", &SYMBOL_FONT_GROUP),
+        }
+    }
+}
+
+impl Default for Math {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Реалізація трейту `TypeFace` для шрифту Math.
+impl TypeFace for Math {
+    /// Повертає назву шрифту Math.
+    fn name(&self) -> &str {
+        self.base.name
+    }
+
+    /// Повертає групу шрифту Math.
+    fn group(&self) -> &FontGroup {
+        self.base.group
+    }
+}
+
+/// Реалізація трейту `FontVariant` для шрифту Math.
+impl FontVariant for Math {}
+
+/// Реалізація трейтів варіантів шрифту для Math.
+impl Complex for Math {}
+impl Simplex for Math {}
@@ -1,12 +1,18 @@
 pub mod cache;
+pub mod catalog;
 pub mod cyrilic;
 pub mod error;
+pub mod fallback;
+#[allow(clippy::module_inception)]
 pub mod font;
 pub mod glyph;
 pub mod gothic;
 pub mod greek;
 pub mod group;
 pub mod italic;
+pub mod math;
+pub mod music;
 pub mod roman;
 pub mod script;
+pub mod symbol;
 pub mod variant;
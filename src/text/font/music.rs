@@ -0,0 +1,66 @@
+use super::{
+    group::{FontGroup, SYMBOL_FONT_GROUP},
+    variant::{BaseFontFace, Complex, FontVariant, Simplex, TypeFace},
+};
+
+/// Структура для музичного шрифту Music: ключі, ноти, паузи та знаки альтерації.
+///
+/// Дієз, бекар і бемоль мають власні коди Unicode (`♯ ♮ ♭`), решта гліфів доступна
+/// через область приватного використання: гліф з Hershey кодом `N` має код `U+E000 + N`
+/// (див. [`private_use_char`](super::group::private_use_char)).
+///
+/// # Simplex (`musics`)
+///
+/// * 2317–2322 — крапка, прапорці, скрипковий ключ, голівки нот (`U+E90D`–`U+E912`).
+/// * 2323–2325 — `♯ ♮ ♭`.
+/// * 2326–2332 — паузи, басовий та альтовий ключі (`U+E916`–`U+E91C`).
+///
+/// # Complex (`musicc`)
+///
+/// * 2367–2372 — ті самі знаки більшого розміру (`U+E93F`–`U+E944`).
+/// * 2373–2375 — `♯ ♮ ♭`.
+/// * 2376–2382 — паузи та ключі (`U+E948`–`U+E94E`).
+#[derive(Debug, Clone)]
+pub struct Music {
+    /// Базовий шрифт.
+    base: BaseFontFace,
+}
+
+impl Music {
+    /// Створює новий екземпляр Music зі статичними значеннями.
+    ///
+    /// # Повертає
+    ///
+    /// * `Self` - новий екземпляр Music.
+    pub fn new() -> Self {
+        Music {
+            base: BaseFontFace::new("music", &SYMBOL_FONT_GROUP),
+        }
+    }
+}
+
+impl Default for Music {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Реалізація трейту `TypeFace` для шрифту Music.
+impl TypeFace for Music {
+    /// Повертає назву шрифту Music.
+    fn name(&self) -> &str {
+        self.base.name
+    }
+
+    /// Повертає групу шрифту Music.
+    fn group(&self) -> &FontGroup {
+        self.base.group
+    }
+}
+
+/// Реалізація трейту `FontVariant` для шрифту Music.
+impl FontVariant for Music {}
+
+/// Реалізація трейтів варіантів шрифту для Music.
+impl Complex for Music {}
+impl Simplex for Music {}
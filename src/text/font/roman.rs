@@ -25,6 +25,12 @@ impl Roman {
     }
 }
 
+impl Default for Roman {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Реалізація трейту `TypeFace` для шрифту Roman.
 impl TypeFace for Roman {
     /// Повертає назву шрифту Roman.
@@ -23,6 +23,12 @@ impl Script {
     }
 }
 
+impl Default for Script {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Реалізація трейту `TypeFace` для шрифту Script.
 impl TypeFace for Script {
     /// Повертає назву шрифту Script.
@@ -0,0 +1,71 @@
+use super::{
+    group::{FontGroup, SYMBOL_FONT_GROUP},
+    variant::{BaseFontFace, Complex, FontVariant, Simplex, TypeFace},
+};
+
+/// Структура для символьного шрифту Symbol: маркери, карткові масті, картографічні,
+/// астрономічні та зодіакальні знаки набору Hershey.
+///
+/// Більшість цих гліфів не має відповідника в Unicode, тому вони доступні через
+/// область приватного використання: гліф з Hershey кодом `N` має код `U+E000 + N`
+/// (див. [`private_use_char`](super::group::private_use_char)).
+///
+/// # Simplex (`symbols`)
+///
+/// * 737–746 — `∥ ⟂ ∠ ⸫ ♤ ♡ ♢ ♧ ☘ ⚜` (власні коди Unicode).
+/// * 840–847 — контурні маркери: коло, квадрат, трикутник, ромб, зірка, плюс, хрест, зірочка
+///   (`U+E348`–`U+E34F`).
+/// * 850–857 — заповнені маркери (`U+E352`–`U+E359`).
+/// * 860–874 — картографічні та метеорологічні знаки: прапорець, якір, хрест, місяць, дзвін,
+///   пальма тощо (`U+E35C`–`U+E36A`).
+/// * 899–909 — кола зростаючого розміру, глобус, щити (`U+E383`–`U+E38D`).
+/// * 1281–1295 — астрономічні знаки: Сонце, планети, Місяць, комета, вузли (`U+E501`–`U+E50F`).
+///
+/// # Complex (`symbolc`)
+///
+/// * 2281–2295 — астрономічні знаки (`U+E8E9`–`U+E8F7`).
+/// * 2301–2312 — знаки зодіаку від Овна до Риб (`U+E8FD`–`U+E908`).
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    /// Базовий шрифт.
+    base: BaseFontFace,
+}
+
+impl Symbol {
+    /// Створює новий екземпляр Symbol зі статичними значеннями.
+    ///
+    /// # Повертає
+    ///
+    /// * `Self` - новий екземпляр Symbol.
+    pub fn new() -> Self {
+        Symbol {
+            base: BaseFontFace::new("symbol", &SYMBOL_FONT_GROUP),
+        }
+    }
+}
+
+impl Default for Symbol {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Реалізація трейту `TypeFace` для шрифту Symbol.
+impl TypeFace for Symbol {
+    /// Повертає назву шрифту Symbol.
+    fn name(&self) -> &str {
+        self.base.name
+    }
+
+    /// Повертає групу шрифту Symbol.
+    fn group(&self) -> &FontGroup {
+        self.base.group
+    }
+}
+
+/// Реалізація трейту `FontVariant` для шрифту Symbol.
+impl FontVariant for Symbol {}
+
+/// Реалізація трейтів варіантів шрифту для Symbol.
+impl Complex for Symbol {}
+impl Simplex for Symbol {}
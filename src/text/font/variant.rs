@@ -1,6 +1,7 @@
-use log::{debug, error};
+use tracing::{debug, error};
 
 use crate::text::font::cache::Fonts;
+use crate::tr;
 
 use super::{error::FontError, font::Font, group::FontGroup};
 
@@ -53,15 +54,27 @@ pub trait FontVariant: TypeFace {
 
         // Перевіряємо, чи є шрифт у кеші.
         if let Some(cached_font) = Fonts::get(&full_name) {
-            debug!("Шрифт '{}' знайдено у кеші.", full_name);
+            debug!(
+                "{}",
+                tr!(
+                    "Font '{}' found in the cache.",
+                    "Шрифт '{}' знайдено у кеші.",
+                    full_name
+                )
+            );
             return Ok(cached_font);
         }
 
         // Якщо шрифту немає в кеші, формуємо назву файлу офсетів.
         let offsets_filename = format!("{}{}", name, suffix);
         debug!(
-            "Шрифт '{}' не знайдено в кеші. Створюємо з файлу офсетів '{}'.",
-            full_name, offsets_filename
+            "{}",
+            tr!(
+                "Font '{}' is not cached. Creating it from offsets file '{}'.",
+                "Шрифт '{}' не знайдено в кеші. Створюємо з файлу офсетів '{}'.",
+                full_name,
+                offsets_filename
+            )
         );
 
         // Отримуємо групу шрифтів.
@@ -76,8 +89,13 @@ pub trait FontVariant: TypeFace {
         )
         .map_err(|e| {
             error!(
-                "Помилка створення шрифту з файлу офсетів '{}': {}",
-                offsets_filename, e
+                "{}",
+                tr!(
+                    "Failed to create font from offsets file '{}': {}",
+                    "Помилка створення шрифту з файлу офсетів '{}': {}",
+                    offsets_filename,
+                    e
+                )
             );
             FontError::FontCreationError {
                 file: offsets_filename,
@@ -87,7 +105,14 @@ pub trait FontVariant: TypeFace {
 
         // Додаємо створений шрифт у кеш.
         Fonts::insert(full_name.clone(), font.clone());
-        debug!("Шрифт '{}' успішно додано до кешу.", full_name);
+        debug!(
+            "{}",
+            tr!(
+                "Font '{}' added to the cache.",
+                "Шрифт '{}' успішно додано до кешу.",
+                full_name
+            )
+        );
 
         Ok(font)
     }
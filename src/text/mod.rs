@@ -1,2 +1,3 @@
 pub mod font;
+#[allow(clippy::module_inception)]
 pub mod text;
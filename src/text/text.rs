@@ -1,10 +1,14 @@
+use std::collections::HashMap;
 use std::str::FromStr;
 
-use crate::drawing::Drawable;
+use crate::drawing::order::PathOrder;
+use crate::drawing::{Drawable, Drawing};
+use crate::error::Result;
 use crate::text::font::error::FontError;
-use anyhow::Result;
-use geo::{MultiLineString, Point};
+use crate::tr;
+use geo::{coord, AffineOps, AffineTransform, MultiLineString, Point, Rect};
 use thiserror::Error;
+use tracing::warn;
 
 use super::font::font::Font;
 use super::font::glyph::Glyph;
@@ -38,6 +42,7 @@ impl FromStr for TextAlign {
     /// # Приклад
     ///
     /// ```
+    /// use rsaxi::text::text::TextAlign;
     /// use std::str::FromStr;
     /// let align = TextAlign::from_str("center").unwrap();
     /// assert_eq!(align, TextAlign::Center);
@@ -47,26 +52,177 @@ impl FromStr for TextAlign {
             "left" => Ok(TextAlign::Left),
             "center" => Ok(TextAlign::Center),
             "right" => Ok(TextAlign::Right),
-            _ => Err(FontError::GenericError(
-                "Недопустиме значення align".to_string(),
-            )),
+            _ => Err(FontError::GenericError(tr!(
+                "Invalid align value",
+                "Недопустиме значення align"
+            ))),
         }
     }
 }
 
+/// Перерахування для визначення напрямку розміщення гліфів у рядку.
+///
+/// - `Horizontal`: гліфи розміщуються зліва направо, рядки йдуть один під одним.
+/// - `Vertical`: гліфи розміщуються згори донизу, стовпці йдуть зліва направо.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextDirection {
+    Horizontal, // Горизонтальний текст.
+    Vertical,   // Вертикальний текст.
+}
+
+impl FromStr for TextDirection {
+    type Err = FontError;
+
+    /// Конвертує текстовий рядок у значення `TextDirection`.
+    ///
+    /// # Аргументи
+    ///
+    /// * `s` - Рядок, що представляє напрямок (`"horizontal"`, `"vertical"`).
+    ///
+    /// # Повертає
+    ///
+    /// * `Result<TextDirection, FontError>` - Успішне значення `TextDirection` або помилка, якщо рядок недопустимий.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "horizontal" => Ok(TextDirection::Horizontal),
+            "vertical" => Ok(TextDirection::Vertical),
+            _ => Err(FontError::GenericError(tr!(
+                "Invalid direction value",
+                "Недопустиме значення direction"
+            ))),
+        }
+    }
+}
+
+/// Зменшення верхніх і нижніх індексів відносно основного тексту.
+const SCRIPT_SCALE: f64 = 0.6;
+
+/// Підйом базової лінії верхнього індексу як частка висоти великих літер.
+const SUPERSCRIPT_SHIFT: f64 = 0.5;
+
+/// Опускання базової лінії нижнього індексу як частка висоти великих літер.
+const SUBSCRIPT_SHIFT: f64 = -0.25;
+
+/// Відстань між проходами пера для тексту з `weight` більше одиниці (в мм).
+const WEIGHT_SPACING: f64 = 0.2;
+
+/// Фрагмент тексту з власним розміром і зсувом базової лінії.
+///
+/// З фрагментів складаються підписи на кшталт `H₂O` чи `x²`: кожен фрагмент
+/// масштабується відносно загального масштабу тексту й піднімається або опускається
+/// над базовою лінією рядка.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextSpan {
+    pub content: String,     // Текст фрагмента.
+    pub scale: f64,          // Масштаб відносно масштабу тексту.
+    pub baseline_shift: f64, // Підйом базової лінії в частках висоти великих літер (униз — від'ємний).
+}
+
+impl TextSpan {
+    /// Створює звичайний фрагмент без зміни розміру та базової лінії.
+    pub fn new(content: &str) -> Self {
+        TextSpan {
+            content: content.to_string(),
+            scale: 1.0,
+            baseline_shift: 0.0,
+        }
+    }
+
+    /// Створює фрагмент із власним розміром на базовій лінії рядка.
+    ///
+    /// # Аргументи
+    ///
+    /// * `content` - текст фрагмента.
+    /// * `scale` - масштаб відносно масштабу тексту.
+    pub fn sized(content: &str, scale: f64) -> Self {
+        TextSpan {
+            scale,
+            ..TextSpan::new(content)
+        }
+    }
+
+    /// Створює верхній індекс.
+    pub fn superscript(content: &str) -> Self {
+        TextSpan {
+            scale: SCRIPT_SCALE,
+            baseline_shift: SUPERSCRIPT_SHIFT,
+            ..TextSpan::new(content)
+        }
+    }
+
+    /// Створює нижній індекс.
+    pub fn subscript(content: &str) -> Self {
+        TextSpan {
+            scale: SCRIPT_SCALE,
+            baseline_shift: SUBSCRIPT_SHIFT,
+            ..TextSpan::new(content)
+        }
+    }
+
+    /// Розбирає просту розмітку індексів на фрагменти.
+    ///
+    /// Текст між `^` стає верхнім індексом, між `~` — нижнім, а `\` екранує наступний
+    /// символ. Незакритий індекс триває до кінця рядка.
+    ///
+    /// # Аргументи
+    ///
+    /// * `markup` - розмітка, наприклад `H~2~O` або `x^2^ + y^2^`.
+    ///
+    /// # Повертає
+    ///
+    /// * `Vec<TextSpan>` - непорожні фрагменти в порядку тексту.
+    ///
+    /// # Приклад
+    ///
+    /// ```
+    /// use rsaxi::text::text::TextSpan;
+    /// let spans = TextSpan::parse_markup("H~2~O");
+    /// assert_eq!(spans, vec![TextSpan::new("H"), TextSpan::subscript("2"), TextSpan::new("O")]);
+    /// ```
+    pub fn parse_markup(markup: &str) -> Vec<TextSpan> {
+        let span = |content: &str, marker: Option<char>| match marker {
+            Some('^') => TextSpan::superscript(content),
+            Some(_) => TextSpan::subscript(content),
+            None => TextSpan::new(content),
+        };
+
+        let mut spans = Vec::new();
+        let mut current = String::new();
+        let mut marker = None;
+        let mut chars = markup.chars();
+        while let Some(char) = chars.next() {
+            match char {
+                '\\' => current.extend(chars.next()),
+                '^' | '~' if marker.is_none() || marker == Some(char) => {
+                    if !current.is_empty() {
+                        spans.push(span(&current, marker));
+                        current.clear();
+                    }
+                    marker = if marker.is_none() { Some(char) } else { None };
+                }
+                _ => current.push(char),
+            }
+        }
+        if !current.is_empty() {
+            spans.push(span(&current, marker));
+        }
+        spans
+    }
+}
+
 /// Спеціалізовані помилки для побудови тексту.
 #[derive(Debug, Error)]
 pub enum TextBuilderError {
-    #[error("Не вказано контент тексту")]
+    #[error("{}", tr!("Text content is not set", "Не вказано контент тексту"))]
     MissingContent,
 
-    #[error("Не вказано шрифт тексту")]
+    #[error("{}", tr!("Text font is not set", "Не вказано шрифт тексту"))]
     MissingFont,
 
-    #[error("Не вказано ширину тексту")]
+    #[error("{}", tr!("Text width is not set", "Не вказано ширину тексту"))]
     MissingWidth,
 
-    #[error("Недопустиме значення align")]
+    #[error("{}", tr!("Invalid align value", "Недопустиме значення align"))]
     InvalidAlignment,
 }
 
@@ -74,6 +230,85 @@ pub enum TextBuilderError {
 /// Включає тільки скомпільовані шляхи `glyphs`.
 pub struct Text {
     pub glyphs: Vec<Glyph>, // Вектор гліфів, що представляють текст.
+    pub missing: Vec<char>, // Символи, для яких не знайдено гліфа в жодному шрифті.
+    line_count: usize,      // Кількість рядків (або стовпців для вертикального тексту).
+}
+
+impl Text {
+    /// Повертає обмежувальну рамку всього тексту з урахуванням вирівнювання та повороту.
+    ///
+    /// # Повертає
+    ///
+    /// * `Option<Rect<f64>>` - рамка, що охоплює всі гліфи, або `None`, якщо текст порожній.
+    pub fn bbox(&self) -> Option<Rect<f64>> {
+        let first = self.glyphs.first()?;
+        let (xmin, xmax, ymin, ymax) = self.glyphs.iter().fold(
+            (first.xmin, first.xmax, first.ymin, first.ymax),
+            |(xmin, xmax, ymin, ymax), glyph| {
+                (
+                    xmin.min(glyph.xmin),
+                    xmax.max(glyph.xmax),
+                    ymin.min(glyph.ymin),
+                    ymax.max(glyph.ymax),
+                )
+            },
+        );
+
+        Some(Rect::new(
+            coord! { x: xmin, y: ymin },
+            coord! { x: xmax, y: ymax },
+        ))
+    }
+
+    /// Повертає кількість рядків після перенесення.
+    ///
+    /// # Повертає
+    ///
+    /// * `usize` - кількість рядків, а для вертикального тексту — кількість стовпців.
+    pub fn line_count(&self) -> usize {
+        self.line_count
+    }
+
+    /// Повертає шляхи тексту в порядку, що скорочує переїзди з піднятим пером.
+    ///
+    /// `draw` віддає штрихи гліфів так, як їх визначено у шрифті, тож перо перескакує
+    /// між окремими штрихами літер на кшталт «H». Тут увесь текстовий блок
+    /// впорядковується як один малюнок, зокрема штрихи можуть малюватися у зворотному
+    /// напрямку, якщо це дозволяє `order`.
+    ///
+    /// # Аргументи
+    ///
+    /// * `order` - налаштування впорядкування шляхів.
+    ///
+    /// # Повертає
+    ///
+    /// * `Result<MultiLineString<f64>>` - ті самі шляхи у впорядкованому вигляді.
+    pub fn optimized_draw(&self, order: &PathOrder) -> Result<MultiLineString<f64>> {
+        let drawing = Drawing::new((0.0, 0.0), self.draw()?);
+        Ok(drawing.optimize(order).paths)
+    }
+
+    /// Повертає текст як окремий малюнок, наприклад для експорту в SVG без плотера.
+    ///
+    /// Шляхи зсуваються так, щоб рамка тексту починалася на відстані `padding` від
+    /// початку координат, а межі малюнка охоплюють текст разом із полями з усіх боків.
+    ///
+    /// # Аргументи
+    ///
+    /// * `padding` - поля навколо тексту (в мм).
+    ///
+    /// # Повертає
+    ///
+    /// * `Result<Drawing>` - малюнок тексту; порожній текст дає малюнок розміром з поля.
+    pub fn to_drawing(&self, padding: f64) -> Result<Drawing> {
+        let paths = self.draw()?;
+        let Some(bbox) = self.bbox() else {
+            return Ok(Drawing::new((2.0 * padding, 2.0 * padding), paths));
+        };
+        let offset = AffineTransform::translate(padding - bbox.min().x, padding - bbox.min().y);
+        let bounds = (bbox.width() + 2.0 * padding, bbox.height() + 2.0 * padding);
+        Ok(Drawing::new(bounds, paths.affine_transform(&offset)))
+    }
 }
 
 impl Drawable for Text {
@@ -99,6 +334,7 @@ impl Drawable for Text {
 #[derive(Default)]
 pub struct TextBuilder {
     content: Option<String>,
+    spans: Vec<TextSpan>,
     font: Option<Font>,
     position: Option<Point<f64>>,
     scale: Option<f64>,
@@ -106,6 +342,12 @@ pub struct TextBuilder {
     line_height: Option<f64>,
     align: Option<TextAlign>,
     justify: Option<bool>,
+    fallbacks: Vec<Font>,
+    missing_glyph: Option<Glyph>,
+    direction: Option<TextDirection>,
+    rotation: Option<f64>,
+    slant: Option<f64>,
+    weight: Option<usize>,
 }
 
 impl TextBuilder {
@@ -123,6 +365,36 @@ impl TextBuilder {
         self
     }
 
+    /// Додає фрагмент тексту з власним розміром і базовою лінією.
+    ///
+    /// Якщо задано фрагменти, текст складається з них, а `content` не використовується.
+    ///
+    /// # Аргумент
+    ///
+    /// * `span` - фрагмент, наприклад `TextSpan::superscript("2")`.
+    ///
+    /// # Повертає
+    ///
+    /// * `TextBuilder` з доданим фрагментом.
+    pub fn span(mut self, span: TextSpan) -> Self {
+        self.spans.push(span);
+        self
+    }
+
+    /// Встановлює текст із розміткою індексів (`H~2~O`, `x^2^`).
+    ///
+    /// # Аргумент
+    ///
+    /// * `markup` - розмітка, яку розбирає `TextSpan::parse_markup`.
+    ///
+    /// # Повертає
+    ///
+    /// * `TextBuilder` з фрагментами з розмітки.
+    pub fn markup(mut self, markup: &str) -> Self {
+        self.spans = TextSpan::parse_markup(markup);
+        self
+    }
+
     /// Встановлює шрифт для тексту.
     ///
     /// # Аргумент
@@ -221,6 +493,121 @@ impl TextBuilder {
         self
     }
 
+    /// Додає шрифт у кінець ланцюжка резервних шрифтів.
+    ///
+    /// Якщо символа немає в основному шрифті, гліф шукається в резервних шрифтах
+    /// у порядку їх додавання.
+    ///
+    /// # Аргумент
+    ///
+    /// * `font` - резервний шрифт.
+    ///
+    /// # Повертає
+    ///
+    /// * `TextBuilder` з доданим резервним шрифтом.
+    pub fn fallback(mut self, font: Font) -> Self {
+        self.fallbacks.push(font);
+        self
+    }
+
+    /// Встановлює весь ланцюжок резервних шрифтів (наприклад, `fallback::default_chain()`).
+    ///
+    /// # Аргумент
+    ///
+    /// * `fonts` - впорядкований список резервних шрифтів.
+    ///
+    /// # Повертає
+    ///
+    /// * `TextBuilder` з встановленим ланцюжком резервних шрифтів.
+    pub fn fallbacks(mut self, fonts: Vec<Font>) -> Self {
+        self.fallbacks = fonts;
+        self
+    }
+
+    /// Встановлює гліф-замінник для символів, яких немає в жодному шрифті.
+    ///
+    /// Без замінника такі символи пропускаються.
+    ///
+    /// # Аргумент
+    ///
+    /// * `glyph` - гліф-замінник, наприклад `Glyph::missing_box(-8.0, 8.0, -12.0, 9.0)`.
+    ///
+    /// # Повертає
+    ///
+    /// * `TextBuilder` з встановленим гліфом-замінником.
+    pub fn missing_glyph(mut self, glyph: Glyph) -> Self {
+        self.missing_glyph = Some(glyph);
+        self
+    }
+
+    /// Встановлює напрямок розміщення гліфів.
+    ///
+    /// У вертикальному режимі `width` обмежує висоту стовпця, `line_height` задає відстань
+    /// між стовпцями, а вирівнювання та виправлення по ширині не застосовуються.
+    ///
+    /// # Аргумент
+    ///
+    /// * `direction` - напрямок тексту (`Horizontal`, `Vertical`).
+    ///
+    /// # Повертає
+    ///
+    /// * `TextBuilder` з встановленим напрямком.
+    pub fn direction(mut self, direction: TextDirection) -> Self {
+        self.direction = Some(direction);
+        self
+    }
+
+    /// Встановлює кут повороту тексту навколо початкової позиції.
+    ///
+    /// Поворот застосовується до вже розміщеного тексту, тож гліфи йдуть уздовж повернутої
+    /// базової лінії.
+    ///
+    /// # Аргумент
+    ///
+    /// * `degrees` - кут повороту в градусах.
+    ///
+    /// # Повертає
+    ///
+    /// * `TextBuilder` з встановленим кутом повороту.
+    pub fn rotation(mut self, degrees: f64) -> Self {
+        self.rotation = Some(degrees);
+        self
+    }
+
+    /// Встановлює нахил гліфів для похилого накреслення.
+    ///
+    /// Кожен гліф нахиляється відносно власної базової лінії, тож рядки й індекси не
+    /// зсуваються один відносно одного.
+    ///
+    /// # Аргумент
+    ///
+    /// * `degrees` - кут нахилу в градусах; додатний нахиляє літери праворуч.
+    ///
+    /// # Повертає
+    ///
+    /// * `TextBuilder` з встановленим нахилом.
+    pub fn slant(mut self, degrees: f64) -> Self {
+        self.slant = Some(degrees);
+        self
+    }
+
+    /// Встановлює товщину штрихів як кількість проходів пера.
+    ///
+    /// Кожен шлях малюється `weight` разів зі зсувом на 0.2 мм убік, що імітує
+    /// напівжирне накреслення однолінійним шрифтом.
+    ///
+    /// # Аргумент
+    ///
+    /// * `weight` - кількість проходів; 1 — звичайний текст.
+    ///
+    /// # Повертає
+    ///
+    /// * `TextBuilder` з встановленою товщиною.
+    pub fn weight(mut self, weight: usize) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
     /// Створює об'єкт `Text`, обробляючи кожен символ тексту та генеруючи скомпільовані шляхи.
     ///
     /// # Повертає
@@ -228,7 +615,12 @@ impl TextBuilder {
     /// * `Result<Text, FontError>` - новий екземпляр `Text` зі скомпільованими шляхами або помилка.
     pub fn build(self) -> Result<Text, TextBuilderError> {
         // Отримуємо значення для побудови тексту або повертаємо помилку, якщо щось не вказано
-        let content = self.content.ok_or(TextBuilderError::MissingContent)?;
+        let spans = if self.spans.is_empty() {
+            let content = self.content.ok_or(TextBuilderError::MissingContent)?;
+            vec![TextSpan::new(&content)]
+        } else {
+            self.spans
+        };
         let font = self.font.ok_or(TextBuilderError::MissingFont)?;
         let width = self.width.ok_or(TextBuilderError::MissingWidth)?;
         let position = self.position.unwrap_or_else(|| Point::new(0.0, 0.0));
@@ -236,44 +628,74 @@ impl TextBuilder {
         let line_height = self.line_height.unwrap_or(1.0);
         let align = self.align.unwrap_or(TextAlign::Left);
         let justify = self.justify.unwrap_or(false);
+        let direction = self.direction.unwrap_or(TextDirection::Horizontal);
+        let rotation = self.rotation.unwrap_or(0.0);
+        let slant = self.slant.unwrap_or(0.0);
+        let weight = self.weight.unwrap_or(1);
 
-        let mut glyphs = Vec::new();
         let mut lines = Vec::new();
         let mut line = Vec::new();
         let mut line_width = 0.0;
+        let mut missing = Vec::new();
+        let mut resolved: HashMap<char, Option<&Glyph>> = HashMap::new();
+
+        // Індекси вирівнюються за базовою лінією основного шрифту
+        let metrics = font.metrics();
+        let characters = spans.iter().flat_map(|span| {
+            let span_scale = scale * span.scale;
+            let dy = metrics.baseline * (scale - span_scale)
+                - span.baseline_shift * metrics.cap_height * scale;
+            span.content.chars().map(move |char| (char, span_scale, dy))
+        });
 
         // Обробка кожного символу послідовно, включаючи пробіли
-        for char in content.chars() {
-            if let Some(glyph) = font.glyph_by_unicode(char as u32) {
-                let scaled_glyph = glyph.scale(scale);
-                let glyph_width = scaled_glyph.bbox().width();
-
-                // Якщо ширина рядка перевищує максимальну ширину, переносимо його на новий рядок
-                if line_width + glyph_width > width {
-                    lines.push(line);
-                    line = Vec::new();
-                    line_width = 0.0;
-                }
+        for (char, scale, dy) in characters {
+            // Шукаємо гліф в основному шрифті, а потім по черзі в резервних шрифтах;
+            // кожен символ шукаємо лише раз, бо в довгому тексті вони повторюються
+            let found = *resolved.entry(char).or_insert_with(|| {
+                std::iter::once(&font)
+                    .chain(self.fallbacks.iter())
+                    .find_map(|font| font.glyph_by_unicode(char as u32))
+            });
 
-                // Додаємо гліф до поточного рядка
-                line.push(scaled_glyph);
-                line_width += glyph_width;
-            } else if char == ' ' {
+            let glyph = match found {
+                Some(glyph) => glyph.scale(scale),
                 // Якщо символ — пробіл, додаємо пробіл як окремий гліф із шириною пробілу
-                let space_glyph = Glyph::SPACE.scale(scale);
-                let space_width = space_glyph.bbox().width();
-
-                // Якщо ширина рядка перевищує максимальну ширину, переносимо його на новий рядок
-                if line_width + space_width > width {
-                    lines.push(line);
-                    line = Vec::new();
-                    line_width = 0.0;
+                None if char == ' ' => Glyph::SPACE.scale(scale),
+                // Керуючі символи не мають гліфів і не вважаються пропущеними
+                None if char.is_control() => continue,
+                None => {
+                    if !missing.contains(&char) {
+                        missing.push(char);
+                    }
+                    match &self.missing_glyph {
+                        Some(missing_glyph) => missing_glyph.scale(scale),
+                        None => continue,
+                    }
                 }
+            };
+            let glyph = if dy != 0.0 {
+                glyph.offset(0.0, dy)
+            } else {
+                glyph
+            };
+            let glyph = if slant != 0.0 {
+                glyph.skew(slant, metrics.baseline * scale + dy)
+            } else {
+                glyph
+            };
+            let glyph_width = Self::advance(&glyph, direction);
 
-                // Додаємо пробіл до поточного рядка
-                line.push(space_glyph);
-                line_width += space_width;
+            // Якщо ширина рядка перевищує максимальну ширину, переносимо його на новий рядок
+            if line_width + glyph_width > width {
+                lines.push(line);
+                line = Vec::new();
+                line_width = 0.0;
             }
+
+            // Додаємо гліф до поточного рядка
+            line.push(glyph);
+            line_width += glyph_width;
         }
 
         // Додаємо останній рядок, якщо залишилися гліфи
@@ -281,26 +703,123 @@ impl TextBuilder {
             lines.push(line);
         }
 
+        if !missing.is_empty() {
+            warn!(
+                "{}",
+                tr!(
+                    "No glyphs in font '{}' or its fallbacks for characters: {:?}",
+                    "Не знайдено гліфів у шрифті '{}' та резервних шрифтах для символів: {:?}",
+                    font.name,
+                    missing
+                )
+            );
+        }
+
         // Обробляємо кожен рядок з вирівнюванням та виправленням по ширині, якщо потрібно
+        let mut glyphs = Vec::new();
+        let mut x_position = position.x();
         let mut y_position = position.y();
-        for line in lines {
-            let mut x_position = position.x();
-            for mut glyph in line {
-                let glyph_width = glyph.bbox().width();
+        let line_count = lines.len();
+        for (index, line) in lines.into_iter().enumerate() {
+            if direction == TextDirection::Vertical {
+                glyphs.extend(
+                    Self::stack_column(&line)
+                        .into_iter()
+                        .map(|glyph| glyph.offset(x_position, position.y())),
+                );
 
-                glyph = glyph.offset(x_position, y_position);
-                glyphs.push(glyph);
-
-                // Оновлюємо позицію для наступного гліфа в рядку
-                x_position += glyph_width;
+                // Зсуваємо `x_position` для наступного стовпця
+                x_position += line_height;
+                continue;
             }
 
+            // Останній рядок не розтягуємо по ширині
+            let positioned = if justify && index + 1 < line_count {
+                Self::justify_line(&line, width)
+            } else {
+                let mut x_position = 0.0;
+                let advanced: Vec<Glyph> = line
+                    .iter()
+                    .map(|glyph| {
+                        let glyph_position = x_position;
+                        // Оновлюємо позицію для наступного гліфа в рядку
+                        x_position += glyph.bbox().width();
+                        glyph.offset(glyph_position, 0.0)
+                    })
+                    .collect();
+                Self::align_line(&advanced, width, align)
+            };
+
+            glyphs.extend(
+                positioned
+                    .into_iter()
+                    .map(|glyph| glyph.offset(position.x(), y_position)),
+            );
+
             // Зсуваємо `y_position` для наступного рядка
-            y_position -= line_height;
+            y_position += line_height;
+        }
+
+        // Повертаємо текст уздовж повернутої базової лінії
+        if rotation != 0.0 {
+            glyphs = glyphs
+                .iter()
+                .map(|glyph| glyph.rotate(rotation, position))
+                .collect();
+        }
+
+        // Потовщуємо вже розміщені штрихи, щоб зсув між проходами був у міліметрах
+        if weight > 1 {
+            glyphs = glyphs
+                .iter()
+                .map(|glyph| glyph.embolden(weight, WEIGHT_SPACING))
+                .collect();
         }
 
         // Повертаємо об'єкт `Text` з усіма сформованими гліфами
-        Ok(Text { glyphs })
+        Ok(Text {
+            glyphs,
+            missing,
+            line_count,
+        })
+    }
+
+    /// Повертає довжину, яку займає гліф уздовж напрямку тексту.
+    ///
+    /// # Аргументи
+    ///
+    /// * `glyph` - гліф для вимірювання.
+    /// * `direction` - напрямок тексту.
+    ///
+    /// # Повертає
+    ///
+    /// * `f64` - ширина гліфа для горизонтального тексту або висота для вертикального.
+    fn advance(glyph: &Glyph, direction: TextDirection) -> f64 {
+        match direction {
+            TextDirection::Horizontal => glyph.bbox().width(),
+            TextDirection::Vertical => glyph.bbox().height(),
+        }
+    }
+
+    /// Розміщує гліфи стовпця один під одним, центруючи кожен відносно осі стовпця.
+    ///
+    /// # Аргументи
+    ///
+    /// * `line` - Зріз, що містить гліфи стовпця.
+    ///
+    /// # Повертає
+    ///
+    /// * `Vec<Glyph>` - Новий вектор з гліфами, розміщеними згори донизу від нуля.
+    fn stack_column(line: &[Glyph]) -> Vec<Glyph> {
+        let mut y_offset = 0.0;
+        line.iter()
+            .map(|glyph| {
+                let center = (glyph.xmin + glyph.xmax) / 2.0;
+                let positioned = glyph.offset(-center, y_offset - glyph.ymin);
+                y_offset += glyph.bbox().height();
+                positioned
+            })
+            .collect()
     }
 
     /// Вирівнює рядок символів по ширині, додаючи рівномірний відступ між символами.
@@ -318,7 +837,7 @@ impl TextBuilder {
         let line_width: f64 = line.iter().map(|glyph| glyph.bbox().width()).sum();
 
         // Розрахунок інтервалу між гліфами
-        let spaces = line.len() - 1;
+        let spaces = line.len().saturating_sub(1);
         let gap = if spaces > 0 {
             (width - line_width) / spaces as f64
         } else {
@@ -369,19 +888,22 @@ impl TextBuilder {
 
 #[cfg(test)]
 mod tests {
-    use crate::drawing::Drawing;
+    use crate::text::font::fallback;
+    use crate::text::font::group::private_use_char;
+    use crate::text::font::math::Math;
+    use crate::text::font::music::Music;
     use crate::text::font::roman::Roman;
-    use crate::text::font::variant::Simplex;
+    use crate::text::font::symbol::Symbol;
+    use crate::text::font::variant::{Complex, Simplex};
 
     use super::*;
-    use env_logger;
     use std::fs::File;
     use std::io::Write;
 
     fn init_logger() {
-        let _ = env_logger::builder()
-            .is_test(true)
-            .filter_level(log::LevelFilter::Debug)
+        let _ = tracing_subscriber::fmt()
+            .with_test_writer()
+            .with_max_level(tracing::Level::DEBUG)
             .try_init();
     }
 
@@ -420,4 +942,310 @@ mod tests {
         // Перевірка наявності даних у згенерованому SVG
         assert!(!svg_data.is_empty(), "SVG файл порожній");
     }
+
+    #[test]
+    fn test_subscript_and_superscript_markup() {
+        let roman_font = Roman::new()
+            .simplex()
+            .expect("Не вдалося ініціалізувати шрифт `Roman`");
+        let metrics = roman_font.metrics();
+
+        assert_eq!(
+            TextSpan::parse_markup("x^2^ + y\\^2"),
+            vec![
+                TextSpan::new("x"),
+                TextSpan::superscript("2"),
+                TextSpan::new(" + y^2"),
+            ]
+        );
+        assert_eq!(
+            TextSpan::parse_markup("CO~2"),
+            vec![TextSpan::new("CO"), TextSpan::subscript("2")],
+            "Незакритий індекс триває до кінця"
+        );
+
+        let build = |markup: &str| {
+            TextBuilder::default()
+                .markup(markup)
+                .font(roman_font.clone())
+                .scale(2.0)
+                .width(500.0)
+                .build()
+                .expect("Не вдалося створити текст")
+        };
+        let water = build("H~2~O");
+        let square = build("x^2^");
+        let plain = build("2");
+        let (two, sub, sup) = (&plain.glyphs[0], &water.glyphs[1], &square.glyphs[1]);
+
+        // Індекси менші за звичайний гліф і зсунуті від базової лінії рядка
+        let ratio = sub.bbox().height() / two.bbox().height();
+        assert!((ratio - SCRIPT_SCALE).abs() < 1e-9);
+        let baseline = metrics.baseline * 2.0;
+        let cap_height = metrics.cap_height * 2.0;
+        assert!((water.glyphs[0].ymax - baseline).abs() < 1e-9);
+        assert!((sub.ymax - (baseline - SUBSCRIPT_SHIFT * cap_height)).abs() < 1e-9);
+        assert!((sup.ymax - (baseline - SUPERSCRIPT_SHIFT * cap_height)).abs() < 1e-9);
+        assert!(
+            sup.ymax < two.ymax && sub.ymax > two.ymax,
+            "Верхній індекс вище, а нижній нижче базової лінії"
+        );
+
+        // Фрагменти мають перевагу над `content`
+        let sized = TextBuilder::default()
+            .content("ігнорується")
+            .span(TextSpan::sized("A", 3.0))
+            .font(roman_font.clone())
+            .width(500.0)
+            .build()
+            .expect("Не вдалося створити текст");
+        assert_eq!(sized.glyphs.len(), 1);
+    }
+
+    #[test]
+    fn test_slant_and_weight() {
+        let roman_font = Roman::new()
+            .simplex()
+            .expect("Не вдалося ініціалізувати шрифт `Roman`");
+        let build = |builder: TextBuilder| {
+            builder
+                .content("I")
+                .font(roman_font.clone())
+                .width(100.0)
+                .build()
+                .expect("Не вдалося створити текст")
+        };
+
+        let plain = build(TextBuilder::default());
+        let oblique = build(TextBuilder::default().slant(12.0));
+        let bold = build(TextBuilder::default().weight(3));
+
+        // Верх вертикалі «I» зсувається праворуч, а нижній кінець лишається на базовій лінії
+        let stem = |text: &Text| text.glyphs[0].paths.0[0].0.clone();
+        let (straight, slanted) = (stem(&plain), stem(&oblique));
+        let top = (0..2)
+            .min_by(|&a, &b| straight[a].y.total_cmp(&straight[b].y))
+            .unwrap();
+        let shift = (straight[1 - top].y - straight[top].y) * 12f64.to_radians().tan();
+        assert!((slanted[top].x - straight[top].x - shift).abs() < 1e-9);
+        assert!((slanted[1 - top].x - straight[1 - top].x).abs() < 1e-9);
+
+        let plain_stats = Drawing::new((100.0, 100.0), plain.draw().unwrap()).stats();
+        let bold_stats = Drawing::new((100.0, 100.0), bold.draw().unwrap()).stats();
+        assert_eq!(
+            bold_stats.paths, plain_stats.paths,
+            "Проходи не додають підйомів пера"
+        );
+        assert!(bold_stats.pen_down_length > 3.0 * plain_stats.pen_down_length - 1e-9);
+    }
+
+    #[test]
+    fn test_optimized_draw_reduces_pen_travel() {
+        let text = TextBuilder::default()
+            .content("HEH\nWHEN")
+            .font(
+                Roman::new()
+                    .simplex()
+                    .expect("Не вдалося ініціалізувати шрифт"),
+            )
+            .width(200.0)
+            .line_height(30.0)
+            .build()
+            .expect("Не вдалося створити текст");
+
+        let plain = Drawing::new((200.0, 60.0), text.draw().unwrap()).stats();
+        let optimized = Drawing::new(
+            (200.0, 60.0),
+            text.optimized_draw(&PathOrder::default()).unwrap(),
+        )
+        .stats();
+
+        // Рядки, перенесені за шириною, йдуть згори вниз, як і вісь y аркуша
+        let wrapped = TextBuilder::default()
+            .content("HEH WHEN")
+            .font(Roman::new().simplex().unwrap())
+            .width(60.0)
+            .line_height(30.0)
+            .build()
+            .unwrap()
+            .draw()
+            .unwrap();
+        let (first, last) = (&wrapped.0[0], wrapped.0.last().unwrap());
+        assert!(
+            last.0[0].y > first.0[0].y + 15.0,
+            "Другий рядок нижче першого"
+        );
+
+        assert_eq!(optimized.paths, plain.paths, "Штрихи лише переставляються");
+        assert!((optimized.pen_down_length - plain.pen_down_length).abs() < 1e-9);
+        assert!(
+            optimized.pen_up_length < plain.pen_up_length,
+            "Впорядковані штрихи мають скоротити переїзди: {} проти {}",
+            optimized.pen_up_length,
+            plain.pen_up_length
+        );
+    }
+
+    #[test]
+    fn test_vertical_text_stacks_glyphs_top_to_bottom() {
+        init_logger();
+
+        let roman_font = Roman::new()
+            .simplex()
+            .expect("Не вдалося ініціалізувати шрифт `Roman`");
+
+        let text = TextBuilder::default()
+            .content("AB")
+            .font(roman_font)
+            .width(200.0)
+            .direction(TextDirection::Vertical)
+            .build()
+            .expect("Не вдалося створити текст");
+
+        assert_eq!(text.glyphs.len(), 2);
+
+        // Обидва гліфи центровані на осі стовпця, другий починається там, де закінчився перший
+        let (a, b) = (&text.glyphs[0], &text.glyphs[1]);
+        assert!(((a.xmin + a.xmax) / 2.0).abs() < 1e-9);
+        assert!(((b.xmin + b.xmax) / 2.0).abs() < 1e-9);
+        assert!((a.ymin).abs() < 1e-9);
+        assert!((b.ymin - a.ymax).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_fallback_chain_and_missing_glyph_box() {
+        init_logger();
+
+        let roman_font = Roman::new()
+            .simplex()
+            .expect("Не вдалося ініціалізувати шрифт `Roman`");
+
+        // Без резервних шрифтів грецька літера та ієрогліф пропускаються
+        let text = TextBuilder::default()
+            .content("Aα中")
+            .font(roman_font.clone())
+            .width(200.0)
+            .build()
+            .expect("Не вдалося створити текст");
+        assert_eq!(text.glyphs.len(), 1);
+        assert_eq!(text.missing, vec!['α', '中']);
+
+        // Грецька літера береться з резервного шрифту, а ієрогліф замінюється рамкою
+        let text = TextBuilder::default()
+            .content("Aα中")
+            .font(roman_font)
+            .fallbacks(fallback::default_chain().expect("Не вдалося завантажити резервні шрифти"))
+            .missing_glyph(Glyph::missing_box(-8.0, 8.0, -12.0, 9.0))
+            .width(200.0)
+            .build()
+            .expect("Не вдалося створити текст");
+        assert_eq!(text.glyphs.len(), 3);
+        assert_eq!(text.glyphs[1].charcode, Some('α' as u32));
+        assert_eq!(text.glyphs[2].charcode, None);
+        assert_eq!(text.glyphs[2].paths.0.len(), 1);
+        assert_eq!(text.missing, vec!['中']);
+    }
+
+    #[test]
+    fn test_symbol_music_and_math_fonts() {
+        init_logger();
+
+        let clef = private_use_char(2320).expect("Недійсний код скрипкового ключа");
+        let music = Music::new()
+            .simplex()
+            .expect("Не вдалося ініціалізувати шрифт `Music`");
+        let text = TextBuilder::default()
+            .content(&format!("{}♯♭", clef))
+            .font(music)
+            .width(200.0)
+            .build()
+            .expect("Не вдалося створити текст");
+        assert!(
+            text.missing.is_empty(),
+            "Музичні символи мають бути доступні"
+        );
+        assert_eq!(text.glyphs.len(), 3);
+
+        // Великий інтеграл не перекриває звичайний `∫`
+        let math = Math::new()
+            .complex()
+            .expect("Не вдалося ініціалізувати шрифт `Math`");
+        let big_integral = private_use_char(2411).expect("Недійсний код інтеграла");
+        assert!(math.glyph_by_unicode('∫' as u32).is_some());
+        assert!(math.glyph_by_unicode(big_integral as u32).is_some());
+
+        // Маркер кола з набору Symbol
+        let symbol = Symbol::new()
+            .simplex()
+            .expect("Не вдалося ініціалізувати шрифт `Symbol`");
+        let circle = private_use_char(840).expect("Недійсний код маркера");
+        assert!(symbol.glyph_by_unicode(circle as u32).is_some());
+        assert!(symbol.glyph_by_unicode('♡' as u32).is_some());
+    }
+
+    #[test]
+    fn test_text_bbox_line_count_and_font_metrics() {
+        init_logger();
+
+        let roman_font = Roman::new()
+            .simplex()
+            .expect("Не вдалося ініціалізувати шрифт `Roman`");
+        let metrics = roman_font.metrics();
+        assert!(
+            metrics.cap_height > metrics.x_height,
+            "Великі літери мають бути вищі за малі"
+        );
+        assert!(metrics.average_advance > 0.0);
+
+        let text = TextBuilder::default()
+            .content("HHHH")
+            .font(roman_font.clone())
+            .width(45.0)
+            .line_height(30.0)
+            .build()
+            .expect("Не вдалося створити текст");
+        assert_eq!(text.line_count(), 2);
+
+        let h_width = roman_font
+            .glyph_by_unicode('H' as u32)
+            .expect("Гліф `H` відсутній")
+            .bbox()
+            .width();
+        let bbox = text.bbox().expect("Текст не має бути порожнім");
+        assert!((bbox.width() - 2.0 * h_width).abs() < 1e-9);
+        assert!((bbox.height() - (30.0 + metrics.cap_height)).abs() < 1e-9);
+
+        let empty = TextBuilder::default()
+            .content("")
+            .font(roman_font)
+            .width(45.0)
+            .build()
+            .expect("Не вдалося створити текст");
+        assert!(empty.bbox().is_none());
+        assert_eq!(empty.line_count(), 0);
+    }
+
+    #[test]
+    fn test_text_to_drawing_fits_padding() {
+        let text = TextBuilder::default()
+            .content("HH")
+            .font(
+                Roman::new()
+                    .simplex()
+                    .expect("Не вдалося ініціалізувати шрифт `Roman`"),
+            )
+            .width(100.0)
+            .build()
+            .expect("Не вдалося створити текст");
+        let bbox = text.bbox().expect("Текст не має бути порожнім");
+        let drawing = text.to_drawing(2.0).expect("Не вдалося створити малюнок");
+        assert_eq!(
+            drawing.bounds,
+            (bbox.width() + 4.0, bbox.height() + 4.0),
+            "Межі охоплюють текст з полями"
+        );
+        let drawn = drawing.bbox().expect("Малюнок не порожній");
+        assert!(drawn.min().x >= 2.0 && drawn.min().y >= 2.0);
+        assert!(drawn.max().x <= drawing.bounds.0 - 2.0 && drawn.max().y <= drawing.bounds.1 - 2.0);
+    }
 }
@@ -0,0 +1,324 @@
+use std::cell::Cell;
+use std::fmt::Debug;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde_json::json;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::Subscriber;
+use tracing_subscriber::filter::{filter_fn, Filtered};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Ціль проміжків сліду; журнал їх не показує, а бачить лише шар `layer`.
+pub const TARGET: &str = "rsaxi::trace";
+
+/// Лічильник номерів потоків для сліду.
+static NEXT_THREAD: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    /// Номер поточного потоку в сліді.
+    static THREAD: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Вид роботи, на яку пішов проміжок часу; це й назва проміжку `tracing`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Category {
+    Planning, // Планування руху малюнка чи порції шляхів.
+    Path,     // Шлях від опускання до підняття пера.
+    Block,    // Виконання одного плану руху.
+    Command,  // Команда до плати від перевірки до відповіді.
+    Serial,   // Очікування відповіді плати на серійному порту.
+}
+
+impl Category {
+    /// Назва виду для сліду та підсумку.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Category::Planning => "planning",
+            Category::Path => "path",
+            Category::Block => "block",
+            Category::Command => "command",
+            Category::Serial => "serial",
+        }
+    }
+
+    /// Вид за назвою проміжку `tracing`.
+    fn from_name(name: &str) -> Option<Self> {
+        [
+            Category::Planning,
+            Category::Path,
+            Category::Block,
+            Category::Command,
+            Category::Serial,
+        ]
+        .into_iter()
+        .find(|category| category.name() == name)
+    }
+}
+
+/// Завершений проміжок часу.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub category: Category,     // Вид роботи.
+    pub name: String,           // Назва проміжку, наприклад назва команди.
+    pub detail: Option<String>, // Подробиці, наприклад параметри команди.
+    pub start: Duration,        // Початок від увімкнення запису.
+    pub duration: Duration,     // Тривалість.
+    pub thread: u64,            // Номер потоку, в якому пройшов проміжок.
+}
+
+/// Дані проміжку, що ще триває, у розширеннях реєстру `tracing-subscriber`.
+struct Open {
+    name: Option<String>,   // Поле `name`; без нього назвою стає вид.
+    detail: Option<String>, // Поле `detail`.
+    started: Instant,       // Момент створення проміжку.
+}
+
+impl Visit for Open {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "name" => self.name = Some(value.to_string()),
+            "detail" => self.detail = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        match field.name() {
+            "name" => self.name = Some(format!("{:?}", value)),
+            "detail" => self.detail = Some(format!("{:?}", value)),
+            _ => {}
+        }
+    }
+}
+
+/// Записані проміжки, спільні для шару та того, хто їх забирає.
+#[derive(Clone)]
+pub struct Recorder {
+    events: Arc<Mutex<Vec<Event>>>, // Проміжки в порядку завершення.
+    epoch: Instant,                 // Момент, від якого відраховується початок проміжків.
+}
+
+impl Recorder {
+    /// Забирає записані проміжки в порядку завершення.
+    pub fn finish(&self) -> Vec<Event> {
+        std::mem::take(&mut *self.events.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+/// Шар `tracing-subscriber`, що записує завершені проміжки з ціллю `TARGET`.
+pub struct TraceLayer {
+    recorder: Recorder, // Куди складаються завершені проміжки.
+}
+
+impl<S> Layer<S> for TraceLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut open = Open {
+            name: None,
+            detail: None,
+            started: Instant::now(),
+        };
+        attrs.record(&mut open);
+        span.extensions_mut().insert(open);
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        if let Some(open) = extensions.get_mut::<Open>() {
+            values.record(open);
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(category) = Category::from_name(span.name()) else {
+            return;
+        };
+        let Some(open) = span.extensions_mut().remove::<Open>() else {
+            return;
+        };
+        let event = Event {
+            category,
+            name: open.name.unwrap_or_else(|| category.name().to_string()),
+            detail: open.detail,
+            start: open.started.saturating_duration_since(self.recorder.epoch),
+            duration: open.started.elapsed(),
+            thread: thread_id(),
+        };
+        self.recorder
+            .events
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(event);
+    }
+}
+
+/// Номер поточного потоку, що видається при першому проміжку в ньому.
+fn thread_id() -> u64 {
+    THREAD.with(|thread| {
+        if thread.get() == 0 {
+            thread.set(NEXT_THREAD.fetch_add(1, Ordering::Relaxed));
+        }
+        thread.get()
+    })
+}
+
+/// Створює шар, що записує проміжки сліду, і сховище записаних проміжків.
+///
+/// Шар бачить лише проміжки з ціллю `TARGET` рівня `TRACE`, тож без нього в підписнику
+/// вони вимкнені й нічого не коштують, а подробиці `detail` навіть не форматуються.
+///
+/// # Повертає
+/// * `(Filtered<TraceLayer, _, S>, Recorder)` - шар для підписника та записані ним проміжки.
+#[allow(clippy::type_complexity)]
+pub fn layer<S>() -> (
+    Filtered<TraceLayer, impl tracing_subscriber::layer::Filter<S>, S>,
+    Recorder,
+)
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    let recorder = Recorder {
+        events: Arc::new(Mutex::new(vec![])),
+        epoch: Instant::now(),
+    };
+    let layer = TraceLayer {
+        recorder: recorder.clone(),
+    }
+    .with_filter(filter_fn(|metadata| metadata.target() == TARGET));
+    (layer, recorder)
+}
+
+/// Кількість і загальна тривалість проміжків кожного виду.
+///
+/// Проміжки вкладаються: час команди входить у час плану, а очікування порту — у час
+/// команди, тож тривалості видів не додаються одна до одної.
+///
+/// # Аргументи
+/// * `events` - записані проміжки.
+///
+/// # Повертає
+/// * `Vec<(Category, usize, Duration)>` - вид, кількість і тривалість у порядку видів.
+pub fn summary(events: &[Event]) -> Vec<(Category, usize, Duration)> {
+    let mut totals: Vec<(Category, usize, Duration)> = vec![];
+    for event in events {
+        match totals
+            .iter_mut()
+            .find(|(category, ..)| *category == event.category)
+        {
+            Some((_, count, total)) => {
+                *count += 1;
+                *total += event.duration;
+            }
+            None => totals.push((event.category, 1, event.duration)),
+        }
+    }
+    totals.sort_by_key(|(category, ..)| *category);
+    totals
+}
+
+/// Записує проміжки у форматі Chrome Trace Event, який відкривають Perfetto та `chrome://tracing`.
+///
+/// # Аргументи
+/// * `events` - записані проміжки.
+/// * `writer` - куди записати JSON.
+///
+/// # Повертає
+/// * `io::Result<()>` - помилка запису.
+pub fn write_chrome(events: &[Event], mut writer: impl Write) -> io::Result<()> {
+    let micros = |duration: Duration| duration.as_secs_f64() * 1e6;
+    let events: Vec<_> = events
+        .iter()
+        .map(|event| {
+            let mut value = json!({
+                "name": event.name,
+                "cat": event.category.name(),
+                "ph": "X",
+                "ts": micros(event.start),
+                "dur": micros(event.duration),
+                "pid": 1,
+                "tid": event.thread,
+            });
+            if let Some(detail) = &event.detail {
+                value["args"] = json!({ "detail": detail });
+            }
+            value
+        })
+        .collect();
+    serde_json::to_writer(&mut writer, &json!({ "traceEvents": events }))?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_layer_records_trace_spans() {
+        let formatted = Cell::new(false);
+        let detail = || {
+            formatted.set(true);
+            "SM,10,5,5"
+        };
+        let _ignored = tracing::trace_span!(target: TARGET, "block", detail = detail()).entered();
+        drop(_ignored);
+        formatted.set(false);
+
+        let (layer, recorder) = layer();
+        let subscriber = tracing_subscriber::registry().with(layer);
+        tracing::subscriber::with_default(subscriber, || {
+            {
+                let _command =
+                    tracing::trace_span!(target: TARGET, "command", name = "SM", detail = detail())
+                        .entered();
+                let _serial = tracing::trace_span!(target: TARGET, "serial").entered();
+                std::thread::sleep(Duration::from_millis(2));
+            }
+            drop(tracing::trace_span!(target: TARGET, "command", name = "QG"));
+            drop(tracing::info_span!("command", name = "other"));
+        });
+        assert!(formatted.get());
+        let events = recorder.finish();
+        assert!(
+            recorder.finish().is_empty(),
+            "Проміжки забираються один раз"
+        );
+
+        let names: Vec<&str> = events.iter().map(|event| event.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["serial", "SM", "QG"],
+            "Вкладений проміжок завершується першим, а чужі цілі не записуються"
+        );
+        assert!(events[0].duration >= Duration::from_millis(2));
+        assert!(events[1].start <= events[0].start);
+        assert_eq!(events[1].detail.as_deref(), Some("SM,10,5,5"));
+
+        let totals = summary(&events);
+        assert_eq!(totals[0].0, Category::Command);
+        assert_eq!(totals[0].1, 2);
+        assert_eq!(totals[1].0, Category::Serial);
+
+        let mut output = vec![];
+        write_chrome(&events, &mut output).unwrap();
+        let value: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(value["traceEvents"][0]["cat"], "serial");
+        assert_eq!(value["traceEvents"][1]["args"]["detail"], "SM,10,5,5");
+    }
+}
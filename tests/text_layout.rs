@@ -0,0 +1,227 @@
+use std::fs;
+use std::path::PathBuf;
+
+use rsaxi::drawing::{Drawable, Drawing};
+use rsaxi::text::font::catalog::{by_name, FONT_NAMES};
+use rsaxi::text::text::{Text, TextAlign, TextBuilder, TextDirection, TextSpan};
+
+/// Найбільша розбіжність числа в SVG з еталоном (в мм).
+const TOLERANCE: f64 = 1e-6;
+
+/// Змінна середовища, з якою тести переписують еталони замість порівняння.
+const BLESS: &str = "RSAXI_BLESS";
+
+/// Абзац, що переноситься на кілька рядків у тестах вирівнювання.
+const PARAGRAPH: &str = "Pack my box with five dozen liquor jugs, then judge my vow.";
+
+/// Частина SVG для порівняння: число з допуском або решта тексту дослівно.
+#[derive(Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    Text(String),
+}
+
+/// Розбиває SVG на числа й текст між ними.
+fn tokens(svg: &str) -> Vec<Token> {
+    let chars: Vec<char> = svg.chars().collect();
+    let (mut tokens, mut text) = (vec![], String::new());
+    let mut index = 0;
+    while index < chars.len() {
+        let digit_at = |i: usize| chars.get(i).is_some_and(char::is_ascii_digit);
+        let starts_number = digit_at(index)
+            || (matches!(chars[index], '-' | '.') && digit_at(index + 1))
+            || (chars[index] == '-' && chars.get(index + 1) == Some(&'.') && digit_at(index + 2));
+        if !starts_number {
+            text.push(chars[index]);
+            index += 1;
+            continue;
+        }
+        let start = index;
+        index += 1;
+        while index < chars.len() && (chars[index].is_ascii_digit() || chars[index] == '.') {
+            index += 1;
+        }
+        let number: String = chars[start..index].iter().collect();
+        match number.parse() {
+            Ok(value) => {
+                if !text.is_empty() {
+                    tokens.push(Token::Text(std::mem::take(&mut text)));
+                }
+                tokens.push(Token::Number(value));
+            }
+            Err(_) => text.push_str(&number),
+        }
+    }
+    if !text.is_empty() {
+        tokens.push(Token::Text(text));
+    }
+    tokens
+}
+
+/// Знаходить першу розбіжність SVG з еталоном або `None`, якщо вони збігаються з допуском.
+fn difference(actual: &str, expected: &str) -> Option<String> {
+    let (actual, expected) = (tokens(actual), tokens(expected));
+    for (index, (a, e)) in actual.iter().zip(&expected).enumerate() {
+        let same = match (a, e) {
+            (Token::Number(a), Token::Number(e)) => (a - e).abs() <= TOLERANCE,
+            _ => a == e,
+        };
+        if !same {
+            return Some(format!(
+                "частина {}: отримано {:?}, очікувалося {:?}",
+                index, a, e
+            ));
+        }
+    }
+    (actual.len() != expected.len()).then(|| {
+        format!(
+            "отримано {} частин, очікувалося {}",
+            actual.len(),
+            expected.len()
+        )
+    })
+}
+
+/// Перевіряє SVG тексту з еталонним файлом `tests/golden/text/<name>.svg`.
+///
+/// З `RSAXI_BLESS=1` еталон переписується, тож зміну верстки видно в diff еталонів.
+///
+/// # Повертає
+/// * `Option<String>` - опис розбіжності або `None`, якщо верстка не змінилася.
+fn check(name: &str, text: &Text) -> Option<String> {
+    let paths = text.draw().expect("Шляхи тексту мають будуватися");
+    let bounds = text
+        .bbox()
+        .map_or((0.0, 0.0), |bbox| (bbox.max().x, bbox.max().y));
+    let svg = Drawing::new(bounds, paths).to_svg();
+
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden/text")
+        .join(format!("{}.svg", name));
+    if std::env::var_os(BLESS).is_some() {
+        fs::create_dir_all(path.parent().unwrap()).expect("Каталог еталонів має створюватися");
+        fs::write(&path, svg + "\n").expect("Еталон має записуватися");
+        return None;
+    }
+    match fs::read_to_string(&path) {
+        Ok(expected) => difference(&svg, expected.trim_end()).map(|e| format!("{}: {}", name, e)),
+        Err(e) => Some(format!(
+            "{}: немає еталона {} ({})",
+            name,
+            path.display(),
+            e
+        )),
+    }
+}
+
+/// Завершує тест переліком усіх розбіжностей, щоб одразу було видно масштаб зміни.
+fn assert_matches(failures: Vec<String>) {
+    assert!(
+        failures.is_empty(),
+        "Верстка відрізняється від еталонів; якщо зміна навмисна, перезапишіть їх \
+         командою `{}=1 cargo test --test text_layout`:\n{}",
+        BLESS,
+        failures.join("\n")
+    );
+}
+
+/// Рядок із символами, які є в шрифті.
+fn sample(font: &str) -> &'static str {
+    match font {
+        "greekp" => "ΑΒΓΔΣΩ ΛΠ 123",
+        name if name.starts_with("greek") => "ΑΒΓΔΣΩ αβγδλω 123",
+        "cyrilc" => "Добрий день 42",
+        "maths" | "mathc" => "∇∈−±÷ΣΠ†‡←→",
+        "musics" | "musicc" => "♭♮♯",
+        "symbols" => "∠∥☘♡♢♤♧⚜",
+        "symbolc" => "\u{E8E9}\u{E8EA}\u{E8EB}\u{E8EC}\u{E8ED}",
+        _ => "Hamburgefonts 0123!",
+    }
+}
+
+/// Будівельник абзацу шрифтом `romans` з переносом на кількох рядках.
+fn paragraph() -> TextBuilder {
+    TextBuilder::default()
+        .content(PARAGRAPH)
+        .font(by_name("romans").unwrap())
+        .scale(0.5)
+        .width(150.0)
+        .line_height(20.0)
+}
+
+#[test]
+fn test_typefaces_match_golden() {
+    let failures = FONT_NAMES
+        .iter()
+        .filter_map(|&name| {
+            let text = TextBuilder::default()
+                .content(sample(name))
+                .font(by_name(name).unwrap())
+                .scale(0.5)
+                .width(400.0)
+                .line_height(20.0)
+                .build()
+                .expect("Текст має будуватися");
+            assert!(text.missing.is_empty(), "{}: {:?}", name, text.missing);
+            check(&format!("font_{}", name), &text)
+        })
+        .collect();
+    assert_matches(failures);
+}
+
+#[test]
+fn test_layout_matches_golden() {
+    let cases = [
+        ("align_left", paragraph().align(TextAlign::Left)),
+        ("align_center", paragraph().align(TextAlign::Center)),
+        ("align_right", paragraph().align(TextAlign::Right)),
+        ("justify", paragraph().justify(true)),
+        (
+            "vertical",
+            paragraph()
+                .content("Tall text")
+                .direction(TextDirection::Vertical),
+        ),
+        ("rotated", paragraph().rotation(30.0)),
+        ("slant_weight", paragraph().slant(12.0).weight(2)),
+        (
+            "markup",
+            paragraph()
+                .span(TextSpan::new("E = mc"))
+                .span(TextSpan::superscript("2"))
+                .span(TextSpan::new(", H"))
+                .span(TextSpan::subscript("2"))
+                .span(TextSpan::new("O")),
+        ),
+    ];
+    let failures = cases
+        .into_iter()
+        .filter_map(|(name, builder)| {
+            let text = builder.build().expect("Текст має будуватися");
+            check(&format!("layout_{}", name), &text)
+        })
+        .collect();
+    assert_matches(failures);
+}
+
+#[test]
+fn test_comparison_tolerates_rounding_only() {
+    let expected = r#"<polyline points="1.5,-2 10,0.25" stroke-width="0.5"/>"#;
+    let rounded = r#"<polyline points="1.5000000001,-2 10,0.25" stroke-width="0.5"/>"#;
+    assert_eq!(difference(rounded, expected), None);
+    assert!(difference(
+        r#"<polyline points="1.6,-2 10,0.25" stroke-width="0.5"/>"#,
+        expected
+    )
+    .is_some());
+    assert!(difference(
+        r#"<polyline points="1.5,-2 10" stroke-width="0.5"/>"#,
+        expected
+    )
+    .is_some());
+    assert!(difference(
+        r#"<polygon points="1.5,-2 10,0.25" stroke-width="0.5"/>"#,
+        expected
+    )
+    .is_some());
+}